@@ -0,0 +1,95 @@
+// Per-stage cost of the frame processing pipeline
+//
+// Run with: cargo bench --bench pipeline
+//
+// Each iteration processes one second of 48kHz stereo capture in 100ms
+// frames, through each stage alone and through the full conversion to
+// 16kHz mono.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use loqa_meetings::audio::{
+    AudioStreamSource, DownmixStage, DownmixStrategy, FloatFrame, GainStage, Pipeline,
+    ResampleStage,
+};
+use loqa_meetings::ResamplerQuality;
+use std::collections::HashMap;
+use std::hint::black_box;
+
+const FROM_RATE: u32 = 48000;
+const TO_RATE: u32 = 16000;
+const FRAME_SAMPLES: usize = 9600;
+
+fn frame() -> FloatFrame {
+    // 440Hz tone, as a stand-in for speech
+    let samples = (0..FRAME_SAMPLES)
+        .map(|i| {
+            let t = (i / 2) as f32 / FROM_RATE as f32;
+            (2.0 * std::f32::consts::PI * 440.0 * t).sin() * 0.25
+        })
+        .collect();
+    FloatFrame {
+        samples,
+        sample_rate: FROM_RATE,
+        channels: 2,
+        timestamp_ms: 0,
+        source: AudioStreamSource::System,
+    }
+}
+
+fn pipelines() -> Vec<(&'static str, Pipeline)> {
+    let mut gains = HashMap::new();
+    gains.insert(AudioStreamSource::System, 0.5);
+    vec![
+        (
+            "downmix",
+            Pipeline::new(false).with(DownmixStage::new(DownmixStrategy::default())),
+        ),
+        (
+            "resample",
+            Pipeline::new(false).with(ResampleStage::new(TO_RATE, ResamplerQuality::default())),
+        ),
+        ("gain", Pipeline::new(false).with(GainStage::new(gains))),
+        (
+            "conversion",
+            Pipeline::conversion(
+                TO_RATE,
+                1,
+                DownmixStrategy::default(),
+                ResamplerQuality::default(),
+                false,
+            ),
+        ),
+        (
+            "conversion_quantized",
+            Pipeline::conversion(
+                TO_RATE,
+                1,
+                DownmixStrategy::default(),
+                ResamplerQuality::default(),
+                true,
+            ),
+        ),
+    ]
+}
+
+fn bench_pipeline(c: &mut Criterion) {
+    let frame = frame();
+
+    let mut group = c.benchmark_group("pipeline_48k_stereo");
+    group.throughput(Throughput::Elements(FROM_RATE as u64));
+
+    for (name, mut pipeline) in pipelines() {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for _ in 0..10 {
+                    black_box(pipeline.process(black_box(frame.clone())));
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_pipeline);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+// Resampler throughput for each quality level
+//
+// Run with: cargo bench --bench resampler
+//
+// Each iteration resamples one second of 48kHz audio to 16kHz in 100ms
+// frames, the way captured system audio is converted for STT.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use loqa_meetings::{Resampler, ResamplerQuality};
+use std::hint::black_box;
+
+const FROM_RATE: u32 = 48000;
+const TO_RATE: u32 = 16000;
+const FRAME_SAMPLES: usize = 4800;
+
+fn qualities() -> Vec<ResamplerQuality> {
+    vec![
+        ResamplerQuality::Nearest,
+        ResamplerQuality::Linear,
+        ResamplerQuality::Cubic,
+        ResamplerQuality::Sinc(8),
+        ResamplerQuality::Sinc(32),
+        ResamplerQuality::Sinc(64),
+    ]
+}
+
+fn bench_resampler(c: &mut Criterion) {
+    // 440Hz tone, as a stand-in for speech
+    let frame: Vec<i16> = (0..FRAME_SAMPLES)
+        .map(|i| {
+            let t = i as f64 / FROM_RATE as f64;
+            ((2.0 * std::f64::consts::PI * 440.0 * t).sin() * 8000.0) as i16
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("resample_48k_to_16k_mono");
+    group.throughput(Throughput::Elements(FROM_RATE as u64));
+
+    for quality in qualities() {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(quality),
+            &quality,
+            |b, &quality| {
+                let mut resampler = Resampler::with_quality(FROM_RATE, TO_RATE, 1, quality);
+                b.iter(|| {
+                    for _ in 0..10 {
+                        black_box(resampler.process(black_box(&frame)));
+                    }
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_resampler);
+criterion_main!(benches);
@@ -1,70 +1,128 @@
-// Build script to compile Swift ScreenCaptureKit bridge on macOS
+// Build script to compile Swift ScreenCaptureKit bridge on macOS, and to
+// record the git commit the server is built from (LOQA_GIT_COMMIT, reported
+// by GET /version)
+//
+// The target architecture and SDK are detected rather than hardcoded, so
+// the bridge builds on Apple Silicon and Intel Macs with either Xcode or
+// only the Command Line Tools installed.
+//
+// Environment:
+// - SWIFTC: Swift compiler to use (default: `xcrun --find swiftc`, then PATH)
+// - SDKROOT: macOS SDK path (default: `xcrun --sdk macosx --show-sdk-path`)
+// - MACOSX_DEPLOYMENT_TARGET: minimum macOS version (default 13.0, required
+//   by ScreenCaptureKit audio capture)
+// - LOQA_UNIVERSAL_BRIDGE=1: build the static library for both arm64 and
+//   x86_64 (lipo), e.g. for universal app bundles
 
 use std::env;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const SWIFT_SRC: &str = "src/screencapture/bridge.swift";
+const DEFAULT_DEPLOYMENT_TARGET: &str = "13.0";
+const UNIVERSAL_ARCHS: &[&str] = &["arm64", "x86_64"];
 
 fn main() {
-    // Only build Swift bridge on macOS
-    if cfg!(target_os = "macos") {
+    record_git_commit();
+
+    // Only build Swift bridge when targeting macOS (the build script itself
+    // runs on the host, so check the target rather than cfg!)
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("macos") {
         build_swift_bridge();
     }
 }
 
-#[cfg(target_os = "macos")]
 fn build_swift_bridge() {
-    let out_dir = env::var("OUT_DIR").unwrap();
-    let swift_src = "src/screencapture/bridge.swift";
-
-    println!("cargo:rerun-if-changed={}", swift_src);
-
-    // Compile Swift to object file
-    let obj_file = PathBuf::from(&out_dir).join("bridge.o");
-
-    let output = Command::new("swiftc")
-        .args(&[
-            "-emit-object",
-            "-o",
-            obj_file.to_str().unwrap(),
-            swift_src,
-            "-target",
-            "arm64-apple-macosx13.0",  // Require macOS 13.0+
-            "-sdk",
-            "/Applications/Xcode.app/Contents/Developer/Platforms/MacOSX.platform/Developer/SDKs/MacOSX.sdk",
-            "-parse-as-library",
-            "-O",  // Optimize
-        ])
-        .output()
-        .expect("Failed to execute swiftc");
+    println!("cargo:rerun-if-changed={}", SWIFT_SRC);
+    for var in [
+        "SWIFTC",
+        "SDKROOT",
+        "MACOSX_DEPLOYMENT_TARGET",
+        "LOQA_UNIVERSAL_BRIDGE",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
 
-    if !output.status.success() {
-        panic!(
-            "Swift compilation failed:\n{}",
-            String::from_utf8_lossy(&output.stderr)
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let swiftc = find_swiftc();
+    let sdk = find_sdk();
+    let deployment_target = env::var("MACOSX_DEPLOYMENT_TARGET")
+        .unwrap_or_else(|_| DEFAULT_DEPLOYMENT_TARGET.to_string());
+
+    let archs: Vec<String> = if env::var("LOQA_UNIVERSAL_BRIDGE").is_ok_and(|v| v == "1") {
+        UNIVERSAL_ARCHS
+            .iter()
+            .map(|arch| arch.to_string())
+            .collect()
+    } else {
+        vec![target_arch()]
+    };
+
+    // Compile Swift to one static library per architecture
+    let mut libs = Vec::new();
+    for arch in &archs {
+        let obj_file = out_dir.join(format!("bridge-{}.o", arch));
+        let target = format!("{}-apple-macosx{}", arch, deployment_target);
+
+        let output = run(
+            Command::new(&swiftc)
+                .arg("-emit-object")
+                .arg("-o")
+                .arg(&obj_file)
+                .arg(SWIFT_SRC)
+                .args(["-target", &target])
+                .arg("-sdk")
+                .arg(&sdk)
+                .arg("-parse-as-library")
+                .arg("-O"), // Optimize
+            "swiftc",
         );
+        if !output.status.success() {
+            panic!(
+                "Swift compilation for {} failed:\n{}",
+                target,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let lib_file = out_dir.join(format!("libloqa_screencapture-{}.a", arch));
+        let _ = std::fs::remove_file(&lib_file);
+        let output = run(
+            Command::new("ar").arg("rcs").arg(&lib_file).arg(&obj_file),
+            "ar",
+        );
+        if !output.status.success() {
+            panic!(
+                "Static library creation failed:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        libs.push(lib_file);
     }
 
-    // Create static library from object file
-    let lib_file = PathBuf::from(&out_dir).join("libloqa_screencapture.a");
-
-    let output = Command::new("ar")
-        .args(&[
-            "rcs",
-            lib_file.to_str().unwrap(),
-            obj_file.to_str().unwrap(),
-        ])
-        .output()
-        .expect("Failed to execute ar");
-
-    if !output.status.success() {
-        panic!(
-            "Static library creation failed:\n{}",
-            String::from_utf8_lossy(&output.stderr)
+    // Combine into the library Rust links against
+    let lib_file = out_dir.join("libloqa_screencapture.a");
+    if let [lib] = libs.as_slice() {
+        std::fs::copy(lib, &lib_file).expect("Failed to copy Swift bridge library");
+    } else {
+        let output = run(
+            Command::new("lipo")
+                .arg("-create")
+                .args(&libs)
+                .arg("-output")
+                .arg(&lib_file),
+            "lipo",
         );
+        if !output.status.success() {
+            panic!(
+                "Universal library creation failed:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
     }
 
     // Link the static library
-    println!("cargo:rustc-link-search=native={}", out_dir);
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
     println!("cargo:rustc-link-lib=static=loqa_screencapture");
 
     // Link required system frameworks
@@ -72,19 +130,125 @@ fn build_swift_bridge() {
     println!("cargo:rustc-link-lib=framework=CoreAudio");
     println!("cargo:rustc-link-lib=framework=Foundation");
 
-    // Link Swift runtime libraries
-    // Find Swift toolchain path
+    // Link Swift runtime libraries: the OS copy first, then the toolchain's
+    // (for back-deployment shims)
     let swift_lib_path = "/usr/lib/swift";
     println!("cargo:rustc-link-search=native={}", swift_lib_path);
-
-    // Add rpath for Swift runtime
     println!("cargo:rustc-link-arg=-Wl,-rpath,{}", swift_lib_path);
-    println!("cargo:rustc-link-arg=-Wl,-rpath,/Applications/Xcode.app/Contents/Developer/Toolchains/XcodeDefault.xctoolchain/usr/lib/swift/macosx");
+    if let Some(toolchain_lib) = toolchain_swift_lib(&swiftc) {
+        println!("cargo:rustc-link-search=native={}", toolchain_lib.display());
+        println!(
+            "cargo:rustc-link-arg=-Wl,-rpath,{}",
+            toolchain_lib.display()
+        );
+    }
 
-    println!("Swift bridge compiled successfully");
+    println!("Swift bridge compiled successfully ({})", archs.join(", "));
 }
 
-#[cfg(not(target_os = "macos"))]
-fn build_swift_bridge() {
-    // No-op on non-macOS platforms
+/// Pass the short hash of the checked-out commit to the crate, if built
+/// from a git checkout with git installed
+fn record_git_commit() {
+    // Rebuild when HEAD moves: HEAD itself changes on checkout, the branch
+    // ref (loose or packed) on commit
+    let git_dir = Path::new(".git");
+    if git_dir.is_dir() {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        println!("cargo:rerun-if-changed=.git/packed-refs");
+        if let Ok(head) = std::fs::read_to_string(git_dir.join("HEAD")) {
+            if let Some(branch) = head.trim().strip_prefix("ref: ") {
+                println!("cargo:rerun-if-changed=.git/{}", branch);
+            }
+        }
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|commit| !commit.is_empty());
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=LOQA_GIT_COMMIT={}", commit);
+    }
+}
+
+/// Swift architecture name for the Rust target
+fn target_arch() -> String {
+    match env::var("CARGO_CFG_TARGET_ARCH").as_deref() {
+        Ok("aarch64") => "arm64".to_string(),
+        Ok("x86_64") => "x86_64".to_string(),
+        Ok(arch) => panic!(
+            "Unsupported macOS architecture {:?}: the ScreenCaptureKit bridge builds for arm64 and x86_64",
+            arch
+        ),
+        Err(_) => panic!("CARGO_CFG_TARGET_ARCH is not set"),
+    }
+}
+
+/// Path to the Swift compiler
+fn find_swiftc() -> PathBuf {
+    if let Ok(swiftc) = env::var("SWIFTC") {
+        return PathBuf::from(swiftc);
+    }
+
+    xcrun(&["--find", "swiftc"])
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("swiftc"))
+}
+
+/// Path to the macOS SDK
+fn find_sdk() -> PathBuf {
+    if let Ok(sdk) = env::var("SDKROOT") {
+        return PathBuf::from(sdk);
+    }
+
+    match xcrun(&["--sdk", "macosx", "--show-sdk-path"]) {
+        Some(sdk) => PathBuf::from(sdk),
+        None => panic!(
+            "Could not find the macOS SDK (`xcrun --sdk macosx --show-sdk-path` failed).\n\
+             Install the Command Line Tools with `xcode-select --install`, or point\n\
+             `xcode-select --switch` at an Xcode installation, or set SDKROOT."
+        ),
+    }
+}
+
+/// The toolchain's Swift runtime directory, next to `swiftc`
+fn toolchain_swift_lib(swiftc: &Path) -> Option<PathBuf> {
+    // Resolve a bare `swiftc` through PATH via xcrun's answer, if any
+    let swiftc = if swiftc.is_absolute() {
+        swiftc.to_path_buf()
+    } else {
+        PathBuf::from(xcrun(&["--find", "swiftc"])?)
+    };
+
+    let lib = swiftc.parent()?.parent()?.join("lib/swift/macosx");
+    lib.is_dir().then_some(lib)
+}
+
+/// Run xcrun, returning its trimmed output on success
+fn xcrun(args: &[&str]) -> Option<String> {
+    let output = Command::new("xcrun").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+/// Run a build tool, failing with install instructions if it is missing
+fn run(command: &mut Command, tool: &str) -> Output {
+    match command.output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => panic!(
+            "`{}` was not found. The ScreenCaptureKit bridge needs the Xcode Command Line Tools:\n\
+             \n    xcode-select --install\n\n\
+             or set SWIFTC to the Swift compiler's path.",
+            tool
+        ),
+        Err(e) => panic!("Failed to run `{}`: {}", tool, e),
+    }
 }
@@ -20,8 +20,10 @@
 use anyhow::Result;
 use futures::stream::StreamExt;
 use hound::{WavSpec, WavWriter};
+use loqa_meetings::audio::{Downmix, DownmixStrategy};
 use loqa_meetings::{
-    AudioBackendConfig, AudioBackendFactory, AudioFrame, AudioSource, NatsClient, TranscriptMessage,
+    AudioBackendConfig, AudioBackendFactory, AudioFrame, AudioSource, ChannelMap, NatsClient,
+    TranscriptMessage,
 };
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -58,39 +60,19 @@ fn downsample_frame(frame: AudioFrame, target_rate: u32) -> AudioFrame {
     }
 }
 
-/// Convert stereo to mono by summing left and right channels
-/// Input samples are interleaved: [L, R, L, R, ...]
-/// Output is mono: [M, M, M, ...]
-/// Note: Does NOT average (no division) to preserve volume when one channel is silent
-fn stereo_to_mono(frame: AudioFrame) -> AudioFrame {
+/// Fold a captured frame to mono
+///
+/// Stereo is summed rather than averaged, to preserve volume when one
+/// channel is silent; surround capture uses the layout's standard matrix.
+fn to_mono(frame: AudioFrame) -> AudioFrame {
     if frame.channels == 1 {
         return frame; // Already mono
     }
 
-    if frame.channels != 2 {
-        // Only support stereo -> mono conversion
-        return frame;
-    }
-
-    let mut mono_samples = Vec::with_capacity(frame.samples.len() / 2);
-
-    // Process pairs of samples (left, right)
-    // Sum without dividing to preserve volume when one channel is silent
-    for chunk in frame.samples.chunks_exact(2) {
-        let left = chunk[0] as i32;
-        let right = chunk[1] as i32;
-        let sum = left + right;
-        // Clamp to prevent overflow
-        let mono = sum.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
-        mono_samples.push(mono);
-    }
-
     AudioFrame {
-        samples: mono_samples,
-        sample_rate: frame.sample_rate,
+        samples: Downmix::for_strategy(&DownmixStrategy::Sum, frame.channels).apply(&frame.samples),
         channels: 1,
-        timestamp_ms: frame.timestamp_ms,
-        source: frame.source,
+        ..frame
     }
 }
 
@@ -110,17 +92,19 @@ async fn main() -> Result<()> {
     info!("✅ Subscribed to transcripts");
 
     // 3. Create macOS audio backend
-    // ScreenCaptureKit captures at 48kHz stereo (System→Left, Mic→Right)
+    // ScreenCaptureKit captures at 48kHz stereo laid out per the default
+    // channel map (System→Left, Mic→Right)
     // Swift handles the mixing with zero-fill for silent sources
+    let channel_map = ChannelMap::default();
     let backend_config = AudioBackendConfig {
         target_sample_rate: 48000, // Native macOS rate (will downsample to 16kHz)
-        target_channels: 2,        // Stereo (System→L, Mic→R)
+        target_channels: channel_map.channels(),
         buffer_duration_ms: 100,
+        ..Default::default()
     };
     let mut backend = AudioBackendFactory::create(AudioSource::System, backend_config)?;
     info!("✅ Audio backend ready: ScreenCaptureKit (48kHz stereo → 16kHz mono)");
-    info!("   System audio → LEFT channel");
-    info!("   Microphone → RIGHT channel");
+    info!("   Channel map: {}", channel_map);
 
     // 4. Spawn transcript listener task
     let stop_flag = Arc::new(AtomicBool::new(false));
@@ -205,7 +189,7 @@ async fn main() -> Result<()> {
                 let downsampled = downsample_frame(frame, 16000);
 
                 // Convert from stereo to mono (Whisper expects mono)
-                let mono = stereo_to_mono(downsampled);
+                let mono = to_mono(downsampled);
 
                 // Collect samples for debugging WAV file
                 all_processed_samples.extend_from_slice(&mono.samples);
@@ -13,6 +13,7 @@
 
 use anyhow::Result;
 use std::path::PathBuf;
+#[cfg(target_os = "macos")]
 use tokio::time::{sleep, Duration};
 
 #[tokio::main]
@@ -34,8 +35,7 @@ async fn main() -> Result<()> {
         println!("✅ ScreenCaptureKit available");
     }
 
-    #[cfg(not(target_os = "macos"))]
-    {
+    if cfg!(not(target_os = "macos")) {
         eprintln!("❌ This test requires macOS");
         return Ok(());
     }
@@ -25,9 +25,7 @@ async fn main() -> Result<()> {
 
     // 4. Send audio in chunks (simulate real-time)
     let chunk_size = 16000 * 5; // 5 seconds at 16kHz
-    let mut chunk_index = 0;
-
-    for chunk_start in (0..audio.samples.len()).step_by(chunk_size) {
+    for (chunk_index, chunk_start) in (0..audio.samples.len()).step_by(chunk_size).enumerate() {
         let chunk_end = (chunk_start + chunk_size).min(audio.samples.len());
         let chunk_samples = &audio.samples[chunk_start..chunk_end];
 
@@ -43,7 +41,7 @@ async fn main() -> Result<()> {
             &pcm_bytes,
             audio.sample_rate,
             audio.channels,
-            chunk_index,
+            chunk_index as u32,
             is_final,
         )
         .await?;
@@ -75,8 +73,6 @@ async fn main() -> Result<()> {
             Ok(None) => break, // Subscription closed
             Err(_) => {}       // Timeout - no transcript yet
         }
-
-        chunk_index += 1;
     }
 
     info!("✅ All chunks sent");
@@ -16,6 +16,7 @@ use anyhow::Result;
 use clap::Parser;
 use loqa_meetings::audio::{
     AudioBackendConfig, AudioBackendFactory, AudioSource, ChunkConfig, ChunkedRecorder,
+    QualityPreset,
 };
 use std::path::PathBuf;
 use std::time::Duration;
@@ -41,6 +42,10 @@ struct Args {
     /// Chunk duration in seconds
     #[arg(short, long, default_value = "300")]
     chunk_duration: u64,
+
+    /// Quality preset (low-latency, balanced, archival)
+    #[arg(short, long, default_value = "balanced")]
+    quality: QualityPreset,
 }
 
 #[tokio::main]
@@ -54,6 +59,7 @@ async fn main() -> Result<()> {
     info!("Recording for {} seconds", args.duration);
     info!("Meeting ID: {}", args.meeting_id);
     info!("Chunk duration: {} seconds", args.chunk_duration);
+    info!("Quality preset: {}", args.quality.name());
 
     // Expand home directory
     let output_dir = shellexpand::tilde(&args.output_dir);
@@ -65,7 +71,8 @@ async fn main() -> Result<()> {
     let backend_config = AudioBackendConfig {
         target_sample_rate: 16000, // 16kHz for Whisper
         target_channels: 1,        // Mono
-        buffer_duration_ms: 100,   // 100ms buffers
+        buffer_duration_ms: args.quality.frame_duration_ms(),
+        ..Default::default()
     };
 
     // Create backend (macOS ScreenCaptureKit for system audio)
@@ -77,8 +84,9 @@ async fn main() -> Result<()> {
     // Create chunked recorder
     let chunk_config = ChunkConfig {
         chunk_duration_secs: args.chunk_duration,
-        output_dir: output_dir.clone(),
-        meeting_id: args.meeting_id.clone(),
+        ..args
+            .quality
+            .chunk_config(args.meeting_id.clone(), output_dir.clone())
     };
 
     let mut recorder = ChunkedRecorder::new(chunk_config)?;
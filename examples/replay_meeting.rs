@@ -0,0 +1,150 @@
+// Example: Replay a stored meeting through NATS
+//
+// This example feeds a recorded meeting back through the STT pipeline
+// without live capture:
+// 1. Load the meeting from storage (configured in config/loqa-meetings.yaml)
+// 2. Check every audio chunk can be decoded (without decoding it)
+// 3. Read its audio chunks one at a time
+// 4. Publish 100ms frames to NATS with the original pacing (or faster)
+// 5. Print the transcripts loqa-core returns
+//
+// Useful for reproducing STT bugs against a known recording, and for
+// demoing the pipeline on machines without capture permissions.
+//
+// Prerequisites:
+// - NATS server running: docker run -p 4222:4222 nats
+// - loqa-core STT service running: cd loqa-core && cargo run
+//
+// Usage: cargo run --example replay_meeting -- <meeting-id> --speed 2
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use futures::stream::StreamExt;
+use loqa_meetings::session::{replay, ReplayOptions, TimeRange};
+use loqa_meetings::{AudioFile, Config, NatsClient, StorageFactory, TranscriptMessage};
+use tracing::{info, warn, Level};
+
+/// Default configuration file (extension resolved by the config loader)
+const CONFIG_PATH: &str = "config/loqa-meetings";
+
+#[derive(Parser)]
+#[command(name = "replay_meeting")]
+#[command(about = "Replay a stored meeting through NATS")]
+struct Args {
+    /// ID of the stored meeting
+    meeting_id: String,
+
+    /// Playback speed (1 = original pacing, 0 = as fast as possible)
+    #[arg(short, long, default_value = "1.0")]
+    speed: f64,
+
+    /// Start of the replayed range, in seconds from the meeting start
+    #[arg(long)]
+    from: Option<f64>,
+
+    /// End of the replayed range, in seconds from the meeting start
+    #[arg(long)]
+    to: Option<f64>,
+
+    /// Session ID to publish under (defaults to <meeting-id>.replay-<n>)
+    #[arg(long)]
+    session_id: Option<String>,
+
+    /// NATS server URL
+    #[arg(long, default_value = "nats://localhost:4222")]
+    nats_url: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging
+    tracing_subscriber::fmt().with_max_level(Level::INFO).init();
+
+    let args = Args::parse();
+
+    let config = match Config::load(CONFIG_PATH) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Failed to load {}: {} (using defaults)", CONFIG_PATH, e);
+            Config::default()
+        }
+    };
+    let storage = StorageFactory::create(&config.storage)?;
+    let record = storage
+        .get_meeting(&args.meeting_id)
+        .await?
+        .with_context(|| format!("Meeting {} not found", args.meeting_id))?;
+
+    // Fail before connecting if any chunk is unreadable
+    for chunk in &record.chunks {
+        let info = AudioFile::probe(&chunk.file_path)
+            .with_context(|| format!("Chunk {} is not playable", chunk.chunk_index))?;
+        info!(
+            "Chunk {}: {} {}Hz {}ch, {:.1}s",
+            chunk.chunk_index,
+            info.codec,
+            info.sample_rate,
+            info.channels,
+            info.duration_seconds.unwrap_or_default()
+        );
+    }
+
+    let range = match (args.from, args.to) {
+        (None, None) => None,
+        (from, to) => {
+            let end_ms = record.chunks.iter().map(|c| c.end_ms).max().unwrap_or(0);
+            let start_ms = from.map(|s| (s * 1000.0) as u64).unwrap_or(0);
+            let end_ms = to.map(|s| (s * 1000.0) as u64).unwrap_or(end_ms);
+            Some(TimeRange::new(start_ms, end_ms)?)
+        }
+    };
+
+    let session_id = args.session_id.unwrap_or_else(|| {
+        format!(
+            "{}.replay-{}",
+            record.meeting_id,
+            uuid::Uuid::new_v4().simple()
+        )
+    });
+    let options = ReplayOptions {
+        speed: args.speed,
+        range,
+        session_id: Some(session_id.clone()),
+        ..Default::default()
+    };
+
+    // Print transcripts for the replayed stream as they arrive
+    let listener = NatsClient::connect(&args.nats_url, session_id.clone()).await?;
+    let mut transcripts = listener.subscribe_transcripts().await?;
+    tokio::spawn(async move {
+        while let Some(msg) = transcripts.next().await {
+            match serde_json::from_slice::<TranscriptMessage>(&msg.payload) {
+                Ok(t) if t.session_id == session_id && !t.partial => {
+                    info!("📝 [{}] {}", t.timestamp, t.text)
+                }
+                _ => {}
+            }
+        }
+    });
+
+    tokio::select! {
+        stats = replay(&args.nats_url, &record, &options) => {
+            let stats = stats?;
+            info!(
+                "Replayed {:.1}s of audio ({} frames) in {:.1}s",
+                stats.audio_ms as f64 / 1000.0,
+                stats.frames,
+                stats.elapsed.as_secs_f64()
+            );
+        }
+        _ = tokio::signal::ctrl_c() => {
+            info!("Replay interrupted");
+            return Ok(());
+        }
+    }
+
+    // Give STT a moment to return the last results
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    Ok(())
+}
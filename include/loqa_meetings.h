@@ -0,0 +1,84 @@
+/*
+ * C API for embedding loqa-meetings recording in desktop shells
+ *
+ * Build the shared library with:
+ *   cargo rustc --release --lib --features capi --crate-type cdylib
+ *
+ * Calls block until they finish. Results are written to `*out` as JSON in
+ * the shapes the HTTP API returns, and errors as {"error": "..."}; free
+ * every returned string with loqa_meetings_string_free. `out` may be NULL
+ * when the result isn't needed.
+ */
+
+#ifndef LOQA_MEETINGS_H
+#define LOQA_MEETINGS_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct LoqaMeetings LoqaMeetings;
+
+typedef enum LoqaStatus {
+    LOQA_OK = 0,
+    /* NULL handle, string that isn't UTF-8, or options that aren't JSON */
+    LOQA_INVALID_ARGUMENT = 1,
+    LOQA_INVALID_REQUEST = 2,
+    LOQA_NOT_FOUND = 3,
+    LOQA_CONFLICT = 4,
+    LOQA_LIMIT_REACHED = 5,
+    LOQA_FAILED = 6,
+} LoqaStatus;
+
+/* Called with each new transcript segment as JSON (valid only for the call) */
+typedef void (*LoqaTranscriptCallback)(void *user_data, const char *segment_json);
+
+/*
+ * Create a meetings service from the configuration at `config_path`
+ * (extension resolved by the config loader), or the defaults if NULL.
+ * Returns NULL if the configuration or storage can't be loaded.
+ */
+LoqaMeetings *loqa_meetings_new(const char *config_path);
+
+/* Free a service, first stopping and saving any meetings still recording */
+void loqa_meetings_free(LoqaMeetings *handle);
+
+/* Free a string returned by the C API */
+void loqa_meetings_string_free(char *string);
+
+/*
+ * Start recording. `options_json` takes the HTTP start request's fields
+ * (NULL = defaults). `*out` gets {"meeting_id", "status": "recording"}, or
+ * {"meeting_id", "status": "queued", "position"} at the session limit with
+ * "queue" set.
+ */
+LoqaStatus loqa_meetings_start(const LoqaMeetings *handle, const char *options_json, char **out);
+
+/*
+ * Stop a recording meeting and save it, or cancel a queued one. `*out` gets
+ * {"meeting_id", "status": "stopped", "stats"} or
+ * {"meeting_id", "status": "cancelled"}.
+ */
+LoqaStatus loqa_meetings_stop(const LoqaMeetings *handle, const char *meeting_id, char **out);
+
+/* Status of a recording or queued meeting, as GET /meetings/:id/status */
+LoqaStatus loqa_meetings_status(const LoqaMeetings *handle, const char *meeting_id, char **out);
+
+/* A recording or stored meeting's whole transcript, as a JSON array */
+LoqaStatus loqa_meetings_transcript(const LoqaMeetings *handle, const char *meeting_id, char **out);
+
+/*
+ * Call `callback` on a service thread with each segment a recording meeting
+ * adds from now on, until it stops. `user_data` must stay valid until then.
+ */
+LoqaStatus loqa_meetings_on_transcript(
+    const LoqaMeetings *handle,
+    const char *meeting_id,
+    LoqaTranscriptCallback callback,
+    void *user_data);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LOQA_MEETINGS_H */
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// An application that was producing audio output when sampled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioApplication {
+    /// Process ID
+    pub pid: i32,
+    /// Bundle identifier (e.g., "us.zoom.xos")
+    pub bundle_id: String,
+    /// Human-readable application name
+    pub name: String,
+}
+
+/// Audio activity attributed to a single application over a reporting window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppActivity {
+    /// Bundle identifier
+    pub bundle_id: String,
+    /// Human-readable application name
+    pub name: String,
+    /// Seconds during which the application was producing audio
+    pub active_secs: f64,
+    /// Share of the window's system audio attributed to this application (0.0 to 1.0)
+    pub share: f32,
+}
+
+/// Per-application activity summary for one reporting window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppActivitySummary {
+    /// Length of the reporting window in seconds
+    pub window_secs: f64,
+    /// Applications sorted by share, largest first
+    pub apps: Vec<AppActivity>,
+}
+
+impl AppActivitySummary {
+    /// The application that contributed most of the audio, if any
+    pub fn dominant(&self) -> Option<&AppActivity> {
+        self.apps.first()
+    }
+}
+
+/// Accumulates which applications were producing audio, weighted by the
+/// system audio level at the time each sample was taken
+///
+/// ScreenCaptureKit delivers a single mixed system stream, so per-application
+/// levels cannot be measured directly. Each sample credits the current system
+/// level to every application that was outputting audio at that moment.
+#[derive(Debug, Default)]
+pub struct AppActivityTracker {
+    apps: HashMap<String, TrackedApp>,
+    window_secs: f64,
+}
+
+#[derive(Debug)]
+struct TrackedApp {
+    name: String,
+    active_secs: f64,
+    weighted_secs: f64,
+}
+
+impl AppActivityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one sample covering `elapsed` time
+    ///
+    /// `level` is the system audio RMS level (0.0 to 1.0) over the same period.
+    pub fn record(&mut self, apps: &[AudioApplication], level: f32, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        self.window_secs += secs;
+
+        for app in apps {
+            let tracked = self
+                .apps
+                .entry(app.bundle_id.clone())
+                .or_insert_with(|| TrackedApp {
+                    name: app.name.clone(),
+                    active_secs: 0.0,
+                    weighted_secs: 0.0,
+                });
+            tracked.active_secs += secs;
+            tracked.weighted_secs += secs * level as f64;
+        }
+    }
+
+    /// Whether any time has been recorded since the last summary
+    pub fn is_empty(&self) -> bool {
+        self.window_secs == 0.0
+    }
+
+    /// Summarize the current window and start a new one
+    pub fn take_summary(&mut self) -> AppActivitySummary {
+        let total_weighted: f64 = self.apps.values().map(|a| a.weighted_secs).sum();
+        let total_active: f64 = self.apps.values().map(|a| a.active_secs).sum();
+
+        let mut apps: Vec<AppActivity> = self
+            .apps
+            .drain()
+            .map(|(bundle_id, tracked)| {
+                // Fall back to active time when the system stream was silent
+                let share = if total_weighted > 0.0 {
+                    tracked.weighted_secs / total_weighted
+                } else if total_active > 0.0 {
+                    tracked.active_secs / total_active
+                } else {
+                    0.0
+                };
+
+                AppActivity {
+                    bundle_id,
+                    name: tracked.name,
+                    active_secs: tracked.active_secs,
+                    share: share as f32,
+                }
+            })
+            .collect();
+
+        apps.sort_by(|a, b| b.share.total_cmp(&a.share));
+
+        let summary = AppActivitySummary {
+            window_secs: self.window_secs,
+            apps,
+        };
+        self.window_secs = 0.0;
+
+        summary
+    }
+}
+
+/// RMS level (0.0 to 1.0) of one channel of interleaved i16 samples
+pub fn channel_level(samples: &[i16], channels: u16, channel: usize) -> f32 {
+    let channels = channels.max(1) as usize;
+    if channel >= channels {
+        return 0.0;
+    }
+
+    let (sum, count) = samples.iter().skip(channel).step_by(channels).fold(
+        (0.0f64, 0usize),
+        |(sum, count), &s| {
+            let v = s as f64 / i16::MAX as f64;
+            (sum + v * v, count + 1)
+        },
+    );
+
+    if count == 0 {
+        0.0
+    } else {
+        (sum / count as f64).sqrt() as f32
+    }
+}
@@ -1,6 +1,11 @@
 use anyhow::Result;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
 use tokio::sync::mpsc;
 
+use super::resample::ResamplerQuality;
+
 /// Audio stream source type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AudioStreamSource {
@@ -8,6 +13,56 @@ pub enum AudioStreamSource {
     System,
     /// Microphone input
     Microphone,
+    /// Additional microphone, indexed by its position in the session's device list
+    MicrophoneDevice(u8),
+}
+
+/// Names used in the HTTP API: "system", "mic", and "mic-<index>" for
+/// additional microphones (indexed from 0, in configured order)
+impl fmt::Display for AudioStreamSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioStreamSource::System => write!(f, "system"),
+            AudioStreamSource::Microphone => write!(f, "mic"),
+            AudioStreamSource::MicrophoneDevice(index) => write!(f, "mic-{}", index),
+        }
+    }
+}
+
+impl FromStr for AudioStreamSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "system" => Ok(AudioStreamSource::System),
+            "mic" | "microphone" => Ok(AudioStreamSource::Microphone),
+            other => other
+                .strip_prefix("mic-")
+                .and_then(|index| index.parse().ok())
+                .map(AudioStreamSource::MicrophoneDevice)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown audio source \"{}\" (expected system, mic, or mic-<index>)",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+/// Serialized by the same names
+impl Serialize for AudioStreamSource {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AudioStreamSource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
 }
 
 /// Audio sample data (16-bit PCM, interleaved)
@@ -25,6 +80,28 @@ pub struct AudioFrame {
     pub source: AudioStreamSource,
 }
 
+/// Kind of capture device change reported by a backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceEventKind {
+    /// The system default input device changed
+    DefaultInputChanged,
+    /// Capture was rebound to a new device
+    Rebound,
+    /// Rebinding to the new device failed (capture may be silent)
+    RebindFailed,
+}
+
+/// Capture device change reported by a backend
+#[derive(Debug, Clone)]
+pub struct DeviceEvent {
+    /// What changed
+    pub kind: DeviceEventKind,
+    /// Device name (or error detail for failed rebinds)
+    pub device: String,
+    /// Timestamp in milliseconds since recording started
+    pub timestamp_ms: u64,
+}
+
 /// Configuration for audio backend
 #[derive(Debug, Clone)]
 pub struct AudioBackendConfig {
@@ -34,6 +111,12 @@ pub struct AudioBackendConfig {
     pub target_channels: u16,
     /// Buffer size in milliseconds (affects latency)
     pub buffer_duration_ms: u64,
+    /// Bundle IDs of applications excluded from system audio capture
+    pub excluded_apps: Vec<String>,
+    /// Pinned microphone device ID (None = follow the system default input)
+    pub microphone_device: Option<String>,
+    /// Interpolation used when captured audio is converted to `target_sample_rate`
+    pub resampler_quality: ResamplerQuality,
 }
 
 impl Default for AudioBackendConfig {
@@ -42,6 +125,9 @@ impl Default for AudioBackendConfig {
             target_sample_rate: 16000, // 16kHz for Whisper
             target_channels: 1,        // Mono
             buffer_duration_ms: 100,   // 100ms buffers
+            excluded_apps: Vec::new(),
+            microphone_device: None,
+            resampler_quality: ResamplerQuality::default(),
         }
     }
 }
@@ -67,12 +153,67 @@ pub trait AudioBackend: Send + Sync {
 
     /// Get backend name for logging
     fn name(&self) -> &str;
+
+    /// Whether the backend has delivered all the audio it has, so silence
+    /// from it is expected (a replayed file at its end)
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    /// Take the receiver for device change events, if the backend reports them
+    ///
+    /// Only available after `start()`; returns `None` on subsequent calls.
+    fn take_device_events(&mut self) -> Option<mpsc::UnboundedReceiver<DeviceEvent>> {
+        None
+    }
+}
+
+/// A capture source and whether this build can capture from it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CaptureBackendInfo {
+    /// Source sessions capture from ("system", "microphone", "network", or
+    /// "file")
+    pub source: &'static str,
+    /// Backend capturing the source on this platform, if any
+    pub backend: Option<&'static str>,
+    pub available: bool,
 }
 
 /// Audio backend factory
 pub struct AudioBackendFactory;
 
 impl AudioBackendFactory {
+    /// Capture sources and the backend each uses on this platform
+    pub fn backends() -> Vec<CaptureBackendInfo> {
+        let system = if cfg!(target_os = "macos") {
+            Some("macOS ScreenCaptureKit")
+        } else {
+            None
+        };
+        vec![
+            CaptureBackendInfo {
+                source: "system",
+                backend: system,
+                available: system.is_some(),
+            },
+            CaptureBackendInfo {
+                source: "microphone",
+                backend: Some("cpal"),
+                available: super::microphone::MicrophoneBackend::is_available(),
+            },
+            CaptureBackendInfo {
+                source: "network",
+                backend: Some("RTP/UDP receiver"),
+                available: true,
+            },
+            CaptureBackendInfo {
+                source: "file",
+                backend: Some("file replay"),
+                available: true,
+            },
+        ]
+    }
+
     /// Create audio backend based on platform and configuration
     pub fn create(
         source: AudioSource,
@@ -89,16 +230,26 @@ impl AudioBackendFactory {
 
                 #[cfg(not(target_os = "macos"))]
                 {
+                    let _ = config;
                     anyhow::bail!("System audio capture is only supported on macOS")
                 }
             }
 
             AudioSource::Microphone => {
-                todo!("Create cpal microphone backend")
+                let backend = super::microphone::MicrophoneBackend::new(config)?;
+                Ok(Box::new(backend))
+            }
+
+            AudioSource::Network(network) => {
+                let _ = config;
+                let backend = super::network::NetworkBackend::new(network)?;
+                Ok(Box::new(backend))
             }
 
-            AudioSource::File(path) => {
-                todo!("Create file-based backend for path: {:?}", path)
+            AudioSource::File(file) => {
+                let backend =
+                    super::file_source::FileBackend::new(file, config.buffer_duration_ms)?;
+                Ok(Box::new(backend))
             }
         }
     }
@@ -109,8 +260,10 @@ impl AudioBackendFactory {
 pub enum AudioSource {
     /// System audio (macOS ScreenCaptureKit only)
     System,
-    /// Microphone input (all platforms)
+    /// Microphone input (all platforms, with the `microphone` feature)
     Microphone,
-    /// File input (for testing/batch processing)
-    File(String),
+    /// Audio streamed from another device over RTP or UDP
+    Network(super::network::NetworkSourceConfig),
+    /// An audio file replayed as captured audio (for testing/batch processing)
+    File(super::file_source::FileSourceConfig),
 }
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::backend::{AudioFrame, AudioStreamSource};
+
+/// What an interleaved capture channel carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelRole {
+    /// System audio (the remote participants)
+    System,
+    /// The local microphone
+    Microphone,
+    /// Ignored when splitting
+    Unused,
+}
+
+impl ChannelRole {
+    /// Stream source of frames split out for this role
+    pub fn source(self) -> Option<AudioStreamSource> {
+        match self {
+            ChannelRole::System => Some(AudioStreamSource::System),
+            ChannelRole::Microphone => Some(AudioStreamSource::Microphone),
+            ChannelRole::Unused => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ChannelRole::System => "system",
+            ChannelRole::Microphone => "microphone",
+            ChannelRole::Unused => "unused",
+        }
+    }
+}
+
+/// Role of each channel in multichannel system capture, in interleaved order
+///
+/// Written as a list in config, e.g. `[system, microphone]`. The default
+/// matches the ScreenCaptureKit bridge, which mixes system audio to the left
+/// channel and the microphone to the right.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChannelMap(Vec<ChannelRole>);
+
+impl Default for ChannelMap {
+    fn default() -> Self {
+        Self(vec![ChannelRole::System, ChannelRole::Microphone])
+    }
+}
+
+impl ChannelMap {
+    pub fn new(roles: Vec<ChannelRole>) -> Self {
+        Self(roles)
+    }
+
+    /// Roles in channel order
+    pub fn roles(&self) -> &[ChannelRole] {
+        &self.0
+    }
+
+    /// Number of channels the map describes
+    pub fn channels(&self) -> u16 {
+        self.0.len() as u16
+    }
+
+    /// Role names in channel order, e.g. `["system", "microphone"]`, as
+    /// stored with recorded chunks
+    pub fn labels(&self) -> Vec<String> {
+        self.0.iter().map(|role| role.name().to_string()).collect()
+    }
+
+    /// Index of the first channel carrying `role`
+    pub fn channel(&self, role: ChannelRole) -> Option<usize> {
+        self.0.iter().position(|&r| r == role)
+    }
+
+    /// Split a multichannel frame into one mono frame per source
+    ///
+    /// Frames come out system first, then microphone, tagged with their
+    /// source. Channels sharing a role are summed, and unused channels are
+    /// dropped. Returns `None` if the frame's channel count does not match
+    /// the map, so a changed capture layout is never split by guesswork.
+    pub fn split(&self, frame: &AudioFrame) -> Option<Vec<AudioFrame>> {
+        if frame.channels != self.channels() || self.0.len() < 2 {
+            return None;
+        }
+
+        let channels = self.0.len();
+        Some(
+            [ChannelRole::System, ChannelRole::Microphone]
+                .into_iter()
+                .filter(|&role| self.channel(role).is_some())
+                .map(|role| AudioFrame {
+                    samples: frame
+                        .samples
+                        .chunks_exact(channels)
+                        .map(|channel_samples| {
+                            channel_samples
+                                .iter()
+                                .zip(&self.0)
+                                .filter(|(_, &r)| r == role)
+                                .fold(0i16, |sum, (&sample, _)| sum.saturating_add(sample))
+                        })
+                        .collect(),
+                    sample_rate: frame.sample_rate,
+                    channels: 1,
+                    timestamp_ms: frame.timestamp_ms,
+                    source: role.source().unwrap_or(frame.source),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for ChannelMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<_> = self.0.iter().map(|role| role.name()).collect();
+        write!(f, "[{}]", names.join(", "))
+    }
+}
@@ -1,11 +1,19 @@
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
 use std::fs::{self, File};
 use std::io::BufWriter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
 use tracing::{info, warn};
 
 use super::backend::AudioFrame;
+use super::float::{sample_to_i16, FloatFrame};
+use super::wavinfo::WavInfo;
+use super::workdir::MeetingDirLock;
+use crate::version::VERSION;
+use chrono::{DateTime, Utc};
 
 /// Chunk configuration
 #[derive(Debug, Clone)]
@@ -16,6 +24,22 @@ pub struct ChunkConfig {
     pub output_dir: PathBuf,
     /// Meeting ID (used for chunk filenames)
     pub meeting_id: String,
+    /// Sample format chunks are written in
+    pub codec: ChunkCodec,
+    /// What each channel carries, e.g. `["system", "microphone"]`, stored
+    /// in multichannel chunks (empty = unlabelled)
+    pub channel_labels: Vec<String>,
+    /// Meeting title, tagged in each chunk
+    pub title: Option<String>,
+    /// Wall-clock time of frame timestamp 0, so each chunk can be tagged
+    /// with when its audio starts
+    pub started_at: Option<DateTime<Utc>>,
+    /// Where chunks are split once they reach the chunk duration
+    pub rotation: ChunkRotation,
+    /// Seconds at the end of each chunk repeated at the start of the next,
+    /// so a chunk transcribed on its own keeps words cut at its start
+    /// (0 = no overlap)
+    pub overlap_secs: u64,
 }
 
 impl ChunkConfig {
@@ -24,12 +48,98 @@ impl ChunkConfig {
             chunk_duration_secs: 300, // 5 minutes default
             output_dir,
             meeting_id,
+            codec: ChunkCodec::default(),
+            channel_labels: Vec::new(),
+            title: None,
+            started_at: None,
+            rotation: ChunkRotation::default(),
+            overlap_secs: 0,
+        }
+    }
+}
+
+/// Where chunks are split
+///
+/// By default a chunk ends exactly at the chunk duration, often mid-word.
+/// With `on_silence`, a full chunk is kept open until the audio pauses, so
+/// each chunk can be played and transcribed on its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkRotation {
+    /// Wait for a pause before starting the next chunk
+    pub on_silence: bool,
+    /// Longest a chunk may run past the chunk duration waiting for a
+    /// pause, in seconds; it is split there regardless
+    pub tolerance_secs: u64,
+    /// Frames quieter than this (RMS, dBFS) count as silence
+    pub silence_threshold_dbfs: f32,
+    /// Pause needed before splitting, in milliseconds
+    pub min_silence_ms: u64,
+}
+
+impl Default for ChunkRotation {
+    fn default() -> Self {
+        Self {
+            on_silence: false,
+            tolerance_secs: 10,
+            silence_threshold_dbfs: -45.0,
+            min_silence_ms: 300,
+        }
+    }
+}
+
+/// Duration of a frame in milliseconds
+fn frame_ms(frame: &FloatFrame) -> u64 {
+    let frames = frame.samples.len() / frame.channels.max(1) as usize;
+    frames as u64 * 1000 / frame.sample_rate.max(1) as u64
+}
+
+/// RMS level of float samples in dBFS (-inf for digital silence)
+fn rms_dbfs(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+
+    let sum: f64 = samples.iter().map(|&s| s as f64 * s as f64).sum();
+    20.0 * (sum / samples.len() as f64).sqrt().log10() as f32
+}
+
+/// Sample format of recorded WAV chunks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkCodec {
+    /// 16-bit integer PCM
+    #[default]
+    Pcm16,
+    /// 32-bit float PCM (headroom for later processing)
+    Float32,
+}
+
+impl ChunkCodec {
+    /// Bytes each sample takes on disk
+    fn bytes_per_sample(self) -> u64 {
+        match self {
+            ChunkCodec::Pcm16 => 2,
+            ChunkCodec::Float32 => 4,
+        }
+    }
+
+    fn wav_spec(self, sample_rate: u32, channels: u16) -> hound::WavSpec {
+        let (bits_per_sample, sample_format) = match self {
+            ChunkCodec::Pcm16 => (16, hound::SampleFormat::Int),
+            ChunkCodec::Float32 => (32, hound::SampleFormat::Float),
+        };
+        hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample,
+            sample_format,
         }
     }
 }
 
 /// Metadata for a single chunk
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChunkMetadata {
     /// Chunk number (0-indexed)
     pub chunk_index: usize,
@@ -45,6 +155,48 @@ pub struct ChunkMetadata {
     pub channels: u16,
     /// Number of samples in this chunk
     pub sample_count: usize,
+    /// Audio track to decode, for files with several (e.g. an uploaded
+    /// screen recording); None means the first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<usize>,
+    /// Milliseconds at the start of the file repeated from the end of the
+    /// previous chunk; the file's audio starts at `start_ms - overlap_ms`
+    #[serde(default)]
+    pub overlap_ms: u64,
+    /// SHA-256 of the finished file (hex), to detect corruption later
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// What each channel carries, e.g. `["system", "microphone"]` (empty =
+    /// unlabelled); also tagged in the WAV file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channel_labels: Vec<String>,
+}
+
+impl ChunkMetadata {
+    /// Interleaved samples at the start of the file repeated from the
+    /// previous chunk
+    pub fn overlap_samples(&self) -> usize {
+        (self.overlap_ms * self.sample_rate as u64 / 1000) as usize * self.channels as usize
+    }
+}
+
+/// SHA-256 of a file's contents, as lowercase hex
+pub fn file_sha256(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// How far a recorder has got writing chunks
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChunkProgress {
+    /// Chunk files written so far, including the one being written
+    pub chunks_count: usize,
+    /// Size of the chunk files (the current chunk counted by samples written)
+    pub audio_bytes: u64,
+    /// How full the current chunk is, 0-100 (None between chunks)
+    pub current_chunk_percent: Option<f64>,
 }
 
 /// Chunked audio recorder
@@ -55,6 +207,20 @@ pub struct ChunkedRecorder {
     current_chunk: Option<ChunkWriter>,
     chunk_index: usize,
     meeting_start_ms: u64,
+    /// Finished chunks, in order
+    chunks: Vec<ChunkMetadata>,
+    /// Size on disk of the finished chunks
+    finished_bytes: u64,
+    /// Set after a write fails; no further audio is written
+    failed: bool,
+    /// Claim on the output directory, checked before each new chunk
+    lock: Option<MeetingDirLock>,
+    /// Trailing silence, for rotating on silence
+    silent_ms: u64,
+    /// Most recent frames, repeated at the start of the next chunk
+    overlap: VecDeque<FloatFrame>,
+    /// Duration of `overlap`
+    overlap_ms: u64,
 }
 
 impl ChunkedRecorder {
@@ -72,95 +238,251 @@ impl ChunkedRecorder {
             current_chunk: None,
             chunk_index: 0,
             meeting_start_ms: 0,
+            chunks: Vec::new(),
+            finished_bytes: 0,
+            failed: false,
+            lock: None,
+            silent_ms: 0,
+            overlap: VecDeque::new(),
+            overlap_ms: 0,
         })
     }
 
-    /// Process incoming audio frames and save to chunks
-    pub async fn record(
-        &mut self,
-        mut audio_rx: mpsc::Receiver<AudioFrame>,
-    ) -> Result<Vec<ChunkMetadata>> {
-        let mut metadata = Vec::new();
+    /// Hold `lock` while recording, refusing to start a chunk once another
+    /// instance has claimed the directory and keeping its manifest current
+    pub fn with_lock(mut self, lock: MeetingDirLock) -> Self {
+        self.lock = Some(lock);
+        self
+    }
 
-        info!("Starting chunked recording");
+    /// Release the directory lock, if held
+    pub fn release_lock(&mut self) {
+        self.lock = None;
+    }
 
-        while let Some(frame) = audio_rx.recv().await {
-            // Initialize meeting start time from first frame
-            if self.meeting_start_ms == 0 {
-                self.meeting_start_ms = frame.timestamp_ms;
+    /// Take the directory lock, if held
+    pub fn take_lock(&mut self) -> Option<MeetingDirLock> {
+        self.lock.take()
+    }
+
+    /// Write one frame, starting a new chunk when the current one is full
+    ///
+    /// Returns the chunk the frame completed, if any. After an error the
+    /// current chunk is closed and later frames are ignored.
+    pub fn write(&mut self, frame: &FloatFrame) -> Result<Option<ChunkMetadata>> {
+        if self.failed {
+            return Ok(None);
+        }
+
+        let result = self.write_frame(frame);
+        if result.is_err() {
+            self.failed = true;
+            if let Err(e) = self.finish() {
+                warn!("Failed to close audio chunk: {:#}", e);
             }
+        }
+        result
+    }
 
-            // Check if we need to start a new chunk
-            if self.should_start_new_chunk(&frame) {
-                // Finish current chunk
-                if let Some(chunk) = self.current_chunk.take() {
-                    let chunk_meta = chunk.finish()?;
-                    info!(
-                        "Chunk {} complete: {:.1}s - {:.1}s ({} samples)",
-                        chunk_meta.chunk_index,
-                        chunk_meta.start_ms as f64 / 1000.0,
-                        chunk_meta.end_ms as f64 / 1000.0,
-                        chunk_meta.sample_count
-                    );
-                    metadata.push(chunk_meta);
-                }
+    fn write_frame(&mut self, frame: &FloatFrame) -> Result<Option<ChunkMetadata>> {
+        // Initialize meeting start time from first frame
+        if self.meeting_start_ms == 0 {
+            self.meeting_start_ms = frame.timestamp_ms;
+        }
+
+        if self.config.rotation.on_silence {
+            if rms_dbfs(&frame.samples) < self.config.rotation.silence_threshold_dbfs {
+                self.silent_ms += frame_ms(frame);
+            } else {
+                self.silent_ms = 0;
+            }
+        }
+
+        // Check if we need to start a new chunk
+        let mut finished = None;
+        if self.should_start_new_chunk(frame) {
+            finished = self.finish()?;
+            self.current_chunk = Some(self.start_new_chunk(frame)?);
+        }
+
+        // Write frame to current chunk
+        if let Some(chunk) = &mut self.current_chunk {
+            chunk.write_frame(frame)?;
+        }
 
-                // Start new chunk
-                self.current_chunk = Some(self.start_new_chunk(&frame)?);
+        if self.config.overlap_secs > 0 {
+            self.overlap.push_back(frame.clone());
+            self.overlap_ms += frame_ms(frame);
+            // Keep just enough frames to cover the overlap
+            while let Some(oldest) = self.overlap.front() {
+                let without = self.overlap_ms - frame_ms(oldest);
+                if without < self.config.overlap_secs * 1000 {
+                    break;
+                }
+                self.overlap_ms = without;
+                self.overlap.pop_front();
             }
+        }
+
+        Ok(finished)
+    }
+
+    /// Close the current chunk, returning it if there was one
+    pub fn finish(&mut self) -> Result<Option<ChunkMetadata>> {
+        let Some(chunk) = self.current_chunk.take() else {
+            return Ok(None);
+        };
 
-            // Write frame to current chunk
-            if let Some(chunk) = &mut self.current_chunk {
-                chunk.write_frame(&frame)?;
+        let chunk_meta = chunk.finish()?;
+        info!(
+            "Chunk {} complete: {:.1}s - {:.1}s ({} samples)",
+            chunk_meta.chunk_index,
+            chunk_meta.start_ms as f64 / 1000.0,
+            chunk_meta.end_ms as f64 / 1000.0,
+            chunk_meta.sample_count
+        );
+        self.finished_bytes += fs::metadata(&chunk_meta.file_path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        self.chunks.push(chunk_meta.clone());
+        if let Some(lock) = &self.lock {
+            if let Err(e) = lock.update_manifest(&self.chunks) {
+                warn!("Failed to update recording manifest: {:#}", e);
             }
         }
+        Ok(Some(chunk_meta))
+    }
 
-        // Finish final chunk
-        if let Some(chunk) = self.current_chunk.take() {
-            let chunk_meta = chunk.finish()?;
-            info!(
-                "Final chunk {} complete: {:.1}s - {:.1}s ({} samples)",
-                chunk_meta.chunk_index,
-                chunk_meta.start_ms as f64 / 1000.0,
-                chunk_meta.end_ms as f64 / 1000.0,
-                chunk_meta.sample_count
-            );
-            metadata.push(chunk_meta);
+    /// Chunks finished so far
+    pub fn chunks(&self) -> &[ChunkMetadata] {
+        &self.chunks
+    }
+
+    /// Whether a write failed and recording stopped
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Chunks and bytes written, and how full the current chunk is
+    pub fn progress(&self) -> ChunkProgress {
+        let current = self.current_chunk.as_ref();
+        let current_bytes = current.map_or(0, |chunk| {
+            chunk.metadata.sample_count as u64 * self.config.codec.bytes_per_sample()
+        });
+        let chunk_duration_ms = self.config.chunk_duration_secs.max(1) * 1000;
+
+        ChunkProgress {
+            chunks_count: self.chunks.len() + usize::from(current.is_some()),
+            audio_bytes: self.finished_bytes + current_bytes,
+            current_chunk_percent: current.map(|chunk| {
+                let elapsed_ms = chunk.metadata.end_ms - chunk.metadata.start_ms;
+                (elapsed_ms as f64 / chunk_duration_ms as f64 * 100.0).min(100.0)
+            }),
+        }
+    }
+
+    /// Process incoming audio frames and save to chunks
+    pub async fn record(
+        &mut self,
+        audio_rx: mpsc::Receiver<AudioFrame>,
+    ) -> Result<Vec<ChunkMetadata>> {
+        self.record_frames(audio_rx).await
+    }
+
+    /// Process incoming float frames and save to chunks
+    ///
+    /// Float chunks receive the samples as they are, without passing through
+    /// 16-bit.
+    pub async fn record_float(
+        &mut self,
+        audio_rx: mpsc::Receiver<FloatFrame>,
+    ) -> Result<Vec<ChunkMetadata>> {
+        self.record_frames(audio_rx).await
+    }
+
+    async fn record_frames<F: Into<FloatFrame>>(
+        &mut self,
+        mut audio_rx: mpsc::Receiver<F>,
+    ) -> Result<Vec<ChunkMetadata>> {
+        info!("Starting chunked recording");
+
+        while let Some(frame) = audio_rx.recv().await {
+            self.write_frame(&frame.into())?;
         }
 
+        // Finish final chunk
+        self.finish()?;
+
         info!(
             "Chunked recording complete: {} chunks saved",
-            metadata.len()
+            self.chunks.len()
         );
 
-        Ok(metadata)
+        Ok(self.chunks.clone())
     }
 
-    fn should_start_new_chunk(&self, frame: &AudioFrame) -> bool {
+    fn should_start_new_chunk(&self, frame: &FloatFrame) -> bool {
         match &self.current_chunk {
             None => true, // No current chunk, start one
             Some(chunk) => {
                 // Check if chunk duration exceeded
                 let chunk_duration_ms = self.config.chunk_duration_secs * 1000;
                 let elapsed_ms = frame.timestamp_ms - chunk.metadata.start_ms;
-                elapsed_ms >= chunk_duration_ms
+                let rotation = &self.config.rotation;
+                if !rotation.on_silence {
+                    return elapsed_ms >= chunk_duration_ms;
+                }
+
+                // A full chunk ends at the first pause, or once the
+                // tolerance runs out
+                elapsed_ms >= chunk_duration_ms + rotation.tolerance_secs * 1000
+                    || (elapsed_ms >= chunk_duration_ms
+                        && self.silent_ms >= rotation.min_silence_ms)
             }
         }
     }
 
-    fn start_new_chunk(&mut self, frame: &AudioFrame) -> Result<ChunkWriter> {
+    fn start_new_chunk(&mut self, frame: &FloatFrame) -> Result<ChunkWriter> {
+        if let Some(lock) = &self.lock {
+            lock.verify()?;
+        }
+
         let chunk_path = self.config.output_dir.join(format!(
             "{}-chunk-{:03}.wav",
             self.config.meeting_id, self.chunk_index
         ));
 
-        let chunk = ChunkWriter::new(
+        // The end of the previous chunk, in this chunk's format
+        let lead_in: Vec<FloatFrame> = self
+            .overlap
+            .iter()
+            .filter(|f| f.sample_rate == frame.sample_rate && f.channels == frame.channels)
+            .cloned()
+            .collect();
+        let overlap_ms: u64 = lead_in.iter().map(frame_ms).sum();
+        let audio_start_ms = frame.timestamp_ms.saturating_sub(overlap_ms);
+
+        let info = WavInfo {
+            title: self.config.title.clone(),
+            meeting_id: Some(self.config.meeting_id.clone()),
+            created: self.config.started_at.map(|started_at| {
+                started_at + chrono::Duration::milliseconds(audio_start_ms as i64)
+            }),
+            track: Some(self.chunk_index + 1),
+            software: Some(format!("loqa-meetings {}", VERSION)),
+            channel_labels: Vec::new(),
+        };
+        let mut chunk = ChunkWriter::new(
             chunk_path,
             self.chunk_index,
             frame.timestamp_ms,
             frame.sample_rate,
             frame.channels,
-        )?;
+            self.config.codec,
+            info,
+        )?
+        .with_channel_labels(&self.config.channel_labels);
+        chunk.write_lead_in(&lead_in, overlap_ms)?;
 
         self.chunk_index += 1;
 
@@ -171,7 +493,10 @@ impl ChunkedRecorder {
 /// Writes a single chunk to disk as WAV file
 struct ChunkWriter {
     writer: Option<hound::WavWriter<BufWriter<File>>>,
+    codec: ChunkCodec,
     metadata: ChunkMetadata,
+    /// Tags appended once the audio is written
+    info: WavInfo,
 }
 
 impl ChunkWriter {
@@ -181,19 +506,18 @@ impl ChunkWriter {
         start_ms: u64,
         sample_rate: u32,
         channels: u16,
+        codec: ChunkCodec,
+        info: WavInfo,
     ) -> Result<Self> {
-        let spec = hound::WavSpec {
-            channels,
-            sample_rate,
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
+        let spec = codec.wav_spec(sample_rate, channels);
 
         let writer = hound::WavWriter::create(&file_path, spec)
             .with_context(|| format!("Failed to create WAV file: {:?}", file_path))?;
 
         Ok(Self {
             writer: Some(writer),
+            codec,
+            info,
             metadata: ChunkMetadata {
                 chunk_index,
                 file_path,
@@ -202,16 +526,42 @@ impl ChunkWriter {
                 sample_rate,
                 channels,
                 sample_count: 0,
+                track: None,
+                overlap_ms: 0,
+                sha256: None,
+                channel_labels: Vec::new(),
             },
         })
     }
 
-    fn write_frame(&mut self, frame: &AudioFrame) -> Result<()> {
+    /// Label the chunk's channels, if `labels` describes each of them
+    fn with_channel_labels(mut self, labels: &[String]) -> Self {
+        if self.metadata.channels > 1 && labels.len() == self.metadata.channels as usize {
+            self.metadata.channel_labels = labels.to_vec();
+            self.info.channel_labels = labels.to_vec();
+        }
+        self
+    }
+
+    /// Write audio repeated from the previous chunk, ahead of this one's own
+    fn write_lead_in(&mut self, frames: &[FloatFrame], overlap_ms: u64) -> Result<()> {
+        let end_ms = self.metadata.end_ms;
+        for frame in frames {
+            self.write_frame(frame)?;
+        }
+        self.metadata.end_ms = end_ms;
+        self.metadata.overlap_ms = overlap_ms;
+        Ok(())
+    }
+
+    fn write_frame(&mut self, frame: &FloatFrame) -> Result<()> {
         if let Some(writer) = &mut self.writer {
             for &sample in &frame.samples {
-                writer
-                    .write_sample(sample)
-                    .context("Failed to write sample to WAV")?;
+                match self.codec {
+                    ChunkCodec::Pcm16 => writer.write_sample(sample_to_i16(sample)),
+                    ChunkCodec::Float32 => writer.write_sample(sample),
+                }
+                .context("Failed to write sample to WAV")?;
             }
 
             self.metadata.end_ms = frame.timestamp_ms;
@@ -226,6 +576,27 @@ impl ChunkWriter {
             writer.finalize().context("Failed to finalize WAV file")?;
         }
 
+        // Untagged audio is still usable
+        if !self.info.is_empty() {
+            if let Err(e) = self.info.append_to(&self.metadata.file_path) {
+                warn!(
+                    "Failed to tag {}: {:#}",
+                    self.metadata.file_path.display(),
+                    e
+                );
+            }
+        }
+
+        // A chunk that can't be hashed is still kept, just unverifiable
+        match file_sha256(&self.metadata.file_path) {
+            Ok(sha256) => self.metadata.sha256 = Some(sha256),
+            Err(e) => warn!(
+                "Failed to checksum {}: {}",
+                self.metadata.file_path.display(),
+                e
+            ),
+        }
+
         Ok(self.metadata.clone())
     }
 }
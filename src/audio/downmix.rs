@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use symphonia::core::audio::Channels;
+
+/// Gain for channels folded into mono at -3dB
+const MINUS_3DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// How captured stereo is folded to mono
+///
+/// Strategies other than `left_only`/`right_only` only decide stereo; more
+/// channels always use the standard layout fold-down (see
+/// [`Downmix::for_channel_count`]) unless `weighted` gives one weight per
+/// channel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownmixStrategy {
+    /// Add the channels: keeps the volume of audio present in only one
+    /// channel, but can clip when both are loud
+    #[default]
+    Sum,
+    /// Mean of the channels: never clips, but one-sided audio is 6dB quieter
+    Average,
+    /// Explicit per-channel weights, in interleaved order
+    Weighted(Vec<f32>),
+    /// First (left) channel only
+    LeftOnly,
+    /// Second (right) channel only
+    RightOnly,
+}
+
+/// Weights for folding interleaved channels down to mono
+///
+/// Built from the file's channel layout following the ITU-R BS.775 stereo
+/// fold-down, then averaged to mono: front left/right at 0.5, centre at
+/// -3dB (dialogue in surround mixes lives there), surrounds at -9dB, and
+/// LFE dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Downmix {
+    weights: Vec<f32>,
+}
+
+impl Downmix {
+    /// Explicit per-channel weights, in interleaved order
+    pub fn new(weights: Vec<f32>) -> Self {
+        Self { weights }
+    }
+
+    /// Matrix for a known channel layout (channels in symphonia order)
+    pub fn for_layout(layout: Channels) -> Self {
+        if layout.count() <= 2 {
+            return Self::for_channel_count(layout.count() as u16);
+        }
+
+        Self::new(layout.iter().map(channel_weight).collect())
+    }
+
+    /// Matrix when only the channel count is known
+    ///
+    /// Six channels are assumed to be 5.1 in SMPTE order (L R C LFE Ls Rs),
+    /// the usual layout for files without a channel mask. Other counts are
+    /// averaged.
+    pub fn for_channel_count(channels: u16) -> Self {
+        match channels {
+            0 | 1 => Self::new(vec![1.0]),
+            6 => Self::for_layout(
+                Channels::FRONT_LEFT
+                    | Channels::FRONT_RIGHT
+                    | Channels::FRONT_CENTRE
+                    | Channels::LFE1
+                    | Channels::REAR_LEFT
+                    | Channels::REAR_RIGHT,
+            ),
+            n => Self::new(vec![1.0 / n as f32; n as usize]),
+        }
+    }
+
+    /// Matrix for `channels` inputs folded with `strategy`
+    pub fn for_strategy(strategy: &DownmixStrategy, channels: u16) -> Self {
+        let only = |index: usize| {
+            let mut weights = vec![0.0; channels as usize];
+            weights[index.min(channels as usize - 1)] = 1.0;
+            Self::new(weights)
+        };
+
+        match (strategy, channels) {
+            (_, 0 | 1) => Self::new(vec![1.0]),
+            (DownmixStrategy::LeftOnly, _) => only(0),
+            (DownmixStrategy::RightOnly, _) => only(1),
+            (DownmixStrategy::Weighted(weights), n) if weights.len() == n as usize => {
+                Self::new(weights.clone())
+            }
+            (DownmixStrategy::Sum, 2) => Self::new(vec![1.0, 1.0]),
+            (_, n) => Self::for_channel_count(n),
+        }
+    }
+
+    /// Number of input channels
+    pub fn channels(&self) -> u16 {
+        self.weights.len() as u16
+    }
+
+    /// Weight of each input channel
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Fold interleaved samples to mono (rounded and clipped)
+    pub fn apply(&self, samples: &[i16]) -> Vec<i16> {
+        if self.weights.len() <= 1 {
+            return samples.to_vec();
+        }
+
+        samples
+            .chunks_exact(self.weights.len())
+            .map(|frame| {
+                let mixed: f32 = frame
+                    .iter()
+                    .zip(&self.weights)
+                    .map(|(&sample, &weight)| sample as f32 * weight)
+                    .sum();
+                mixed.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16
+            })
+            .collect()
+    }
+
+    /// Fold interleaved float samples to mono (unclipped)
+    pub fn apply_f32(&self, samples: &[f32]) -> Vec<f32> {
+        if self.weights.len() <= 1 {
+            return samples.to_vec();
+        }
+
+        samples
+            .chunks_exact(self.weights.len())
+            .map(|frame| frame.iter().zip(&self.weights).map(|(s, w)| s * w).sum())
+            .collect()
+    }
+}
+
+fn channel_weight(channel: Channels) -> f32 {
+    if channel == Channels::FRONT_CENTRE {
+        MINUS_3DB
+    } else if channel.intersects(Channels::LFE1 | Channels::LFE2) {
+        0.0
+    } else if channel.intersects(
+        Channels::FRONT_LEFT
+            | Channels::FRONT_RIGHT
+            | Channels::FRONT_LEFT_CENTRE
+            | Channels::FRONT_RIGHT_CENTRE
+            | Channels::FRONT_LEFT_WIDE
+            | Channels::FRONT_RIGHT_WIDE,
+    ) {
+        0.5
+    } else {
+        // Surround, rear and height channels
+        0.5 * MINUS_3DB
+    }
+}
@@ -1,25 +1,119 @@
+use super::downmix::Downmix;
 use anyhow::{Context, Result};
+use serde::Serialize;
 use std::fs::File;
 use std::path::Path;
-use symphonia::core::audio::{AudioBufferRef, Signal};
-use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::audio::{AudioBufferRef, Channels, Signal};
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
 use symphonia::core::errors::Error as SymphoniaError;
-use symphonia::core::formats::FormatOptions;
+use symphonia::core::formats::{FormatOptions, FormatReader};
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
+use tokio::sync::mpsc;
 use tracing::info;
 
+/// Blocks buffered between the decode thread and an [`AudioStream`] reader
+pub const DEFAULT_STREAM_CAPACITY: usize = 32;
+
 pub struct AudioFile {
     pub path: String,
     pub duration_seconds: f64,
     pub sample_rate: u32,
     pub channels: u16,
     pub samples: Vec<i16>,
+    /// How the file's channels fold down to mono
+    pub downmix: Downmix,
 }
 
-impl AudioFile {
+/// Format details of an audio file, read without decoding it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AudioInfo {
+    pub path: String,
+    /// Length in seconds, when the container declares it
+    pub duration_seconds: Option<f64>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Short codec name (e.g. "pcm_s16le", "opus")
+    pub codec: String,
+    /// Index of the described track among the file's audio tracks
+    pub track: usize,
+    /// Audio tracks in the file
+    pub tracks: usize,
+    /// Other tracks in the file (video, subtitles), which are never decoded
+    pub other_tracks: usize,
+}
+
+/// A run of decoded audio (one container packet)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioBlock {
+    /// Position of the first frame, in frames from the start of the file
+    pub offset_frames: u64,
+    /// Interleaved i16 samples
+    pub samples: Vec<i16>,
+}
+
+/// How far a decode has got
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodeProgress {
+    pub decoded_frames: u64,
+    /// Frames in the file, when the container declares it
+    pub total_frames: Option<u64>,
+}
+
+impl DecodeProgress {
+    /// Fraction decoded (0.0-1.0), when the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total_frames {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.decoded_frames as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+}
+
+/// Decodes an audio file one packet at a time
+///
+/// Only the current packet is held in memory, so hour-long recordings can be
+/// processed without loading them whole. Iterating yields [`AudioBlock`]s
+/// until the end of the file.
+pub struct AudioDecoder {
+    pub path: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Frames in the file, when the container declares it
+    pub total_frames: Option<u64>,
+    /// Short codec name (e.g. "pcm_s16le", "opus")
+    pub codec: String,
+    /// Index of the decoded track among the file's audio tracks
+    pub track: usize,
+    /// Audio tracks in the file
+    pub tracks: usize,
+    /// Other tracks in the file (video, subtitles)
+    pub other_tracks: usize,
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    /// Speaker positions, when the file declares them
+    layout: Option<Channels>,
+    decoded_frames: u64,
+    /// Block decoded while probing the channel count
+    pending: Option<Vec<i16>>,
+    finished: bool,
+}
+
+impl AudioDecoder {
+    /// Open the first audio track
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_track(path, 0)
+    }
+
+    /// Open one audio track of a multi-track file (e.g. a screen recording
+    /// with separate system audio and microphone tracks)
+    ///
+    /// `track` counts audio tracks only, from 0. Video containers (MP4, MOV,
+    /// MKV) work the same way: their video tracks are skipped.
+    pub fn open_track(path: impl AsRef<Path>, track: usize) -> Result<Self> {
         let path = path.as_ref();
         info!("Opening audio file: {}", path.display());
 
@@ -47,33 +141,115 @@ impl AudioFile {
             )
             .context("Failed to probe audio format")?;
 
-        let mut format = probed.format;
+        let format = probed.format;
 
-        // Find the first audio track
-        let track = format
+        // Find the requested audio track
+        let audio_tracks: Vec<_> = format
             .tracks()
             .iter()
-            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-            .context("No audio tracks found")?;
+            .filter(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .collect();
+        if audio_tracks.is_empty() {
+            anyhow::bail!("No audio tracks found");
+        }
+        let tracks = audio_tracks.len();
+        let other_tracks = format.tracks().len() - tracks;
+        let track_index = track;
+        let track = *audio_tracks.get(track_index).with_context(|| {
+            format!(
+                "No audio track {} (file has {} audio track{})",
+                track_index,
+                tracks,
+                if tracks == 1 { "" } else { "s" }
+            )
+        })?;
 
         let track_id = track.id;
         let sample_rate = track
             .codec_params
             .sample_rate
             .context("Sample rate not specified")?;
+        let total_frames = track.codec_params.n_frames;
+        let layout = track.codec_params.channels;
+        let channels = layout.map(|ch| ch.count() as u16);
+        let codec = symphonia::default::get_codecs()
+            .get_codec(track.codec_params.codec)
+            .map(|descriptor| descriptor.short_name.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
 
         // Create a decoder for the track
-        let mut decoder = symphonia::default::get_codecs()
+        let decoder = symphonia::default::get_codecs()
             .make(&track.codec_params, &DecoderOptions::default())
             .context("Failed to create decoder")?;
 
-        // Decode all packets and collect samples
-        let mut samples = Vec::new();
-        let mut channels: Option<u16> = track.codec_params.channels.map(|ch| ch.count() as u16);
+        let mut this = Self {
+            path: path.display().to_string(),
+            sample_rate,
+            channels: channels.unwrap_or(0),
+            total_frames,
+            codec,
+            track: track_index,
+            tracks,
+            other_tracks,
+            format,
+            decoder,
+            track_id,
+            layout,
+            decoded_frames: 0,
+            pending: None,
+            finished: false,
+        };
+
+        // Get channel count from first decoded buffer if not already known
+        if channels.is_none() {
+            this.pending = this.decode_next()?;
+            if this.channels == 0 {
+                anyhow::bail!("Could not determine channel count from audio");
+            }
+        }
 
-        loop {
+        Ok(this)
+    }
+
+    /// Length of the file in seconds, when the container declares it
+    pub fn duration_seconds(&self) -> Option<f64> {
+        self.total_frames
+            .map(|frames| frames as f64 / self.sample_rate as f64)
+    }
+
+    pub fn info(&self) -> AudioInfo {
+        AudioInfo {
+            path: self.path.clone(),
+            duration_seconds: self.duration_seconds(),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            codec: self.codec.clone(),
+            track: self.track,
+            tracks: self.tracks,
+            other_tracks: self.other_tracks,
+        }
+    }
+
+    /// How this track's channels fold down to mono
+    pub fn downmix(&self) -> Downmix {
+        match self.layout {
+            Some(layout) if layout.count() == self.channels as usize => Downmix::for_layout(layout),
+            _ => Downmix::for_channel_count(self.channels),
+        }
+    }
+
+    pub fn progress(&self) -> DecodeProgress {
+        DecodeProgress {
+            decoded_frames: self.decoded_frames,
+            total_frames: self.total_frames,
+        }
+    }
+
+    /// Decode the next packet of our track (None at end of file)
+    fn decode_next(&mut self) -> Result<Option<Vec<i16>>> {
+        while !self.finished {
             // Get the next packet from the format reader
-            let packet = match format.next_packet() {
+            let packet = match self.format.next_packet() {
                 Ok(packet) => packet,
                 Err(SymphoniaError::IoError(e))
                     if e.kind() == std::io::ErrorKind::UnexpectedEof =>
@@ -88,19 +264,22 @@ impl AudioFile {
             };
 
             // Skip packets that don't belong to our track
-            if packet.track_id() != track_id {
+            if packet.track_id() != self.track_id {
                 continue;
             }
 
             // Decode the packet
-            match decoder.decode(&packet) {
+            match self.decoder.decode(&packet) {
                 Ok(decoded) => {
-                    // Get channel count from first decoded buffer if not already known
-                    if channels.is_none() {
-                        channels = Some(decoded.spec().channels.count() as u16);
+                    if self.channels == 0 {
+                        let layout = decoded.spec().channels;
+                        self.channels = layout.count() as u16;
+                        self.layout = Some(layout);
                     }
                     // Convert decoded audio to i16 samples
+                    let mut samples = Vec::with_capacity(decoded.frames() * self.channels as usize);
                     convert_audio_buffer_to_i16(&decoded, &mut samples);
+                    return Ok(Some(samples));
                 }
                 Err(SymphoniaError::DecodeError(e)) => {
                     // Decode errors are not fatal
@@ -111,7 +290,175 @@ impl AudioFile {
             }
         }
 
-        let channels = channels.context("Could not determine channel count from audio")?;
+        self.finished = true;
+        Ok(None)
+    }
+
+    /// Decode the next block (None at end of file)
+    pub fn next_block(&mut self) -> Result<Option<AudioBlock>> {
+        let samples = match self.pending.take() {
+            Some(samples) => samples,
+            None => match self.decode_next()? {
+                Some(samples) => samples,
+                None => return Ok(None),
+            },
+        };
+
+        let offset_frames = self.decoded_frames;
+        self.decoded_frames += (samples.len() / self.channels.max(1) as usize) as u64;
+        Ok(Some(AudioBlock {
+            offset_frames,
+            samples,
+        }))
+    }
+}
+
+impl Iterator for AudioDecoder {
+    type Item = Result<AudioBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_block() {
+            Ok(block) => block.map(Ok),
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Decoded blocks delivered from a background decode thread
+///
+/// At most `capacity` blocks are buffered: decoding pauses until the reader
+/// catches up. Dropping the stream stops the decode.
+pub struct AudioStream {
+    pub path: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Frames in the file, when the container declares it
+    pub total_frames: Option<u64>,
+    /// How the file's channels fold down to mono
+    pub downmix: Downmix,
+    receiver: mpsc::Receiver<Result<AudioBlock>>,
+}
+
+impl AudioStream {
+    /// Next decoded block (None at end of file)
+    pub async fn next_block(&mut self) -> Result<Option<AudioBlock>> {
+        self.receiver.recv().await.transpose()
+    }
+}
+
+impl AudioFile {
+    /// Read a file's format without decoding its samples
+    ///
+    /// Fails if the container or codec is unsupported, so callers can reject
+    /// bad input before doing any real work. At most one packet is decoded,
+    /// and only for containers that don't declare a channel count.
+    pub fn probe(path: impl AsRef<Path>) -> Result<AudioInfo> {
+        Self::probe_track(path, 0)
+    }
+
+    /// Read the format of one audio track (see [`AudioDecoder::open_track`])
+    pub fn probe_track(path: impl AsRef<Path>, track: usize) -> Result<AudioInfo> {
+        Ok(AudioDecoder::open_track(path, track)?.info())
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_track(path, 0)
+    }
+
+    /// Decode one audio track of a multi-track file (see [`AudioDecoder::open_track`])
+    pub fn open_track(path: impl AsRef<Path>, track: usize) -> Result<Self> {
+        let mut decoder = AudioDecoder::open_track(path, track)?;
+
+        // Decode all packets and collect samples
+        let mut samples = Vec::new();
+        while let Some(block) = decoder.next_block()? {
+            samples.extend_from_slice(&block.samples);
+        }
+
+        let downmix = decoder.downmix();
+        Self::from_samples(
+            decoder.path,
+            decoder.sample_rate,
+            decoder.channels,
+            downmix,
+            samples,
+        )
+    }
+
+    /// Decode a whole file without blocking the runtime
+    ///
+    /// `progress` is called after every decoded block.
+    pub async fn open_async(
+        path: impl AsRef<Path>,
+        progress: impl FnMut(DecodeProgress) + Send + 'static,
+    ) -> Result<Self> {
+        let mut stream = Self::stream(path, DEFAULT_STREAM_CAPACITY, progress).await?;
+
+        let mut samples = Vec::new();
+        while let Some(block) = stream.next_block().await? {
+            samples.extend_from_slice(&block.samples);
+        }
+
+        Self::from_samples(
+            stream.path,
+            stream.sample_rate,
+            stream.channels,
+            stream.downmix,
+            samples,
+        )
+    }
+
+    /// Decode a file on a blocking thread, delivering blocks as they are ready
+    ///
+    /// Memory use is bounded by `capacity` blocks regardless of the file's
+    /// length. `progress` is called on the decode thread after every block.
+    pub async fn stream(
+        path: impl AsRef<Path>,
+        capacity: usize,
+        mut progress: impl FnMut(DecodeProgress) + Send + 'static,
+    ) -> Result<AudioStream> {
+        let path = path.as_ref().to_path_buf();
+        let decoder = tokio::task::spawn_blocking(move || AudioDecoder::open(path))
+            .await
+            .context("Audio decode task failed")??;
+
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let stream = AudioStream {
+            path: decoder.path.clone(),
+            sample_rate: decoder.sample_rate,
+            channels: decoder.channels,
+            total_frames: decoder.total_frames,
+            downmix: decoder.downmix(),
+            receiver,
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let mut decoder = decoder;
+            while let Some(block) = decoder.next() {
+                let failed = block.is_err();
+                if block.is_ok() {
+                    progress(decoder.progress());
+                }
+                // Stop when the reader has gone away
+                if sender.blocking_send(block).is_err() || failed {
+                    return;
+                }
+            }
+        });
+
+        Ok(stream)
+    }
+
+    fn from_samples(
+        path: String,
+        sample_rate: u32,
+        channels: u16,
+        downmix: Downmix,
+        samples: Vec<i16>,
+    ) -> Result<Self> {
         let duration_seconds = samples.len() as f64 / (sample_rate as f64 * channels as f64);
 
         info!(
@@ -123,14 +470,20 @@ impl AudioFile {
         );
 
         Ok(Self {
-            path: path.display().to_string(),
+            path,
             duration_seconds,
             sample_rate,
             channels,
             samples,
+            downmix,
         })
     }
 
+    /// All channels folded to mono with the file's downmix matrix
+    pub fn to_mono(&self) -> Vec<i16> {
+        self.downmix.apply(&self.samples)
+    }
+
     pub fn resample_to_mono_16khz(&self) -> Result<Vec<i16>> {
         // TODO: Implement resampling for Whisper (16kHz mono)
         // For Week 1, just return original samples if already 16kHz mono
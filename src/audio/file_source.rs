@@ -0,0 +1,226 @@
+// File audio backend replaying a decoded audio file as captured frames
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use super::backend::{AudioBackend, AudioFrame, AudioStreamSource};
+use super::file::{AudioFile, AudioStream, DEFAULT_STREAM_CAPACITY};
+
+/// Frames buffered for the session before replay waits
+const FRAME_CHANNEL_CAPACITY: usize = 64;
+
+/// How fast a file is replayed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilePacing {
+    /// Each frame is sent once its audio would have been captured live
+    #[default]
+    Realtime,
+    /// Frames are sent as fast as the session takes them
+    Fast,
+}
+
+/// An audio file replayed in place of live capture, for batch processing
+/// and exercising sessions in tests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSourceConfig {
+    /// File to decode (any format the decoder reads: WAV, M4A, MP3, FLAC, OGG)
+    pub path: PathBuf,
+    #[serde(default)]
+    pub pacing: FilePacing,
+}
+
+/// File audio backend
+///
+/// Decodes the file on a blocking thread and sends it as frames of the
+/// configured buffer duration. Frames are in the file's format; the session
+/// converts them. Once the whole file is sent, capture stays open but
+/// silent until stopped, so the session isn't restarted.
+pub struct FileBackend {
+    source: FileSourceConfig,
+    buffer_duration_ms: u64,
+    finished: Arc<AtomicBool>,
+    shutdown: CancellationToken,
+    task: Option<JoinHandle<()>>,
+}
+
+impl FileBackend {
+    pub fn new(source: FileSourceConfig, buffer_duration_ms: u64) -> Result<Self> {
+        if !source.path.is_file() {
+            bail!("Audio file not found: {}", source.path.display());
+        }
+
+        Ok(Self {
+            source,
+            buffer_duration_ms: buffer_duration_ms.max(1),
+            finished: Arc::new(AtomicBool::new(false)),
+            shutdown: CancellationToken::new(),
+            task: None,
+        })
+    }
+
+    /// Send the stream's audio as frames until the end of the file or `shutdown`
+    async fn replay(
+        mut stream: AudioStream,
+        pacing: FilePacing,
+        buffer_duration_ms: u64,
+        frame_tx: mpsc::Sender<AudioFrame>,
+        finished: Arc<AtomicBool>,
+        shutdown: CancellationToken,
+    ) {
+        let channels = stream.channels.max(1) as usize;
+        let frame_len =
+            (stream.sample_rate as u64 * buffer_duration_ms / 1000).max(1) as usize * channels;
+        let mut replay = Replay {
+            sample_rate: stream.sample_rate,
+            channels: stream.channels,
+            pacing,
+            started: Instant::now(),
+            sent_frames: 0,
+            frame_tx,
+            shutdown: shutdown.clone(),
+        };
+
+        let mut pending = Vec::with_capacity(frame_len);
+        loop {
+            let block = tokio::select! {
+                _ = shutdown.cancelled() => return,
+                block = stream.next_block() => block,
+            };
+            match block {
+                Ok(Some(block)) => pending.extend_from_slice(&block.samples),
+                Ok(None) => break,
+                Err(e) => {
+                    error!(
+                        "Failed to decode {}, replay ends here: {:#}",
+                        stream.path, e
+                    );
+                    break;
+                }
+            }
+
+            while pending.len() >= frame_len {
+                let samples = pending.drain(..frame_len).collect();
+                if !replay.send(samples).await {
+                    return;
+                }
+            }
+        }
+        if !pending.is_empty() && !replay.send(pending).await {
+            return;
+        }
+
+        finished.store(true, Ordering::Relaxed);
+        info!(
+            "Replayed {} ({:.1}s)",
+            stream.path,
+            replay.sent_frames as f64 / stream.sample_rate as f64
+        );
+        shutdown.cancelled().await;
+    }
+}
+
+/// Where a replay has got to
+struct Replay {
+    sample_rate: u32,
+    channels: u16,
+    pacing: FilePacing,
+    started: Instant,
+    /// Sample frames sent so far
+    sent_frames: u64,
+    frame_tx: mpsc::Sender<AudioFrame>,
+    shutdown: CancellationToken,
+}
+
+impl Replay {
+    /// Send the next frame, paced; false once the session or backend has
+    /// stopped
+    async fn send(&mut self, samples: Vec<i16>) -> bool {
+        let frame = AudioFrame {
+            timestamp_ms: self.sent_frames * 1000 / self.sample_rate as u64,
+            samples,
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            source: AudioStreamSource::System,
+        };
+        self.sent_frames += (frame.samples.len() / self.channels.max(1) as usize) as u64;
+
+        if self.pacing == FilePacing::Realtime {
+            // Live capture delivers a frame once its last sample is heard
+            let due = self.started
+                + Duration::from_micros(self.sent_frames * 1_000_000 / self.sample_rate as u64);
+            tokio::select! {
+                _ = self.shutdown.cancelled() => return false,
+                _ = tokio::time::sleep_until(due) => {}
+            }
+        }
+
+        tokio::select! {
+            _ = self.shutdown.cancelled() => false,
+            sent = self.frame_tx.send(frame) => sent.is_ok(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AudioBackend for FileBackend {
+    async fn start(&mut self) -> Result<mpsc::Receiver<AudioFrame>> {
+        if self.task.is_some() {
+            bail!("Already capturing");
+        }
+
+        let stream = AudioFile::stream(&self.source.path, DEFAULT_STREAM_CAPACITY, |_| {})
+            .await
+            .with_context(|| format!("Failed to open {}", self.source.path.display()))?;
+        info!(
+            "Replaying {} ({}Hz, {} channels, {:?} pacing)",
+            stream.path, stream.sample_rate, stream.channels, self.source.pacing
+        );
+
+        let (frame_tx, frame_rx) = mpsc::channel(FRAME_CHANNEL_CAPACITY);
+        self.shutdown = CancellationToken::new();
+        self.finished.store(false, Ordering::Relaxed);
+        self.task = Some(tokio::spawn(Self::replay(
+            stream,
+            self.source.pacing,
+            self.buffer_duration_ms,
+            frame_tx,
+            Arc::clone(&self.finished),
+            self.shutdown.clone(),
+        )));
+        Ok(frame_rx)
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        let Some(task) = self.task.take() else {
+            return Ok(());
+        };
+
+        self.shutdown.cancel();
+        task.await.context("File replay task failed")?;
+        info!("File replay stopped");
+        Ok(())
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.task.is_some()
+    }
+
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    /// Whether the whole file has been sent
+    fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+}
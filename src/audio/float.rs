@@ -0,0 +1,133 @@
+use super::backend::{AudioFrame, AudioStreamSource};
+use super::downmix::{Downmix, DownmixStrategy};
+
+/// Scale between 16-bit PCM and normalized float samples
+const PCM16_SCALE: f32 = 32768.0;
+
+/// Audio frame with 32-bit float samples, normalized to -1.0..1.0
+///
+/// The float pipeline carries these between processing stages (gain,
+/// downmixing, resampling, mixing) so intermediate results keep their
+/// precision and headroom. Samples may exceed ±1.0 until they are
+/// converted back with [`FloatFrame::to_pcm16`] at the WAV/NATS boundary.
+#[derive(Debug, Clone)]
+pub struct FloatFrame {
+    /// Interleaved samples
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Milliseconds since capture started
+    pub timestamp_ms: u64,
+    pub source: AudioStreamSource,
+}
+
+impl FloatFrame {
+    /// Convert to 16-bit PCM, rounding and clipping out-of-range samples
+    pub fn to_pcm16(&self) -> AudioFrame {
+        AudioFrame {
+            samples: self.samples.iter().map(|&s| sample_to_i16(s)).collect(),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            timestamp_ms: self.timestamp_ms,
+            source: self.source,
+        }
+    }
+
+    /// Round samples to the values 16-bit PCM can hold, in place
+    ///
+    /// Reproduces the precision of the i16 pipeline at a stage boundary.
+    pub fn quantize(&mut self) {
+        for sample in &mut self.samples {
+            *sample = sample_to_f32(sample_to_i16(*sample));
+        }
+    }
+
+    /// Multiply every sample by a linear gain
+    pub fn apply_gain(&mut self, gain: f32) {
+        if gain != 1.0 {
+            for sample in &mut self.samples {
+                *sample *= gain;
+            }
+        }
+    }
+
+    /// Sum stereo channels to mono (other channel counts use the standard
+    /// fold-down)
+    pub fn to_mono(self) -> FloatFrame {
+        self.downmix(&DownmixStrategy::Sum)
+    }
+
+    /// Convert to `channels` channels: averaged to mono, mono copied to
+    /// every channel, and otherwise channels kept in order with missing
+    /// ones silent
+    pub fn with_channels(self, channels: u16) -> FloatFrame {
+        if self.channels == channels || channels == 0 || self.channels == 0 {
+            return self;
+        }
+        if channels == 1 {
+            return self.downmix(&DownmixStrategy::Average);
+        }
+
+        let from = self.channels as usize;
+        let to = channels as usize;
+        let mut samples = Vec::with_capacity(self.samples.len() / from * to);
+        for frame in self.samples.chunks_exact(from) {
+            for channel in 0..to {
+                samples.push(match (from, frame.get(channel)) {
+                    (1, _) => frame[0],
+                    (_, Some(&sample)) => sample,
+                    (_, None) => 0.0,
+                });
+            }
+        }
+
+        FloatFrame {
+            samples,
+            channels,
+            ..self
+        }
+    }
+
+    /// Fold all channels to mono with `strategy`
+    pub fn downmix(self, strategy: &DownmixStrategy) -> FloatFrame {
+        if self.channels <= 1 {
+            return self;
+        }
+
+        FloatFrame {
+            samples: Downmix::for_strategy(strategy, self.channels).apply_f32(&self.samples),
+            channels: 1,
+            ..self
+        }
+    }
+}
+
+impl From<&AudioFrame> for FloatFrame {
+    fn from(frame: &AudioFrame) -> Self {
+        Self {
+            samples: frame.samples.iter().map(|&s| sample_to_f32(s)).collect(),
+            sample_rate: frame.sample_rate,
+            channels: frame.channels,
+            timestamp_ms: frame.timestamp_ms,
+            source: frame.source,
+        }
+    }
+}
+
+impl From<AudioFrame> for FloatFrame {
+    fn from(frame: AudioFrame) -> Self {
+        Self::from(&frame)
+    }
+}
+
+/// 16-bit PCM sample as a normalized float (exact)
+pub fn sample_to_f32(sample: i16) -> f32 {
+    sample as f32 / PCM16_SCALE
+}
+
+/// Normalized float sample as 16-bit PCM, rounded and clipped
+pub fn sample_to_i16(sample: f32) -> i16 {
+    (sample * PCM16_SCALE)
+        .round()
+        .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
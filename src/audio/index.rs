@@ -0,0 +1,140 @@
+use super::chunk::ChunkMetadata;
+use super::workdir::{LOCK_FILE, MANIFEST_FILE};
+use crate::storage::write_atomic;
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Human-readable summary of a meeting directory, for people browsing the
+/// recordings folder
+pub const INDEX_FILE: &str = "index.md";
+
+/// What a meeting directory holds, written to its `index.md`
+///
+/// Rewritten as each chunk finishes, when the meeting is saved, and when
+/// its note is exported, so it always describes the folder's current
+/// contents.
+#[derive(Debug, Clone)]
+pub struct MeetingIndex {
+    pub meeting_id: String,
+    pub title: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// How long the meeting recorded; None while it is still in progress
+    pub duration: Option<Duration>,
+    /// Chunks in the directory, in order
+    pub chunks: Vec<ChunkMetadata>,
+    /// Where the transcript is stored, once the meeting is saved
+    pub transcript: Option<String>,
+    /// The transcript and audio are encrypted to a client key
+    pub encrypted: bool,
+    /// Note last exported to the vault
+    pub note_path: Option<PathBuf>,
+}
+
+impl MeetingIndex {
+    /// Index of a meeting in progress with nothing recorded yet
+    pub fn new(meeting_id: impl Into<String>, started_at: DateTime<Utc>) -> Self {
+        Self {
+            meeting_id: meeting_id.into(),
+            title: None,
+            started_at,
+            duration: None,
+            chunks: Vec::new(),
+            transcript: None,
+            encrypted: false,
+            note_path: None,
+        }
+    }
+
+    /// The index as Markdown
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "# {}\n\n",
+            self.title.as_deref().unwrap_or(&self.meeting_id)
+        );
+        out.push_str(&format!(
+            "Audio and metadata for meeting `{}`, recorded by Loqa Meetings.\n\n",
+            self.meeting_id
+        ));
+
+        out.push_str("| | |\n|---|---|\n");
+        out.push_str(&format!(
+            "| Started | {} |\n",
+            self.started_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+        ));
+        match self.duration {
+            Some(duration) => {
+                out.push_str("| Status | Complete |\n");
+                out.push_str(&format!(
+                    "| Duration | {} |\n",
+                    clock(duration.as_millis() as u64)
+                ));
+            }
+            None => out.push_str("| Status | In progress |\n"),
+        }
+        out.push_str(&format!(
+            "| Transcript | {} |\n",
+            match &self.transcript {
+                Some(location) => location.clone(),
+                None => "Saved when the meeting stops".to_string(),
+            }
+        ));
+        out.push_str(&format!(
+            "| Note | {} |\n",
+            match &self.note_path {
+                Some(path) => format!("Exported to `{}`", path.display()),
+                None => "Not exported".to_string(),
+            }
+        ));
+        if self.encrypted {
+            out.push_str("| Encrypted | Audio and transcript (client key) |\n");
+        }
+
+        out.push_str("\n## Chunks\n\n");
+        if self.chunks.is_empty() {
+            out.push_str("No audio chunks yet.\n");
+        } else {
+            out.push_str("| # | File | Start | End | Length |\n|---|---|---|---|---|\n");
+            for chunk in &self.chunks {
+                let file = chunk
+                    .file_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    chunk.chunk_index,
+                    file,
+                    clock(chunk.start_ms),
+                    clock(chunk.end_ms),
+                    clock(chunk.end_ms.saturating_sub(chunk.start_ms))
+                ));
+            }
+        }
+
+        out.push_str(&format!(
+            "\n`{}` lists the chunks for the server, and `{}` is present while \
+             the meeting is being written. This file is regenerated as the \
+             meeting changes; edits to it are lost.\n",
+            MANIFEST_FILE, LOCK_FILE
+        ));
+        out
+    }
+
+    /// Write the index into `dir`, replacing the previous one
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        write_atomic(&dir.join(INDEX_FILE), self.render())
+    }
+}
+
+/// Meeting offset as MM:SS, or H:MM:SS from the first hour on
+fn clock(offset_ms: u64) -> String {
+    let secs = offset_ms / 1000;
+    match secs / 3600 {
+        0 => format!("{:02}:{:02}", secs / 60, secs % 60),
+        hours => format!("{}:{:02}:{:02}", hours, (secs % 3600) / 60, secs % 60),
+    }
+}
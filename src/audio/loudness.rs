@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Gating block length (ITU-R BS.1770-4)
+const BLOCK_MS: u64 = 400;
+
+/// Blocks overlap by 75%
+const BLOCK_STEP_MS: u64 = 100;
+
+/// Blocks quieter than this never count towards integrated loudness
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+
+/// Blocks this far below the absolute-gated loudness are dropped
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Loudness normalization of exported meeting audio (EBU R128 measurement)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoudnessConfig {
+    /// Normalize exports unless the request says otherwise
+    pub enabled: bool,
+    /// Integrated loudness exports are brought to, in LUFS
+    pub target_lufs: f64,
+    /// Highest sample peak after normalization, in dBFS; the gain is
+    /// lowered rather than clipping
+    pub max_peak_dbfs: f64,
+}
+
+impl Default for LoudnessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            target_lufs: -16.0,
+            max_peak_dbfs: -1.0,
+        }
+    }
+}
+
+/// What normalization measured and did
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LoudnessReport {
+    /// Integrated loudness before normalization (None = silence, or too
+    /// short to measure)
+    pub measured_lufs: Option<f64>,
+    /// Sample peak before normalization, in dBFS
+    pub peak_dbfs: f64,
+    /// Gain applied, in dB
+    pub gain_db: f64,
+}
+
+/// Biquad filter, direct form I
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [input, self.x[0]];
+        self.y = [output, self.y[0]];
+        output
+    }
+}
+
+/// The BS.1770 K-weighting filter for `sample_rate`: a high shelf for the
+/// head's acoustic effect, then a high-pass
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let rate = sample_rate as f64;
+
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        x: [0.0; 2],
+        y: [0.0; 2],
+    };
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        x: [0.0; 2],
+        y: [0.0; 2],
+    };
+
+    [shelf, high_pass]
+}
+
+fn block_loudness(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+/// Integrated loudness of interleaved audio, in LUFS (ITU-R BS.1770-4,
+/// as used by EBU R128)
+///
+/// Every channel is weighted equally. Returns None when no 400 ms block
+/// is above the absolute gate, e.g. for silence or audio under 400 ms.
+pub fn integrated_loudness(samples: &[i16], sample_rate: u32, channels: u16) -> Option<f64> {
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    let step = (sample_rate as u64 * BLOCK_STEP_MS / 1000) as usize;
+    let steps_per_block = (BLOCK_MS / BLOCK_STEP_MS) as usize;
+    if step == 0 || frames < step * steps_per_block {
+        return None;
+    }
+
+    // Sum of squared K-weighted samples per 100 ms step, over all channels
+    let mut filters = vec![k_weighting(sample_rate); channels];
+    let mut step_energy = vec![0.0; frames / step];
+    for (frame, samples) in samples
+        .chunks_exact(channels)
+        .take(step_energy.len() * step)
+        .enumerate()
+    {
+        let energy = &mut step_energy[frame / step];
+        for (sample, filter) in samples.iter().zip(filters.iter_mut()) {
+            let weighted = filter
+                .iter_mut()
+                .fold(*sample as f64 / 32768.0, |x, stage| stage.process(x));
+            *energy += weighted * weighted;
+        }
+    }
+
+    let block_frames = (step * steps_per_block) as f64;
+    let powers: Vec<f64> = step_energy
+        .windows(steps_per_block)
+        .map(|window| window.iter().sum::<f64>() / block_frames)
+        .filter(|&power| power > 0.0 && block_loudness(power) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if powers.is_empty() {
+        return None;
+    }
+
+    let mean = |powers: &mut dyn Iterator<Item = f64>| {
+        let (sum, count) = powers.fold((0.0, 0usize), |(sum, count), p| (sum + p, count + 1));
+        (count > 0).then(|| sum / count as f64)
+    };
+    let relative_gate = block_loudness(mean(&mut powers.iter().copied())?) + RELATIVE_GATE_LU;
+    let gated = mean(
+        &mut powers
+            .iter()
+            .copied()
+            .filter(|&power| block_loudness(power) > relative_gate),
+    )?;
+    Some(block_loudness(gated))
+}
+
+/// Highest absolute sample, in dBFS (negative infinity for silence)
+pub fn sample_peak_dbfs(samples: &[i16]) -> f64 {
+    let peak = samples
+        .iter()
+        .map(|&sample| (sample as i32).unsigned_abs())
+        .max()
+        .unwrap_or(0);
+    20.0 * (peak as f64 / 32768.0).log10()
+}
+
+/// Bring interleaved audio to the configured integrated loudness
+///
+/// The gain is capped so the sample peak stays at or below
+/// `max_peak_dbfs`; audio that can't be measured is left as it is.
+pub fn normalize_loudness(
+    samples: &mut [i16],
+    sample_rate: u32,
+    channels: u16,
+    config: &LoudnessConfig,
+) -> LoudnessReport {
+    let measured_lufs = integrated_loudness(samples, sample_rate, channels);
+    let peak_dbfs = sample_peak_dbfs(samples);
+    let gain_db = match measured_lufs {
+        Some(measured) => (config.target_lufs - measured).min(config.max_peak_dbfs - peak_dbfs),
+        None => 0.0,
+    };
+
+    if gain_db != 0.0 {
+        let gain = 10f64.powf(gain_db / 20.0);
+        for sample in samples.iter_mut() {
+            *sample = (*sample as f64 * gain)
+                .round()
+                .clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+        }
+    }
+
+    LoudnessReport {
+        measured_lufs,
+        peak_dbfs,
+        gain_db,
+    }
+}
@@ -4,7 +4,7 @@ use anyhow::{bail, Result};
 use tokio::sync::mpsc;
 use tracing::info;
 
-use super::backend::{AudioBackend, AudioBackendConfig, AudioFrame};
+use super::backend::{AudioBackend, AudioBackendConfig, AudioFrame, DeviceEvent};
 use crate::screencapture;
 
 /// macOS audio backend
@@ -13,6 +13,7 @@ use crate::screencapture;
 pub struct MacOSBackend {
     config: AudioBackendConfig,
     session: Option<screencapture::ScreenCaptureSession>,
+    device_events: Option<mpsc::UnboundedReceiver<DeviceEvent>>,
     capturing: bool,
 }
 
@@ -34,6 +35,7 @@ impl MacOSBackend {
         Ok(Self {
             config,
             session: None,
+            device_events: None,
             capturing: false,
         })
     }
@@ -52,10 +54,13 @@ impl AudioBackend for MacOSBackend {
         let mut session = screencapture::ScreenCaptureSession::new(
             self.config.target_sample_rate,
             self.config.target_channels,
-        );
+        )
+        .with_excluded_apps(self.config.excluded_apps.clone())
+        .with_microphone_device(self.config.microphone_device.clone());
 
         // Start capture
         let rx = session.start()?;
+        self.device_events = session.take_device_events();
 
         self.session = Some(session);
         self.capturing = true;
@@ -90,4 +95,8 @@ impl AudioBackend for MacOSBackend {
     fn name(&self) -> &str {
         "macOS ScreenCaptureKit"
     }
+
+    fn take_device_events(&mut self) -> Option<mpsc::UnboundedReceiver<DeviceEvent>> {
+        self.device_events.take()
+    }
 }
@@ -0,0 +1,395 @@
+// Microphone audio backend using cpal (CoreAudio, ALSA, WASAPI)
+
+use anyhow::{bail, Result};
+use tokio::sync::mpsc;
+use tracing::info;
+
+use super::backend::{AudioBackend, AudioBackendConfig, AudioFrame, AudioStreamSource};
+use super::resample::Resampler;
+
+/// Frames buffered for the session before captured audio is dropped
+#[cfg(feature = "microphone")]
+const FRAME_CHANNEL_CAPACITY: usize = 64;
+
+/// Turns audio in a device's native format into frames in the backend's
+/// target format
+///
+/// Samples arrive in whatever blocks the device delivers; they are
+/// collected into frames of the configured buffer duration, mapped to the
+/// target channel count (averaged down to mono, mono copied to each
+/// channel), and resampled to the target rate.
+pub struct CaptureConverter {
+    native_channels: u16,
+    target_channels: u16,
+    target_sample_rate: u32,
+    /// None when the device already runs at the target rate
+    resampler: Option<Resampler>,
+    /// Native samples per frame (interleaved)
+    frame_len: usize,
+    /// Native samples not yet in a frame
+    pending: Vec<i16>,
+    /// Target-rate sample frames sent so far, for timestamps
+    sent_frames: u64,
+}
+
+impl CaptureConverter {
+    pub fn new(native_sample_rate: u32, native_channels: u16, config: &AudioBackendConfig) -> Self {
+        let resampler = (native_sample_rate != config.target_sample_rate).then(|| {
+            Resampler::with_quality(
+                native_sample_rate,
+                config.target_sample_rate,
+                config.target_channels,
+                config.resampler_quality,
+            )
+        });
+        let frame_len = (native_sample_rate as u64 * config.buffer_duration_ms / 1000).max(1)
+            as usize
+            * native_channels as usize;
+
+        Self {
+            native_channels,
+            target_channels: config.target_channels,
+            target_sample_rate: config.target_sample_rate,
+            resampler,
+            frame_len,
+            pending: Vec::with_capacity(frame_len),
+            sent_frames: 0,
+        }
+    }
+
+    /// Frames completed by `samples` (interleaved, in the native layout)
+    pub fn push(&mut self, samples: &[i16]) -> Vec<AudioFrame> {
+        self.pending.extend_from_slice(samples);
+
+        let mut frames = Vec::new();
+        while self.pending.len() >= self.frame_len {
+            let native: Vec<i16> = self.pending.drain(..self.frame_len).collect();
+            let mapped = self.map_channels(&native);
+            let samples = match &mut self.resampler {
+                Some(resampler) => resampler.process(&mapped),
+                None => mapped,
+            };
+
+            let frame = AudioFrame {
+                timestamp_ms: self.sent_frames * 1000 / self.target_sample_rate as u64,
+                samples,
+                sample_rate: self.target_sample_rate,
+                channels: self.target_channels,
+                source: AudioStreamSource::Microphone,
+            };
+            self.sent_frames += (frame.samples.len() / self.target_channels as usize) as u64;
+            frames.push(frame);
+        }
+        frames
+    }
+
+    /// Interleaved native samples in the target channel layout
+    fn map_channels(&self, samples: &[i16]) -> Vec<i16> {
+        let native = self.native_channels as usize;
+        let target = self.target_channels as usize;
+        if native == target {
+            return samples.to_vec();
+        }
+
+        let mut mapped = Vec::with_capacity(samples.len() / native * target);
+        for frame in samples.chunks_exact(native) {
+            if target == 1 {
+                let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+                mapped.push((sum / native as i32) as i16);
+            } else {
+                // Device channels in order; mono (or missing channels) from the first
+                mapped.extend((0..target).map(|c| frame.get(c).copied().unwrap_or(frame[0])));
+            }
+        }
+        mapped
+    }
+}
+
+/// Microphone audio backend
+///
+/// Captures the pinned input device (matched by name) or the system default
+/// input with cpal. Capture needs the `microphone` feature; without it,
+/// `new` fails.
+pub struct MicrophoneBackend {
+    config: AudioBackendConfig,
+    #[cfg(feature = "microphone")]
+    capture: Option<capture::CaptureThread>,
+}
+
+impl MicrophoneBackend {
+    /// Whether this build can capture from microphones
+    pub fn is_available() -> bool {
+        cfg!(feature = "microphone")
+    }
+
+    pub fn new(config: AudioBackendConfig) -> Result<Self> {
+        if !Self::is_available() {
+            bail!("Microphone capture requires building with the `microphone` feature");
+        }
+        if config.target_sample_rate == 0 || config.target_channels == 0 {
+            bail!(
+                "Unsupported microphone target format: {}Hz, {} channels",
+                config.target_sample_rate,
+                config.target_channels
+            );
+        }
+
+        info!(
+            "Microphone backend initialized ({}Hz, {} channels, device: {})",
+            config.target_sample_rate,
+            config.target_channels,
+            config
+                .microphone_device
+                .as_deref()
+                .unwrap_or("system default")
+        );
+
+        Ok(Self {
+            config,
+            #[cfg(feature = "microphone")]
+            capture: None,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AudioBackend for MicrophoneBackend {
+    async fn start(&mut self) -> Result<mpsc::Receiver<AudioFrame>> {
+        #[cfg(feature = "microphone")]
+        {
+            if self.capture.is_some() {
+                bail!("Already capturing");
+            }
+
+            let (frame_tx, frame_rx) = mpsc::channel(FRAME_CHANNEL_CAPACITY);
+            let config = self.config.clone();
+            let capture = tokio::task::spawn_blocking(move || {
+                capture::CaptureThread::start(config, frame_tx)
+            })
+            .await??;
+            self.capture = Some(capture);
+            Ok(frame_rx)
+        }
+
+        #[cfg(not(feature = "microphone"))]
+        {
+            let _ = &self.config;
+            bail!("Microphone capture requires building with the `microphone` feature")
+        }
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        #[cfg(feature = "microphone")]
+        if let Some(capture) = self.capture.take() {
+            tokio::task::spawn_blocking(move || capture.stop()).await?;
+            info!("Microphone capture stopped");
+        }
+        Ok(())
+    }
+
+    fn is_capturing(&self) -> bool {
+        #[cfg(feature = "microphone")]
+        {
+            self.capture.is_some()
+        }
+
+        #[cfg(not(feature = "microphone"))]
+        {
+            false
+        }
+    }
+
+    fn name(&self) -> &str {
+        "cpal microphone"
+    }
+}
+
+#[cfg(feature = "microphone")]
+mod capture {
+    use anyhow::{anyhow, bail, Context, Result};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use cpal::{FromSample, SampleFormat, SizedSample};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{mpsc as std_mpsc, Arc};
+    use std::thread::JoinHandle;
+    use tokio::sync::mpsc;
+    use tracing::{error, info, warn};
+
+    use super::CaptureConverter;
+    use crate::audio::backend::{AudioBackendConfig, AudioFrame};
+
+    /// Thread owning the cpal stream, which can't move between threads on
+    /// every platform
+    pub(super) struct CaptureThread {
+        stop: std_mpsc::Sender<()>,
+        handle: JoinHandle<()>,
+    }
+
+    impl CaptureThread {
+        /// Open the device and start streaming, returning once audio flows
+        /// or opening fails
+        pub(super) fn start(
+            config: AudioBackendConfig,
+            frame_tx: mpsc::Sender<AudioFrame>,
+        ) -> Result<Self> {
+            let (ready_tx, ready_rx) = std_mpsc::channel();
+            let (stop, stop_rx) = std_mpsc::channel::<()>();
+
+            let handle = std::thread::Builder::new()
+                .name("microphone-capture".to_string())
+                .spawn(move || {
+                    let dropped = Arc::new(AtomicU64::new(0));
+                    let stream = match open_stream(&config, frame_tx, Arc::clone(&dropped)) {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e));
+                            return;
+                        }
+                    };
+                    let _ = ready_tx.send(Ok(()));
+
+                    // Capture until stopped (or the backend is dropped)
+                    let _ = stop_rx.recv();
+                    drop(stream);
+
+                    let dropped = dropped.load(Ordering::Relaxed);
+                    if dropped > 0 {
+                        warn!(
+                            "Dropped {} microphone frames the session didn't take",
+                            dropped
+                        );
+                    }
+                })
+                .context("Failed to spawn microphone capture thread")?;
+
+            match ready_rx.recv() {
+                Ok(Ok(())) => Ok(Self { stop, handle }),
+                Ok(Err(e)) => {
+                    let _ = handle.join();
+                    Err(e)
+                }
+                Err(_) => {
+                    let _ = handle.join();
+                    bail!("Microphone capture thread exited while starting")
+                }
+            }
+        }
+
+        pub(super) fn stop(self) {
+            let _ = self.stop.send(());
+            if self.handle.join().is_err() {
+                error!("Microphone capture thread panicked");
+            }
+        }
+    }
+
+    /// The pinned device by name, or the default input
+    fn input_device(config: &AudioBackendConfig) -> Result<cpal::Device> {
+        let host = cpal::default_host();
+        let Some(name) = &config.microphone_device else {
+            return host
+                .default_input_device()
+                .ok_or_else(|| anyhow!("No default microphone"));
+        };
+
+        let mut available = Vec::new();
+        for device in host.input_devices().context("Failed to list microphones")? {
+            let Ok(device_name) = device.name() else {
+                continue;
+            };
+            if &device_name == name {
+                return Ok(device);
+            }
+            available.push(device_name);
+        }
+        bail!(
+            "Unknown microphone \"{}\" (available: {})",
+            name,
+            available.join(", ")
+        )
+    }
+
+    fn open_stream(
+        config: &AudioBackendConfig,
+        frame_tx: mpsc::Sender<AudioFrame>,
+        dropped: Arc<AtomicU64>,
+    ) -> Result<cpal::Stream> {
+        let device = input_device(config)?;
+        let device_name = device.name().unwrap_or_else(|_| "unknown".to_string());
+        let supported = device
+            .default_input_config()
+            .with_context(|| format!("Failed to read the format of microphone {}", device_name))?;
+        let stream_config = supported.config();
+        info!(
+            "Capturing microphone {} ({}Hz, {} channels, {:?})",
+            device_name,
+            stream_config.sample_rate.0,
+            stream_config.channels,
+            supported.sample_format()
+        );
+
+        let converter =
+            CaptureConverter::new(stream_config.sample_rate.0, stream_config.channels, config);
+        let stream = match supported.sample_format() {
+            SampleFormat::I8 => build::<i8>(&device, &stream_config, converter, frame_tx, dropped),
+            SampleFormat::I16 => {
+                build::<i16>(&device, &stream_config, converter, frame_tx, dropped)
+            }
+            SampleFormat::I32 => {
+                build::<i32>(&device, &stream_config, converter, frame_tx, dropped)
+            }
+            SampleFormat::U8 => build::<u8>(&device, &stream_config, converter, frame_tx, dropped),
+            SampleFormat::U16 => {
+                build::<u16>(&device, &stream_config, converter, frame_tx, dropped)
+            }
+            SampleFormat::U32 => {
+                build::<u32>(&device, &stream_config, converter, frame_tx, dropped)
+            }
+            SampleFormat::F32 => {
+                build::<f32>(&device, &stream_config, converter, frame_tx, dropped)
+            }
+            SampleFormat::F64 => {
+                build::<f64>(&device, &stream_config, converter, frame_tx, dropped)
+            }
+            format => bail!("Unsupported microphone sample format {:?}", format),
+        }
+        .with_context(|| format!("Failed to open microphone {}", device_name))?;
+
+        stream
+            .play()
+            .with_context(|| format!("Failed to start microphone {}", device_name))?;
+        Ok(stream)
+    }
+
+    /// Input stream converting samples of type `T` to frames
+    fn build<T>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        mut converter: CaptureConverter,
+        frame_tx: mpsc::Sender<AudioFrame>,
+        dropped: Arc<AtomicU64>,
+    ) -> Result<cpal::Stream>
+    where
+        T: SizedSample,
+        i16: FromSample<T>,
+    {
+        let mut samples = Vec::new();
+        let stream = device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                samples.clear();
+                samples.extend(data.iter().map(|sample| sample.to_sample::<i16>()));
+                // The audio callback must not block: frames the session
+                // hasn't taken are dropped and counted
+                for frame in converter.push(&samples) {
+                    if frame_tx.try_send(frame).is_err() {
+                        dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            },
+            |e| error!("Microphone capture error: {}", e),
+            None,
+        )?;
+        Ok(stream)
+    }
+}
@@ -0,0 +1,407 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use tracing::{error, info, warn};
+
+use super::backend::{AudioFrame, AudioStreamSource};
+use super::float::FloatFrame;
+use super::resample::Resampler;
+
+/// A mixer input and its gain
+#[derive(Debug, Clone)]
+pub struct MixerInput {
+    /// Source whose frames feed this input
+    pub source: AudioStreamSource,
+    /// Linear gain applied before summing (1.0 = unchanged)
+    pub gain: f32,
+}
+
+/// What the mixer does with a frame whose sample rate or channel count
+/// differs from the mix
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatMismatch {
+    /// Resample and remix the frame to the mix format
+    #[default]
+    Coerce,
+    /// Drop the frame with a warning
+    Drop,
+    /// Reject the frame with an error, failing whatever is feeding the mixer
+    Fail,
+}
+
+/// Configuration for mixing N sources into one stream
+#[derive(Debug, Clone)]
+pub struct MixerConfig {
+    /// Sample rate of the mix
+    pub sample_rate: u32,
+    /// Channel count of the mix
+    pub channels: u16,
+    /// What happens to input frames in another format
+    pub format_mismatch: FormatMismatch,
+    /// Duration of each mixed output frame
+    pub frame_duration_ms: u64,
+    /// How far a source may fall behind before it is zero-filled
+    pub max_latency_ms: u64,
+    /// Sources to mix
+    pub inputs: Vec<MixerInput>,
+}
+
+impl MixerConfig {
+    pub fn new(sample_rate: u32, channels: u16, inputs: Vec<MixerInput>) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            format_mismatch: FormatMismatch::default(),
+            frame_duration_ms: 100, // Matches backend buffer size
+            max_latency_ms: 500,
+            inputs,
+        }
+    }
+
+    fn samples_per_ms(&self) -> f64 {
+        self.sample_rate as f64 * self.channels as f64 / 1000.0
+    }
+}
+
+/// Frames received by the mixer and how many were dropped, by reason
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrameDropStats {
+    /// Frames pushed into the mixer
+    pub frames_received: u64,
+    /// Dropped (or rejected) because sample rate or channel count did not match
+    pub mismatched_format: u64,
+    /// Converted to the mix's sample rate and channel count (not dropped)
+    #[serde(default)]
+    pub coerced_format: u64,
+    /// Dropped because the source is not a configured input
+    pub unknown_source: u64,
+    /// Dropped because the frame ended before the current mix position
+    pub late: u64,
+}
+
+impl FrameDropStats {
+    /// Total dropped frames across all reasons
+    pub fn dropped(&self) -> u64 {
+        self.mismatched_format + self.unknown_source + self.late
+    }
+
+    /// Fraction of received frames that were dropped (0.0 when nothing received)
+    pub fn drop_rate(&self) -> f64 {
+        if self.frames_received == 0 {
+            return 0.0;
+        }
+        self.dropped() as f64 / self.frames_received as f64
+    }
+}
+
+/// Pending samples for one mixer input
+struct InputBuffer {
+    gain: f32,
+    /// Normalized float samples
+    samples: VecDeque<f32>,
+}
+
+/// Mixes frames from several sources into a single stream
+///
+/// Sources are aligned by frame timestamp. A source that stops delivering
+/// frames (e.g. a silent ScreenCaptureKit stream) is zero-filled once the
+/// others are more than `max_latency_ms` ahead, so it cannot stall the mix.
+///
+/// Mixing happens in 32-bit float. The `*_float` methods keep the result in
+/// float; the others take and return 16-bit PCM frames.
+pub struct Mixer {
+    config: MixerConfig,
+    inputs: HashMap<AudioStreamSource, InputBuffer>,
+    /// Timestamp of the next output frame
+    position_ms: u64,
+    /// Received/dropped frame counters
+    drops: FrameDropStats,
+    /// Converts each coerced source to the mix's sample rate
+    resamplers: HashMap<AudioStreamSource, Resampler>,
+}
+
+impl Mixer {
+    pub fn new(config: MixerConfig) -> Self {
+        let inputs = config
+            .inputs
+            .iter()
+            .map(|input| {
+                (
+                    input.source,
+                    InputBuffer {
+                        gain: input.gain,
+                        samples: VecDeque::new(),
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            config,
+            inputs,
+            position_ms: 0,
+            drops: FrameDropStats::default(),
+            resamplers: HashMap::new(),
+        }
+    }
+
+    /// Mix output from `position_ms` on, for a mixer created partway
+    /// through a recording
+    pub fn starting_at(mut self, position_ms: u64) -> Self {
+        self.position_ms = position_ms;
+        self
+    }
+
+    /// Whether `source` is mixed
+    pub fn has_input(&self, source: AudioStreamSource) -> bool {
+        self.inputs.contains_key(&source)
+    }
+
+    /// Start mixing a source that joined partway through, from the current
+    /// position (no-op if it is already mixed)
+    pub fn add_input(&mut self, input: MixerInput) {
+        self.inputs.entry(input.source).or_insert(InputBuffer {
+            gain: input.gain,
+            samples: VecDeque::new(),
+        });
+        if !self.config.inputs.iter().any(|i| i.source == input.source) {
+            self.config.inputs.push(input);
+        }
+    }
+
+    /// Stop mixing a source that has gone away, so the mix no longer waits
+    /// for it (audio it still has buffered, under a frame, is dropped)
+    pub fn remove_input(&mut self, source: AudioStreamSource) {
+        self.config.inputs.retain(|input| input.source != source);
+        self.resamplers.remove(&source);
+        self.inputs.remove(&source);
+    }
+
+    /// Add a frame from one of the configured sources
+    pub fn push(&mut self, frame: AudioFrame) {
+        self.push_float(FloatFrame::from(frame));
+    }
+
+    /// Add a float frame from one of the configured sources
+    ///
+    /// A frame rejected by [`FormatMismatch::Fail`] is logged and dropped;
+    /// use [`Mixer::try_push_float`] to act on the error.
+    pub fn push_float(&mut self, frame: FloatFrame) {
+        if let Err(e) = self.try_push_float(frame) {
+            error!("{:#}", e);
+        }
+    }
+
+    /// Add a float frame, failing if its format does not match the mix and
+    /// the policy is [`FormatMismatch::Fail`]
+    pub fn try_push_float(&mut self, frame: FloatFrame) -> Result<()> {
+        self.drops.frames_received += 1;
+
+        let frame = if frame.sample_rate != self.config.sample_rate
+            || frame.channels != self.config.channels
+        {
+            match self.config.format_mismatch {
+                FormatMismatch::Coerce => {
+                    self.drops.coerced_format += 1;
+                    self.coerce(frame)
+                }
+                FormatMismatch::Drop => {
+                    warn!(
+                        "Mixer dropping {:?} frame: {}Hz/{}ch does not match {}Hz/{}ch",
+                        frame.source,
+                        frame.sample_rate,
+                        frame.channels,
+                        self.config.sample_rate,
+                        self.config.channels
+                    );
+                    self.drops.mismatched_format += 1;
+                    return Ok(());
+                }
+                FormatMismatch::Fail => {
+                    self.drops.mismatched_format += 1;
+                    anyhow::bail!(
+                        "{:?} audio is {}Hz/{}ch, but the mix is {}Hz/{}ch",
+                        frame.source,
+                        frame.sample_rate,
+                        frame.channels,
+                        self.config.sample_rate,
+                        self.config.channels
+                    );
+                }
+            }
+        } else {
+            frame
+        };
+
+        self.buffer(frame);
+        Ok(())
+    }
+
+    /// Convert a frame to the mix's channel count and sample rate
+    fn coerce(&mut self, frame: FloatFrame) -> FloatFrame {
+        // Remix first, so fewer channels are resampled when folding down
+        let frame = frame.with_channels(self.config.channels);
+        if frame.sample_rate == self.config.sample_rate {
+            return frame;
+        }
+
+        let resampler = self.resamplers.entry(frame.source).or_insert_with(|| {
+            info!(
+                "Mixer resampling {:?} from {}Hz to {}Hz",
+                frame.source, frame.sample_rate, self.config.sample_rate
+            );
+            Resampler::new(frame.sample_rate, self.config.sample_rate, frame.channels)
+        });
+        // A source that changes rate mid-stream starts a fresh resampler
+        if resampler.from_rate() != frame.sample_rate {
+            info!(
+                "Mixer resampling {:?} from {}Hz (was {}Hz) to {}Hz",
+                frame.source,
+                frame.sample_rate,
+                resampler.from_rate(),
+                self.config.sample_rate
+            );
+            *resampler = Resampler::new(frame.sample_rate, self.config.sample_rate, frame.channels);
+        }
+        resampler.resample_float_frame(frame)
+    }
+
+    /// Append a frame already in the mix format to its source's buffer
+    fn buffer(&mut self, frame: FloatFrame) {
+        let samples_per_ms = self.config.samples_per_ms();
+        let position_ms = self.position_ms;
+
+        let Some(input) = self.inputs.get_mut(&frame.source) else {
+            warn!(
+                "Mixer dropping frame from unconfigured source {:?}",
+                frame.source
+            );
+            self.drops.unknown_source += 1;
+            return;
+        };
+
+        // Timestamp just past the samples already buffered for this source
+        let buffered_end_ms = position_ms + (input.samples.len() as f64 / samples_per_ms) as u64;
+        let frame_end_ms =
+            frame.timestamp_ms + (frame.samples.len() as f64 / samples_per_ms) as u64;
+
+        if frame_end_ms <= position_ms {
+            warn!(
+                "Mixer dropping old {:?} frame ({}ms, mix is at {}ms)",
+                frame.source, frame.timestamp_ms, position_ms
+            );
+            self.drops.late += 1;
+            return;
+        }
+
+        // Zero-fill gaps longer than one frame so sources stay aligned
+        if frame.timestamp_ms > buffered_end_ms + self.config.frame_duration_ms {
+            let gap_samples =
+                ((frame.timestamp_ms - buffered_end_ms) as f64 * samples_per_ms) as usize;
+            let gap_samples = gap_samples - gap_samples % self.config.channels as usize;
+            input.samples.extend(std::iter::repeat_n(0.0, gap_samples));
+        }
+
+        input.samples.extend(frame.samples);
+    }
+
+    /// Counts of received and dropped frames so far
+    pub fn drop_stats(&self) -> FrameDropStats {
+        self.drops
+    }
+
+    /// Mix and return every output frame that is ready
+    pub fn pop_ready(&mut self) -> Vec<AudioFrame> {
+        self.pop_ready_float()
+            .iter()
+            .map(FloatFrame::to_pcm16)
+            .collect()
+    }
+
+    /// Mix and return every output frame that is ready, in float
+    pub fn pop_ready_float(&mut self) -> Vec<FloatFrame> {
+        let frame_samples = self.frame_samples();
+        let max_lag_samples =
+            (self.config.max_latency_ms as f64 * self.config.samples_per_ms()) as usize;
+        let mut frames = Vec::new();
+
+        loop {
+            let longest = self.inputs.values().map(|i| i.samples.len()).max();
+            let shortest = self.inputs.values().map(|i| i.samples.len()).min();
+
+            let (Some(longest), Some(shortest)) = (longest, shortest) else {
+                break;
+            };
+
+            // Ready when every source has a full frame, or the leader is far
+            // enough ahead that lagging sources are treated as silent
+            let ready = shortest >= frame_samples || longest >= frame_samples + max_lag_samples;
+            if !ready {
+                break;
+            }
+
+            frames.push(self.mix(frame_samples));
+        }
+
+        frames
+    }
+
+    /// Mix whatever is buffered into a final (possibly short) frame
+    pub fn flush(&mut self) -> Option<AudioFrame> {
+        self.flush_float().as_ref().map(FloatFrame::to_pcm16)
+    }
+
+    /// Mix whatever is buffered into a final (possibly short) float frame
+    pub fn flush_float(&mut self) -> Option<FloatFrame> {
+        let mut frames = self.pop_ready_float();
+        let remaining = self
+            .inputs
+            .values()
+            .map(|i| i.samples.len())
+            .max()
+            .unwrap_or(0);
+
+        if remaining > 0 {
+            frames.push(self.mix(remaining));
+        }
+
+        // Merge into a single frame
+        let mut frames = frames.into_iter();
+        let mut merged = frames.next()?;
+        for frame in frames {
+            merged.samples.extend(frame.samples);
+        }
+        Some(merged)
+    }
+
+    fn frame_samples(&self) -> usize {
+        let samples =
+            (self.config.frame_duration_ms as f64 * self.config.samples_per_ms()) as usize;
+        (samples - samples % self.config.channels as usize).max(self.config.channels as usize)
+    }
+
+    /// Sum `count` samples from every input (zero-filling short inputs)
+    fn mix(&mut self, count: usize) -> FloatFrame {
+        let mut samples = vec![0f32; count];
+
+        for input in self.inputs.values_mut() {
+            let available = input.samples.len().min(count);
+            for (out, sample) in samples.iter_mut().zip(input.samples.drain(..available)) {
+                *out += sample * input.gain;
+            }
+        }
+
+        let frame = FloatFrame {
+            samples,
+            sample_rate: self.config.sample_rate,
+            channels: self.config.channels,
+            timestamp_ms: self.position_ms,
+            source: AudioStreamSource::System, // Mixed frames are tagged as system
+        };
+
+        self.position_ms += (count as f64 / self.config.samples_per_ms()) as u64;
+
+        frame
+    }
+}
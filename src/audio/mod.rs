@@ -1,13 +1,64 @@
+pub mod activity;
 pub mod backend;
+pub mod channels;
 pub mod chunk;
+pub mod downmix;
 pub mod file;
+pub mod file_source;
+pub mod float;
+pub mod index;
+pub mod loudness;
+pub mod microphone;
+pub mod mixer;
+pub mod network;
+pub mod opus;
+pub mod pipeline;
+pub mod playback;
+pub mod preroll;
+pub mod preset;
+pub mod resample;
+pub mod splitter;
+pub mod wavinfo;
+pub mod workdir;
 
 #[cfg(target_os = "macos")]
 pub mod macos;
 
+pub use activity::{AppActivity, AppActivitySummary, AppActivityTracker, AudioApplication};
 pub use backend::{
     AudioBackend, AudioBackendConfig, AudioBackendFactory, AudioFrame, AudioSource,
-    AudioStreamSource,
+    AudioStreamSource, CaptureBackendInfo, DeviceEvent, DeviceEventKind,
+};
+pub use channels::{ChannelMap, ChannelRole};
+pub use chunk::{
+    file_sha256, ChunkCodec, ChunkConfig, ChunkMetadata, ChunkProgress, ChunkRotation,
+    ChunkedRecorder,
+};
+pub use downmix::{Downmix, DownmixStrategy};
+pub use file::{AudioBlock, AudioDecoder, AudioFile, AudioInfo, AudioStream, DecodeProgress};
+pub use file_source::{FileBackend, FilePacing, FileSourceConfig};
+pub use float::FloatFrame;
+pub use index::{MeetingIndex, INDEX_FILE};
+pub use loudness::{
+    integrated_loudness, normalize_loudness, sample_peak_dbfs, LoudnessConfig, LoudnessReport,
+};
+pub use microphone::{CaptureConverter, MicrophoneBackend};
+pub use mixer::{FormatMismatch, FrameDropStats, Mixer, MixerConfig, MixerInput};
+pub use network::{
+    NetworkBackend, NetworkCodec, NetworkProtocol, NetworkSourceConfig, PacketDecoder,
+};
+pub use opus::{OpusDecoder, OpusEncoder};
+pub use pipeline::{
+    AudioProcessor, DownmixStage, GainStage, GateStage, NoiseSuppressionStage, Pipeline,
+    ResampleStage, StageConfig,
+};
+pub use playback::{PlaybackBridge, PlaybackConfig};
+pub use preroll::{write_clip, PrerollBuffer};
+pub use preset::QualityPreset;
+pub use resample::{Resampler, ResamplerQuality};
+pub use splitter::StereoSplitter;
+pub use wavinfo::WavInfo;
+pub use workdir::{
+    instance_id, is_workdir_file, DirInUse, InstanceStamp, ManifestChunk, MeetingDirLock,
+    RecordingManifest,
 };
-pub use chunk::{ChunkConfig, ChunkMetadata, ChunkedRecorder};
-pub use file::AudioFile;
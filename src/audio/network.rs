@@ -0,0 +1,353 @@
+// Network audio backend receiving PCM or Opus over RTP or plain UDP
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use super::backend::{AudioBackend, AudioFrame, AudioStreamSource};
+use super::opus::OpusDecoder;
+
+/// Largest datagram accepted (the UDP maximum)
+const MAX_DATAGRAM_BYTES: usize = 65535;
+
+/// Frames buffered for the session before the receiver waits
+const FRAME_CHANNEL_CAPACITY: usize = 64;
+
+/// Lost RTP packets replaced with silence; a longer gap is taken as the
+/// sender restarting rather than as loss
+const MAX_CONCEALED_PACKETS: u16 = 50;
+
+/// How audio arrives in each datagram
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkProtocol {
+    /// RTP packets (RFC 3550); PCM payloads are big-endian, as L16
+    /// (RFC 3551) specifies
+    #[default]
+    Rtp,
+    /// The payload alone, one datagram per packet; PCM is little-endian
+    Udp,
+}
+
+/// Payload encoding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkCodec {
+    /// Interleaved 16-bit PCM
+    #[default]
+    Pcm,
+    /// One Opus packet per datagram (needs the `opus` feature)
+    Opus,
+}
+
+/// Audio streamed to the server from another device, e.g. a
+/// conference-room Raspberry Pi
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSourceConfig {
+    /// Address to receive on
+    pub bind: SocketAddr,
+    pub protocol: NetworkProtocol,
+    pub codec: NetworkCodec,
+    /// Sample rate the sender streams at (Opus: 8, 12, 16, 24, or 48 kHz)
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Only accept packets from this address (None = any sender)
+    pub sender: Option<IpAddr>,
+}
+
+impl Default for NetworkSourceConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([0, 0, 0, 0], 5004)),
+            protocol: NetworkProtocol::default(),
+            codec: NetworkCodec::default(),
+            sample_rate: 16000,
+            channels: 1,
+            sender: None,
+        }
+    }
+}
+
+/// RTP header fields the receiver uses, and the payload
+struct RtpPacket<'a> {
+    sequence: u16,
+    ssrc: u32,
+    payload: &'a [u8],
+}
+
+/// Parse an RTP packet, skipping CSRCs, header extensions, and padding
+fn parse_rtp(packet: &[u8]) -> Option<RtpPacket<'_>> {
+    if packet.len() < 12 || packet[0] >> 6 != 2 {
+        return None;
+    }
+    let padding = packet[0] & 0x20 != 0;
+    let extension = packet[0] & 0x10 != 0;
+    let csrc_count = (packet[0] & 0x0f) as usize;
+
+    let mut start = 12 + 4 * csrc_count;
+    if extension {
+        let header = packet.get(start..start + 4)?;
+        start += 4 + u16::from_be_bytes([header[2], header[3]]) as usize * 4;
+    }
+    let mut end = packet.len();
+    if padding {
+        end = end.checked_sub(packet[end - 1] as usize)?;
+    }
+
+    Some(RtpPacket {
+        sequence: u16::from_be_bytes([packet[2], packet[3]]),
+        ssrc: u32::from_be_bytes([packet[8], packet[9], packet[10], packet[11]]),
+        payload: packet.get(start..end)?,
+    })
+}
+
+/// Turns received datagrams into samples
+///
+/// RTP streams are followed by sequence number: late and repeated packets
+/// are dropped and lost ones replaced with silence, so the audio keeps its
+/// timing. Packets from a second sender (SSRC) are ignored.
+pub struct PacketDecoder {
+    protocol: NetworkProtocol,
+    /// None for PCM
+    opus: Option<OpusDecoder>,
+    ssrc: Option<u32>,
+    /// Sequence number of the next packet
+    next_sequence: Option<u16>,
+    /// Samples in the last packet, the size of concealed ones
+    last_samples: usize,
+}
+
+impl PacketDecoder {
+    pub fn new(config: &NetworkSourceConfig) -> Result<Self> {
+        let opus = match config.codec {
+            NetworkCodec::Pcm => None,
+            NetworkCodec::Opus => Some(OpusDecoder::new(config.sample_rate, config.channels)?),
+        };
+        Ok(Self {
+            protocol: config.protocol,
+            opus,
+            ssrc: None,
+            next_sequence: None,
+            last_samples: 0,
+        })
+    }
+
+    /// Samples carried by `datagram` (preceded by silence for any packets
+    /// lost before it); empty for packets that are dropped
+    pub fn decode(&mut self, datagram: &[u8]) -> Result<Vec<i16>> {
+        let (payload, concealed) = match self.protocol {
+            NetworkProtocol::Udp => (datagram, 0),
+            NetworkProtocol::Rtp => {
+                let Some(packet) = parse_rtp(datagram) else {
+                    bail!("Not an RTP packet ({} bytes)", datagram.len());
+                };
+                match self.ssrc {
+                    Some(ssrc) if ssrc != packet.ssrc => return Ok(Vec::new()),
+                    Some(_) => {}
+                    None => self.ssrc = Some(packet.ssrc),
+                }
+
+                let lost = match self.next_sequence {
+                    Some(next) => {
+                        let ahead = packet.sequence.wrapping_sub(next);
+                        // Behind the expected packet: late or repeated
+                        if ahead >= u16::MAX / 2 {
+                            return Ok(Vec::new());
+                        }
+                        ahead
+                    }
+                    None => 0,
+                };
+                self.next_sequence = Some(packet.sequence.wrapping_add(1));
+                let concealed = if lost <= MAX_CONCEALED_PACKETS {
+                    lost as usize * self.last_samples
+                } else {
+                    0
+                };
+                (packet.payload, concealed)
+            }
+        };
+
+        let samples = match &mut self.opus {
+            Some(opus) => opus.decode(payload)?,
+            None => {
+                let bytes = payload.chunks_exact(2);
+                match self.protocol {
+                    NetworkProtocol::Rtp => bytes
+                        .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+                        .collect(),
+                    NetworkProtocol::Udp => bytes
+                        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                        .collect(),
+                }
+            }
+        };
+        self.last_samples = samples.len();
+
+        if concealed == 0 {
+            return Ok(samples);
+        }
+        let mut audio = vec![0; concealed];
+        audio.extend(samples);
+        Ok(audio)
+    }
+}
+
+/// Network audio backend
+///
+/// Receives a stream sent from another device over RTP or plain UDP, so the
+/// server can transcribe and chunk audio it doesn't capture itself. Frames
+/// arrive in the sender's format; the session converts them.
+pub struct NetworkBackend {
+    config: NetworkSourceConfig,
+    local_addr: Option<SocketAddr>,
+    shutdown: CancellationToken,
+    task: Option<JoinHandle<()>>,
+}
+
+impl NetworkBackend {
+    pub fn new(config: NetworkSourceConfig) -> Result<Self> {
+        if config.sample_rate == 0 || !(1..=2).contains(&config.channels) {
+            bail!(
+                "Unsupported network audio format: {}Hz, {} channels",
+                config.sample_rate,
+                config.channels
+            );
+        }
+        // Fail now rather than when the session starts
+        PacketDecoder::new(&config)?;
+
+        Ok(Self {
+            config,
+            local_addr: None,
+            shutdown: CancellationToken::new(),
+            task: None,
+        })
+    }
+
+    /// Address receiving audio, once started (the bound port when the
+    /// configured one is 0)
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Receive datagrams until `shutdown`, sending their audio as frames
+    async fn receive(
+        socket: UdpSocket,
+        config: NetworkSourceConfig,
+        mut decoder: PacketDecoder,
+        frame_tx: mpsc::Sender<AudioFrame>,
+        shutdown: CancellationToken,
+    ) {
+        let mut buffer = vec![0u8; MAX_DATAGRAM_BYTES];
+        let mut received_frames: u64 = 0;
+        let mut bad_packets: u64 = 0;
+
+        loop {
+            let (len, peer) = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                received = socket.recv_from(&mut buffer) => match received {
+                    Ok(received) => received,
+                    Err(e) => {
+                        debug!("Network audio receive failed: {}", e);
+                        continue;
+                    }
+                },
+            };
+            if config.sender.is_some_and(|sender| sender != peer.ip()) {
+                continue;
+            }
+
+            let samples = match decoder.decode(&buffer[..len]) {
+                Ok(samples) => samples,
+                Err(e) => {
+                    // Logged once, then counted
+                    bad_packets += 1;
+                    if bad_packets == 1 {
+                        warn!("Dropping network audio from {}: {:#}", peer, e);
+                    }
+                    continue;
+                }
+            };
+            if samples.is_empty() {
+                continue;
+            }
+
+            let frame = AudioFrame {
+                timestamp_ms: received_frames * 1000 / config.sample_rate as u64,
+                samples,
+                sample_rate: config.sample_rate,
+                channels: config.channels,
+                source: AudioStreamSource::System,
+            };
+            received_frames += (frame.samples.len() / config.channels as usize) as u64;
+            if frame_tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+
+        if bad_packets > 0 {
+            warn!("Dropped {} unreadable network audio packets", bad_packets);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AudioBackend for NetworkBackend {
+    async fn start(&mut self) -> Result<mpsc::Receiver<AudioFrame>> {
+        if self.task.is_some() {
+            bail!("Already capturing");
+        }
+
+        let socket = UdpSocket::bind(self.config.bind)
+            .await
+            .with_context(|| format!("Failed to receive network audio on {}", self.config.bind))?;
+        let local_addr = socket.local_addr()?;
+        info!(
+            "Receiving {:?} {:?} audio on {} ({}Hz, {} channels)",
+            self.config.protocol,
+            self.config.codec,
+            local_addr,
+            self.config.sample_rate,
+            self.config.channels
+        );
+
+        let (frame_tx, frame_rx) = mpsc::channel(FRAME_CHANNEL_CAPACITY);
+        self.shutdown = CancellationToken::new();
+        self.task = Some(tokio::spawn(Self::receive(
+            socket,
+            self.config.clone(),
+            PacketDecoder::new(&self.config)?,
+            frame_tx,
+            self.shutdown.clone(),
+        )));
+        self.local_addr = Some(local_addr);
+        Ok(frame_rx)
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        let Some(task) = self.task.take() else {
+            return Ok(());
+        };
+
+        self.shutdown.cancel();
+        task.await.context("Network audio task failed")?;
+        self.local_addr = None;
+        info!("Network audio capture stopped");
+        Ok(())
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.task.is_some()
+    }
+
+    fn name(&self) -> &str {
+        "network"
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::Result;
+
+/// Duration of each Opus packet
+pub const OPUS_FRAME_MS: usize = 20;
+
+/// Largest packet the encoder may produce (recommended by libopus)
+#[cfg(feature = "opus")]
+const MAX_PACKET_BYTES: usize = 4000;
+
+/// Encodes 16-bit PCM into 20 ms Opus packets
+///
+/// Samples that do not fill a whole packet are kept for the next call, so
+/// frames of any size can be fed in. Encoding needs the `opus` feature (and
+/// libopus); without it, `new` fails and callers fall back to PCM.
+pub struct OpusEncoder {
+    #[cfg(feature = "opus")]
+    encoder: audiopus::coder::Encoder,
+    /// Interleaved samples per packet
+    frame_samples: usize,
+    /// Samples not yet encoded
+    pending: Vec<i16>,
+}
+
+impl OpusEncoder {
+    /// Whether this build can encode Opus
+    pub fn is_available() -> bool {
+        cfg!(feature = "opus")
+    }
+
+    /// Encoder for speech at `sample_rate` (8, 12, 16, 24, or 48 kHz)
+    pub fn new(sample_rate: u32, channels: u16) -> Result<Self> {
+        let frame_samples = sample_rate as usize * OPUS_FRAME_MS / 1000 * channels as usize;
+
+        #[cfg(feature = "opus")]
+        {
+            use anyhow::{anyhow, Context};
+            use audiopus::{coder::Encoder, Application, Channels, SampleRate};
+
+            let rate = SampleRate::try_from(sample_rate as i32)
+                .map_err(|_| anyhow!("Opus does not support {} Hz", sample_rate))?;
+            let layout = Channels::try_from(channels as i32)
+                .ok()
+                .filter(|layout| *layout != Channels::Auto)
+                .ok_or_else(|| anyhow!("Opus does not support {} channels", channels))?;
+            let encoder = Encoder::new(rate, layout, Application::Voip)
+                .context("Failed to create Opus encoder")?;
+
+            Ok(Self {
+                encoder,
+                frame_samples,
+                pending: Vec::with_capacity(frame_samples),
+            })
+        }
+
+        #[cfg(not(feature = "opus"))]
+        {
+            let _ = frame_samples;
+            anyhow::bail!("Opus encoding requires building with the `opus` feature")
+        }
+    }
+
+    /// Encode `samples`, returning every packet completed so far
+    pub fn encode(&mut self, samples: &[i16]) -> Result<Vec<Vec<u8>>> {
+        self.pending.extend_from_slice(samples);
+
+        let mut packets = Vec::new();
+        while self.pending.len() >= self.frame_samples {
+            let frame: Vec<i16> = self.pending.drain(..self.frame_samples).collect();
+            packets.push(self.encode_frame(&frame)?);
+        }
+        Ok(packets)
+    }
+
+    /// Encode what is left, padded with silence to a whole packet
+    pub fn flush(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+
+        let mut frame = std::mem::take(&mut self.pending);
+        frame.resize(self.frame_samples, 0);
+        self.encode_frame(&frame).map(Some)
+    }
+
+    #[cfg(feature = "opus")]
+    fn encode_frame(&self, frame: &[i16]) -> Result<Vec<u8>> {
+        use anyhow::Context;
+
+        let mut packet = vec![0u8; MAX_PACKET_BYTES];
+        let len = self
+            .encoder
+            .encode(frame, &mut packet)
+            .context("Opus encoding failed")?;
+        packet.truncate(len);
+        Ok(packet)
+    }
+
+    #[cfg(not(feature = "opus"))]
+    fn encode_frame(&self, _frame: &[i16]) -> Result<Vec<u8>> {
+        anyhow::bail!("Opus encoding requires building with the `opus` feature")
+    }
+}
+
+/// Largest packet duration Opus allows, which sizes the decode buffer
+#[cfg(feature = "opus")]
+const MAX_PACKET_MS: usize = 120;
+
+/// Decodes Opus packets into 16-bit PCM
+///
+/// Like encoding, decoding needs the `opus` feature; without it, `new`
+/// fails.
+pub struct OpusDecoder {
+    #[cfg(feature = "opus")]
+    decoder: audiopus::coder::Decoder,
+    /// Interleaved samples in the longest packet
+    #[cfg(feature = "opus")]
+    max_samples: usize,
+    #[cfg(feature = "opus")]
+    channels: usize,
+}
+
+impl OpusDecoder {
+    /// Decoder producing `channels` at `sample_rate` (8, 12, 16, 24, or
+    /// 48 kHz), whatever the packets were encoded at
+    pub fn new(sample_rate: u32, channels: u16) -> Result<Self> {
+        #[cfg(feature = "opus")]
+        {
+            use anyhow::{anyhow, Context};
+            use audiopus::{coder::Decoder, Channels, SampleRate};
+
+            let rate = SampleRate::try_from(sample_rate as i32)
+                .map_err(|_| anyhow!("Opus does not support {} Hz", sample_rate))?;
+            let layout = Channels::try_from(channels as i32)
+                .ok()
+                .filter(|layout| *layout != Channels::Auto)
+                .ok_or_else(|| anyhow!("Opus does not support {} channels", channels))?;
+            let decoder = Decoder::new(rate, layout).context("Failed to create Opus decoder")?;
+
+            Ok(Self {
+                decoder,
+                max_samples: sample_rate as usize * MAX_PACKET_MS / 1000 * channels as usize,
+                channels: channels as usize,
+            })
+        }
+
+        #[cfg(not(feature = "opus"))]
+        {
+            let _ = (sample_rate, channels);
+            anyhow::bail!("Opus decoding requires building with the `opus` feature")
+        }
+    }
+
+    /// Decode one packet into interleaved samples
+    #[cfg(feature = "opus")]
+    pub fn decode(&mut self, packet: &[u8]) -> Result<Vec<i16>> {
+        use anyhow::Context;
+        use audiopus::{packet::Packet, MutSignals};
+
+        let packet = Packet::try_from(packet).context("Invalid Opus packet")?;
+        let mut samples = vec![0i16; self.max_samples];
+        let signals = MutSignals::try_from(&mut samples)?;
+        let frames = self
+            .decoder
+            .decode(Some(packet), signals, false)
+            .context("Opus decoding failed")?;
+        samples.truncate(frames * self.channels);
+        Ok(samples)
+    }
+
+    #[cfg(not(feature = "opus"))]
+    pub fn decode(&mut self, _packet: &[u8]) -> Result<Vec<i16>> {
+        anyhow::bail!("Opus decoding requires building with the `opus` feature")
+    }
+}
@@ -0,0 +1,439 @@
+// Composable per-frame processing: stages that each convert or shape audio
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use tracing::info;
+
+use super::backend::AudioStreamSource;
+use super::downmix::DownmixStrategy;
+use super::float::FloatFrame;
+use super::resample::{Resampler, ResamplerQuality};
+
+/// One step of per-frame audio processing, e.g. resampling or gain
+///
+/// Stages keep whatever state they need between frames (per source where
+/// sources differ), so a pipeline is owned by the task processing one
+/// stream of frames.
+pub trait AudioProcessor: Send {
+    /// Short name, for logs
+    fn name(&self) -> &'static str;
+
+    /// Whether the stage changes `frame` at all; skipped stages cost
+    /// nothing, not even 16-bit rounding
+    fn applies(&self, _frame: &FloatFrame) -> bool {
+        true
+    }
+
+    fn process(&mut self, frame: FloatFrame) -> FloatFrame;
+}
+
+/// A pipeline stage as configured for a session
+///
+/// Sessions build their frame processing from a list of these, in order,
+/// e.g. `[{stage: gain, gains: {system: 0.5}}, {stage: downmix},
+/// {stage: resample}, {stage: noise_suppression}, {stage: gate}]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum StageConfig {
+    /// Fold multi-channel frames to mono (only when recording mono)
+    Downmix,
+    /// Resample to the recording rate
+    Resample,
+    /// Linear gain per source (sources not listed pass unchanged)
+    Gain {
+        gains: HashMap<AudioStreamSource, f32>,
+    },
+    /// Silence frames below `threshold_dbfs` once they have been quiet for
+    /// `hold_ms`, so pauses carry no background noise
+    Gate {
+        #[serde(default = "default_gate_threshold_dbfs")]
+        threshold_dbfs: f32,
+        #[serde(default = "default_gate_hold_ms")]
+        hold_ms: u64,
+    },
+    /// Attenuate audio near each source's background noise level by up to
+    /// `reduction_db`
+    NoiseSuppression {
+        #[serde(default = "default_noise_reduction_db")]
+        reduction_db: f32,
+    },
+}
+
+fn default_gate_threshold_dbfs() -> f32 {
+    -45.0
+}
+
+fn default_gate_hold_ms() -> u64 {
+    300
+}
+
+fn default_noise_reduction_db() -> f32 {
+    12.0
+}
+
+impl StageConfig {
+    /// The conversion every captured frame needs: downmix, then resample
+    pub fn conversion() -> Vec<Self> {
+        vec![Self::Downmix, Self::Resample]
+    }
+}
+
+/// Stages run in order over each frame
+///
+/// With `quantize` set, each stage's output is rounded to 16-bit precision,
+/// matching the i16 pipeline; otherwise samples stay in full float.
+pub struct Pipeline {
+    stages: Vec<Box<dyn AudioProcessor>>,
+    quantize: bool,
+}
+
+impl Pipeline {
+    /// A pipeline that passes frames through until stages are added
+    pub fn new(quantize: bool) -> Self {
+        Self {
+            stages: Vec::new(),
+            quantize,
+        }
+    }
+
+    /// The conversion every captured frame gets: folded to mono when
+    /// `target_channels` is 1, then resampled to `target_sample_rate`
+    pub fn conversion(
+        target_sample_rate: u32,
+        target_channels: u16,
+        downmix: DownmixStrategy,
+        quality: ResamplerQuality,
+        quantize: bool,
+    ) -> Self {
+        Self::from_stages(
+            &StageConfig::conversion(),
+            target_sample_rate,
+            target_channels,
+            downmix,
+            quality,
+            quantize,
+        )
+    }
+
+    /// The configured `stages`, converting to `target_sample_rate` and
+    /// `target_channels` (a downmix stage is left out for a multi-channel
+    /// target)
+    pub fn from_stages(
+        stages: &[StageConfig],
+        target_sample_rate: u32,
+        target_channels: u16,
+        downmix: DownmixStrategy,
+        quality: ResamplerQuality,
+        quantize: bool,
+    ) -> Self {
+        let mut pipeline = Self::new(quantize);
+        for stage in stages {
+            match stage {
+                StageConfig::Downmix if target_channels == 1 => {
+                    pipeline.push(Box::new(DownmixStage::new(downmix.clone())))
+                }
+                StageConfig::Downmix => {}
+                StageConfig::Resample => {
+                    pipeline.push(Box::new(ResampleStage::new(target_sample_rate, quality)))
+                }
+                StageConfig::Gain { gains } => {
+                    pipeline.push(Box::new(GainStage::new(gains.clone())))
+                }
+                StageConfig::Gate {
+                    threshold_dbfs,
+                    hold_ms,
+                } => pipeline.push(Box::new(GateStage::new(*threshold_dbfs, *hold_ms))),
+                StageConfig::NoiseSuppression { reduction_db } => {
+                    pipeline.push(Box::new(NoiseSuppressionStage::new(*reduction_db)))
+                }
+            }
+        }
+        pipeline
+    }
+
+    /// Add `stage` after the stages so far
+    pub fn with(mut self, stage: impl AudioProcessor + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Add a boxed stage after the stages so far
+    pub fn push(&mut self, stage: Box<dyn AudioProcessor>) {
+        self.stages.push(stage);
+    }
+
+    /// Names of the stages, in order
+    pub fn stage_names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    pub fn process(&mut self, frame: FloatFrame) -> FloatFrame {
+        let mut frame = frame;
+        for stage in &mut self.stages {
+            if !stage.applies(&frame) {
+                continue;
+            }
+            frame = stage.process(frame);
+            if self.quantize {
+                frame.quantize();
+            }
+        }
+        frame
+    }
+}
+
+/// Folds multi-channel frames to mono with a downmix strategy
+pub struct DownmixStage {
+    strategy: DownmixStrategy,
+}
+
+impl DownmixStage {
+    pub fn new(strategy: DownmixStrategy) -> Self {
+        Self { strategy }
+    }
+}
+
+impl AudioProcessor for DownmixStage {
+    fn name(&self) -> &'static str {
+        "downmix"
+    }
+
+    fn applies(&self, frame: &FloatFrame) -> bool {
+        frame.channels > 1
+    }
+
+    fn process(&mut self, frame: FloatFrame) -> FloatFrame {
+        frame.downmix(&self.strategy)
+    }
+}
+
+/// Resamples frames to a target rate
+///
+/// Resamplers are kept per source so each stream resamples continuously. If a
+/// source's native rate changes mid-session (e.g. CoreAudio renegotiating
+/// 44.1kHz↔48kHz), its resampler is replaced rather than dropping audio.
+pub struct ResampleStage {
+    target_sample_rate: u32,
+    quality: ResamplerQuality,
+    resamplers: HashMap<AudioStreamSource, Resampler>,
+}
+
+impl ResampleStage {
+    pub fn new(target_sample_rate: u32, quality: ResamplerQuality) -> Self {
+        Self {
+            target_sample_rate,
+            quality,
+            resamplers: HashMap::new(),
+        }
+    }
+}
+
+impl AudioProcessor for ResampleStage {
+    fn name(&self) -> &'static str {
+        "resample"
+    }
+
+    fn applies(&self, frame: &FloatFrame) -> bool {
+        frame.sample_rate != self.target_sample_rate
+    }
+
+    fn process(&mut self, frame: FloatFrame) -> FloatFrame {
+        // Replace the source's resampler if its input format changed
+        let resampler = match self.resamplers.entry(frame.source) {
+            Entry::Occupied(entry) => {
+                let resampler = entry.into_mut();
+                if resampler.from_rate() != frame.sample_rate
+                    || resampler.channels() != frame.channels
+                {
+                    info!(
+                        "{:?} input changed to {}Hz/{}ch (was {}Hz), resampling to {}Hz",
+                        frame.source,
+                        frame.sample_rate,
+                        frame.channels,
+                        resampler.from_rate(),
+                        self.target_sample_rate
+                    );
+                    *resampler = Resampler::with_quality(
+                        frame.sample_rate,
+                        self.target_sample_rate,
+                        frame.channels,
+                        self.quality,
+                    );
+                }
+                resampler
+            }
+            Entry::Vacant(entry) => entry.insert(Resampler::with_quality(
+                frame.sample_rate,
+                self.target_sample_rate,
+                frame.channels,
+                self.quality,
+            )),
+        };
+
+        resampler.resample_float_frame(frame)
+    }
+}
+
+/// Applies a linear gain per source (sources without one pass unchanged)
+pub struct GainStage {
+    gains: HashMap<AudioStreamSource, f32>,
+}
+
+impl GainStage {
+    pub fn new(gains: HashMap<AudioStreamSource, f32>) -> Self {
+        Self { gains }
+    }
+}
+
+impl AudioProcessor for GainStage {
+    fn name(&self) -> &'static str {
+        "gain"
+    }
+
+    fn applies(&self, frame: &FloatFrame) -> bool {
+        self.gains
+            .get(&frame.source)
+            .is_some_and(|&gain| gain != 1.0)
+    }
+
+    fn process(&mut self, mut frame: FloatFrame) -> FloatFrame {
+        if let Some(&gain) = self.gains.get(&frame.source) {
+            frame.apply_gain(gain);
+        }
+        frame
+    }
+}
+
+/// RMS level of float samples in dBFS (-inf for silence)
+fn float_dbfs(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    20.0 * (sum / samples.len() as f64).sqrt().log10() as f32
+}
+
+/// Milliseconds of audio in a frame
+fn frame_ms(frame: &FloatFrame) -> u64 {
+    let frames = frame.samples.len() as u64 / frame.channels.max(1) as u64;
+    frames * 1000 / frame.sample_rate.max(1) as u64
+}
+
+/// Silences each source once it has stayed below a level for a while
+///
+/// The same RMS threshold the utterance tracker detects pauses with: a
+/// frame at or above `threshold_dbfs` counts as voice and passes, along
+/// with `hold_ms` of quiet after it so word endings aren't clipped. Frames
+/// keep their length, so timing is unchanged.
+pub struct GateStage {
+    threshold_dbfs: f32,
+    hold_ms: u64,
+    /// Milliseconds each source has been below the threshold
+    quiet_ms: HashMap<AudioStreamSource, u64>,
+}
+
+impl GateStage {
+    pub fn new(threshold_dbfs: f32, hold_ms: u64) -> Self {
+        Self {
+            threshold_dbfs,
+            hold_ms,
+            quiet_ms: HashMap::new(),
+        }
+    }
+}
+
+impl AudioProcessor for GateStage {
+    fn name(&self) -> &'static str {
+        "gate"
+    }
+
+    fn process(&mut self, mut frame: FloatFrame) -> FloatFrame {
+        let quiet_ms = self.quiet_ms.entry(frame.source).or_default();
+        if float_dbfs(&frame.samples) >= self.threshold_dbfs {
+            *quiet_ms = 0;
+            return frame;
+        }
+
+        *quiet_ms += frame_ms(&frame);
+        if *quiet_ms > self.hold_ms {
+            frame.samples.fill(0.0);
+        }
+        frame
+    }
+}
+
+/// Blocks each source's noise level is tracked and gain applied over
+const SUPPRESSION_BLOCK_MS: u32 = 10;
+
+/// How fast the noise estimate may rise per block (about 1dB a second), so
+/// speech doesn't raise it while louder noise is still followed
+const NOISE_FLOOR_RISE: f32 = 1.0012;
+
+/// How far the gain moves towards its new value per block, so it doesn't
+/// flutter between blocks
+const SUPPRESSION_SMOOTHING: f32 = 0.3;
+
+/// A source's background noise estimate and current gain
+struct NoiseState {
+    /// RMS of the background noise (linear)
+    floor: f32,
+    gain: f32,
+}
+
+/// Broadband noise suppression: attenuates audio near each source's
+/// background noise level
+///
+/// The noise level is the quietest recent 10ms block level, rising slowly
+/// between pauses. Each block is scaled by a Wiener-style gain from its
+/// level over that floor, down to `reduction_db` below unity, so steady
+/// background noise (fans, hum, hiss) is turned down in pauses and speech
+/// well above it passes unchanged. It works in the time domain; noise under
+/// speech is not removed.
+pub struct NoiseSuppressionStage {
+    min_gain: f32,
+    noise: HashMap<AudioStreamSource, NoiseState>,
+}
+
+impl NoiseSuppressionStage {
+    pub fn new(reduction_db: f32) -> Self {
+        Self {
+            min_gain: 10f32.powf(-reduction_db.max(0.0) / 20.0),
+            noise: HashMap::new(),
+        }
+    }
+}
+
+impl AudioProcessor for NoiseSuppressionStage {
+    fn name(&self) -> &'static str {
+        "noise_suppression"
+    }
+
+    fn applies(&self, _frame: &FloatFrame) -> bool {
+        self.min_gain < 1.0
+    }
+
+    fn process(&mut self, mut frame: FloatFrame) -> FloatFrame {
+        let channels = frame.channels.max(1) as usize;
+        let block = (frame.sample_rate * SUPPRESSION_BLOCK_MS / 1000).max(1) as usize * channels;
+        let min_gain = self.min_gain;
+
+        for samples in frame.samples.chunks_mut(block) {
+            let rms = 10f32.powf(float_dbfs(samples) / 20.0);
+            let state = self.noise.entry(frame.source).or_insert(NoiseState {
+                floor: rms,
+                gain: 1.0,
+            });
+            state.floor = rms.min(state.floor * NOISE_FLOOR_RISE).max(f32::EPSILON);
+
+            // Wiener gain from the block's signal-to-noise power ratio
+            let snr = (rms / state.floor).powi(2);
+            let target = (1.0 - 1.0 / snr).clamp(min_gain, 1.0);
+            state.gain += (target - state.gain) * SUPPRESSION_SMOOTHING;
+
+            let gain = state.gain;
+            samples.iter_mut().for_each(|sample| *sample *= gain);
+        }
+        frame
+    }
+}
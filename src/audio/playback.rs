@@ -0,0 +1,272 @@
+// Playback bridge writing a session's audio to a named pipe (Linux)
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::thread::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::backend::AudioStreamSource;
+use super::float::FloatFrame;
+use super::mixer::{Mixer, MixerConfig, MixerInput};
+
+/// Mixed frames queued for the pipe before new ones are dropped
+const PLAYBACK_QUEUE_FRAMES: usize = 16;
+
+/// Largest write a pipe takes whole (POSIX `PIPE_BUF`), so a reader that
+/// falls behind loses whole writes and never half a sample
+const ATOMIC_WRITE_BYTES: usize = 4096;
+
+/// Playing a session's audio back out, e.g. as the microphone of a
+/// conferencing app while the session records (Linux only)
+///
+/// Audio is passed through as recorded: the mix after the configured
+/// `audio.stages` and mutes, with no further processing. It is written to a
+/// named pipe as raw little-endian 16-bit PCM at the session's rate and
+/// channels (16 kHz mono). With PulseAudio or PipeWire, `pactl load-module
+/// module-pipe-source file=<pipe> format=s16le rate=16000 channels=1` turns
+/// it into an input device apps can select. Other platforms have no such
+/// module, so starting playback fails there.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlaybackConfig {
+    pub enabled: bool,
+    /// Named pipe to write to, created if missing
+    pub pipe: String,
+    /// Play system audio too (off: only microphones, so the other side of
+    /// a call doesn't hear itself)
+    pub include_system: bool,
+}
+
+impl Default for PlaybackConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pipe: "~/.loqa/playback.pcm".to_string(),
+            include_system: false,
+        }
+    }
+}
+
+impl PlaybackConfig {
+    /// Pipe path with `~` expanded
+    pub fn resolved_pipe(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.pipe).into_owned())
+    }
+}
+
+/// Mixes a session's processed sources and writes the result to the
+/// playback pipe
+///
+/// Writing happens on its own thread and never holds up the session:
+/// while nothing reads the pipe, or the reader falls behind, audio is
+/// dropped.
+pub struct PlaybackBridge {
+    mixer: Mixer,
+    include_system: bool,
+    /// None once the writer has stopped
+    tx: Option<SyncSender<Vec<i16>>>,
+    writer: Option<JoinHandle<()>>,
+    /// Frames dropped because the writer was behind
+    dropped: u64,
+}
+
+impl PlaybackBridge {
+    /// Start playing `config`'s sources at the mix format, from
+    /// `position_ms` on (None when playback is off)
+    ///
+    /// `mixer` is the session's mix; system audio is taken out of it unless
+    /// the config includes it.
+    pub fn start(
+        config: &PlaybackConfig,
+        mixer: MixerConfig,
+        position_ms: u64,
+    ) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let path = config.resolved_pipe();
+        create_pipe(&path)?;
+        info!(
+            "Playing {} audio to {} ({}Hz, {} channels, s16le)",
+            if config.include_system {
+                "the session's"
+            } else {
+                "microphone"
+            },
+            path.display(),
+            mixer.sample_rate,
+            mixer.channels
+        );
+
+        let mut mixer = mixer;
+        if !config.include_system {
+            mixer
+                .inputs
+                .retain(|input| input.source != AudioStreamSource::System);
+        }
+
+        let (tx, rx) = sync_channel(PLAYBACK_QUEUE_FRAMES);
+        let writer = std::thread::Builder::new()
+            .name("playback".to_string())
+            .spawn(move || write_pipe(&path, rx))
+            .context("Failed to start playback writer")?;
+
+        Ok(Some(Self {
+            mixer: Mixer::new(mixer).starting_at(position_ms),
+            include_system: config.include_system,
+            tx: Some(tx),
+            writer: Some(writer),
+            dropped: 0,
+        }))
+    }
+
+    /// Add a processed frame, playing whatever mix it completes
+    ///
+    /// Sources the bridge wasn't started with (e.g. companion devices) are
+    /// mixed in from their first frame.
+    pub fn push(&mut self, frame: FloatFrame) {
+        if frame.source == AudioStreamSource::System && !self.include_system {
+            return;
+        }
+        if !self.mixer.has_input(frame.source) {
+            self.mixer.add_input(MixerInput {
+                source: frame.source,
+                gain: 1.0,
+            });
+        }
+
+        self.mixer.push_float(frame);
+        for mixed in self.mixer.pop_ready() {
+            self.play(mixed.samples);
+        }
+    }
+
+    /// Stop mixing a source that has gone away
+    pub fn remove_source(&mut self, source: AudioStreamSource) {
+        self.mixer.remove_input(source);
+    }
+
+    fn play(&mut self, samples: Vec<i16>) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        match tx.try_send(samples) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.dropped += 1;
+                if self.dropped == 1 {
+                    debug!("Playback writer is behind; dropping audio");
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => self.tx = None,
+        }
+    }
+}
+
+impl Drop for PlaybackBridge {
+    /// Play what is still mixed, then wait for the writer to finish
+    fn drop(&mut self) {
+        if let Some(remaining) = self.mixer.flush() {
+            self.play(remaining.samples);
+        }
+        self.tx = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        if self.dropped > 0 {
+            warn!(
+                "Playback dropped {} frames the reader couldn't keep up with",
+                self.dropped
+            );
+        }
+    }
+}
+
+/// Create a named pipe at `path`, or check the file there is one
+#[cfg(target_os = "linux")]
+fn create_pipe(path: &Path) -> Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.file_type().is_fifo() => return Ok(()),
+        Ok(_) => bail!("{} exists and is not a named pipe", path.display()),
+        Err(_) => {}
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .context("Pipe path contains a NUL byte")?;
+    // SAFETY: c_path is a valid NUL-terminated string
+    if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to create pipe {}", path.display()));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn create_pipe(path: &Path) -> Result<()> {
+    bail!(
+        "Playback to {} needs PulseAudio or PipeWire on Linux to become an input device; \
+         this platform has no virtual output for it",
+        path.display()
+    )
+}
+
+/// Write each mixed frame to the pipe while a reader has it open
+///
+/// The pipe is opened without blocking, so with no reader frames are
+/// dropped and opening is retried with the next one; a reader that goes
+/// away is waited for again the same way.
+#[cfg(target_os = "linux")]
+fn write_pipe(path: &Path, rx: Receiver<Vec<i16>>) {
+    use std::io::{ErrorKind, Write};
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut pipe: Option<std::fs::File> = None;
+    let mut connected = false;
+
+    while let Ok(samples) = rx.recv() {
+        if pipe.is_none() {
+            pipe = std::fs::OpenOptions::new()
+                .write(true)
+                .custom_flags(libc::O_NONBLOCK)
+                .open(path)
+                .ok();
+            if pipe.is_some() && !connected {
+                info!("Playback reader connected to {}", path.display());
+                connected = true;
+            }
+        }
+        let Some(file) = pipe.as_mut() else {
+            continue;
+        };
+
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        for chunk in bytes.chunks(ATOMIC_WRITE_BYTES) {
+            match file.write(chunk) {
+                Ok(_) => {}
+                // The reader is behind; this write is dropped whole
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => {
+                    info!("Playback reader disconnected: {}", e);
+                    pipe = None;
+                    connected = false;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn write_pipe(_path: &Path, rx: Receiver<Vec<i16>>) {
+    while rx.recv().is_ok() {}
+}
@@ -0,0 +1,88 @@
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+
+/// The most recent stretch of a session's audio, kept in memory so a
+/// bookmark can save what was just said
+///
+/// Once full, each frame pushed evicts the oldest samples.
+#[derive(Debug, Clone)]
+pub struct PrerollBuffer {
+    samples: VecDeque<i16>,
+    capacity: usize,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl PrerollBuffer {
+    /// Buffer holding up to `duration` of audio in the given format
+    pub fn new(duration: Duration, sample_rate: u32, channels: u16) -> Self {
+        let capacity =
+            (duration.as_secs_f64() * sample_rate as f64).round() as usize * channels as usize;
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            sample_rate,
+            channels,
+        }
+    }
+
+    /// Add interleaved samples, dropping the oldest beyond the capacity
+    pub fn push(&mut self, samples: &[i16]) {
+        let samples = &samples[samples.len().saturating_sub(self.capacity)..];
+        let overflow = (self.samples.len() + samples.len()).saturating_sub(self.capacity);
+        self.samples.drain(..overflow);
+        self.samples.extend(samples);
+    }
+
+    /// Audio currently held
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(
+            self.samples.len() as f64 / (self.sample_rate as f64 * self.channels as f64),
+        )
+    }
+
+    /// The last `duration` of audio (or all of it, if less is held), on a
+    /// whole-frame boundary
+    pub fn last(&self, duration: Duration) -> Vec<i16> {
+        let frames = (duration.as_secs_f64() * self.sample_rate as f64).round() as usize;
+        let wanted = (frames * self.channels as usize).min(self.samples.len());
+        let wanted = wanted - wanted % self.channels.max(1) as usize;
+        self.samples
+            .range(self.samples.len() - wanted..)
+            .copied()
+            .collect()
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+}
+
+/// Write samples as a 16-bit PCM WAV clip, creating its directory
+pub fn write_clip(path: &Path, samples: &[i16], sample_rate: u32, channels: u16) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer
+        .finalize()
+        .with_context(|| format!("Failed to finish {}", path.display()))
+}
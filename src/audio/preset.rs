@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::chunk::{ChunkCodec, ChunkConfig};
+use super::resample::ResamplerQuality;
+
+/// Recording quality preset, trading latency against fidelity
+///
+/// A preset sets every latency/quality knob of the pipeline together so the
+/// backend, mixer, and recorder agree on frame sizes and buffering.
+/// `Balanced` matches the pipeline's long-standing defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QualityPreset {
+    /// Small frames and shallow buffers for the fastest live transcript
+    LowLatency,
+    /// Default trade-off between transcript latency and robustness
+    #[default]
+    Balanced,
+    /// Deep buffers, windowed-sinc resampling, and float processing and chunks
+    Archival,
+}
+
+impl QualityPreset {
+    pub const ALL: [QualityPreset; 3] = [
+        QualityPreset::LowLatency,
+        QualityPreset::Balanced,
+        QualityPreset::Archival,
+    ];
+
+    /// Name used in requests and config (e.g. "low-latency")
+    pub fn name(self) -> &'static str {
+        match self {
+            QualityPreset::LowLatency => "low-latency",
+            QualityPreset::Balanced => "balanced",
+            QualityPreset::Archival => "archival",
+        }
+    }
+
+    /// One-line summary for capability listings
+    pub fn description(self) -> &'static str {
+        match self {
+            QualityPreset::LowLatency => {
+                "Smallest frames and buffers for the fastest live transcript"
+            }
+            QualityPreset::Balanced => "Default trade-off between latency and robustness",
+            QualityPreset::Archival => {
+                "Deep buffers, 32-tap sinc resampling, and 32-bit float processing and chunks for long-term storage"
+            }
+        }
+    }
+
+    /// Capture buffer and mixed frame duration
+    pub fn frame_duration_ms(self) -> u64 {
+        match self {
+            QualityPreset::LowLatency => 20,
+            QualityPreset::Balanced => 100,
+            QualityPreset::Archival => 200,
+        }
+    }
+
+    /// How far a mixer input may fall behind before it is zero-filled
+    pub fn max_latency_ms(self) -> u64 {
+        match self {
+            QualityPreset::LowLatency => 200,
+            QualityPreset::Balanced => 500,
+            QualityPreset::Archival => 2000,
+        }
+    }
+
+    /// Frames buffered between capture and processing
+    pub fn channel_capacity(self) -> usize {
+        match self {
+            QualityPreset::LowLatency => 50,
+            QualityPreset::Balanced => 100,
+            QualityPreset::Archival => 400,
+        }
+    }
+
+    /// Format audio chunks are written in
+    pub fn chunk_codec(self) -> ChunkCodec {
+        match self {
+            QualityPreset::LowLatency | QualityPreset::Balanced => ChunkCodec::Pcm16,
+            QualityPreset::Archival => ChunkCodec::Float32,
+        }
+    }
+
+    /// Interpolation used when capture and target sample rates differ
+    pub fn resampler_quality(self) -> ResamplerQuality {
+        match self {
+            QualityPreset::LowLatency | QualityPreset::Balanced => ResamplerQuality::Linear,
+            QualityPreset::Archival => ResamplerQuality::Sinc(32),
+        }
+    }
+
+    /// Whether samples stay in 32-bit float between processing stages
+    pub fn float_pipeline(self) -> bool {
+        matches!(self, QualityPreset::Archival)
+    }
+
+    /// Chunk recorder configuration using this preset's codec
+    pub fn chunk_config(self, meeting_id: String, output_dir: PathBuf) -> ChunkConfig {
+        ChunkConfig {
+            codec: self.chunk_codec(),
+            ..ChunkConfig::new(meeting_id, output_dir)
+        }
+    }
+}
+
+impl FromStr for QualityPreset {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown quality preset {} (expected low-latency, balanced, or archival)",
+                    name
+                )
+            })
+    }
+}
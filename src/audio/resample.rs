@@ -0,0 +1,334 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use super::backend::AudioFrame;
+use super::float::{sample_to_f32, FloatFrame};
+
+/// Scale between normalized output values and 16-bit PCM
+const PCM16_SCALE: f64 = 32768.0;
+
+/// Fractional positions tabulated per sinc kernel
+const SINC_PHASES: usize = 256;
+
+/// Interpolation used between input samples
+///
+/// Written as "nearest", "linear", "cubic", or "sinc-N" (N taps) in config
+/// and requests. Cost grows with quality: nearest and linear suit live STT,
+/// windowed sinc suits archival audio.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ResamplerQuality {
+    /// Nearest input sample (cheapest, audible aliasing)
+    Nearest,
+    /// Two-point linear interpolation
+    #[default]
+    Linear,
+    /// Four-point Catmull-Rom interpolation (smoother, less aliasing)
+    Cubic,
+    /// Blackman-windowed sinc with the given (even) number of taps,
+    /// low-pass filtered when downsampling
+    Sinc(u16),
+}
+
+impl ResamplerQuality {
+    /// Fewest and most taps accepted for sinc interpolation
+    pub const SINC_TAPS: std::ops::RangeInclusive<u16> = 4..=256;
+
+    /// Input frames needed after the interpolation point
+    fn lookahead(self) -> usize {
+        match self {
+            ResamplerQuality::Nearest | ResamplerQuality::Linear => 1,
+            ResamplerQuality::Cubic => 2,
+            ResamplerQuality::Sinc(taps) => taps as usize / 2,
+        }
+    }
+
+    /// Input frames needed before the interpolation point
+    fn lookbehind(self) -> usize {
+        match self {
+            ResamplerQuality::Nearest | ResamplerQuality::Linear => 0,
+            ResamplerQuality::Cubic => 1,
+            ResamplerQuality::Sinc(taps) => taps as usize / 2 - 1,
+        }
+    }
+
+    /// Input frames kept from the previous block
+    ///
+    /// Enough that the next block's first output still has `lookbehind`
+    /// frames before it.
+    fn history(self) -> usize {
+        self.lookahead() + self.lookbehind()
+    }
+}
+
+impl fmt::Display for ResamplerQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResamplerQuality::Nearest => write!(f, "nearest"),
+            ResamplerQuality::Linear => write!(f, "linear"),
+            ResamplerQuality::Cubic => write!(f, "cubic"),
+            ResamplerQuality::Sinc(taps) => write!(f, "sinc-{}", taps),
+        }
+    }
+}
+
+impl FromStr for ResamplerQuality {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "nearest" => Ok(ResamplerQuality::Nearest),
+            "linear" => Ok(ResamplerQuality::Linear),
+            "cubic" => Ok(ResamplerQuality::Cubic),
+            _ => {
+                let taps: u16 = name
+                    .strip_prefix("sinc-")
+                    .and_then(|taps| taps.parse().ok())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown resampler quality {} (expected nearest, linear, cubic, or sinc-N)",
+                            name
+                        )
+                    })?;
+                if !Self::SINC_TAPS.contains(&taps) || !taps.is_multiple_of(2) {
+                    anyhow::bail!(
+                        "Sinc taps must be even and between {} and {}",
+                        Self::SINC_TAPS.start(),
+                        Self::SINC_TAPS.end()
+                    );
+                }
+                Ok(ResamplerQuality::Sinc(taps))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for ResamplerQuality {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+impl From<ResamplerQuality> for String {
+    fn from(quality: ResamplerQuality) -> Self {
+        quality.to_string()
+    }
+}
+
+/// Streaming interpolating resampler
+///
+/// Keeps the fractional read position and the last few input frames between
+/// calls, so consecutive frames resample without clicks at frame
+/// boundaries. Works for any rate ratio (up- or downsampling).
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from_rate: u32,
+    to_rate: u32,
+    channels: u16,
+    quality: ResamplerQuality,
+    /// Input frames advanced per output frame
+    step: f64,
+    /// Read position of the next output frame, relative to the start of the
+    /// kept history (positions before `history` frames fall between the
+    /// previous block's samples and the next block's)
+    position: f64,
+    /// Last input frames (interleaved, normalized) from the previous block
+    history: Vec<f32>,
+    /// Sinc weights, `taps` per phase for `SINC_PHASES + 1` phases
+    kernel: Vec<f64>,
+}
+
+impl Resampler {
+    /// Linear-interpolation resampler
+    pub fn new(from_rate: u32, to_rate: u32, channels: u16) -> Self {
+        Self::with_quality(from_rate, to_rate, channels, ResamplerQuality::Linear)
+    }
+
+    pub fn with_quality(
+        from_rate: u32,
+        to_rate: u32,
+        channels: u16,
+        quality: ResamplerQuality,
+    ) -> Self {
+        Self {
+            from_rate,
+            to_rate,
+            channels,
+            quality,
+            step: from_rate as f64 / to_rate as f64,
+            position: quality.history() as f64,
+            history: vec![0.0; quality.history() * channels as usize],
+            kernel: match quality {
+                ResamplerQuality::Sinc(taps) => sinc_kernel(taps as usize, from_rate, to_rate),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    /// Input sample rate this resampler was created for
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    /// Output sample rate
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    /// Interleaved channel count
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn quality(&self) -> ResamplerQuality {
+        self.quality
+    }
+
+    /// Resample a block of interleaved samples
+    pub fn process(&mut self, samples: &[i16]) -> Vec<i16> {
+        let mut output = Vec::with_capacity(self.estimated_output(samples.len()));
+        self.run(samples.iter().map(|&s| sample_to_f32(s)), |value| {
+            let value = (value * PCM16_SCALE).round();
+            output.push(value.clamp(i16::MIN as f64, i16::MAX as f64) as i16)
+        });
+        output
+    }
+
+    /// Resample a block of interleaved float samples (no quantization)
+    ///
+    /// Shares position and history with `process`, so a stream may switch
+    /// between the two without a discontinuity.
+    pub fn process_f32(&mut self, samples: &[f32]) -> Vec<f32> {
+        let mut output = Vec::with_capacity(self.estimated_output(samples.len()));
+        self.run(samples.iter().copied(), |value| output.push(value as f32));
+        output
+    }
+
+    fn estimated_output(&self, samples: usize) -> usize {
+        (samples as f64 / self.step) as usize + self.channels as usize
+    }
+
+    /// Interpolate a block of normalized samples, passing each output to `emit`
+    fn run(&mut self, samples: impl ExactSizeIterator<Item = f32>, mut emit: impl FnMut(f64)) {
+        let channels = self.channels as usize;
+        let input_frames = samples.len() / channels;
+        if input_frames == 0 {
+            return;
+        }
+
+        // Kept history followed by the new block
+        let mut buffer = Vec::with_capacity(self.history.len() + input_frames * channels);
+        buffer.extend_from_slice(&self.history);
+        buffer.extend(samples.take(input_frames * channels));
+        let buffer_frames = buffer.len() / channels;
+        let lookahead = self.quality.lookahead() as f64;
+
+        // Each output frame interpolates around input frames idx and idx + 1
+        while self.position.floor() + lookahead < buffer_frames as f64 {
+            let idx = self.position.floor();
+            let frac = self.position - idx;
+            let idx = idx as usize;
+            let at = |frame: usize, ch: usize| buffer[frame * channels + ch] as f64;
+
+            for ch in 0..channels {
+                let value = match self.quality {
+                    ResamplerQuality::Nearest => at(idx + (frac >= 0.5) as usize, ch),
+                    ResamplerQuality::Linear => {
+                        let (s0, s1) = (at(idx, ch), at(idx + 1, ch));
+                        s0 + (s1 - s0) * frac
+                    }
+                    ResamplerQuality::Cubic => catmull_rom(
+                        at(idx - 1, ch),
+                        at(idx, ch),
+                        at(idx + 1, ch),
+                        at(idx + 2, ch),
+                        frac,
+                    ),
+                    ResamplerQuality::Sinc(taps) => {
+                        let taps = taps as usize;
+                        let phase = (frac * SINC_PHASES as f64).round() as usize;
+                        let weights = &self.kernel[phase * taps..(phase + 1) * taps];
+                        let first = idx + 1 - taps / 2;
+                        weights
+                            .iter()
+                            .enumerate()
+                            .map(|(k, w)| w * at(first + k, ch))
+                            .sum()
+                    }
+                };
+                emit(value);
+            }
+
+            self.position += self.step;
+        }
+
+        self.position -= input_frames as f64;
+        let keep = self.history.len();
+        self.history.copy_from_slice(&buffer[buffer.len() - keep..]);
+    }
+
+    /// Resample a frame to the output rate (channel count is unchanged)
+    pub fn resample_frame(&mut self, frame: AudioFrame) -> AudioFrame {
+        AudioFrame {
+            samples: self.process(&frame.samples),
+            sample_rate: self.to_rate,
+            channels: frame.channels,
+            timestamp_ms: frame.timestamp_ms,
+            source: frame.source,
+        }
+    }
+
+    /// Resample a float frame to the output rate (channel count is unchanged)
+    pub fn resample_float_frame(&mut self, frame: FloatFrame) -> FloatFrame {
+        FloatFrame {
+            samples: self.process_f32(&frame.samples),
+            sample_rate: self.to_rate,
+            ..frame
+        }
+    }
+}
+
+/// Catmull-Rom spline through p1..p2 at `t` (0..1), with p0 and p3 as neighbours
+fn catmull_rom(p0: f64, p1: f64, p2: f64, p3: f64, t: f64) -> f64 {
+    p1 + 0.5
+        * t
+        * (p2 - p0 + t * (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3 + t * (3.0 * (p1 - p2) + p3 - p0)))
+}
+
+/// Blackman-windowed sinc weights for each tabulated fractional position
+///
+/// Tap `k` weighs input frame `idx + 1 - taps / 2 + k`. The cutoff drops to
+/// the output Nyquist frequency when downsampling, and each phase is
+/// normalized to unit gain so constant signals pass unchanged.
+fn sinc_kernel(taps: usize, from_rate: u32, to_rate: u32) -> Vec<f64> {
+    let cutoff = (to_rate as f64 / from_rate as f64).min(1.0);
+    let half = (taps / 2) as f64;
+    let mut kernel = Vec::with_capacity((SINC_PHASES + 1) * taps);
+
+    for phase in 0..=SINC_PHASES {
+        let frac = phase as f64 / SINC_PHASES as f64;
+        let weights: Vec<f64> = (0..taps)
+            .map(|k| {
+                // Distance from the interpolation point, in input frames
+                let x = k as f64 + 1.0 - half - frac;
+                let sinc = if x == 0.0 {
+                    1.0
+                } else {
+                    (PI * cutoff * x).sin() / (PI * cutoff * x)
+                };
+                let n = (x + half) / (2.0 * half);
+                let window = 0.42 - 0.5 * (2.0 * PI * n).cos() + 0.08 * (4.0 * PI * n).cos();
+                sinc * window
+            })
+            .collect();
+
+        let sum: f64 = weights.iter().sum();
+        kernel.extend(weights.iter().map(|w| w / sum));
+    }
+
+    kernel
+}
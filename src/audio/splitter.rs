@@ -0,0 +1,63 @@
+use tracing::warn;
+
+use super::backend::{AudioFrame, AudioStreamSource};
+use super::channels::{ChannelMap, ChannelRole};
+
+/// Pipeline stage that separates multichannel system capture into sources
+///
+/// ScreenCaptureKit delivers system audio and the microphone as one stereo
+/// stream. The splitter turns each such frame into tagged mono frames
+/// (system, then microphone) laid out per the channel map, so later stages
+/// see distinct streams. Frames from other sources, mono frames, and frames
+/// whose layout does not match the map pass through unchanged.
+#[derive(Debug, Clone)]
+pub struct StereoSplitter {
+    channel_map: ChannelMap,
+    /// Whether a layout mismatch has been reported
+    mismatch_warned: bool,
+}
+
+impl StereoSplitter {
+    pub fn new(channel_map: ChannelMap) -> Self {
+        Self {
+            channel_map,
+            mismatch_warned: false,
+        }
+    }
+
+    pub fn channel_map(&self) -> &ChannelMap {
+        &self.channel_map
+    }
+
+    /// Sources split frames are tagged with, in output order
+    pub fn sources(&self) -> Vec<AudioStreamSource> {
+        [ChannelRole::System, ChannelRole::Microphone]
+            .into_iter()
+            .filter(|&role| self.channel_map.channel(role).is_some())
+            .filter_map(ChannelRole::source)
+            .collect()
+    }
+
+    /// Split one captured frame
+    pub fn process(&mut self, frame: AudioFrame) -> Vec<AudioFrame> {
+        if frame.source != AudioStreamSource::System || frame.channels == 1 {
+            return vec![frame];
+        }
+
+        match self.channel_map.split(&frame) {
+            Some(frames) => frames,
+            None => {
+                if !self.mismatch_warned {
+                    warn!(
+                        "System capture has {} channels but the channel map {} describes {}; not splitting sources",
+                        frame.channels,
+                        self.channel_map,
+                        self.channel_map.channels()
+                    );
+                    self.mismatch_warned = true;
+                }
+                vec![frame]
+            }
+        }
+    }
+}
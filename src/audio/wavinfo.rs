@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Start of the INFO comment that labels a file's channels
+const CHANNEL_LABELS_PREFIX: &str = "channels: ";
+
+/// Tags in a WAV file's LIST/INFO chunk, so chunks copied out of the
+/// recordings directory still say what they are
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WavInfo {
+    /// Meeting title (INAM)
+    pub title: Option<String>,
+    /// Meeting the file belongs to (IPRD)
+    pub meeting_id: Option<String>,
+    /// When the file's audio starts, as RFC 3339 (ICRD)
+    pub created: Option<DateTime<Utc>>,
+    /// Chunk number, from 1 (ITRK)
+    pub track: Option<usize>,
+    /// Software that wrote the file, with its version (ISFT)
+    pub software: Option<String>,
+    /// What each channel carries, in order (ICMT, e.g. "channels: system,
+    /// microphone")
+    pub channel_labels: Vec<String>,
+}
+
+impl WavInfo {
+    /// Whether there is no tag to write
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    fn entries(&self) -> Vec<(&'static [u8; 4], String)> {
+        let mut entries = Vec::new();
+        if let Some(title) = &self.title {
+            entries.push((b"INAM", title.clone()));
+        }
+        if let Some(meeting_id) = &self.meeting_id {
+            entries.push((b"IPRD", meeting_id.clone()));
+        }
+        if let Some(created) = self.created {
+            entries.push((
+                b"ICRD",
+                created.to_rfc3339_opts(SecondsFormat::Millis, true),
+            ));
+        }
+        if let Some(track) = self.track {
+            entries.push((b"ITRK", track.to_string()));
+        }
+        if let Some(software) = &self.software {
+            entries.push((b"ISFT", software.clone()));
+        }
+        if !self.channel_labels.is_empty() {
+            entries.push((
+                b"ICMT",
+                format!(
+                    "{}{}",
+                    CHANNEL_LABELS_PREFIX,
+                    self.channel_labels.join(", ")
+                ),
+            ));
+        }
+        entries
+    }
+
+    /// Append the tags to a finished WAV file as a LIST/INFO chunk
+    pub fn append_to(&self, path: &Path) -> Result<()> {
+        let mut info = b"INFO".to_vec();
+        for (id, text) in self.entries() {
+            let mut value = text.into_bytes();
+            value.retain(|&byte| byte != 0);
+            value.push(0);
+            info.extend_from_slice(id);
+            info.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            if value.len() % 2 == 1 {
+                value.push(0);
+            }
+            info.extend_from_slice(&value);
+        }
+
+        let mut list = Vec::with_capacity(8 + info.len());
+        list.extend_from_slice(b"LIST");
+        list.extend_from_slice(&(info.len() as u32).to_le_bytes());
+        list.extend_from_slice(&info);
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let len = file.seek(SeekFrom::End(0))? + list.len() as u64;
+        file.write_all(&list)?;
+
+        // The RIFF size covers everything after its own header
+        file.seek(SeekFrom::Start(4))?;
+        file.write_all(&(len as u32 - 8).to_le_bytes())?;
+        Ok(())
+    }
+
+    /// Tags from a WAV file's LIST/INFO chunk (None = the file has none)
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        let mut file =
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut header = [0u8; 12];
+        file.read_exact(&mut header)?;
+        if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+            anyhow::bail!("{} is not a WAV file", path.display());
+        }
+
+        let mut chunk = [0u8; 8];
+        while file.read_exact(&mut chunk).is_ok() {
+            let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+            let padded = size + size % 2;
+            if &chunk[0..4] != b"LIST" {
+                file.seek(SeekFrom::Current(padded as i64))?;
+                continue;
+            }
+
+            let mut list = vec![0u8; padded as usize];
+            file.read_exact(&mut list)?;
+            if list.len() >= 4 && &list[0..4] == b"INFO" {
+                return Ok(Some(Self::parse(&list[4..])));
+            }
+        }
+        Ok(None)
+    }
+
+    fn parse(mut rest: &[u8]) -> Self {
+        let mut info = Self::default();
+        while rest.len() >= 8 {
+            let size = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+            let value = String::from_utf8_lossy(&rest[8..(8 + size).min(rest.len())])
+                .trim_end_matches('\0')
+                .to_string();
+            match &rest[0..4] {
+                b"INAM" => info.title = Some(value),
+                b"IPRD" => info.meeting_id = Some(value),
+                b"ICRD" => {
+                    info.created = DateTime::parse_from_rfc3339(&value)
+                        .ok()
+                        .map(|created| created.with_timezone(&Utc))
+                }
+                b"ITRK" => info.track = value.parse().ok(),
+                b"ISFT" => info.software = Some(value),
+                b"ICMT" => {
+                    if let Some(labels) = value.strip_prefix(CHANNEL_LABELS_PREFIX) {
+                        info.channel_labels = labels.split(", ").map(str::to_string).collect();
+                    }
+                }
+                _ => {}
+            }
+            rest = &rest[(8 + size + size % 2).min(rest.len())..];
+        }
+        info
+    }
+}
@@ -0,0 +1,306 @@
+use super::chunk::ChunkMetadata;
+use super::index::{MeetingIndex, INDEX_FILE};
+use crate::storage::{create_atomic, partial_target, write_atomic};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// Lock file held while a session writes into a meeting directory
+pub const LOCK_FILE: &str = ".loqa-lock";
+
+/// Manifest naming the instance that wrote a meeting directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Whether a file in a meeting directory is the lock, manifest, or index
+/// (or a write of one cut short) rather than meeting data
+pub fn is_workdir_file(name: &std::ffi::OsStr) -> bool {
+    let Some(name) = name.to_str() else {
+        return false;
+    };
+    let name = partial_target(name).unwrap_or(name);
+    [LOCK_FILE, MANIFEST_FILE, INDEX_FILE].contains(&name)
+}
+
+/// ID of this server instance, stamped into the meeting directories it
+/// writes (new each time the process starts)
+pub fn instance_id() -> &'static str {
+    static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+    INSTANCE_ID.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Who holds a meeting directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceStamp {
+    pub instance_id: String,
+    pub host: String,
+    pub pid: u32,
+    pub since: DateTime<Utc>,
+}
+
+impl InstanceStamp {
+    fn current() -> Self {
+        Self {
+            instance_id: instance_id().to_string(),
+            host: gethostname::gethostname().to_string_lossy().into_owned(),
+            pid: std::process::id(),
+            since: Utc::now(),
+        }
+    }
+
+    /// Whether the holder is a process on this host that no longer runs
+    /// (a crashed instance); holders on other hosts can't be checked
+    fn is_stale(&self) -> bool {
+        let host = gethostname::gethostname().to_string_lossy().into_owned();
+        self.host == host && self.instance_id != instance_id() && !process_alive(self.pid)
+    }
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks that the process exists; EPERM means it exists
+    // but belongs to another user
+    let exists = unsafe { libc::kill(pid, 0) } == 0;
+    exists || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+/// Which instance wrote a meeting directory, and the chunks it wrote
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingManifest {
+    pub meeting_id: String,
+    pub writer: InstanceStamp,
+    pub updated_at: DateTime<Utc>,
+    /// Chunks written so far, in order
+    #[serde(default)]
+    pub chunks: Vec<ManifestChunk>,
+}
+
+/// A chunk file listed in the manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestChunk {
+    /// File name within the meeting directory
+    pub file: String,
+    /// SHA-256 of the file (hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Milliseconds at the start of the file repeated from the previous chunk
+    #[serde(default)]
+    pub overlap_ms: u64,
+}
+
+impl RecordingManifest {
+    /// Read a meeting directory's manifest (None if it has none)
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(MANIFEST_FILE);
+        match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .with_context(|| format!("Invalid manifest {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    fn save(&self, dir: &Path) -> Result<()> {
+        write_atomic(&dir.join(MANIFEST_FILE), serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// A meeting directory is held by another live instance
+#[derive(Debug)]
+pub struct DirInUse {
+    pub dir: PathBuf,
+    pub holder: InstanceStamp,
+}
+
+impl fmt::Display for DirInUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Meeting directory {} is in use by instance {} (pid {} on {}, since {}); \
+             if that instance is gone, delete {}",
+            self.dir.display(),
+            self.holder.instance_id,
+            self.holder.pid,
+            self.holder.host,
+            self.holder.since.to_rfc3339(),
+            self.dir.join(LOCK_FILE).display()
+        )
+    }
+}
+
+impl std::error::Error for DirInUse {}
+
+/// Exclusive claim on a meeting directory for this instance
+///
+/// Held through a lock file created atomically with its stamp, so two
+/// instances can't both acquire it and none sees a half-written lock; a
+/// lock left by a crashed instance on this host is taken over. The
+/// manifest is stamped with this instance's ID, letting the recorder
+/// detect another instance writing into the directory anyway. The lock is
+/// released on drop.
+#[derive(Debug)]
+pub struct MeetingDirLock {
+    dir: PathBuf,
+    meeting_id: String,
+    /// Title shown in the directory's index
+    title: Option<String>,
+    stamp: InstanceStamp,
+}
+
+impl MeetingDirLock {
+    /// Claim `dir` for `meeting_id`, creating it if needed
+    ///
+    /// Fails with `DirInUse` if another live instance holds it.
+    pub fn acquire(dir: &Path, meeting_id: &str) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create meeting directory {}", dir.display()))?;
+
+        let stamp = InstanceStamp::current();
+        let path = dir.join(LOCK_FILE);
+        loop {
+            match create_atomic(&path, serde_json::to_vec(&stamp)?) {
+                Ok(()) => break,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    match serde_json::from_slice::<InstanceStamp>(&fs::read(&path)?) {
+                        Ok(holder) if !holder.is_stale() => {
+                            return Err(DirInUse {
+                                dir: dir.to_path_buf(),
+                                holder,
+                            }
+                            .into());
+                        }
+                        Ok(holder) => warn!(
+                            "Taking over {} from instance {} (pid {} is no longer running)",
+                            dir.display(),
+                            holder.instance_id,
+                            holder.pid
+                        ),
+                        // Locks are created whole, so this one was cut short
+                        // by a crash of an older version
+                        Err(e) => warn!(
+                            "Taking over {} from an unreadable lock file: {}",
+                            dir.display(),
+                            e
+                        ),
+                    }
+                    fs::remove_file(&path)
+                        .with_context(|| format!("Failed to remove {}", path.display()))?;
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to create {}", path.display()))
+                }
+            }
+        }
+
+        let lock = Self {
+            dir: dir.to_path_buf(),
+            meeting_id: meeting_id.to_string(),
+            title: None,
+            stamp,
+        };
+        lock.write_manifest(&[])?;
+        Ok(lock)
+    }
+
+    /// Title the directory's index shows for the meeting
+    pub fn with_title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self.write_index(&[]);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Fail if another instance has stamped the manifest since this lock
+    /// was acquired
+    pub fn verify(&self) -> Result<()> {
+        match RecordingManifest::load(&self.dir)? {
+            Some(manifest) if manifest.writer.instance_id != self.stamp.instance_id => {
+                Err(DirInUse {
+                    dir: self.dir.clone(),
+                    holder: manifest.writer,
+                })
+                .context("Another instance is writing into this meeting directory")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Stamp the manifest with the chunks written so far, unless another
+    /// instance has claimed the directory
+    pub fn update_manifest(&self, chunks: &[ChunkMetadata]) -> Result<()> {
+        self.verify()?;
+        self.write_manifest(chunks)
+    }
+
+    fn write_manifest(&self, chunks: &[ChunkMetadata]) -> Result<()> {
+        self.write_index(chunks);
+        RecordingManifest {
+            meeting_id: self.meeting_id.clone(),
+            writer: self.stamp.clone(),
+            updated_at: Utc::now(),
+            chunks: chunks
+                .iter()
+                .filter_map(|chunk| {
+                    Some(ManifestChunk {
+                        file: chunk.file_path.file_name()?.to_string_lossy().into_owned(),
+                        sha256: chunk.sha256.clone(),
+                        overlap_ms: chunk.overlap_ms,
+                    })
+                })
+                .collect(),
+        }
+        .save(&self.dir)
+    }
+
+    /// Describe the meeting in progress in the directory's index (only
+    /// logged on failure; the index is for people, not the recorder)
+    fn write_index(&self, chunks: &[ChunkMetadata]) {
+        let index = MeetingIndex {
+            title: self.title.clone(),
+            chunks: chunks.to_vec(),
+            ..MeetingIndex::new(&self.meeting_id, self.stamp.since)
+        };
+        if let Err(e) = index.write(&self.dir) {
+            warn!("Failed to update meeting index: {:#}", e);
+        }
+    }
+
+    /// Release the lock and remove the manifest and index, for a directory
+    /// nothing was recorded into
+    pub fn abandon(self) {
+        let _ = fs::remove_file(self.dir.join(MANIFEST_FILE));
+        let _ = fs::remove_file(self.dir.join(INDEX_FILE));
+    }
+}
+
+impl Drop for MeetingDirLock {
+    fn drop(&mut self) {
+        // Leave a lock another instance took over alone
+        let path = self.dir.join(LOCK_FILE);
+        let ours = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<InstanceStamp>(&bytes).ok())
+            .is_some_and(|holder| holder.instance_id == self.stamp.instance_id);
+        if ours {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to release {}: {}", path.display(), e);
+            }
+        }
+    }
+}
@@ -0,0 +1,436 @@
+//! C API over `MeetingsService` (feature "capi")
+//!
+//! Lets desktop shells not written in Rust (e.g. Electron or a Swift app)
+//! record in-process instead of through the HTTP API. Build the shared
+//! library with
+//! `cargo rustc --release --lib --features capi --crate-type cdylib`; the
+//! declarations are in `include/loqa_meetings.h`.
+//!
+//! Calls block until they finish. Results come back as JSON in the same
+//! shapes the HTTP API returns, and errors as `{"error": "..."}`; the caller
+//! frees every returned string with `loqa_meetings_string_free`.
+
+use crate::config::Config;
+use crate::service::{
+    MeetingStatus, MeetingsError, MeetingsService, StartOptions, StartOutcome, StopOutcome,
+};
+use crate::session::{RecordingSession, TranscriptQuery};
+use serde_json::json;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, warn};
+
+/// Result of a C API call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoqaStatus {
+    Ok = 0,
+    /// A null handle, a string that isn't UTF-8, or options that aren't
+    /// valid JSON
+    InvalidArgument = 1,
+    InvalidRequest = 2,
+    NotFound = 3,
+    Conflict = 4,
+    LimitReached = 5,
+    Failed = 6,
+}
+
+impl From<&MeetingsError> for LoqaStatus {
+    fn from(error: &MeetingsError) -> Self {
+        match error {
+            MeetingsError::InvalidRequest(_) => Self::InvalidRequest,
+            MeetingsError::NotFound(_) => Self::NotFound,
+            MeetingsError::Conflict(_) => Self::Conflict,
+            MeetingsError::LimitReached(_) => Self::LimitReached,
+            MeetingsError::Failed(_) => Self::Failed,
+        }
+    }
+}
+
+/// Called with each new transcript segment as JSON (valid only for the call)
+pub type LoqaTranscriptCallback =
+    extern "C" fn(user_data: *mut c_void, segment_json: *const c_char);
+
+/// A meetings service and the runtime its sessions run on
+pub struct LoqaMeetings {
+    runtime: tokio::runtime::Runtime,
+    service: MeetingsService,
+}
+
+/// The callback's user data, handed to the forwarding task
+#[derive(Clone, Copy)]
+struct UserData(*mut c_void);
+
+// The caller promises the pointer may be used from another thread
+unsafe impl Send for UserData {}
+
+/// Create a meetings service from the configuration at `config_path`
+/// (extension resolved by the config loader), or from the defaults if it is
+/// null
+///
+/// Returns null if the configuration or storage can't be loaded.
+///
+/// # Safety
+///
+/// `config_path` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn loqa_meetings_new(config_path: *const c_char) -> *mut LoqaMeetings {
+    let config = if config_path.is_null() {
+        Config::default()
+    } else {
+        let Some(path) = str_arg(config_path) else {
+            return std::ptr::null_mut();
+        };
+        match Config::load(path) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Failed to load {}: {:#}", path, e);
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            error!("Failed to start runtime: {}", e);
+            return std::ptr::null_mut();
+        }
+    };
+    let service = match runtime.block_on(async { MeetingsService::open(config) }) {
+        Ok(service) => service,
+        Err(e) => {
+            error!("Failed to open meeting storage: {:#}", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    Box::into_raw(Box::new(LoqaMeetings { runtime, service }))
+}
+
+/// Free a meetings service, first stopping and saving any meetings still
+/// recording
+///
+/// # Safety
+///
+/// `handle` must be null or come from `loqa_meetings_new`, and not be used
+/// again.
+#[no_mangle]
+pub unsafe extern "C" fn loqa_meetings_free(handle: *mut LoqaMeetings) {
+    if !handle.is_null() {
+        let meetings = Box::from_raw(handle);
+        if let Err(e) = meetings.runtime.block_on(meetings.service.close()) {
+            warn!("{}", e);
+        }
+    }
+}
+
+/// Free a string returned by the C API
+///
+/// # Safety
+///
+/// `string` must be null or a string the C API returned, and not be used
+/// again.
+#[no_mangle]
+pub unsafe extern "C" fn loqa_meetings_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Start recording with `options_json` (the HTTP start request's fields;
+/// null = defaults)
+///
+/// `*out` gets `{"meeting_id", "status": "recording"}`, or
+/// `{"meeting_id", "status": "queued", "position"}` at the session limit
+/// with `queue` set.
+///
+/// # Safety
+///
+/// `handle` must come from `loqa_meetings_new`, `options_json` must be null
+/// or a NUL-terminated string, and `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn loqa_meetings_start(
+    handle: *const LoqaMeetings,
+    options_json: *const c_char,
+    out: *mut *mut c_char,
+) -> LoqaStatus {
+    let Some(meetings) = handle.as_ref() else {
+        return invalid_argument(out, "Null handle");
+    };
+    let options: StartOptions = if options_json.is_null() {
+        StartOptions::default()
+    } else {
+        let Some(json) = str_arg(options_json) else {
+            return invalid_argument(out, "Options are not UTF-8");
+        };
+        match serde_json::from_str(json) {
+            Ok(options) => options,
+            Err(e) => return invalid_argument(out, &format!("Invalid options: {}", e)),
+        }
+    };
+
+    let result = meetings.runtime.block_on(meetings.service.start(options));
+    respond(
+        out,
+        result.map(|outcome| match outcome {
+            StartOutcome::Recording(session) => json!({
+                "meeting_id": session.session_id(),
+                "status": "recording",
+            }),
+            StartOutcome::Queued {
+                meeting_id,
+                position,
+            } => json!({
+                "meeting_id": meeting_id,
+                "status": "queued",
+                "position": position,
+            }),
+        }),
+    )
+}
+
+/// Stop a recording meeting and save it, or cancel a queued one
+///
+/// `*out` gets `{"meeting_id", "status": "stopped", "stats"}` or
+/// `{"meeting_id", "status": "cancelled"}`.
+///
+/// # Safety
+///
+/// `handle` must come from `loqa_meetings_new`, `meeting_id` must be a
+/// NUL-terminated string, and `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn loqa_meetings_stop(
+    handle: *const LoqaMeetings,
+    meeting_id: *const c_char,
+    out: *mut *mut c_char,
+) -> LoqaStatus {
+    let (Some(meetings), Some(meeting_id)) = (handle.as_ref(), str_arg(meeting_id)) else {
+        return invalid_argument(out, "Null handle or meeting ID");
+    };
+
+    let result = meetings.runtime.block_on(meetings.service.stop(meeting_id));
+    respond(
+        out,
+        result.map(|outcome| match outcome {
+            StopOutcome::Stopped(stats) => json!({
+                "meeting_id": meeting_id,
+                "status": "stopped",
+                "stats": stats,
+            }),
+            StopOutcome::Cancelled => json!({
+                "meeting_id": meeting_id,
+                "status": "cancelled",
+            }),
+        }),
+    )
+}
+
+/// Status of a recording or queued meeting
+///
+/// `*out` gets what GET /meetings/:id/status returns.
+///
+/// # Safety
+///
+/// `handle` must come from `loqa_meetings_new`, `meeting_id` must be a
+/// NUL-terminated string, and `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn loqa_meetings_status(
+    handle: *const LoqaMeetings,
+    meeting_id: *const c_char,
+    out: *mut *mut c_char,
+) -> LoqaStatus {
+    let (Some(meetings), Some(meeting_id)) = (handle.as_ref(), str_arg(meeting_id)) else {
+        return invalid_argument(out, "Null handle or meeting ID");
+    };
+
+    let result = meetings
+        .runtime
+        .block_on(meetings.service.status(meeting_id));
+    respond(
+        out,
+        result.map(|status| match status {
+            MeetingStatus::Recording(status) => json!(status),
+            MeetingStatus::Queued(position) => json!({
+                "meeting_id": meeting_id,
+                "status": "queued",
+                "position": position,
+            }),
+        }),
+    )
+}
+
+/// A recording or stored meeting's whole transcript, as a JSON array of
+/// segments
+///
+/// # Safety
+///
+/// `handle` must come from `loqa_meetings_new`, `meeting_id` must be a
+/// NUL-terminated string, and `out` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn loqa_meetings_transcript(
+    handle: *const LoqaMeetings,
+    meeting_id: *const c_char,
+    out: *mut *mut c_char,
+) -> LoqaStatus {
+    let (Some(meetings), Some(meeting_id)) = (handle.as_ref(), str_arg(meeting_id)) else {
+        return invalid_argument(out, "Null handle or meeting ID");
+    };
+
+    let result = meetings.runtime.block_on(meetings.service.transcript(
+        meeting_id,
+        &TranscriptQuery::default(),
+        usize::MAX,
+    ));
+    respond(out, result.map(|transcript| json!(transcript)))
+}
+
+/// Call `callback` with each transcript segment a recording meeting adds
+/// from now on, until it stops
+///
+/// The callback runs on a service thread.
+///
+/// # Safety
+///
+/// `handle` must come from `loqa_meetings_new` and outlive the recording,
+/// `meeting_id` must be a NUL-terminated string, and `user_data` must be
+/// safe to use from another thread until the meeting stops.
+#[no_mangle]
+pub unsafe extern "C" fn loqa_meetings_on_transcript(
+    handle: *const LoqaMeetings,
+    meeting_id: *const c_char,
+    callback: LoqaTranscriptCallback,
+    user_data: *mut c_void,
+) -> LoqaStatus {
+    let (Some(meetings), Some(meeting_id)) = (handle.as_ref(), str_arg(meeting_id)) else {
+        return LoqaStatus::InvalidArgument;
+    };
+
+    let session = meetings.runtime.block_on(async {
+        meetings
+            .service
+            .sessions
+            .read()
+            .await
+            .get(meeting_id)
+            .cloned()
+    });
+    let Some(session) = session else {
+        return LoqaStatus::NotFound;
+    };
+
+    let sequence = meetings
+        .runtime
+        .block_on(session.transcript_page(&TranscriptQuery::default(), 0))
+        .sequence;
+    meetings.runtime.spawn(forward_transcript(
+        session,
+        sequence,
+        callback,
+        UserData(user_data),
+    ));
+    LoqaStatus::Ok
+}
+
+/// Hand segments added after `sequence` to `callback` until the session
+/// stops, including those collected while it stopped
+async fn forward_transcript(
+    session: Arc<RecordingSession>,
+    mut sequence: u64,
+    callback: LoqaTranscriptCallback,
+    user_data: UserData,
+) {
+    let mut transcripts = session.subscribe_transcripts();
+    let mut state = session.watch_state();
+    loop {
+        sequence = deliver_transcript(&session, sequence, callback, user_data).await;
+        if session.stopped_at().is_some() {
+            return;
+        }
+
+        // Woken by each new segment, or by the session stopping
+        tokio::select! {
+            segment = transcripts.recv() => {
+                if let Err(RecvError::Closed) = segment {
+                    break;
+                }
+            }
+            changed = state.changed() => {
+                let recording = matches!(session.get_stats().await, Ok(stats) if stats.is_recording);
+                if changed.is_err() || !recording {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Stopping waits for the transcript task, so what it collected on the
+    // way out is in the transcript by now
+    deliver_transcript(&session, sequence, callback, user_data).await;
+}
+
+/// Hand segments added after `sequence` to `callback`, returning the
+/// sequence to continue from
+async fn deliver_transcript(
+    session: &RecordingSession,
+    sequence: u64,
+    callback: LoqaTranscriptCallback,
+    user_data: UserData,
+) -> u64 {
+    let query = TranscriptQuery {
+        since_seq: Some(sequence),
+        ..Default::default()
+    };
+    let page = session.transcript_page(&query, usize::MAX).await;
+    for segment in page.segments {
+        match CString::new(json!(segment).to_string()) {
+            Ok(json) => callback(user_data.0, json.as_ptr()),
+            Err(e) => warn!("Transcript segment not passed to callback: {}", e),
+        }
+    }
+    page.sequence
+}
+
+/// A string argument, None if it is null or not UTF-8
+unsafe fn str_arg<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+    CStr::from_ptr(string).to_str().ok()
+}
+
+/// Write a result's JSON to `out` and return its status
+unsafe fn respond(
+    out: *mut *mut c_char,
+    result: Result<serde_json::Value, MeetingsError>,
+) -> LoqaStatus {
+    match result {
+        Ok(value) => {
+            write_out(out, &value);
+            LoqaStatus::Ok
+        }
+        Err(e) => {
+            write_out(out, &json!({ "error": e.to_string() }));
+            LoqaStatus::from(&e)
+        }
+    }
+}
+
+unsafe fn invalid_argument(out: *mut *mut c_char, error: &str) -> LoqaStatus {
+    write_out(out, &json!({ "error": error }));
+    LoqaStatus::InvalidArgument
+}
+
+unsafe fn write_out(out: *mut *mut c_char, value: &serde_json::Value) {
+    if out.is_null() {
+        return;
+    }
+    // JSON escapes control characters, so it never contains a NUL
+    *out = CString::new(value.to_string())
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut());
+}
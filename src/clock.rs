@@ -0,0 +1,68 @@
+//! Time source for the wall-clock timestamps sessions and messages carry
+//!
+//! Components take a `SharedClock` rather than calling `Utc::now()`
+//! directly, so tests can pin time with a `ManualClock` and advance it by
+//! hand instead of sleeping.
+
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Source of the current wall-clock time
+pub trait Clock: Send + Sync + fmt::Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A clock shared between a session's tasks
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The system's real time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The system clock, shared
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// A clock that only moves when told to, for tests
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    /// A clock stopped at `start`
+    pub fn new(start: DateTime<Utc>) -> Arc<Self> {
+        Arc::new(Self {
+            now: Mutex::new(start),
+        })
+    }
+
+    /// Move the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        let by = chrono::Duration::from_std(by).unwrap_or(chrono::Duration::MAX);
+        let mut now = self.now.lock().unwrap();
+        *now = now
+            .checked_add_signed(by)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+    }
+
+    /// Set the clock to `at`, which may be in its past
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.now.lock().unwrap() = at;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
@@ -1,36 +1,481 @@
+use crate::audio::{
+    ChannelMap, ChunkRotation, DownmixStrategy, FormatMismatch, LoudnessConfig,
+    NetworkSourceConfig, PlaybackConfig, QualityPreset, StageConfig,
+};
+use crate::export::NoteFormat;
+use crate::nats::{AudioCodec, TranscriptStreamConfig};
+use crate::session::{
+    MicrophoneConfig, NormalizationConfig, StandbyConfig, TranscriptFilterConfig,
+    TranslationConfig, UtteranceConfig, WatchdogConfig, WindowConfig,
+};
+use crate::stt::{SttConfig, SttProviderKind};
 use anyhow::Result;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct Config {
     pub service: ServiceConfig,
     pub audio: AudioConfig,
     pub obsidian: ObsidianConfig,
+    /// Where finished meetings are persisted
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// Automatic chapters for finished meetings
+    #[serde(default)]
+    pub chapters: ChapterConfig,
+    /// Limits on concurrent recording sessions
+    #[serde(default)]
+    pub sessions: SessionLimitsConfig,
+    /// What sessions report about the machine recording them
+    #[serde(default)]
+    pub host: HostConfig,
+    /// Transcription of uploaded recordings
+    #[serde(default)]
+    pub uploads: UploadConfig,
+    /// Recordings dropped into an inbox directory, transcribed automatically
+    #[serde(default)]
+    pub ingest: IngestConfig,
+    /// How sessions receive transcripts from the STT service
+    #[serde(default)]
+    pub transcripts: TranscriptStreamConfig,
+    /// STT provider sessions use by default, and cloud provider settings
+    #[serde(default)]
+    pub stt: SttConfig,
+    /// Spoken numbers, dates, and currencies written as digits in final
+    /// transcript segments
+    #[serde(default)]
+    pub normalization: NormalizationConfig,
+    /// Translation of final transcript segments while recording
+    #[serde(default)]
+    pub translation: TranslationConfig,
+    /// Dropping of likely hallucinated final segments from live sessions
+    /// and uploads
+    #[serde(default)]
+    pub transcript_filter: TranscriptFilterConfig,
+    /// Playing live sessions' processed microphone audio back out, e.g.
+    /// as the microphone of a conferencing app
+    #[serde(default)]
+    pub playback: PlaybackConfig,
+    /// Rolling in-memory buffer of system audio between meetings, prepended
+    /// to the next session started
+    #[serde(default)]
+    pub standby: StandbyConfig,
+    /// Shell commands run as meetings start, stop, and are exported
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Named session profiles (selected per start request; "default" applies otherwise)
+    #[serde(default)]
+    pub profiles: HashMap<String, SessionProfile>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ServiceConfig {
     pub name: String,
     pub http: HttpConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct HttpConfig {
     pub bind: String,
     pub port: u16,
+    /// Compression of response bodies
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// Advertisement of the API on the local network
+    #[serde(default)]
+    pub advertise: AdvertiseConfig,
 }
 
-#[derive(Debug, Deserialize)]
+/// mDNS (Bonjour) advertisement of the API as `_loqa-meetings._tcp`, so
+/// companion apps on the LAN find the server without being configured
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AdvertiseConfig {
+    pub enabled: bool,
+    /// Name the server is listed under (unset = the host name)
+    pub instance_name: Option<String>,
+}
+
+/// Response compression, negotiated with the client's `Accept-Encoding`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CompressionConfig {
+    pub enabled: bool,
+    pub gzip: bool,
+    pub deflate: bool,
+    /// Smaller responses are sent uncompressed
+    pub min_size_bytes: u16,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            gzip: true,
+            deflate: true,
+            min_size_bytes: 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct AudioConfig {
     pub recordings_path: String,
     pub sample_rate: u32,
     pub channels: u16,
+    /// Role of each channel in multichannel system capture
+    /// (default: system left, microphone right)
+    #[serde(default)]
+    pub channel_map: ChannelMap,
+    /// How multichannel capture is folded to mono (default: sum)
+    #[serde(default)]
+    pub downmix: DownmixStrategy,
+    /// Processing captured frames go through, in order, for sessions and
+    /// standby (default: downmix, then resample)
+    #[serde(default = "StageConfig::conversion")]
+    pub stages: Vec<StageConfig>,
+    /// What the mixer does with sources in another sample rate or channel
+    /// count: coerce, drop, or fail (default: coerce)
+    #[serde(default)]
+    pub format_mismatch: FormatMismatch,
+    /// Codec for audio published to the STT service (default: PCM)
+    #[serde(default)]
+    pub codec: AudioCodec,
+    /// Most capture frames per published audio message (0 or 1 = no batching)
+    #[serde(default)]
+    pub batch_frames: u32,
+    /// When published audio is marked as the end of an utterance
+    #[serde(default)]
+    pub utterance: UtteranceConfig,
+    /// Where audio chunks are split (default: exactly at the chunk duration)
+    #[serde(default)]
+    pub chunk_rotation: ChunkRotation,
+    /// Seconds at the end of each audio chunk repeated at the start of the
+    /// next, for transcribing chunks on their own (0 = no overlap)
+    #[serde(default)]
+    pub chunk_overlap_secs: u64,
+    /// Milliseconds start waits for the first captured frame before failing
+    /// (0 = return as soon as capture starts)
+    #[serde(default)]
+    pub warmup_ms: u64,
+    /// Warning about (or restarting) capture that stops delivering frames
+    /// mid-session (default: warn after 10 seconds)
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+    /// Seconds of recent audio kept in memory for bookmarks to save as
+    /// highlight clips (0 = bookmarks add a marker only)
+    #[serde(default = "default_preroll_secs")]
+    pub preroll_secs: u64,
+    /// Loudness normalization of full-meeting audio exports
+    #[serde(default)]
+    pub loudness: LoudnessConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ObsidianConfig {
     pub vault_path: String,
     pub meetings_folder: String,
+    /// Default note formatting (profiles may override)
+    #[serde(default)]
+    pub note_format: NoteFormat,
+    /// Jinja2-style note template (unset = built-in layout)
+    #[serde(default)]
+    pub template_path: Option<String>,
+    /// Whether meetings get their own note or a section in the daily note
+    #[serde(default)]
+    pub export_mode: NoteExportMode,
+    /// Daily note location (used in `daily_note` mode)
+    #[serde(default)]
+    pub daily_notes: DailyNotesConfig,
+    /// What to do once a note has been written
+    #[serde(default)]
+    pub after_export: AfterExportConfig,
+    /// Refresh the meeting's note every N seconds while recording (0 = off)
+    #[serde(default)]
+    pub live_draft_interval_secs: u64,
+}
+
+/// Where exported meeting notes are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteExportMode {
+    /// One note per meeting in the meetings folder
+    #[default]
+    PerMeeting,
+    /// A section appended to the day's daily note
+    DailyNote,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DailyNotesConfig {
+    /// Folder inside the vault (empty = vault root)
+    pub folder: String,
+    /// strftime format for daily note file names (without `.md`)
+    pub file_name_format: String,
+    /// Template for new daily notes; `{{date}}` and `{{title}}` are substituted
+    pub template_path: Option<String>,
+}
+
+/// Actions run after a note is written to the vault
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AfterExportConfig {
+    /// Open the note in Obsidian via its `obsidian://open` URI
+    pub open_in_obsidian: bool,
+    /// URL that receives a JSON POST with the meeting ID and note path
+    pub callback_url: Option<String>,
+}
+
+/// Shell commands run at points in a meeting's life, with the meeting
+/// described in `LOQA_*` environment variables (see [`crate::hooks`])
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Run once a meeting is recording
+    pub on_start: Vec<String>,
+    /// Run once a stopped meeting is saved
+    pub on_stop: Vec<String>,
+    /// Run after a meeting's note is written to the vault
+    pub on_export: Vec<String>,
+    /// Seconds each command may run before it is killed
+    pub timeout_secs: u64,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_start: Vec::new(),
+            on_stop: Vec::new(),
+            on_export: Vec::new(),
+            timeout_secs: 30,
+        }
+    }
+}
+
+/// Chapter detection run on the final transcript of each meeting
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ChapterConfig {
+    pub enabled: bool,
+    /// Shortest chapter that will be split off
+    pub min_chapter_secs: u64,
+    /// A pause at least this long starts a new chapter
+    pub gap_secs: u64,
+    /// Segments compared on each side of a candidate boundary
+    pub window_segments: usize,
+    /// Vocabulary overlap (cosine, 0-1) below which the topic has shifted
+    pub cohesion_threshold: f64,
+    /// External chapter model; replaces the built-in heuristics when set
+    pub detector_url: Option<String>,
+}
+
+/// Limits on how many sessions record at once
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SessionLimitsConfig {
+    /// Most sessions recording at once (0 = unlimited)
+    pub max_concurrent: usize,
+    /// Queue start requests over the limit until a session stops, instead
+    /// of rejecting them (start requests may override)
+    pub queue: bool,
+    /// Times a start that fails during setup (e.g. capture not ready) is
+    /// retried before the request fails (0 = never)
+    pub start_retries: u32,
+    /// Delay before the first start retry, doubled for each one after it
+    pub start_retry_backoff_ms: u64,
+}
+
+/// Host metadata reported with sessions
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HostConfig {
+    /// Report host name, version, platform, and capture backend
+    pub report: bool,
+    /// Name to report instead of the OS host name
+    pub name: Option<String>,
+}
+
+/// Transcription of uploaded recordings
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct UploadConfig {
+    /// Transcribe uploads as overlapping windows published concurrently
+    /// (upload requests may override)
+    pub parallel: bool,
+    /// Window size, overlap, and concurrency for parallel transcription
+    pub windows: WindowConfig,
+}
+
+/// Automatic ingest of recordings dropped into an inbox directory
+///
+/// Each file is transcribed like an upload, as a meeting titled from its
+/// file name, and exported to the vault.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct IngestConfig {
+    /// Directory to watch (None = ingest disabled)
+    pub inbox: Option<String>,
+    /// How often the inbox is checked, in seconds
+    pub poll_interval_secs: u64,
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            inbox: None,
+            poll_interval_secs: 5,
+        }
+    }
+}
+
+impl IngestConfig {
+    /// Inbox directory with `~` expanded
+    pub fn resolved_inbox(&self) -> Option<PathBuf> {
+        self.inbox
+            .as_ref()
+            .map(|inbox| PathBuf::from(shellexpand::tilde(inbox).into_owned()))
+    }
+}
+
+/// Meeting persistence configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct StorageConfig {
+    /// Storage backend
+    #[serde(default)]
+    pub backend: StorageBackend,
+    /// Storage directory (SQLite keeps `meetings.db` here)
+    #[serde(default = "default_storage_path")]
+    pub path: String,
+    /// How often expired meetings are deleted, in seconds (0 = never)
+    #[serde(default = "default_retention_interval_secs")]
+    pub retention_interval_secs: u64,
+}
+
+/// Available storage backends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// One JSON document per meeting
+    #[default]
+    Filesystem,
+    /// Embedded SQLite database
+    Sqlite,
+}
+
+impl AudioConfig {
+    /// Recordings directory with `~` expanded
+    pub fn resolved_recordings_path(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.recordings_path).into_owned())
+    }
+}
+
+impl ObsidianConfig {
+    /// Vault directory with `~` expanded
+    pub fn resolved_vault_path(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.vault_path).into_owned())
+    }
+
+    /// Note template path with `~` expanded
+    pub fn resolved_template_path(&self) -> Option<PathBuf> {
+        self.template_path
+            .as_ref()
+            .map(|path| PathBuf::from(shellexpand::tilde(path).into_owned()))
+    }
+}
+
+impl DailyNotesConfig {
+    /// Daily note template path with `~` expanded
+    pub fn resolved_template_path(&self) -> Option<PathBuf> {
+        self.template_path
+            .as_ref()
+            .map(|path| PathBuf::from(shellexpand::tilde(path).into_owned()))
+    }
+}
+
+impl StorageConfig {
+    /// Storage directory with `~` expanded
+    pub fn resolved_path(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.path).into_owned())
+    }
+}
+
+fn default_storage_path() -> String {
+    "~/.loqa/meetings".to_string()
+}
+
+fn default_retention_interval_secs() -> u64 {
+    300
+}
+
+fn default_preroll_secs() -> u64 {
+    30
+}
+
+/// Session defaults applied when a profile is selected
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SessionProfile {
+    /// Bundle IDs of applications excluded from system audio capture
+    #[serde(default)]
+    pub excluded_apps: Vec<String>,
+
+    /// Pinned microphone device ID (unset = follow the system default input)
+    #[serde(default)]
+    pub microphone_device: Option<String>,
+
+    /// Additional microphones to capture and mix, with per-device gains
+    #[serde(default)]
+    pub microphones: Vec<MicrophoneConfig>,
+
+    /// Receive audio streamed from another device (e.g. a conference-room
+    /// Raspberry Pi) instead of capturing system audio
+    #[serde(default)]
+    pub network_source: Option<NetworkSourceConfig>,
+
+    /// Mixing gain for system audio (unset = 1.0)
+    #[serde(default)]
+    pub system_gain: Option<f32>,
+
+    /// Mixing gain for the system capture's microphone channel (unset = 1.0)
+    #[serde(default)]
+    pub microphone_gain: Option<f32>,
+
+    /// Note formatting for meetings recorded with this profile
+    #[serde(default)]
+    pub note_format: Option<NoteFormat>,
+
+    /// Transcribe system and microphone audio separately, attributing
+    /// segments to "Them" and "Me"
+    #[serde(default)]
+    pub dual_stream: bool,
+
+    /// Store chunks with system and microphone audio in separate, labelled
+    /// channels instead of the mix
+    #[serde(default)]
+    pub stereo_chunks: bool,
+
+    /// Transcript-only sessions: audio is streamed to STT but never stored
+    #[serde(default)]
+    pub privacy: bool,
+
+    /// Transcribe sessions; false records audio only, without STT
+    /// (unset = true)
+    #[serde(default)]
+    pub transcription: Option<bool>,
+
+    /// STT provider for sessions (unset = the `stt.provider` default)
+    #[serde(default)]
+    pub stt: Option<SttProviderKind>,
+
+    /// Delete meetings this many seconds after recording stops (unset = keep)
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+
+    /// Recording quality preset (unset = balanced)
+    #[serde(default)]
+    pub quality: Option<QualityPreset>,
 }
 
 impl Config {
@@ -41,4 +486,144 @@ impl Config {
 
         Ok(settings.try_deserialize()?)
     }
+
+    /// Look up a profile by name, falling back to the "default" profile
+    ///
+    /// Returns `None` only if a name was given and no such profile exists.
+    pub fn profile(&self, name: Option<&str>) -> Option<SessionProfile> {
+        match name {
+            Some(name) => self.profiles.get(name).cloned(),
+            None => Some(self.profiles.get("default").cloned().unwrap_or_default()),
+        }
+    }
+
+    /// Note format for a meeting recorded with `profile`
+    ///
+    /// Uses the profile's format if it sets one, otherwise the Obsidian default.
+    pub fn note_format(&self, profile: Option<&str>) -> NoteFormat {
+        self.profile(profile)
+            .and_then(|p| p.note_format)
+            .unwrap_or_else(|| self.obsidian.note_format.clone())
+    }
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            name: "loqa-meetings".to_string(),
+            http: HttpConfig::default(),
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            bind: "0.0.0.0".to_string(),
+            port: 8081,
+            compression: CompressionConfig::default(),
+            advertise: AdvertiseConfig::default(),
+        }
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            recordings_path: "~/.loqa/recordings".to_string(),
+            sample_rate: 16000,
+            channels: 1,
+            channel_map: ChannelMap::default(),
+            downmix: DownmixStrategy::default(),
+            stages: StageConfig::conversion(),
+            format_mismatch: FormatMismatch::default(),
+            codec: AudioCodec::default(),
+            batch_frames: 1,
+            utterance: UtteranceConfig::default(),
+            chunk_rotation: ChunkRotation::default(),
+            chunk_overlap_secs: 0,
+            warmup_ms: 0,
+            watchdog: WatchdogConfig::default(),
+            preroll_secs: default_preroll_secs(),
+            loudness: LoudnessConfig::default(),
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::default(),
+            path: default_storage_path(),
+            retention_interval_secs: default_retention_interval_secs(),
+        }
+    }
+}
+
+impl Default for ObsidianConfig {
+    fn default() -> Self {
+        Self {
+            vault_path: "~/Documents/Obsidian/LoqaVault".to_string(),
+            meetings_folder: "Meetings".to_string(),
+            note_format: NoteFormat::default(),
+            template_path: None,
+            export_mode: NoteExportMode::default(),
+            daily_notes: DailyNotesConfig::default(),
+            after_export: AfterExportConfig::default(),
+            live_draft_interval_secs: 0,
+        }
+    }
+}
+
+impl Default for ChapterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_chapter_secs: 180,
+            gap_secs: 60,
+            window_segments: 8,
+            cohesion_threshold: 0.1,
+            detector_url: None,
+        }
+    }
+}
+
+impl Default for SessionLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: default_max_concurrent_sessions(),
+            queue: false,
+            start_retries: 0,
+            start_retry_backoff_ms: 500,
+        }
+    }
+}
+
+impl Default for HostConfig {
+    fn default() -> Self {
+        Self {
+            report: true,
+            name: None,
+        }
+    }
+}
+
+/// ScreenCaptureKit capture runs through one process-wide bridge, so macOS
+/// can record a single session at a time
+fn default_max_concurrent_sessions() -> usize {
+    if cfg!(target_os = "macos") {
+        1
+    } else {
+        0
+    }
+}
+
+impl Default for DailyNotesConfig {
+    fn default() -> Self {
+        Self {
+            folder: String::new(),
+            file_name_format: "%Y-%m-%d".to_string(),
+            template_path: None,
+        }
+    }
 }
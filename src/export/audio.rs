@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use std::io::Cursor;
+use std::time::Duration;
+
+use crate::audio::{normalize_loudness, AudioFile, LoudnessConfig, LoudnessReport, Resampler};
+use crate::storage::MeetingRecord;
+
+/// A stored meeting's audio chunks joined into one track
+#[derive(Debug, Clone)]
+pub struct MeetingAudio {
+    /// Interleaved samples
+    pub samples: Vec<i16>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl MeetingAudio {
+    /// Decode and join a meeting's chunks in order, in the first chunk's
+    /// format (later chunks in another format are converted)
+    ///
+    /// Audio a chunk repeats from the previous one is left out.
+    pub fn load(record: &MeetingRecord) -> Result<Self> {
+        let mut chunks: Vec<_> = record.chunks.iter().collect();
+        chunks.sort_by_key(|chunk| chunk.start_ms);
+
+        let mut joined: Option<Self> = None;
+        for chunk in chunks {
+            let mut audio = AudioFile::open_track(&chunk.file_path, chunk.track.unwrap_or(0))
+                .with_context(|| format!("Failed to read audio chunk {}", chunk.chunk_index))?;
+            let overlap = chunk.overlap_samples().min(audio.samples.len());
+            audio.samples.drain(..overlap);
+            match joined.as_mut() {
+                None => {
+                    joined = Some(Self {
+                        sample_rate: audio.sample_rate,
+                        channels: audio.channels,
+                        samples: audio.samples,
+                    })
+                }
+                Some(joined) => {
+                    let samples = joined.convert(&audio);
+                    joined.samples.extend(samples);
+                }
+            }
+        }
+
+        joined.with_context(|| format!("Meeting {} has no stored audio", record.meeting_id))
+    }
+
+    /// A decoded chunk's samples in this track's format
+    fn convert(&self, audio: &AudioFile) -> Vec<i16> {
+        let samples = if audio.channels == self.channels {
+            audio.samples.clone()
+        } else {
+            // Mismatched layouts go through mono
+            audio
+                .to_mono()
+                .into_iter()
+                .flat_map(|sample| std::iter::repeat_n(sample, self.channels as usize))
+                .collect()
+        };
+
+        if audio.sample_rate == self.sample_rate {
+            samples
+        } else {
+            Resampler::new(audio.sample_rate, self.sample_rate, self.channels).process(&samples)
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(
+            self.samples.len() as f64 / (self.sample_rate as f64 * self.channels.max(1) as f64),
+        )
+    }
+
+    /// Bring the track to the configured loudness
+    pub fn normalize(&mut self, config: &LoudnessConfig) -> LoudnessReport {
+        normalize_loudness(&mut self.samples, self.sample_rate, self.channels, config)
+    }
+
+    /// The track as a 16-bit PCM WAV file
+    pub fn to_wav(&self) -> Result<Vec<u8>> {
+        let spec = hound::WavSpec {
+            channels: self.channels,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = Cursor::new(Vec::with_capacity(44 + self.samples.len() * 2));
+        let mut writer = hound::WavWriter::new(&mut wav, spec)?;
+        for &sample in &self.samples {
+            writer.write_sample(sample)?;
+        }
+        writer.finalize().context("Failed to finish WAV")?;
+        Ok(wav.into_inner())
+    }
+}
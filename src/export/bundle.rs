@@ -0,0 +1,267 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, Write};
+use std::path::Path;
+use tracing::warn;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::audio::ChunkMetadata;
+use crate::storage::MeetingRecord;
+
+/// Bundle layout version written to the manifest
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Name of the manifest inside a bundle
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Full meeting document inside a bundle
+pub const MEETING_FILE: &str = "meeting.json";
+
+/// Transcript segments inside a bundle
+pub const TRANSCRIPT_FILE: &str = "transcript.json";
+
+/// Obsidian note inside a bundle
+pub const NOTE_FILE: &str = "note.md";
+
+/// Directory holding audio chunks inside a bundle
+pub const AUDIO_DIR: &str = "audio";
+
+/// What a bundled file contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleFileKind {
+    Meeting,
+    Transcript,
+    Note,
+    Audio,
+}
+
+/// A file listed in the bundle manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path inside the archive
+    pub path: String,
+    pub kind: BundleFileKind,
+    /// Uncompressed size
+    pub size_bytes: u64,
+}
+
+/// Describes the contents of a meeting bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub meeting_id: String,
+    pub title: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// When the bundle was created
+    pub created_at: DateTime<Utc>,
+    pub files: Vec<BundleFile>,
+}
+
+/// Progress while writing a bundle
+#[derive(Debug, Clone)]
+pub struct BundleProgress {
+    /// Archive path of the file just written
+    pub file: String,
+    pub files_done: usize,
+    pub files_total: usize,
+}
+
+/// Write a zip bundle for a stored meeting, including its rendered `note`,
+/// its manifest stamped as created at `created_at`
+///
+/// The writer does not need to be seekable, so the archive can be streamed
+/// directly to a client. Audio chunks missing from disk are skipped with a
+/// warning. The manifest is written last, once every file is known.
+pub fn write_bundle<W: Write>(
+    record: &MeetingRecord,
+    note: &str,
+    created_at: DateTime<Utc>,
+    writer: W,
+    mut on_progress: impl FnMut(BundleProgress),
+) -> Result<BundleManifest> {
+    let mut zip = ZipWriter::new_stream(writer);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // WAV data barely compresses; store it to save CPU
+    let audio_options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    let files_total = 3 + record.chunks.len();
+    let mut files = Vec::with_capacity(files_total);
+
+    let mut progress = |files: &Vec<BundleFile>| {
+        if let Some(file) = files.last() {
+            on_progress(BundleProgress {
+                file: file.path.clone(),
+                files_done: files.len(),
+                files_total,
+            });
+        }
+    };
+
+    // Documents
+    let documents = [
+        (
+            MEETING_FILE,
+            BundleFileKind::Meeting,
+            serde_json::to_vec_pretty(record)?,
+        ),
+        (
+            TRANSCRIPT_FILE,
+            BundleFileKind::Transcript,
+            serde_json::to_vec_pretty(&record.transcript)?,
+        ),
+        (NOTE_FILE, BundleFileKind::Note, note.as_bytes().to_vec()),
+    ];
+
+    for (path, kind, data) in documents {
+        zip.start_file(path, options)?;
+        zip.write_all(&data)?;
+        files.push(BundleFile {
+            path: path.to_string(),
+            kind,
+            size_bytes: data.len() as u64,
+        });
+        progress(&files);
+    }
+
+    // Audio chunks
+    for chunk in &record.chunks {
+        let Some(name) = chunk.file_path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let path = format!("{}/{}", AUDIO_DIR, name);
+
+        let mut file = match File::open(&chunk.file_path) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!(
+                    "Skipping audio chunk {} in bundle: {}",
+                    chunk.file_path.display(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        zip.start_file(path.as_str(), audio_options)?;
+        let size_bytes = io::copy(&mut file, &mut zip)
+            .with_context(|| format!("Failed to bundle {}", chunk.file_path.display()))?;
+
+        files.push(BundleFile {
+            path,
+            kind: BundleFileKind::Audio,
+            size_bytes,
+        });
+        progress(&files);
+    }
+
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        meeting_id: record.meeting_id.clone(),
+        title: record.title.clone(),
+        started_at: record.started_at,
+        ended_at: record.ended_at,
+        created_at,
+        files,
+    };
+
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+
+    let mut writer = zip.finish().context("Failed to finish bundle")?;
+    writer.flush()?;
+
+    Ok(manifest)
+}
+
+/// An opened bundle whose manifest and meeting document have been validated
+pub struct BundleReader<R: Read + Seek> {
+    archive: ZipArchive<R>,
+    /// Bundle manifest
+    pub manifest: BundleManifest,
+    /// Meeting document (chunk paths still refer to the exporting machine)
+    pub record: MeetingRecord,
+}
+
+impl<R: Read + Seek> BundleReader<R> {
+    /// Open a bundle and read its manifest and meeting document
+    pub fn open(reader: R) -> Result<Self> {
+        let mut archive = ZipArchive::new(reader).context("Not a valid bundle archive")?;
+
+        let manifest: BundleManifest = read_json(&mut archive, MANIFEST_FILE)?;
+        if manifest.format_version > BUNDLE_FORMAT_VERSION {
+            anyhow::bail!(
+                "Bundle format version {} is newer than supported version {}",
+                manifest.format_version,
+                BUNDLE_FORMAT_VERSION
+            );
+        }
+
+        let record: MeetingRecord = read_json(&mut archive, MEETING_FILE)?;
+        if record.meeting_id != manifest.meeting_id {
+            anyhow::bail!(
+                "Bundle manifest is for {} but contains meeting {}",
+                manifest.meeting_id,
+                record.meeting_id
+            );
+        }
+
+        Ok(Self {
+            archive,
+            manifest,
+            record,
+        })
+    }
+
+    /// Extract audio chunks into `audio_dir` and return the restored record
+    ///
+    /// Chunk paths are rewritten to the extracted files; chunks that were not
+    /// bundled are dropped from the record.
+    pub fn extract(mut self, audio_dir: &Path) -> Result<MeetingRecord> {
+        fs::create_dir_all(audio_dir)
+            .with_context(|| format!("Failed to create {}", audio_dir.display()))?;
+
+        let mut chunks = Vec::new();
+        for chunk in std::mem::take(&mut self.record.chunks) {
+            let Some(name) = chunk.file_path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let archive_path = format!("{}/{}", AUDIO_DIR, name);
+            if !self.manifest.files.iter().any(|f| f.path == archive_path) {
+                warn!("Bundle has no audio for chunk {}", chunk.chunk_index);
+                continue;
+            }
+
+            let target = audio_dir.join(name);
+            let mut entry = self
+                .archive
+                .by_name(&archive_path)
+                .with_context(|| format!("Bundle is missing {}", archive_path))?;
+            let mut file = File::create(&target)
+                .with_context(|| format!("Failed to create {}", target.display()))?;
+            io::copy(&mut entry, &mut file)?;
+
+            chunks.push(ChunkMetadata {
+                file_path: target,
+                ..chunk
+            });
+        }
+
+        self.record.chunks = chunks;
+        Ok(self.record)
+    }
+}
+
+fn read_json<R: Read + Seek, T: serde::de::DeserializeOwned>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<T> {
+    let entry = archive
+        .by_name(name)
+        .with_context(|| format!("Bundle is missing {}", name))?;
+    serde_json::from_reader(entry).with_context(|| format!("Failed to parse {}", name))
+}
@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::AfterExportConfig;
+
+/// How long to wait for the export callback to respond
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Body POSTed to the configured callback URL after a note is written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteExportedCallback {
+    pub meeting_id: String,
+    /// Absolute path of the written note
+    pub note_path: String,
+    /// URI that opens the note in Obsidian
+    pub obsidian_uri: String,
+}
+
+/// `obsidian://open` URI for a note, addressed by absolute path
+pub fn obsidian_uri(note_path: &Path) -> String {
+    format!(
+        "obsidian://open?path={}",
+        percent_encode(&note_path.to_string_lossy())
+    )
+}
+
+/// Run the configured after-export actions for a freshly written note
+///
+/// Failures are logged rather than returned; the note itself was written
+/// successfully and the export should not fail because Obsidian or the
+/// callback endpoint is unavailable.
+pub async fn after_note_exported(config: &AfterExportConfig, meeting_id: &str, note_path: &Path) {
+    let uri = obsidian_uri(note_path);
+
+    if config.open_in_obsidian {
+        match open_uri(&uri) {
+            Ok(()) => info!("Opened note for {} in Obsidian", meeting_id),
+            Err(e) => warn!(
+                "Failed to open note for {} in Obsidian: {:#}",
+                meeting_id, e
+            ),
+        }
+    }
+
+    if let Some(url) = &config.callback_url {
+        let payload = NoteExportedCallback {
+            meeting_id: meeting_id.to_string(),
+            note_path: note_path.display().to_string(),
+            obsidian_uri: uri,
+        };
+        if let Err(e) = post_callback(url, &payload).await {
+            warn!("Export callback for {} failed: {:#}", meeting_id, e);
+        }
+    }
+}
+
+/// POST the export notification to `url`
+pub async fn post_callback(url: &str, payload: &NoteExportedCallback) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(CALLBACK_TIMEOUT)
+        .build()?;
+
+    client
+        .post(url)
+        .json(payload)
+        .send()
+        .await
+        .with_context(|| format!("Failed to call {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Callback {} returned an error", url))?;
+
+    Ok(())
+}
+
+/// Hand a URI to the desktop's default handler
+fn open_uri(uri: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    let mut child = command
+        .arg(uri)
+        .spawn()
+        .with_context(|| format!("Failed to launch handler for {}", uri))?;
+
+    // Reap in the background; the handler may not exit until Obsidian does
+    std::thread::spawn(move || child.wait());
+
+    Ok(())
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
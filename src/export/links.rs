@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Wikilink and tag rules applied to exported transcripts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoteLinks {
+    /// Phrases turned into wikilinks, mapped to the note they link to
+    /// (e.g. "alice" -> "People/Alice Smith")
+    pub wikilinks: HashMap<String, String>,
+
+    /// Phrases that tag the note, mapped to the tag without `#`
+    /// (e.g. "roadmap" -> "project/roadmap")
+    pub tags: HashMap<String, String>,
+
+    /// Render speaker labels as wikilinks to the speaker's note
+    pub link_speakers: bool,
+}
+
+/// A phrase to look for and what it turns into
+enum Rule<'a> {
+    Link(&'a str),
+    Tag(&'a str),
+}
+
+/// Applies `NoteLinks` to transcript text
+///
+/// Phrases match case-insensitively (ASCII) on word boundaries; when
+/// phrases overlap the longest one wins.
+pub struct Linker<'a> {
+    rules: Vec<(&'a str, Rule<'a>)>,
+    link_speakers: bool,
+}
+
+/// Transcript text with links inserted, and the tags it mentions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkedText {
+    pub text: String,
+    pub tags: Vec<String>,
+}
+
+impl<'a> Linker<'a> {
+    pub fn new(links: &'a NoteLinks) -> Self {
+        let mut rules: Vec<(&str, Rule)> = links
+            .wikilinks
+            .iter()
+            .map(|(phrase, note)| (phrase.as_str(), Rule::Link(note.as_str())))
+            .chain(
+                links
+                    .tags
+                    .iter()
+                    .map(|(phrase, tag)| (phrase.as_str(), Rule::Tag(tag.as_str()))),
+            )
+            .filter(|(phrase, _)| !phrase.trim().is_empty())
+            .collect();
+        // Longest first so "Project Apollo" beats "Apollo"; then by phrase
+        // so the output does not depend on map order
+        rules.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+
+        Self {
+            rules,
+            link_speakers: links.link_speakers,
+        }
+    }
+
+    /// Insert wikilinks into `text` and collect the tags it mentions
+    ///
+    /// Tagged phrases are left as written; their tags are returned so the
+    /// caller can place them.
+    pub fn link(&self, text: &str) -> LinkedText {
+        let mut out = String::with_capacity(text.len());
+        let mut tags = BTreeSet::new();
+        let mut pos = 0;
+
+        'scan: while pos < text.len() {
+            if at_word_start(text, pos) {
+                for (phrase, rule) in &self.rules {
+                    let end = pos + phrase.len();
+                    let matched = match text.get(pos..end) {
+                        Some(candidate) if candidate.eq_ignore_ascii_case(phrase) => candidate,
+                        _ => continue,
+                    };
+                    if !at_word_end(text, end) {
+                        continue;
+                    }
+
+                    match rule {
+                        Rule::Link(note) => out.push_str(&wikilink(note, matched)),
+                        Rule::Tag(tag) => {
+                            tags.insert(tag.trim_start_matches('#').to_string());
+                            out.push_str(matched);
+                        }
+                    }
+                    pos = end;
+                    continue 'scan;
+                }
+            }
+
+            let c = text[pos..].chars().next().unwrap_or_default();
+            out.push(c);
+            pos += c.len_utf8();
+        }
+
+        LinkedText {
+            text: out,
+            tags: tags.into_iter().collect(),
+        }
+    }
+
+    /// Speaker label, linked if configured (and mapped through `wikilinks`)
+    pub fn speaker(&self, speaker: &str) -> String {
+        if !self.link_speakers {
+            return speaker.to_string();
+        }
+
+        let note = self
+            .rules
+            .iter()
+            .find_map(|(phrase, rule)| match rule {
+                Rule::Link(note) if phrase.eq_ignore_ascii_case(speaker) => Some(*note),
+                _ => None,
+            })
+            .unwrap_or(speaker);
+        wikilink(note, speaker)
+    }
+}
+
+/// `[[note]]`, or `[[note|alias]]` when the visible text differs
+fn wikilink(note: &str, alias: &str) -> String {
+    if note == alias {
+        format!("[[{}]]", note)
+    } else {
+        format!("[[{}|{}]]", note, alias)
+    }
+}
+
+fn at_word_start(text: &str, pos: usize) -> bool {
+    text[..pos]
+        .chars()
+        .next_back()
+        .is_none_or(|c| !c.is_alphanumeric())
+}
+
+fn at_word_end(text: &str, pos: usize) -> bool {
+    text[pos..]
+        .chars()
+        .next()
+        .is_none_or(|c| !c.is_alphanumeric())
+}
@@ -0,0 +1,417 @@
+use chrono::{DateTime, Local, Locale, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::str::FromStr;
+use tracing::warn;
+
+use super::links::{Linker, NoteLinks};
+use crate::session::{meeting_analytics, normalize_language, MarkerKind, TranscriptSegment};
+use crate::storage::MeetingRecord;
+
+/// Segments further apart than this start a new paragraph in paragraph layout
+const PARAGRAPH_GAP_SECS: i64 = 30;
+
+/// How segment timestamps are shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampStyle {
+    /// Offset from the start of the meeting (hh:mm:ss)
+    #[default]
+    Relative,
+    /// Wall-clock time in the local timezone, using `time_format`
+    Absolute,
+    /// No timestamps
+    None,
+}
+
+/// How speaker names are shown before segment text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeakerLabelStyle {
+    /// **Alice:** text
+    #[default]
+    Bold,
+    /// Alice: text
+    Plain,
+    /// No speaker labels
+    None,
+}
+
+/// How transcript segments are laid out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptLayout {
+    /// One line per segment
+    #[default]
+    Segments,
+    /// Consecutive segments from the same speaker merged into paragraphs
+    Paragraphs,
+}
+
+/// Formatting options for generated notes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoteFormat {
+    pub timestamps: TimestampStyle,
+
+    /// strftime format for absolute timestamps
+    pub time_format: String,
+
+    /// strftime format for dates in the heading
+    pub date_format: String,
+
+    /// Locale for month/day names in dates and times (e.g. "de_DE"); unset = English
+    pub locale: Option<String>,
+
+    pub speaker_labels: SpeakerLabelStyle,
+
+    pub layout: TranscriptLayout,
+
+    /// Heading line; `{title}`, `{date}`, and `{meeting_id}` are substituted
+    pub heading: String,
+
+    /// Wikilinks and tags added to the transcript
+    pub links: NoteLinks,
+
+    /// Only include segments in these languages (e.g. ["en", "de"]); empty
+    /// includes all. Segments with no known language are always included.
+    pub languages: Vec<String>,
+
+    /// Split the transcript into one section per language, in the order
+    /// the languages are first spoken
+    pub group_by_language: bool,
+
+    /// Quote each segment's translation (if it has one) below the original
+    pub bilingual: bool,
+}
+
+impl Default for NoteFormat {
+    fn default() -> Self {
+        Self {
+            timestamps: TimestampStyle::default(),
+            time_format: "%H:%M:%S".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            locale: None,
+            speaker_labels: SpeakerLabelStyle::default(),
+            layout: TranscriptLayout::default(),
+            heading: "# {title}".to_string(),
+            links: NoteLinks::default(),
+            languages: Vec::new(),
+            group_by_language: false,
+            bilingual: false,
+        }
+    }
+}
+
+impl NoteFormat {
+    /// Whether a segment passes the `languages` filter
+    pub(crate) fn includes(&self, segment: &TranscriptSegment) -> bool {
+        let Some(language) = &segment.language else {
+            return true;
+        };
+        self.languages.is_empty()
+            || self
+                .languages
+                .iter()
+                .any(|wanted| normalize_language(wanted).as_ref() == Some(language))
+    }
+
+    fn locale(&self) -> Locale {
+        let Some(name) = self.locale.as_deref() else {
+            return Locale::POSIX;
+        };
+
+        Locale::from_str(name).unwrap_or_else(|_| {
+            warn!("Unknown note locale {:?}, using default", name);
+            Locale::POSIX
+        })
+    }
+
+    /// Format a wall-clock time in the local timezone
+    pub(crate) fn format_time(&self, at: DateTime<Utc>, format: &str) -> String {
+        at.with_timezone(&Local)
+            .format_localized(format, self.locale())
+            .to_string()
+    }
+}
+
+/// Render a stored meeting as an Obsidian note (YAML front matter + transcript)
+pub fn render_note(record: &MeetingRecord, format: &NoteFormat) -> String {
+    let mut note = String::new();
+    let title = record.title.as_deref().unwrap_or(&record.meeting_id);
+    let linker = Linker::new(&format.links);
+
+    // Transcript first, so tags it mentions can go in the front matter
+    let mut transcript = String::new();
+    let mut tags = vec!["meeting".to_string()];
+    let segments: Vec<&TranscriptSegment> = record
+        .transcript
+        .iter()
+        .filter(|segment| format.includes(segment))
+        .collect();
+    if record.transcript.is_empty() {
+        let _ = writeln!(transcript, "_No transcript was captured._");
+    } else if segments.is_empty() {
+        let _ = writeln!(transcript, "_Nothing was said in the selected languages._");
+    }
+
+    // Languages in the order they are first spoken
+    let mut languages: Vec<Option<&str>> = Vec::new();
+    for segment in &segments {
+        if !languages.contains(&segment.language.as_deref()) {
+            languages.push(segment.language.as_deref());
+        }
+    }
+
+    let sections: Vec<(Option<&str>, Vec<&TranscriptSegment>)> = if format.group_by_language {
+        languages
+            .iter()
+            .map(|&language| {
+                let section = segments
+                    .iter()
+                    .copied()
+                    .filter(|segment| segment.language.as_deref() == language)
+                    .collect();
+                (language, section)
+            })
+            .collect()
+    } else {
+        vec![(None, segments.clone())]
+    };
+
+    for (language, section) in sections {
+        if format.group_by_language {
+            let _ = writeln!(transcript, "### {}", language.unwrap_or("Unknown language"));
+            let _ = writeln!(transcript);
+        }
+        render_segments(
+            &mut transcript,
+            &mut tags,
+            record,
+            format,
+            &linker,
+            &section,
+        );
+    }
+
+    // Front matter
+    let _ = writeln!(note, "---");
+    let _ = writeln!(note, "meeting_id: {}", record.meeting_id);
+    let _ = writeln!(note, "title: {}", yaml_string(title));
+    let _ = writeln!(note, "date: {}", record.started_at.to_rfc3339());
+    let _ = writeln!(
+        note,
+        "duration: {}",
+        format_offset(record.stats.duration_secs as i64)
+    );
+    let languages: Vec<&str> = languages.into_iter().flatten().collect();
+    if !languages.is_empty() {
+        let _ = writeln!(note, "languages: [{}]", languages.join(", "));
+    }
+    let _ = writeln!(note, "tags: [{}]", tags.join(", "));
+    let _ = writeln!(note, "---");
+    let _ = writeln!(note);
+
+    let heading = format
+        .heading
+        .replace("{title}", title)
+        .replace(
+            "{date}",
+            &format.format_time(record.started_at, &format.date_format),
+        )
+        .replace("{meeting_id}", &record.meeting_id);
+    let _ = writeln!(note, "{}", heading);
+    let _ = writeln!(note);
+
+    // Talk time, when segments say who spoke
+    let analytics = meeting_analytics(
+        record.started_at,
+        record.stats.duration_secs,
+        &record.transcript,
+    );
+    if analytics.has_speakers() {
+        let speaker_name = |speaker: Option<&str>| match speaker {
+            Some(speaker) => linker.speaker(speaker),
+            None => "Unknown".to_string(),
+        };
+        let talk_time: Vec<String> = analytics
+            .speakers
+            .iter()
+            .map(|speaker| {
+                format!(
+                    "{} {:.0}% ({})",
+                    speaker_name(speaker.speaker.as_deref()),
+                    speaker.percent,
+                    format_offset(speaker.talk_secs.round() as i64)
+                )
+            })
+            .collect();
+
+        let _ = writeln!(note, "## Summary");
+        let _ = writeln!(note);
+        let _ = writeln!(note, "- **Talk time:** {}", talk_time.join(", "));
+        if let Some(monologue) = &analytics.longest_monologue {
+            let _ = writeln!(
+                note,
+                "- **Longest monologue:** {}, {} from [{}]",
+                speaker_name(monologue.speaker.as_deref()),
+                format_offset(monologue.duration_secs.round() as i64),
+                format_offset((monologue.offset_ms / 1000) as i64)
+            );
+        }
+        let _ = writeln!(note);
+    }
+
+    let (chapters, markers): (Vec<_>, Vec<_>) = record
+        .markers
+        .iter()
+        .partition(|marker| marker.kind == MarkerKind::Chapter);
+
+    if !chapters.is_empty() {
+        let _ = writeln!(note, "## Chapters");
+        let _ = writeln!(note);
+        for (n, chapter) in chapters.iter().enumerate() {
+            let _ = writeln!(
+                note,
+                "{}. **[{}]** {}",
+                n + 1,
+                format_offset((chapter.offset_ms / 1000) as i64),
+                chapter.label
+            );
+        }
+        let _ = writeln!(note);
+    }
+
+    let _ = writeln!(note, "## Transcript");
+    let _ = writeln!(note);
+    note.push_str(&transcript);
+
+    if !markers.is_empty() {
+        let _ = writeln!(note, "## Markers");
+        let _ = writeln!(note);
+        for marker in markers {
+            let _ = writeln!(
+                note,
+                "- **[{}]** {}",
+                format_offset((marker.offset_ms / 1000) as i64),
+                marker.label
+            );
+        }
+    }
+
+    note
+}
+
+/// Render transcript segments as lines or paragraphs, collecting the tags
+/// they mention
+fn render_segments(
+    transcript: &mut String,
+    tags: &mut Vec<String>,
+    record: &MeetingRecord,
+    format: &NoteFormat,
+    linker: &Linker,
+    segments: &[&TranscriptSegment],
+) {
+    let blocks: Vec<Vec<&TranscriptSegment>> = match format.layout {
+        TranscriptLayout::Segments => segments.iter().map(|&s| vec![s]).collect(),
+        TranscriptLayout::Paragraphs => paragraphs(segments),
+    };
+
+    for block in blocks {
+        let first = block[0];
+        let mut line = String::new();
+
+        match format.timestamps {
+            TimestampStyle::Relative => {
+                line.push_str(&format!(
+                    "**[{}]** ",
+                    relative(record.started_at, first.timestamp)
+                ));
+            }
+            TimestampStyle::Absolute => {
+                line.push_str(&format!(
+                    "**[{}]** ",
+                    format.format_time(first.timestamp, &format.time_format)
+                ));
+            }
+            TimestampStyle::None => {}
+        }
+
+        if let Some(speaker) = &first.speaker {
+            let speaker = linker.speaker(speaker);
+            match format.speaker_labels {
+                SpeakerLabelStyle::Bold => line.push_str(&format!("**{}:** ", speaker)),
+                SpeakerLabelStyle::Plain => line.push_str(&format!("{}: ", speaker)),
+                SpeakerLabelStyle::None => {}
+            }
+        }
+
+        let text: Vec<&str> = block.iter().map(|s| s.text.trim()).collect();
+        let linked = linker.link(&text.join(" "));
+        line.push_str(&linked.text);
+        for tag in linked.tags {
+            line.push_str(&format!(" #{}", tag));
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let _ = writeln!(transcript, "{}", line);
+
+        let translations: Vec<&str> = block
+            .iter()
+            .filter_map(|s| s.translation.as_ref())
+            .map(|translation| translation.text.trim())
+            .collect();
+        if format.bilingual && !translations.is_empty() {
+            let _ = writeln!(transcript, "> {}", translations.join(" "));
+        }
+        let _ = writeln!(transcript);
+    }
+}
+
+/// Group consecutive segments by speaker (and language), breaking on long
+/// pauses
+fn paragraphs<'a>(segments: &[&'a TranscriptSegment]) -> Vec<Vec<&'a TranscriptSegment>> {
+    let mut blocks: Vec<Vec<&TranscriptSegment>> = Vec::new();
+
+    for &segment in segments {
+        match blocks.last_mut() {
+            Some(block)
+                if block[0].speaker == segment.speaker
+                    && block[0].language == segment.language
+                    && segment
+                        .timestamp
+                        .signed_duration_since(block[block.len() - 1].timestamp)
+                        .num_seconds()
+                        <= PARAGRAPH_GAP_SECS =>
+            {
+                block.push(segment);
+            }
+            _ => blocks.push(vec![segment]),
+        }
+    }
+
+    blocks
+}
+
+/// Offset of `at` from `start` as hh:mm:ss
+fn relative(start: DateTime<Utc>, at: DateTime<Utc>) -> String {
+    format_offset(at.signed_duration_since(start).num_seconds())
+}
+
+/// Format whole seconds as hh:mm:ss (negative offsets clamp to zero)
+pub(crate) fn format_offset(secs: i64) -> String {
+    let secs = secs.max(0);
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// Quote a string for YAML front matter
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
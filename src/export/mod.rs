@@ -0,0 +1,33 @@
+//! Exporting stored meetings
+//!
+//! This module turns a stored `MeetingRecord` into shareable artifacts:
+//! - Markdown notes for Obsidian (built-in layout or user Jinja2 templates),
+//!   with configured wikilinks and tags
+//! - The full meeting audio as one WAV, loudness-normalized (EBU R128)
+//! - Zip bundles (audio chunks, transcript, note, manifest) for archiving
+//!   and importing on another machine
+//! - Writing notes into the Obsidian vault, as separate notes or as
+//!   sections of the daily note
+//! - Opening exported notes in Obsidian or notifying a callback URL
+
+pub mod audio;
+pub mod bundle;
+pub mod callback;
+pub mod links;
+pub mod markdown;
+pub mod obsidian;
+pub mod template;
+
+pub use audio::MeetingAudio;
+pub use bundle::{
+    write_bundle, BundleFile, BundleFileKind, BundleManifest, BundleProgress, BundleReader,
+    BUNDLE_FORMAT_VERSION,
+};
+pub use callback::{after_note_exported, obsidian_uri, post_callback, NoteExportedCallback};
+pub use links::{LinkedText, Linker, NoteLinks};
+pub use markdown::{render_note, NoteFormat, SpeakerLabelStyle, TimestampStyle, TranscriptLayout};
+pub use obsidian::{
+    append_to_daily_note, export_meeting_note, refresh_exported_note, render_meeting_note,
+    write_meeting_note,
+};
+pub use template::{NoteContext, NoteTemplate};
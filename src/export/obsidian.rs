@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use super::markdown::{render_note, NoteFormat};
+use super::template::NoteTemplate;
+use crate::config::{DailyNotesConfig, NoteExportMode, ObsidianConfig};
+use crate::storage::{write_atomic, MeetingRecord};
+
+/// Opens a meeting's section in a daily note (followed by the meeting ID)
+const SECTION_START: &str = "<!-- loqa-meeting:start";
+
+/// Closes a meeting's section in a daily note (followed by the meeting ID)
+const SECTION_END: &str = "<!-- loqa-meeting:end";
+
+/// Export a meeting note according to the configured export mode
+pub fn export_meeting_note(
+    config: &ObsidianConfig,
+    format: &NoteFormat,
+    record: &MeetingRecord,
+) -> Result<PathBuf> {
+    match config.export_mode {
+        NoteExportMode::PerMeeting => write_meeting_note(config, format, record),
+        NoteExportMode::DailyNote => append_to_daily_note(config, format, record),
+    }
+}
+
+/// Re-export a meeting's note if it is already in the vault
+///
+/// Used after the stored meeting changes (e.g. a transcript correction) so
+/// the vault reflects it; meetings that were never exported are left alone.
+/// Returns the rewritten note's path.
+pub fn refresh_exported_note(
+    config: &ObsidianConfig,
+    format: &NoteFormat,
+    record: &MeetingRecord,
+) -> Result<Option<PathBuf>> {
+    let exported = match config.export_mode {
+        NoteExportMode::PerMeeting => meeting_note_path(config, record).exists(),
+        NoteExportMode::DailyNote => fs::read_to_string(daily_note_path(config, record))
+            .map(|note| note.contains(&format!("{} {} -->", SECTION_START, record.meeting_id)))
+            .unwrap_or(false),
+    };
+
+    if !exported {
+        return Ok(None);
+    }
+    export_meeting_note(config, format, record).map(Some)
+}
+
+/// Render a meeting note with the configured template, or the built-in layout
+pub fn render_meeting_note(
+    config: &ObsidianConfig,
+    format: &NoteFormat,
+    record: &MeetingRecord,
+) -> Result<String> {
+    match config.resolved_template_path() {
+        // Reloaded on every export so template edits apply without a restart
+        Some(path) => NoteTemplate::load(&path)?.render(record, format),
+        None => Ok(render_note(record, format)),
+    }
+}
+
+/// Write a meeting's note into the vault's meetings folder, returning its path
+///
+/// Re-exporting the same meeting overwrites its note.
+pub fn write_meeting_note(
+    config: &ObsidianConfig,
+    format: &NoteFormat,
+    record: &MeetingRecord,
+) -> Result<PathBuf> {
+    let path = meeting_note_path(config, record);
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)
+            .with_context(|| format!("Failed to create {}", folder.display()))?;
+    }
+
+    let note = render_meeting_note(config, format, record)?;
+    write_atomic(&path, note)?;
+
+    Ok(path)
+}
+
+/// Add a meeting's section to the daily note for the day it started
+///
+/// The daily note is created (from the daily note template, if configured)
+/// when it does not exist yet. Sections are delimited by HTML comments
+/// carrying the meeting ID, so re-exporting a meeting replaces its section
+/// instead of appending a duplicate; the rest of the note is left untouched.
+pub fn append_to_daily_note(
+    config: &ObsidianConfig,
+    format: &NoteFormat,
+    record: &MeetingRecord,
+) -> Result<PathBuf> {
+    let daily = &config.daily_notes;
+    let day = record.started_at.with_timezone(&Local);
+    let path = daily_note_path(config, record);
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)
+            .with_context(|| format!("Failed to create {}", folder.display()))?;
+    }
+
+    let existing = match fs::read_to_string(&path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == ErrorKind::NotFound => new_daily_note(daily, day)?,
+        Err(e) => return Err(e).with_context(|| format!("Failed to read note {}", path.display())),
+    };
+
+    let note = render_meeting_note(config, format, record)?;
+    let section = daily_section(&record.meeting_id, &note);
+    let updated = upsert_section(&existing, &record.meeting_id, &section);
+    // The daily note holds the user's own writing; never leave it half-written
+    write_atomic(&path, updated)?;
+
+    Ok(path)
+}
+
+/// Where a meeting's own note lives in the vault
+fn meeting_note_path(config: &ObsidianConfig, record: &MeetingRecord) -> PathBuf {
+    config
+        .resolved_vault_path()
+        .join(&config.meetings_folder)
+        .join(note_file_name(record))
+}
+
+/// The daily note for the day a meeting started
+fn daily_note_path(config: &ObsidianConfig, record: &MeetingRecord) -> PathBuf {
+    let daily = &config.daily_notes;
+    let day = record.started_at.with_timezone(&Local);
+    config
+        .resolved_vault_path()
+        .join(&daily.folder)
+        .join(format!("{}.md", day.format(&daily.file_name_format)))
+}
+
+/// Contents of a new daily note
+fn new_daily_note(daily: &DailyNotesConfig, day: DateTime<Local>) -> Result<String> {
+    let Some(template_path) = daily.resolved_template_path() else {
+        return Ok(String::new());
+    };
+
+    let template = fs::read_to_string(&template_path).with_context(|| {
+        format!(
+            "Failed to read daily note template {}",
+            template_path.display()
+        )
+    })?;
+    let date = day.format("%Y-%m-%d").to_string();
+    let title = day.format(&daily.file_name_format).to_string();
+
+    Ok(template
+        .replace("{{date}}", &date)
+        .replace("{{title}}", &title))
+}
+
+/// Wrap a rendered meeting note as a daily note section
+///
+/// Front matter is dropped and headings are demoted one level so the
+/// meeting nests under the daily note's own structure.
+fn daily_section(meeting_id: &str, note: &str) -> String {
+    let mut section = format!("{} {} -->\n", SECTION_START, meeting_id);
+    for line in strip_front_matter(note).trim().lines() {
+        if line.starts_with('#') && !line.starts_with("######") {
+            section.push('#');
+        }
+        section.push_str(line);
+        section.push('\n');
+    }
+    section.push_str(&format!("{} {} -->\n", SECTION_END, meeting_id));
+    section
+}
+
+/// Replace a meeting's existing section, or append it to the end of the note
+fn upsert_section(note: &str, meeting_id: &str, section: &str) -> String {
+    let start_marker = format!("{} {} -->", SECTION_START, meeting_id);
+    let end_marker = format!("{} {} -->", SECTION_END, meeting_id);
+
+    if let Some(start) = note.find(&start_marker) {
+        if let Some(end) = note[start..].find(&end_marker) {
+            let mut end = start + end + end_marker.len();
+            if note[end..].starts_with('\n') {
+                end += 1;
+            }
+            return format!("{}{}{}", &note[..start], section, &note[end..]);
+        }
+    }
+
+    let mut updated = note.to_string();
+    if !updated.is_empty() {
+        if !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push('\n');
+    }
+    updated.push_str(section);
+    updated
+}
+
+/// Drop a leading `---` delimited YAML block
+fn strip_front_matter(note: &str) -> &str {
+    note.strip_prefix("---\n")
+        .and_then(|rest| rest.find("\n---\n").map(|end| &rest[end + 5..]))
+        .unwrap_or(note)
+}
+
+/// Note file name: "<date> <title>.md", with characters Obsidian rejects removed
+pub fn note_file_name(record: &MeetingRecord) -> String {
+    let title = record.title.as_deref().unwrap_or(&record.meeting_id);
+    let title: String = title
+        .chars()
+        .filter(|c| {
+            !matches!(
+                c,
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']'
+            )
+        })
+        .collect();
+
+    format!(
+        "{} {}.md",
+        record.started_at.format("%Y-%m-%d"),
+        title.trim()
+    )
+}
@@ -0,0 +1,216 @@
+use anyhow::{Context, Result};
+use minijinja::Environment;
+use serde::Serialize;
+use std::path::Path;
+
+use super::links::Linker;
+use super::markdown::{format_offset, NoteFormat, TimestampStyle};
+use crate::storage::MeetingRecord;
+
+/// Template name used when registering a user template
+const TEMPLATE_NAME: &str = "note.md";
+
+/// Phrases that mark a transcript segment as an action item
+const ACTION_ITEM_CUES: &[&str] = &["action item", "todo", "to-do", "follow up", "follow-up"];
+
+/// A user-provided Jinja2-style template for meeting notes
+///
+/// Templates see `meeting`, `segments`, `markers`, `chapters`,
+/// `action_items`, and `summary`; segment and marker `time` values are
+/// already formatted according to the note format, and segments are
+/// already filtered to the note format's `languages`.
+pub struct NoteTemplate {
+    env: Environment<'static>,
+}
+
+impl NoteTemplate {
+    /// Load and compile a template file
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read note template {}", path.display()))?;
+        Self::from_source(&source)
+            .with_context(|| format!("Invalid note template {}", path.display()))
+    }
+
+    /// Compile a template from a string
+    pub fn from_source(source: &str) -> Result<Self> {
+        let mut env = Environment::new();
+        env.add_template_owned(TEMPLATE_NAME, source.to_string())?;
+        Ok(Self { env })
+    }
+
+    /// Render a meeting note
+    pub fn render(&self, record: &MeetingRecord, format: &NoteFormat) -> Result<String> {
+        self.env
+            .get_template(TEMPLATE_NAME)?
+            .render(NoteContext::new(record, format))
+            .context("Failed to render note template")
+    }
+}
+
+/// Data available to note templates
+#[derive(Debug, Serialize)]
+pub struct NoteContext {
+    pub meeting: MeetingContext,
+    pub segments: Vec<SegmentContext>,
+    pub markers: Vec<MarkerContext>,
+    /// Chapter markers only, in timeline order
+    pub chapters: Vec<MarkerContext>,
+    pub action_items: Vec<SegmentContext>,
+    /// Meeting summary (not generated yet; always null)
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MeetingContext {
+    pub id: String,
+    /// Title, or the meeting ID if untitled
+    pub title: String,
+    pub profile: Option<String>,
+    /// Start date formatted with the note's date format
+    pub date: String,
+    pub started_at: String,
+    pub ended_at: String,
+    /// Duration as hh:mm:ss
+    pub duration: String,
+    pub duration_secs: f64,
+    /// Tags mentioned anywhere in the transcript
+    pub tags: Vec<String>,
+    /// Languages spoken, in the order they were first spoken
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SegmentContext {
+    /// Text with configured wikilinks inserted
+    pub text: String,
+    /// Speaker, as a wikilink if speaker linking is enabled
+    pub speaker: Option<String>,
+    /// Tags the segment mentions (without `#`)
+    pub tags: Vec<String>,
+    /// Timestamp formatted per the note format (empty when timestamps are off)
+    pub time: String,
+    /// Seconds from the start of the meeting
+    pub offset_secs: i64,
+    pub confidence: Option<f32>,
+    /// Language code (e.g. "en"), if known
+    pub language: Option<String>,
+    /// Translated text, if the segment was translated
+    pub translation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkerContext {
+    pub kind: String,
+    pub label: String,
+    /// Offset from the start of capture as hh:mm:ss
+    pub time: String,
+}
+
+impl NoteContext {
+    pub fn new(record: &MeetingRecord, format: &NoteFormat) -> Self {
+        let linker = Linker::new(&format.links);
+        let segments: Vec<SegmentContext> = record
+            .transcript
+            .iter()
+            .filter(|segment| format.includes(segment))
+            .map(|segment| {
+                let offset_secs = segment
+                    .timestamp
+                    .signed_duration_since(record.started_at)
+                    .num_seconds();
+                let time = match format.timestamps {
+                    TimestampStyle::Relative => format_offset(offset_secs),
+                    TimestampStyle::Absolute => {
+                        format.format_time(segment.timestamp, &format.time_format)
+                    }
+                    TimestampStyle::None => String::new(),
+                };
+
+                let linked = linker.link(segment.text.trim());
+
+                SegmentContext {
+                    text: linked.text,
+                    speaker: segment.speaker.as_deref().map(|s| linker.speaker(s)),
+                    tags: linked.tags,
+                    time,
+                    offset_secs,
+                    confidence: segment.confidence,
+                    language: segment.language.clone(),
+                    translation: segment
+                        .translation
+                        .as_ref()
+                        .map(|translation| translation.text.clone()),
+                }
+            })
+            .collect();
+
+        let action_items = segments
+            .iter()
+            .filter(|s| is_action_item(&s.text))
+            .cloned()
+            .collect();
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in segments.iter().flat_map(|s| &s.tags) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+
+        let mut languages: Vec<String> = Vec::new();
+        for language in segments.iter().filter_map(|s| s.language.as_ref()) {
+            if !languages.contains(language) {
+                languages.push(language.clone());
+            }
+        }
+
+        let markers: Vec<MarkerContext> = record
+            .markers
+            .iter()
+            .map(|marker| MarkerContext {
+                kind: serde_json::to_value(marker.kind)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+                label: marker.label.clone(),
+                time: format_offset((marker.offset_ms / 1000) as i64),
+            })
+            .collect();
+
+        let chapters = markers
+            .iter()
+            .filter(|marker| marker.kind == "chapter")
+            .cloned()
+            .collect();
+
+        Self {
+            meeting: MeetingContext {
+                id: record.meeting_id.clone(),
+                title: record
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| record.meeting_id.clone()),
+                profile: record.profile.clone(),
+                date: format.format_time(record.started_at, &format.date_format),
+                started_at: record.started_at.to_rfc3339(),
+                ended_at: record.ended_at.to_rfc3339(),
+                duration: format_offset(record.stats.duration_secs as i64),
+                duration_secs: record.stats.duration_secs,
+                tags,
+                languages,
+            },
+            segments,
+            markers,
+            chapters,
+            action_items,
+            summary: None,
+        }
+    }
+}
+
+/// Whether a segment reads like an action item ("Action item: ...", "TODO ...")
+fn is_action_item(text: &str) -> bool {
+    let text = text.to_lowercase();
+    ACTION_ITEM_CUES.iter().any(|cue| text.contains(cue))
+}
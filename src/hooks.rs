@@ -0,0 +1,184 @@
+//! Automation hooks: shell commands run as meetings start, stop, and are
+//! exported
+//!
+//! Each hook runs through the platform shell (`sh -c`, or `cmd /C` on
+//! Windows) with the meeting described in `LOQA_*` environment variables,
+//! so a script can e.g. pause music, switch on a "recording" light, or
+//! commit the exported note to git. Hooks never fail the meeting: a hook
+//! that fails or runs past the timeout is logged and the next one runs.
+
+use crate::config::{Config, HooksConfig};
+use crate::storage::MeetingRecord;
+use anyhow::{bail, Context, Result};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+
+/// When a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// A meeting started recording
+    Start,
+    /// A meeting stopped and was saved
+    Stop,
+    /// A meeting's note was written to the vault
+    Export,
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HookEvent::Start => "start",
+            HookEvent::Stop => "stop",
+            HookEvent::Export => "export",
+        })
+    }
+}
+
+/// The meeting a hook runs for, passed to it as environment variables
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    /// `LOQA_MEETING_ID`
+    pub meeting_id: String,
+    /// `LOQA_MEETING_TITLE` (empty without a title)
+    pub title: Option<String>,
+    /// `LOQA_PROFILE` (empty for the default profile)
+    pub profile: Option<String>,
+    /// `LOQA_AUDIO_DIR`: where the meeting's audio chunks are written
+    pub audio_dir: Option<PathBuf>,
+    /// `LOQA_NOTE_PATH`: the note written to the vault, if any
+    pub note_path: Option<PathBuf>,
+    /// `LOQA_DURATION_SECS`: how long the meeting recorded, once stopped
+    pub duration: Option<Duration>,
+}
+
+impl HookContext {
+    pub fn new(meeting_id: impl Into<String>) -> Self {
+        Self {
+            meeting_id: meeting_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// A stored meeting, with its audio where `config` records it
+    pub fn for_meeting(config: &Config, record: &MeetingRecord) -> Self {
+        Self {
+            title: record.title.clone(),
+            profile: record.profile.clone(),
+            audio_dir: Some(
+                config
+                    .audio
+                    .resolved_recordings_path()
+                    .join(&record.meeting_id),
+            ),
+            ..Self::new(&record.meeting_id)
+        }
+    }
+
+    /// Environment variables describing the meeting for `event`
+    pub fn env(&self, event: HookEvent) -> Vec<(&'static str, String)> {
+        let path = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+        };
+        vec![
+            ("LOQA_HOOK", event.to_string()),
+            ("LOQA_MEETING_ID", self.meeting_id.clone()),
+            ("LOQA_MEETING_TITLE", self.title.clone().unwrap_or_default()),
+            ("LOQA_PROFILE", self.profile.clone().unwrap_or_default()),
+            ("LOQA_AUDIO_DIR", path(&self.audio_dir)),
+            ("LOQA_NOTE_PATH", path(&self.note_path)),
+            (
+                "LOQA_DURATION_SECS",
+                self.duration
+                    .map(|duration| duration.as_secs().to_string())
+                    .unwrap_or_default(),
+            ),
+        ]
+    }
+}
+
+/// Run the hooks configured for `event` one after another, in order
+///
+/// Failures are logged rather than returned, like the after-export
+/// actions.
+pub async fn run_hooks(config: &HooksConfig, event: HookEvent, context: &HookContext) {
+    let timeout = Duration::from_secs(config.timeout_secs);
+    for command in commands(config, event)
+        .iter()
+        .filter(|command| !command.trim().is_empty())
+    {
+        match run_hook(command, event, context, timeout).await {
+            Ok(()) => info!("Ran {} hook for {}: {}", event, context.meeting_id, command),
+            Err(e) => warn!(
+                "{} hook for {} failed: {}: {:#}",
+                event, context.meeting_id, command, e
+            ),
+        }
+    }
+}
+
+/// Run the hooks for `event` in the background, so a slow script never
+/// holds up the meeting
+pub fn spawn_hooks(config: &HooksConfig, event: HookEvent, context: HookContext) {
+    if commands(config, event).is_empty() {
+        return;
+    }
+
+    let config = config.clone();
+    tokio::spawn(async move { run_hooks(&config, event, &context).await });
+}
+
+/// Commands configured for `event`
+fn commands(config: &HooksConfig, event: HookEvent) -> &[String] {
+    match event {
+        HookEvent::Start => &config.on_start,
+        HookEvent::Stop => &config.on_stop,
+        HookEvent::Export => &config.on_export,
+    }
+}
+
+/// Run one hook command through the shell, waiting up to `timeout`
+async fn run_hook(
+    command: &str,
+    event: HookEvent,
+    context: &HookContext,
+    timeout: Duration,
+) -> Result<()> {
+    #[cfg(windows)]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    };
+    #[cfg(not(windows))]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell
+        .arg(command)
+        .envs(context.env(event))
+        .stdin(std::process::Stdio::null())
+        // Killed if it runs past the timeout
+        .kill_on_drop(true);
+
+    let output = match tokio::time::timeout(timeout, shell.output()).await {
+        Ok(output) => output.context("Failed to run the shell")?,
+        Err(_) => bail!("Timed out after {}s", timeout.as_secs()),
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !stdout.trim().is_empty() {
+        debug!("{} hook output: {}", event, stdout.trim());
+    }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{} {}", output.status, stderr.trim());
+    }
+    Ok(())
+}
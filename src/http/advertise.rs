@@ -0,0 +1,83 @@
+use crate::config::AdvertiseConfig;
+use crate::version::VERSION;
+use anyhow::{bail, Context, Result};
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use std::net::SocketAddr;
+use tracing::{debug, info};
+
+/// mDNS service type the API is advertised as
+pub const SERVICE_TYPE: &str = "_loqa-meetings._tcp.local.";
+
+/// The API advertised over mDNS, withdrawn when dropped
+pub struct Advertisement {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl Advertisement {
+    /// Advertise the API listening on `addr` (None when advertisement is
+    /// off)
+    ///
+    /// A loopback listener is refused: LAN clients would resolve the record
+    /// to a port they can't reach.
+    pub fn start(config: &AdvertiseConfig, addr: SocketAddr) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        if addr.ip().is_loopback() {
+            bail!(
+                "Listening on {} is only reachable from this machine; bind to a LAN address to advertise",
+                addr
+            );
+        }
+
+        let service = service_info(config, addr.port())?;
+        let fullname = service.get_fullname().to_string();
+        let daemon = ServiceDaemon::new().context("Failed to start mDNS responder")?;
+        daemon
+            .register(service)
+            .context("Failed to advertise the API over mDNS")?;
+        info!("Advertising the API over mDNS as {}", fullname);
+
+        Ok(Some(Self { daemon, fullname }))
+    }
+
+    /// Full mDNS name of the advertised service
+    pub fn fullname(&self) -> &str {
+        &self.fullname
+    }
+}
+
+impl Drop for Advertisement {
+    /// Tell the network the API is gone and stop responding
+    fn drop(&mut self) {
+        if let Err(e) = self.daemon.unregister(&self.fullname) {
+            debug!("Failed to withdraw mDNS advertisement: {}", e);
+        }
+        let _ = self.daemon.shutdown();
+    }
+}
+
+/// The service record for the API on `port`: the instance named after
+/// the host unless configured, at the host's addresses, with the version
+pub fn service_info(config: &AdvertiseConfig, port: u16) -> Result<ServiceInfo> {
+    let host = gethostname::gethostname().to_string_lossy().into_owned();
+    let host = host.trim_end_matches(".local").to_string();
+    let instance = config
+        .instance_name
+        .clone()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| host.clone());
+
+    let service = ServiceInfo::new(
+        SERVICE_TYPE,
+        &instance,
+        &format!("{}.local.", host),
+        "",
+        port,
+        &[("version", VERSION)][..],
+    )
+    .context("Invalid mDNS service record")?;
+    // Answer with whichever addresses the host has, as they change
+    Ok(service.enable_addr_auto())
+}
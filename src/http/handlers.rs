@@ -1,34 +1,123 @@
 use super::state::AppState;
-use crate::session::{RecordingSession, SessionConfig, SessionStats, TranscriptSegment};
+use crate::audio::{
+    file_sha256, is_workdir_file, AudioFile, AudioInfo, AudioStreamSource, ChunkCodec,
+    ChunkMetadata, DirInUse, MeetingDirLock, MeetingIndex, QualityPreset, ResamplerQuality,
+};
+use crate::config::Config;
+use crate::export::{
+    after_note_exported, export_meeting_note, refresh_exported_note, render_meeting_note,
+    write_bundle, BundleReader, MeetingAudio,
+};
+use crate::hooks::{spawn_hooks, HookContext, HookEvent};
+use crate::nats::AudioCodec;
+use crate::service::{
+    saved_meeting_index, write_meeting_index, MeetingStatus, MeetingsError, StartOptions,
+    StartOutcome, StopOutcome, NATS_URL,
+};
+use crate::session::{
+    apply_chapters, detect_chapters, diff_transcripts, load_range_audio, meeting_analytics,
+    plan_windows, replace_range, retranscribe, stt_audio, transcribe_windows, AudioRetention,
+    CompanionFormat, CompanionInput, HostInfo, Marker, MarkerKind, MeetingEvent, RecordingSession,
+    SessionEvent, SessionState, SessionStats, TimeRange, TranscriptDiff, TranscriptFilter,
+    TranscriptQuery, TranscriptSegment,
+};
+use crate::storage::{validate_new_meeting_id, IntegrityReport, MeetingRecord, SegmentEdit};
+use crate::version::BuildInfo;
+use anyhow::Context;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::{IntoResponse, Json},
+    body::{Body, Bytes},
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Multipart, Path, Query, State,
+    },
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Json, Response},
 };
 use serde::{Deserialize, Serialize};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{error, info};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{error, info, warn};
+
+/// Capture page served to paired companion devices
+const COMPANION_PAGE: &str = include_str!("companion.html");
 
 // ============================================================================
 // Request/Response Types
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
-pub struct StartRecordingRequest {
-    /// Optional meeting ID (if not provided, generate UUID)
-    pub meeting_id: Option<String>,
+#[derive(Debug, Serialize)]
+pub struct StartRecordingResponse {
+    pub meeting_id: String,
+    pub status: String,
+    pub message: String,
+    /// Milliseconds until the first audio frame arrived, when the start
+    /// waited for one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_frame_ms: Option<u64>,
+    /// Milliseconds of standby audio from before the start the session
+    /// began with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backfilled_ms: Option<u64>,
+}
 
-    /// Optional meeting title
-    pub title: Option<String>,
+/// A live session source after a mute or unmute
+#[derive(Debug, Serialize)]
+pub struct SourceMuteResponse {
+    pub meeting_id: String,
+    pub source: String,
+    pub muted: bool,
+    /// False when the source was already in the requested state
+    pub changed: bool,
+}
+
+/// Bookmark options, as query parameters so a hotkey can send an empty POST
+#[derive(Debug, Default, Deserialize)]
+pub struct BookmarkQuery {
+    /// Seconds of audio before the bookmark to save (default and most: the
+    /// configured pre-roll)
+    pub seconds: Option<u64>,
+    /// Marker label (default "Bookmark")
+    pub label: Option<String>,
+}
 
-    /// Chunk duration in seconds (default: 300 = 5 minutes)
-    pub chunk_duration_secs: Option<u64>,
+/// Token a companion device was paired with, as a query parameter since
+/// browsers can't set headers on WebSocket requests
+#[derive(Debug, Default, Deserialize)]
+pub struct PairingQuery {
+    #[serde(default)]
+    pub token: String,
 }
 
 #[derive(Debug, Serialize)]
-pub struct StartRecordingResponse {
+pub struct CompanionPairingResponse {
+    pub meeting_id: String,
+    /// Token the companion device presents to join
+    pub token: String,
+    /// Capture page to open on the device (e.g. as a QR code)
+    pub page_url: String,
+    /// WebSocket to stream audio to, for companion apps
+    pub ingest_url: String,
+}
+
+/// Full-meeting audio export options
+#[derive(Debug, Default, Deserialize)]
+pub struct AudioExportQuery {
+    /// Normalize loudness to the configured target (default: the
+    /// `audio.loudness.enabled` config)
+    pub normalize: Option<bool>,
+}
+
+/// A session waiting for a free recording slot (or cancelled while waiting)
+#[derive(Debug, Serialize)]
+pub struct QueuedSessionResponse {
     pub meeting_id: String,
     pub status: String,
+    /// 1-based place in the start queue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<usize>,
     pub message: String,
 }
 
@@ -40,92 +129,305 @@ pub struct StopRecordingResponse {
     pub stats: SessionStats,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListMeetingsQuery {
+    /// Maximum meetings to return (default: 50, max: 500)
+    pub limit: Option<usize>,
+
+    /// Meetings to skip (for paging)
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    /// Words that must all appear in a transcript segment
+    pub q: String,
+
+    /// Maximum hits to return (default: 50, max: 500)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Replace an existing meeting with the same ID
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    pub meeting_id: String,
+    pub status: String,
+    /// Restored audio chunks
+    pub chunks_count: usize,
+    /// Obsidian note written for the meeting (None if writing failed)
+    pub note_path: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UploadQuery {
+    /// Meeting ID (if not provided, generated from the date and title, or a
+    /// UUID without a title)
+    pub meeting_id: Option<String>,
+
+    /// Meeting title
+    pub title: Option<String>,
+
+    /// Audio track to transcribe, for files with several (default: the first)
+    #[serde(default)]
+    pub track: usize,
+
+    /// STT model hint
+    pub model: Option<String>,
+
+    /// Transcribe as parallel overlapping windows (default: from config)
+    pub parallel: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadResponse {
+    pub meeting_id: String,
+    pub status: String,
+    /// Format of the upload and the track that was transcribed
+    pub audio: AudioInfo,
+    /// Transcript segments returned by STT
+    pub segments_count: usize,
+    /// Windows the recording was transcribed in (1 unless parallel)
+    pub windows: usize,
+    /// Obsidian note written for the meeting (None if writing failed)
+    pub note_path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NoteExportResponse {
+    pub meeting_id: String,
+    pub status: String,
+    /// Note the meeting was written to (its own note or the daily note)
+    pub note_path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EditSegmentRequest {
+    /// Corrected segment text
+    pub text: String,
+
+    /// Who is making the edit (recorded in the edit history)
+    pub editor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EditSegmentResponse {
+    pub meeting_id: String,
+    pub segment: TranscriptSegment,
+    pub edit: SegmentEdit,
+    /// Vault note re-rendered with the edit (None if the meeting was never exported)
+    pub note_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExtendRequest {
+    /// Seconds to add to the meeting's expiry (counted from now if it has
+    /// already passed)
+    pub extend_secs: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExtendResponse {
+    pub meeting_id: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RetranscribeRequest {
+    /// Range start in seconds from the start of the meeting
+    pub start_secs: Option<f64>,
+
+    /// Range end in seconds from the start of the meeting
+    pub end_secs: Option<f64>,
+
+    /// Audio chunk indices to re-transcribe (instead of a time range)
+    #[serde(default)]
+    pub chunks: Vec<usize>,
+
+    /// STT model hint (e.g. a larger model than live transcription uses)
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetranscribeResponse {
+    pub meeting_id: String,
+    pub status: String,
+    pub range: TimeRange,
+    /// Segments removed from the range
+    pub replaced_segments: usize,
+    /// New segments for the range
+    pub segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffResponse {
+    /// Reference meeting
+    pub meeting_a: String,
+    /// Meeting compared against it
+    pub meeting_b: String,
+    #[serde(flatten)]
+    pub diff: TranscriptDiff,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChaptersResponse {
+    pub meeting_id: String,
+    /// Chapter markers, in timeline order
+    pub chapters: Vec<Marker>,
+    /// Vault note re-rendered with the chapters (None if the meeting was never exported)
+    pub note_path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CapabilitiesResponse {
+    /// Quality presets accepted by `quality` in start requests
+    pub quality_presets: Vec<QualityPresetInfo>,
+    /// Preset used when neither the request nor the profile picks one
+    pub default_quality: QualityPreset,
+    /// Values accepted by `resampler_quality` in start requests
+    pub resampler_qualities: Vec<String>,
+    /// Most sessions that may record at once (None = unlimited)
+    pub max_concurrent_sessions: Option<usize>,
+    /// Values accepted by `codec` in start requests that this build can encode
+    pub audio_codecs: Vec<AudioCodec>,
+}
+
+/// What a quality preset sets across the capture pipeline
+#[derive(Debug, Serialize)]
+pub struct QualityPresetInfo {
+    pub name: QualityPreset,
+    pub description: String,
+    /// Capture buffer and mixed frame duration
+    pub frame_duration_ms: u64,
+    /// How far a mixer input may lag before it is zero-filled
+    pub max_latency_ms: u64,
+    /// Frames buffered between capture and processing
+    pub buffer_frames: usize,
+    pub chunk_codec: ChunkCodec,
+    pub resampler_quality: ResamplerQuality,
+    /// Samples stay in 32-bit float between processing stages
+    pub float_pipeline: bool,
+}
+
+impl From<QualityPreset> for QualityPresetInfo {
+    fn from(preset: QualityPreset) -> Self {
+        Self {
+            name: preset,
+            description: preset.description().to_string(),
+            frame_duration_ms: preset.frame_duration_ms(),
+            max_latency_ms: preset.max_latency_ms(),
+            buffer_frames: preset.channel_capacity(),
+            chunk_codec: preset.chunk_codec(),
+            resampler_quality: preset.resampler_quality(),
+            float_pipeline: preset.float_pipeline(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
     pub error: String,
 }
 
+impl IntoResponse for MeetingsError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            MeetingsError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            MeetingsError::NotFound(_) => StatusCode::NOT_FOUND,
+            MeetingsError::Conflict(_) => StatusCode::CONFLICT,
+            MeetingsError::LimitReached(_) => StatusCode::TOO_MANY_REQUESTS,
+            MeetingsError::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: self.to_string(),
+            }),
+        )
+            .into_response()
+    }
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
 
+/// Default page size for listing and search
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Largest page size a client may request
+const MAX_PAGE_SIZE: usize = 500;
+
+/// Bytes buffered before a bundle chunk is sent to the client
+const BUNDLE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Bundle chunks in flight before the writer waits for the client
+const BUNDLE_CHANNEL_CAPACITY: usize = 8;
+
 /// POST /meetings/record/start
 /// Start a new recording session
+///
+/// At the session limit this fails with 429, or with `queue` answers 202 and
+/// starts the session once another one stops.
 pub async fn start_recording(
     State(state): State<AppState>,
-    Json(req): Json<StartRecordingRequest>,
+    Json(req): Json<StartOptions>,
 ) -> impl IntoResponse {
-    // Generate or use provided meeting ID
-    let meeting_id = req
-        .meeting_id
-        .unwrap_or_else(|| format!("meeting-{}", uuid::Uuid::new_v4()));
-
-    info!("Starting recording for meeting: {}", meeting_id);
-
-    // Check if already recording
-    {
-        let sessions = state.sessions.read().await;
-        if sessions.contains_key(&meeting_id) {
+    let session = match state.start(req).await {
+        Ok(StartOutcome::Recording(session)) => session,
+        Ok(StartOutcome::Queued {
+            meeting_id,
+            position,
+        }) => {
             return (
-                StatusCode::CONFLICT,
-                Json(ErrorResponse {
-                    error: format!("Meeting {} is already recording", meeting_id),
+                StatusCode::ACCEPTED,
+                Json(QueuedSessionResponse {
+                    message: format!(
+                        "Recording for meeting {} starts when another session stops",
+                        meeting_id
+                    ),
+                    meeting_id,
+                    status: "queued".to_string(),
+                    position,
                 }),
             )
                 .into_response();
         }
-    }
-
-    // Create session config
-    let config = SessionConfig {
-        session_id: meeting_id.clone(),
-        chunk_duration: std::time::Duration::from_secs(req.chunk_duration_secs.unwrap_or(300)),
-        sample_rate: 16000,                            // Whisper expects 16kHz
-        channels: 1,                                   // Mono
-        nats_url: "nats://localhost:4222".to_string(), // TODO: Make configurable
+        Err(e) => return e.into_response(),
     };
+    let meeting_id = session.session_id().to_string();
 
-    // Create recording session
-    let session = match RecordingSession::new(config).await {
-        Ok(s) => Arc::new(s),
-        Err(e) => {
-            error!("Failed to create session: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Failed to create session: {}", e),
-                }),
-            )
-                .into_response();
-        }
+    let first_frame_ms = session
+        .first_frame_latency()
+        .map(|latency| latency.as_millis() as u64);
+    let backfilled_ms = Some(session.backfilled().as_millis() as u64).filter(|&ms| ms > 0);
+    let mut message = match first_frame_ms {
+        Some(ms) => format!(
+            "Recording started for meeting {} (audio received after {} ms)",
+            meeting_id, ms
+        ),
+        None => format!("Recording started for meeting {}", meeting_id),
     };
-
-    // Start recording
-    if let Err(e) = session.start().await {
-        error!("Failed to start recording: {}", e);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to start recording: {}", e),
-            }),
-        )
-            .into_response();
+    if let Some(ms) = backfilled_ms {
+        message.push_str(&format!(
+            ", including {:.1}s of standby audio",
+            ms as f64 / 1000.0
+        ));
     }
 
-    // Store session
-    {
-        let mut sessions = state.sessions.write().await;
-        sessions.insert(meeting_id.clone(), session);
-    }
-
-    info!("Recording started successfully for meeting: {}", meeting_id);
-
     (
         StatusCode::OK,
         Json(StartRecordingResponse {
-            meeting_id: meeting_id.clone(),
+            meeting_id,
             status: "recording".to_string(),
-            message: format!("Recording started for meeting {}", meeting_id),
+            message,
+            first_frame_ms,
+            backfilled_ms,
         }),
     )
         .into_response()
@@ -137,49 +439,72 @@ pub async fn stop_recording(
     State(state): State<AppState>,
     Path(meeting_id): Path<String>,
 ) -> impl IntoResponse {
-    info!("Stopping recording for meeting: {}", meeting_id);
+    match state.stop(&meeting_id).await {
+        Ok(StopOutcome::Stopped(stats)) => (
+            StatusCode::OK,
+            Json(StopRecordingResponse {
+                meeting_id,
+                status: "stopped".to_string(),
+                message: "Recording stopped".to_string(),
+                stats,
+            }),
+        )
+            .into_response(),
+        Ok(StopOutcome::Cancelled) => (
+            StatusCode::OK,
+            Json(QueuedSessionResponse {
+                meeting_id,
+                status: "cancelled".to_string(),
+                position: None,
+                message: "Queued recording cancelled".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// GET /meetings
+/// List stored meetings, newest first
+pub async fn list_meetings(
+    State(state): State<AppState>,
+    Query(query): Query<ListMeetingsQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0);
 
-    // Find and remove session
-    let session = {
-        let mut sessions = state.sessions.write().await;
-        sessions.remove(&meeting_id)
-    };
+    match state.list(limit, offset).await {
+        Ok(meetings) => (StatusCode::OK, Json(meetings)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
 
-    match session {
-        Some(session) => {
-            // Stop recording
-            match session.stop().await {
-                Ok(stats) => {
-                    info!("Recording stopped successfully for meeting: {}", meeting_id);
-                    (
-                        StatusCode::OK,
-                        Json(StopRecordingResponse {
-                            meeting_id: meeting_id.clone(),
-                            status: "stopped".to_string(),
-                            message: "Recording stopped".to_string(),
-                            stats,
-                        }),
-                    )
-                        .into_response()
-                }
-                Err(e) => {
-                    error!("Failed to stop recording: {}", e);
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ErrorResponse {
-                            error: format!("Failed to stop recording: {}", e),
-                        }),
-                    )
-                        .into_response()
-                }
-            }
-        }
-        None => {
-            error!("Meeting {} not found", meeting_id);
+/// GET /meetings/search?q=
+/// Search stored transcripts
+pub async fn search_meetings(
+    State(state): State<AppState>,
+    Query(query): Query<SearchQuery>,
+) -> impl IntoResponse {
+    if query.q.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Search query must not be empty".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+    match state.storage.search_transcripts(&query.q, limit).await {
+        Ok(hits) => (StatusCode::OK, Json(hits)).into_response(),
+        Err(e) => {
+            error!("Failed to search meetings: {}", e);
             (
-                StatusCode::NOT_FOUND,
+                StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
-                    error: format!("Meeting {} not found", meeting_id),
+                    error: format!("Failed to search meetings: {}", e),
                 }),
             )
                 .into_response()
@@ -193,57 +518,1928 @@ pub async fn get_meeting_status(
     State(state): State<AppState>,
     Path(meeting_id): Path<String>,
 ) -> impl IntoResponse {
-    let sessions = state.sessions.read().await;
-
-    match sessions.get(&meeting_id) {
-        Some(session) => match session.get_stats().await {
-            Ok(stats) => (StatusCode::OK, Json(stats)).into_response(),
-            Err(e) => {
-                error!("Failed to get stats: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to get stats: {}", e),
-                    }),
-                )
-                    .into_response()
-            }
-        },
-        None => (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Meeting {} not found", meeting_id),
+    match state.status(&meeting_id).await {
+        Ok(MeetingStatus::Recording(status)) => (StatusCode::OK, Json(status)).into_response(),
+        Ok(MeetingStatus::Queued(position)) => (
+            StatusCode::ACCEPTED,
+            Json(QueuedSessionResponse {
+                meeting_id,
+                status: "queued".to_string(),
+                position: Some(position),
+                message: format!("Waiting for a recording slot (position {})", position),
             }),
         )
             .into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
 /// GET /meetings/:meeting_id/transcript
-/// Get transcript for a meeting (accumulated so far)
+/// Get transcript for a meeting (accumulated so far, or stored once stopped)
+///
+/// Without query parameters the whole transcript is returned as a list. With
+/// any of `offset`, `limit` (default: 50, max: 500), `since`, `from_ms`,
+/// `to_ms`, `since_seq`, or `language`, one page is returned; polling clients pass the
+/// page's `sequence` back as `since_seq` to get only new segments.
 pub async fn get_meeting_transcript(
     State(state): State<AppState>,
     Path(meeting_id): Path<String>,
+    Query(query): Query<TranscriptQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+    match state.transcript(&meeting_id, &query, limit).await {
+        Ok(transcript) => (StatusCode::OK, Json(transcript)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// PATCH /meetings/:meeting_id/transcript/segments/:index
+/// Correct the text of a stored transcript segment
+///
+/// The change is recorded in the meeting's edit history, and the meeting's
+/// vault note is re-rendered if it was exported.
+pub async fn edit_transcript_segment(
+    State(state): State<AppState>,
+    Path((meeting_id, index)): Path<(String, usize)>,
+    Json(req): Json<EditSegmentRequest>,
+) -> impl IntoResponse {
+    if state.sessions.read().await.contains_key(&meeting_id) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Meeting {} is still recording", meeting_id),
+            }),
+        )
+            .into_response();
+    }
+
+    let mut record = match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+
+    let edit = match record.edit_segment(index, &req.text, req.editor) {
+        Ok(edit) => edit.clone(),
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    if let Err(e) = state.storage.save_meeting(&record).await {
+        error!("Failed to save meeting {}: {}", meeting_id, e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to save meeting: {}", e),
+            }),
+        )
+            .into_response();
+    }
+
+    info!("Edited segment {} of meeting {}", index, meeting_id);
+
+    // Keep an exported note in step with the stored transcript
+    let segment = record.transcript[index].clone();
+    let note_path = refresh_note(&state.config, record).await;
+
+    (
+        StatusCode::OK,
+        Json(EditSegmentResponse {
+            meeting_id,
+            segment,
+            edit,
+            note_path,
+        }),
+    )
+        .into_response()
+}
+
+/// GET /meetings/:meeting_id/transcript/edits
+/// Get the manual correction history of a stored transcript
+pub async fn get_transcript_edits(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> impl IntoResponse {
+    match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => (StatusCode::OK, Json(record.edits)).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// GET /meetings/:meeting_id/markers
+/// Get timeline markers (gaps, chapters, etc.) recorded so far, or stored once stopped
+pub async fn get_meeting_markers(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
 ) -> impl IntoResponse {
     let sessions = state.sessions.read().await;
 
-    match sessions.get(&meeting_id) {
-        Some(session) => {
-            let transcript: Vec<TranscriptSegment> = session.get_transcript().await;
-            (StatusCode::OK, Json(transcript)).into_response()
-        }
-        None => (
+    if let Some(session) = sessions.get(&meeting_id) {
+        let markers: Vec<Marker> = session.get_markers().await;
+        return (StatusCode::OK, Json(markers)).into_response();
+    }
+
+    // Fall back to stored meetings
+    match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => (StatusCode::OK, Json(record.markers)).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// POST /meetings/:meeting_id/sources/:source/mute
+/// Replace a live session source's audio with silence, keeping its timing
+pub async fn mute_source(
+    State(state): State<AppState>,
+    Path((meeting_id, source)): Path<(String, String)>,
+) -> impl IntoResponse {
+    set_source_muted(&state, meeting_id, &source, true).await
+}
+
+/// POST /meetings/:meeting_id/sources/:source/unmute
+/// Let a muted source through again, marking the span it was muted for
+pub async fn unmute_source(
+    State(state): State<AppState>,
+    Path((meeting_id, source)): Path<(String, String)>,
+) -> impl IntoResponse {
+    set_source_muted(&state, meeting_id, &source, false).await
+}
+
+async fn set_source_muted(
+    state: &AppState,
+    meeting_id: String,
+    source: &str,
+    muted: bool,
+) -> Response {
+    let source: AudioStreamSource = match source.parse() {
+        Ok(source) => source,
+        Err(e) => return bad_request(format!("{:#}", e)),
+    };
+
+    let Some(session) = state.sessions.read().await.get(&meeting_id).cloned() else {
+        return (
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
-                error: format!("Meeting {} not found", meeting_id),
+                error: format!("Meeting {} is not recording", meeting_id),
+            }),
+        )
+            .into_response();
+    };
+
+    let changed = if muted {
+        session.mute_source(source).await
+    } else {
+        session.unmute_source(source).await
+    };
+
+    match changed {
+        Ok(changed) => (
+            StatusCode::OK,
+            Json(SourceMuteResponse {
+                meeting_id,
+                source: source.to_string(),
+                muted,
+                changed,
             }),
         )
             .into_response(),
+        Err(e) => bad_request(format!("{:#}", e)),
     }
 }
 
-/// GET /health
-/// Health check endpoint
-pub async fn health_check() -> impl IntoResponse {
+/// POST /meetings/:meeting_id/bookmark
+/// Mark what was just said, saving the audio leading up to it as a
+/// highlight clip
+pub async fn bookmark_meeting(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+    Query(query): Query<BookmarkQuery>,
+) -> Response {
+    let Some(session) = state.sessions.read().await.get(&meeting_id).cloned() else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Meeting {} is not recording", meeting_id),
+            }),
+        )
+            .into_response();
+    };
+
+    match session
+        .bookmark(query.seconds.map(Duration::from_secs), query.label)
+        .await
+    {
+        Ok(bookmark) => (StatusCode::CREATED, Json(bookmark)).into_response(),
+        Err(e) => {
+            error!("Failed to bookmark meeting {}: {:#}", meeting_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to bookmark: {:#}", e),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /meetings/:meeting_id/analytics
+/// Get talk time per speaker and the longest monologues, so far for a live
+/// session or from the stored transcript once stopped
+pub async fn get_meeting_analytics(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> impl IntoResponse {
+    let sessions = state.sessions.read().await;
+
+    if let Some(session) = sessions.get(&meeting_id) {
+        let stats = match session.get_stats().await {
+            Ok(stats) => stats,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: format!("Failed to get stats: {}", e),
+                    }),
+                )
+                    .into_response()
+            }
+        };
+        let analytics = meeting_analytics(
+            stats.started_at,
+            stats.duration_secs,
+            &session.get_transcript().await,
+        );
+        return (StatusCode::OK, Json(analytics)).into_response();
+    }
+
+    match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => (
+            StatusCode::OK,
+            Json(meeting_analytics(
+                record.started_at,
+                record.stats.duration_secs,
+                &record.transcript,
+            )),
+        )
+            .into_response(),
+        Err(response) => response,
+    }
+}
+
+/// GET /meetings/:meeting_id/chapters
+/// Get the chapters detected in a stored meeting
+pub async fn get_meeting_chapters(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> impl IntoResponse {
+    match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => (StatusCode::OK, Json(chapter_markers(&record))).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// POST /meetings/:meeting_id/chapters
+/// Detect chapters again (e.g. after edits or re-transcription)
+pub async fn detect_meeting_chapters(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> impl IntoResponse {
+    if state.sessions.read().await.contains_key(&meeting_id) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Meeting {} is still recording", meeting_id),
+            }),
+        )
+            .into_response();
+    }
+
+    let mut record = match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+
+    let chapters = detect_chapters(
+        &state.config.chapters,
+        record.started_at,
+        &record.transcript,
+    )
+    .await;
+    apply_chapters(&mut record.markers, record.started_at, chapters);
+
+    if let Err(e) = state.storage.save_meeting(&record).await {
+        error!("Failed to save meeting {}: {}", meeting_id, e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to save meeting: {}", e),
+            }),
+        )
+            .into_response();
+    }
+
+    let chapters = chapter_markers(&record);
+    info!(
+        "Detected {} chapters in meeting {}",
+        chapters.len(),
+        meeting_id
+    );
+    let note_path = refresh_note(&state.config, record).await;
+
+    (
+        StatusCode::OK,
+        Json(ChaptersResponse {
+            meeting_id,
+            chapters,
+            note_path,
+        }),
+    )
+        .into_response()
+}
+
+/// GET /meetings/:meeting_id/events
+/// Stream live session events over a WebSocket
+///
+/// For stopped and queued meetings this streams service-side events instead
+/// (e.g. bundle export progress, or a queued session starting).
+pub async fn meeting_events(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    {
+        let sessions = state.sessions.read().await;
+        if let Some(session) = sessions.get(&meeting_id) {
+            let events = session.subscribe_events();
+            return ws
+                .on_upgrade(move |socket| forward_events(socket, events, Some))
+                .into_response();
+        }
+    }
+
+    // Queued meetings report when they start (or fail to)
+    if state.slots.queue_position(&meeting_id).is_none() {
+        if let Err(response) = load_stored_meeting(&state, &meeting_id).await {
+            return response;
+        }
+    }
+
+    let events = state.meeting_events.subscribe();
+    ws.on_upgrade(move |socket| {
+        forward_events(socket, events, move |e: MeetingEvent| {
+            (e.meeting_id == meeting_id).then_some(e.event)
+        })
+    })
+    .into_response()
+}
+
+/// POST /meetings/:meeting_id/companion
+/// Pair a companion device (e.g. a phone) with a live session
+///
+/// Returns the session's pairing token and the capture page and ingest
+/// WebSocket paths carrying it. The token stays the same for the rest of
+/// the session.
+pub async fn pair_companion(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> Response {
+    let session = match recording_session(&state, &meeting_id).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+    let token = match session.pair_companion() {
+        Ok(token) => token,
+        Err(e) => return bad_request(format!("{:#}", e)),
+    };
+
+    info!("Companion pairing issued for meeting {}", meeting_id);
+    (
+        StatusCode::OK,
+        Json(CompanionPairingResponse {
+            page_url: format!("/meetings/{}/companion?token={}", meeting_id, token),
+            ingest_url: format!("/meetings/{}/ingest?token={}", meeting_id, token),
+            meeting_id,
+            token,
+        }),
+    )
+        .into_response()
+}
+
+/// GET /meetings/:meeting_id/companion?token=
+/// Page a paired device's browser opens to stream its microphone into the
+/// session
+///
+/// Browsers only allow microphone access on HTTPS (or localhost) pages, so
+/// a phone reaches this through an HTTPS proxy in front of the server.
+pub async fn companion_page(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+    Query(pairing): Query<PairingQuery>,
+) -> Response {
+    let session = match recording_session(&state, &meeting_id).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+    if !session.is_paired_companion(&pairing.token) {
+        return unpaired(&meeting_id);
+    }
+    Html(COMPANION_PAGE).into_response()
+}
+
+/// GET /meetings/:meeting_id/ingest?token=
+/// Stream a companion device's audio (e.g. from a phone's browser) into a
+/// live session as an additional microphone, over a WebSocket
+///
+/// The device must present the session's pairing token. The query also
+/// sets the audio format (`sample_rate`, `channels`, `codec`, `gain`). Each
+/// binary message is little-endian 16-bit PCM or one Opus packet. Once
+/// connected, the server sends the source the device is mixed as (e.g.
+/// `{"source":"mic-1"}`), which can be muted like any other.
+pub async fn companion_ingest(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+    Query(pairing): Query<PairingQuery>,
+    Query(format): Query<CompanionFormat>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let session = match recording_session(&state, &meeting_id).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+    if !session.is_paired_companion(&pairing.token) {
+        warn!(
+            "Refused unpaired companion device for meeting {}",
+            meeting_id
+        );
+        return unpaired(&meeting_id);
+    }
+
+    let input = match session.join_companion(&format) {
+        Ok(input) => input,
+        Err(e) => return bad_request(format!("{:#}", e)),
+    };
+    ws.on_upgrade(move |socket| forward_companion_audio(socket, input))
+        .into_response()
+}
+
+/// The live session recording `meeting_id`, or a 404 response
+async fn recording_session(
+    state: &AppState,
+    meeting_id: &str,
+) -> Result<Arc<RecordingSession>, Response> {
+    state
+        .sessions
+        .read()
+        .await
+        .get(meeting_id)
+        .cloned()
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Meeting {} is not recording", meeting_id),
+                }),
+            )
+                .into_response()
+        })
+}
+
+/// Response to a companion device without the session's pairing token
+fn unpaired(meeting_id: &str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ErrorResponse {
+            error: format!(
+                "Pair the device with POST /meetings/{}/companion first",
+                meeting_id
+            ),
+        }),
+    )
+        .into_response()
+}
+
+/// Pass a companion device's audio messages to its session until either
+/// side stops
+async fn forward_companion_audio(mut socket: WebSocket, mut input: CompanionInput) {
+    let joined = serde_json::json!({ "source": input.source().to_string() }).to_string();
+    if socket.send(Message::Text(joined)).await.is_err() {
+        return;
+    }
+
+    while let Some(Ok(message)) = socket.recv().await {
+        let audio = match message {
+            Message::Binary(audio) => audio,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        if let Err(e) = input.send(&audio) {
+            warn!("Disconnecting companion device {}: {:#}", input.source(), e);
+            let close = CloseFrame {
+                code: close_code::POLICY,
+                reason: format!("{:#}", e).into(),
+            };
+            let _ = socket.send(Message::Close(Some(close))).await;
+            break;
+        }
+    }
+}
+
+/// Forward events to a WebSocket client as JSON text messages
+///
+/// `select` maps each received event to the session event to send, or `None` to skip it.
+async fn forward_events<T: Clone>(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<T>,
+    select: impl Fn(T) -> Option<SessionEvent>,
+) {
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                let Some(event) = select(event) else {
+                    continue;
+                };
+
+                let json = match serde_json::to_string(&event) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        error!("Failed to serialize session event: {}", e);
+                        continue;
+                    }
+                };
+
+                if socket.send(Message::Text(json)).await.is_err() {
+                    break; // Client disconnected
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("WebSocket client lagging, skipped {} events", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => break, // Session ended
+        }
+    }
+}
+
+/// POST /meetings/:meeting_id/bundle
+/// Export a stored meeting as a zip bundle
+///
+/// The archive is streamed as it is written; progress is published on the
+/// meeting's events WebSocket.
+pub async fn export_bundle(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> impl IntoResponse {
+    if state.sessions.read().await.contains_key(&meeting_id) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Meeting {} is still recording", meeting_id),
+            }),
+        )
+            .into_response();
+    }
+
+    let record = match load_stored_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+
+    info!("Exporting bundle for meeting: {}", meeting_id);
+
+    let (tx, rx) = mpsc::channel(BUNDLE_CHANNEL_CAPACITY);
+    let events = state.meeting_events.clone();
+    let bundle_id = meeting_id.clone();
+    let format = state.config.note_format(record.profile.as_deref());
+    let note = match render_meeting_note(&state.config.obsidian, &format, &record) {
+        Ok(note) => note,
+        Err(e) => {
+            error!("Failed to render note for {}: {:#}", meeting_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to render note: {:#}", e),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let created_at = state.clock.now();
+    tokio::task::spawn_blocking(move || {
+        let writer = BufWriter::with_capacity(BUNDLE_BUFFER_SIZE, ChannelWriter { tx: tx.clone() });
+
+        let result = write_bundle(&record, &note, created_at, writer, |progress| {
+            // No subscribers is not an error
+            let _ = events.send(MeetingEvent {
+                meeting_id: bundle_id.clone(),
+                event: SessionEvent::BundleProgress {
+                    file: progress.file,
+                    files_done: progress.files_done,
+                    files_total: progress.files_total,
+                },
+            });
+        });
+
+        match result {
+            Ok(manifest) => info!(
+                "Bundle for meeting {} complete ({} files)",
+                bundle_id,
+                manifest.files.len()
+            ),
+            Err(e) => {
+                error!("Failed to export bundle for {}: {}", bundle_id, e);
+                // Abort the response so the client sees a failed download
+                let _ = tx.blocking_send(Err(io::Error::other(e.to_string())));
+            }
+        }
+    });
+
+    let body = Body::from_stream(futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    }));
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"{}.zip\"",
+                    meeting_id.replace('"', "_")
+                ),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// GET /meetings/:meeting_id/audio
+/// Download a stored meeting's audio as one WAV file
+///
+/// The chunks are joined in order and, unless `normalize=false`, brought to
+/// the configured integrated loudness (EBU R128), so shared recordings play
+/// at a consistent volume. What was measured is reported in `X-Loudness-*`
+/// headers.
+pub async fn export_meeting_audio(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+    Query(query): Query<AudioExportQuery>,
+) -> impl IntoResponse {
+    if state.sessions.read().await.contains_key(&meeting_id) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Meeting {} is still recording", meeting_id),
+            }),
+        )
+            .into_response();
+    }
+
+    let record = match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+    if record.chunks.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Meeting {} has no stored audio", meeting_id),
+            }),
+        )
+            .into_response();
+    }
+
+    let loudness = state.config.audio.loudness.clone();
+    let normalize = query.normalize.unwrap_or(loudness.enabled);
+
+    // Decode, measure, and encode off the async runtime
+    let exported = tokio::task::spawn_blocking(move || {
+        let mut audio = MeetingAudio::load(&record)?;
+        let report = normalize.then(|| audio.normalize(&loudness));
+        anyhow::Ok((audio.to_wav()?, report))
+    })
+    .await;
+    let (wav, report) = match exported {
+        Ok(Ok(exported)) => exported,
+        Ok(Err(e)) => {
+            error!("Failed to export audio for {}: {:#}", meeting_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to export audio: {:#}", e),
+                }),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!("Audio export task failed: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let mut response = (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "audio/wav".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"{}.wav\"",
+                    meeting_id.replace('"', "_")
+                ),
+            ),
+        ],
+        wav,
+    )
+        .into_response();
+
+    if let Some(report) = report {
+        let headers = response.headers_mut();
+        if let Some(measured) = report.measured_lufs {
+            info!(
+                "Exported audio for {}: {:.1} LUFS, {:+.1} dB gain",
+                meeting_id, measured, report.gain_db
+            );
+            if let Ok(value) = header::HeaderValue::from_str(&format!("{:.1}", measured)) {
+                headers.insert("x-loudness-measured-lufs", value);
+            }
+        }
+        if let Ok(value) = header::HeaderValue::from_str(&format!("{:.1}", report.gain_db)) {
+            headers.insert("x-loudness-gain-db", value);
+        }
+    }
+    response
+}
+
+/// POST /meetings/:meeting_id/note
+/// Write a stored meeting's note into the Obsidian vault
+///
+/// Depending on `obsidian.export_mode` this writes the meeting's own note or
+/// its section of the daily note. Re-running the export replaces the
+/// previous output rather than duplicating it.
+pub async fn export_note(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> impl IntoResponse {
+    let record = match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+
+    info!("Exporting note for meeting: {}", meeting_id);
+
+    let hooks = HookContext::for_meeting(&state.config, &record);
+    let mut index = saved_meeting_index(state.storage.as_ref(), &record);
+    let config = Arc::clone(&state.config);
+    let exported = tokio::task::spawn_blocking(move || {
+        let format = config.note_format(record.profile.as_deref());
+        export_meeting_note(&config.obsidian, &format, &record)
+    })
+    .await;
+
+    match exported {
+        Ok(Ok(path)) => {
+            after_note_exported(&state.config.obsidian.after_export, &meeting_id, &path).await;
+            index.note_path = Some(path.clone());
+            write_meeting_index(&state.config, &index);
+            spawn_hooks(
+                &state.config.hooks,
+                HookEvent::Export,
+                HookContext {
+                    note_path: Some(path.clone()),
+                    ..hooks
+                },
+            );
+            (
+                StatusCode::OK,
+                Json(NoteExportResponse {
+                    meeting_id,
+                    status: "exported".to_string(),
+                    note_path: path.display().to_string(),
+                }),
+            )
+                .into_response()
+        }
+        Ok(Err(e)) => {
+            error!("Failed to export note for {}: {:#}", meeting_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to export note: {:#}", e),
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!("Note export task panicked: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Note export failed".to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /meetings/:meeting_id/sealed
+/// Get a meeting's encrypted content, to be decrypted by the client
+pub async fn get_sealed_content(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> impl IntoResponse {
+    let record = match load_stored_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+
+    match record.sealed {
+        Some(sealed) => (StatusCode::OK, Json(sealed)).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Meeting {} is not encrypted", meeting_id),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /meetings/:meeting_id/diff/:other_id
+/// Compare two stored transcripts word by word
+///
+/// The first meeting is the reference, e.g. the transcript from the current
+/// STT configuration, and the second the one being evaluated.
+pub async fn diff_meetings(
+    State(state): State<AppState>,
+    Path((meeting_id, other_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let a = match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+    let b = match load_readable_meeting(&state, &other_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+
+    let diff = diff_transcripts(a.started_at, &a.transcript, b.started_at, &b.transcript);
+    (
+        StatusCode::OK,
+        Json(DiffResponse {
+            meeting_a: meeting_id,
+            meeting_b: other_id,
+            diff,
+        }),
+    )
+        .into_response()
+}
+
+/// POST /meetings/:meeting_id/extend
+/// Push back when a meeting started with a TTL is deleted
+pub async fn extend_meeting(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+    Json(req): Json<ExtendRequest>,
+) -> impl IntoResponse {
+    let mut record = match load_stored_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+    let Some(expires_at) = record.expires_at else {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Meeting {} has no expiry", meeting_id),
+            }),
+        )
+            .into_response();
+    };
+
+    let Some(expires_at) = i64::try_from(req.extend_secs)
+        .ok()
+        .and_then(chrono::Duration::try_seconds)
+        .and_then(|extend| expires_at.max(state.clock.now()).checked_add_signed(extend))
+    else {
+        return bad_request(format!("extend_secs is too large: {}", req.extend_secs));
+    };
+    record.expires_at = Some(expires_at);
+
+    if let Err(e) = state.storage.save_meeting(&record).await {
+        error!("Failed to save meeting {}: {}", meeting_id, e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to save meeting: {}", e),
+            }),
+        )
+            .into_response();
+    }
+
+    info!("Meeting {} now expires at {}", meeting_id, expires_at);
+    (
+        StatusCode::OK,
+        Json(ExtendResponse {
+            meeting_id,
+            expires_at,
+        }),
+    )
+        .into_response()
+}
+
+/// GET /meetings/:meeting_id/verify
+/// Check a stored meeting's audio chunks against their checksums
+///
+/// Each chunk's file is hashed again and compared with the SHA-256 stored
+/// when it was written, reporting corrupted and missing files (e.g. after
+/// syncing recordings between machines).
+pub async fn verify_meeting(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+) -> impl IntoResponse {
+    let record = match load_stored_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+
+    match tokio::task::spawn_blocking(move || IntegrityReport::check(&record)).await {
+        Ok(report) => {
+            if !report.intact {
+                warn!(
+                    "Meeting {} has {} corrupted and {} missing audio chunks",
+                    meeting_id, report.corrupted, report.missing
+                );
+            }
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            error!("Verification task panicked: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Verification failed".to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// POST /meetings/:meeting_id/retranscribe
+/// Re-run STT over a time range (or chunks) of a stored meeting
+///
+/// The stored audio for the range is sent through the STT pipeline again
+/// and the range's transcript segments are replaced with the results.
+pub async fn retranscribe_meeting(
+    State(state): State<AppState>,
+    Path(meeting_id): Path<String>,
+    Json(req): Json<RetranscribeRequest>,
+) -> impl IntoResponse {
+    if state.sessions.read().await.contains_key(&meeting_id) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Meeting {} is still recording", meeting_id),
+            }),
+        )
+            .into_response();
+    }
+
+    let mut record = match load_readable_meeting(&state, &meeting_id).await {
+        Ok(record) => record,
+        Err(response) => return response,
+    };
+    if record.stats.audio_retention.privacy {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!(
+                    "Meeting {} was recorded in privacy mode; no audio was kept",
+                    meeting_id
+                ),
+            }),
+        )
+            .into_response();
+    }
+    if record.stats.audio_retention.transcribe_only {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!(
+                    "Meeting {} was recorded transcribe-only; no audio was kept",
+                    meeting_id
+                ),
+            }),
+        )
+            .into_response();
+    }
+
+    let range = match (req.start_secs, req.end_secs, req.chunks.is_empty()) {
+        (Some(start), Some(end), true) if start >= 0.0 => {
+            TimeRange::new((start * 1000.0) as u64, (end * 1000.0) as u64)
+        }
+        (None, None, false) => TimeRange::from_chunks(&record, &req.chunks),
+        _ => Err(anyhow::anyhow!(
+            "Give either start_secs and end_secs, or chunks"
+        )),
+    };
+    let range = match range {
+        Ok(range) => range,
+        Err(e) => return bad_request(e.to_string()),
+    };
+
+    info!(
+        "Re-transcribing meeting {} ({}ms-{}ms)",
+        meeting_id, range.start_ms, range.end_ms
+    );
+
+    // Decode the stored audio off the async runtime
+    let loaded = {
+        let record = record.clone();
+        tokio::task::spawn_blocking(move || load_range_audio(&record, range)).await
+    };
+    let pcm = match loaded {
+        Ok(Ok(pcm)) => pcm,
+        Ok(Err(e)) => return bad_request(format!("{:#}", e)),
+        Err(e) => {
+            error!("Audio loading task panicked: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Failed to load audio".to_string(),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let segments = match retranscribe(NATS_URL, &record, range, &pcm, req.model.as_deref()).await {
+        Ok(segments) => filter_transcript(&state, segments),
+        Err(e) => {
+            error!("Failed to re-transcribe {}: {:#}", meeting_id, e);
+            return (
+                StatusCode::BAD_GATEWAY,
+                Json(ErrorResponse {
+                    error: format!("Re-transcription failed: {:#}", e),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let started_at = record.started_at;
+    let replaced_segments =
+        replace_range(&mut record.transcript, started_at, range, segments.clone());
+    record.stats.transcript_segments_count = record.transcript.len();
+
+    if let Err(e) = state.storage.save_meeting(&record).await {
+        error!("Failed to save meeting {}: {}", meeting_id, e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to save meeting: {}", e),
+            }),
+        )
+            .into_response();
+    }
+
+    info!(
+        "Replaced {} segments of meeting {} with {}",
+        replaced_segments,
+        meeting_id,
+        segments.len()
+    );
+
+    (
+        StatusCode::OK,
+        Json(RetranscribeResponse {
+            meeting_id,
+            status: "retranscribed".to_string(),
+            range,
+            replaced_segments,
+            segments,
+        }),
+    )
+        .into_response()
+}
+
+/// Check that every extracted chunk is audio we can decode
+///
+/// Sealed chunks are skipped: only the client can decrypt them.
+fn probe_chunks(record: &MeetingRecord) -> anyhow::Result<()> {
+    if record.sealed.is_some() {
+        return Ok(());
+    }
+    for chunk in &record.chunks {
+        let info = AudioFile::probe_track(&chunk.file_path, chunk.track.unwrap_or(0))
+            .with_context(|| format!("Chunk {}", chunk.chunk_index))?;
+        if info.sample_rate == 0 || info.channels == 0 {
+            anyhow::bail!(
+                "Chunk {} has no audio ({}Hz, {} channels)",
+                chunk.chunk_index,
+                info.sample_rate,
+                info.channels
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A directory removed, with everything in it, once the request is done
+/// with it
+struct ScratchDir(PathBuf);
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Move a meeting directory's files, but not its lock, manifest, or index,
+/// from `from` into `to`
+fn move_meeting_files(from: &std::path::Path, to: &std::path::Path) -> anyhow::Result<()> {
+    let entries =
+        std::fs::read_dir(from).with_context(|| format!("Failed to read {}", from.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        if is_workdir_file(&entry.file_name()) {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        std::fs::rename(entry.path(), &target).with_context(|| {
+            format!(
+                "Failed to move {} to {}",
+                entry.path().display(),
+                target.display()
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// An imported bundle's files swapped into a meeting's directory, held by
+/// `lock`; the files they replaced are set aside until the import is saved
+/// and deleted on drop
+struct SwappedImport {
+    lock: MeetingDirLock,
+    replaced: ScratchDir,
+}
+
+impl SwappedImport {
+    /// Set the directory's files aside in `replaced` (on the same
+    /// filesystem) and move the files extracted into `staging` in
+    fn swap(
+        staging: &std::path::Path,
+        lock: MeetingDirLock,
+        replaced: PathBuf,
+    ) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(&replaced)
+            .with_context(|| format!("Failed to create {}", replaced.display()))?;
+        let swapped = Self {
+            lock,
+            replaced: ScratchDir(replaced),
+        };
+        let moved = move_meeting_files(swapped.lock.dir(), &swapped.replaced.0)
+            .and_then(|()| move_meeting_files(staging, swapped.lock.dir()));
+        if let Err(e) = moved {
+            swapped.restore();
+            return Err(e);
+        }
+        Ok(swapped)
+    }
+
+    /// Put the replaced files back in place of the imported ones
+    fn restore(self) {
+        let dir = self.lock.dir();
+        let restored = std::fs::read_dir(dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|entries| {
+                for entry in entries.flatten() {
+                    if !is_workdir_file(&entry.file_name()) {
+                        let path = entry.path();
+                        let _ =
+                            std::fs::remove_file(&path).or_else(|_| std::fs::remove_dir_all(&path));
+                    }
+                }
+                move_meeting_files(&self.replaced.0, dir)
+            });
+        if let Err(e) = restored {
+            // Left in place rather than deleted with the scratch directory
+            error!(
+                "Failed to restore {} after a failed import; its files are in {}: {:#}",
+                dir.display(),
+                self.replaced.0.display(),
+                e
+            );
+            std::mem::forget(self.replaced);
+        }
+    }
+}
+
+/// POST /meetings/import
+/// Restore a meeting from an uploaded bundle (multipart field "bundle")
+///
+/// The bundle's audio is extracted and checked in a staging directory,
+/// then swapped into the meeting's directory while holding its lock, so a
+/// failed import leaves an overwritten meeting's audio as it was.
+pub async fn import_bundle(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    // Write the bundle upload to a temporary file as it arrives, rather
+    // than holding up to a gigabyte in memory
+    let upload =
+        TempUpload(std::env::temp_dir().join(format!("loqa-import-{}.zip", uuid::Uuid::new_v4())));
+    loop {
+        match multipart.next_field().await {
+            Ok(Some(field)) if field.name() == Some("bundle") => {
+                match write_field(field, &upload.0).await {
+                    Ok(()) => break,
+                    Err(e) => return bad_request(format!("Failed to read bundle upload: {:#}", e)),
+                }
+            }
+            Ok(Some(_)) => continue,
+            Ok(None) => return bad_request("Missing multipart field \"bundle\"".to_string()),
+            Err(e) => return bad_request(format!("Invalid multipart request: {}", e)),
+        }
+    }
+
+    let reader = match std::fs::File::open(&upload.0)
+        .context("Failed to open the bundle upload")
+        .and_then(|file| BundleReader::open(io::BufReader::new(file)))
+    {
+        Ok(reader) => reader,
+        Err(e) => return bad_request(format!("Invalid bundle: {:#}", e)),
+    };
+
+    let meeting_id = reader.record.meeting_id.clone();
+    if let Err(e) = validate_new_meeting_id(&meeting_id) {
+        return bad_request(e.to_string());
+    }
+
+    info!("Importing bundle for meeting: {}", meeting_id);
+
+    // Refuse to clobber a live session, or a stored meeting unless asked to
+    let exists = match state.storage.get_meeting(&meeting_id).await {
+        Ok(existing) => existing.is_some(),
+        Err(e) => {
+            error!("Failed to check for meeting {}: {}", meeting_id, e);
+            false
+        }
+    };
+    if state.sessions.read().await.contains_key(&meeting_id) || (exists && !query.overwrite) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Meeting {} already exists", meeting_id),
+            }),
+        )
+            .into_response();
+    }
+
+    // Extract audio and write the note off the async runtime
+    let config = Arc::clone(&state.config);
+    let restored = tokio::task::spawn_blocking(move || {
+        let recordings = config.audio.resolved_recordings_path();
+        std::fs::create_dir_all(&recordings)
+            .with_context(|| format!("Failed to create {}", recordings.display()))?;
+        // Next to the meeting's directory, so swapping in is only renames
+        let import_id = uuid::Uuid::new_v4().simple();
+        let staging = ScratchDir(recordings.join(format!(".import-{}", import_id)));
+        let mut record = reader.extract(&staging.0)?;
+        if let Err(e) = probe_chunks(&record) {
+            return Ok(Err(e));
+        }
+
+        let lock =
+            MeetingDirLock::acquire(&recordings.join(&record.meeting_id), &record.meeting_id)?;
+        let replaced = recordings.join(format!(".replaced-{}", import_id));
+        let swapped = SwappedImport::swap(&staging.0, lock, replaced)?;
+        for chunk in &mut record.chunks {
+            if let Some(name) = chunk.file_path.file_name() {
+                chunk.file_path = swapped.lock.dir().join(name);
+            }
+        }
+        if let Err(e) = swapped.lock.update_manifest(&record.chunks) {
+            warn!(
+                "Failed to update manifest of {}: {:#}",
+                record.meeting_id, e
+            );
+        }
+
+        let format = config.note_format(record.profile.as_deref());
+        let note_path = match export_meeting_note(&config.obsidian, &format, &record) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                warn!("Failed to write note for {}: {}", record.meeting_id, e);
+                None
+            }
+        };
+        anyhow::Ok(Ok((record, note_path, swapped)))
+    })
+    .await;
+
+    let (record, note_path, swapped) = match restored {
+        Ok(Ok(Ok(restored))) => restored,
+        Ok(Ok(Err(e))) => return bad_request(format!("Unsupported bundle audio: {:#}", e)),
+        Ok(Err(e)) if e.is::<DirInUse>() => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    error: format!("{:#}", e),
+                }),
+            )
+                .into_response();
+        }
+        Ok(Err(e)) => {
+            error!("Failed to import bundle for {}: {}", meeting_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to import bundle: {:#}", e),
+                }),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!("Bundle import task panicked: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Bundle import failed".to_string(),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    if let Err(e) = state.storage.save_meeting(&record).await {
+        error!("Failed to save imported meeting {}: {}", meeting_id, e);
+        let _ = tokio::task::spawn_blocking(move || swapped.restore()).await;
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to save meeting: {}", e),
+            }),
+        )
+            .into_response();
+    }
+
+    info!("Imported meeting {}", meeting_id);
+    // Replaced files deleted and the lock released
+    drop(swapped);
+    write_meeting_index(
+        &state.config,
+        &MeetingIndex {
+            note_path: note_path.clone(),
+            ..saved_meeting_index(state.storage.as_ref(), &record)
+        },
+    );
+
+    if let Some(path) = &note_path {
+        after_note_exported(&state.config.obsidian.after_export, &meeting_id, path).await;
+        spawn_hooks(
+            &state.config.hooks,
+            HookEvent::Export,
+            HookContext {
+                note_path: Some(path.clone()),
+                ..HookContext::for_meeting(&state.config, &record)
+            },
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(ImportResponse {
+            meeting_id,
+            status: "imported".to_string(),
+            chunks_count: record.chunks.len(),
+            note_path: note_path.map(|path| path.display().to_string()),
+        }),
+    )
+        .into_response()
+}
+
+/// POST /meetings/upload
+/// Transcribe an uploaded recording (multipart field "recording")
+///
+/// Accepts audio files and video containers (MP4, MOV, MKV); the audio
+/// track is extracted and the video ignored. The upload is kept as the
+/// meeting's only audio chunk, so it can be re-transcribed later. The
+/// meeting starts at the time of the upload. Long recordings can be split
+/// into overlapping windows transcribed in parallel (`parallel`).
+pub async fn upload_recording(
+    State(state): State<AppState>,
+    Query(query): Query<UploadQuery>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let meeting_id = match (query.meeting_id.clone(), &query.title) {
+        (Some(meeting_id), _) => meeting_id,
+        (None, Some(title)) => state.generate_meeting_id(title).await,
+        (None, None) => uuid::Uuid::new_v4().to_string(),
+    };
+    if let Err(e) = validate_new_meeting_id(&meeting_id) {
+        return bad_request(e.to_string());
+    }
+
+    let exists = match state.storage.get_meeting(&meeting_id).await {
+        Ok(existing) => existing.is_some(),
+        Err(e) => {
+            error!("Failed to check for meeting {}: {}", meeting_id, e);
+            false
+        }
+    };
+    if exists || state.sessions.read().await.contains_key(&meeting_id) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Meeting {} already exists", meeting_id),
+            }),
+        )
+            .into_response();
+    }
+
+    // Hold the meeting's directory until the upload is stored, so another
+    // instance writing the same meeting is refused rather than clobbered
+    let audio_dir = state
+        .config
+        .audio
+        .resolved_recordings_path()
+        .join(&meeting_id);
+    let lock = match MeetingDirLock::acquire(&audio_dir, &meeting_id) {
+        Ok(lock) => lock,
+        Err(e) => {
+            error!("{:#}", e);
+            let status = if e.is::<DirInUse>() {
+                StatusCode::CONFLICT
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            return (
+                status,
+                Json(ErrorResponse {
+                    error: format!("{:#}", e),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    // Write the upload to disk as it arrives
+    let upload = async {
+        loop {
+            match multipart.next_field().await {
+                Ok(Some(field)) if field.name() == Some("recording") => {
+                    return save_upload(field, &audio_dir)
+                        .await
+                        .map_err(|e| format!("Failed to read recording upload: {:#}", e));
+                }
+                Ok(Some(_)) => continue,
+                Ok(None) => return Err("Missing multipart field \"recording\"".to_string()),
+                Err(e) => return Err(format!("Invalid multipart request: {}", e)),
+            }
+        }
+    };
+    let path = match upload.await {
+        Ok(path) => path,
+        Err(e) => {
+            // Leave nothing behind for a recording that never arrived
+            let _ = tokio::fs::remove_dir_all(&audio_dir).await;
+            return bad_request(e);
+        }
+    };
+
+    match transcribe_upload(&state, &meeting_id, &query, path, lock).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err((status, error)) => (status, Json(ErrorResponse { error })).into_response(),
+    }
+}
+
+/// Transcribe a recording saved at `path` in the meeting's directory
+/// (held by `lock`) and store it as a new meeting
+///
+/// Shared by uploads and the ingest inbox. The meeting's directory is
+/// removed if the recording can't be transcribed.
+pub(crate) async fn transcribe_upload(
+    state: &AppState,
+    meeting_id: &str,
+    query: &UploadQuery,
+    path: PathBuf,
+    lock: MeetingDirLock,
+) -> Result<UploadResponse, (StatusCode, String)> {
+    let audio_dir = lock.dir().to_path_buf();
+    info!(
+        "Transcribing upload {} as meeting {}",
+        path.display(),
+        meeting_id
+    );
+
+    // Check the format before decoding, then decode off the async runtime
+    let track = query.track;
+    let decoded = tokio::task::spawn_blocking(move || {
+        let info = AudioFile::probe_track(&path, track)?;
+        let audio = AudioFile::open_track(&path, track)?;
+        let frames = audio.samples.len() as u64 / audio.channels.max(1) as u64;
+        let sha256 = file_sha256(&path)?;
+        let chunk = ChunkMetadata {
+            chunk_index: 0,
+            file_path: path,
+            start_ms: 0,
+            end_ms: frames * 1000 / audio.sample_rate as u64,
+            sample_rate: audio.sample_rate,
+            channels: audio.channels,
+            sample_count: audio.samples.len(),
+            track: Some(track),
+            sha256: Some(sha256),
+            channel_labels: Vec::new(),
+            overlap_ms: 0,
+        };
+        anyhow::Ok((info, chunk, stt_audio(&audio)))
+    })
+    .await;
+    let (audio, chunk, pcm) = match decoded {
+        Ok(Ok(decoded)) => decoded,
+        Ok(Err(e)) => {
+            let _ = tokio::fs::remove_dir_all(&audio_dir).await;
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported recording: {:#}", e),
+            ));
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_dir_all(&audio_dir).await;
+            error!("Upload decoding task panicked: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to decode recording".to_string(),
+            ));
+        }
+    };
+
+    let duration_ms = chunk.end_ms;
+    let range = match TimeRange::new(0, duration_ms) {
+        Ok(range) => range,
+        Err(_) => {
+            let _ = tokio::fs::remove_dir_all(&audio_dir).await;
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Recording has no audio".to_string(),
+            ));
+        }
+    };
+
+    let started_at = state.meetings.clock.now();
+    let audio_retention = AudioRetention::check(false, &audio_dir, started_at).unwrap_or_default();
+    let mut record = MeetingRecord {
+        meeting_id: meeting_id.to_string(),
+        title: query.title.clone(),
+        profile: None,
+        host: state
+            .config
+            .host
+            .report
+            .then(|| HostInfo::current(state.config.host.name.clone())),
+        started_at,
+        ended_at: started_at + chrono::Duration::milliseconds(duration_ms as i64),
+        stats: SessionStats {
+            is_recording: false,
+            state: SessionState::Stopped,
+            started_at,
+            duration_secs: duration_ms as f64 / 1000.0,
+            chunks_count: 1,
+            audio_bytes: audio_retention.audio_bytes,
+            current_chunk_percent: None,
+            transcript_segments_count: 0,
+            frame_drops: Default::default(),
+            warnings: Vec::new(),
+            audio_retention,
+        },
+        transcript: Vec::new(),
+        markers: Vec::new(),
+        chunks: vec![chunk],
+        edits: Vec::new(),
+        expires_at: None,
+        sealed: None,
+    };
+
+    let model = query.model.as_deref();
+    let windows = &state.config.uploads.windows;
+    let parallel = query.parallel.unwrap_or(state.config.uploads.parallel);
+    let (transcribed, window_count) = if parallel {
+        (
+            transcribe_windows(NATS_URL, &record, range, &pcm, model, windows).await,
+            plan_windows(range, windows).len(),
+        )
+    } else {
+        (retranscribe(NATS_URL, &record, range, &pcm, model).await, 1)
+    };
+    record.transcript = match transcribed {
+        Ok(segments) => filter_transcript(state, segments),
+        Err(e) => {
+            let _ = tokio::fs::remove_dir_all(&audio_dir).await;
+            error!("Failed to transcribe upload {}: {:#}", meeting_id, e);
+            return Err((
+                StatusCode::BAD_GATEWAY,
+                format!("Transcription failed: {:#}", e),
+            ));
+        }
+    };
+    record.stats.transcript_segments_count = record.transcript.len();
+
+    if let Err(e) = lock.update_manifest(&record.chunks) {
+        warn!("Failed to update recording manifest: {:#}", e);
+    }
+    if let Err(e) = state.storage.save_meeting(&record).await {
+        error!("Failed to save meeting {}: {}", meeting_id, e);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save meeting: {}", e),
+        ));
+    }
+
+    info!(
+        "Transcribed upload {} ({} segments)",
+        meeting_id,
+        record.transcript.len()
+    );
+
+    let note_path = {
+        let config = Arc::clone(&state.config);
+        let record = record.clone();
+        tokio::task::spawn_blocking(move || {
+            let format = config.note_format(record.profile.as_deref());
+            export_meeting_note(&config.obsidian, &format, &record)
+        })
+        .await
+    };
+    let note_path = match note_path {
+        Ok(Ok(path)) => {
+            after_note_exported(&state.config.obsidian.after_export, meeting_id, &path).await;
+            spawn_hooks(
+                &state.config.hooks,
+                HookEvent::Export,
+                HookContext {
+                    note_path: Some(path.clone()),
+                    ..HookContext::for_meeting(&state.config, &record)
+                },
+            );
+            Some(path)
+        }
+        Ok(Err(e)) => {
+            warn!("Failed to write note for {}: {}", meeting_id, e);
+            None
+        }
+        Err(e) => {
+            warn!("Note export task for {} panicked: {}", meeting_id, e);
+            None
+        }
+    };
+    write_meeting_index(
+        &state.config,
+        &MeetingIndex {
+            note_path: note_path.clone(),
+            ..saved_meeting_index(state.storage.as_ref(), &record)
+        },
+    );
+
+    Ok(UploadResponse {
+        meeting_id: meeting_id.to_string(),
+        status: "transcribed".to_string(),
+        audio,
+        segments_count: record.transcript.len(),
+        windows: window_count,
+        note_path: note_path.map(|path| path.display().to_string()),
+    })
+}
+
+/// Transcribed segments without the ones the transcript filter drops
+fn filter_transcript(state: &AppState, segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
+    match TranscriptFilter::from_config(&state.config.transcript_filter) {
+        Some(filter) => filter.apply(segments),
+        None => segments,
+    }
+}
+
+/// Stream an uploaded file into `audio_dir`, keeping its extension so the
+/// container can be recognized
+async fn save_upload(
+    field: axum::extract::multipart::Field<'_>,
+    audio_dir: &std::path::Path,
+) -> anyhow::Result<PathBuf> {
+    let extension = field
+        .file_name()
+        .and_then(|name| std::path::Path::new(name).extension())
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_else(|| "bin".to_string());
+
+    tokio::fs::create_dir_all(audio_dir)
+        .await
+        .with_context(|| format!("Failed to create {}", audio_dir.display()))?;
+    let path = audio_dir.join(format!("recording.{}", extension));
+    write_field(field, &path).await?;
+
+    Ok(path)
+}
+
+/// Write a multipart field to `path` as it arrives
+async fn write_field(
+    mut field: axum::extract::multipart::Field<'_>,
+    path: &std::path::Path,
+) -> anyhow::Result<()> {
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+
+    while let Some(chunk) = field.chunk().await? {
+        tokio::io::AsyncWriteExt::write_all(&mut file, &chunk).await?;
+    }
+    tokio::io::AsyncWriteExt::flush(&mut file).await?;
+    Ok(())
+}
+
+/// An uploaded file that is deleted once the request is done with it
+struct TempUpload(PathBuf);
+
+impl Drop for TempUpload {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn bad_request(error: String) -> Response {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response()
+}
+
+/// Blocking writer that forwards bytes to a streaming response body
+struct ChannelWriter {
+    tx: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.tx
+            .blocking_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Client disconnected"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Re-render a meeting's note if it was already exported, logging failures
+async fn refresh_note(config: &Arc<Config>, record: MeetingRecord) -> Option<String> {
+    let config = Arc::clone(config);
+    let meeting_id = record.meeting_id.clone();
+
+    let refreshed = tokio::task::spawn_blocking(move || {
+        let format = config.note_format(record.profile.as_deref());
+        refresh_exported_note(&config.obsidian, &format, &record)
+    })
+    .await;
+
+    match refreshed {
+        Ok(Ok(path)) => path.map(|path| path.display().to_string()),
+        Ok(Err(e)) => {
+            warn!("Failed to re-render note for {}: {:#}", meeting_id, e);
+            None
+        }
+        Err(e) => {
+            error!("Note refresh task panicked: {}", e);
+            None
+        }
+    }
+}
+
+/// A meeting's chapter markers, in timeline order
+fn chapter_markers(record: &MeetingRecord) -> Vec<Marker> {
+    record
+        .markers
+        .iter()
+        .filter(|marker| marker.kind == MarkerKind::Chapter)
+        .cloned()
+        .collect()
+}
+
+/// Load a stored meeting whose content the server can read
+///
+/// Meetings sealed to a client key get a 409 pointing at the sealed content.
+async fn load_readable_meeting(
+    state: &AppState,
+    meeting_id: &str,
+) -> Result<MeetingRecord, Response> {
+    state
+        .readable_meeting(meeting_id)
+        .await
+        .map_err(IntoResponse::into_response)
+}
+
+/// Load a stored meeting, mapping misses and failures to error responses
+async fn load_stored_meeting(
+    state: &AppState,
+    meeting_id: &str,
+) -> Result<MeetingRecord, Response> {
+    state
+        .stored_meeting(meeting_id)
+        .await
+        .map_err(IntoResponse::into_response)
+}
+
+/// GET /health
+/// Health check endpoint
+pub async fn health_check() -> impl IntoResponse {
     (StatusCode::OK, "OK")
 }
+
+/// GET /version
+/// Crate version, git commit, enabled features, platform, and which capture
+/// backends this build has
+pub async fn get_version() -> impl IntoResponse {
+    (StatusCode::OK, Json(BuildInfo::current()))
+}
+
+/// GET /metrics
+/// Service metrics in the Prometheus text format: sessions recording and
+/// cloud STT usage against the budget
+pub async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let active_sessions = state.sessions.read().await.len();
+    let budget = state.stt_budget.status();
+
+    let mut body = String::new();
+    let mut metric = |name: &str, help: &str, samples: &[(&str, f64)]| {
+        body.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} gauge\n",
+            name, help, name
+        ));
+        for (labels, value) in samples {
+            body.push_str(&format!("{}{} {}\n", name, labels, value));
+        }
+    };
+
+    metric(
+        "loqa_meetings_active_sessions",
+        "Sessions currently recording",
+        &[("", active_sessions as f64)],
+    );
+    metric(
+        "loqa_meetings_stt_minutes",
+        "Minutes transcribed by cloud STT providers in the current UTC period",
+        &[
+            ("{period=\"day\"}", budget.day_minutes),
+            ("{period=\"month\"}", budget.month_minutes),
+        ],
+    );
+    let limits: Vec<(&str, f64)> = [
+        ("{period=\"day\"}", budget.daily_limit),
+        ("{period=\"month\"}", budget.monthly_limit),
+    ]
+    .into_iter()
+    .filter_map(|(labels, limit)| limit.map(|limit| (labels, limit)))
+    .collect();
+    metric(
+        "loqa_meetings_stt_budget_minutes",
+        "Configured cloud STT budget (periods without a limit are omitted)",
+        &limits,
+    );
+    metric(
+        "loqa_meetings_stt_budget_exhausted",
+        "1 when the cloud STT budget has run out and sessions record without transcription",
+        &[("", if budget.exhausted.is_some() { 1.0 } else { 0.0 })],
+    );
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        body,
+    )
+}
+
+/// GET /capabilities
+/// Describe the options start requests accept (e.g. quality presets) and
+/// the session limit
+pub async fn get_capabilities(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(CapabilitiesResponse {
+            quality_presets: QualityPreset::ALL.into_iter().map(Into::into).collect(),
+            default_quality: QualityPreset::default(),
+            resampler_qualities: vec![
+                ResamplerQuality::Nearest.to_string(),
+                ResamplerQuality::Linear.to_string(),
+                ResamplerQuality::Cubic.to_string(),
+                format!(
+                    "sinc-N (N even, {}-{})",
+                    ResamplerQuality::SINC_TAPS.start(),
+                    ResamplerQuality::SINC_TAPS.end()
+                ),
+            ],
+            max_concurrent_sessions: state.slots.limit(),
+            audio_codecs: AudioCodec::supported(),
+        }),
+    )
+}
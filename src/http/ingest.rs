@@ -0,0 +1,199 @@
+use super::handlers::{transcribe_upload, UploadQuery, UploadResponse};
+use super::AppState;
+use crate::audio::MeetingDirLock;
+use crate::storage::validate_new_meeting_id;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+use tracing::{error, info, warn};
+
+/// Subdirectory of the inbox that recordings which failed to ingest move to
+pub const FAILED_DIR: &str = "failed";
+
+/// Extensions of the files picked up from the inbox
+const INGEST_EXTENSIONS: &[&str] = &[
+    "wav", "mp3", "m4a", "aac", "flac", "ogg", "opus", "webm", "mp4", "mov", "mkv",
+];
+
+/// Meeting title for a recording dropped into the inbox, from its file name
+///
+/// Underscores become spaces, so `weekly_sync.m4a` is titled "weekly sync".
+pub fn title_from_filename(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let title = stem.replace('_', " ");
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Whether the inbox worker picks up `path`: an audio or video file that
+/// isn't hidden
+pub fn is_ingestable(path: &Path) -> bool {
+    let visible = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| !name.starts_with('.'));
+    let supported = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| INGEST_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+    visible && supported
+}
+
+/// Transcribe a copy of the recording at `path` in a new meeting's
+/// directory, and store it as a new meeting
+///
+/// Without a title in `query` the meeting is titled after the file, and
+/// without a meeting ID one is generated from the title.
+pub(crate) async fn transcribe_copy(
+    state: &AppState,
+    path: &Path,
+    mut query: UploadQuery,
+) -> Result<UploadResponse> {
+    let title = match query.title.take() {
+        Some(title) => title,
+        None => title_from_filename(path).context("File name gives no meeting title")?,
+    };
+    let meeting_id = match query.meeting_id.take() {
+        Some(meeting_id) => {
+            validate_new_meeting_id(&meeting_id)?;
+            if state.storage.get_meeting(&meeting_id).await?.is_some() {
+                anyhow::bail!("Meeting {} already exists", meeting_id);
+            }
+            meeting_id
+        }
+        None => state.generate_meeting_id(&title).await,
+    };
+
+    let audio_dir = state
+        .config
+        .audio
+        .resolved_recordings_path()
+        .join(&meeting_id);
+    let lock = MeetingDirLock::acquire(&audio_dir, &meeting_id)?;
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("bin")
+        .to_ascii_lowercase();
+    let recording = audio_dir.join(format!("recording.{}", extension));
+    if let Err(e) = fs::copy(path, &recording).await {
+        lock.abandon();
+        let _ = fs::remove_dir_all(&audio_dir).await;
+        return Err(e).with_context(|| format!("Failed to copy to {}", recording.display()));
+    }
+
+    query.meeting_id = Some(meeting_id.clone());
+    query.title = Some(title);
+    transcribe_upload(state, &meeting_id, &query, recording, lock)
+        .await
+        .map_err(|(_, error)| anyhow::anyhow!(error))
+}
+
+/// Transcribes recordings dropped into an inbox directory as new meetings
+///
+/// A file is picked up once its size is unchanged between two checks, so a
+/// recording still being copied in isn't read half-written. It is
+/// transcribed like an upload and removed from the inbox; one that fails is
+/// moved to `failed/` rather than retried.
+pub struct IngestWorker {
+    state: AppState,
+    inbox: PathBuf,
+    /// Size of each waiting file when last checked
+    pending: HashMap<PathBuf, u64>,
+}
+
+impl IngestWorker {
+    pub fn new(state: AppState, inbox: PathBuf) -> Self {
+        Self {
+            state,
+            inbox,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Check the inbox every `interval`, forever
+    pub async fn run(mut self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.scan().await {
+                error!("Failed to check ingest inbox: {:#}", e);
+            }
+        }
+    }
+
+    /// Ingest every recording that stopped growing since the last check,
+    /// returning the IDs of the meetings created
+    pub async fn scan(&mut self) -> Result<Vec<String>> {
+        fs::create_dir_all(&self.inbox)
+            .await
+            .with_context(|| format!("Failed to create {}", self.inbox.display()))?;
+
+        let mut sizes = HashMap::new();
+        let mut entries = fs::read_dir(&self.inbox)
+            .await
+            .with_context(|| format!("Failed to read {}", self.inbox.display()))?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() && is_ingestable(&path) {
+                sizes.insert(path, metadata.len());
+            }
+        }
+
+        let mut ready: Vec<PathBuf> = sizes
+            .iter()
+            .filter(|(path, size)| self.pending.get(*path) == Some(*size))
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready.sort();
+        for path in &ready {
+            sizes.remove(path);
+        }
+        self.pending = sizes;
+
+        let mut ingested = Vec::new();
+        for path in ready {
+            match self.ingest(&path).await {
+                Ok(response) => {
+                    info!(
+                        "Ingested {} as meeting {} ({} segments)",
+                        path.display(),
+                        response.meeting_id,
+                        response.segments_count
+                    );
+                    if let Err(e) = fs::remove_file(&path).await {
+                        warn!("Failed to remove ingested {}: {}", path.display(), e);
+                    }
+                    ingested.push(response.meeting_id);
+                }
+                Err(e) => {
+                    error!("Failed to ingest {}: {:#}", path.display(), e);
+                    if let Err(e) = self.set_aside(&path).await {
+                        error!("{:#}", e);
+                    }
+                }
+            }
+        }
+        Ok(ingested)
+    }
+
+    /// Transcribe a copy of the recording, leaving the original in the inbox
+    async fn ingest(&self, path: &Path) -> Result<UploadResponse> {
+        transcribe_copy(&self.state, path, UploadQuery::default()).await
+    }
+
+    /// Move a recording that failed to ingest into `failed/`
+    async fn set_aside(&self, path: &Path) -> Result<()> {
+        let failed = self.inbox.join(FAILED_DIR);
+        fs::create_dir_all(&failed)
+            .await
+            .with_context(|| format!("Failed to create {}", failed.display()))?;
+        let name = path.file_name().context("Recording has no file name")?;
+        fs::rename(path, failed.join(name))
+            .await
+            .with_context(|| format!("Failed to move {} to {}", path.display(), failed.display()))
+    }
+}
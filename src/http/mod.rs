@@ -3,13 +3,58 @@
 //! This module provides a REST API for controlling recording sessions:
 //! - POST /meetings/record/start - Start a new recording
 //! - POST /meetings/record/stop/:id - Stop a recording
-//! - GET /meetings/:id/status - Query session status
-//! - GET /meetings/:id/transcript - Get accumulated transcript
+//! - GET /meetings - List stored meetings
+//! - GET /meetings/search?q= - Search stored transcripts
+//! - GET /meetings/:id/status - Query session status and configuration
+//! - GET /meetings/:id/transcript - Get accumulated transcript (optionally paged)
+//! - PATCH /meetings/:id/transcript/segments/:idx - Correct a stored segment
+//! - GET /meetings/:id/transcript/edits - Transcript correction history
+//! - GET /meetings/:id/markers - Get timeline markers
+//! - POST /meetings/:id/bookmark - Bookmark what was just said, saving it as a clip
+//! - POST /meetings/:id/sources/:source/mute - Silence a source while recording
+//! - POST /meetings/:id/sources/:source/unmute - Let a muted source through again
+//! - GET /meetings/:id/analytics - Talk time and longest monologues per speaker
+//! - GET /meetings/:id/chapters - Get detected chapters
+//! - POST /meetings/:id/chapters - Detect chapters again
+//! - GET /meetings/:id/events - Live session events (WebSocket)
+//! - POST /meetings/:id/companion - Pair a companion device with a live session
+//! - GET /meetings/:id/companion?token= - Capture page for a paired device's browser
+//! - GET /meetings/:id/ingest?token= - Mix in a paired companion device's audio (WebSocket)
+//! - POST /meetings/:id/retranscribe - Re-run STT over part of a stored meeting
+//! - GET /meetings/:id/sealed - Content of a meeting encrypted to a client key
+//! - POST /meetings/:id/extend - Push back a meeting's expiry
+//! - GET /meetings/:id/verify - Check stored audio against its checksums
+//! - GET /meetings/:a/diff/:b - Word-level diff of two stored transcripts
+//! - GET /meetings/:id/audio - The whole meeting's audio as one WAV, loudness-normalized
+//! - POST /meetings/:id/bundle - Export a stored meeting as a zip bundle
+//! - POST /meetings/:id/note - Write a stored meeting's note into the vault
+//! - POST /meetings/import - Restore a meeting from a bundle (multipart upload)
+//! - POST /meetings/upload - Transcribe an uploaded audio or video recording
 //! - GET /health - Health check
+//! - GET /version - Build version, commit, features, and capture backends
+//! - GET /capabilities - Supported quality presets and session limit
+//! - GET /metrics - Active sessions and cloud STT usage (Prometheus format)
+//!
+//! Starting, stopping, listing, status, and transcripts go through
+//! `MeetingsService`, which applications can also use without the server.
+//!
+//! `IngestWorker` transcribes recordings dropped into the configured inbox
+//! the same way as uploads.
+//!
+//! `Advertisement` announces the API over mDNS so companion apps on the
+//! LAN can find it.
 
+mod advertise;
 mod handlers;
+mod ingest;
 mod routes;
 mod state;
 
+pub use advertise::{service_info, Advertisement, SERVICE_TYPE};
+#[cfg(feature = "python")]
+pub(crate) use handlers::UploadQuery;
+#[cfg(feature = "python")]
+pub(crate) use ingest::transcribe_copy;
+pub use ingest::{is_ingestable, title_from_filename, IngestWorker};
 pub use routes::create_router;
 pub use state::AppState;
@@ -1,22 +1,39 @@
 use super::handlers;
 use super::state::AppState;
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{get, patch, post},
     Router,
 };
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
 use tower_http::trace::TraceLayer;
 
+/// Largest bundle accepted by the import endpoint
+const MAX_BUNDLE_BYTES: usize = 1024 * 1024 * 1024; // 1 GiB
+
+/// Largest recording accepted by the upload endpoint (hour-long screen
+/// recordings with video)
+const MAX_RECORDING_BYTES: usize = 2 * 1024 * 1024 * 1024; // 2 GiB
+
 /// Create the HTTP router with all routes
 pub fn create_router(state: AppState) -> Router {
-    Router::new()
+    let compression = state.config.service.http.compression.clone();
+    let router = Router::new()
         // Health check
         .route("/health", get(handlers::health_check))
+        .route("/version", get(handlers::get_version))
+        .route("/capabilities", get(handlers::get_capabilities))
+        .route("/metrics", get(handlers::get_metrics))
         // Recording control
         .route("/meetings/record/start", post(handlers::start_recording))
         .route(
             "/meetings/record/stop/:meeting_id",
             post(handlers::stop_recording),
         )
+        // Stored meetings
+        .route("/meetings", get(handlers::list_meetings))
+        .route("/meetings/search", get(handlers::search_meetings))
         // Meeting queries
         .route(
             "/meetings/:meeting_id/status",
@@ -26,7 +43,112 @@ pub fn create_router(state: AppState) -> Router {
             "/meetings/:meeting_id/transcript",
             get(handlers::get_meeting_transcript),
         )
+        .route(
+            "/meetings/:meeting_id/transcript/segments/:index",
+            patch(handlers::edit_transcript_segment),
+        )
+        .route(
+            "/meetings/:meeting_id/transcript/edits",
+            get(handlers::get_transcript_edits),
+        )
+        .route(
+            "/meetings/:meeting_id/markers",
+            get(handlers::get_meeting_markers),
+        )
+        .route(
+            "/meetings/:meeting_id/bookmark",
+            post(handlers::bookmark_meeting),
+        )
+        .route(
+            "/meetings/:meeting_id/sources/:source/mute",
+            post(handlers::mute_source),
+        )
+        .route(
+            "/meetings/:meeting_id/sources/:source/unmute",
+            post(handlers::unmute_source),
+        )
+        .route(
+            "/meetings/:meeting_id/analytics",
+            get(handlers::get_meeting_analytics),
+        )
+        .route(
+            "/meetings/:meeting_id/chapters",
+            get(handlers::get_meeting_chapters).post(handlers::detect_meeting_chapters),
+        )
+        .route(
+            "/meetings/:meeting_id/retranscribe",
+            post(handlers::retranscribe_meeting),
+        )
+        .route(
+            "/meetings/:meeting_id/sealed",
+            get(handlers::get_sealed_content),
+        )
+        .route(
+            "/meetings/:meeting_id/extend",
+            post(handlers::extend_meeting),
+        )
+        .route(
+            "/meetings/:meeting_id/verify",
+            get(handlers::verify_meeting),
+        )
+        .route(
+            "/meetings/:meeting_id/diff/:other_id",
+            get(handlers::diff_meetings),
+        )
+        // Export / import
+        .route(
+            "/meetings/:meeting_id/audio",
+            get(handlers::export_meeting_audio),
+        )
+        .route(
+            "/meetings/:meeting_id/bundle",
+            post(handlers::export_bundle),
+        )
+        .route("/meetings/:meeting_id/note", post(handlers::export_note))
+        .route(
+            "/meetings/import",
+            post(handlers::import_bundle).layer(DefaultBodyLimit::max(MAX_BUNDLE_BYTES)),
+        )
+        .route(
+            "/meetings/upload",
+            post(handlers::upload_recording).layer(DefaultBodyLimit::max(MAX_RECORDING_BYTES)),
+        )
+        // Live session events (WebSocket)
+        .route(
+            "/meetings/:meeting_id/events",
+            get(handlers::meeting_events),
+        )
+        // Companion devices: pairing, the capture page, and audio (WebSocket)
+        .route(
+            "/meetings/:meeting_id/companion",
+            get(handlers::companion_page).post(handlers::pair_companion),
+        )
+        .route(
+            "/meetings/:meeting_id/ingest",
+            get(handlers::companion_ingest),
+        )
         // Add tracing middleware for request logging
-        .layer(TraceLayer::new_for_http())
-        .with_state(state)
+        .layer(TraceLayer::new_for_http());
+
+    // Compress large JSON responses; audio, archives, and event streams are
+    // already compact or must not be buffered
+    let router = if compression.enabled {
+        router.layer(
+            CompressionLayer::new()
+                .gzip(compression.gzip)
+                .deflate(compression.deflate)
+                .compress_when(
+                    SizeAbove::new(compression.min_size_bytes)
+                        .and(NotForContentType::GRPC)
+                        .and(NotForContentType::IMAGES)
+                        .and(NotForContentType::SSE)
+                        .and(NotForContentType::const_new("audio/"))
+                        .and(NotForContentType::const_new("application/zip")),
+                ),
+        )
+    } else {
+        router
+    };
+
+    router.with_state(state)
 }
@@ -1,25 +1,36 @@
-use crate::session::RecordingSession;
-use std::collections::HashMap;
+use crate::config::Config;
+use crate::service::MeetingsService;
+use crate::storage::Storage;
+use std::ops::Deref;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
-/// Shared application state for HTTP handlers
-#[derive(Clone)]
+/// Shared application state for HTTP handlers: the meetings service the
+/// routes expose
+#[derive(Clone, Default)]
 pub struct AppState {
-    /// Active recording sessions (meeting_id → session)
-    pub sessions: Arc<RwLock<HashMap<String, Arc<RecordingSession>>>>,
+    pub meetings: MeetingsService,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        Self {
-            sessions: Arc::new(RwLock::new(HashMap::new())),
-        }
+        Self::default()
+    }
+
+    pub fn with_config(config: Config, storage: Arc<dyn Storage>) -> Self {
+        MeetingsService::with_config(config, storage).into()
+    }
+}
+
+impl From<MeetingsService> for AppState {
+    fn from(meetings: MeetingsService) -> Self {
+        Self { meetings }
     }
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self::new()
+impl Deref for AppState {
+    type Target = MeetingsService;
+
+    fn deref(&self) -> &MeetingsService {
+        &self.meetings
     }
 }
@@ -1,15 +1,45 @@
 pub mod audio;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod clock;
 pub mod config;
+pub mod export;
+pub mod hooks;
 pub mod http;
 pub mod nats;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod screencapture;
+pub mod service;
 pub mod session;
+pub mod storage;
+pub mod stt;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+pub mod version;
 
 pub use audio::{
     AudioBackend, AudioBackendConfig, AudioBackendFactory, AudioFile, AudioFrame, AudioSource,
-    AudioStreamSource, ChunkConfig, ChunkMetadata, ChunkedRecorder,
+    AudioStreamSource, ChannelMap, ChannelRole, ChunkCodec, ChunkConfig, ChunkMetadata,
+    ChunkedRecorder, FrameDropStats, Mixer, MixerConfig, MixerInput, QualityPreset, Resampler,
+    ResamplerQuality, StereoSplitter,
+};
+pub use config::{
+    ChapterConfig, Config, HooksConfig, HostConfig, SessionLimitsConfig, StorageBackend,
+    StorageConfig,
+};
+pub use export::{render_note, write_bundle, BundleManifest, NoteFormat};
+pub use http::{create_router, Advertisement, AppState, IngestWorker};
+pub use nats::{AppActivityMessage, AudioFrameMessage, NatsClient, TranscriptMessage};
+pub use service::{
+    MeetingStatus, MeetingsError, MeetingsService, StartOptions, StartOutcome, StopOutcome,
+    Transcript,
+};
+pub use session::{
+    Marker, MarkerKind, MeetingEvent, MicrophoneConfig, RecordingSession, RecordingSessionBuilder,
+    SessionConfig, SessionEvent, SessionState, SessionStats, SessionWarning, TranscriptSegment,
+};
+pub use storage::{
+    ExpiredMeeting, FilesystemStorage, MeetingRecord, MeetingSummary, RetentionWorker, SearchHit,
+    SegmentEdit, SqliteStorage, Storage, StorageFactory,
 };
-pub use config::Config;
-pub use http::{create_router, AppState};
-pub use nats::{AudioFrameMessage, NatsClient, TranscriptMessage};
-pub use session::{RecordingSession, SessionConfig, SessionStats, TranscriptSegment};
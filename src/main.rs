@@ -1,32 +1,182 @@
-use anyhow::Result;
-use loqa_meetings::{create_router, AppState};
-use tracing::info;
+use anyhow::{Context, Result};
+use loqa_meetings::{
+    create_router, version, Advertisement, AppState, Config, IngestWorker, RetentionWorker,
+    StorageFactory,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Default configuration file (extension resolved by the config loader)
+const CONFIG_PATH: &str = "config/loqa-meetings";
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
-    tracing_subscriber::fmt::init();
+    init_tracing();
+
+    info!(
+        "🎙️  Loqa Meetings v{}{} - HTTP API Server",
+        version::VERSION,
+        version::GIT_COMMIT
+            .map(|commit| format!(" ({})", commit))
+            .unwrap_or_default()
+    );
+    info!("🪪  Instance {}", loqa_meetings::audio::instance_id());
+
+    // Load configuration
+    let config = match Config::load(CONFIG_PATH) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Failed to load {}: {} (using defaults)", CONFIG_PATH, e);
+            Config::default()
+        }
+    };
 
-    info!("🎙️  Loqa Meetings v0.1.0 - HTTP API Server");
+    // Open meeting storage
+    let storage = StorageFactory::create(&config.storage)?;
+    info!(
+        "💾 Storing meetings with {} backend in {}",
+        storage.name(),
+        config.storage.resolved_path().display()
+    );
+
+    match config.sessions.max_concurrent {
+        0 => info!("🎙️  No limit on concurrent sessions"),
+        limit => info!(
+            "🎙️  Up to {} concurrent session(s){}",
+            limit,
+            if config.sessions.queue {
+                ", queueing the rest"
+            } else {
+                ""
+            }
+        ),
+    }
 
     // Create application state
-    let app_state = AppState::new();
+    let app_state = AppState::with_config(config, storage);
+
+    // Delete meetings started with a TTL once they expire
+    match app_state.config.storage.retention_interval_secs {
+        0 => info!("🗑️  Expired meetings are not deleted automatically"),
+        interval => {
+            let worker = RetentionWorker::new(
+                Arc::clone(&app_state.storage),
+                app_state.config.audio.resolved_recordings_path(),
+            )
+            .with_clock(Arc::clone(&app_state.clock));
+            tokio::spawn(worker.run(Duration::from_secs(interval)));
+            info!("🗑️  Deleting expired meetings every {}s", interval);
+        }
+    }
+
+    // Transcribe recordings dropped into the inbox
+    let ingest = &app_state.config.ingest;
+    match ingest.resolved_inbox() {
+        None => info!("📥 No ingest inbox configured"),
+        Some(inbox) => {
+            let interval = Duration::from_secs(ingest.poll_interval_secs.max(1));
+            info!("📥 Ingesting recordings dropped into {}", inbox.display());
+            let worker = IngestWorker::new(app_state.clone(), inbox);
+            tokio::spawn(worker.run(interval));
+        }
+    }
+
+    // Buffer audio between meetings for retroactive starts
+    if let Some(standby) = &app_state.standby {
+        match standby.resume().await {
+            Ok(()) => info!(
+                "⏪ Standby buffering the last {} minute(s) of audio",
+                app_state.config.standby.minutes
+            ),
+            Err(e) => warn!("Standby is not buffering audio: {:#}", e),
+        }
+    }
+
+    let http = app_state.config.service.http.clone();
 
     // Create HTTP router
     let app = create_router(app_state);
 
     // Start HTTP server
-    let addr = "127.0.0.1:3000";
-    info!("🌐 Starting HTTP server on http://{}", addr);
+    let addr = format!("{}:{}", http.bind, http.port);
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("Failed to listen on {}", addr))?;
+    let local_addr = listener.local_addr()?;
+    info!("🌐 Starting HTTP server on http://{}", local_addr);
     info!("📋 API endpoints:");
     info!("   POST   /meetings/record/start");
     info!("   POST   /meetings/record/stop/:meeting_id");
+    info!("   GET    /meetings");
+    info!("   GET    /meetings/search?q=");
     info!("   GET    /meetings/:meeting_id/status");
     info!("   GET    /meetings/:meeting_id/transcript");
+    info!("   PATCH  /meetings/:meeting_id/transcript/segments/:index");
+    info!("   GET    /meetings/:meeting_id/transcript/edits");
+    info!("   GET    /meetings/:meeting_id/markers");
+    info!("   POST   /meetings/:meeting_id/bookmark");
+    info!("   POST   /meetings/:meeting_id/sources/:source/mute");
+    info!("   POST   /meetings/:meeting_id/sources/:source/unmute");
+    info!("   GET    /meetings/:meeting_id/analytics");
+    info!("   GET    /meetings/:meeting_id/chapters");
+    info!("   POST   /meetings/:meeting_id/chapters");
+    info!("   GET    /meetings/:meeting_id/events (WebSocket)");
+    info!("   POST   /meetings/:meeting_id/companion");
+    info!("   GET    /meetings/:meeting_id/companion");
+    info!("   GET    /meetings/:meeting_id/ingest (WebSocket)");
+    info!("   POST   /meetings/:meeting_id/retranscribe");
+    info!("   GET    /meetings/:meeting_id/sealed");
+    info!("   POST   /meetings/:meeting_id/extend");
+    info!("   GET    /meetings/:meeting_id/verify");
+    info!("   GET    /meetings/:meeting_id/diff/:other_id");
+    info!("   GET    /meetings/:meeting_id/audio");
+    info!("   POST   /meetings/:meeting_id/bundle");
+    info!("   POST   /meetings/:meeting_id/note");
+    info!("   POST   /meetings/import");
+    info!("   POST   /meetings/upload");
     info!("   GET    /health");
+    info!("   GET    /version");
+    info!("   GET    /capabilities");
+    info!("   GET    /metrics");
+
+    // Let companion apps on the LAN find the server at the port actually
+    // bound (withdrawn on exit)
+    let _advertisement = match Advertisement::start(&http.advertise, local_addr) {
+        Ok(advertisement) => advertisement,
+        Err(e) => {
+            warn!("📡 The API is not advertised: {:#}", e);
+            None
+        }
+    };
+
+    let served = axum::serve(listener, app).await;
+
+    #[cfg(feature = "otel")]
+    if let Err(e) = loqa_meetings::telemetry::shutdown() {
+        warn!("{:#}", e);
+    }
+    Ok(served?)
+}
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+/// Log to stdout; with the `console` feature also serve task data to
+/// tokio-console, and with `otel` export spans to an OpenTelemetry collector
+fn init_tracing() {
+    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::prelude::*;
 
-    Ok(())
+    let registry = tracing_subscriber::registry();
+    #[cfg(feature = "console")]
+    let registry = registry.with(console_subscriber::spawn());
+    // Logging isn't up yet, so a failed export is reported on stderr
+    #[cfg(feature = "otel")]
+    let registry = registry.with(
+        loqa_meetings::telemetry::layer()
+            .inspect_err(|e| eprintln!("Spans will not be exported: {:#}", e))
+            .ok(),
+    );
+    registry
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+        .init();
 }
@@ -1,11 +1,25 @@
+use super::durable::{
+    delete_durable, subscribe_durable, TranscriptStreamConfig, TranscriptSubscription,
+};
+use super::messages::{AudioCodec, HandshakeReply, HandshakeRequest, Protocol, PROTOCOL_VERSION};
+use crate::audio::AppActivitySummary;
+use crate::clock::{system_clock, SharedClock};
 use anyhow::{Context, Result};
-use async_nats::Client;
+use async_nats::{Client, RequestErrorKind};
 use base64::Engine;
-use tracing::info;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How long to wait for the STT service to answer the session handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub struct NatsClient {
     client: Client,
     meeting_id: String,
+    /// Timestamps published messages
+    clock: SharedClock,
 }
 
 impl NatsClient {
@@ -19,7 +33,17 @@ impl NatsClient {
 
         info!("Connected to NATS successfully");
 
-        Ok(Self { client, meeting_id })
+        Ok(Self {
+            client,
+            meeting_id,
+            clock: system_clock(),
+        })
+    }
+
+    /// Timestamp published messages with `clock`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
     }
 
     /// Publish audio frame to NATS
@@ -31,19 +55,101 @@ impl NatsClient {
         chunk_index: u32,
         is_final: bool,
     ) -> Result<()> {
-        let subject = format!("audio.frame.meeting-{}", self.meeting_id);
+        self.publish_audio_frame_as(
+            &self.meeting_id,
+            pcm_bytes,
+            sample_rate,
+            channels,
+            chunk_index,
+            is_final,
+        )
+        .await
+    }
 
+    /// Publish audio frame to NATS under another session ID (e.g. a dual-stream sub-ID)
+    pub async fn publish_audio_frame_as(
+        &self,
+        session_id: &str,
+        pcm_bytes: &[u8],
+        sample_rate: u32,
+        channels: u16,
+        chunk_index: u32,
+        is_final: bool,
+    ) -> Result<()> {
         let message = super::messages::AudioFrameMessage {
-            session_id: self.meeting_id.clone(),
+            version: PROTOCOL_VERSION,
+            session_id: session_id.to_string(),
             sequence: chunk_index,
             pcm: base64::engine::general_purpose::STANDARD.encode(pcm_bytes),
             sample_rate,
             channels,
-            timestamp: chrono::Utc::now().to_rfc3339(),
+            timestamp: self.clock.now().to_rfc3339(),
             final_frame: is_final,
+            segment_final: false,
+            model: None,
+            codec: AudioCodec::Pcm16,
+            packets: Vec::new(),
         };
 
-        let payload = serde_json::to_vec(&message)?;
+        self.publish_audio_message(&message).await
+    }
+
+    /// Agree on the protocol for a session with the STT service
+    ///
+    /// STT services that do not answer the handshake predate it and get
+    /// protocol version 1. A service that answers but is incompatible fails
+    /// the handshake.
+    pub async fn handshake(&self, offer: &HandshakeRequest) -> Result<Protocol> {
+        let payload = serde_json::to_vec(offer)?;
+
+        let reply = match tokio::time::timeout(
+            HANDSHAKE_TIMEOUT,
+            self.client.request("stt.handshake", payload.into()),
+        )
+        .await
+        {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(e)) if e.kind() != RequestErrorKind::Other => {
+                warn!(
+                    "STT service did not answer the handshake ({}); using protocol v1",
+                    e
+                );
+                return Ok(Protocol::legacy());
+            }
+            Ok(Err(e)) => return Err(e).context("Failed to send handshake"),
+            Err(_) => {
+                warn!(
+                    "STT service did not answer the handshake within {:?}; using protocol v1",
+                    HANDSHAKE_TIMEOUT
+                );
+                return Ok(Protocol::legacy());
+            }
+        };
+
+        let reply: HandshakeReply =
+            serde_json::from_slice(&reply.payload).context("Invalid handshake reply")?;
+        let protocol = Protocol::negotiate(offer, &reply).context("Incompatible STT service")?;
+
+        info!(
+            "Agreed on protocol v{} with the STT service ({:?}, {} frame(s) per message)",
+            protocol.version, protocol.codec, protocol.batch_frames
+        );
+
+        Ok(protocol)
+    }
+
+    /// Publish a prepared audio frame message to its session's subject
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(session = %message.session_id, sequence = message.sequence)
+    )]
+    pub async fn publish_audio_message(
+        &self,
+        message: &super::messages::AudioFrameMessage,
+    ) -> Result<()> {
+        let subject = format!("audio.frame.meeting-{}", message.session_id);
+        let payload = serde_json::to_vec(message)?;
 
         self.client
             .publish(subject.clone(), payload.into())
@@ -51,16 +157,95 @@ impl NatsClient {
             .context("Failed to publish audio frame")?;
 
         info!(
-            "Published audio frame to {} (chunk={}, bytes={}, final={})",
+            "Published audio frame to {} (chunk={}, final={})",
+            subject, message.sequence, message.final_frame
+        );
+
+        Ok(())
+    }
+
+    /// Publish a per-application audio activity summary
+    #[tracing::instrument(level = "debug", skip_all, fields(session = %self.meeting_id))]
+    pub async fn publish_app_activity(&self, summary: &AppActivitySummary) -> Result<()> {
+        let subject = format!("meetings.apps.{}", self.meeting_id);
+
+        let message = super::messages::AppActivityMessage {
+            version: PROTOCOL_VERSION,
+            session_id: self.meeting_id.clone(),
+            timestamp: self.clock.now().to_rfc3339(),
+            window_secs: summary.window_secs,
+            apps: summary.apps.clone(),
+        };
+
+        let payload = serde_json::to_vec(&message)?;
+
+        self.client
+            .publish(subject.clone(), payload.into())
+            .await
+            .context("Failed to publish app activity")?;
+
+        info!(
+            "Published app activity to {} ({} apps over {:.0}s)",
             subject,
-            chunk_index,
-            pcm_bytes.len(),
-            is_final
+            message.apps.len(),
+            message.window_secs
         );
 
         Ok(())
     }
 
+    /// Publish a session lifecycle change
+    #[tracing::instrument(level = "debug", skip_all, fields(session = %self.meeting_id))]
+    pub async fn publish_status(&self, status: super::messages::SessionStatus) -> Result<()> {
+        let subject = format!("meetings.status.{}", self.meeting_id);
+
+        let message = super::messages::StatusMessage {
+            version: PROTOCOL_VERSION,
+            session_id: self.meeting_id.clone(),
+            timestamp: self.clock.now().to_rfc3339(),
+            status,
+        };
+
+        let payload = serde_json::to_vec(&message)?;
+
+        self.client
+            .publish(subject.clone(), payload.into())
+            .await
+            .context("Failed to publish session status")?;
+
+        info!(
+            "Published session status to {}: {:?}",
+            subject, message.status
+        );
+
+        Ok(())
+    }
+
+    /// Send a JSON request to `subject` and parse the JSON reply
+    pub async fn request_json<Req, Reply>(
+        &self,
+        subject: &str,
+        request: &Req,
+        timeout: Duration,
+    ) -> Result<Reply>
+    where
+        Req: Serialize,
+        Reply: DeserializeOwned,
+    {
+        let payload = serde_json::to_vec(request)?;
+
+        let reply = tokio::time::timeout(
+            timeout,
+            self.client.request(subject.to_string(), payload.into()),
+        )
+        .await
+        .with_context(|| format!("No reply on {} within {:?}", subject, timeout))?
+        .with_context(|| format!("Request on {} failed", subject))?;
+
+        serde_json::from_slice(&reply.payload)
+            .with_context(|| format!("Invalid reply on {}", subject))
+    }
+
     /// Subscribe to transcript messages
     pub async fn subscribe_transcripts(&self) -> Result<async_nats::Subscriber> {
         // Subscribe to all transcripts (partial and final)
@@ -81,10 +266,29 @@ impl NatsClient {
         Ok(subscriber)
     }
 
-    /// Close NATS connection
-    pub async fn close(self) -> Result<()> {
+    /// Subscribe to transcripts through this meeting's durable consumer,
+    /// picking up any published since the consumer was last read
+    pub async fn subscribe_transcripts_durable(
+        &self,
+        config: &TranscriptStreamConfig,
+    ) -> Result<TranscriptSubscription> {
+        subscribe_durable(&self.client, &self.meeting_id, config).await
+    }
+
+    /// Delete this meeting's durable transcript consumer
+    pub async fn delete_transcript_consumer(&self, config: &TranscriptStreamConfig) -> Result<()> {
+        delete_durable(&self.client, &self.meeting_id, config).await
+    }
+
+    /// Flush anything still queued for the server before the client is dropped
+    ///
+    /// async-nats closes the connection itself once the last handle to it
+    /// is dropped.
+    pub async fn close(&self) -> Result<()> {
         info!("Closing NATS connection");
-        // async-nats handles cleanup on drop
-        Ok(())
+        self.client
+            .flush()
+            .await
+            .context("Failed to flush NATS connection")
     }
 }
@@ -0,0 +1,184 @@
+use super::messages::TranscriptMessage;
+use anyhow::{Context, Result};
+use async_nats::jetstream::{self, consumer, stream};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{info, warn};
+
+/// Subjects the STT service publishes transcripts on
+pub const TRANSCRIPT_SUBJECTS: &str = "stt.text.>";
+
+/// Durable (JetStream) delivery of transcripts
+///
+/// With a durable consumer, transcripts published while loqa-meetings is
+/// down are delivered once a session with the same meeting ID starts again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptStreamConfig {
+    /// Use a durable consumer (falls back to a plain subscription if
+    /// JetStream is unavailable)
+    pub durable: bool,
+    /// JetStream stream holding `stt.text.>`, created if missing
+    pub stream: String,
+    /// How long a session's consumer outlives loqa-meetings, and how long
+    /// a created stream keeps transcripts
+    pub retain_secs: u64,
+}
+
+impl Default for TranscriptStreamConfig {
+    fn default() -> Self {
+        Self {
+            durable: false,
+            stream: "STT_TEXT".to_string(),
+            retain_secs: 3600,
+        }
+    }
+}
+
+/// Durable consumer name for a session (NATS names can't contain `.`,
+/// `*`, `>` or whitespace)
+pub fn consumer_name(session_id: &str) -> String {
+    let name: String = session_id
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    format!("loqa-meetings-{}", name)
+}
+
+/// A transcript message and, for durable delivery, when NATS received it
+pub struct TranscriptDelivery {
+    /// The transcript, or why its payload could not be parsed
+    pub transcript: serde_json::Result<TranscriptMessage>,
+    /// Publish time recorded by JetStream (None for a plain subscription)
+    pub published: Option<DateTime<Utc>>,
+}
+
+impl TranscriptDelivery {
+    fn parse(message: &async_nats::Message, published: Option<DateTime<Utc>>) -> Self {
+        Self {
+            transcript: serde_json::from_slice(&message.payload),
+            published,
+        }
+    }
+}
+
+/// Transcripts from a plain or durable subscription, or pushed by an STT
+/// provider that doesn't use NATS
+pub enum TranscriptSubscription {
+    Core(async_nats::Subscriber),
+    Durable(Box<consumer::pull::Stream>),
+    /// Shared so the subscription can be taken again after a restart
+    Provider(Arc<Mutex<mpsc::Receiver<TranscriptMessage>>>),
+}
+
+impl TranscriptSubscription {
+    /// Next transcript, acknowledged if durable (None once the
+    /// subscription ends or fails)
+    pub async fn next(&mut self) -> Option<TranscriptDelivery> {
+        match self {
+            Self::Core(subscriber) => subscriber
+                .next()
+                .await
+                .map(|message| TranscriptDelivery::parse(&message, None)),
+            Self::Durable(messages) => match messages.next().await? {
+                Ok(message) => {
+                    let published = message.info().ok().and_then(|info| {
+                        i64::try_from(info.published.unix_timestamp_nanos())
+                            .ok()
+                            .map(DateTime::from_timestamp_nanos)
+                    });
+                    if let Err(e) = message.ack().await {
+                        warn!("Failed to acknowledge transcript: {}", e);
+                    }
+                    Some(TranscriptDelivery::parse(&message.message, published))
+                }
+                Err(e) => {
+                    warn!("Durable transcript delivery failed: {}", e);
+                    None
+                }
+            },
+            Self::Provider(receiver) => {
+                receiver
+                    .lock()
+                    .await
+                    .recv()
+                    .await
+                    .map(|transcript| TranscriptDelivery {
+                        transcript: Ok(transcript),
+                        published: None,
+                    })
+            }
+        }
+    }
+}
+
+/// Open (or resume) a session's durable transcript consumer
+pub(crate) async fn subscribe_durable(
+    client: &async_nats::Client,
+    session_id: &str,
+    config: &TranscriptStreamConfig,
+) -> Result<TranscriptSubscription> {
+    let retain = Duration::from_secs(config.retain_secs.max(1));
+    let context = jetstream::new(client.clone());
+    let stream = context
+        .get_or_create_stream(stream::Config {
+            name: config.stream.clone(),
+            subjects: vec![TRANSCRIPT_SUBJECTS.to_string()],
+            max_age: retain,
+            ..Default::default()
+        })
+        .await
+        .context("Failed to open transcript stream")?;
+
+    // An existing consumer resumes after the last transcript it acknowledged
+    let name = consumer_name(session_id);
+    let consumer: consumer::PullConsumer = stream
+        .get_or_create_consumer(
+            &name,
+            consumer::pull::Config {
+                durable_name: Some(name.clone()),
+                filter_subject: TRANSCRIPT_SUBJECTS.to_string(),
+                deliver_policy: consumer::DeliverPolicy::New,
+                ack_policy: consumer::AckPolicy::Explicit,
+                inactive_threshold: retain,
+                ..Default::default()
+            },
+        )
+        .await
+        .context("Failed to open durable transcript consumer")?;
+    let messages = consumer
+        .messages()
+        .await
+        .context("Failed to read durable transcript consumer")?;
+
+    info!(
+        "Subscribed to {} via durable consumer {} on {}",
+        TRANSCRIPT_SUBJECTS, name, config.stream
+    );
+    Ok(TranscriptSubscription::Durable(Box::new(messages)))
+}
+
+/// Remove a session's durable consumer once its transcripts are stored
+pub(crate) async fn delete_durable(
+    client: &async_nats::Client,
+    session_id: &str,
+    config: &TranscriptStreamConfig,
+) -> Result<()> {
+    let context = jetstream::new(client.clone());
+    let stream = context
+        .get_stream(&config.stream)
+        .await
+        .context("Failed to open transcript stream")?;
+    stream
+        .delete_consumer(&consumer_name(session_id))
+        .await
+        .context("Failed to delete durable transcript consumer")?;
+    Ok(())
+}
@@ -1,25 +1,266 @@
+use crate::audio::{AppActivity, OpusEncoder};
+use crate::session::{HostInfo, SessionTask};
 use serde::{Deserialize, Serialize};
 
+/// NATS protocol version spoken by this build
+///
+/// Version 2 added message versions, the session handshake, Opus `codec`
+/// and `packets`, batched audio frames, and `segment_final` utterance hints.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest protocol version still spoken
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Version of messages from peers that predate versioning
+fn legacy_version() -> u32 {
+    1
+}
+
+fn one_frame() -> u32 {
+    1
+}
+
+/// How the audio in an [`AudioFrameMessage`] is encoded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioCodec {
+    /// Raw 16-bit PCM in `pcm`
+    #[default]
+    Pcm16,
+    /// 20 ms Opus packets in `packets`
+    Opus,
+}
+
+impl AudioCodec {
+    pub fn is_pcm16(&self) -> bool {
+        *self == AudioCodec::Pcm16
+    }
+
+    /// Whether this build can encode the codec
+    pub fn is_supported(self) -> bool {
+        match self {
+            AudioCodec::Pcm16 => true,
+            AudioCodec::Opus => OpusEncoder::is_available(),
+        }
+    }
+
+    /// Codecs this build can encode
+    pub fn supported() -> Vec<AudioCodec> {
+        [AudioCodec::Pcm16, AudioCodec::Opus]
+            .into_iter()
+            .filter(|codec| codec.is_supported())
+            .collect()
+    }
+}
+
 /// Audio frame message published to NATS
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AudioFrameMessage {
+    /// Protocol version the message is written in
+    #[serde(default = "legacy_version")]
+    pub version: u32,
     pub session_id: String,
     pub sequence: u32, // Frame sequence number (matches loqa-core protocol)
-    pub pcm: String,   // Base64-encoded PCM bytes
+    pub pcm: String,   // Base64-encoded PCM bytes (several frames when batched)
     pub sample_rate: u32,
     pub channels: u16,
     pub timestamp: String, // RFC3339 timestamp
     #[serde(rename = "final")]
     pub final_frame: bool,
+    /// The audio so far ends an utterance: finalize the current segment but
+    /// keep the stream open (omitted when false; older consumers ignore it)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub segment_final: bool,
+    /// Preferred STT model (e.g. a larger model when re-transcribing)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Payload encoding (omitted for PCM, which version 1 consumers expect)
+    #[serde(default, skip_serializing_if = "AudioCodec::is_pcm16")]
+    pub codec: AudioCodec,
+    /// Base64-encoded Opus packets, in order, when `codec` is opus
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packets: Vec<String>,
+}
+
+/// Offer sent to the STT service on `stt.handshake` when a session starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandshakeRequest {
+    /// Highest protocol version spoken
+    pub version: u32,
+    /// Lowest protocol version spoken
+    pub min_version: u32,
+    pub session_id: String,
+    /// Codecs that can be published, most preferred first
+    pub codecs: Vec<AudioCodec>,
+    /// Most capture frames that would be sent per message
+    pub max_batch_frames: u32,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// The STT service's answer to a [`HandshakeRequest`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandshakeReply {
+    /// Highest protocol version the service speaks
+    pub version: u32,
+    /// Lowest protocol version the service speaks
+    #[serde(default = "legacy_version")]
+    pub min_version: u32,
+    /// Codecs the service accepts (empty = PCM only)
+    #[serde(default)]
+    pub codecs: Vec<AudioCodec>,
+    /// Most capture frames the service accepts per message
+    #[serde(default = "one_frame")]
+    pub max_batch_frames: u32,
+}
+
+/// What loqa-meetings and the STT service agreed on for a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Protocol {
+    /// Version audio frames are written in
+    pub version: u32,
+    pub codec: AudioCodec,
+    /// Capture frames combined into each audio message
+    pub batch_frames: u32,
+}
+
+impl Protocol {
+    /// Version 1, for STT services that do not answer the handshake:
+    /// PCM, one frame per message
+    pub fn legacy() -> Self {
+        Self {
+            version: 1,
+            codec: AudioCodec::Pcm16,
+            batch_frames: 1,
+        }
+    }
+
+    /// Offer for a session preferring `codec` and up to `batch_frames` per message
+    pub fn offer(
+        session_id: &str,
+        codec: AudioCodec,
+        batch_frames: u32,
+        sample_rate: u32,
+        channels: u16,
+    ) -> HandshakeRequest {
+        let mut codecs = AudioCodec::supported();
+        if let Some(index) = codecs.iter().position(|&other| other == codec) {
+            codecs[..=index].rotate_right(1);
+        }
+
+        HandshakeRequest {
+            version: PROTOCOL_VERSION,
+            min_version: MIN_PROTOCOL_VERSION,
+            session_id: session_id.to_string(),
+            codecs,
+            max_batch_frames: batch_frames.max(1),
+            sample_rate,
+            channels,
+        }
+    }
+
+    /// Agree with the service's `reply` to `offer`
+    ///
+    /// Picks the highest version both speak, the most preferred offered codec
+    /// the service accepts, and the smaller batch size. Fails if the versions
+    /// do not overlap or no codec is acceptable to both.
+    pub fn negotiate(offer: &HandshakeRequest, reply: &HandshakeReply) -> anyhow::Result<Self> {
+        let version = offer.version.min(reply.version);
+        if version < offer.min_version.max(reply.min_version) {
+            anyhow::bail!(
+                "STT service speaks protocol v{}-v{}, but loqa-meetings speaks v{}-v{}",
+                reply.min_version,
+                reply.version,
+                offer.min_version,
+                offer.version
+            );
+        }
+
+        // Version 1 is plain PCM, one frame per message
+        if version < 2 {
+            return Ok(Self::legacy());
+        }
+
+        let accepted = |codec: &AudioCodec| {
+            reply.codecs.contains(codec) || (reply.codecs.is_empty() && codec.is_pcm16())
+        };
+        let Some(&codec) = offer.codecs.iter().find(|codec| accepted(codec)) else {
+            anyhow::bail!(
+                "STT service accepts none of the offered codecs {:?} (it accepts {:?})",
+                offer.codecs,
+                reply.codecs
+            );
+        };
+
+        Ok(Self {
+            version,
+            codec,
+            batch_frames: offer.max_batch_frames.min(reply.max_batch_frames).max(1),
+        })
+    }
 }
 
 /// Transcript message received from STT service
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TranscriptMessage {
+    /// Protocol version the message is written in
+    #[serde(default = "legacy_version")]
+    pub version: u32,
     pub session_id: String,
     pub text: String,
     pub partial: bool,
     pub timestamp: String,
     #[serde(default)]
     pub confidence: Option<f32>,
+    /// Speaker label from diarization, if the STT service provides one
+    #[serde(default)]
+    pub speaker: Option<String>,
+    /// Stable ID of the segment, if the STT service assigns one; a resent
+    /// segment keeps its ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_id: Option<String>,
+    /// Language of the segment, if the STT service reports one (a code such
+    /// as "en" or "en-US", or a name such as "english")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// Per-application audio activity summary published during a session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppActivityMessage {
+    /// Protocol version the message is written in
+    #[serde(default = "legacy_version")]
+    pub version: u32,
+    pub session_id: String,
+    pub timestamp: String, // RFC3339 timestamp
+    pub window_secs: f64,
+    pub apps: Vec<AppActivity>, // Sorted by share, largest first
+}
+
+/// Session lifecycle change published to `meetings.status.{session_id}`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusMessage {
+    /// Protocol version the message is written in
+    #[serde(default = "legacy_version")]
+    pub version: u32,
+    pub session_id: String,
+    pub timestamp: String, // RFC3339 timestamp
+    #[serde(flatten)]
+    pub status: SessionStatus,
+}
+
+/// Lifecycle change of a recording session, tagged by `status`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SessionStatus {
+    /// Capture and transcription are running
+    Started {
+        /// Machine recording the session, if reported
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        host: Option<HostInfo>,
+    },
+    /// Recording stopped; the meeting is complete
+    Stopped { duration_secs: f64 },
+    /// A session task failed and could not be restarted
+    Failed { task: SessionTask, error: String },
 }
@@ -1,5 +1,12 @@
 pub mod client;
+pub mod durable;
 pub mod messages;
 
 pub use client::NatsClient;
-pub use messages::{AudioFrameMessage, TranscriptMessage};
+pub use durable::{
+    consumer_name, TranscriptDelivery, TranscriptStreamConfig, TranscriptSubscription,
+};
+pub use messages::{
+    AppActivityMessage, AudioCodec, AudioFrameMessage, HandshakeReply, HandshakeRequest, Protocol,
+    SessionStatus, StatusMessage, TranscriptMessage, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
+};
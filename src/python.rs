@@ -0,0 +1,304 @@
+//! Python module (feature "python")
+//!
+//! Exposes audio decoding, file transcription, and session control to
+//! Python, for scripting batch transcription and analysis of a meeting
+//! archive. Build the extension with
+//! `cargo rustc --release --lib --features python,pyo3/extension-module --crate-type cdylib`
+//! and copy the library to `loqa_meetings.so` on the Python path.
+//!
+//! Meetings, transcripts, and stats come back as plain dicts and lists in
+//! the shapes the HTTP API returns.
+
+// pyo3's generated wrappers convert each PyResult's error into itself
+#![allow(clippy::useless_conversion)]
+
+use crate::audio::AudioFile;
+use crate::config::Config;
+use crate::http::{transcribe_copy, AppState, UploadQuery};
+use crate::service::{
+    MeetingStatus, MeetingsError, MeetingsService, StartOptions, StartOutcome, StopOutcome,
+};
+use crate::session::TranscriptQuery;
+use pyo3::exceptions::{PyKeyError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::json;
+use std::path::PathBuf;
+
+/// A decoded audio file
+#[pyclass(name = "AudioFile", module = "loqa_meetings")]
+struct PyAudioFile {
+    inner: AudioFile,
+}
+
+#[pymethods]
+impl PyAudioFile {
+    /// Decode one audio track of a file (video containers' video is skipped)
+    #[staticmethod]
+    #[pyo3(signature = (path, track = 0))]
+    fn open(py: Python<'_>, path: PathBuf, track: usize) -> PyResult<Self> {
+        let inner = py
+            .allow_threads(|| AudioFile::open_track(&path, track))
+            .map_err(|e| PyValueError::new_err(format!("{:#}", e)))?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn path(&self) -> &str {
+        &self.inner.path
+    }
+
+    #[getter]
+    fn duration_seconds(&self) -> f64 {
+        self.inner.duration_seconds
+    }
+
+    #[getter]
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate
+    }
+
+    #[getter]
+    fn channels(&self) -> u16 {
+        self.inner.channels
+    }
+
+    /// Interleaved 16-bit samples
+    #[getter]
+    fn samples(&self) -> Vec<i16> {
+        self.inner.samples.clone()
+    }
+
+    /// Samples folded down to mono
+    fn to_mono(&self) -> Vec<i16> {
+        self.inner.to_mono()
+    }
+
+    /// Mono samples at 16 kHz, as sent to STT
+    fn resample_to_mono_16khz(&self, py: Python<'_>) -> PyResult<Vec<i16>> {
+        py.allow_threads(|| self.inner.resample_to_mono_16khz())
+            .map_err(|e| PyRuntimeError::new_err(format!("{:#}", e)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AudioFile({:?}, {} Hz, {} channels, {:.1}s)",
+            self.inner.path,
+            self.inner.sample_rate,
+            self.inner.channels,
+            self.inner.duration_seconds
+        )
+    }
+}
+
+/// Read a file's format without decoding its samples
+#[pyfunction]
+#[pyo3(signature = (path, track = 0))]
+fn probe(py: Python<'_>, path: PathBuf, track: usize) -> PyResult<PyObject> {
+    let info = py
+        .allow_threads(|| AudioFile::probe_track(&path, track))
+        .map_err(|e| PyValueError::new_err(format!("{:#}", e)))?;
+    to_py(py, &info)
+}
+
+/// The meetings service, with the runtime its sessions run on
+#[pyclass(name = "Meetings", module = "loqa_meetings")]
+struct PyMeetings {
+    runtime: tokio::runtime::Runtime,
+    service: MeetingsService,
+}
+
+#[pymethods]
+impl PyMeetings {
+    /// Open the service with the configuration at `config_path` (extension
+    /// resolved by the config loader), or the defaults
+    #[new]
+    #[pyo3(signature = (config_path = None))]
+    fn new(config_path: Option<&str>) -> PyResult<Self> {
+        let config = match config_path {
+            Some(path) => Config::load(path)
+                .map_err(|e| PyValueError::new_err(format!("Failed to load {}: {}", path, e)))?,
+            None => Config::default(),
+        };
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let service = runtime
+            .block_on(async { MeetingsService::open(config) })
+            .map_err(|e| PyRuntimeError::new_err(format!("{:#}", e)))?;
+        Ok(Self { runtime, service })
+    }
+
+    /// Start recording; `options` takes the HTTP start request's fields
+    #[pyo3(signature = (options = None))]
+    fn start(&self, py: Python<'_>, options: Option<&Bound<'_, PyDict>>) -> PyResult<PyObject> {
+        let options: StartOptions = match options {
+            Some(options) => from_py(py, options.as_any())?,
+            None => StartOptions::default(),
+        };
+        let outcome = py
+            .allow_threads(|| self.runtime.block_on(self.service.start(options)))
+            .map_err(to_py_err)?;
+        let result = match outcome {
+            StartOutcome::Recording(session) => json!({
+                "meeting_id": session.session_id(),
+                "status": "recording",
+            }),
+            StartOutcome::Queued {
+                meeting_id,
+                position,
+            } => json!({
+                "meeting_id": meeting_id,
+                "status": "queued",
+                "position": position,
+            }),
+        };
+        to_py(py, &result)
+    }
+
+    /// Stop a recording meeting and save it, or cancel a queued one
+    fn stop(&self, py: Python<'_>, meeting_id: &str) -> PyResult<PyObject> {
+        let outcome = py
+            .allow_threads(|| self.runtime.block_on(self.service.stop(meeting_id)))
+            .map_err(to_py_err)?;
+        let result = match outcome {
+            StopOutcome::Stopped(stats) => json!({
+                "meeting_id": meeting_id,
+                "status": "stopped",
+                "stats": stats,
+            }),
+            StopOutcome::Cancelled => json!({
+                "meeting_id": meeting_id,
+                "status": "cancelled",
+            }),
+        };
+        to_py(py, &result)
+    }
+
+    /// Status of a recording or queued meeting
+    fn status(&self, py: Python<'_>, meeting_id: &str) -> PyResult<PyObject> {
+        let status = py
+            .allow_threads(|| self.runtime.block_on(self.service.status(meeting_id)))
+            .map_err(to_py_err)?;
+        match status {
+            MeetingStatus::Recording(status) => to_py(py, &status),
+            MeetingStatus::Queued(position) => to_py(
+                py,
+                &json!({
+                    "meeting_id": meeting_id,
+                    "status": "queued",
+                    "position": position,
+                }),
+            ),
+        }
+    }
+
+    /// A recording or stored meeting's whole transcript
+    fn transcript(&self, py: Python<'_>, meeting_id: &str) -> PyResult<PyObject> {
+        let transcript = py
+            .allow_threads(|| {
+                self.runtime.block_on(self.service.transcript(
+                    meeting_id,
+                    &TranscriptQuery::default(),
+                    usize::MAX,
+                ))
+            })
+            .map_err(to_py_err)?;
+        to_py(py, &transcript)
+    }
+
+    /// Stop and save every recording meeting, cancel queued ones, and stop
+    /// standby capture
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        py.allow_threads(|| self.runtime.block_on(self.service.close()))
+            .map_err(to_py_err)
+    }
+
+    /// Stored meetings, newest first
+    #[pyo3(signature = (limit = 50, offset = 0))]
+    fn list(&self, py: Python<'_>, limit: usize, offset: usize) -> PyResult<PyObject> {
+        let meetings = py
+            .allow_threads(|| self.runtime.block_on(self.service.list(limit, offset)))
+            .map_err(to_py_err)?;
+        to_py(py, &meetings)
+    }
+
+    /// A stored meeting: stats, transcript, markers, and audio chunks
+    fn meeting(&self, py: Python<'_>, meeting_id: &str) -> PyResult<PyObject> {
+        let record = py
+            .allow_threads(|| {
+                self.runtime
+                    .block_on(self.service.readable_meeting(meeting_id))
+            })
+            .map_err(to_py_err)?;
+        to_py(py, &record)
+    }
+
+    /// Transcribe a recording file as a new stored meeting, titled after
+    /// the file unless `title` is given
+    #[pyo3(signature = (path, title = None, meeting_id = None, track = 0, model = None, parallel = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn transcribe_file(
+        &self,
+        py: Python<'_>,
+        path: PathBuf,
+        title: Option<String>,
+        meeting_id: Option<String>,
+        track: usize,
+        model: Option<String>,
+        parallel: Option<bool>,
+    ) -> PyResult<PyObject> {
+        let query = UploadQuery {
+            meeting_id,
+            title,
+            track,
+            model,
+            parallel,
+        };
+        let state = AppState::from(self.service.clone());
+        let response = py
+            .allow_threads(|| self.runtime.block_on(transcribe_copy(&state, &path, query)))
+            .map_err(|e| PyRuntimeError::new_err(format!("{:#}", e)))?;
+        to_py(py, &response)
+    }
+}
+
+/// Python exception for a service error: ValueError for invalid requests,
+/// KeyError for unknown meetings, RuntimeError otherwise
+fn to_py_err(error: MeetingsError) -> PyErr {
+    match error {
+        MeetingsError::InvalidRequest(_) => PyValueError::new_err(error.to_string()),
+        MeetingsError::NotFound(_) => PyKeyError::new_err(error.to_string()),
+        _ => PyRuntimeError::new_err(error.to_string()),
+    }
+}
+
+/// A value as Python dicts and lists, through JSON
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(py
+        .import_bound("json")?
+        .call_method1("loads", (json,))?
+        .unbind())
+}
+
+/// A value from Python dicts and lists, through JSON
+fn from_py<T: DeserializeOwned>(py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json: String = py
+        .import_bound("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&json)
+        .map_err(|e| PyValueError::new_err(format!("Invalid options: {}", e)))
+}
+
+#[pymodule]
+pub fn loqa_meetings(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyAudioFile>()?;
+    m.add_class::<PyMeetings>()?;
+    m.add_function(wrap_pyfunction!(probe, m)?)?;
+    m.add("__version__", crate::version::VERSION)?;
+    Ok(())
+}
@@ -85,6 +85,11 @@ class AudioCaptureSession: NSObject, SCStreamDelegate, SCStreamOutput {
     private var callback: (@convention(c) (UnsafePointer<Int16>?, Int32, UInt32, UInt16, UInt8) -> Void)?
     private let sampleRate: UInt32
     private let channels: UInt16
+    private let excludedBundleIDs: Set<String>
+    private let pinnedMicrophoneID: String?
+    private var streamConfig: SCStreamConfiguration?
+    private var eventCallback: (@convention(c) (UInt8, UnsafePointer<CChar>?) -> Void)?
+    private var deviceListener: AudioObjectPropertyListenerBlock?
 
     // AVAudioEngine for mixing
     private let engine = AVAudioEngine()
@@ -97,15 +102,24 @@ class AudioCaptureSession: NSObject, SCStreamDelegate, SCStreamOutput {
     // Mix format: 48kHz stereo
     private let mixFormat = AVAudioFormat(standardFormatWithSampleRate: 48000, channels: 2)!
 
-    init(sampleRate: UInt32, channels: UInt16) {
+    // Shareable content snapshot (used to resolve application names)
+    private var shareableContent: SCShareableContent?
+
+    init(sampleRate: UInt32, channels: UInt16, excludedBundleIDs: [String], microphoneID: String?) {
         self.sampleRate = sampleRate
         self.channels = channels
+        self.excludedBundleIDs = Set(excludedBundleIDs)
+        self.pinnedMicrophoneID = microphoneID
         super.init()
     }
 
 
-    func start(callback: @escaping @convention(c) (UnsafePointer<Int16>?, Int32, UInt32, UInt16, UInt8) -> Void) async throws {
+    func start(
+        callback: @escaping @convention(c) (UnsafePointer<Int16>?, Int32, UInt32, UInt16, UInt8) -> Void,
+        eventCallback: @escaping @convention(c) (UInt8, UnsafePointer<CChar>?) -> Void
+    ) async throws {
         self.callback = callback
+        self.eventCallback = eventCallback
 
         // Create AVAudioSourceNode that pulls from ring buffers
         let systemRB = self.systemRingBuffer
@@ -167,20 +181,30 @@ class AudioCaptureSession: NSObject, SCStreamDelegate, SCStreamOutput {
             onScreenWindowsOnly: true
         )
 
+        shareableContent = content
+
         guard let display = content.displays.first else {
             throw NSError(domain: "ScreenCapture", code: 1, userInfo: [
                 NSLocalizedDescriptionKey: "No displays available"
             ])
         }
 
-        let filter = SCContentFilter(display: display, excludingWindows: [])
+        // Exclude applications (e.g. music players) from the captured audio
+        let excludedApps = content.applications.filter {
+            excludedBundleIDs.contains($0.bundleIdentifier)
+        }
+        if !excludedApps.isEmpty {
+            NSLog("ScreenCaptureKit: Excluding \(excludedApps.count) application(s) from capture")
+        }
+
+        let filter = SCContentFilter(display: display, excludingApplications: excludedApps, exceptingWindows: [])
         let config = SCStreamConfiguration()
         config.capturesAudio = true
         config.excludesCurrentProcessAudio = true
 
         if #available(macOS 15.0, *) {
             config.captureMicrophone = true
-            config.microphoneCaptureDeviceID = nil
+            config.microphoneCaptureDeviceID = pinnedMicrophoneID  // nil = system default
             NSLog("ScreenCaptureKit: Microphone capture enabled (macOS 15.0+)")
         } else {
             NSLog("ScreenCaptureKit: Microphone capture not available (requires macOS 15.0+)")
@@ -198,10 +222,15 @@ class AudioCaptureSession: NSObject, SCStreamDelegate, SCStreamOutput {
             }
         }
 
+        streamConfig = config
         try await stream?.startCapture()
+
+        installDeviceListener()
     }
 
     func stop() async throws {
+        removeDeviceListener()
+
         // Stop ScreenCaptureKit
         try await stream?.stopCapture()
         stream = nil
@@ -214,9 +243,100 @@ class AudioCaptureSession: NSObject, SCStreamDelegate, SCStreamOutput {
         sourceNode = nil
 
         callback = nil
+        eventCallback = nil
+        streamConfig = nil
+        shareableContent = nil
         NSLog("AudioCaptureSession stopped")
     }
 
+    /// Application names keyed by process ID, from the capture's content snapshot
+    func applicationNames() -> [pid_t: String] {
+        var names: [pid_t: String] = [:]
+        for app in shareableContent?.applications ?? [] {
+            names[app.processID] = app.applicationName
+        }
+        return names
+    }
+
+    // MARK: - Microphone device changes
+
+    private var inputDeviceAddress = AudioObjectPropertyAddress(
+        mSelector: kAudioHardwarePropertyDefaultInputDevice,
+        mScope: kAudioObjectPropertyScopeGlobal,
+        mElement: kAudioObjectPropertyElementMain
+    )
+
+    /// Watch for default input changes (e.g. AirPods connecting mid-meeting)
+    private func installDeviceListener() {
+        guard #available(macOS 15.0, *) else { return }
+
+        let listener: AudioObjectPropertyListenerBlock = { [weak self] _, _ in
+            self?.handleInputDeviceChange()
+        }
+
+        let status = AudioObjectAddPropertyListenerBlock(
+            AudioObjectID(kAudioObjectSystemObject),
+            &inputDeviceAddress,
+            DispatchQueue.global(qos: .userInitiated),
+            listener
+        )
+
+        if status == noErr {
+            deviceListener = listener
+        } else {
+            NSLog("ScreenCaptureKit: Failed to install input device listener (\(status))")
+        }
+    }
+
+    private func removeDeviceListener() {
+        guard let listener = deviceListener else { return }
+
+        AudioObjectRemovePropertyListenerBlock(
+            AudioObjectID(kAudioObjectSystemObject),
+            &inputDeviceAddress,
+            DispatchQueue.global(qos: .userInitiated),
+            listener
+        )
+        deviceListener = nil
+    }
+
+    /// Rebind microphone capture to the new default input (or the pinned device)
+    private func handleInputDeviceChange() {
+        guard #available(macOS 15.0, *), let stream = stream, let config = streamConfig else { return }
+
+        let defaultDevice = AVCaptureDevice.default(for: .audio)
+        emitEvent(0, defaultDevice?.localizedName ?? "unknown")
+
+        let target: AVCaptureDevice?
+        if let pinned = pinnedMicrophoneID {
+            target = AVCaptureDevice(uniqueID: pinned)
+            if target == nil {
+                emitEvent(2, "Pinned microphone \(pinned) is not available")
+                return
+            }
+        } else {
+            target = defaultDevice
+        }
+
+        config.microphoneCaptureDeviceID = target?.uniqueID
+        let name = target?.localizedName ?? "system default"
+
+        stream.updateConfiguration(config) { [weak self] error in
+            if let error = error {
+                NSLog("ScreenCaptureKit: Failed to rebind microphone: \(error)")
+                self?.emitEvent(2, "\(error.localizedDescription)")
+            } else {
+                NSLog("ScreenCaptureKit: Microphone rebound to \(name)")
+                self?.emitEvent(1, name)
+            }
+        }
+    }
+
+    private func emitEvent(_ kind: UInt8, _ detail: String) {
+        guard let cb = eventCallback else { return }
+        detail.withCString { cb(kind, $0) }
+    }
+
     // MARK: - SCStreamOutput (audio callback)
 
     func stream(
@@ -326,14 +446,29 @@ private var globalSession: AudioCaptureSession?
 public func startCapture(
     sampleRate: UInt32,
     channels: UInt16,
-    callback: @escaping @convention(c) (UnsafePointer<Int16>?, Int32, UInt32, UInt16, UInt8) -> Void
+    excludedApps: UnsafePointer<CChar>?,
+    microphoneDevice: UnsafePointer<CChar>?,
+    callback: @escaping @convention(c) (UnsafePointer<Int16>?, Int32, UInt32, UInt16, UInt8) -> Void,
+    eventCallback: @escaping @convention(c) (UInt8, UnsafePointer<CChar>?) -> Void
 ) -> Int32 {
     guard #available(macOS 13.0, *) else {
         return -1  // Not available
     }
 
     do {
-        let session = AudioCaptureSession(sampleRate: sampleRate, channels: channels)
+        // Comma-separated bundle IDs
+        let excluded = excludedApps.map { String(cString: $0) } ?? ""
+        let bundleIDs = excluded.split(separator: ",").map { String($0) }
+
+        // Empty string = follow the system default input
+        let microphone = microphoneDevice.map { String(cString: $0) } ?? ""
+
+        let session = AudioCaptureSession(
+            sampleRate: sampleRate,
+            channels: channels,
+            excludedBundleIDs: bundleIDs,
+            microphoneID: microphone.isEmpty ? nil : microphone
+        )
 
         // Start capture (async, but we'll block here for FFI simplicity)
         let group = DispatchGroup()
@@ -342,7 +477,7 @@ public func startCapture(
         group.enter()
         Task {
             do {
-                try await session.start(callback: callback)
+                try await session.start(callback: callback, eventCallback: eventCallback)
             } catch let e {
                 error = e
             }
@@ -392,3 +527,87 @@ public func stopCapture() -> Int32 {
 
     return error == nil ? 0 : -4  // Success or stop failed
 }
+
+// MARK: - Per-application audio activity
+
+/// Processes currently producing audio output (CoreAudio process objects, macOS 14.2+)
+@available(macOS 14.2, *)
+private func activeAudioProcesses() -> [(pid: pid_t, bundleID: String)] {
+    let systemObject = AudioObjectID(kAudioObjectSystemObject)
+    var address = AudioObjectPropertyAddress(
+        mSelector: kAudioHardwarePropertyProcessObjectList,
+        mScope: kAudioObjectPropertyScopeGlobal,
+        mElement: kAudioObjectPropertyElementMain
+    )
+
+    var size: UInt32 = 0
+    guard AudioObjectGetPropertyDataSize(systemObject, &address, 0, nil, &size) == noErr else {
+        return []
+    }
+
+    var objects = [AudioObjectID](repeating: 0, count: Int(size) / MemoryLayout<AudioObjectID>.size)
+    guard AudioObjectGetPropertyData(systemObject, &address, 0, nil, &size, &objects) == noErr else {
+        return []
+    }
+
+    var result: [(pid: pid_t, bundleID: String)] = []
+    let ownPid = getpid()
+
+    for object in objects {
+        var running: UInt32 = 0
+        var runningSize = UInt32(MemoryLayout<UInt32>.size)
+        address.mSelector = kAudioProcessPropertyIsRunningOutput
+        guard AudioObjectGetPropertyData(object, &address, 0, nil, &runningSize, &running) == noErr,
+              running != 0 else {
+            continue
+        }
+
+        var pid: pid_t = -1
+        var pidSize = UInt32(MemoryLayout<pid_t>.size)
+        address.mSelector = kAudioProcessPropertyPID
+        guard AudioObjectGetPropertyData(object, &address, 0, nil, &pidSize, &pid) == noErr,
+              pid != ownPid else {
+            continue
+        }
+
+        var bundleID: Unmanaged<CFString>?
+        var bundleSize = UInt32(MemoryLayout<Unmanaged<CFString>?>.size)
+        address.mSelector = kAudioProcessPropertyBundleID
+        let status = AudioObjectGetPropertyData(object, &address, 0, nil, &bundleSize, &bundleID)
+        let bundle = status == noErr ? (bundleID?.takeRetainedValue() as String?) ?? "" : ""
+
+        result.append((pid: pid, bundleID: bundle))
+    }
+
+    return result
+}
+
+/// Returns a JSON array of applications producing audio, or nil if unsupported.
+/// The caller must release the string with loqa_screencapture_free_string.
+@_cdecl("loqa_screencapture_copy_active_apps")
+public func copyActiveApps() -> UnsafeMutablePointer<CChar>? {
+    guard #available(macOS 14.2, *) else {
+        return nil  // Process audio objects not available
+    }
+
+    let names = globalSession?.applicationNames() ?? [:]
+    let apps: [[String: Any]] = activeAudioProcesses().map { process in
+        [
+            "pid": Int(process.pid),
+            "bundle_id": process.bundleID,
+            "name": names[process.pid] ?? process.bundleID,
+        ]
+    }
+
+    guard let data = try? JSONSerialization.data(withJSONObject: apps),
+          let json = String(data: data, encoding: .utf8) else {
+        return nil
+    }
+
+    return strdup(json)
+}
+
+@_cdecl("loqa_screencapture_free_string")
+public func freeString(_ ptr: UnsafeMutablePointer<CChar>?) {
+    free(ptr)
+}
@@ -5,12 +5,21 @@
 // This module provides a safe Rust interface to capture system audio
 // on macOS using ScreenCaptureKit via Swift FFI.
 
+#[cfg(target_os = "macos")]
+use anyhow::Context;
 use anyhow::{bail, Result};
+#[cfg(target_os = "macos")]
+use std::ffi::{c_char, CStr, CString};
+#[cfg(target_os = "macos")]
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
+#[cfg(target_os = "macos")]
 use tracing::{error, info};
 
-use crate::audio::backend::{AudioFrame, AudioStreamSource};
+use crate::audio::activity::AudioApplication;
+use crate::audio::backend::{AudioFrame, DeviceEvent};
+#[cfg(target_os = "macos")]
+use crate::audio::backend::{AudioStreamSource, DeviceEventKind};
 
 // MARK: - FFI declarations
 
@@ -22,10 +31,17 @@ extern "C" {
     fn loqa_screencapture_start(
         sample_rate: u32,
         channels: u16,
+        excluded_apps: *const c_char,
+        microphone_device: *const c_char,
         callback: extern "C" fn(*const i16, i32, u32, u16, u8),
+        event_callback: extern "C" fn(u8, *const c_char),
     ) -> i32;
 
     fn loqa_screencapture_stop() -> i32;
+
+    fn loqa_screencapture_copy_active_apps() -> *mut c_char;
+
+    fn loqa_screencapture_free_string(ptr: *mut c_char);
 }
 
 // MARK: - Safe Rust interface
@@ -41,12 +57,39 @@ pub fn is_available() -> bool {
     false
 }
 
+/// List applications currently producing audio output
+///
+/// Requires macOS 14.2+ (CoreAudio process objects); returns an empty list on
+/// older systems.
+#[cfg(target_os = "macos")]
+pub fn active_audio_applications() -> Result<Vec<AudioApplication>> {
+    let ptr = unsafe { loqa_screencapture_copy_active_apps() };
+    if ptr.is_null() {
+        return Ok(Vec::new());
+    }
+
+    let json = unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { loqa_screencapture_free_string(ptr) };
+
+    serde_json::from_str(&json).context("Failed to parse application list from bridge")
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn active_audio_applications() -> Result<Vec<AudioApplication>> {
+    Ok(Vec::new())
+}
+
 /// ScreenCaptureKit audio capture session
 #[cfg(target_os = "macos")]
 pub struct ScreenCaptureSession {
     sample_rate: u32,
     channels: u16,
+    excluded_apps: Vec<String>,
+    microphone_device: Option<String>,
     audio_tx: Option<mpsc::Sender<AudioFrame>>,
+    device_events: Option<mpsc::UnboundedReceiver<DeviceEvent>>,
     start_time_ms: Arc<Mutex<Option<u64>>>,
 }
 
@@ -57,11 +100,33 @@ impl ScreenCaptureSession {
         Self {
             sample_rate,
             channels,
+            excluded_apps: Vec::new(),
+            microphone_device: None,
             audio_tx: None,
+            device_events: None,
             start_time_ms: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Exclude applications (by bundle ID) from the system audio capture
+    pub fn with_excluded_apps(mut self, bundle_ids: Vec<String>) -> Self {
+        self.excluded_apps = bundle_ids;
+        self
+    }
+
+    /// Pin microphone capture to a device ID (None follows the system default)
+    pub fn with_microphone_device(mut self, device_id: Option<String>) -> Self {
+        self.microphone_device = device_id;
+        self
+    }
+
+    /// Take the receiver for microphone device change events
+    ///
+    /// Only available after `start()`.
+    pub fn take_device_events(&mut self) -> Option<mpsc::UnboundedReceiver<DeviceEvent>> {
+        self.device_events.take()
+    }
+
     /// Start capturing system audio
     ///
     /// Returns a channel receiver that will receive audio frames
@@ -75,6 +140,22 @@ impl ScreenCaptureSession {
             self.sample_rate, self.channels
         );
 
+        if !self.excluded_apps.is_empty() {
+            info!("Excluding applications: {}", self.excluded_apps.join(", "));
+        }
+
+        // Comma-separated bundle IDs for the Swift content filter
+        let excluded_apps = CString::new(self.excluded_apps.join(","))
+            .context("Excluded app bundle IDs must not contain NUL bytes")?;
+
+        // Pinned microphone (empty string = follow system default)
+        let microphone_device = CString::new(self.microphone_device.clone().unwrap_or_default())
+            .context("Microphone device ID must not contain NUL bytes")?;
+
+        // Create channel for device change events
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        self.device_events = Some(event_rx);
+
         // Create channel for audio frames (stereo output now)
         let (tx, rx) = mpsc::channel(100);
 
@@ -95,11 +176,20 @@ impl ScreenCaptureSession {
         unsafe {
             GLOBAL_START_TIME = start_time_ptr as *mut _;
             GLOBAL_AUDIO_TX = tx_ptr;
+            GLOBAL_EVENT_TX = Box::into_raw(Box::new(event_tx));
         }
 
         // Start capture
-        let result =
-            unsafe { loqa_screencapture_start(self.sample_rate, self.channels, audio_callback) };
+        let result = unsafe {
+            loqa_screencapture_start(
+                self.sample_rate,
+                self.channels,
+                excluded_apps.as_ptr(),
+                microphone_device.as_ptr(),
+                audio_callback,
+                device_event_callback,
+            )
+        };
 
         if result != 0 {
             bail!(
@@ -129,9 +219,14 @@ impl ScreenCaptureSession {
                 let _ = Box::from_raw(GLOBAL_AUDIO_TX);
                 GLOBAL_AUDIO_TX = std::ptr::null_mut();
             }
+            if !GLOBAL_EVENT_TX.is_null() {
+                let _ = Box::from_raw(GLOBAL_EVENT_TX);
+                GLOBAL_EVENT_TX = std::ptr::null_mut();
+            }
         }
 
         self.audio_tx = None;
+        self.device_events = None;
         *self.start_time_ms.lock().unwrap() = None;
 
         if result != 0 {
@@ -164,6 +259,25 @@ static mut GLOBAL_START_TIME: *mut Mutex<Option<u64>> = std::ptr::null_mut();
 #[cfg(target_os = "macos")]
 static mut GLOBAL_AUDIO_TX: *mut mpsc::Sender<AudioFrame> = std::ptr::null_mut();
 
+#[cfg(target_os = "macos")]
+static mut GLOBAL_EVENT_TX: *mut mpsc::UnboundedSender<DeviceEvent> = std::ptr::null_mut();
+
+/// Milliseconds elapsed since capture started
+#[cfg(target_os = "macos")]
+unsafe fn elapsed_ms() -> u64 {
+    let start_time_ms = if GLOBAL_START_TIME.is_null() {
+        0
+    } else {
+        (*GLOBAL_START_TIME).lock().unwrap().unwrap_or(0)
+    };
+
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    now_ms.saturating_sub(start_time_ms)
+}
+
 #[cfg(target_os = "macos")]
 extern "C" fn audio_callback(
     samples_ptr: *const i16,
@@ -182,19 +296,8 @@ extern "C" fn audio_callback(
             return;
         }
 
-        // Get start time
-        let start_time_ms = if GLOBAL_START_TIME.is_null() {
-            0
-        } else {
-            (*GLOBAL_START_TIME).lock().unwrap().unwrap_or(0)
-        };
-
         // Calculate timestamp
-        let now_ms = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
-        let timestamp_ms = now_ms - start_time_ms;
+        let timestamp_ms = elapsed_ms();
 
         // Copy stereo samples (already mixed by Swift)
         let samples = std::slice::from_raw_parts(samples_ptr, sample_count as usize).to_vec();
@@ -216,6 +319,47 @@ extern "C" fn audio_callback(
     }
 }
 
+// MARK: - Device event callback
+//
+// Swift reports microphone device changes (e.g. AirPods connecting) and
+// whether capture was rebound. Event codes:
+// 0 = default input changed, 1 = rebound, 2 = rebind failed
+
+#[cfg(target_os = "macos")]
+extern "C" fn device_event_callback(kind: u8, detail: *const c_char) {
+    let kind = match kind {
+        0 => DeviceEventKind::DefaultInputChanged,
+        1 => DeviceEventKind::Rebound,
+        2 => DeviceEventKind::RebindFailed,
+        other => {
+            error!("Unknown device event code: {}", other);
+            return;
+        }
+    };
+
+    unsafe {
+        if GLOBAL_EVENT_TX.is_null() {
+            return;
+        }
+
+        let device = if detail.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(detail).to_string_lossy().into_owned()
+        };
+
+        let event = DeviceEvent {
+            kind,
+            device,
+            timestamp_ms: elapsed_ms(),
+        };
+
+        if let Err(e) = (*GLOBAL_EVENT_TX).send(event) {
+            error!("Failed to send device event: {}", e);
+        }
+    }
+}
+
 // MARK: - Placeholder for non-macOS platforms
 
 #[cfg(not(target_os = "macos"))]
@@ -227,6 +371,18 @@ impl ScreenCaptureSession {
         Self
     }
 
+    pub fn with_excluded_apps(self, _bundle_ids: Vec<String>) -> Self {
+        self
+    }
+
+    pub fn with_microphone_device(self, _device_id: Option<String>) -> Self {
+        self
+    }
+
+    pub fn take_device_events(&mut self) -> Option<mpsc::UnboundedReceiver<DeviceEvent>> {
+        None
+    }
+
     pub fn start(&mut self) -> Result<mpsc::Receiver<AudioFrame>> {
         bail!("ScreenCaptureKit is only available on macOS")
     }
@@ -0,0 +1,1075 @@
+//! Meetings service: the recording session registry and meeting lifecycle
+//!
+//! `MeetingsService` starts, stops, lists, and reports on meetings the way
+//! the HTTP API does, so other applications (e.g. a tray app or CLI) can
+//! embed the service in-process without running the HTTP server.
+
+use crate::audio::{AudioBackendConfig, DirInUse, MeetingIndex, QualityPreset, ResamplerQuality};
+use crate::clock::{system_clock, SharedClock};
+use crate::config::Config;
+use crate::export::{after_note_exported, export_meeting_note};
+use crate::hooks::{spawn_hooks, HookContext, HookEvent};
+use crate::nats::AudioCodec;
+use crate::session::{
+    apply_chapters, detect_chapters, HostInfo, MeetingEvent, MicrophoneConfig, PipelineStage,
+    RecordingSession, SessionConfig, SessionEvent, SessionSlots, SessionStats, StageLatency,
+    Standby, StartRetryPolicy, TranscriptLog, TranscriptPage, TranscriptQuery, TranscriptSegment,
+};
+use crate::storage::{
+    candidate_ids, meeting_slug, parse_public_key, remove_unsealed, validate_new_meeting_id,
+    FilesystemStorage, MeetingRecord, MeetingSummary, Storage, StorageFactory,
+};
+use crate::stt::{BudgetStatus, SttBudget, SttConfig, SttProviderKind};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{error, info, warn};
+
+/// NATS server used for STT (TODO: Make configurable)
+pub(crate) const NATS_URL: &str = "nats://localhost:4222";
+
+/// Buffered service-wide events before slow subscribers start lagging
+const MEETING_EVENT_CAPACITY: usize = 256;
+
+/// How to start a meeting; anything not set comes from the profile and the
+/// service configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StartOptions {
+    /// Optional meeting ID (if not provided, generated from the date and
+    /// title, e.g. "2025-06-03-product-sync", or a UUID without a title)
+    pub meeting_id: Option<String>,
+
+    /// Optional meeting title
+    pub title: Option<String>,
+
+    /// Chunk duration in seconds (default: 300 = 5 minutes)
+    pub chunk_duration_secs: Option<u64>,
+
+    /// Seconds repeated from the end of each chunk at the start of the next
+    /// (overrides the config)
+    pub chunk_overlap_secs: Option<u64>,
+
+    /// Optional session profile name (default: "default" profile if configured)
+    pub profile: Option<String>,
+
+    /// Additional bundle IDs to exclude from capture (added to the profile's list)
+    #[serde(default)]
+    pub excluded_apps: Vec<String>,
+
+    /// Pin microphone capture to a device ID (overrides the profile)
+    pub microphone_device: Option<String>,
+
+    /// Additional microphones to capture and mix (overrides the profile)
+    pub microphones: Option<Vec<MicrophoneConfig>>,
+
+    /// Mixing gain for system audio (overrides the profile)
+    pub system_gain: Option<f32>,
+
+    /// Mixing gain for the captured microphone channel (overrides the profile)
+    pub microphone_gain: Option<f32>,
+
+    /// Live draft note refresh interval in seconds (overrides the config; 0 = off)
+    pub live_draft_interval_secs: Option<u64>,
+
+    /// Transcribe system and microphone audio as separate streams (overrides the profile)
+    pub dual_stream: Option<bool>,
+
+    /// Store chunks with system and microphone audio in separate, labelled
+    /// channels (overrides the profile)
+    pub stereo_chunks: Option<bool>,
+
+    /// Quality preset: "low-latency", "balanced", or "archival" (overrides the profile)
+    pub quality: Option<QualityPreset>,
+
+    /// Resampler interpolation, e.g. "linear" or "sinc-32" (overrides the preset)
+    pub resampler_quality: Option<ResamplerQuality>,
+
+    /// Process audio in 32-bit float between stages (overrides the preset)
+    pub float_pipeline: Option<bool>,
+
+    /// Codec for audio published to the STT service (overrides the config)
+    pub codec: Option<AudioCodec>,
+
+    /// Wait for a free slot when the session limit is reached, instead of
+    /// failing (overrides the config)
+    pub queue: Option<bool>,
+
+    /// Transcript-only session that never stores audio (overrides the profile)
+    pub privacy: Option<bool>,
+
+    /// Stream audio to STT; false records audio only (overrides the profile)
+    pub transcription: Option<bool>,
+
+    /// Store captured audio; false only streams it to STT, keeping the
+    /// transcript (default: true)
+    pub store_audio: Option<bool>,
+
+    /// STT provider, e.g. "nats", "openai", or "deepgram" (overrides the
+    /// profile)
+    pub stt: Option<SttProviderKind>,
+
+    /// Delete the meeting this many seconds after recording stops
+    /// (overrides the profile)
+    pub ttl_secs: Option<u64>,
+
+    /// X25519 public key (base64) to encrypt the stored transcript and audio
+    /// to; only the holder of the matching secret key can read them
+    pub encryption_key: Option<String>,
+
+    /// Milliseconds to wait for the first captured frame before failing the
+    /// start (overrides the config; 0 = don't wait)
+    pub warmup_ms: Option<u64>,
+
+    /// Start with the audio standby buffered before the request (default:
+    /// true; ignored when standby is off)
+    pub retroactive: Option<bool>,
+}
+
+/// Why a meetings service call failed
+#[derive(Debug)]
+pub enum MeetingsError {
+    /// The request is malformed or can't be satisfied as given
+    InvalidRequest(String),
+    /// No live, queued, or stored meeting has the ID
+    NotFound(String),
+    /// The meeting's current state rules the call out (e.g. it is already
+    /// recording, or is encrypted to a client key)
+    Conflict(String),
+    /// The session limit or the STT budget is reached
+    LimitReached(String),
+    /// Capture, STT, or storage failed
+    Failed(anyhow::Error),
+}
+
+impl fmt::Display for MeetingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRequest(message)
+            | Self::NotFound(message)
+            | Self::Conflict(message)
+            | Self::LimitReached(message) => f.write_str(message),
+            Self::Failed(e) => write!(f, "{:#}", e),
+        }
+    }
+}
+
+impl std::error::Error for MeetingsError {}
+
+/// What a start did
+pub enum StartOutcome {
+    /// The session is recording
+    Recording(Arc<RecordingSession>),
+    /// The session limit is reached; the session starts once another stops
+    Queued {
+        meeting_id: String,
+        /// 1-based place in the start queue
+        position: Option<usize>,
+    },
+}
+
+/// What a stop did
+#[derive(Debug)]
+pub enum StopOutcome {
+    /// The session stopped and the meeting was saved
+    Stopped(SessionStats),
+    /// The meeting was waiting for a slot and won't start
+    Cancelled,
+}
+
+/// A recording session's stats, pipeline timings, and the configuration it
+/// runs with
+#[derive(Debug, Serialize)]
+pub struct RecordingStatus {
+    #[serde(flatten)]
+    pub stats: SessionStats,
+    /// Time spent in each pipeline stage, to attribute latency spikes
+    pub latency: BTreeMap<PipelineStage, StageLatency>,
+    /// Sources currently muted ("system", "mic", "mic-<index>")
+    pub muted_sources: Vec<String>,
+    /// Cloud STT usage against the budget (cloud-transcribed sessions only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt_budget: Option<BudgetStatus>,
+    /// Effective session configuration (profile and request overrides
+    /// applied, credentials removed)
+    pub config: SessionConfig,
+}
+
+/// Where a live meeting is
+#[derive(Debug)]
+pub enum MeetingStatus {
+    Recording(Box<RecordingStatus>),
+    /// Waiting for a recording slot, at this 1-based place in the queue
+    Queued(usize),
+}
+
+/// A meeting's transcript, whole or one page of it
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Transcript {
+    Full(Vec<TranscriptSegment>),
+    Page(TranscriptPage),
+}
+
+/// Recording sessions and stored meetings, shared by the HTTP API and
+/// in-process embedders
+#[derive(Clone)]
+pub struct MeetingsService {
+    /// Active recording sessions (meeting_id → session)
+    pub sessions: Arc<RwLock<HashMap<String, Arc<RecordingSession>>>>,
+
+    /// Admission control for concurrent sessions (and the start queue)
+    pub slots: Arc<SessionSlots>,
+
+    /// Service configuration (profiles, paths)
+    pub config: Arc<Config>,
+
+    /// Persistence for finished meetings
+    pub storage: Arc<dyn Storage>,
+
+    /// Events for meetings that are not live (e.g. bundle export progress)
+    pub meeting_events: broadcast::Sender<MeetingEvent>,
+
+    /// Minutes transcribed by cloud STT providers, against the budget
+    pub stt_budget: Arc<SttBudget>,
+
+    /// Audio buffered between meetings (None = standby is off)
+    pub standby: Option<Arc<Standby>>,
+
+    /// Time source for the service and the sessions it starts
+    pub clock: SharedClock,
+}
+
+impl MeetingsService {
+    pub fn new() -> Self {
+        let config = Config::default();
+        let storage = Arc::new(FilesystemStorage::new(config.storage.resolved_path()));
+        Self::with_config(config, storage)
+    }
+
+    /// Open the storage `config` names and create a service over it
+    pub fn open(config: Config) -> anyhow::Result<Self> {
+        let storage = StorageFactory::create(&config.storage)?;
+        Ok(Self::with_config(config, storage))
+    }
+
+    pub fn with_config(config: Config, storage: Arc<dyn Storage>) -> Self {
+        // Sessions record 16kHz mono, the backend default
+        let standby = config.standby.enabled.then(|| {
+            Arc::new(Standby::new(
+                &config.standby,
+                AudioBackendConfig::default(),
+                config.audio.downmix.clone(),
+                config.audio.stages.clone(),
+            ))
+        });
+
+        Self {
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            standby,
+            slots: Arc::new(SessionSlots::new(config.sessions.max_concurrent)),
+            stt_budget: Arc::new(SttBudget::new(config.stt.budget.clone())),
+            config: Arc::new(config),
+            storage,
+            meeting_events: broadcast::channel(MEETING_EVENT_CAPACITY).0,
+            clock: system_clock(),
+        }
+    }
+
+    /// Take the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Start recording a meeting
+    ///
+    /// At the session limit this fails, or with `queue` returns
+    /// [`StartOutcome::Queued`] and starts the session once another one
+    /// stops (reporting the outcome as a meeting event).
+    pub async fn start(&self, options: StartOptions) -> Result<StartOutcome, MeetingsError> {
+        // Generate or use provided meeting ID
+        let meeting_id = match options.meeting_id.clone() {
+            Some(meeting_id) => {
+                validate_new_meeting_id(&meeting_id)
+                    .map_err(|e| MeetingsError::InvalidRequest(e.to_string()))?;
+                meeting_id
+            }
+            None => match &options.title {
+                Some(title) => self.generate_meeting_id(title).await,
+                None => format!("meeting-{}", uuid::Uuid::new_v4()),
+            },
+        };
+
+        match &options.title {
+            Some(title) => info!("Starting recording for meeting: {} ({})", meeting_id, title),
+            None => info!("Starting recording for meeting: {}", meeting_id),
+        }
+
+        // Check if already recording
+        if self.sessions.read().await.contains_key(&meeting_id) {
+            return Err(MeetingsError::Conflict(format!(
+                "Meeting {} is already recording",
+                meeting_id
+            )));
+        }
+        if self.slots.queue_position(&meeting_id).is_some() {
+            return Err(MeetingsError::Conflict(format!(
+                "Meeting {} is already queued to record",
+                meeting_id
+            )));
+        }
+
+        let queue = options.queue.unwrap_or(self.config.sessions.queue);
+        let config = self.session_config(meeting_id.clone(), options)?;
+
+        // Take a recording slot, or wait in line for one
+        if !self.slots.try_admit(&meeting_id) {
+            if !queue {
+                return Err(MeetingsError::LimitReached(self.sessions_full().await));
+            }
+
+            let admission = self.slots.admit(&meeting_id);
+            let position = self.slots.queue_position(&meeting_id);
+            info!(
+                "Recording limit reached, queued meeting {} (position {})",
+                meeting_id,
+                position.unwrap_or(1)
+            );
+            tokio::spawn(self.clone().start_when_admitted(config, admission));
+            return Ok(StartOutcome::Queued {
+                meeting_id,
+                position,
+            });
+        }
+
+        match self.launch_session(config).await {
+            Ok(session) => {
+                info!("Recording started successfully for meeting: {}", meeting_id);
+                Ok(StartOutcome::Recording(session))
+            }
+            Err(e) => {
+                error!("{:#}", e);
+                if e.downcast_ref::<DirInUse>().is_some() {
+                    Err(MeetingsError::Conflict(format!("{:#}", e)))
+                } else {
+                    Err(MeetingsError::Failed(e))
+                }
+            }
+        }
+    }
+
+    /// The session configuration `options` make for `meeting_id`, with the
+    /// profile and service configuration filling the gaps
+    fn session_config(
+        &self,
+        meeting_id: String,
+        options: StartOptions,
+    ) -> Result<SessionConfig, MeetingsError> {
+        let config = &self.config;
+
+        // Resolve session profile
+        let profile = config.profile(options.profile.as_deref()).ok_or_else(|| {
+            MeetingsError::InvalidRequest(format!(
+                "Unknown profile: {}",
+                options.profile.clone().unwrap_or_default()
+            ))
+        })?;
+
+        if let Some(key) = &options.encryption_key {
+            parse_public_key(key).map_err(|e| MeetingsError::InvalidRequest(format!("{:#}", e)))?;
+        }
+
+        let privacy = options.privacy.unwrap_or(profile.privacy);
+        let transcription = options
+            .transcription
+            .or(profile.transcription)
+            .unwrap_or(true);
+        if privacy && !transcription {
+            return Err(MeetingsError::InvalidRequest(
+                "A privacy session must be transcribed; it would record nothing".to_string(),
+            ));
+        }
+        let store_audio = options.store_audio.unwrap_or(true);
+        if !store_audio && !transcription {
+            return Err(MeetingsError::InvalidRequest(
+                "A session that stores no audio must be transcribed; it would record nothing"
+                    .to_string(),
+            ));
+        }
+
+        // Cloud-transcribed sessions record without STT once the budget is spent
+        let stt_provider = options.stt.or(profile.stt).unwrap_or(config.stt.provider);
+        let transcription = match self.stt_budget.exhausted() {
+            Some(period) if transcription && stt_provider.is_cloud() => {
+                if privacy || !store_audio {
+                    return Err(MeetingsError::LimitReached(format!(
+                        "The {} STT budget is exhausted, and this session stores no audio",
+                        period
+                    )));
+                }
+                warn!(
+                    "The {} STT budget is exhausted; meeting {} records without transcription",
+                    period, meeting_id
+                );
+                false
+            }
+            _ => transcription,
+        };
+
+        // The meeting's expiry must be a representable time
+        let ttl_secs = options.ttl_secs.or(profile.ttl_secs);
+        if let Some(secs) = ttl_secs {
+            let expiry = i64::try_from(secs)
+                .ok()
+                .and_then(chrono::Duration::try_seconds)
+                .and_then(|ttl| self.clock.now().checked_add_signed(ttl));
+            if expiry.is_none() {
+                return Err(MeetingsError::InvalidRequest(format!(
+                    "ttl_secs is too large: {}",
+                    secs
+                )));
+            }
+        }
+
+        let mut excluded_apps = profile.excluded_apps;
+        for app in options.excluded_apps {
+            if !excluded_apps.contains(&app) {
+                excluded_apps.push(app);
+            }
+        }
+
+        Ok(SessionConfig {
+            audio_dir: Some(config.audio.resolved_recordings_path().join(&meeting_id)),
+            session_id: meeting_id,
+            title: options.title,
+            profile: options.profile,
+            chunk_duration: Duration::from_secs(options.chunk_duration_secs.unwrap_or(300)),
+            sample_rate: 16000, // Whisper expects 16kHz
+            channels: 1,        // Mono
+            nats_url: NATS_URL.to_string(),
+            excluded_apps,
+            microphone_device: options.microphone_device.or(profile.microphone_device),
+            microphones: options.microphones.unwrap_or(profile.microphones),
+            network_source: profile.network_source,
+            system_gain: options.system_gain.or(profile.system_gain).unwrap_or(1.0),
+            microphone_gain: options
+                .microphone_gain
+                .or(profile.microphone_gain)
+                .unwrap_or(1.0),
+            // A live draft would write an encrypted meeting's transcript in plaintext
+            live_draft_interval: match options.encryption_key {
+                Some(_) => Duration::ZERO,
+                None => Duration::from_secs(
+                    options
+                        .live_draft_interval_secs
+                        .unwrap_or(config.obsidian.live_draft_interval_secs),
+                ),
+            },
+            dual_stream: options.dual_stream.unwrap_or(profile.dual_stream),
+            stereo_chunks: options.stereo_chunks.unwrap_or(profile.stereo_chunks),
+            privacy,
+            store_audio,
+            transcription,
+            ttl: ttl_secs.map(Duration::from_secs),
+            encryption_key: options.encryption_key,
+            quality: options.quality.or(profile.quality).unwrap_or_default(),
+            resampler_quality: options.resampler_quality,
+            float_pipeline: options.float_pipeline,
+            audio_codec: options.codec.unwrap_or(config.audio.codec),
+            batch_frames: config.audio.batch_frames.max(1),
+            utterance: config.audio.utterance.clone(),
+            chunk_rotation: config.audio.chunk_rotation.clone(),
+            chunk_overlap: Duration::from_secs(
+                options
+                    .chunk_overlap_secs
+                    .unwrap_or(config.audio.chunk_overlap_secs),
+            ),
+            transcript_stream: config.transcripts.clone(),
+            stt: SttConfig {
+                provider: stt_provider,
+                ..config.stt.clone()
+            },
+            normalization: config.normalization.clone(),
+            translation: config.translation.clone(),
+            transcript_filter: config.transcript_filter.clone(),
+            playback: config.playback.clone(),
+            channel_map: config.audio.channel_map.clone(),
+            downmix: config.audio.downmix.clone(),
+            stages: config.audio.stages.clone(),
+            format_mismatch: config.audio.format_mismatch,
+            warmup: Duration::from_millis(options.warmup_ms.unwrap_or(config.audio.warmup_ms)),
+            watchdog: config.audio.watchdog.clone(),
+            preroll: Duration::from_secs(config.audio.preroll_secs),
+            retroactive: options.retroactive.unwrap_or(true),
+            start_retry: StartRetryPolicy {
+                max_retries: config.sessions.start_retries,
+                backoff: Duration::from_millis(config.sessions.start_retry_backoff_ms),
+            },
+            host: config
+                .host
+                .report
+                .then(|| HostInfo::current(config.host.name.clone())),
+            clock: Arc::clone(&self.clock),
+            ..SessionConfig::default()
+        })
+    }
+
+    /// Stop a recording meeting and save it, or cancel a queued one
+    ///
+    /// The stored meeting gets its chapters, is encrypted if the session was
+    /// started with a client key, and replaces the live draft note.
+    pub async fn stop(&self, meeting_id: &str) -> Result<StopOutcome, MeetingsError> {
+        info!("Stopping recording for meeting: {}", meeting_id);
+
+        // Find and remove session
+        let Some(session) = self.sessions.write().await.remove(meeting_id) else {
+            if self.slots.cancel(meeting_id) {
+                info!("Cancelled queued meeting {}", meeting_id);
+                return Ok(StopOutcome::Cancelled);
+            }
+            error!("Meeting {} not found", meeting_id);
+            return Err(MeetingsError::NotFound(format!(
+                "Meeting {} not found",
+                meeting_id
+            )));
+        };
+
+        let stats = match session.stop().await {
+            Ok(stats) => stats,
+            Err(e) => {
+                error!("Failed to stop recording: {}", e);
+                self.release_slot(meeting_id).await;
+                return Err(MeetingsError::Failed(e.context("Failed to stop recording")));
+            }
+        };
+        info!("Recording stopped successfully for meeting: {}", meeting_id);
+
+        let mut record = meeting_record(&session, &stats).await;
+        let chapters =
+            detect_chapters(&self.config.chapters, record.started_at, &record.transcript).await;
+        apply_chapters(&mut record.markers, record.started_at, chapters);
+        let mut unsealed = Vec::new();
+        if let Some(key) = session.encryption_key() {
+            let key = key.to_string();
+            let sealed = tokio::task::spawn_blocking(move || {
+                record.seal(&key).map(|unsealed| (record, unsealed))
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|sealed| sealed);
+            (record, unsealed) = match sealed {
+                Ok(sealed) => sealed,
+                Err(e) => {
+                    // Never store an encrypted meeting's plaintext. Sealing
+                    // left the chunks untouched, so keep the stopped session
+                    // for stopping again to retry.
+                    error!("Failed to encrypt meeting {}: {:#}", meeting_id, e);
+                    self.keep_unsaved(meeting_id, session).await;
+                    return Err(MeetingsError::Failed(e.context(
+                        "Failed to encrypt meeting; it is kept unsaved until stopped again",
+                    )));
+                }
+            };
+        }
+
+        // Stored before the plaintext of a sealed meeting is deleted, so a
+        // failure leaves the chunks to seal and save again
+        if let Err(e) = persist_meeting(self.storage.as_ref(), &record).await {
+            error!("{:#}", e);
+            if record.sealed.is_some() {
+                // The chunks sealed for this attempt; sealing again rewrites them
+                let sealed: Vec<_> = record.chunks.iter().map(|c| c.file_path.clone()).collect();
+                remove_unsealed(meeting_id, &sealed);
+            }
+            self.keep_unsaved(meeting_id, session).await;
+            return Err(MeetingsError::Failed(e.context(
+                "Failed to save meeting; it is kept unsaved until stopped again",
+            )));
+        }
+        remove_unsealed(meeting_id, &unsealed);
+        self.release_slot(meeting_id).await;
+        let mut index = saved_meeting_index(self.storage.as_ref(), &record);
+        let mut hooks = self.hook_context(&session);
+        hooks.duration = Some(Duration::from_secs_f64(stats.duration_secs.max(0.0)));
+        if !session.live_draft_interval().is_zero() {
+            // Replace the draft with the complete transcript
+            if let Some(path) = write_draft(&self.config, record).await {
+                after_note_exported(&self.config.obsidian.after_export, meeting_id, &path).await;
+                index.note_path = Some(path.clone());
+                hooks.note_path = Some(path);
+                spawn_hooks(&self.config.hooks, HookEvent::Export, hooks.clone());
+            }
+        }
+        write_meeting_index(&self.config, &index);
+        spawn_hooks(&self.config.hooks, HookEvent::Stop, hooks);
+        if let Err(e) = session.close().await {
+            warn!(
+                "Failed to close meeting {}'s STT connection: {:#}",
+                meeting_id, e
+            );
+        }
+
+        Ok(StopOutcome::Stopped(stats))
+    }
+
+    /// Shut the service down: cancel queued starts, stop and save every
+    /// recording meeting, and stop standby capture
+    ///
+    /// Every meeting is stopped even if one fails; the first failure is
+    /// returned.
+    pub async fn close(&self) -> Result<(), MeetingsError> {
+        // Cancelled first, so stopped sessions' slots don't admit them
+        for meeting_id in self.slots.queued() {
+            self.slots.cancel(&meeting_id);
+        }
+
+        let mut result = Ok(());
+        let meeting_ids: Vec<String> = self.sessions.read().await.keys().cloned().collect();
+        for meeting_id in meeting_ids {
+            match self.stop(&meeting_id).await {
+                // Stopped by someone else meanwhile
+                Ok(_) | Err(MeetingsError::NotFound(_)) => {}
+                Err(e) => {
+                    if result.is_ok() {
+                        result = Err(e);
+                    }
+                }
+            }
+        }
+
+        // Stopping the last session resumes standby
+        if let Some(standby) = &self.standby {
+            standby.pause().await;
+        }
+        result
+    }
+
+    /// Stored meetings, newest first
+    pub async fn list(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<MeetingSummary>, MeetingsError> {
+        self.storage
+            .list_meetings(limit, offset)
+            .await
+            .map_err(|e| {
+                error!("Failed to list meetings: {}", e);
+                MeetingsError::Failed(e.context("Failed to list meetings"))
+            })
+    }
+
+    /// Status of a recording or queued meeting
+    pub async fn status(&self, meeting_id: &str) -> Result<MeetingStatus, MeetingsError> {
+        let sessions = self.sessions.read().await;
+
+        let Some(session) = sessions.get(meeting_id) else {
+            return match self.slots.queue_position(meeting_id) {
+                Some(position) => Ok(MeetingStatus::Queued(position)),
+                None => Err(MeetingsError::NotFound(format!(
+                    "Meeting {} not found",
+                    meeting_id
+                ))),
+            };
+        };
+
+        let stats = session.get_stats().await.map_err(|e| {
+            error!("Failed to get stats: {}", e);
+            MeetingsError::Failed(e.context("Failed to get stats"))
+        })?;
+        Ok(MeetingStatus::Recording(Box::new(RecordingStatus {
+            stats,
+            latency: session.pipeline_latency(),
+            muted_sources: session
+                .muted_sources()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            stt_budget: session.stt_budget(),
+            config: session.effective_config(),
+        })))
+    }
+
+    /// A meeting's transcript: accumulated so far while it records, stored
+    /// once stopped
+    ///
+    /// A paged query (see [`TranscriptQuery::is_paged`]) returns one page of
+    /// at most `limit` segments, otherwise the whole transcript.
+    pub async fn transcript(
+        &self,
+        meeting_id: &str,
+        query: &TranscriptQuery,
+        limit: usize,
+    ) -> Result<Transcript, MeetingsError> {
+        if let Some(session) = self.sessions.read().await.get(meeting_id) {
+            if query.is_paged() {
+                return Ok(Transcript::Page(
+                    session.transcript_page(query, limit).await,
+                ));
+            }
+            return Ok(Transcript::Full(session.get_transcript().await));
+        }
+
+        // Fall back to stored meetings
+        let record = self.readable_meeting(meeting_id).await?;
+        if query.is_paged() {
+            let log = TranscriptLog::from_segments(record.transcript);
+            return Ok(Transcript::Page(log.page(record.started_at, query, limit)));
+        }
+        Ok(Transcript::Full(record.transcript))
+    }
+
+    /// Load a stored meeting whose content the service can read
+    ///
+    /// Meetings sealed to a client key are a conflict pointing at the
+    /// sealed content.
+    pub async fn readable_meeting(&self, meeting_id: &str) -> Result<MeetingRecord, MeetingsError> {
+        let record = self.stored_meeting(meeting_id).await?;
+        if record.sealed.is_some() {
+            return Err(MeetingsError::Conflict(format!(
+                "Meeting {} is encrypted to a client key; fetch GET /meetings/{}/sealed and decrypt it client-side",
+                meeting_id, meeting_id
+            )));
+        }
+        Ok(record)
+    }
+
+    /// Load a stored meeting, sealed or not
+    pub async fn stored_meeting(&self, meeting_id: &str) -> Result<MeetingRecord, MeetingsError> {
+        match self.storage.get_meeting(meeting_id).await {
+            // Expired meetings are gone even before the retention worker runs
+            Ok(Some(record)) if !record.is_expired(self.clock.now()) => Ok(record),
+            Ok(_) => Err(MeetingsError::NotFound(format!(
+                "Meeting {} not found",
+                meeting_id
+            ))),
+            Err(e) => {
+                error!("Failed to load meeting {}: {}", meeting_id, e);
+                Err(MeetingsError::Failed(e.context("Failed to load meeting")))
+            }
+        }
+    }
+
+    /// Readable ID for a new meeting titled `title`, suffixed (`-2`, `-3`,
+    /// ...) if a live, queued, or stored meeting already has it
+    pub(crate) async fn generate_meeting_id(&self, title: &str) -> String {
+        let today = self.clock.now().with_timezone(&chrono::Local).date_naive();
+        let base = meeting_slug(today, title);
+        for candidate in candidate_ids(&base) {
+            let live = self.sessions.read().await.contains_key(&candidate)
+                || self.slots.queue_position(&candidate).is_some();
+            let stored = match self.storage.get_meeting(&candidate).await {
+                Ok(existing) => existing.is_some(),
+                Err(e) => {
+                    error!("Failed to check for meeting {}: {}", candidate, e);
+                    true
+                }
+            };
+            if !live && !stored {
+                return candidate;
+            }
+        }
+
+        format!(
+            "{}-{}",
+            base,
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        )
+    }
+
+    /// Create and start a session that holds a recording slot, and register it
+    ///
+    /// The slot is released again if the session fails to start.
+    async fn launch_session(&self, config: SessionConfig) -> anyhow::Result<Arc<RecordingSession>> {
+        let meeting_id = config.session_id.clone();
+
+        // Standby shares the system capture, so it pauses while sessions record
+        let backfill = match &self.standby {
+            Some(standby) => standby.pause().await,
+            None => None,
+        };
+        let backfill = backfill.filter(|_| config.retroactive);
+
+        let result = async {
+            let mut session =
+                RecordingSession::with_stt_budget(config, Some(Arc::clone(&self.stt_budget)))
+                    .await
+                    .context("Failed to create session")?;
+            if let Some(audio) = backfill {
+                session = session.with_backfill(audio);
+            }
+            let session = Arc::new(session);
+            session.start().await.context("Failed to start recording")?;
+            Ok(session)
+        }
+        .await;
+
+        let session = match result {
+            Ok(session) => session,
+            Err(e) => {
+                self.slots.release(&meeting_id);
+                self.resume_standby().await;
+                return Err(e);
+            }
+        };
+
+        if !session.live_draft_interval().is_zero() {
+            spawn_live_draft(Arc::clone(&self.config), Arc::clone(&session));
+        }
+
+        self.sessions
+            .write()
+            .await
+            .insert(meeting_id, Arc::clone(&session));
+        spawn_hooks(
+            &self.config.hooks,
+            HookEvent::Start,
+            self.hook_context(&session),
+        );
+        Ok(session)
+    }
+
+    /// The meeting `session` records, as hooks see it
+    fn hook_context(&self, session: &RecordingSession) -> HookContext {
+        HookContext {
+            title: session.title().map(str::to_string),
+            profile: session.profile().map(str::to_string),
+            audio_dir: Some(
+                self.config
+                    .audio
+                    .resolved_recordings_path()
+                    .join(session.session_id()),
+            ),
+            ..HookContext::new(session.session_id())
+        }
+    }
+
+    /// Free a stopped meeting's slot for the next queued session
+    async fn release_slot(&self, meeting_id: &str) {
+        self.slots.release(meeting_id);
+        self.resume_standby().await;
+    }
+
+    /// Put back a stopped session whose meeting could not be saved, still
+    /// holding its slot, so stopping it again retries
+    async fn keep_unsaved(&self, meeting_id: &str, session: Arc<RecordingSession>) {
+        self.sessions
+            .write()
+            .await
+            .entry(meeting_id.to_string())
+            .or_insert(session);
+    }
+
+    /// Resume standby once no session is recording
+    async fn resume_standby(&self) {
+        let Some(standby) = &self.standby else {
+            return;
+        };
+        if !self.sessions.read().await.is_empty() {
+            return;
+        }
+        if let Err(e) = standby.resume().await {
+            warn!("Standby is not buffering audio: {:#}", e);
+        }
+    }
+
+    /// Start a queued session once it is admitted, reporting the outcome as a
+    /// meeting event
+    async fn start_when_admitted(
+        self,
+        config: SessionConfig,
+        admission: impl std::future::Future<Output = bool>,
+    ) {
+        let meeting_id = config.session_id.clone();
+        if !admission.await {
+            info!("Queued meeting {} was cancelled", meeting_id);
+            return;
+        }
+
+        info!(
+            "Recording slot free, starting queued meeting {}",
+            meeting_id
+        );
+        let event = match self.launch_session(config).await {
+            Ok(_) => {
+                info!("Recording started successfully for meeting: {}", meeting_id);
+                SessionEvent::Started
+            }
+            Err(e) => {
+                error!("Queued meeting {}: {:#}", meeting_id, e);
+                SessionEvent::StartFailed {
+                    error: format!("{:#}", e),
+                }
+            }
+        };
+
+        // No subscribers is not an error
+        let _ = self.meeting_events.send(MeetingEvent { meeting_id, event });
+    }
+
+    /// Session limit error naming the sessions holding every slot
+    async fn sessions_full(&self) -> String {
+        let mut active: Vec<String> = self.sessions.read().await.keys().cloned().collect();
+        active.sort();
+
+        format!(
+            "Recording limit reached: {} of {} sessions recording ({}). Stop one, or start with \"queue\": true to record once a slot frees up",
+            self.slots.active(),
+            self.slots.limit().unwrap_or_default(),
+            active.join(", ")
+        )
+    }
+}
+
+impl Default for MeetingsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Snapshot a session as a meeting record (final transcript segments only)
+async fn meeting_record(session: &RecordingSession, stats: &SessionStats) -> MeetingRecord {
+    let transcript = session
+        .get_transcript()
+        .await
+        .into_iter()
+        .filter(|segment| !segment.partial)
+        .collect();
+    // As of the first stop, however long a retried save takes
+    let ended_at = session
+        .stopped_at()
+        .unwrap_or_else(|| session.clock().now());
+
+    MeetingRecord {
+        meeting_id: session.session_id().to_string(),
+        title: session.title().map(str::to_string),
+        profile: session.profile().map(str::to_string),
+        host: session.host(),
+        started_at: stats.started_at,
+        ended_at,
+        stats: stats.clone(),
+        transcript,
+        markers: session.get_markers().await,
+        chunks: session.chunks(),
+        edits: Vec::new(),
+        sealed: None,
+        // Past the last representable time, it expires then rather than never
+        expires_at: session.ttl().map(|ttl| {
+            chrono::Duration::from_std(ttl)
+                .ok()
+                .and_then(|ttl| ended_at.checked_add_signed(ttl))
+                .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC)
+        }),
+    }
+}
+
+/// Save a stopped meeting to storage
+async fn persist_meeting(storage: &dyn Storage, record: &MeetingRecord) -> anyhow::Result<()> {
+    storage
+        .save_meeting(record)
+        .await
+        .with_context(|| format!("Failed to save meeting {}", record.meeting_id))?;
+    info!(
+        "Saved meeting {} to {} storage",
+        record.meeting_id,
+        storage.name()
+    );
+    Ok(())
+}
+
+/// Refresh a recording meeting's note in the vault at its live draft interval
+///
+/// The task ends on the first tick after the session stops; stopping the
+/// meeting writes the final version.
+fn spawn_live_draft(config: Arc<Config>, session: Arc<RecordingSession>) {
+    let interval = session.live_draft_interval();
+
+    tokio::spawn(async move {
+        info!(
+            "Live draft for {} every {}s",
+            session.session_id(),
+            interval.as_secs()
+        );
+
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+        let mut written = 0;
+
+        loop {
+            ticker.tick().await;
+
+            let stats = match session.get_stats().await {
+                Ok(stats) if stats.is_recording => stats,
+                _ => break,
+            };
+
+            // Nothing new since the last refresh
+            if stats.transcript_segments_count == written {
+                continue;
+            }
+            written = stats.transcript_segments_count;
+
+            let record = meeting_record(&session, &stats).await;
+            write_draft(&config, record).await;
+        }
+    });
+}
+
+/// Index describing a saved meeting's recordings directory
+pub(crate) fn saved_meeting_index(storage: &dyn Storage, record: &MeetingRecord) -> MeetingIndex {
+    let transcript = match storage.meeting_file(&record.meeting_id) {
+        Some(path) => format!("`{}`", path.display()),
+        None => format!("In the {} meeting store", storage.name()),
+    };
+    MeetingIndex {
+        title: record.title.clone(),
+        duration: Some(Duration::from_secs_f64(record.stats.duration_secs.max(0.0))),
+        chunks: record.chunks.clone(),
+        transcript: Some(transcript),
+        encrypted: record.sealed.is_some(),
+        ..MeetingIndex::new(&record.meeting_id, record.started_at)
+    }
+}
+
+/// Write `index` into its meeting's recordings directory, if the meeting
+/// has one, logging failures
+pub(crate) fn write_meeting_index(config: &Config, index: &MeetingIndex) {
+    let dir = config
+        .audio
+        .resolved_recordings_path()
+        .join(&index.meeting_id);
+    if !dir.is_dir() {
+        return;
+    }
+    if let Err(e) = index.write(&dir) {
+        warn!("Failed to update index for {}: {:#}", index.meeting_id, e);
+    }
+}
+
+/// Write a meeting's note per the export mode, logging failures
+async fn write_draft(config: &Arc<Config>, record: MeetingRecord) -> Option<PathBuf> {
+    let config = Arc::clone(config);
+    let meeting_id = record.meeting_id.clone();
+
+    let written = tokio::task::spawn_blocking(move || {
+        let format = config.note_format(record.profile.as_deref());
+        export_meeting_note(&config.obsidian, &format, &record)
+    })
+    .await;
+
+    match written {
+        Ok(Ok(path)) => Some(path),
+        Ok(Err(e)) => {
+            warn!("Failed to write draft note for {}: {:#}", meeting_id, e);
+            None
+        }
+        Err(e) => {
+            error!("Draft note task panicked: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+
+/// Admission control for concurrently recording sessions
+///
+/// Each recording session holds a slot from admission until it stops.
+/// Requests beyond the limit either fail fast (`try_admit`) or wait in
+/// first-come, first-served order (`admit`) until a slot is released.
+pub struct SessionSlots {
+    /// Most sessions admitted at once (None = unlimited)
+    limit: Option<usize>,
+    semaphore: Option<Arc<Semaphore>>,
+    /// Slots held by admitted sessions
+    held: Mutex<HashMap<String, Option<OwnedSemaphorePermit>>>,
+    /// Sessions waiting for a slot, oldest first, with a handle to cancel each
+    queued: Mutex<Vec<(String, oneshot::Sender<()>)>>,
+}
+
+impl SessionSlots {
+    /// Slots for up to `limit` sessions (0 = unlimited)
+    pub fn new(limit: usize) -> Self {
+        let limit = (limit > 0).then_some(limit);
+        Self {
+            limit,
+            semaphore: limit.map(|limit| Arc::new(Semaphore::new(limit))),
+            held: Mutex::new(HashMap::new()),
+            queued: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Sessions currently holding a slot
+    pub fn active(&self) -> usize {
+        self.held.lock().unwrap().len()
+    }
+
+    /// Take a slot for `meeting_id` if one is free
+    pub fn try_admit(&self, meeting_id: &str) -> bool {
+        let permit = match &self.semaphore {
+            Some(semaphore) => match Arc::clone(semaphore).try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => return false,
+            },
+            None => None,
+        };
+
+        self.held
+            .lock()
+            .unwrap()
+            .insert(meeting_id.to_string(), permit);
+        true
+    }
+
+    /// Join the queue for a slot for `meeting_id`
+    ///
+    /// The meeting is queued as soon as this returns; the future resolves
+    /// once it holds a slot (`true`) or the wait is cancelled with
+    /// [`SessionSlots::cancel`] (`false`).
+    pub fn admit(
+        self: &Arc<Self>,
+        meeting_id: &str,
+    ) -> impl Future<Output = bool> + Send + 'static {
+        let slots = Arc::clone(self);
+        let meeting_id = meeting_id.to_string();
+        let waiting = self.semaphore.clone().map(|semaphore| {
+            let (cancel_tx, cancel_rx) = oneshot::channel();
+            self.queued
+                .lock()
+                .unwrap()
+                .push((meeting_id.clone(), cancel_tx));
+            (semaphore, cancel_rx)
+        });
+
+        async move {
+            let Some((semaphore, cancel_rx)) = waiting else {
+                return slots.try_admit(&meeting_id);
+            };
+
+            // The semaphore hands out slots in the order waiters arrive
+            let permit = tokio::select! {
+                permit = semaphore.acquire_owned() => permit.ok(),
+                _ = cancel_rx => None,
+            };
+
+            slots
+                .queued
+                .lock()
+                .unwrap()
+                .retain(|(id, _)| *id != meeting_id);
+            match permit {
+                Some(permit) => {
+                    slots.held.lock().unwrap().insert(meeting_id, Some(permit));
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    /// Give back `meeting_id`'s slot, admitting the next queued session
+    pub fn release(&self, meeting_id: &str) -> bool {
+        self.held.lock().unwrap().remove(meeting_id).is_some()
+    }
+
+    /// Stop `meeting_id` from waiting for a slot
+    pub fn cancel(&self, meeting_id: &str) -> bool {
+        let mut queued = self.queued.lock().unwrap();
+        let before = queued.len();
+        // Dropping the sender wakes the waiting `admit`
+        queued.retain(|(id, _)| id != meeting_id);
+        queued.len() != before
+    }
+
+    /// Meetings waiting for a slot, oldest first
+    pub fn queued(&self) -> Vec<String> {
+        self.queued
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// 1-based place of `meeting_id` in the queue
+    pub fn queue_position(&self, meeting_id: &str) -> Option<usize> {
+        self.queued
+            .lock()
+            .unwrap()
+            .iter()
+            .position(|(id, _)| id == meeting_id)
+            .map(|index| index + 1)
+    }
+}
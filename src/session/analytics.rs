@@ -0,0 +1,323 @@
+use super::stats::TranscriptSegment;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Speaking time assumed per word when estimating how long a segment took
+/// to say (about 150 words per minute)
+const MS_PER_WORD: u64 = 400;
+
+/// A pause longer than this ends a monologue, even if nobody else spoke
+const MONOLOGUE_PAUSE_MS: u64 = 5000;
+
+/// Someone starting longer than this after another speaker stopped is not
+/// counted as responding to them
+const RESPONSE_WINDOW_MS: u64 = 10_000;
+
+/// How much one speaker talked in a meeting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerTalkTime {
+    /// Speaker label: "Me"/"Them" for dual-stream sessions, or from
+    /// diarization (None = segments with no speaker)
+    pub speaker: Option<String>,
+    /// Estimated seconds spent talking
+    pub talk_secs: f64,
+    /// Share of the meeting's talk time, in percent
+    pub percent: f64,
+    /// Final segments attributed to the speaker
+    pub segments: usize,
+    pub words: usize,
+    /// Seconds of the speaker's longest monologue
+    pub longest_monologue_secs: f64,
+    /// Times the speaker started while someone else was still talking
+    pub interruptions: usize,
+    /// Mean seconds between someone else stopping and the speaker starting
+    /// (None = the speaker never responded to anyone)
+    pub avg_response_secs: Option<f64>,
+}
+
+/// An uninterrupted run of one speaker's segments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Monologue {
+    pub speaker: Option<String>,
+    /// Where the run starts, in milliseconds from the start of the meeting
+    pub offset_ms: u64,
+    pub duration_secs: f64,
+    pub segments: usize,
+}
+
+/// Talk-time statistics for a meeting
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeetingAnalytics {
+    /// Estimated seconds of speech, over all speakers
+    pub talk_secs: f64,
+    /// Speakers by talk time, most first
+    pub speakers: Vec<SpeakerTalkTime>,
+    pub longest_monologue: Option<Monologue>,
+    /// Seconds of the meeting nobody was speaking
+    pub silence_secs: f64,
+    /// Times a speaker started while another was still talking
+    pub overlaps: usize,
+    /// Seconds two or more speakers talked at once
+    pub overlap_secs: f64,
+    /// Turn changes where one speaker started after another stopped
+    pub responses: usize,
+    /// Mean seconds between one speaker stopping and another starting
+    /// (None = no responses)
+    pub avg_response_secs: Option<f64>,
+}
+
+impl MeetingAnalytics {
+    /// Whether any segment was attributed to a speaker
+    pub fn has_speakers(&self) -> bool {
+        self.speakers.iter().any(|s| s.speaker.is_some())
+    }
+}
+
+/// A final segment's estimated span, in milliseconds from the start
+struct Span<'a> {
+    speaker: Option<&'a str>,
+    start_ms: u64,
+    end_ms: u64,
+    words: usize,
+}
+
+/// Talk time, monologues, silence, overlaps, and response times in a final
+/// transcript of a meeting lasting `duration_secs`
+///
+/// Segments only carry the time they were finalized, so each is taken to
+/// end then and to have lasted as long as its words take to say, never
+/// reaching back past the same speaker's previous segment. A monologue is
+/// a run of one speaker's segments that nobody else speaks into and that
+/// has no pause longer than five seconds. Overlaps and responses are only
+/// counted between attributed speakers (e.g. the system and mic streams),
+/// and a start more than ten seconds after the previous speaker stopped is
+/// a new topic rather than a response.
+pub fn meeting_analytics(
+    started_at: DateTime<Utc>,
+    duration_secs: f64,
+    transcript: &[TranscriptSegment],
+) -> MeetingAnalytics {
+    let mut finals: Vec<&TranscriptSegment> = transcript.iter().filter(|s| !s.partial).collect();
+    finals.sort_by_key(|segment| segment.timestamp);
+
+    let mut spans: Vec<Span> = Vec::with_capacity(finals.len());
+    for segment in finals {
+        let speaker = segment.speaker.as_deref();
+        let words = segment.text.split_whitespace().count();
+        let end_ms = segment
+            .timestamp
+            .signed_duration_since(started_at)
+            .num_milliseconds()
+            .max(0) as u64;
+        let earliest = spans
+            .iter()
+            .rev()
+            .find(|span| span.speaker == speaker)
+            .map_or(0, |span| span.end_ms);
+        spans.push(Span {
+            speaker,
+            start_ms: end_ms
+                .saturating_sub(words as u64 * MS_PER_WORD)
+                .max(earliest),
+            end_ms,
+            words,
+        });
+    }
+
+    let mut speakers: Vec<SpeakerTalkTime> = Vec::new();
+    for span in &spans {
+        let index = match speakers
+            .iter()
+            .position(|s| s.speaker.as_deref() == span.speaker)
+        {
+            Some(index) => index,
+            None => {
+                speakers.push(SpeakerTalkTime {
+                    speaker: span.speaker.map(str::to_string),
+                    talk_secs: 0.0,
+                    percent: 0.0,
+                    segments: 0,
+                    words: 0,
+                    longest_monologue_secs: 0.0,
+                    interruptions: 0,
+                    avg_response_secs: None,
+                });
+                speakers.len() - 1
+            }
+        };
+        let entry = &mut speakers[index];
+        entry.talk_secs += (span.end_ms - span.start_ms) as f64 / 1000.0;
+        entry.segments += 1;
+        entry.words += span.words;
+    }
+
+    let mut longest_monologue: Option<Monologue> = None;
+    for run in monologues(&spans) {
+        let speaker = speakers
+            .iter_mut()
+            .find(|s| s.speaker == run.speaker)
+            .expect("Every span's speaker is counted");
+        speaker.longest_monologue_secs = speaker.longest_monologue_secs.max(run.duration_secs);
+        if longest_monologue
+            .as_ref()
+            .is_none_or(|longest| run.duration_secs > longest.duration_secs)
+        {
+            longest_monologue = Some(run);
+        }
+    }
+
+    let turns = turns(&spans);
+    for speaker in &mut speakers {
+        let own = || {
+            turns
+                .iter()
+                .filter(|turn| speaker.speaker.as_deref() == Some(turn.speaker))
+        };
+        speaker.interruptions = own().filter(|turn| turn.latency_ms.is_none()).count();
+        speaker.avg_response_secs = mean_secs(own().filter_map(|turn| turn.latency_ms));
+    }
+    let latencies = turns.iter().filter_map(|turn| turn.latency_ms);
+    let responses = latencies.clone().count();
+    let avg_response_secs = mean_secs(latencies);
+    let overlaps = turns.len() - responses;
+
+    let (silence_secs, overlap_secs) = coverage(&spans, duration_secs);
+
+    let talk_secs: f64 = speakers.iter().map(|s| s.talk_secs).sum();
+    for speaker in &mut speakers {
+        if talk_secs > 0.0 {
+            speaker.percent = speaker.talk_secs / talk_secs * 100.0;
+        }
+    }
+    speakers.sort_by(|a, b| b.talk_secs.total_cmp(&a.talk_secs));
+
+    MeetingAnalytics {
+        talk_secs,
+        speakers,
+        longest_monologue,
+        silence_secs,
+        overlaps,
+        overlap_secs,
+        responses,
+        avg_response_secs,
+    }
+}
+
+/// A change of attributed speaker
+struct Turn<'a> {
+    speaker: &'a str,
+    /// How long after the previous speaker stopped this one started (None
+    /// = they started while the previous speaker was still talking)
+    latency_ms: Option<u64>,
+}
+
+/// Changes of attributed speaker, in the order they start talking
+///
+/// Starts long after the previous speaker stopped are left out.
+fn turns<'a>(spans: &[Span<'a>]) -> Vec<Turn<'a>> {
+    let mut attributed: Vec<(&'a str, u64, u64)> = spans
+        .iter()
+        .filter_map(|span| Some((span.speaker?, span.start_ms, span.end_ms)))
+        .collect();
+    attributed.sort_by_key(|&(_, start_ms, _)| start_ms);
+
+    let mut turns = Vec::new();
+    // Whoever finishes last among the spans started so far
+    let mut last: Option<(&str, u64)> = None;
+    for (speaker, start_ms, end_ms) in attributed {
+        if let Some((previous, previous_end)) = last {
+            if previous != speaker {
+                if start_ms < previous_end {
+                    turns.push(Turn {
+                        speaker,
+                        latency_ms: None,
+                    });
+                } else if start_ms - previous_end <= RESPONSE_WINDOW_MS {
+                    turns.push(Turn {
+                        speaker,
+                        latency_ms: Some(start_ms - previous_end),
+                    });
+                }
+            }
+        }
+        if last.is_none_or(|(_, previous_end)| end_ms >= previous_end) {
+            last = Some((speaker, end_ms));
+        }
+    }
+    turns
+}
+
+/// Seconds nobody spoke and seconds two or more attributed speakers spoke,
+/// in a meeting lasting `duration_secs` (at least until the last span)
+fn coverage(spans: &[Span<'_>], duration_secs: f64) -> (f64, f64) {
+    // (time, change in speaking spans, whether the span is attributed)
+    let mut edges: Vec<(u64, i32, bool)> = spans
+        .iter()
+        .flat_map(|span| {
+            let attributed = span.speaker.is_some();
+            [
+                (span.start_ms, 1, attributed),
+                (span.end_ms, -1, attributed),
+            ]
+        })
+        .collect();
+    edges.sort_by_key(|&(at, change, _)| (at, change));
+
+    let (mut silent_ms, mut overlap_ms) = (0, 0);
+    let (mut active, mut attributed_active, mut previous) = (0, 0, 0);
+    for (at, change, attributed) in edges {
+        let elapsed = at - previous;
+        if active == 0 {
+            silent_ms += elapsed;
+        }
+        if attributed_active > 1 {
+            overlap_ms += elapsed;
+        }
+        active += change;
+        if attributed {
+            attributed_active += change;
+        }
+        previous = at;
+    }
+
+    let end_ms = ((duration_secs.max(0.0) * 1000.0) as u64).max(previous);
+    silent_ms += end_ms - previous;
+    (silent_ms as f64 / 1000.0, overlap_ms as f64 / 1000.0)
+}
+
+fn mean_secs(latencies_ms: impl Iterator<Item = u64>) -> Option<f64> {
+    let (sum, count) = latencies_ms.fold((0, 0), |(sum, count), ms| (sum + ms, count + 1));
+    (count > 0).then(|| sum as f64 / count as f64 / 1000.0)
+}
+
+/// Runs of consecutive spans from one speaker, split at long pauses
+fn monologues(spans: &[Span<'_>]) -> Vec<Monologue> {
+    let mut runs: Vec<Monologue> = Vec::new();
+    let mut current: Option<(&Span, u64, usize)> = None;
+
+    for span in spans {
+        current = match current {
+            Some((first, end_ms, count))
+                if first.speaker == span.speaker
+                    && span.start_ms.saturating_sub(end_ms) <= MONOLOGUE_PAUSE_MS =>
+            {
+                Some((first, end_ms.max(span.end_ms), count + 1))
+            }
+            previous => {
+                runs.extend(previous.map(|(first, end_ms, count)| run(first, end_ms, count)));
+                Some((span, span.end_ms, 1))
+            }
+        };
+    }
+    runs.extend(current.map(|(first, end_ms, count)| run(first, end_ms, count)));
+    runs
+}
+
+fn run(first: &Span<'_>, end_ms: u64, segments: usize) -> Monologue {
+    Monologue {
+        speaker: first.speaker.map(str::to_string),
+        offset_ms: first.start_ms,
+        duration_secs: (end_ms - first.start_ms) as f64 / 1000.0,
+        segments,
+    }
+}
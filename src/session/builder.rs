@@ -0,0 +1,249 @@
+use super::config::{MicrophoneConfig, SessionConfig};
+use super::session::RecordingSession;
+use crate::audio::{ChunkRotation, StageConfig};
+use crate::clock::SharedClock;
+use crate::storage::validate_new_meeting_id;
+use crate::stt::{SttBudget, SttConfig, SttProviderKind};
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What a session captures and how it is mixed
+#[derive(Debug, Clone)]
+pub struct SessionSources {
+    /// Bundle IDs of applications excluded from system audio capture
+    pub excluded_apps: Vec<String>,
+    /// Pinned microphone device ID (None = follow the system default input)
+    pub microphone_device: Option<String>,
+    /// Additional microphones captured alongside system audio
+    pub microphones: Vec<MicrophoneConfig>,
+    /// Linear gain applied to system audio when mixing
+    pub system_gain: f32,
+    /// Linear gain applied to the system capture's microphone channel
+    pub microphone_gain: f32,
+    /// Transcribe system and microphone audio as separate streams,
+    /// attributing segments to "Them" and "Me"
+    pub dual_stream: bool,
+}
+
+impl Default for SessionSources {
+    fn default() -> Self {
+        let config = SessionConfig::default();
+        Self {
+            excluded_apps: config.excluded_apps,
+            microphone_device: config.microphone_device,
+            microphones: config.microphones,
+            system_gain: config.system_gain,
+            microphone_gain: config.microphone_gain,
+            dual_stream: config.dual_stream,
+        }
+    }
+}
+
+/// Builds a [`RecordingSession`] without assembling a [`SessionConfig`] by
+/// hand
+///
+/// Settings not given keep their [`SessionConfig`] defaults, and
+/// [`build`](Self::build) checks the combination before anything connects.
+#[derive(Debug, Clone)]
+pub struct RecordingSessionBuilder {
+    config: SessionConfig,
+    budget: Option<Arc<SttBudget>>,
+}
+
+impl RecordingSessionBuilder {
+    /// Start from the defaults, with the meeting ID `session_id`
+    pub fn new(session_id: impl Into<String>) -> Self {
+        Self::from_config(SessionConfig {
+            session_id: session_id.into(),
+            ..SessionConfig::default()
+        })
+    }
+
+    /// Start from an existing configuration
+    pub fn from_config(config: SessionConfig) -> Self {
+        Self {
+            config,
+            budget: None,
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.config.title = Some(title.into());
+        self
+    }
+
+    /// Profile the session is reported under
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.config.profile = Some(profile.into());
+        self
+    }
+
+    /// What to capture and how to mix it
+    pub fn sources(mut self, sources: SessionSources) -> Self {
+        self.config.excluded_apps = sources.excluded_apps;
+        self.config.microphone_device = sources.microphone_device;
+        self.config.microphones = sources.microphones;
+        self.config.system_gain = sources.system_gain;
+        self.config.microphone_gain = sources.microphone_gain;
+        self.config.dual_stream = sources.dual_stream;
+        self
+    }
+
+    /// Store audio in `audio_dir`, rotating to a new chunk file every
+    /// `chunk_duration`
+    pub fn chunking(mut self, audio_dir: impl Into<PathBuf>, chunk_duration: Duration) -> Self {
+        self.config.audio_dir = Some(audio_dir.into());
+        self.config.chunk_duration = chunk_duration;
+        self
+    }
+
+    /// Where chunks are split once they reach the chunk duration
+    pub fn chunk_rotation(mut self, rotation: ChunkRotation) -> Self {
+        self.config.chunk_rotation = rotation;
+        self
+    }
+
+    /// Audio repeated from the end of each chunk at the start of the next
+    pub fn chunk_overlap(mut self, overlap: Duration) -> Self {
+        self.config.chunk_overlap = overlap;
+        self
+    }
+
+    /// Transcribe through the loqa-core STT service at `url`
+    pub fn nats(mut self, url: impl Into<String>) -> Self {
+        self.config.nats_url = url.into();
+        self.config.stt.provider = SttProviderKind::Nats;
+        self
+    }
+
+    /// Transcribe with a provider other than the NATS STT service
+    pub fn stt(mut self, stt: SttConfig) -> Self {
+        self.config.stt = stt;
+        self
+    }
+
+    /// Count cloud transcription against `budget`; once it runs out the
+    /// session continues record-only
+    pub fn stt_budget(mut self, budget: Arc<SttBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Process captured frames with `stages`, in order, instead of the
+    /// plain downmix and resample
+    pub fn stages(mut self, stages: Vec<StageConfig>) -> Self {
+        self.config.stages = stages;
+        self
+    }
+
+    /// Take timestamps from `clock` instead of the system clock
+    pub fn clock(mut self, clock: SharedClock) -> Self {
+        self.config.clock = clock;
+        self
+    }
+
+    /// Stream audio to STT without writing it anywhere (transcribe-only)
+    pub fn no_audio_persistence(mut self) -> Self {
+        self.config.store_audio = false;
+        self
+    }
+
+    /// Record audio without transcribing it (record-only)
+    pub fn no_transcription(mut self) -> Self {
+        self.config.transcription = false;
+        self
+    }
+
+    /// Adjust any other setting
+    pub fn configure(mut self, f: impl FnOnce(&mut SessionConfig)) -> Self {
+        f(&mut self.config);
+        self
+    }
+
+    /// Check the settings and return the configuration they make
+    pub fn build_config(self) -> Result<SessionConfig> {
+        self.validate()?;
+        Ok(self.config)
+    }
+
+    /// Check the settings and create the session (connecting to NATS if it
+    /// transcribes through the STT service)
+    pub async fn build(self) -> Result<RecordingSession> {
+        self.validate()?;
+        RecordingSession::with_stt_budget(self.config, self.budget).await
+    }
+
+    fn validate(&self) -> Result<()> {
+        let config = &self.config;
+        validate_new_meeting_id(&config.session_id)?;
+
+        if config.chunk_duration < Duration::from_secs(1) {
+            anyhow::bail!("Chunk duration must be at least a second");
+        }
+        if config.chunk_overlap >= config.chunk_duration {
+            anyhow::bail!("Chunk overlap must be shorter than the chunk duration");
+        }
+        if config.sample_rate == 0 || !(1..=2).contains(&config.channels) {
+            anyhow::bail!(
+                "Unsupported audio format: {}Hz, {} channels",
+                config.sample_rate,
+                config.channels
+            );
+        }
+
+        if config.file_source.is_some() && config.network_source.is_some() {
+            anyhow::bail!("A session replays a file or receives network audio, not both");
+        }
+
+        let gains = [
+            ("System", config.system_gain),
+            ("Microphone", config.microphone_gain),
+        ]
+        .into_iter()
+        .chain(
+            config
+                .microphones
+                .iter()
+                .map(|mic| (mic.device_id.as_str(), mic.gain)),
+        );
+        for (source, gain) in gains {
+            if !gain.is_finite() || gain < 0.0 {
+                anyhow::bail!("{} gain must be zero or more, got {}", source, gain);
+            }
+        }
+        for stage in &config.stages {
+            if let StageConfig::Gain { gains } = stage {
+                for (source, &gain) in gains {
+                    if !gain.is_finite() || gain < 0.0 {
+                        anyhow::bail!(
+                            "Gain stage {} gain must be zero or more, got {}",
+                            source,
+                            gain
+                        );
+                    }
+                }
+            }
+        }
+
+        let stores_audio = config.store_audio && !config.privacy;
+        if !config.transcription && !stores_audio {
+            anyhow::bail!("A session that neither transcribes nor stores audio records nothing");
+        }
+        if config.transcription
+            && config.stt.provider == SttProviderKind::Nats
+            && config.nats_url.trim().is_empty()
+        {
+            anyhow::bail!("Transcribing through the STT service needs a NATS URL");
+        }
+        Ok(())
+    }
+}
+
+impl RecordingSession {
+    /// Build a session with meeting ID `session_id`
+    pub fn builder(session_id: impl Into<String>) -> RecordingSessionBuilder {
+        RecordingSessionBuilder::new(session_id)
+    }
+}
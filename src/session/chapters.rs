@@ -0,0 +1,447 @@
+use super::markers::{Marker, MarkerKind};
+use super::stats::TranscriptSegment;
+use crate::config::ChapterConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How long to wait for an external chapter model
+const DETECTOR_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Keywords used to title a detected chapter
+const TITLE_WORDS: usize = 3;
+
+/// Common words ignored when comparing vocabulary
+const STOPWORDS: &[&str] = &[
+    "about",
+    "actually",
+    "after",
+    "again",
+    "also",
+    "and",
+    "any",
+    "are",
+    "back",
+    "because",
+    "been",
+    "before",
+    "being",
+    "but",
+    "can",
+    "could",
+    "did",
+    "does",
+    "doing",
+    "don",
+    "down",
+    "for",
+    "from",
+    "get",
+    "going",
+    "gonna",
+    "got",
+    "had",
+    "has",
+    "have",
+    "her",
+    "here",
+    "him",
+    "his",
+    "how",
+    "into",
+    "its",
+    "just",
+    "know",
+    "let",
+    "like",
+    "maybe",
+    "mean",
+    "more",
+    "much",
+    "need",
+    "not",
+    "now",
+    "okay",
+    "one",
+    "only",
+    "other",
+    "our",
+    "out",
+    "over",
+    "really",
+    "right",
+    "said",
+    "say",
+    "see",
+    "she",
+    "should",
+    "some",
+    "something",
+    "still",
+    "sure",
+    "than",
+    "that",
+    "the",
+    "their",
+    "them",
+    "then",
+    "there",
+    "these",
+    "they",
+    "thing",
+    "things",
+    "think",
+    "this",
+    "those",
+    "through",
+    "too",
+    "want",
+    "was",
+    "way",
+    "well",
+    "were",
+    "what",
+    "when",
+    "where",
+    "which",
+    "while",
+    "who",
+    "why",
+    "will",
+    "with",
+    "would",
+    "yeah",
+    "yes",
+    "you",
+    "your",
+];
+
+/// A detected chapter start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    /// Offset from the start of the meeting in milliseconds
+    pub offset_ms: u64,
+    pub title: String,
+}
+
+/// Something that can split a transcript into chapters
+#[async_trait]
+pub trait ChapterDetector: Send + Sync {
+    /// Detector name, for logging
+    fn name(&self) -> &str;
+
+    /// Detect chapter starts in a final transcript (ordered by time)
+    async fn detect(
+        &self,
+        started_at: DateTime<Utc>,
+        segments: &[TranscriptSegment],
+    ) -> Result<Vec<Chapter>>;
+}
+
+/// Built-in detector using time gaps and lexical cohesion
+///
+/// A new chapter starts after a long pause, or where the vocabulary of the
+/// segments before and after a point overlaps least (a TextTiling-style
+/// cohesion dip). Chapters shorter than the configured minimum are not
+/// split off, and a transcript that yields a single chapter gets none.
+pub struct LexicalChapterDetector {
+    config: ChapterConfig,
+}
+
+impl LexicalChapterDetector {
+    pub fn new(config: ChapterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Chapter starts, as segment indices
+    pub fn boundaries(
+        &self,
+        started_at: DateTime<Utc>,
+        segments: &[TranscriptSegment],
+    ) -> Vec<usize> {
+        if segments.is_empty() {
+            return Vec::new();
+        }
+
+        let offsets: Vec<u64> = segments.iter().map(|s| offset_ms(started_at, s)).collect();
+        let words: Vec<Vec<String>> = segments.iter().map(|s| keywords(&s.text)).collect();
+        let window = self.config.window_segments.max(1);
+        let min_chapter_ms = self.config.min_chapter_secs * 1000;
+        let gap_ms = self.config.gap_secs * 1000;
+        let end_ms = offsets[offsets.len() - 1];
+
+        // Cohesion across each point, where there is a full window either side
+        let cohesion: Vec<Option<f64>> = (0..segments.len())
+            .map(|i| {
+                (i >= window && i + window <= segments.len()).then(|| {
+                    similarity(
+                        &word_counts(&words[i - window..i]),
+                        &word_counts(&words[i..i + window]),
+                    )
+                })
+            })
+            .collect();
+
+        let mut boundaries = vec![0];
+        for i in 1..segments.len() {
+            let since_last = offsets[i].saturating_sub(offsets[boundaries[boundaries.len() - 1]]);
+            if since_last < min_chapter_ms || end_ms.saturating_sub(offsets[i]) < min_chapter_ms {
+                continue;
+            }
+
+            let paused = offsets[i].saturating_sub(offsets[i - 1]) >= gap_ms;
+            let dip = cohesion[i].is_some_and(|score| {
+                score < self.config.cohesion_threshold
+                    && cohesion[i - 1].is_none_or(|prev| score <= prev)
+                    && cohesion
+                        .get(i + 1)
+                        .copied()
+                        .flatten()
+                        .is_none_or(|next| score <= next)
+            });
+
+            if paused || dip {
+                boundaries.push(i);
+            }
+        }
+
+        if boundaries.len() < 2 {
+            return Vec::new();
+        }
+        boundaries
+    }
+}
+
+#[async_trait]
+impl ChapterDetector for LexicalChapterDetector {
+    fn name(&self) -> &str {
+        "lexical"
+    }
+
+    async fn detect(
+        &self,
+        started_at: DateTime<Utc>,
+        segments: &[TranscriptSegment],
+    ) -> Result<Vec<Chapter>> {
+        let boundaries = self.boundaries(started_at, segments);
+
+        Ok(boundaries
+            .iter()
+            .enumerate()
+            .map(|(n, &start)| {
+                let end = boundaries.get(n + 1).copied().unwrap_or(segments.len());
+                Chapter {
+                    offset_ms: offset_ms(started_at, &segments[start]),
+                    title: chapter_title(&segments[start..end])
+                        .unwrap_or_else(|| format!("Chapter {}", n + 1)),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Detector backed by an external model over HTTP
+///
+/// POSTs `{"segments": [{"offset_ms", "speaker", "text"}]}` to the URL and
+/// expects `{"chapters": [{"offset_ms", "title"}]}` back.
+pub struct RemoteChapterDetector {
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RemoteRequest<'a> {
+    segments: Vec<RemoteSegment<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct RemoteSegment<'a> {
+    offset_ms: u64,
+    speaker: Option<&'a str>,
+    text: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteResponse {
+    chapters: Vec<Chapter>,
+}
+
+impl RemoteChapterDetector {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+#[async_trait]
+impl ChapterDetector for RemoteChapterDetector {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    async fn detect(
+        &self,
+        started_at: DateTime<Utc>,
+        segments: &[TranscriptSegment],
+    ) -> Result<Vec<Chapter>> {
+        let request = RemoteRequest {
+            segments: segments
+                .iter()
+                .map(|segment| RemoteSegment {
+                    offset_ms: offset_ms(started_at, segment),
+                    speaker: segment.speaker.as_deref(),
+                    text: &segment.text,
+                })
+                .collect(),
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(DETECTOR_TIMEOUT)
+            .build()?;
+
+        let response: RemoteResponse = client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to call {}", self.url))?
+            .error_for_status()
+            .with_context(|| format!("Chapter model {} returned an error", self.url))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid response from chapter model {}", self.url))?;
+
+        Ok(response.chapters)
+    }
+}
+
+/// The detector `config` selects: the external model if one is set,
+/// otherwise the built-in heuristics
+pub fn chapter_detector(config: &ChapterConfig) -> Box<dyn ChapterDetector> {
+    match &config.detector_url {
+        Some(url) => Box::new(RemoteChapterDetector::new(url.clone())),
+        None => Box::new(LexicalChapterDetector::new(config.clone())),
+    }
+}
+
+/// Split a final transcript into chapters
+///
+/// Returns nothing when chapters are disabled. If the external model fails
+/// the built-in heuristics are used instead.
+pub async fn detect_chapters(
+    config: &ChapterConfig,
+    started_at: DateTime<Utc>,
+    segments: &[TranscriptSegment],
+) -> Vec<Chapter> {
+    if !config.enabled || segments.is_empty() {
+        return Vec::new();
+    }
+
+    let detector = chapter_detector(config);
+    let chapters = match detector.detect(started_at, segments).await {
+        Ok(chapters) => chapters,
+        Err(e) => {
+            warn!(
+                "Chapter detection with {} failed, using heuristics: {:#}",
+                detector.name(),
+                e
+            );
+            LexicalChapterDetector::new(config.clone())
+                .detect(started_at, segments)
+                .await
+                .unwrap_or_default()
+        }
+    };
+
+    info!(
+        "Detected {} chapters with {}",
+        chapters.len(),
+        detector.name()
+    );
+    chapters
+}
+
+/// Replace any chapter markers with `chapters`, keeping markers ordered by offset
+pub fn apply_chapters(
+    markers: &mut Vec<Marker>,
+    started_at: DateTime<Utc>,
+    chapters: Vec<Chapter>,
+) {
+    markers.retain(|marker| marker.kind != MarkerKind::Chapter);
+    markers.extend(chapters.into_iter().map(|chapter| Marker {
+        kind: MarkerKind::Chapter,
+        offset_ms: chapter.offset_ms,
+        timestamp: started_at + chrono::Duration::milliseconds(chapter.offset_ms as i64),
+        label: chapter.title,
+    }));
+    markers.sort_by_key(|marker| marker.offset_ms);
+}
+
+/// Title made of the chapter's most frequent keywords
+fn chapter_title(segments: &[TranscriptSegment]) -> Option<String> {
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for (position, word) in segments.iter().flat_map(|s| keywords(&s.text)).enumerate() {
+        counts.entry(word).or_insert((0, position)).0 += 1;
+    }
+
+    // Most frequent first; ties go to the word mentioned first
+    let mut ranked: Vec<_> = counts.into_iter().collect();
+    ranked.sort_by(|(_, (a_count, a_first)), (_, (b_count, b_first))| {
+        b_count.cmp(a_count).then(a_first.cmp(b_first))
+    });
+
+    let title = ranked
+        .into_iter()
+        .take(TITLE_WORDS)
+        .map(|(word, _)| word)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut chars = title.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+}
+
+/// Lowercased content words of at least three letters
+fn keywords(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+fn word_counts(words: &[Vec<String>]) -> HashMap<&str, f64> {
+    let mut counts = HashMap::new();
+    for word in words.iter().flatten() {
+        *counts.entry(word.as_str()).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+/// Cosine similarity of two word-count vectors (0 when either is empty)
+fn similarity(a: &HashMap<&str, f64>, b: &HashMap<&str, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(word, count)| b.get(word).map(|other| count * other))
+        .sum();
+    let norm = |counts: &HashMap<&str, f64>| counts.values().map(|c| c * c).sum::<f64>().sqrt();
+
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+fn offset_ms(started_at: DateTime<Utc>, segment: &TranscriptSegment) -> u64 {
+    segment
+        .timestamp
+        .signed_duration_since(started_at)
+        .num_milliseconds()
+        .max(0) as u64
+}
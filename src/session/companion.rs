@@ -0,0 +1,188 @@
+use crate::audio::{
+    AudioFrame, AudioStreamSource, NetworkCodec, NetworkProtocol, NetworkSourceConfig,
+    PacketDecoder,
+};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Companion devices one session accepts at once
+pub const MAX_COMPANIONS: usize = 4;
+
+/// Audio format a companion device (e.g. a phone's browser) streams in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompanionFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Little-endian 16-bit PCM, or one Opus packet per message
+    pub codec: NetworkCodec,
+    /// Linear gain when mixing (1.0 = unchanged)
+    pub gain: f32,
+}
+
+impl Default for CompanionFormat {
+    fn default() -> Self {
+        Self {
+            // What browsers capture at
+            sample_rate: 48000,
+            channels: 1,
+            codec: NetworkCodec::Pcm,
+            gain: 1.0,
+        }
+    }
+}
+
+/// Companion audio for the session's audio task
+pub(super) enum CompanionAudio {
+    /// Audio timed from the companion's first frame
+    Frame(AudioFrame),
+    /// The companion disconnected; stop mixing it
+    Left(AudioStreamSource),
+}
+
+/// Companions connected to a session, with their mixing gains
+pub(super) type Companions = Arc<Mutex<HashMap<AudioStreamSource, f32>>>;
+
+/// Token companion devices pair with, issued once per session so only
+/// devices it was handed to can add a microphone
+#[derive(Debug, Default)]
+pub(super) struct CompanionPairing {
+    token: Mutex<Option<String>>,
+}
+
+impl CompanionPairing {
+    /// The session's pairing token, issued on first use
+    pub(super) fn token(&self) -> String {
+        self.token
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| uuid::Uuid::new_v4().simple().to_string())
+            .clone()
+    }
+
+    /// Whether `token` is the issued pairing token (never before one is
+    /// issued)
+    pub(super) fn accepts(&self, token: &str) -> bool {
+        let issued = self.token.lock().unwrap();
+        let Some(issued) = issued.as_deref() else {
+            return false;
+        };
+        // Compared in full, so timing doesn't reveal a matching prefix
+        issued.len() == token.len()
+            && issued
+                .bytes()
+                .zip(token.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+}
+
+/// A companion device connected to a recording session as an additional
+/// microphone, mixed in until it is dropped
+pub struct CompanionInput {
+    source: AudioStreamSource,
+    decoder: PacketDecoder,
+    sample_rate: u32,
+    channels: u16,
+    /// Frames received so far, which time the next one
+    received_frames: u64,
+    audio_tx: mpsc::UnboundedSender<CompanionAudio>,
+    companions: Companions,
+    is_recording: Arc<AtomicBool>,
+}
+
+impl CompanionInput {
+    /// Register a companion as the first free source after the session's
+    /// configured microphones
+    pub(super) fn join(
+        format: &CompanionFormat,
+        first_index: usize,
+        audio_tx: mpsc::UnboundedSender<CompanionAudio>,
+        companions: Companions,
+        is_recording: Arc<AtomicBool>,
+    ) -> Result<Self> {
+        if format.sample_rate == 0 || !(1..=2).contains(&format.channels) {
+            bail!(
+                "Unsupported companion audio format: {}Hz, {} channels",
+                format.sample_rate,
+                format.channels
+            );
+        }
+        let decoder = PacketDecoder::new(&NetworkSourceConfig {
+            protocol: NetworkProtocol::Udp,
+            codec: format.codec,
+            sample_rate: format.sample_rate,
+            channels: format.channels,
+            ..Default::default()
+        })?;
+
+        let source = {
+            let mut companions = companions.lock().unwrap();
+            let Some(source) = (first_index..first_index + MAX_COMPANIONS)
+                .filter_map(|index| u8::try_from(index).ok())
+                .map(AudioStreamSource::MicrophoneDevice)
+                .find(|source| !companions.contains_key(source))
+            else {
+                bail!("{} companion devices are already connected", MAX_COMPANIONS);
+            };
+            companions.insert(source, format.gain);
+            source
+        };
+
+        Ok(Self {
+            source,
+            decoder,
+            sample_rate: format.sample_rate,
+            channels: format.channels,
+            received_frames: 0,
+            audio_tx,
+            companions,
+            is_recording,
+        })
+    }
+
+    /// Source the companion's audio is mixed as ("mic-<index>")
+    pub fn source(&self) -> AudioStreamSource {
+        self.source
+    }
+
+    /// Pass one message of audio to the session
+    ///
+    /// Fails for audio that can't be decoded, or once the session has
+    /// stopped recording.
+    pub fn send(&mut self, message: &[u8]) -> Result<()> {
+        if !self.is_recording.load(Ordering::SeqCst) {
+            bail!("The session is no longer recording");
+        }
+        let samples = self.decoder.decode(message)?;
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        let frame = AudioFrame {
+            timestamp_ms: self.received_frames * 1000 / self.sample_rate as u64,
+            samples,
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            source: self.source,
+        };
+        self.received_frames += (frame.samples.len() / self.channels as usize) as u64;
+        if self.audio_tx.send(CompanionAudio::Frame(frame)).is_err() {
+            bail!("The session is no longer recording");
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CompanionInput {
+    fn drop(&mut self) {
+        // Queued behind the companion's audio, and sent before its source
+        // can be handed to another companion
+        let _ = self.audio_tx.send(CompanionAudio::Left(self.source));
+        self.companions.lock().unwrap().remove(&self.source);
+    }
+}
@@ -1,4 +1,19 @@
+use super::filter::TranscriptFilterConfig;
+use super::host::HostInfo;
+use super::normalize::NormalizationConfig;
+use super::supervisor::RestartPolicy;
+use super::translation::TranslationConfig;
+use super::utterance::UtteranceConfig;
+use super::watchdog::WatchdogConfig;
+use crate::audio::{
+    ChannelMap, ChunkRotation, DownmixStrategy, FileSourceConfig, FormatMismatch,
+    NetworkSourceConfig, PlaybackConfig, QualityPreset, ResamplerQuality, StageConfig,
+};
+use crate::clock::{system_clock, SharedClock};
+use crate::nats::{AudioCodec, TranscriptStreamConfig};
+use crate::stt::SttConfig;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// Configuration for a recording session
@@ -7,6 +22,12 @@ pub struct SessionConfig {
     /// Unique session identifier (e.g., "meeting-2025-10-28-standup")
     pub session_id: String,
 
+    /// Optional meeting title
+    pub title: Option<String>,
+
+    /// Session profile name (None = "default")
+    pub profile: Option<String>,
+
     /// Duration of each audio chunk before rotating files
     /// Default: 300 seconds (5 minutes)
     pub chunk_duration: Duration,
@@ -19,16 +40,328 @@ pub struct SessionConfig {
 
     /// NATS server URL
     pub nats_url: String,
+
+    /// Bundle IDs of applications excluded from system audio capture
+    pub excluded_apps: Vec<String>,
+
+    /// Pinned microphone device ID (None = follow the system default input)
+    pub microphone_device: Option<String>,
+
+    /// Additional microphones captured alongside system audio and mixed in
+    pub microphones: Vec<MicrophoneConfig>,
+
+    /// Receive the session's audio from another device over the network
+    /// instead of capturing system audio
+    /// Default: None (capture system audio)
+    pub network_source: Option<NetworkSourceConfig>,
+
+    /// Replay an audio file as the session's audio instead of capturing
+    /// (batch processing, tests)
+    /// Default: None (capture system audio)
+    pub file_source: Option<FileSourceConfig>,
+
+    /// Linear gain applied to system audio when mixing (1.0 = unchanged)
+    pub system_gain: f32,
+
+    /// Linear gain applied to the system capture's microphone channel when
+    /// mixing (1.0 = unchanged)
+    pub microphone_gain: f32,
+
+    /// How often to publish the per-application audio activity summary
+    /// Default: 30 seconds (zero disables app tracking)
+    pub app_activity_interval: Duration,
+
+    /// Fraction of dropped audio frames above which a session warning is raised
+    /// Default: 0.05 (5%)
+    pub max_drop_rate: f64,
+
+    /// How often to refresh the meeting's draft note in the vault while recording
+    /// Default: zero (no live draft)
+    pub live_draft_interval: Duration,
+
+    /// Publish system and microphone audio as separate STT streams
+    /// (`{id}.system`, `{id}.mic`) and attribute transcripts to "Them"/"Me"
+    pub dual_stream: bool,
+
+    /// Store chunks in the system capture's channel layout (system left,
+    /// microphone right by default) instead of the mix, each channel
+    /// labelled with its role. Standby audio, being mono, is not stored.
+    /// Default: false
+    pub stereo_chunks: bool,
+
+    /// Latency/quality preset for capture buffers, mixing, and resampling
+    /// Default: balanced
+    pub quality: QualityPreset,
+
+    /// Resampler interpolation (None = the quality preset's choice)
+    pub resampler_quality: Option<ResamplerQuality>,
+
+    /// Keep samples in 32-bit float between processing stages, converting
+    /// to 16-bit only when publishing (None = the quality preset's choice)
+    pub float_pipeline: Option<bool>,
+
+    /// Role of each channel in multichannel system capture, used to split
+    /// system and microphone audio and to meter the system level
+    pub channel_map: ChannelMap,
+
+    /// How multichannel frames are folded to mono before publishing
+    /// Default: sum
+    pub downmix: DownmixStrategy,
+
+    /// Processing every captured frame goes through, in order
+    /// Default: downmix, then resample
+    pub stages: Vec<StageConfig>,
+
+    /// What the mixer does with a source whose sample rate or channel count
+    /// differs from the mix (e.g. a microphone reporting 44.1kHz)
+    /// Default: coerce
+    pub format_mismatch: FormatMismatch,
+
+    /// How failed audio and transcript tasks are restarted
+    pub restart_policy: RestartPolicy,
+
+    /// How long start waits for the first captured frame before failing
+    /// (catches capture that starts but never delivers audio)
+    /// Default: zero (no warm-up)
+    pub warmup: Duration,
+
+    /// How a start that fails during setup (handshake, capture, transcript
+    /// subscription) is retried
+    /// Default: not retried
+    pub start_retry: StartRetryPolicy,
+
+    /// When capture that stops delivering frames is warned about or
+    /// restarted
+    /// Default: warn after 10 seconds without frames
+    pub watchdog: WatchdogConfig,
+
+    /// How long stop waits for each session task before aborting it
+    /// Default: 5 seconds
+    pub stop_timeout: Duration,
+
+    /// Machine reported with the session (None = not reported)
+    pub host: Option<HostInfo>,
+
+    /// Codec for published audio; Opus falls back to PCM unless this build
+    /// and the STT service support it
+    pub audio_codec: AudioCodec,
+
+    /// Most capture frames to combine into each published message; the STT
+    /// service may lower it in the handshake
+    /// Default: 1
+    pub batch_frames: u32,
+
+    /// Transcript-only session: audio is streamed to STT but never stored
+    pub privacy: bool,
+
+    /// Recent audio kept in memory, saved as a highlight clip when the
+    /// session is bookmarked
+    /// Default: 30 seconds (zero keeps none)
+    pub preroll: Duration,
+
+    /// Start with the audio standby buffered before the session, when
+    /// standby is enabled
+    /// Default: true
+    pub retroactive: bool,
+
+    /// Write captured audio to chunk files; false streams it to STT only
+    /// (transcribe-only session). Unlike privacy mode, nothing is checked
+    /// afterwards to confirm no audio was written.
+    /// Default: true
+    pub store_audio: bool,
+
+    /// Stream audio to STT and collect transcripts; false records audio
+    /// without ever connecting to NATS (record-only session)
+    /// Default: true
+    pub transcription: bool,
+
+    /// Directory the meeting's audio files are stored in, checked when
+    /// reporting audio retention (None = not checked)
+    pub audio_dir: Option<PathBuf>,
+
+    /// How long the stored meeting is kept after recording stops before the
+    /// retention worker deletes it (None = kept until deleted)
+    pub ttl: Option<Duration>,
+
+    /// X25519 public key (base64) the stored meeting is encrypted to
+    /// (None = stored in plaintext)
+    pub encryption_key: Option<String>,
+
+    /// When published audio is marked as the end of an utterance
+    /// Default: only at the end of the session
+    pub utterance: UtteranceConfig,
+
+    /// Where audio chunks are split
+    /// Default: exactly at the chunk duration
+    pub chunk_rotation: ChunkRotation,
+
+    /// Audio at the end of each chunk repeated at the start of the next
+    /// Default: none
+    pub chunk_overlap: Duration,
+
+    /// How transcripts are received; a durable consumer delivers those
+    /// published while the service was down once the session restarts
+    /// Default: plain subscription
+    pub transcript_stream: TranscriptStreamConfig,
+
+    /// STT provider that transcribes the session, and cloud provider settings
+    /// Default: the loqa-core STT service over NATS
+    pub stt: SttConfig,
+
+    /// Spoken numbers, dates, and currencies written as digits in final
+    /// segments
+    /// Default: off
+    pub normalization: NormalizationConfig,
+
+    /// Where final segments are sent to be translated
+    /// Default: not translated
+    pub translation: TranslationConfig,
+
+    /// Dropping of likely hallucinated final segments before they are stored
+    /// Default: off
+    pub transcript_filter: TranscriptFilterConfig,
+
+    /// Playing the processed microphone audio back out through a named
+    /// pipe, e.g. into a conferencing app
+    /// Default: off
+    pub playback: PlaybackConfig,
+
+    /// Time source for the session's timestamps (tests inject a manual one)
+    /// Default: the system clock
+    #[serde(skip, default = "system_clock")]
+    pub clock: SharedClock,
 }
 
 impl Default for SessionConfig {
     fn default() -> Self {
         Self {
             session_id: format!("meeting-{}", uuid::Uuid::new_v4()),
+            title: None,
+            profile: None,
             chunk_duration: Duration::from_secs(300), // 5 minutes
             sample_rate: 16000,                       // Whisper expects 16kHz
             channels: 1,                              // Mono
             nats_url: "nats://localhost:4222".to_string(),
+            excluded_apps: Vec::new(),
+            microphone_device: None,
+            microphones: Vec::new(),
+            network_source: None,
+            file_source: None,
+            system_gain: 1.0,
+            microphone_gain: 1.0,
+            app_activity_interval: Duration::from_secs(30),
+            max_drop_rate: 0.05,
+            live_draft_interval: Duration::ZERO,
+            dual_stream: false,
+            stereo_chunks: false,
+            quality: QualityPreset::default(),
+            resampler_quality: None,
+            float_pipeline: None,
+            channel_map: ChannelMap::default(),
+            downmix: DownmixStrategy::default(),
+            stages: StageConfig::conversion(),
+            format_mismatch: FormatMismatch::default(),
+            restart_policy: RestartPolicy::default(),
+            warmup: Duration::ZERO,
+            start_retry: StartRetryPolicy::default(),
+            watchdog: WatchdogConfig::default(),
+            stop_timeout: Duration::from_secs(5),
+            host: None,
+            audio_codec: AudioCodec::default(),
+            batch_frames: 1,
+            privacy: false,
+            preroll: Duration::from_secs(30),
+            retroactive: true,
+            store_audio: true,
+            transcription: true,
+            audio_dir: None,
+            ttl: None,
+            encryption_key: None,
+            utterance: UtteranceConfig::default(),
+            chunk_rotation: ChunkRotation::default(),
+            chunk_overlap: Duration::ZERO,
+            transcript_stream: TranscriptStreamConfig::default(),
+            stt: SttConfig::default(),
+            normalization: NormalizationConfig::default(),
+            translation: TranslationConfig::default(),
+            transcript_filter: TranscriptFilterConfig::default(),
+            playback: PlaybackConfig::default(),
+            clock: system_clock(),
         }
     }
 }
+
+impl SessionConfig {
+    /// Whether audio is processed in float, per the override or the preset
+    pub fn float_pipeline(&self) -> bool {
+        self.float_pipeline.unwrap_or(self.quality.float_pipeline())
+    }
+
+    /// This configuration with credentials removed, safe to show to clients
+    pub fn redacted(&self) -> Self {
+        Self {
+            nats_url: redact_url(&self.nats_url),
+            ..self.clone()
+        }
+    }
+}
+
+/// How a failed session start is retried
+///
+/// Each failed attempt is rolled back completely before the next, so a
+/// retry starts from the same clean state as the first attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StartRetryPolicy {
+    /// Attempts after the first before start gives up (0 = no retries)
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub backoff: Duration,
+}
+
+impl Default for StartRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl StartRetryPolicy {
+    /// Longest delay between attempts, however many retries are allowed
+    pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    /// Delay before retry number `retry` (0 = the first retry)
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(Self::MAX_BACKOFF)
+    }
+}
+
+/// Replace the user info (user, password, or token) in a URL with `***`
+pub fn redact_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) => format!("{}://***{}", scheme, &rest[at..]),
+        None => url.to_string(),
+    }
+}
+
+/// An additional microphone device to capture and mix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MicrophoneConfig {
+    /// Device ID (e.g., a USB audio interface)
+    pub device_id: String,
+
+    /// Linear gain applied when mixing (1.0 = unchanged)
+    #[serde(default = "default_gain")]
+    pub gain: f32,
+}
+
+fn default_gain() -> f32 {
+    1.0
+}
@@ -0,0 +1,63 @@
+use crate::nats::TranscriptMessage;
+use std::collections::HashSet;
+
+/// Identity of a final segment, stable across STT resends
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SegmentKey {
+    /// Segment ID assigned by the STT service
+    Id { stream: String, id: String },
+    /// Services without IDs resend the same audio time and text
+    Offset {
+        stream: String,
+        timestamp: String,
+        text: String,
+    },
+}
+
+/// Final segments already accepted from the STT service
+///
+/// STT services may resend segments after a reconnect; the ledger lets each
+/// one through once per stream (session or dual-stream sub-session). Partial
+/// results are never recorded, since they are replaced anyway.
+#[derive(Debug, Clone, Default)]
+pub struct SegmentLedger {
+    seen: HashSet<SegmentKey>,
+    duplicates: usize,
+}
+
+impl SegmentLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a transcript message should be stored (false for a repeat)
+    pub fn admit(&mut self, transcript: &TranscriptMessage) -> bool {
+        if transcript.partial {
+            return true;
+        }
+
+        let stream = transcript.session_id.clone();
+        let key = match &transcript.segment_id {
+            Some(id) => SegmentKey::Id {
+                stream,
+                id: id.clone(),
+            },
+            None => SegmentKey::Offset {
+                stream,
+                timestamp: transcript.timestamp.clone(),
+                text: transcript.text.trim().to_string(),
+            },
+        };
+
+        let new = self.seen.insert(key);
+        if !new {
+            self.duplicates += 1;
+        }
+        new
+    }
+
+    /// Repeated segments dropped so far
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+}
@@ -0,0 +1,159 @@
+use super::stats::TranscriptSegment;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+
+/// How a run of words differs between two transcripts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    /// Same words in both
+    Equal,
+    /// Words only in the first transcript
+    Delete,
+    /// Words only in the second transcript
+    Insert,
+    /// Different words in the same place
+    Replace,
+}
+
+/// An aligned run of words
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffChunk {
+    pub kind: DiffKind,
+    /// Words from the first transcript (empty for inserts)
+    pub a: String,
+    /// Words from the second transcript (empty for deletes)
+    pub b: String,
+    /// Where the run starts, in milliseconds from the start of the meeting
+    pub offset_ms: u64,
+}
+
+/// Word-level comparison of two transcripts, treating the first as reference
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub words_a: usize,
+    pub words_b: usize,
+    /// Words the transcripts agree on
+    pub matched: usize,
+    pub substituted: usize,
+    /// Words only in the second transcript
+    pub inserted: usize,
+    /// Words only in the first transcript
+    pub deleted: usize,
+    /// (substituted + inserted + deleted) / words_a
+    pub word_error_rate: f64,
+}
+
+/// Aligned diff of two transcripts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptDiff {
+    pub chunks: Vec<DiffChunk>,
+    pub stats: DiffStats,
+}
+
+/// A transcript word with its position in the meeting
+struct Word<'a> {
+    text: &'a str,
+    /// Lowercased with surrounding punctuation removed, so "Hello," and
+    /// "hello" compare equal
+    normalized: String,
+    offset_ms: u64,
+}
+
+fn words(started_at: DateTime<Utc>, transcript: &[TranscriptSegment]) -> Vec<Word<'_>> {
+    transcript
+        .iter()
+        .filter(|segment| !segment.partial)
+        .flat_map(|segment| {
+            let offset_ms = segment
+                .timestamp
+                .signed_duration_since(started_at)
+                .num_milliseconds()
+                .max(0) as u64;
+            segment.text.split_whitespace().map(move |text| Word {
+                text,
+                normalized: text
+                    .trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase(),
+                offset_ms,
+            })
+        })
+        .filter(|word| !word.normalized.is_empty())
+        .collect()
+}
+
+fn join(words: &[Word<'_>]) -> String {
+    words.iter().map(|w| w.text).collect::<Vec<_>>().join(" ")
+}
+
+/// Align two final transcripts word by word
+///
+/// Words are compared ignoring case and punctuation, and runs keep the
+/// original text. Offsets are relative to each meeting's own start, so two
+/// transcriptions of the same recording line up even if they were made at
+/// different times.
+pub fn diff_transcripts(
+    a_started_at: DateTime<Utc>,
+    a: &[TranscriptSegment],
+    b_started_at: DateTime<Utc>,
+    b: &[TranscriptSegment],
+) -> TranscriptDiff {
+    let a = words(a_started_at, a);
+    let b = words(b_started_at, b);
+    let a_keys: Vec<&str> = a.iter().map(|w| w.normalized.as_str()).collect();
+    let b_keys: Vec<&str> = b.iter().map(|w| w.normalized.as_str()).collect();
+
+    let mut stats = DiffStats {
+        words_a: a.len(),
+        words_b: b.len(),
+        ..Default::default()
+    };
+    let mut chunks = Vec::new();
+    for op in capture_diff_slices(Algorithm::Myers, &a_keys, &b_keys) {
+        let (tag, a_range, b_range) = op.as_tag_tuple();
+        let (a_words, b_words) = (&a[a_range], &b[b_range]);
+        let kind = match tag {
+            DiffTag::Equal => {
+                stats.matched += a_words.len();
+                DiffKind::Equal
+            }
+            DiffTag::Delete => {
+                stats.deleted += a_words.len();
+                DiffKind::Delete
+            }
+            DiffTag::Insert => {
+                stats.inserted += b_words.len();
+                DiffKind::Insert
+            }
+            DiffTag::Replace => {
+                let substituted = a_words.len().min(b_words.len());
+                stats.substituted += substituted;
+                stats.deleted += a_words.len() - substituted;
+                stats.inserted += b_words.len() - substituted;
+                DiffKind::Replace
+            }
+        };
+
+        let offset_ms = a_words
+            .first()
+            .or(b_words.first())
+            .map(|w| w.offset_ms)
+            .unwrap_or(0);
+        chunks.push(DiffChunk {
+            kind,
+            a: join(a_words),
+            b: join(b_words),
+            offset_ms,
+        });
+    }
+
+    let errors = stats.substituted + stats.inserted + stats.deleted;
+    stats.word_error_rate = match stats.words_a {
+        0 if errors == 0 => 0.0,
+        0 => 1.0,
+        words => errors as f64 / words as f64,
+    };
+
+    TranscriptDiff { chunks, stats }
+}
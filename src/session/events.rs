@@ -0,0 +1,71 @@
+use super::stats::SessionWarning;
+use super::supervisor::SessionTask;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Live session event pushed to subscribers (e.g., WebSocket clients)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// The microphone input device changed mid-session
+    DeviceChanged {
+        /// New device name (or bridge error message if rebinding failed)
+        device: String,
+        /// Whether capture was successfully rebound to the device
+        rebound: bool,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A source was muted or unmuted
+    SourceMuted {
+        /// Source name ("system", "mic", "mic-<index>")
+        source: String,
+        muted: bool,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A quality warning was raised
+    Warning { warning: SessionWarning },
+
+    /// A capture source delivered frames again after the watchdog reported
+    /// it stalled
+    CaptureResumed {
+        /// Source name ("system", "mic-<index>")
+        source: String,
+        /// How long the source had delivered nothing
+        stalled_secs: f64,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// A session task failed
+    TaskFailed {
+        task: SessionTask,
+        error: String,
+        /// Restarts of this task so far
+        restarts: u32,
+        /// Whether the task is being restarted; otherwise the session has failed
+        restarting: bool,
+    },
+
+    /// A queued session got a slot and started recording
+    Started,
+
+    /// A queued session got a slot but failed to start
+    StartFailed { error: String },
+
+    /// A bundle export wrote another file
+    BundleProgress {
+        /// Archive path of the file just written
+        file: String,
+        files_done: usize,
+        files_total: usize,
+    },
+}
+
+/// A session event tagged with its meeting, for service-wide channels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingEvent {
+    pub meeting_id: String,
+    #[serde(flatten)]
+    pub event: SessionEvent,
+}
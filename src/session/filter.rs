@@ -0,0 +1,135 @@
+use super::stats::TranscriptSegment;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Phrases Whisper is known to produce from silence or music
+const HALLUCINATED_PHRASES: &[&str] = &[
+    "Thanks for watching!",
+    "Thank you for watching.",
+    "Thank you so much for watching.",
+    "Please subscribe to my channel.",
+    "Subtitles by the Amara.org community",
+];
+
+/// Dropping of final segments that are likely hallucinated, before they
+/// are stored
+///
+/// Each filter is skipped at its zero value; with none set, enabling the
+/// filter only drops the blocklisted phrases.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptFilterConfig {
+    pub enabled: bool,
+    /// Segments less confident than this (0.0 to 1.0) are dropped; those
+    /// without a confidence are kept
+    pub min_confidence: f32,
+    /// Segments with fewer words are dropped
+    pub min_words: usize,
+    /// Segments that are only one of these phrases are dropped (case and
+    /// punctuation ignored)
+    pub blocklist: Vec<String>,
+    /// Segments that follow less speech than this since the previous final
+    /// segment are dropped; speech is audio above the utterance silence
+    /// threshold. Not checked for uploads.
+    pub min_speech_ms: u64,
+}
+
+impl Default for TranscriptFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_confidence: 0.0,
+            min_words: 0,
+            blocklist: HALLUCINATED_PHRASES
+                .iter()
+                .map(|phrase| phrase.to_string())
+                .collect(),
+            min_speech_ms: 0,
+        }
+    }
+}
+
+/// Decides which final segments to keep
+#[derive(Debug, Clone)]
+pub struct TranscriptFilter {
+    min_confidence: f32,
+    min_words: usize,
+    /// Blocklisted phrases, folded as segments are compared
+    blocklist: Vec<String>,
+    min_speech: Duration,
+}
+
+impl TranscriptFilter {
+    /// The filter `config` selects (None when filtering is off)
+    pub fn from_config(config: &TranscriptFilterConfig) -> Option<Self> {
+        config.enabled.then(|| Self {
+            min_confidence: config.min_confidence,
+            min_words: config.min_words,
+            blocklist: config
+                .blocklist
+                .iter()
+                .map(|phrase| fold(phrase))
+                .filter(|phrase| !phrase.is_empty())
+                .collect(),
+            min_speech: Duration::from_millis(config.min_speech_ms),
+        })
+    }
+
+    /// Whether the speech heard before segments is checked
+    pub fn checks_speech(&self) -> bool {
+        !self.min_speech.is_zero()
+    }
+
+    /// Why `segment` should be dropped (None = keep it)
+    ///
+    /// `speech` is how much speech was heard since the previous final
+    /// segment, if known. Partial segments are always kept; they are
+    /// superseded by the final one.
+    pub fn rejects(
+        &self,
+        segment: &TranscriptSegment,
+        speech: Option<Duration>,
+    ) -> Option<&'static str> {
+        if segment.partial {
+            return None;
+        }
+        if segment
+            .confidence
+            .is_some_and(|confidence| confidence < self.min_confidence)
+        {
+            return Some("low confidence");
+        }
+        if segment.text.split_whitespace().count() < self.min_words {
+            return Some("too few words");
+        }
+        if !self.blocklist.is_empty() && self.blocklist.contains(&fold(&segment.text)) {
+            return Some("blocklisted phrase");
+        }
+        if speech.is_some_and(|speech| speech < self.min_speech) {
+            return Some("no speech heard");
+        }
+        None
+    }
+
+    /// `segments` without the ones to drop (speech isn't checked)
+    pub fn apply(&self, segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
+        segments
+            .into_iter()
+            .filter(|segment| self.rejects(segment, None).is_none())
+            .collect()
+    }
+}
+
+/// Lowercase words without punctuation, single-spaced
+fn fold(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// The machine a session was recorded on
+///
+/// Announced when a session starts and kept with the stored meeting, so
+/// multi-machine deployments can tell which device produced which meeting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostInfo {
+    /// Host name (configured, or as reported by the OS)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+
+    /// loqa-meetings version
+    pub app_version: String,
+
+    /// Operating system and architecture, e.g. "macos-aarch64"
+    pub platform: String,
+
+    /// Audio backend capturing system audio, once capture has started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_backend: Option<String>,
+}
+
+impl HostInfo {
+    /// Describe this machine, using `hostname` instead of the OS host name if given
+    pub fn current(hostname: Option<String>) -> Self {
+        let hostname = hostname.or_else(|| {
+            gethostname::gethostname()
+                .into_string()
+                .ok()
+                .filter(|name| !name.is_empty())
+        });
+
+        Self {
+            hostname,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+            capture_backend: None,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+
+/// Words a segment needs before its language is guessed locally
+const MIN_DETECT_WORDS: usize = 4;
+
+/// Share of a segment's words that must be stopwords of the guessed language
+const MIN_STOPWORD_SHARE: f64 = 0.25;
+
+/// Common short words of the languages detected locally, by ISO 639-1 code
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "was", "that", "this", "with", "for", "you", "we", "it",
+            "of", "to", "have", "not", "but", "what", "they", "be",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "wir", "mit", "auf", "ein", "eine",
+            "zu", "den", "es", "sie", "auch", "noch", "haben", "wie",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "une", "des", "pas", "nous", "vous", "je", "que",
+            "pour", "dans", "sur", "avec", "ce", "il", "sont", "du",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "es", "una", "que", "por", "para", "con", "pero", "muy",
+            "esta", "está", "del", "lo", "se", "como", "nosotros", "hay",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "gli", "della", "che", "è", "non", "sono", "una", "per", "con", "del", "questo",
+            "anche", "come", "ma", "noi", "ci", "lo", "nel", "abbiamo",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "niet", "ik", "wij", "we", "met", "op", "van", "dat",
+            "die", "zijn", "ook", "maar", "voor", "hebben", "nog",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "as", "e", "é", "não", "uma", "que", "para", "com", "mas", "muito", "isso",
+            "do", "da", "em", "nós", "um", "são", "está",
+        ],
+    ),
+];
+
+/// Language names some STT services report instead of codes (e.g. Whisper's
+/// `verbose_json`), mapped to ISO 639-1
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("german", "de"),
+    ("french", "fr"),
+    ("spanish", "es"),
+    ("italian", "it"),
+    ("dutch", "nl"),
+    ("portuguese", "pt"),
+    ("polish", "pl"),
+    ("swedish", "sv"),
+    ("danish", "da"),
+    ("norwegian", "no"),
+    ("finnish", "fi"),
+    ("russian", "ru"),
+    ("ukrainian", "uk"),
+    ("turkish", "tr"),
+    ("japanese", "ja"),
+    ("chinese", "zh"),
+    ("korean", "ko"),
+    ("arabic", "ar"),
+    ("hindi", "hi"),
+];
+
+/// Normalize a language an STT service reported to a lowercase ISO 639-1
+/// style code: "en-US" becomes "en", "English" becomes "en"
+///
+/// Unknown names are kept lowercased; blank tags are None.
+pub fn normalize_language(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return None;
+    }
+    if let Some((_, code)) = LANGUAGE_NAMES.iter().find(|(name, _)| *name == tag) {
+        return Some(code.to_string());
+    }
+
+    let primary = tag.split(['-', '_']).next().unwrap_or(&tag);
+    Some(primary.to_string())
+}
+
+/// Guess a segment's language from the stopwords it contains
+///
+/// Only segments of a few words or more are guessed, and only when one
+/// language's stopwords clearly make up a share of them, so short or mixed
+/// segments stay untagged rather than mislabelled.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < MIN_DETECT_WORDS {
+        return None;
+    }
+
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let stopwords: HashSet<&str> = stopwords.iter().copied().collect();
+            let hits = words
+                .iter()
+                .filter(|word| stopwords.contains(word.as_str()))
+                .count();
+            (*code, hits)
+        })
+        .collect();
+    scores.sort_by_key(|&(_, hits)| std::cmp::Reverse(hits));
+
+    let (best, hits) = scores[0];
+    let runner_up = scores[1].1;
+    let share = hits as f64 / words.len() as f64;
+    (share >= MIN_STOPWORD_SHARE && hits > runner_up).then_some(best)
+}
+
+/// Language of a segment: the one the STT service reported, or else one
+/// detected from the text
+pub fn segment_language(reported: Option<&str>, text: &str) -> Option<String> {
+    reported
+        .and_then(normalize_language)
+        .or_else(|| detect_language(text).map(str::to_string))
+}
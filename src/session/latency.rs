@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+/// Samples a stage needs before its spikes are reported
+const MIN_SAMPLES_FOR_SPIKES: u64 = 50;
+
+/// A sample is a spike when it is this many times the stage's mean...
+const SPIKE_FACTOR: f64 = 4.0;
+
+/// ...and at least this long, so sub-millisecond jitter never counts
+const SPIKE_FLOOR: Duration = Duration::from_millis(20);
+
+/// Stage of the audio-to-transcript pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Captured frames waiting to be processed, relative to the first frame
+    /// of their source (backend and channel queueing)
+    Capture,
+    /// Downmixing, resampling, and mixing a frame
+    Process,
+    /// Storing, encoding, and publishing a frame to NATS
+    Publish,
+    /// From the STT service's segment timestamp to the session receiving it
+    Transcript,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 4] = [
+        PipelineStage::Capture,
+        PipelineStage::Process,
+        PipelineStage::Publish,
+        PipelineStage::Transcript,
+    ];
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineStage::Capture => write!(f, "capture"),
+            PipelineStage::Process => write!(f, "process"),
+            PipelineStage::Publish => write!(f, "publish"),
+            PipelineStage::Transcript => write!(f, "transcript"),
+        }
+    }
+}
+
+/// Timing summary of one pipeline stage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct StageLatency {
+    /// Samples recorded
+    pub samples: u64,
+    /// Mean time spent in the stage, in milliseconds
+    pub mean_ms: f64,
+    /// Longest time spent in the stage, in milliseconds
+    pub max_ms: f64,
+    /// Most recent sample, in milliseconds
+    pub last_ms: f64,
+    /// Samples reported as spikes
+    pub spikes: u64,
+}
+
+/// Per-stage timings of a session's pipeline, so a latency spike can be
+/// traced to the stage that caused it
+#[derive(Debug, Clone, Default)]
+pub struct PipelineLatency {
+    stages: HashMap<PipelineStage, StageLatency>,
+}
+
+impl PipelineLatency {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record time spent in a stage
+    ///
+    /// Returns true if the sample is a spike: well above the stage's mean
+    /// once enough samples have been seen.
+    pub fn record(&mut self, stage: PipelineStage, elapsed: Duration) -> bool {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let timing = self.stages.entry(stage).or_default();
+
+        let spike = timing.samples >= MIN_SAMPLES_FOR_SPIKES
+            && elapsed >= SPIKE_FLOOR
+            && ms > timing.mean_ms * SPIKE_FACTOR;
+
+        timing.samples += 1;
+        timing.mean_ms += (ms - timing.mean_ms) / timing.samples as f64;
+        timing.max_ms = timing.max_ms.max(ms);
+        timing.last_ms = ms;
+        if spike {
+            timing.spikes += 1;
+        }
+        spike
+    }
+
+    /// Timing summary of a stage (all zero before its first sample)
+    pub fn stage(&self, stage: PipelineStage) -> StageLatency {
+        self.stages.get(&stage).copied().unwrap_or_default()
+    }
+
+    /// Summaries of every stage, keyed by stage name
+    pub fn summary(&self) -> BTreeMap<PipelineStage, StageLatency> {
+        PipelineStage::ALL
+            .iter()
+            .map(|&stage| (stage, self.stage(stage)))
+            .collect()
+    }
+}
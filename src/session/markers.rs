@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Kind of event a marker records in the meeting timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkerKind {
+    /// Audio may be missing (e.g., input device switched)
+    Gap,
+    /// Start of a chapter detected in the final transcript
+    Chapter,
+    /// A source was muted; the label gives the span, e.g. "mic muted 12:03–12:08"
+    Muted,
+    /// Bookmarked by the user; marks the start of the saved highlight clip
+    Bookmark,
+}
+
+/// A point in the meeting timeline worth surfacing alongside the transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Marker {
+    /// What happened
+    pub kind: MarkerKind,
+
+    /// Offset from the start of capture in milliseconds
+    pub offset_ms: u64,
+
+    /// When the marker was recorded
+    pub timestamp: DateTime<Utc>,
+
+    /// Human-readable description
+    pub label: String,
+}
+
+/// A bookmark added while recording, with the audio leading up to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub marker: Marker,
+    /// Audio saved before the bookmark, in milliseconds
+    pub clip_ms: u64,
+    /// Highlight clip with that audio (None when the session keeps no
+    /// audio, e.g. in privacy mode or when stored encrypted)
+    pub clip: Option<PathBuf>,
+}
@@ -0,0 +1,61 @@
+use crate::audio::{AudioFrame, AudioStreamSource};
+use anyhow::{Context, Result};
+use tokio::sync::mpsc;
+
+/// Several capture backends' frames merged into one stream, each tagged
+/// with its source
+///
+/// Backends stamp frames from their own start, so a source restarted
+/// mid-recording is stamped on from where the mix has got to; counted from
+/// 0 again, the mixer would drop all of its frames as late.
+#[derive(Debug, Clone)]
+pub struct MergedCapture {
+    /// Weak, so the stream still ends once every source has
+    tx: mpsc::WeakSender<AudioFrame>,
+}
+
+impl MergedCapture {
+    /// Merge backend receivers into one stream
+    pub fn new(
+        receivers: Vec<(AudioStreamSource, mpsc::Receiver<AudioFrame>)>,
+        capacity: usize,
+    ) -> (mpsc::Receiver<AudioFrame>, Self) {
+        let (tx, rx) = mpsc::channel(capacity);
+        for (source, source_rx) in receivers {
+            forward(source, source_rx, tx.clone(), 0);
+        }
+        (rx, Self { tx: tx.downgrade() })
+    }
+
+    /// Add a restarted source's frames to the stream, their timestamps
+    /// moved on to `position_ms`
+    pub fn restart(
+        &self,
+        source: AudioStreamSource,
+        source_rx: mpsc::Receiver<AudioFrame>,
+        position_ms: u64,
+    ) -> Result<()> {
+        let tx = self.tx.upgrade().context("Capture has ended")?;
+        forward(source, source_rx, tx, position_ms);
+        Ok(())
+    }
+}
+
+/// Forward a source's frames into the merged stream, tagged with it and
+/// offset by `offset_ms`
+fn forward(
+    source: AudioStreamSource,
+    mut source_rx: mpsc::Receiver<AudioFrame>,
+    tx: mpsc::Sender<AudioFrame>,
+    offset_ms: u64,
+) {
+    tokio::spawn(async move {
+        while let Some(mut frame) = source_rx.recv().await {
+            frame.source = source;
+            frame.timestamp_ms += offset_ms;
+            if tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+}
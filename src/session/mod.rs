@@ -1,16 +1,107 @@
 //! Recording session management
 //!
-//! This module provides the `RecordingSession` abstraction that manages:
+//! This module provides the `RecordingSession` abstraction (built from a
+//! `SessionConfig` or with `RecordingSessionBuilder`) that manages:
 //! - Audio capture from system/microphone
 //! - Audio processing (downsampling, mono conversion)
-//! - NATS publishing for STT service
-//! - Transcript collection and storage
-//! - Session statistics and state management
+//! - Publishing to the STT provider (the NATS STT service or a cloud API;
+//!   one mixed stream, or separate system/mic streams for speaker
+//!   attribution)
+//! - Transcript collection and storage, ignoring segments the STT service
+//!   resends, with paged and incremental reads
+//! - Session statistics, quality warnings, and state management
+//! - Per-stage pipeline timings (capture, process, publish, transcript)
+//! - Rolling back a failed start, retrying it per policy
+//! - Timeline markers and live session events
+//! - Bookmarks that save the audio just before them as a highlight clip
+//! - Muting individual sources mid-meeting, marking the muted spans
+//! - Mixing in companion devices (e.g. a phone) as additional microphones
+//!   while recording
+//! - Standby capture between meetings, so sessions can start with the
+//!   minutes before the start
+//! - Re-transcribing ranges of stored meetings
+//! - Transcribing long recordings as parallel overlapping windows
+//! - Replaying stored meetings through NATS at their original pacing
+//! - Splitting final transcripts into chapters
+//! - Word-level diffs between transcripts
+//! - Talk time and longest monologues per speaker
+//! - Limiting how many sessions record at once
+//! - Supervising session tasks, restarting them when they fail
+//! - Watching for capture that stops delivering frames, restarting a
+//!   stalled source from where the mix has got to
+//! - Describing the host a session was recorded on
+//! - Marking utterance boundaries so STT finalizes segments promptly
+//! - Tagging segments with their language, as reported or detected locally
+//! - Dropping likely hallucinated final segments (low confidence, too
+//!   short, blocklisted phrases, or heard over silence)
+//! - Post-processing final segments: writing spoken numbers, dates, and
+//!   currencies as digits, and translating them
 
+mod admission;
+mod analytics;
+mod builder;
+mod chapters;
+mod companion;
 mod config;
+mod dedup;
+mod diff;
+mod events;
+mod filter;
+mod host;
+mod language;
+mod latency;
+mod markers;
+mod merge;
+mod mutes;
+mod normalize;
+mod parallel;
+mod replay;
+mod retranscribe;
+#[allow(clippy::module_inception)]
 mod session;
+mod standby;
 mod stats;
+mod streams;
+mod supervisor;
+mod transcript_log;
+mod translation;
+mod utterance;
+mod watchdog;
 
-pub use config::SessionConfig;
+pub use admission::SessionSlots;
+pub use analytics::{meeting_analytics, MeetingAnalytics, Monologue, SpeakerTalkTime};
+pub use builder::{RecordingSessionBuilder, SessionSources};
+pub use chapters::{
+    apply_chapters, chapter_detector, detect_chapters, Chapter, ChapterDetector,
+    LexicalChapterDetector, RemoteChapterDetector,
+};
+pub use companion::{CompanionFormat, CompanionInput, MAX_COMPANIONS};
+pub use config::{redact_url, MicrophoneConfig, SessionConfig, StartRetryPolicy};
+pub use dedup::SegmentLedger;
+pub use diff::{diff_transcripts, DiffChunk, DiffKind, DiffStats, TranscriptDiff};
+pub use events::{MeetingEvent, SessionEvent};
+pub use filter::{TranscriptFilter, TranscriptFilterConfig};
+pub use host::HostInfo;
+pub use language::{detect_language, normalize_language, segment_language};
+pub use latency::{PipelineLatency, PipelineStage, StageLatency};
+pub use markers::{Bookmark, Marker, MarkerKind};
+pub use merge::MergedCapture;
+pub use mutes::{MuteSpan, SourceMutes};
+pub use normalize::{NormalizationConfig, NumberFormat, SpokenNormalizer};
+pub use parallel::{merge_windows, plan_windows, transcribe_windows, WindowConfig};
+pub use replay::{chunk_frames, replay, replay_delay, ReplayFrame, ReplayOptions, ReplayStats};
+pub use retranscribe::{
+    load_range_audio, place_segments, replace_range, retranscribe, stt_audio, TimeRange,
+};
 pub use session::RecordingSession;
-pub use stats::{SessionStats, TranscriptSegment};
+pub use standby::{Standby, StandbyConfig};
+pub use stats::{AudioRetention, SessionStats, SessionWarning, TranscriptSegment};
+pub use streams::{insert_by_timestamp, split_stereo, StreamRole};
+pub use supervisor::{RestartPolicy, SessionState, SessionTask, Supervisor, TaskFactory};
+pub use transcript_log::{TranscriptLog, TranscriptPage, TranscriptQuery, TranscriptUpdate};
+pub use translation::{
+    TranscriptProcessor, Translation, TranslationConfig, TranslationProcessor, TranslationReply,
+    TranslationRequest,
+};
+pub use utterance::{rms_dbfs, UtteranceBoundary, UtteranceConfig, UtteranceTracker};
+pub use watchdog::{CaptureWatchdog, WatchdogConfig};
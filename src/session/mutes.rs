@@ -0,0 +1,98 @@
+use super::markers::{Marker, MarkerKind};
+use crate::audio::AudioStreamSource;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// A span of meeting time a source was muted for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MuteSpan {
+    pub source: AudioStreamSource,
+    /// Offset from the start of the session when the source was muted
+    pub start_ms: u64,
+    /// Offset when it was unmuted (or the session stopped)
+    pub end_ms: u64,
+}
+
+impl MuteSpan {
+    /// Timeline marker for the span, e.g. "mic muted 12:03–12:08"
+    pub fn marker(&self, started_at: DateTime<Utc>) -> Marker {
+        Marker {
+            kind: MarkerKind::Muted,
+            offset_ms: self.start_ms,
+            timestamp: started_at + Duration::milliseconds(self.start_ms as i64),
+            label: format!(
+                "{} muted {}–{}",
+                self.source,
+                clock(self.start_ms),
+                clock(self.end_ms)
+            ),
+        }
+    }
+}
+
+/// Meeting offset as MM:SS, or H:MM:SS from the first hour on
+fn clock(offset_ms: u64) -> String {
+    let secs = offset_ms / 1000;
+    match secs / 3600 {
+        0 => format!("{:02}:{:02}", secs / 60, secs % 60),
+        hours => format!("{}:{:02}:{:02}", hours, (secs % 3600) / 60, secs % 60),
+    }
+}
+
+/// Sources currently muted in a session
+///
+/// Muted audio is replaced with silence rather than removed, so the
+/// recording and transcript keep their timing.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMutes {
+    /// Muted sources and the offset each was muted at
+    active: HashMap<AudioStreamSource, u64>,
+}
+
+impl SourceMutes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mute a source; returns false if it was already muted
+    pub fn mute(&mut self, source: AudioStreamSource, offset_ms: u64) -> bool {
+        if self.active.contains_key(&source) {
+            return false;
+        }
+        self.active.insert(source, offset_ms);
+        true
+    }
+
+    /// Unmute a source, returning the span it was muted for (None if it
+    /// wasn't muted)
+    pub fn unmute(&mut self, source: AudioStreamSource, offset_ms: u64) -> Option<MuteSpan> {
+        let start_ms = self.active.remove(&source)?;
+        Some(MuteSpan {
+            source,
+            start_ms,
+            end_ms: offset_ms.max(start_ms),
+        })
+    }
+
+    pub fn is_muted(&self, source: AudioStreamSource) -> bool {
+        self.active.contains_key(&source)
+    }
+
+    /// Muted sources, by name
+    pub fn muted(&self) -> Vec<AudioStreamSource> {
+        let mut muted: Vec<AudioStreamSource> = self.active.keys().copied().collect();
+        muted.sort_by_key(|source| source.to_string());
+        muted
+    }
+
+    /// End every mute at `offset_ms` (when the session stops), earliest first
+    pub fn finish(&mut self, offset_ms: u64) -> Vec<MuteSpan> {
+        let mut spans: Vec<MuteSpan> = self
+            .muted()
+            .into_iter()
+            .filter_map(|source| self.unmute(source, offset_ms))
+            .collect();
+        spans.sort_by_key(|span| span.start_ms);
+        spans
+    }
+}
@@ -0,0 +1,767 @@
+use super::stats::TranscriptSegment;
+use super::translation::TranscriptProcessor;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Month names, as they are written in dates
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Spoken currency names and their symbols
+const CURRENCIES: &[(&str, &str)] = &[
+    ("dollar", "$"),
+    ("dollars", "$"),
+    ("buck", "$"),
+    ("bucks", "$"),
+    ("euro", "€"),
+    ("euros", "€"),
+    ("pound", "£"),
+    ("pounds", "£"),
+    ("yen", "¥"),
+];
+
+/// Rewriting of spoken numbers, dates, and currencies in final segments
+/// ("twenty five thousand dollars" becomes "$25,000")
+///
+/// Number words are recognized in English; `locale` picks how the result
+/// is written (separators, currency and date order).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NormalizationConfig {
+    pub enabled: bool,
+    /// e.g. "en-US" ("$25,000", "March 5, 2025") or "en-GB" ("5 March 2025")
+    pub locale: String,
+}
+
+impl Default for NormalizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            locale: "en-US".to_string(),
+        }
+    }
+}
+
+/// How a locale writes numbers and dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Thousands separator
+    pub group: &'static str,
+    /// Decimal separator
+    pub decimal: &'static str,
+    /// Currency symbol after the amount ("25.000 €") rather than before
+    pub currency_after: bool,
+    /// Space between a number and `%`
+    pub percent_space: bool,
+    /// Day before month ("5 March") rather than after ("March 5")
+    pub day_first: bool,
+}
+
+impl NumberFormat {
+    /// Format for a locale such as "en-US", "en_GB", or "de"; unknown
+    /// locales are written the US way
+    pub fn for_locale(locale: &str) -> Self {
+        let locale = locale.to_lowercase().replace('_', "-");
+        let (language, region) = locale.split_once('-').unwrap_or((&locale, ""));
+
+        match language {
+            "en" => Self {
+                day_first: !matches!(region, "" | "us" | "ca" | "ph"),
+                ..Self::US
+            },
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" => Self {
+                group: ".",
+                decimal: ",",
+                currency_after: true,
+                percent_space: true,
+                day_first: true,
+            },
+            "fr" | "sv" | "nb" | "fi" | "pl" | "cs" | "ru" => Self {
+                group: " ",
+                decimal: ",",
+                currency_after: true,
+                percent_space: true,
+                day_first: true,
+            },
+            _ => Self::US,
+        }
+    }
+
+    const US: Self = Self {
+        group: ",",
+        decimal: ".",
+        currency_after: false,
+        percent_space: false,
+        day_first: false,
+    };
+
+    /// Integer with thousands separators
+    fn integer(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push_str(self.group);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    fn number(&self, number: &Spoken) -> String {
+        match &number.fraction {
+            Some(fraction) => format!("{}{}{}", self.integer(number.value), self.decimal, fraction),
+            None => self.integer(number.value),
+        }
+    }
+
+    fn currency(&self, symbol: &str, amount: &str) -> String {
+        if self.currency_after {
+            format!("{} {}", amount, symbol)
+        } else {
+            format!("{}{}", symbol, amount)
+        }
+    }
+
+    fn percent(&self, amount: &str) -> String {
+        if self.percent_space {
+            format!("{} %", amount)
+        } else {
+            format!("{}%", amount)
+        }
+    }
+
+    fn date(&self, month: usize, day: u64, year: Option<u64>) -> String {
+        let month = MONTHS[month];
+        match (self.day_first, year) {
+            (true, Some(year)) => format!("{} {} {}", day, month, year),
+            (true, None) => format!("{} {}", day, month),
+            (false, Some(year)) => format!("{} {}, {}", month, day, year),
+            (false, None) => format!("{} {}", month, day),
+        }
+    }
+}
+
+/// Ordinal with its English suffix ("21st", "12th")
+fn ordinal(value: u64) -> String {
+    let suffix = match (value % 100, value % 10) {
+        (11..=13, _) => "th",
+        (_, 1) => "st",
+        (_, 2) => "nd",
+        (_, 3) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", value, suffix)
+}
+
+/// What a number word contributes to a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Zero,
+    /// 1-9
+    Unit,
+    /// 10-19
+    Teen,
+    /// 20, 30, ... 90
+    Tens,
+    Hundred,
+    /// Thousand, million, billion
+    Scale,
+}
+
+/// A number word's kind, value, and whether it is an ordinal ("fifth")
+fn number_word(word: &str) -> Option<(Kind, u64, bool)> {
+    const UNITS: [(&str, &str); 9] = [
+        ("one", "first"),
+        ("two", "second"),
+        ("three", "third"),
+        ("four", "fourth"),
+        ("five", "fifth"),
+        ("six", "sixth"),
+        ("seven", "seventh"),
+        ("eight", "eighth"),
+        ("nine", "ninth"),
+    ];
+    const TEENS: [(&str, &str); 10] = [
+        ("ten", "tenth"),
+        ("eleven", "eleventh"),
+        ("twelve", "twelfth"),
+        ("thirteen", "thirteenth"),
+        ("fourteen", "fourteenth"),
+        ("fifteen", "fifteenth"),
+        ("sixteen", "sixteenth"),
+        ("seventeen", "seventeenth"),
+        ("eighteen", "eighteenth"),
+        ("nineteen", "nineteenth"),
+    ];
+    const TENS: [(&str, &str); 8] = [
+        ("twenty", "twentieth"),
+        ("thirty", "thirtieth"),
+        ("forty", "fortieth"),
+        ("fifty", "fiftieth"),
+        ("sixty", "sixtieth"),
+        ("seventy", "seventieth"),
+        ("eighty", "eightieth"),
+        ("ninety", "ninetieth"),
+    ];
+    const SCALES: [(&str, &str, u64); 3] = [
+        ("thousand", "thousandth", 1_000),
+        ("million", "millionth", 1_000_000),
+        ("billion", "billionth", 1_000_000_000),
+    ];
+
+    let find = |words: &[(&str, &str)], kind: Kind, value: &dyn Fn(usize) -> u64| {
+        words
+            .iter()
+            .enumerate()
+            .find_map(|(i, (cardinal, ordinal))| {
+                if word == *cardinal {
+                    Some((kind, value(i), false))
+                } else if word == *ordinal {
+                    Some((kind, value(i), true))
+                } else {
+                    None
+                }
+            })
+    };
+
+    match word {
+        "zero" => Some((Kind::Zero, 0, false)),
+        "hundred" => Some((Kind::Hundred, 100, false)),
+        "hundredth" => Some((Kind::Hundred, 100, true)),
+        _ => find(&UNITS, Kind::Unit, &|i| i as u64 + 1)
+            .or_else(|| find(&TEENS, Kind::Teen, &|i| i as u64 + 10))
+            .or_else(|| find(&TENS, Kind::Tens, &|i| (i as u64 + 2) * 10))
+            .or_else(|| {
+                SCALES.iter().find_map(|(cardinal, ordinal, scale)| {
+                    (word == *cardinal)
+                        .then_some((Kind::Scale, *scale, false))
+                        .or((word == *ordinal).then_some((Kind::Scale, *scale, true)))
+                })
+            }),
+    }
+}
+
+/// A number being read word by word
+#[derive(Debug, Clone, Copy)]
+struct Reading {
+    total: u64,
+    current: u64,
+    last: Option<Kind>,
+    /// Smallest scale used so far; each must be smaller than the last
+    scale: u64,
+    ordinal: bool,
+    words: usize,
+}
+
+impl Reading {
+    fn new() -> Self {
+        Self {
+            total: 0,
+            current: 0,
+            last: None,
+            scale: u64::MAX,
+            ordinal: false,
+            words: 0,
+        }
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+
+    /// Whether `kind` can follow the words read so far
+    fn accepts(&self, kind: Kind) -> bool {
+        if self.ordinal || self.last == Some(Kind::Zero) {
+            return false;
+        }
+        let after_group = matches!(self.last, None | Some(Kind::Hundred) | Some(Kind::Scale));
+        match kind {
+            Kind::Zero => self.last.is_none(),
+            Kind::Unit => after_group || self.last == Some(Kind::Tens),
+            Kind::Teen | Kind::Tens => after_group,
+            Kind::Hundred => {
+                matches!(self.last, Some(Kind::Unit | Kind::Teen | Kind::Tens))
+                    && self.current < 100
+            }
+            Kind::Scale => matches!(
+                self.last,
+                Some(Kind::Unit | Kind::Teen | Kind::Tens | Kind::Hundred)
+            ),
+        }
+    }
+
+    /// Read one word; false (leaving the reading unchanged) if it can't be
+    /// part of the number
+    fn push(&mut self, word: &str) -> bool {
+        let Some((kind, value, ordinal)) = number_word(word) else {
+            return false;
+        };
+        if !self.accepts(kind) || (kind == Kind::Scale && value >= self.scale) {
+            return false;
+        }
+
+        match kind {
+            Kind::Zero | Kind::Unit | Kind::Teen | Kind::Tens => self.current += value,
+            Kind::Hundred => self.current *= 100,
+            Kind::Scale => {
+                self.total += self.current * value;
+                self.current = 0;
+                self.scale = value;
+            }
+        }
+        self.last = Some(kind);
+        self.ordinal = ordinal;
+        self.words += 1;
+        true
+    }
+}
+
+/// A word of the text, with the punctuation around it
+#[derive(Debug, Clone)]
+struct Token<'a> {
+    lead: &'a str,
+    /// Lowercased word
+    word: String,
+    trail: &'a str,
+    original: &'a str,
+}
+
+impl<'a> Token<'a> {
+    fn new(original: &'a str) -> Self {
+        let start = original
+            .find(|c: char| c.is_alphanumeric())
+            .unwrap_or(original.len());
+        let end = original
+            .rfind(|c: char| c.is_alphanumeric())
+            .map(|i| i + original[i..].chars().next().map_or(1, char::len_utf8))
+            .unwrap_or(start)
+            .max(start);
+        Self {
+            lead: &original[..start],
+            word: original[start..end].to_lowercase(),
+            trail: &original[end..],
+            original,
+        }
+    }
+
+    /// Whether the number can carry on past this token (no punctuation
+    /// after it)
+    fn open(&self) -> bool {
+        self.trail.is_empty()
+    }
+
+    /// Parts of a hyphenated word ("twenty-five")
+    fn parts(&self) -> impl Iterator<Item = &str> {
+        self.word.split('-')
+    }
+
+    fn is_number_word(&self) -> bool {
+        self.parts().all(|part| number_word(part).is_some())
+    }
+}
+
+/// A number read from the text
+#[derive(Debug, Clone)]
+struct Spoken {
+    value: u64,
+    /// Digits after the decimal point
+    fraction: Option<String>,
+    ordinal: bool,
+    /// Read as a year ("nineteen ninety nine")
+    year: bool,
+    /// Spelled out as a single word below ten, which reads better left alone
+    small: bool,
+    /// Already written as digits
+    digits: bool,
+    /// Tokens read
+    len: usize,
+}
+
+/// Read a number starting at `tokens[start]`
+fn read_number(tokens: &[Token], start: usize) -> Option<Spoken> {
+    let first = tokens.get(start)?;
+    if !first.lead.is_empty() && first.lead != "(" {
+        return None;
+    }
+
+    // Numbers already written as digits
+    if first.word.chars().all(|c| c.is_ascii_digit()) {
+        let value = first.word.parse().ok()?;
+        return Some(Spoken {
+            value,
+            fraction: None,
+            ordinal: false,
+            year: false,
+            small: false,
+            digits: true,
+            len: 1,
+        });
+    }
+
+    let mut reading = Reading::new();
+    let mut len = 0;
+    while let Some(token) = tokens.get(start + len) {
+        if len > 0 && !token.lead.is_empty() {
+            break;
+        }
+
+        // "one hundred and five"
+        if token.word == "and" && token.open() && len > 0 {
+            let next = tokens.get(start + len + 1);
+            let mut after = reading;
+            let joined = matches!(reading.last, Some(Kind::Hundred | Kind::Scale))
+                && next.is_some_and(|next| next.lead.is_empty() && read_token(&mut after, next));
+            if !joined {
+                break;
+            }
+            reading = after;
+            len += 2;
+            if !tokens[start + len - 1].open() {
+                break;
+            }
+            continue;
+        }
+
+        let mut next = reading;
+        // "twenty second" as two words is more often a duration than an ordinal
+        let duration = token.word == "second" && reading.last == Some(Kind::Tens);
+        if duration || !read_token(&mut next, token) {
+            break;
+        }
+        reading = next;
+        len += 1;
+        if !token.open() {
+            break;
+        }
+    }
+    if len == 0 {
+        return None;
+    }
+
+    let mut spoken = Spoken {
+        value: reading.value(),
+        fraction: None,
+        ordinal: reading.ordinal,
+        year: false,
+        small: reading.words == 1 && reading.value() < 10,
+        digits: false,
+        len,
+    };
+    let last_open = tokens[start + len - 1].open();
+
+    // "nineteen ninety nine", "twenty twenty five"
+    if last_open && !spoken.ordinal && reading.words == 1 && matches!(spoken.value, 19 | 20) {
+        if let Some(rest) = read_number(tokens, start + len) {
+            if (10..=99).contains(&rest.value) && rest.fraction.is_none() && !rest.small {
+                let rest_tokens = &tokens[start + len..start + len + rest.len];
+                if rest_tokens.iter().all(Token::is_number_word) {
+                    spoken.value = spoken.value * 100 + rest.value;
+                    spoken.ordinal = rest.ordinal;
+                    spoken.year = !rest.ordinal;
+                    spoken.small = false;
+                    spoken.len += rest.len;
+                }
+            }
+        }
+    }
+
+    // "three point five"
+    let end = start + spoken.len;
+    if tokens[end - 1].open()
+        && !spoken.ordinal
+        && !spoken.year
+        && tokens
+            .get(end)
+            .is_some_and(|t| t.word == "point" && t.lead.is_empty())
+    {
+        let mut digits = String::new();
+        let mut used = 1;
+        while let Some(token) = tokens.get(end + used) {
+            match number_word(&token.word) {
+                Some((Kind::Zero | Kind::Unit, value, false)) if token.lead.is_empty() => {
+                    digits.push_str(&value.to_string());
+                    used += 1;
+                    if !token.open() {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+        if !digits.is_empty() {
+            spoken.fraction = Some(digits);
+            spoken.small = false;
+            spoken.len += used;
+        }
+    }
+
+    Some(spoken)
+}
+
+/// Read every part of a (possibly hyphenated) token; false if any part
+/// doesn't fit
+fn read_token(reading: &mut Reading, token: &Token) -> bool {
+    let mut next = *reading;
+    for part in token.parts() {
+        if !next.push(part) {
+            return false;
+        }
+    }
+    *reading = next;
+    true
+}
+
+/// Month a token names; only capitalized, since "may" and "march" are
+/// also everyday words
+fn month(token: &Token) -> Option<usize> {
+    if !token.original[token.lead.len()..].starts_with(char::is_uppercase) {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|month| month.eq_ignore_ascii_case(&token.word))
+}
+
+/// Rewrites spoken numbers, dates, currencies, and percentages as digits
+pub struct SpokenNormalizer {
+    format: NumberFormat,
+}
+
+impl SpokenNormalizer {
+    pub fn new(locale: &str) -> Self {
+        Self {
+            format: NumberFormat::for_locale(locale),
+        }
+    }
+
+    /// The normalizer `config` selects (None when normalization is off)
+    pub fn from_config(config: &NormalizationConfig) -> Option<Self> {
+        config.enabled.then(|| Self::new(&config.locale))
+    }
+
+    /// `text` with spoken numbers written as digits
+    ///
+    /// Single words below ten ("two questions") are left spelled out, as
+    /// are runs of number words that don't read as one number ("four five
+    /// six").
+    pub fn normalize(&self, text: &str) -> String {
+        let tokens: Vec<Token> = text.split_whitespace().map(Token::new).collect();
+        let mut out: Vec<String> = Vec::with_capacity(tokens.len());
+
+        let mut i = 0;
+        while i < tokens.len() {
+            if let Some((written, len)) = self.date(&tokens, i) {
+                out.push(written);
+                i += len;
+                continue;
+            }
+
+            let Some(number) = read_number(&tokens, i) else {
+                out.push(tokens[i].original.to_string());
+                i += 1;
+                continue;
+            };
+            let end = i + number.len;
+            let last = &tokens[end - 1];
+
+            // A number word it couldn't absorb means several numbers were
+            // spoken in a row; leave them all as they were
+            if last.open() && tokens.get(end).is_some_and(Token::is_number_word) {
+                let mut j = end;
+                while tokens.get(j).is_some_and(Token::is_number_word) {
+                    j += 1;
+                }
+                out.extend(tokens[i..j].iter().map(|t| t.original.to_string()));
+                i = j;
+                continue;
+            }
+
+            let (written, extra) = self.number(&tokens, end, &number);
+            match written {
+                Some(written) => {
+                    let trail = tokens[end + extra - 1].trail;
+                    out.push(format!("{}{}{}", tokens[i].lead, written, trail));
+                }
+                None => out.extend(tokens[i..end].iter().map(|t| t.original.to_string())),
+            }
+            i = end + extra;
+        }
+
+        out.join(" ")
+    }
+
+    /// A number written out with any unit that follows it (currency,
+    /// percent), and how many tokens the unit took; None keeps the words
+    fn number(&self, tokens: &[Token], end: usize, number: &Spoken) -> (Option<String>, usize) {
+        let open = tokens[end - 1].open();
+        let next = tokens.get(end).filter(|t| open && t.lead.is_empty());
+
+        if !number.ordinal {
+            let amount = match number.year || number.digits {
+                true => number.value.to_string(),
+                false => self.format.number(number),
+            };
+
+            if let Some(symbol) = next.and_then(|t| currency(&t.word)) {
+                let mut len = 1;
+                let mut amount = amount;
+                if let Some(cents) = self.cents(tokens, end + 1) {
+                    if number.fraction.is_none() {
+                        amount = format!("{}{}{:02}", amount, self.format.decimal, cents.0);
+                        len += cents.1;
+                    }
+                }
+                return (Some(self.format.currency(symbol, &amount)), len);
+            }
+
+            if let Some(len) = percent(tokens, end) {
+                return (Some(self.format.percent(&amount)), len);
+            }
+
+            if number.small || number.digits {
+                return (None, 0);
+            }
+            return (Some(amount), 0);
+        }
+
+        if number.value < 10 {
+            return (None, 0);
+        }
+        (Some(ordinal(number.value)), 0)
+    }
+
+    /// "and fifty cents" after a currency word: the cents and tokens taken
+    fn cents(&self, tokens: &[Token], start: usize) -> Option<(u64, usize)> {
+        let and = tokens.get(start)?;
+        if and.word != "and" || !tokens[start - 1].open() || !and.open() {
+            return None;
+        }
+        let number = read_number(tokens, start + 1)?;
+        let unit = tokens.get(start + 1 + number.len)?;
+        let cents = matches!(unit.word.as_str(), "cent" | "cents")
+            && unit.lead.is_empty()
+            && tokens[start + number.len].open()
+            && !number.ordinal
+            && number.fraction.is_none()
+            && number.value < 100;
+        cents.then_some((number.value, number.len + 2))
+    }
+
+    /// A date starting at `tokens[start]` ("March fifth twenty twenty
+    /// five", "the fifth of March"), written out, and the tokens it took
+    fn date(&self, tokens: &[Token], start: usize) -> Option<(String, usize)> {
+        let first = &tokens[start];
+
+        let (month_index, day, mut len) = if let Some(month_index) = month(first) {
+            // Month first
+            if !first.open() {
+                return None;
+            }
+            let skip = usize::from(tokens.get(start + 1).is_some_and(|t| t.word == "the"));
+            let day = read_number(tokens, start + 1 + skip)?;
+            let len = 1 + skip + day.len;
+            (month_index, day, len)
+        } else if first.word == "the" && first.open() {
+            // Day first: "the fifth of March"
+            let day = read_number(tokens, start + 1)?;
+            let of = tokens.get(start + 1 + day.len)?;
+            let month_token = tokens.get(start + 2 + day.len)?;
+            if of.word != "of" || !of.open() || !tokens[start + day.len].open() {
+                return None;
+            }
+            let len = 3 + day.len;
+            (month(month_token)?, day, len)
+        } else {
+            return None;
+        };
+
+        if !day.ordinal || day.fraction.is_some() || !(1..=31).contains(&day.value) {
+            return None;
+        }
+
+        let mut year = None;
+        if tokens[start + len - 1].open() {
+            if let Some(number) = read_number(tokens, start + len) {
+                let plausible = number.year || (1000..=2999).contains(&number.value);
+                if plausible && !number.ordinal && number.fraction.is_none() {
+                    year = Some(number.value);
+                    len += number.len;
+                }
+            }
+        }
+
+        let lead = if first.word == "the" { "" } else { first.lead };
+        let written = self.format.date(month_index, day.value, year);
+        Some((
+            format!("{}{}{}", lead, written, tokens[start + len - 1].trail),
+            len,
+        ))
+    }
+}
+
+fn currency(word: &str) -> Option<&'static str> {
+    CURRENCIES
+        .iter()
+        .find(|(name, _)| *name == word)
+        .map(|(_, symbol)| *symbol)
+}
+
+/// Tokens taken by "percent" or "per cent" at `start`
+fn percent(tokens: &[Token], start: usize) -> Option<usize> {
+    if !tokens[start - 1].open() {
+        return None;
+    }
+    let first = tokens.get(start).filter(|t| t.lead.is_empty())?;
+    match first.word.as_str() {
+        "percent" => Some(1),
+        "per" if first.open() && tokens.get(start + 1).is_some_and(|t| t.word == "cent") => Some(2),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl TranscriptProcessor for SpokenNormalizer {
+    fn name(&self) -> &str {
+        "number normalizer"
+    }
+
+    async fn process(
+        &self,
+        _session_id: &str,
+        segment: &TranscriptSegment,
+    ) -> Result<Option<TranscriptSegment>> {
+        // Number words are only recognized in English
+        if segment.partial || segment.language.as_deref().is_some_and(|l| l != "en") {
+            return Ok(None);
+        }
+
+        let text = self.normalize(&segment.text);
+        if text
+            == segment
+                .text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        {
+            return Ok(None);
+        }
+        Ok(Some(TranscriptSegment {
+            text,
+            ..segment.clone()
+        }))
+    }
+}
@@ -0,0 +1,144 @@
+use super::retranscribe::{ms_to_samples, retranscribe, TimeRange};
+use super::stats::TranscriptSegment;
+use super::streams::insert_by_timestamp;
+use crate::storage::MeetingRecord;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// How a long recording is split for parallel transcription
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    /// Length of each window
+    pub window_secs: u64,
+    /// Audio shared by neighbouring windows, so words cut at a window edge
+    /// are heard whole by one of them
+    pub overlap_secs: u64,
+    /// Windows transcribed at once
+    pub concurrency: usize,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 120,
+            overlap_secs: 5,
+            concurrency: 4,
+        }
+    }
+}
+
+/// Split `range` into overlapping windows
+///
+/// Windows start every `window_secs - overlap_secs` and the last one ends
+/// with the range. A range no longer than one window is returned whole.
+pub fn plan_windows(range: TimeRange, config: &WindowConfig) -> Vec<TimeRange> {
+    let window_ms = config.window_secs.max(1) * 1000;
+    let overlap_ms = (config.overlap_secs * 1000).min(window_ms / 2);
+    let step_ms = window_ms - overlap_ms;
+
+    let mut windows = Vec::new();
+    let mut start_ms = range.start_ms;
+    loop {
+        let end_ms = (start_ms + window_ms).min(range.end_ms);
+        windows.push(TimeRange { start_ms, end_ms });
+        if end_ms >= range.end_ms {
+            return windows;
+        }
+        start_ms += step_ms;
+    }
+}
+
+/// Merge per-window transcripts into one, dropping overlap duplicates
+///
+/// Each overlap is cut at its midpoint: the earlier window keeps segments
+/// before it, the later window those after. A segment repeated word for
+/// word on both sides of a cut is kept once.
+pub fn merge_windows(
+    started_at: DateTime<Utc>,
+    windows: &[(TimeRange, Vec<TranscriptSegment>)],
+) -> Vec<TranscriptSegment> {
+    let mut merged: Vec<TranscriptSegment> = Vec::new();
+
+    for (index, (window, segments)) in windows.iter().enumerate() {
+        let from_ms = match index.checked_sub(1).map(|i| windows[i].0) {
+            Some(previous) if previous.end_ms > window.start_ms => {
+                (window.start_ms + previous.end_ms) / 2
+            }
+            _ => window.start_ms,
+        };
+        let to_ms = match windows.get(index + 1).map(|(next, _)| *next) {
+            Some(next) if window.end_ms > next.start_ms => (next.start_ms + window.end_ms) / 2,
+            _ => window.end_ms,
+        };
+        let kept = TimeRange {
+            start_ms: from_ms,
+            end_ms: to_ms,
+        };
+
+        let last_text = merged.last().map(|segment| normalize(&segment.text));
+        for (position, segment) in segments
+            .iter()
+            .filter(|segment| kept.contains(started_at, segment))
+            .enumerate()
+        {
+            if position == 0 && last_text.as_deref() == Some(normalize(&segment.text).as_str()) {
+                continue;
+            }
+            insert_by_timestamp(&mut merged, segment.clone());
+        }
+    }
+
+    merged
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Transcribe a long range as overlapping windows published concurrently
+///
+/// `pcm` is the range's 16kHz mono audio. Each window goes out under its
+/// own sub-session ID (see [`retranscribe`]), at most
+/// `config.concurrency` at a time, and the results are merged with
+/// [`merge_windows`].
+pub async fn transcribe_windows(
+    nats_url: &str,
+    record: &MeetingRecord,
+    range: TimeRange,
+    pcm: &[i16],
+    model: Option<&str>,
+    config: &WindowConfig,
+) -> Result<Vec<TranscriptSegment>> {
+    let windows = plan_windows(range, config);
+    info!(
+        "Transcribing {} as {} windows ({} at a time)",
+        record.meeting_id,
+        windows.len(),
+        config.concurrency.max(1)
+    );
+
+    let mut results: Vec<(TimeRange, Vec<TranscriptSegment>)> = stream::iter(windows)
+        .map(|window| async move {
+            let from = ms_to_samples(window.start_ms - range.start_ms).min(pcm.len());
+            let to = ms_to_samples(window.end_ms - range.start_ms).min(pcm.len());
+            let segments = retranscribe(nats_url, record, window, &pcm[from..to], model).await?;
+            anyhow::Ok((window, segments))
+        })
+        .buffer_unordered(config.concurrency.max(1))
+        .try_collect()
+        .await?;
+    results.sort_by_key(|(window, _)| window.start_ms);
+
+    Ok(merge_windows(record.started_at, &results))
+}
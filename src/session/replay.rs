@@ -0,0 +1,187 @@
+use super::retranscribe::{frame_message, load_chunk_audio, ms_to_samples, TimeRange, FRAME_MS};
+use crate::audio::ChunkMetadata;
+use crate::nats::NatsClient;
+use crate::storage::MeetingRecord;
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::info;
+
+/// How a stored meeting is replayed
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    /// Playback speed relative to the original pacing (2.0 = twice as fast,
+    /// 0 = as fast as possible)
+    pub speed: f64,
+    /// Part of the meeting to replay (None = all of it)
+    pub range: Option<TimeRange>,
+    /// Session ID to publish under (None = `{id}.replay-{n}`)
+    pub session_id: Option<String>,
+    /// Model hint passed to STT
+    pub model: Option<String>,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            range: None,
+            session_id: None,
+            model: None,
+        }
+    }
+}
+
+/// One frame of replayed audio
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFrame {
+    /// Position in the meeting, in milliseconds from its start
+    pub offset_ms: u64,
+    /// 16kHz mono PCM
+    pub samples: Vec<i16>,
+}
+
+/// Outcome of a replay
+#[derive(Debug, Clone)]
+pub struct ReplayStats {
+    /// Session ID the frames were published under
+    pub session_id: String,
+    /// Audio frames published (not counting the final marker)
+    pub frames: u32,
+    /// Span of meeting time replayed, in milliseconds
+    pub audio_ms: u64,
+    /// Wall-clock time the replay took
+    pub elapsed: Duration,
+}
+
+/// Split a stored chunk into frames at their original meeting offsets
+///
+/// Only frames starting inside `range` are kept, so gaps between chunks
+/// (e.g. a restarted capture) survive the replay.
+pub fn chunk_frames(chunk: &ChunkMetadata, range: Option<TimeRange>) -> Result<Vec<ReplayFrame>> {
+    let samples = load_chunk_audio(chunk)?;
+
+    Ok(samples
+        .chunks(ms_to_samples(FRAME_MS))
+        .enumerate()
+        .map(|(index, frame)| ReplayFrame {
+            offset_ms: chunk.start_ms + index as u64 * FRAME_MS,
+            samples: frame.to_vec(),
+        })
+        .filter(|frame| {
+            range.is_none_or(|r| frame.offset_ms >= r.start_ms && frame.offset_ms < r.end_ms)
+        })
+        .collect())
+}
+
+/// When a frame is due, relative to the first replayed frame
+///
+/// Returns `None` when frames should go out as fast as possible.
+pub fn replay_delay(offset_ms: u64, first_ms: u64, speed: f64) -> Option<Duration> {
+    if !speed.is_finite() || speed <= 0.0 {
+        return None;
+    }
+    let elapsed_ms = offset_ms.saturating_sub(first_ms) as f64;
+    Some(Duration::from_secs_f64(elapsed_ms / 1000.0 / speed))
+}
+
+/// Publish a stored meeting's audio to NATS with its original pacing
+///
+/// Chunks are loaded one at a time as the replay reaches them. Frames carry
+/// their original meeting time, and are sent as a live session would send
+/// them so the STT service cannot tell the difference. The stream ends with
+/// a final frame.
+pub async fn replay(
+    nats_url: &str,
+    record: &MeetingRecord,
+    options: &ReplayOptions,
+) -> Result<ReplayStats> {
+    let session_id = options.session_id.clone().unwrap_or_else(|| {
+        format!(
+            "{}.replay-{}",
+            record.meeting_id,
+            uuid::Uuid::new_v4().simple()
+        )
+    });
+    let nats = NatsClient::connect(nats_url, session_id.clone())
+        .await
+        .context("Failed to connect to NATS")?;
+
+    let mut chunks: Vec<_> = record
+        .chunks
+        .iter()
+        .filter(|c| {
+            options
+                .range
+                .is_none_or(|r| c.end_ms > r.start_ms && c.start_ms < r.end_ms)
+        })
+        .collect();
+    chunks.sort_by_key(|c| c.start_ms);
+    if chunks.is_empty() {
+        anyhow::bail!("Meeting {} has no audio to replay", record.meeting_id);
+    }
+
+    info!(
+        "Replaying {} ({} chunks) as {} at {}x",
+        record.meeting_id,
+        chunks.len(),
+        session_id,
+        options.speed
+    );
+
+    let started = Instant::now();
+    let model = options.model.as_deref();
+    let mut first_ms = None;
+    let mut end_ms = 0;
+    let mut sequence = 0;
+    for chunk in chunks {
+        for frame in chunk_frames(chunk, options.range)? {
+            let first_ms = *first_ms.get_or_insert(frame.offset_ms);
+            if let Some(delay) = replay_delay(frame.offset_ms, first_ms, options.speed) {
+                tokio::time::sleep_until(started + delay).await;
+            }
+
+            let bytes: Vec<u8> = frame.samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+            let timestamp =
+                record.started_at + chrono::Duration::milliseconds(frame.offset_ms as i64);
+            nats.publish_audio_message(&frame_message(
+                &session_id,
+                sequence,
+                &bytes,
+                timestamp,
+                false,
+                model,
+            ))
+            .await?;
+
+            sequence += 1;
+            end_ms = frame.offset_ms + FRAME_MS;
+        }
+    }
+
+    let first_ms = first_ms.unwrap_or(end_ms);
+    nats.publish_audio_message(&frame_message(
+        &session_id,
+        sequence,
+        &[],
+        record.started_at + chrono::Duration::milliseconds(end_ms as i64),
+        true,
+        model,
+    ))
+    .await?;
+
+    let stats = ReplayStats {
+        session_id,
+        frames: sequence,
+        audio_ms: end_ms.saturating_sub(first_ms),
+        elapsed: started.elapsed(),
+    };
+    info!(
+        "Replayed {} frames of {} in {:.1}s",
+        stats.frames,
+        record.meeting_id,
+        stats.elapsed.as_secs_f64()
+    );
+
+    Ok(stats)
+}
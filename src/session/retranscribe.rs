@@ -0,0 +1,297 @@
+use super::dedup::SegmentLedger;
+use super::language::segment_language;
+use super::stats::TranscriptSegment;
+use super::streams::insert_by_timestamp;
+use crate::audio::{AudioFile, ChunkMetadata, Resampler};
+use crate::nats::{AudioCodec, AudioFrameMessage, NatsClient, TranscriptMessage, PROTOCOL_VERSION};
+use crate::storage::MeetingRecord;
+use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures::stream::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::info;
+
+/// Sample rate audio is sent to STT at (Whisper expects 16kHz mono)
+pub(super) const STT_SAMPLE_RATE: u32 = 16000;
+
+/// Duration of each published frame (matches live capture)
+pub(super) const FRAME_MS: u64 = 100;
+
+/// Stop collecting once STT has been quiet this long after the audio was sent
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper bound on waiting for a single re-transcription
+const MAX_WAIT: Duration = Duration::from_secs(600);
+
+/// A span of meeting time, in milliseconds from the start of the meeting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl TimeRange {
+    pub fn new(start_ms: u64, end_ms: u64) -> Result<Self> {
+        if end_ms <= start_ms {
+            anyhow::bail!("Time range end must be after its start");
+        }
+        Ok(Self { start_ms, end_ms })
+    }
+
+    /// Span covered by the given audio chunks
+    pub fn from_chunks(record: &MeetingRecord, indices: &[usize]) -> Result<Self> {
+        let chunks = indices
+            .iter()
+            .map(|index| {
+                record
+                    .chunks
+                    .iter()
+                    .find(|c| c.chunk_index == *index)
+                    .with_context(|| format!("Meeting has no audio chunk {}", index))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let start_ms = chunks.iter().map(|c| c.start_ms).min();
+        let end_ms = chunks.iter().map(|c| c.end_ms).max();
+        match (start_ms, end_ms) {
+            (Some(start_ms), Some(end_ms)) => Self::new(start_ms, end_ms),
+            _ => anyhow::bail!("No chunks given"),
+        }
+    }
+
+    /// Whether a transcript segment falls inside the range
+    pub fn contains(&self, started_at: DateTime<Utc>, segment: &TranscriptSegment) -> bool {
+        let offset_ms = segment
+            .timestamp
+            .signed_duration_since(started_at)
+            .num_milliseconds();
+        offset_ms >= self.start_ms as i64 && offset_ms < self.end_ms as i64
+    }
+
+    fn start_time(&self, started_at: DateTime<Utc>) -> DateTime<Utc> {
+        started_at + chrono::Duration::milliseconds(self.start_ms as i64)
+    }
+
+    fn end_time(&self, started_at: DateTime<Utc>) -> DateTime<Utc> {
+        started_at + chrono::Duration::milliseconds(self.end_ms as i64)
+    }
+}
+
+/// Load a range of a meeting's stored audio as 16kHz mono PCM
+pub fn load_range_audio(record: &MeetingRecord, range: TimeRange) -> Result<Vec<i16>> {
+    let mut pcm = Vec::new();
+
+    let mut chunks: Vec<_> = record
+        .chunks
+        .iter()
+        .filter(|c| c.end_ms > range.start_ms && c.start_ms < range.end_ms)
+        .collect();
+    chunks.sort_by_key(|c| c.start_ms);
+
+    for chunk in chunks {
+        let samples = load_chunk_audio(chunk)?;
+        let from = ms_to_samples(range.start_ms.saturating_sub(chunk.start_ms)).min(samples.len());
+        let to = ms_to_samples(range.end_ms.min(chunk.end_ms) - chunk.start_ms).min(samples.len());
+        pcm.extend_from_slice(&samples[from..to]);
+    }
+
+    if pcm.is_empty() {
+        anyhow::bail!(
+            "No stored audio covers {}ms-{}ms",
+            range.start_ms,
+            range.end_ms
+        );
+    }
+
+    Ok(pcm)
+}
+
+/// Load one stored audio chunk as 16kHz mono PCM, from `start_ms` (without
+/// the audio it repeats from the previous chunk)
+pub(super) fn load_chunk_audio(chunk: &ChunkMetadata) -> Result<Vec<i16>> {
+    let audio = AudioFile::open_track(&chunk.file_path, chunk.track.unwrap_or(0))
+        .with_context(|| format!("Failed to read audio chunk {}", chunk.chunk_index))?;
+    let mut samples = stt_audio(&audio);
+    samples.drain(..ms_to_samples(chunk.overlap_ms).min(samples.len()));
+    Ok(samples)
+}
+
+/// Fold a decoded file to the 16kHz mono PCM STT expects
+pub fn stt_audio(audio: &AudioFile) -> Vec<i16> {
+    let samples = audio.to_mono();
+    if audio.sample_rate != STT_SAMPLE_RATE {
+        return Resampler::new(audio.sample_rate, STT_SAMPLE_RATE, 1).process(&samples);
+    }
+    samples
+}
+
+/// Send a meeting range through the STT pipeline and return the new segments
+///
+/// Audio is published under a throwaway sub-ID of the meeting
+/// (`{id}.retranscribe-{n}`) so results cannot mix with a live session.
+/// Frames carry their original meeting time, and `model` is passed to STT
+/// as a hint.
+pub async fn retranscribe(
+    nats_url: &str,
+    record: &MeetingRecord,
+    range: TimeRange,
+    pcm: &[i16],
+    model: Option<&str>,
+) -> Result<Vec<TranscriptSegment>> {
+    let stream_id = format!(
+        "{}.retranscribe-{}",
+        record.meeting_id,
+        uuid::Uuid::new_v4().simple()
+    );
+    let nats = NatsClient::connect(nats_url, stream_id.clone())
+        .await
+        .context("Failed to connect to NATS")?;
+
+    // Subscribe before publishing so no result is missed
+    let mut transcripts = nats.subscribe_transcripts().await?;
+
+    info!(
+        "Re-transcribing {} ({}ms-{}ms) as {}",
+        record.meeting_id, range.start_ms, range.end_ms, stream_id
+    );
+
+    let start = range.start_time(record.started_at);
+    let frame_samples = ms_to_samples(FRAME_MS);
+    let mut sequence = 0;
+    for (index, frame) in pcm.chunks(frame_samples).enumerate() {
+        let bytes: Vec<u8> = frame.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let timestamp = start + chrono::Duration::milliseconds((index as u64 * FRAME_MS) as i64);
+        nats.publish_audio_message(&frame_message(
+            &stream_id, sequence, &bytes, timestamp, false, model,
+        ))
+        .await?;
+        sequence += 1;
+    }
+    nats.publish_audio_message(&frame_message(
+        &stream_id,
+        sequence,
+        &[],
+        range.end_time(record.started_at),
+        true,
+        model,
+    ))
+    .await?;
+
+    // Collect final results until STT goes quiet
+    let deadline = Instant::now() + MAX_WAIT;
+    let mut results = Vec::new();
+    let mut ledger = SegmentLedger::new();
+    loop {
+        let wait = IDLE_TIMEOUT.min(deadline.saturating_duration_since(Instant::now()));
+        let Ok(Some(msg)) = tokio::time::timeout(wait, transcripts.next()).await else {
+            break;
+        };
+
+        match serde_json::from_slice::<TranscriptMessage>(&msg.payload) {
+            Ok(transcript)
+                if transcript.session_id == stream_id
+                    && !transcript.partial
+                    && ledger.admit(&transcript) =>
+            {
+                results.push(transcript)
+            }
+            _ => {}
+        }
+    }
+
+    info!(
+        "Re-transcription of {} returned {} segments",
+        record.meeting_id,
+        results.len()
+    );
+
+    Ok(place_segments(&results, record.started_at, range))
+}
+
+/// Turn STT results into segments positioned inside `range`
+///
+/// Results whose timestamp falls in the range keep it (the STT service
+/// echoed the audio time); the rest are spread evenly across the range in
+/// arrival order.
+pub fn place_segments(
+    results: &[TranscriptMessage],
+    started_at: DateTime<Utc>,
+    range: TimeRange,
+) -> Vec<TranscriptSegment> {
+    let start = range.start_time(started_at);
+    let end = range.end_time(started_at);
+    let step_ms = (range.end_ms - range.start_ms) / results.len().max(1) as u64;
+
+    results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| {
+            let timestamp = DateTime::parse_from_rfc3339(&result.timestamp)
+                .map(|t| t.with_timezone(&Utc))
+                .ok()
+                .filter(|t| *t >= start && *t < end)
+                .unwrap_or_else(|| {
+                    start + chrono::Duration::milliseconds((index as u64 * step_ms) as i64)
+                });
+
+            TranscriptSegment {
+                text: result.text.clone(),
+                timestamp,
+                confidence: result.confidence,
+                partial: false,
+                speaker: result.speaker.clone(),
+                language: segment_language(result.language.as_deref(), &result.text),
+                translation: None,
+            }
+        })
+        .collect()
+}
+
+/// Replace the segments inside `range` with `segments`, returning how many were removed
+pub fn replace_range(
+    transcript: &mut Vec<TranscriptSegment>,
+    started_at: DateTime<Utc>,
+    range: TimeRange,
+    segments: Vec<TranscriptSegment>,
+) -> usize {
+    let before = transcript.len();
+    transcript.retain(|segment| !range.contains(started_at, segment));
+    let removed = before - transcript.len();
+
+    for segment in segments {
+        insert_by_timestamp(transcript, segment);
+    }
+
+    removed
+}
+
+pub(super) fn frame_message(
+    stream_id: &str,
+    sequence: u32,
+    pcm_bytes: &[u8],
+    timestamp: DateTime<Utc>,
+    final_frame: bool,
+    model: Option<&str>,
+) -> AudioFrameMessage {
+    AudioFrameMessage {
+        version: PROTOCOL_VERSION,
+        session_id: stream_id.to_string(),
+        sequence,
+        pcm: base64::engine::general_purpose::STANDARD.encode(pcm_bytes),
+        sample_rate: STT_SAMPLE_RATE,
+        channels: 1,
+        timestamp: timestamp.to_rfc3339(),
+        final_frame,
+        segment_final: false,
+        model: model.map(str::to_string),
+        codec: AudioCodec::Pcm16,
+        packets: Vec::new(),
+    }
+}
+
+pub(super) fn ms_to_samples(ms: u64) -> usize {
+    (ms * STT_SAMPLE_RATE as u64 / 1000) as usize
+}
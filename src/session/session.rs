@@ -1,72 +1,370 @@
+use super::companion::{
+    CompanionAudio, CompanionFormat, CompanionInput, CompanionPairing, Companions,
+};
 use super::config::SessionConfig;
-use super::stats::{SessionStats, TranscriptSegment};
-use crate::audio::{AudioBackendConfig, AudioBackendFactory, AudioFrame, AudioSource};
-use crate::nats::{NatsClient, TranscriptMessage};
+use super::dedup::SegmentLedger;
+use super::events::SessionEvent;
+use super::filter::TranscriptFilter;
+use super::host::HostInfo;
+use super::language::segment_language;
+use super::latency::{PipelineLatency, PipelineStage, StageLatency};
+use super::markers::{Bookmark, Marker, MarkerKind};
+use super::merge::MergedCapture;
+use super::mutes::SourceMutes;
+use super::normalize::SpokenNormalizer;
+use super::stats::{AudioRetention, SessionStats, SessionWarning, TranscriptSegment};
+use super::streams::StreamRole;
+use super::supervisor::{SessionState, SessionTask, Supervisor};
+use super::transcript_log::{TranscriptLog, TranscriptPage, TranscriptQuery, TranscriptUpdate};
+use super::translation::{TranscriptProcessor, TranslationProcessor};
+use super::utterance::{rms_dbfs, UtteranceTracker};
+use super::watchdog::{stall_deadline, CaptureWatchdog};
+use crate::audio::activity::channel_level;
+use crate::audio::{
+    AppActivitySummary, AppActivityTracker, AudioBackend, AudioBackendConfig, AudioBackendFactory,
+    AudioFrame, AudioSource, AudioStreamSource, ChannelMap, ChunkConfig, ChunkMetadata,
+    ChunkedRecorder, DeviceEvent, DeviceEventKind, DirInUse, DownmixStrategy, FloatFrame,
+    FrameDropStats, MeetingDirLock, Mixer, MixerConfig, MixerInput, OpusEncoder, Pipeline,
+    PlaybackBridge, PrerollBuffer, StereoSplitter,
+};
+use crate::clock::{Clock, SharedClock};
+use crate::nats::{
+    AudioCodec, AudioFrameMessage, HandshakeRequest, NatsClient, Protocol, SessionStatus,
+    TranscriptStreamConfig, TranscriptSubscription,
+};
+use crate::screencapture;
+use crate::stt::{self, BudgetStatus, NatsProvider, SttBudget, SttProvider, SttProviderKind};
 use anyhow::{Context, Result};
+use base64::Engine;
 use chrono::Utc;
-use futures::stream::StreamExt;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
-use tracing::{error, info, warn};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, watch, Mutex};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, debug_span, error, info, warn, Instrument};
+
+/// How often running applications are sampled for audio activity
+const APP_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Buffered live events per subscriber before slow subscribers start lagging
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Buffered audio frames per frame subscriber before slow subscribers start
+/// lagging
+const FRAME_CHANNEL_CAPACITY: usize = 256;
+
+/// Buffered transcript segments per subscriber before slow subscribers
+/// start lagging (partials arrive several times a second)
+const TRANSCRIPT_CHANNEL_CAPACITY: usize = 256;
+
+/// Frames the mixer must receive before the drop rate is considered meaningful
+const MIN_FRAMES_FOR_DROP_RATE: u64 = 50;
 
 /// A recording session that manages audio capture, NATS publishing, and transcript collection
 pub struct RecordingSession {
     /// Session configuration
     config: SessionConfig,
 
-    /// NATS client for publishing audio and receiving transcripts
-    nats_client: Arc<NatsClient>,
+    /// NATS client for session status and app activity (None = the
+    /// session doesn't use NATS)
+    nats_client: Option<Arc<NatsClient>>,
+
+    /// Where audio is sent for transcription (None = record-only session)
+    stt: Option<Arc<dyn SttProvider>>,
+
+    /// Run on each final segment once it is stored (e.g. translation)
+    processors: Arc<Vec<Box<dyn TranscriptProcessor>>>,
+
+    /// Budget cloud transcription counts against (None = not limited)
+    stt_budget: Option<Arc<SttBudget>>,
+
+    /// Cleared when the STT budget runs out, leaving the session record-only
+    transcribing: Arc<AtomicBool>,
 
     /// When the session started
     started_at: chrono::DateTime<chrono::Utc>,
 
+    /// When the session was first stopped; its duration ends there
+    stopped_at: std::sync::Mutex<Option<chrono::DateTime<chrono::Utc>>>,
+
+    /// Stats as of the first stop, returned by later stops (e.g. to retry
+    /// saving the meeting)
+    final_stats: std::sync::Mutex<Option<SessionStats>>,
+
     /// Whether recording is currently active
     is_recording: Arc<AtomicBool>,
 
-    /// Number of chunks recorded
-    chunks_recorded: Arc<AtomicUsize>,
+    /// Cancelled by stop to end the session's tasks (replaced on each start)
+    shutdown: std::sync::Mutex<CancellationToken>,
+
+    /// Writes the session's audio to chunk files (None = audio not stored)
+    recorder: Arc<std::sync::Mutex<Option<ChunkedRecorder>>>,
 
     /// Accumulated transcript segments
-    transcript_segments: Arc<Mutex<Vec<TranscriptSegment>>>,
+    transcript_segments: Arc<Mutex<TranscriptLog>>,
+
+    /// New transcript segments for subscribers
+    transcripts: broadcast::Sender<TranscriptSegment>,
+
+    /// Drops likely hallucinated final segments (None = not filtered)
+    transcript_filter: Option<Arc<TranscriptFilter>>,
+
+    /// Speech heard in published audio, in milliseconds (counted only when
+    /// the transcript filter checks it)
+    speech_ms: Arc<AtomicU64>,
+
+    /// Final segments already stored, kept across transcript task restarts
+    /// so resent segments are not duplicated
+    segment_ledger: Arc<std::sync::Mutex<SegmentLedger>>,
 
-    /// Handle for the audio processing task
-    audio_task_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Machine recording the session, with its capture backend once started
+    host: std::sync::Mutex<Option<HostInfo>>,
 
-    /// Handle for the transcript receiving task
-    transcript_task_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Lifecycle state, updated by the task supervisor
+    state: watch::Sender<SessionState>,
+
+    /// Handle for the supervisor of the audio and transcript tasks
+    supervisor_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
 
     /// Frame sequence counter
     frame_sequence: Arc<AtomicUsize>,
+
+    /// Frame sequence counter for the dual-stream mic stream
+    mic_sequence: Arc<AtomicUsize>,
+
+    /// Most recent system audio RMS level (f32 bits)
+    system_level: Arc<AtomicU32>,
+
+    /// Most recent per-application activity summary
+    app_activity: Arc<Mutex<Option<AppActivitySummary>>>,
+
+    /// Handle for the app activity sampling task
+    app_activity_task_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+
+    /// Handle for the device change monitoring task
+    device_task_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+
+    /// Timeline markers (gaps, etc.)
+    markers: Arc<Mutex<Vec<Marker>>>,
+
+    /// Live session events for subscribers
+    events: broadcast::Sender<SessionEvent>,
+
+    /// Processed audio frames for subscribers
+    frames: broadcast::Sender<AudioFrame>,
+
+    /// Mixer frame drop counters
+    frame_drops: Arc<Mutex<FrameDropStats>>,
+
+    /// Quality warnings raised during the session
+    warnings: Arc<Mutex<Vec<SessionWarning>>>,
+
+    /// How long the first audio frame took to arrive during warm-up
+    /// (None = no warm-up)
+    first_frame_latency: std::sync::Mutex<Option<Duration>>,
+
+    /// Time spent in each pipeline stage
+    latency: Arc<std::sync::Mutex<PipelineLatency>>,
+
+    /// Sources whose audio is replaced with silence
+    mutes: Arc<std::sync::Mutex<SourceMutes>>,
+
+    /// Recent recorded audio, saved when the session is bookmarked (None
+    /// when the session keeps no audio)
+    preroll: Option<Arc<std::sync::Mutex<PrerollBuffer>>>,
+
+    /// Audio from before the start (standby), recorded and published ahead
+    /// of captured audio
+    backfill: Arc<std::sync::Mutex<Option<Vec<i16>>>>,
+
+    /// How much audio `backfill` held
+    backfilled: Duration,
+
+    /// Audio from connected companion devices, for the audio task
+    companion_tx: mpsc::UnboundedSender<CompanionAudio>,
+    companion_rx: Arc<Mutex<mpsc::UnboundedReceiver<CompanionAudio>>>,
+
+    /// Connected companion devices' sources and mixing gains
+    companions: Companions,
+
+    /// Token companion devices must present to join
+    companion_pairing: CompanionPairing,
 }
 
 impl RecordingSession {
     /// Create a new recording session
     pub async fn new(config: SessionConfig) -> Result<Self> {
+        Self::with_stt_budget(config, None).await
+    }
+
+    /// Create a recording session whose cloud transcription counts against
+    /// `budget`; once it runs out the session continues record-only
+    pub async fn with_stt_budget(
+        mut config: SessionConfig,
+        budget: Option<Arc<SttBudget>>,
+    ) -> Result<Self> {
         info!("Creating recording session: {}", config.session_id);
+        let stt_budget = budget.filter(|_| config.transcription && config.stt.provider.is_cloud());
+
+        // Record-only sessions never talk to an STT service, and sessions
+        // transcribed by a cloud provider don't need NATS
+        let (nats_client, stt) = if !config.transcription {
+            if config.privacy || !config.store_audio {
+                anyhow::bail!(
+                    "A session that neither transcribes nor stores audio records nothing"
+                );
+            }
+            // Separate streams only matter to STT; record everything mixed
+            config.dual_stream = false;
+            (None, None)
+        } else if config.stt.provider == SttProviderKind::Nats {
+            let nats_client = Arc::new(
+                NatsClient::connect(&config.nats_url, config.session_id.clone())
+                    .await
+                    .context("Failed to connect to NATS")?
+                    .with_clock(Arc::clone(&config.clock)),
+            );
+            let stt: Arc<dyn SttProvider> = Arc::new(NatsProvider::new(Arc::clone(&nats_client)));
+            (Some(nats_client), Some(stt))
+        } else {
+            let stt = stt::cloud_provider(&config.stt, stt_budget.clone())?;
+            info!("Transcribing with {}", stt.name());
+            (None, Some(stt))
+        };
 
-        // Connect to NATS
-        let nats_client = Arc::new(
-            NatsClient::connect(&config.nats_url, config.session_id.clone())
-                .await
-                .context("Failed to connect to NATS")?,
-        );
+        // Numbers are normalized before translation, so translations get
+        // the digits too. Translation is best effort; the session records
+        // without it.
+        let mut processors: Vec<Box<dyn TranscriptProcessor>> = Vec::new();
+        if let Some(normalizer) = SpokenNormalizer::from_config(&config.normalization) {
+            processors.push(Box::new(normalizer));
+        }
+        if stt.is_some() {
+            match TranslationProcessor::from_config(
+                &config.translation,
+                &config.session_id,
+                nats_client.clone(),
+                &config.nats_url,
+            )
+            .await
+            {
+                Ok(Some(translator)) => {
+                    info!(
+                        "Translating transcripts into {} with {}",
+                        translator.target_language(),
+                        translator.name()
+                    );
+                    processors.push(Box::new(translator));
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Transcripts will not be translated: {:#}", e),
+            }
+        }
+
+        // Clips are plaintext, so encrypted meetings get bookmarks without them
+        let keeps_audio = config.store_audio && !config.privacy && config.encryption_key.is_none();
+        let preroll = (keeps_audio && !config.preroll.is_zero()).then(|| {
+            Arc::new(std::sync::Mutex::new(PrerollBuffer::new(
+                config.preroll,
+                config.sample_rate,
+                config.channels,
+            )))
+        });
+
+        let transcript_filter =
+            TranscriptFilter::from_config(&config.transcript_filter).map(Arc::new);
+        let (companion_tx, companion_rx) = mpsc::unbounded_channel();
 
         Ok(Self {
+            started_at: config.clock.now(),
+            stopped_at: std::sync::Mutex::new(None),
+            final_stats: std::sync::Mutex::new(None),
+            preroll,
+            host: std::sync::Mutex::new(config.host.clone()),
             config,
             nats_client,
-            started_at: Utc::now(),
+            transcribing: Arc::new(AtomicBool::new(stt.is_some())),
+            stt,
+            processors: Arc::new(processors),
+            stt_budget,
             is_recording: Arc::new(AtomicBool::new(false)),
-            chunks_recorded: Arc::new(AtomicUsize::new(0)),
-            transcript_segments: Arc::new(Mutex::new(Vec::new())),
-            audio_task_handle: Arc::new(Mutex::new(None)),
-            transcript_task_handle: Arc::new(Mutex::new(None)),
+            shutdown: std::sync::Mutex::new(CancellationToken::new()),
+            recorder: Arc::new(std::sync::Mutex::new(None)),
+            transcript_segments: Arc::new(Mutex::new(TranscriptLog::new())),
+            transcripts: broadcast::channel(TRANSCRIPT_CHANNEL_CAPACITY).0,
+            transcript_filter,
+            speech_ms: Arc::new(AtomicU64::new(0)),
+            segment_ledger: Arc::new(std::sync::Mutex::new(SegmentLedger::new())),
+            state: watch::channel(SessionState::default()).0,
+            supervisor_handle: Arc::new(Mutex::new(None)),
             frame_sequence: Arc::new(AtomicUsize::new(0)),
+            mic_sequence: Arc::new(AtomicUsize::new(0)),
+            system_level: Arc::new(AtomicU32::new(0)),
+            app_activity: Arc::new(Mutex::new(None)),
+            app_activity_task_handle: Arc::new(Mutex::new(None)),
+            device_task_handle: Arc::new(Mutex::new(None)),
+            markers: Arc::new(Mutex::new(Vec::new())),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            frames: broadcast::channel(FRAME_CHANNEL_CAPACITY).0,
+            frame_drops: Arc::new(Mutex::new(FrameDropStats::default())),
+            warnings: Arc::new(Mutex::new(Vec::new())),
+            first_frame_latency: std::sync::Mutex::new(None),
+            latency: Arc::new(std::sync::Mutex::new(PipelineLatency::new())),
+            mutes: Arc::new(std::sync::Mutex::new(SourceMutes::new())),
+            backfill: Arc::new(std::sync::Mutex::new(None)),
+            backfilled: Duration::ZERO,
+            companion_tx,
+            companion_rx: Arc::new(Mutex::new(companion_rx)),
+            companions: Arc::default(),
+            companion_pairing: CompanionPairing::default(),
         })
     }
 
+    /// Start the session with `audio` captured just before it, moving its
+    /// start back by the audio's duration
+    ///
+    /// Audio in a different format than the session's is ignored.
+    pub fn with_backfill(mut self, audio: PrerollBuffer) -> Self {
+        if audio.sample_rate() != self.config.sample_rate
+            || audio.channels() != self.config.channels
+        {
+            warn!(
+                "Ignoring {}Hz/{}ch standby audio for a {}Hz/{}ch session",
+                audio.sample_rate(),
+                audio.channels(),
+                self.config.sample_rate,
+                self.config.channels
+            );
+            return self;
+        }
+
+        let duration = audio.duration();
+        info!(
+            "Session {} starts with {:.1}s of standby audio",
+            self.config.session_id,
+            duration.as_secs_f64()
+        );
+        self.started_at -= chrono::Duration::milliseconds(duration.as_millis() as i64);
+        self.backfilled = duration;
+        *self.backfill.lock().unwrap() = Some(audio.last(duration));
+        self
+    }
+
+    /// Audio from before the start the session began with
+    pub fn backfilled(&self) -> Duration {
+        self.backfilled
+    }
+
+    /// Whether chunks keep the capture's channels rather than the mix
+    fn stereo_chunks(&self) -> bool {
+        self.config.stereo_chunks && self.config.channel_map.channels() > 1
+    }
+
     /// Start recording
+    #[tracing::instrument(skip_all, fields(session = %self.config.session_id))]
     pub async fn start(&self) -> Result<()> {
         if self.is_recording.load(Ordering::SeqCst) {
             warn!("Recording already started");
@@ -74,303 +372,2257 @@ impl RecordingSession {
         }
 
         info!("Starting recording session: {}", self.config.session_id);
+        if self.config.privacy {
+            info!("Privacy mode: audio is transcribed but not stored");
+        }
+        if !self.config.transcription {
+            info!("Record-only mode: audio is stored but not transcribed");
+        }
+        if !self.config.store_audio && !self.config.privacy {
+            info!("Transcribe-only mode: audio is not stored");
+        }
+        if self.config.encryption_key.is_some() {
+            info!("The stored meeting will be encrypted to the client's key");
+        }
+
+        // Transcript-only sessions record no chunks
+        let store_audio = self.config.store_audio && !self.config.privacy;
+        if let (true, Some(audio_dir)) = (store_audio, &self.config.audio_dir) {
+            let channel_labels = if self.stereo_chunks() {
+                self.config.channel_map.labels()
+            } else {
+                Vec::new()
+            };
+            let chunks = ChunkConfig {
+                chunk_duration_secs: self.config.chunk_duration.as_secs().max(1),
+                channel_labels,
+                title: self.config.title.clone(),
+                started_at: Some(self.started_at),
+                rotation: self.config.chunk_rotation.clone(),
+                overlap_secs: self.config.chunk_overlap.as_secs(),
+                ..ChunkConfig::new(self.config.session_id.clone(), audio_dir.clone())
+            };
+            // Another instance writing the same meeting is refused outright,
+            // rather than recording without audio
+            let recorder = MeetingDirLock::acquire(audio_dir, &self.config.session_id)
+                .map(|lock| lock.with_title(self.config.title.clone()))
+                .and_then(|lock| Ok(ChunkedRecorder::new(chunks)?.with_lock(lock)));
+            match recorder {
+                Ok(recorder) => *self.recorder.lock().unwrap() = Some(recorder),
+                Err(e) if e.is::<DirInUse>() => {
+                    self.roll_back_start().await;
+                    return Err(e);
+                }
+                Err(e) => warn!("Audio will not be stored: {:#}", e),
+            }
+        }
+
+        // A stop before this start no longer says when the session ended
+        *self.stopped_at.lock().unwrap() = None;
+        *self.final_stats.lock().unwrap() = None;
 
         // Mark as recording
         self.is_recording.store(true, Ordering::SeqCst);
+        self.state.send_replace(SessionState::Recording);
+
+        // Start capture and subscribe to transcripts up front, so setup
+        // failures are returned rather than handled as task failures
+        let retry = self.config.start_retry;
+        let mut attempt = 0;
+        let (shutdown, audio, capture, transcripts) = loop {
+            let shutdown = CancellationToken::new();
+            *self.shutdown.lock().unwrap() = shutdown.clone();
+
+            match self.start_attempt(&shutdown).await {
+                Ok((audio, capture, transcripts)) => break (shutdown, audio, capture, transcripts),
+                Err(e) => {
+                    self.roll_back_attempt(&shutdown).await;
+                    if attempt >= retry.max_retries {
+                        self.roll_back_start().await;
+                        return Err(if attempt == 0 {
+                            e
+                        } else {
+                            e.context(format!(
+                                "Session did not start after {} attempts",
+                                attempt + 1
+                            ))
+                        });
+                    }
 
-        // Create audio backend
-        let backend_config = AudioBackendConfig {
-            target_sample_rate: self.config.sample_rate,
-            target_channels: self.config.channels,
-            buffer_duration_ms: 100, // 100ms latency
+                    let delay = retry.delay(attempt);
+                    attempt += 1;
+                    warn!(
+                        "Session {} failed to start: {:#}; retrying in {:?} ({}/{})",
+                        self.config.session_id, e, delay, attempt, retry.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
         };
 
-        let mut audio_backend = AudioBackendFactory::create(AudioSource::System, backend_config)
-            .context("Failed to create audio backend")?;
+        if let Some(host) = self.host.lock().unwrap().as_mut() {
+            host.capture_backend = Some(capture.backends[0].1.name().to_string());
+        }
 
-        // Start capturing audio
-        let mut audio_rx = audio_backend
-            .start()
-            .await
-            .context("Failed to start audio capture")?;
+        // Supervise the audio and transcript tasks, restarting them on failure
+        let mut supervisor = Supervisor::new(
+            self.config.restart_policy,
+            shutdown.clone(),
+            self.state.clone(),
+            self.events.clone(),
+        );
+        let restart_audio = audio.clone();
+        supervisor.spawn(
+            SessionTask::Audio,
+            audio.run(capture),
+            Arc::new(move || {
+                let audio = restart_audio.clone();
+                Box::pin(async move {
+                    let capture = audio.start_capture().await?;
+                    audio.run(capture).await
+                })
+            }),
+        );
+        if let Some((transcripts, transcript_sub)) = transcripts {
+            let restart_transcripts = transcripts.clone();
+            supervisor.spawn(
+                SessionTask::Transcript,
+                transcripts.run(transcript_sub),
+                Arc::new(move || {
+                    let transcripts = restart_transcripts.clone();
+                    Box::pin(async move {
+                        let transcript_sub = transcripts.subscribe().await?;
+                        transcripts.run(transcript_sub).await
+                    })
+                }),
+            );
+        }
 
-        // Spawn audio processing task
-        let nats_client = Arc::clone(&self.nats_client);
-        let is_recording = Arc::clone(&self.is_recording);
-        let frame_sequence = Arc::clone(&self.frame_sequence);
-        let chunks_recorded = Arc::clone(&self.chunks_recorded);
-        let sample_rate = self.config.sample_rate;
-        let channels = self.config.channels;
+        {
+            let mut handle = self.supervisor_handle.lock().await;
+            *handle = Some(tokio::spawn(supervisor.run()));
+        }
 
-        let audio_task = tokio::spawn(async move {
-            info!("Audio processing task started");
+        // Announce a failed session as soon as the supervisor gives up on it
+        if let Some(nats_client) = self.nats_client.clone() {
+            let shutdown = shutdown.clone();
+            let mut state_rx = self.state.subscribe();
+
+            tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        _ = shutdown.cancelled() => break,
+                        changed = state_rx.changed() => {
+                            if changed.is_err() {
+                                break;
+                            }
+                        }
+                    }
 
-            while let Some(frame) = audio_rx.recv().await {
-                if !is_recording.load(Ordering::SeqCst) {
-                    break;
+                    let state = state_rx.borrow_and_update().clone();
+                    if let SessionState::Failed { task, error } = state {
+                        Self::publish_status(&nats_client, SessionStatus::Failed { task, error })
+                            .await;
+                    }
                 }
+            });
+        }
 
-                // Process frame: downsample and convert to mono if needed
-                let processed_frame = Self::process_frame(frame, sample_rate, channels);
-
-                // Convert to PCM bytes
-                let pcm_bytes: Vec<u8> = processed_frame
-                    .samples
-                    .iter()
-                    .flat_map(|s| s.to_le_bytes())
-                    .collect();
-
-                // Get sequence number
-                let seq = frame_sequence.fetch_add(1, Ordering::SeqCst);
+        // Spawn app activity sampling task
+        if !self.config.app_activity_interval.is_zero() && screencapture::is_available() {
+            let nats_client = self.nats_client.clone();
+            let shutdown = shutdown.clone();
+            let system_level = Arc::clone(&self.system_level);
+            let app_activity = Arc::clone(&self.app_activity);
+            let report_interval = self.config.app_activity_interval;
+
+            let app_task = tokio::spawn(async move {
+                info!("App activity task started");
+
+                let mut tracker = AppActivityTracker::new();
+                let mut ticker = tokio::time::interval_at(
+                    tokio::time::Instant::now() + APP_SAMPLE_INTERVAL,
+                    APP_SAMPLE_INTERVAL,
+                );
+                let mut since_report = Duration::ZERO;
+
+                loop {
+                    tokio::select! {
+                        _ = shutdown.cancelled() => break,
+                        _ = ticker.tick() => {}
+                    }
 
-                // Publish to NATS
-                if let Err(e) = nats_client
-                    .publish_audio_frame(&pcm_bytes, sample_rate, channels, seq as u32, false)
-                    .await
-                {
-                    error!("Failed to publish audio frame: {}", e);
+                    let apps = screencapture::active_audio_applications().unwrap_or_else(|e| {
+                        warn!("Failed to list audio applications: {}", e);
+                        Vec::new()
+                    });
+                    let level = f32::from_bits(system_level.load(Ordering::Relaxed));
+                    tracker.record(&apps, level, APP_SAMPLE_INTERVAL);
+
+                    since_report += APP_SAMPLE_INTERVAL;
+                    if since_report >= report_interval {
+                        since_report = Duration::ZERO;
+                        Self::report_app_activity(
+                            nats_client.as_deref(),
+                            &app_activity,
+                            &mut tracker,
+                        )
+                        .await;
+                    }
                 }
 
-                // Update chunks count every 100 frames (~10 seconds at 10 frames/sec)
-                if seq % 100 == 0 {
-                    chunks_recorded.store(seq / 100, Ordering::SeqCst);
+                // Report the final partial window
+                if !tracker.is_empty() {
+                    Self::report_app_activity(nats_client.as_deref(), &app_activity, &mut tracker)
+                        .await;
                 }
-            }
-
-            info!("Audio processing task stopped");
-
-            // Send final frame
-            if let Err(e) = nats_client
-                .publish_audio_frame(
-                    &[],
-                    sample_rate,
-                    channels,
-                    frame_sequence.load(Ordering::SeqCst) as u32,
-                    true,
-                )
-                .await
-            {
-                error!("Failed to send final frame: {}", e);
-            }
 
-            // Stop the backend
-            if let Err(e) = audio_backend.stop().await {
-                error!("Failed to stop audio backend: {}", e);
-            }
-        });
+                info!("App activity task stopped");
+            });
 
-        {
-            let mut handle = self.audio_task_handle.lock().await;
-            *handle = Some(audio_task);
+            let mut handle = self.app_activity_task_handle.lock().await;
+            *handle = Some(app_task);
         }
 
-        // Subscribe to transcripts
-        let mut transcript_sub = self
-            .nats_client
-            .subscribe_transcripts()
-            .await
-            .context("Failed to subscribe to transcripts")?;
-
-        // Spawn transcript receiving task
-        let transcript_segments = Arc::clone(&self.transcript_segments);
-        let session_id = self.config.session_id.clone();
-        let is_recording = Arc::clone(&self.is_recording);
+        if let Some(nats_client) = &self.nats_client {
+            Self::publish_status(nats_client, SessionStatus::Started { host: self.host() }).await;
+        }
 
-        let transcript_task = tokio::spawn(async move {
-            info!("Transcript receiving task started");
+        info!("Recording session started successfully");
 
-            while let Some(msg) = transcript_sub.next().await {
-                if !is_recording.load(Ordering::SeqCst) {
-                    break;
-                }
+        Ok(())
+    }
 
-                // Parse transcript message
-                match serde_json::from_slice::<TranscriptMessage>(&msg.payload) {
-                    Ok(transcript) => {
-                        // Filter by session_id
-                        if transcript.session_id != session_id {
-                            continue;
-                        }
+    /// One attempt at the handshake, capture, and transcript subscription
+    /// (record-only sessions just start capture)
+    ///
+    /// With a warm-up configured, capture only counts as started once it
+    /// delivers a frame. Capture already running when a later step fails is
+    /// stopped before the error is returned.
+    async fn start_attempt(
+        &self,
+        shutdown: &CancellationToken,
+    ) -> Result<(
+        AudioPipeline,
+        Capture,
+        Option<(TranscriptCollector, TranscriptSubscription)>,
+    )> {
+        let protocol = match &self.stt {
+            Some(stt) => stt.handshake(&self.handshake_offer()).await?,
+            None => Protocol::legacy(),
+        };
+        let audio = self.audio_pipeline(shutdown, protocol);
+        let Some(transcripts) = self.transcript_collector(shutdown) else {
+            let capture = self.start_verified_capture(&audio).await?;
+            return Ok((audio, capture, None));
+        };
+        let capture = self.start_verified_capture(&audio).await?;
 
-                        // Create segment
-                        let segment = TranscriptSegment {
-                            text: transcript.text.clone(),
-                            timestamp: Utc::now(),
-                            confidence: transcript.confidence,
-                            partial: transcript.partial,
-                        };
+        match transcripts.subscribe().await {
+            Ok(transcript_sub) => Ok((audio, capture, Some((transcripts, transcript_sub)))),
+            Err(e) => {
+                AudioPipeline::stop_backends(capture.backends).await;
+                Err(e)
+            }
+        }
+    }
 
-                        // Store segment
-                        {
-                            let mut segments = transcript_segments.lock().await;
-                            segments.push(segment);
-                        }
+    /// Start capture, waiting out the warm-up if one is configured
+    async fn start_verified_capture(&self, audio: &AudioPipeline) -> Result<Capture> {
+        let mut capture = audio.start_capture().await?;
 
-                        // Log to console
-                        if transcript.partial {
-                            print!("\r{}", transcript.text);
-                            std::io::Write::flush(&mut std::io::stdout()).ok();
-                        } else {
-                            println!("\n{}", transcript.text);
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to parse transcript message: {}", e);
-                    }
+        if !self.config.warmup.is_zero() {
+            match audio.warm_up(&mut capture).await {
+                Ok(latency) => *self.first_frame_latency.lock().unwrap() = Some(latency),
+                Err(e) => {
+                    AudioPipeline::stop_backends(capture.backends).await;
+                    return Err(e);
                 }
             }
+        }
 
-            info!("Transcript receiving task stopped");
-        });
+        Ok(capture)
+    }
 
+    /// Undo a failed start attempt: end its tasks and remove the durable
+    /// transcript consumer it may have created
+    async fn roll_back_attempt(&self, shutdown: &CancellationToken) {
+        shutdown.cancel();
+
+        if let Some(device_task) = self.device_task_handle.lock().await.take() {
+            device_task.abort();
+        }
+
+        if let (true, Some(nats_client)) =
+            (self.config.transcript_stream.durable, &self.nats_client)
         {
-            let mut handle = self.transcript_task_handle.lock().await;
-            *handle = Some(transcript_task);
+            if let Err(e) = nats_client
+                .delete_transcript_consumer(&self.config.transcript_stream)
+                .await
+            {
+                debug!("{:#}", e);
+            }
         }
+    }
 
-        info!("Recording session started successfully");
+    /// Leave a session whose start failed as it was before start: not
+    /// recording, no chunk recorder, and nothing left on disk or queued on
+    /// the NATS connection
+    async fn roll_back_start(&self) {
+        self.is_recording.store(false, Ordering::SeqCst);
+        self.state.send_replace(SessionState::Stopped);
+
+        // Nothing has been written yet, so the meeting's directory is empty
+        // unless it held audio before this session
+        let recorder = self.recorder.lock().unwrap().take();
+        if let Some(mut recorder) = recorder {
+            if let Some(lock) = recorder.take_lock() {
+                lock.abandon();
+            }
+            if let Some(audio_dir) = &self.config.audio_dir {
+                let _ = std::fs::remove_dir(audio_dir);
+            }
+        }
 
-        Ok(())
+        if let Some(stt) = &self.stt {
+            if let Err(e) = stt.close().await {
+                warn!("{:#}", e);
+            }
+        }
     }
 
     /// Stop recording
+    ///
+    /// Stopping again returns the stats as of the first stop.
+    #[tracing::instrument(skip_all, fields(session = %self.config.session_id))]
     pub async fn stop(&self) -> Result<SessionStats> {
+        if let Some(stats) = self.final_stats.lock().unwrap().clone() {
+            return Ok(stats);
+        }
+        let stopped_at = self.config.clock.now();
         if !self.is_recording.load(Ordering::SeqCst) {
             warn!("Recording not active");
-            return self.get_stats().await;
+            return self.finish_stop(stopped_at).await;
         }
 
         info!("Stopping recording session: {}", self.config.session_id);
 
-        // Mark as stopped (this will signal tasks to finish)
+        // Mark as stopped and signal tasks to finish
         self.is_recording.store(false, Ordering::SeqCst);
+        self.shutdown.lock().unwrap().cancel();
+
+        // Wait for the supervised audio and transcript tasks to finish
+        let timeout = self.config.stop_timeout;
+        let supervisor = self.supervisor_handle.lock().await.take();
+        join_or_abort("Session supervisor", supervisor, timeout).await;
+
+        // Wait for device monitoring task to finish (ends when the backend stops)
+        let device_task = self.device_task_handle.lock().await.take();
+        join_or_abort("Device monitoring task", device_task, timeout).await;
+
+        // Wait for app activity task to finish
+        let app_task = self.app_activity_task_handle.lock().await.take();
+        join_or_abort("App activity task", app_task, timeout).await;
+
+        // Sources still muted are muted until the end
+        let spans = self.mutes.lock().unwrap().finish(self.elapsed_ms());
+        if !spans.is_empty() {
+            let mut markers = self.markers.lock().await;
+            markers.extend(spans.iter().map(|span| span.marker(self.started_at)));
+            markers.sort_by_key(|marker| marker.offset_ms);
+        }
 
-        // Wait for audio task to finish
-        {
-            let mut handle = self.audio_task_handle.lock().await;
-            if let Some(task) = handle.take() {
-                if let Err(e) = task.await {
-                    error!("Audio task panicked: {}", e);
-                }
+        // Close the chunk still being written
+        if let Some(recorder) = self.recorder.lock().unwrap().as_mut() {
+            if let Err(e) = recorder.finish() {
+                error!("Failed to finish audio chunk: {:#}", e);
             }
+            recorder.release_lock();
         }
 
-        // Wait for transcript task to finish
+        // Everything the durable consumer delivered is collected now
+        if let (true, Some(nats_client)) =
+            (self.config.transcript_stream.durable, &self.nats_client)
         {
-            let mut handle = self.transcript_task_handle.lock().await;
-            if let Some(task) = handle.take() {
-                if let Err(e) = task.await {
-                    error!("Transcript task panicked: {}", e);
-                }
+            if let Err(e) = nats_client
+                .delete_transcript_consumer(&self.config.transcript_stream)
+                .await
+            {
+                warn!("{:#}", e);
             }
         }
 
+        // A failed session keeps its failure as the final state, but its
+        // watchers are still told it stopped
+        self.state.send_modify(|state| {
+            if *state == SessionState::Recording {
+                *state = SessionState::Stopped;
+            }
+        });
+
         info!("Recording session stopped successfully");
 
         // Return final stats
-        self.get_stats().await
+        let stats = self.finish_stop(stopped_at).await?;
+        if let Some(nats_client) = &self.nats_client {
+            Self::publish_status(
+                nats_client,
+                SessionStatus::Stopped {
+                    duration_secs: stats.duration_secs,
+                },
+            )
+            .await;
+        }
+        Ok(stats)
+    }
+
+    /// Keep the stop instant, and the stats as of it for later stops
+    async fn finish_stop(&self, stopped_at: chrono::DateTime<chrono::Utc>) -> Result<SessionStats> {
+        *self.stopped_at.lock().unwrap() = Some(stopped_at);
+        let stats = self.get_stats().await?;
+        *self.final_stats.lock().unwrap() = Some(stats.clone());
+        Ok(stats)
+    }
+
+    /// When the session was stopped (None while it hasn't been)
+    pub fn stopped_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        *self.stopped_at.lock().unwrap()
+    }
+
+    /// Stop recording if the session still is, then flush and close its
+    /// STT connection
+    ///
+    /// The transcript and stats stay readable afterwards, for saving the
+    /// meeting.
+    pub async fn close(&self) -> Result<()> {
+        if self.is_recording.load(Ordering::SeqCst) {
+            self.stop().await?;
+        }
+        if let Some(stt) = &self.stt {
+            stt.close().await?;
+        }
+        Ok(())
     }
 
     /// Get current session statistics
     pub async fn get_stats(&self) -> Result<SessionStats> {
-        let duration = Utc::now().signed_duration_since(self.started_at);
+        let duration = self
+            .stopped_at()
+            .unwrap_or_else(|| self.config.clock.now())
+            .signed_duration_since(self.started_at);
 
         let transcript_count = {
             let segments = self.transcript_segments.lock().await;
             segments.len()
         };
 
+        let chunks = self
+            .recorder
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(ChunkedRecorder::progress)
+            .unwrap_or_default();
+
         Ok(SessionStats {
             is_recording: self.is_recording.load(Ordering::SeqCst),
+            state: self.state(),
             started_at: self.started_at,
             duration_secs: duration.num_milliseconds() as f64 / 1000.0,
-            chunks_count: self.chunks_recorded.load(Ordering::SeqCst),
+            chunks_count: chunks.chunks_count,
+            audio_bytes: chunks.audio_bytes,
+            current_chunk_percent: chunks.current_chunk_percent,
             transcript_segments_count: transcript_count,
+            frame_drops: *self.frame_drops.lock().await,
+            warnings: self.warnings.lock().await.clone(),
+            audio_retention: self.audio_retention(),
+        })
+    }
+
+    /// Audio chunk files finished so far
+    pub fn chunks(&self) -> Vec<ChunkMetadata> {
+        self.recorder
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|recorder| recorder.chunks().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Check what audio the session has stored
+    fn audio_retention(&self) -> AudioRetention {
+        let privacy = self.config.privacy;
+        if !privacy && !self.config.store_audio {
+            return AudioRetention {
+                transcribe_only: true,
+                ..Default::default()
+            };
+        }
+        let Some(audio_dir) = &self.config.audio_dir else {
+            return AudioRetention {
+                privacy,
+                ..Default::default()
+            };
+        };
+
+        AudioRetention::check(privacy, audio_dir, self.config.clock.now()).unwrap_or_else(|e| {
+            warn!(
+                "Failed to check {} for stored audio: {}",
+                audio_dir.display(),
+                e
+            );
+            AudioRetention {
+                privacy,
+                ..Default::default()
+            }
         })
     }
 
+    /// Current lifecycle state
+    pub fn state(&self) -> SessionState {
+        self.state.borrow().clone()
+    }
+
+    /// Watch the lifecycle state for changes
+    pub fn watch_state(&self) -> watch::Receiver<SessionState> {
+        self.state.subscribe()
+    }
+
+    /// Machine recording the session, if reported
+    pub fn host(&self) -> Option<HostInfo> {
+        self.host.lock().unwrap().clone()
+    }
+
+    /// Session (meeting) identifier
+    pub fn session_id(&self) -> &str {
+        &self.config.session_id
+    }
+
+    /// Configuration the session was started with, without credentials
+    pub fn effective_config(&self) -> SessionConfig {
+        self.config.redacted()
+    }
+
+    /// Meeting title, if one was given
+    pub fn title(&self) -> Option<&str> {
+        self.config.title.as_deref()
+    }
+
+    /// Session profile the meeting was started with
+    pub fn profile(&self) -> Option<&str> {
+        self.config.profile.as_deref()
+    }
+
+    /// Clock the session takes its timestamps from
+    pub fn clock(&self) -> &SharedClock {
+        &self.config.clock
+    }
+
+    /// How long the stored meeting is kept after recording stops
+    pub fn ttl(&self) -> Option<Duration> {
+        self.config.ttl
+    }
+
+    /// Public key the stored meeting is encrypted to, if any
+    pub fn encryption_key(&self) -> Option<&str> {
+        self.config.encryption_key.as_deref()
+    }
+
+    /// How long the first audio frame took to arrive when start waited for
+    /// one (None = started without a warm-up)
+    pub fn first_frame_latency(&self) -> Option<Duration> {
+        *self.first_frame_latency.lock().unwrap()
+    }
+
+    /// Cloud STT usage against the budget (None unless the session is
+    /// transcribed by a budgeted cloud provider)
+    pub fn stt_budget(&self) -> Option<BudgetStatus> {
+        self.stt_budget.as_ref().map(|budget| budget.status())
+    }
+
+    /// Time spent in each stage of the audio-to-transcript pipeline
+    pub fn pipeline_latency(&self) -> BTreeMap<PipelineStage, StageLatency> {
+        self.latency.lock().unwrap().summary()
+    }
+
+    /// How often the meeting's draft note is refreshed (zero = no live draft)
+    pub fn live_draft_interval(&self) -> Duration {
+        self.config.live_draft_interval
+    }
+
     /// Get accumulated transcript
     pub async fn get_transcript(&self) -> Vec<TranscriptSegment> {
         let segments = self.transcript_segments.lock().await;
-        segments.clone()
+        segments.segments()
+    }
+
+    /// Transcript segments added or changed since `cursor`, with the cursor
+    /// to pass next time
+    ///
+    /// Start from 0 to get the whole transcript. Unlike
+    /// [`get_transcript`](Self::get_transcript), only what changed is cloned,
+    /// so polling stays cheap however long the meeting runs.
+    pub async fn get_transcript_since(&self, cursor: u64) -> TranscriptUpdate {
+        let segments = self.transcript_segments.lock().await;
+        segments.changes_since(cursor)
+    }
+
+    /// One page of the accumulated transcript
+    pub async fn transcript_page(&self, query: &TranscriptQuery, limit: usize) -> TranscriptPage {
+        let segments = self.transcript_segments.lock().await;
+        segments.page(self.started_at, query, limit)
     }
 
-    /// Process audio frame: downsample and convert to target format
-    fn process_frame(
-        frame: AudioFrame,
-        target_sample_rate: u32,
-        target_channels: u16,
-    ) -> AudioFrame {
-        let mut processed = frame;
+    /// What to offer the STT service in the session handshake
+    fn handshake_offer(&self) -> HandshakeRequest {
+        Protocol::offer(
+            &self.config.session_id,
+            self.config.audio_codec,
+            self.config.batch_frames,
+            self.config.sample_rate,
+            self.config.channels,
+        )
+    }
 
-        // Downsample if needed
-        if processed.sample_rate != target_sample_rate {
-            processed = Self::downsample_frame(processed, target_sample_rate);
+    fn audio_pipeline(&self, shutdown: &CancellationToken, protocol: Protocol) -> AudioPipeline {
+        AudioPipeline {
+            config: self.config.clone(),
+            protocol,
+            stt: self.stt.clone(),
+            shutdown: shutdown.clone(),
+            frame_sequence: Arc::clone(&self.frame_sequence),
+            mic_sequence: Arc::clone(&self.mic_sequence),
+            recorder: Arc::clone(&self.recorder),
+            system_level: Arc::clone(&self.system_level),
+            frame_drops: Arc::clone(&self.frame_drops),
+            warnings: Arc::clone(&self.warnings),
+            markers: Arc::clone(&self.markers),
+            events: self.events.clone(),
+            frames: self.frames.clone(),
+            speech_ms: self
+                .transcript_filter
+                .as_ref()
+                .filter(|filter| filter.checks_speech())
+                .map(|_| Arc::clone(&self.speech_ms)),
+            device_task_handle: Arc::clone(&self.device_task_handle),
+            latency: Arc::clone(&self.latency),
+            mutes: Arc::clone(&self.mutes),
+            stt_budget: self.stt_budget.clone(),
+            transcribing: Arc::clone(&self.transcribing),
+            preroll: self.preroll.clone(),
+            backfill: Arc::clone(&self.backfill),
+            backfilled: self.backfilled,
+            stereo_chunks: self.stereo_chunks(),
+            companion_rx: Arc::clone(&self.companion_rx),
+            companions: Arc::clone(&self.companions),
         }
+    }
 
-        // Convert to mono if needed
-        if processed.channels != target_channels && target_channels == 1 {
-            processed = Self::stereo_to_mono(processed);
+    /// Transcript task state (None for record-only sessions)
+    fn transcript_collector(&self, shutdown: &CancellationToken) -> Option<TranscriptCollector> {
+        Some(TranscriptCollector {
+            session_id: self.config.session_id.clone(),
+            stt: Arc::clone(self.stt.as_ref()?),
+            shutdown: shutdown.clone(),
+            transcript_segments: Arc::clone(&self.transcript_segments),
+            transcripts: self.transcripts.clone(),
+            filter: self.transcript_filter.clone(),
+            speech_ms: Arc::clone(&self.speech_ms),
+            segment_ledger: Arc::clone(&self.segment_ledger),
+            stream_config: self.config.transcript_stream.clone(),
+            latency: Arc::clone(&self.latency),
+            processors: Arc::clone(&self.processors),
+            clock: Arc::clone(&self.config.clock),
+        })
+    }
+
+    /// Merge backend receivers into one stream, tagging frames with their source
+    ///
+    /// A single receiver is used as it is, with no merged stream to add a
+    /// restarted source to.
+    fn merge_sources(
+        mut receivers: Vec<(AudioStreamSource, mpsc::Receiver<AudioFrame>)>,
+        capacity: usize,
+    ) -> (mpsc::Receiver<AudioFrame>, Option<MergedCapture>) {
+        if receivers.len() == 1 {
+            return (receivers.remove(0).1, None);
         }
+        let (rx, merged) = MergedCapture::new(receivers, capacity);
+        (rx, Some(merged))
+    }
 
-        processed
+    /// Sources captured in this session: system audio, the system
+    /// capture's microphone channel, each additional microphone, and each
+    /// connected companion device
+    pub fn sources(&self) -> Vec<AudioStreamSource> {
+        let mut sources = StereoSplitter::new(self.config.channel_map.clone()).sources();
+        sources.extend(
+            (0..self.config.microphones.len())
+                .map(|index| AudioStreamSource::MicrophoneDevice(index as u8)),
+        );
+        let mut companions: Vec<_> = self.companions.lock().unwrap().keys().copied().collect();
+        companions.sort_by_key(ToString::to_string);
+        sources.extend(companions);
+        sources
     }
 
-    /// Downsample audio frame by decimation
-    fn downsample_frame(frame: AudioFrame, target_rate: u32) -> AudioFrame {
-        if frame.sample_rate == target_rate {
-            return frame;
+    /// Connect a companion device (e.g. a phone's browser) as an additional
+    /// microphone, mixed in while the returned input is kept
+    ///
+    /// Fails if the session isn't recording, the format isn't supported, or
+    /// the session already has `MAX_COMPANIONS` connected.
+    pub fn join_companion(&self, format: &CompanionFormat) -> Result<CompanionInput> {
+        if !self.is_recording.load(Ordering::SeqCst) {
+            anyhow::bail!("Session {} is not recording", self.config.session_id);
         }
+        let input = CompanionInput::join(
+            format,
+            self.config.microphones.len(),
+            self.companion_tx.clone(),
+            Arc::clone(&self.companions),
+            Arc::clone(&self.is_recording),
+        )?;
+        info!(
+            "Companion device joined session {} as {}",
+            self.config.session_id,
+            input.source()
+        );
+        Ok(input)
+    }
 
-        let ratio = frame.sample_rate / target_rate;
-        if ratio <= 1 {
-            return frame; // Can't upsample
+    /// Token a companion device presents to join this session, issued on
+    /// the first call and the same for the rest of the session
+    ///
+    /// Fails if the session isn't recording.
+    pub fn pair_companion(&self) -> Result<String> {
+        if !self.is_recording.load(Ordering::SeqCst) {
+            anyhow::bail!("Session {} is not recording", self.config.session_id);
         }
+        Ok(self.companion_pairing.token())
+    }
 
-        // Decimate: take every Nth sample
-        let downsampled: Vec<i16> = frame
-            .samples
-            .iter()
-            .step_by(ratio as usize)
-            .copied()
-            .collect();
+    /// Whether `token` is this session's companion pairing token
+    pub fn is_paired_companion(&self, token: &str) -> bool {
+        self.companion_pairing.accepts(token)
+    }
 
-        AudioFrame {
-            samples: downsampled,
-            sample_rate: target_rate,
-            channels: frame.channels,
-            timestamp_ms: frame.timestamp_ms,
-            source: frame.source,
+    /// Sources currently muted
+    pub fn muted_sources(&self) -> Vec<AudioStreamSource> {
+        self.mutes.lock().unwrap().muted()
+    }
+
+    /// Replace a source's audio with silence until it is unmuted
+    ///
+    /// Returns false if it was already muted. Fails for sources this
+    /// session does not capture.
+    pub async fn mute_source(&self, source: AudioStreamSource) -> Result<bool> {
+        self.check_source(source)?;
+        let muted = self.mutes.lock().unwrap().mute(source, self.elapsed_ms());
+        if muted {
+            info!("Muted {} in session {}", source, self.config.session_id);
+            self.send_mute_event(source, true);
         }
+        Ok(muted)
     }
 
-    /// Convert stereo to mono by summing channels
-    fn stereo_to_mono(frame: AudioFrame) -> AudioFrame {
-        if frame.channels == 1 {
-            return frame;
+    /// Let a muted source's audio through again, marking the muted span
+    ///
+    /// Returns false if it wasn't muted.
+    pub async fn unmute_source(&self, source: AudioStreamSource) -> Result<bool> {
+        self.check_source(source)?;
+        let span = self.mutes.lock().unwrap().unmute(source, self.elapsed_ms());
+        let Some(span) = span else {
+            return Ok(false);
+        };
+
+        info!("Unmuted {} in session {}", source, self.config.session_id);
+        {
+            // Marked where the mute began, among the markers since then
+            let mut markers = self.markers.lock().await;
+            markers.push(span.marker(self.started_at));
+            markers.sort_by_key(|marker| marker.offset_ms);
         }
+        self.send_mute_event(source, false);
+        Ok(true)
+    }
 
-        if frame.channels != 2 {
-            return frame; // Only support stereo -> mono
+    fn check_source(&self, source: AudioStreamSource) -> Result<()> {
+        if !self.sources().contains(&source) {
+            anyhow::bail!(
+                "Session {} does not capture {} (sources: {})",
+                self.config.session_id,
+                source,
+                self.sources()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
         }
+        Ok(())
+    }
+
+    fn send_mute_event(&self, source: AudioStreamSource, muted: bool) {
+        // No subscribers is not an error
+        let _ = self.events.send(SessionEvent::SourceMuted {
+            source: source.to_string(),
+            muted,
+            timestamp: self.config.clock.now(),
+        });
+    }
 
-        let mut mono_samples = Vec::with_capacity(frame.samples.len() / 2);
+    /// Milliseconds since the session started
+    fn elapsed_ms(&self) -> u64 {
+        self.config
+            .clock
+            .now()
+            .signed_duration_since(self.started_at)
+            .num_milliseconds()
+            .max(0) as u64
+    }
+
+    /// Bookmark the meeting, saving up to `preroll` of the audio just
+    /// before it (capped at the session's pre-roll) as a highlight clip
+    ///
+    /// The bookmark marker sits where the clip starts, so it lands on what
+    /// was being said rather than when the bookmark was asked for.
+    pub async fn bookmark(
+        &self,
+        preroll: Option<Duration>,
+        label: Option<String>,
+    ) -> Result<Bookmark> {
+        if !self.is_recording.load(Ordering::SeqCst) {
+            anyhow::bail!("Session {} is not recording", self.config.session_id);
+        }
 
-        // Sum left and right channels (no division to preserve volume)
-        for chunk in frame.samples.chunks_exact(2) {
-            let left = chunk[0] as i32;
-            let right = chunk[1] as i32;
-            let sum = left + right;
-            let mono = sum.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
-            mono_samples.push(mono);
+        let now_ms = self.elapsed_ms();
+        let wanted = preroll
+            .unwrap_or(self.config.preroll)
+            .min(self.config.preroll);
+        let audio = self.preroll.as_ref().map(|buffer| {
+            let buffer = buffer.lock().unwrap();
+            (buffer.last(wanted), buffer.sample_rate(), buffer.channels())
+        });
+        let clip_ms = audio
+            .as_ref()
+            .map_or(0, |(samples, sample_rate, channels)| {
+                samples.len() as u64 * 1000 / (*sample_rate as u64 * *channels as u64).max(1)
+            });
+        let offset_ms = now_ms.saturating_sub(clip_ms);
+
+        let mut clip = None;
+        if let (Some((samples, sample_rate, channels)), Some(dir)) = (audio, &self.config.audio_dir)
+        {
+            if !samples.is_empty() {
+                let path = dir
+                    .join("highlights")
+                    .join(format!("bookmark-{}.wav", offset_ms));
+                let target = path.clone();
+                tokio::task::spawn_blocking(move || {
+                    crate::audio::write_clip(&target, &samples, sample_rate, channels)
+                })
+                .await??;
+                clip = Some(path);
+            }
         }
 
-        AudioFrame {
-            samples: mono_samples,
-            sample_rate: frame.sample_rate,
-            channels: 1,
-            timestamp_ms: frame.timestamp_ms,
-            source: frame.source,
+        // Without a clip the bookmark marks the moment it was asked for
+        let (offset_ms, clip_ms) = match clip {
+            Some(_) => (offset_ms, clip_ms),
+            None => (now_ms, 0),
+        };
+
+        let marker = Marker {
+            kind: MarkerKind::Bookmark,
+            offset_ms,
+            timestamp: self.started_at + chrono::Duration::milliseconds(offset_ms as i64),
+            label: label.unwrap_or_else(|| "Bookmark".to_string()),
+        };
+        {
+            let mut markers = self.markers.lock().await;
+            markers.push(marker.clone());
+            markers.sort_by_key(|marker| marker.offset_ms);
         }
+        info!(
+            "Bookmarked session {} at {}ms ({}ms of audio saved)",
+            self.config.session_id, offset_ms, clip_ms
+        );
+
+        Ok(Bookmark {
+            marker,
+            clip_ms,
+            clip,
+        })
+    }
+
+    /// Get timeline markers recorded so far
+    pub async fn get_markers(&self) -> Vec<Marker> {
+        self.markers.lock().await.clone()
+    }
+
+    /// Subscribe to live session events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SessionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Subscribe to processed audio frames, as they are recorded and sent
+    /// to STT
+    ///
+    /// Frames are mixed, resampled, and muted as configured; in dual-stream
+    /// sessions system and microphone frames both arrive, told apart by
+    /// their source (otherwise every frame is tagged as system audio).
+    /// Subscribers that fall behind miss frames rather than holding up the
+    /// pipeline.
+    pub fn subscribe_frames(&self) -> broadcast::Receiver<AudioFrame> {
+        self.frames.subscribe()
+    }
+
+    /// Subscribe to transcript segments as they arrive
+    ///
+    /// Partial segments come as they are heard, each superseded by the
+    /// next until the final one. Final segments that are normalized or
+    /// translated come once processed, so they may follow later segments.
+    /// Subscribers that fall behind miss segments; the whole transcript
+    /// stays available from [`get_transcript`](Self::get_transcript).
+    pub fn subscribe_transcripts(&self) -> broadcast::Receiver<TranscriptSegment> {
+        self.transcripts.subscribe()
+    }
+
+    /// Record device changes as gap markers and forward them as live events
+    async fn monitor_devices(
+        mut device_rx: mpsc::UnboundedReceiver<DeviceEvent>,
+        markers: Arc<Mutex<Vec<Marker>>>,
+        events: broadcast::Sender<SessionEvent>,
+        clock: SharedClock,
+    ) {
+        while let Some(event) = device_rx.recv().await {
+            let (label, rebound) = match event.kind {
+                DeviceEventKind::DefaultInputChanged => {
+                    info!("Default input device changed to {}", event.device);
+                    continue;
+                }
+                DeviceEventKind::Rebound => {
+                    info!("Microphone capture rebound to {}", event.device);
+                    (format!("Microphone switched to {}", event.device), true)
+                }
+                DeviceEventKind::RebindFailed => {
+                    warn!("Microphone rebind failed: {}", event.device);
+                    (format!("Microphone lost: {}", event.device), false)
+                }
+            };
+
+            let now = clock.now();
+            markers.lock().await.push(Marker {
+                kind: MarkerKind::Gap,
+                offset_ms: event.timestamp_ms,
+                timestamp: now,
+                label,
+            });
+
+            // No subscribers is not an error
+            let _ = events.send(SessionEvent::DeviceChanged {
+                device: event.device,
+                rebound,
+                timestamp: now,
+            });
+        }
+    }
+
+    /// Raise a high drop rate warning the first time the threshold is exceeded
+    async fn check_drop_rate(
+        drops: &FrameDropStats,
+        max_drop_rate: f64,
+        warnings: &Mutex<Vec<SessionWarning>>,
+        events: &broadcast::Sender<SessionEvent>,
+        clock: &dyn Clock,
+    ) {
+        if drops.frames_received < MIN_FRAMES_FOR_DROP_RATE || drops.drop_rate() <= max_drop_rate {
+            return;
+        }
+
+        let mut warnings = warnings.lock().await;
+        if warnings
+            .iter()
+            .any(|w| matches!(w, SessionWarning::HighDropRate { .. }))
+        {
+            return;
+        }
+
+        warn!(
+            "Dropped {} of {} audio frames ({:.1}%, threshold {:.1}%)",
+            drops.dropped(),
+            drops.frames_received,
+            drops.drop_rate() * 100.0,
+            max_drop_rate * 100.0
+        );
+
+        let warning = SessionWarning::HighDropRate {
+            drop_rate: drops.drop_rate(),
+            threshold: max_drop_rate,
+            raised_at: clock.now(),
+        };
+        warnings.push(warning.clone());
+
+        // No subscribers is not an error
+        let _ = events.send(SessionEvent::Warning { warning });
+    }
+
+    /// Warn that capture has delivered nothing for `silent`
+    async fn report_capture_stall(
+        source: AudioStreamSource,
+        backend: &str,
+        silent: Duration,
+        restarting: bool,
+        warnings: &Mutex<Vec<SessionWarning>>,
+        events: &broadcast::Sender<SessionEvent>,
+        clock: &dyn Clock,
+    ) {
+        warn!(
+            "No audio captured from {} ({}) for {:.1}s{}",
+            source,
+            backend,
+            silent.as_secs_f64(),
+            if restarting { "; restarting it" } else { "" }
+        );
+
+        let warning = SessionWarning::CaptureStalled {
+            source: source.to_string(),
+            silent_secs: silent.as_secs_f64(),
+            restarting,
+            raised_at: clock.now(),
+        };
+        warnings.lock().await.push(warning.clone());
+
+        // No subscribers is not an error
+        let _ = events.send(SessionEvent::Warning { warning });
+    }
+
+    /// Fall back to record-only the first time the STT budget is exhausted
+    async fn check_stt_budget(
+        budget: &SttBudget,
+        transcribing: &AtomicBool,
+        warnings: &Mutex<Vec<SessionWarning>>,
+        events: &broadcast::Sender<SessionEvent>,
+        clock: &dyn Clock,
+    ) {
+        if !transcribing.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(period) = budget.exhausted() else {
+            return;
+        };
+
+        transcribing.store(false, Ordering::Relaxed);
+        warn!(
+            "The {} STT budget is exhausted; recording without transcription",
+            period
+        );
+
+        let warning = SessionWarning::SttBudgetExhausted {
+            period,
+            raised_at: clock.now(),
+        };
+        warnings.lock().await.push(warning.clone());
+
+        // No subscribers is not an error
+        let _ = events.send(SessionEvent::Warning { warning });
+    }
+
+    /// Get the most recent per-application activity summary
+    pub async fn get_app_activity(&self) -> Option<AppActivitySummary> {
+        self.app_activity.lock().await.clone()
+    }
+
+    /// Summarize tracked app activity, store it, and publish it to NATS
+    /// (unless the session is record-only)
+    async fn report_app_activity(
+        nats_client: Option<&NatsClient>,
+        latest: &Mutex<Option<AppActivitySummary>>,
+        tracker: &mut AppActivityTracker,
+    ) {
+        let summary = tracker.take_summary();
+
+        if let Some(app) = summary.dominant() {
+            info!(
+                "Audio mostly from {} ({:.0}% over {:.0}s)",
+                app.name,
+                app.share * 100.0,
+                summary.window_secs
+            );
+        }
+
+        if let Some(nats_client) = nats_client {
+            if let Err(e) = nats_client.publish_app_activity(&summary).await {
+                error!("Failed to publish app activity: {}", e);
+            }
+        }
+
+        *latest.lock().await = Some(summary);
+    }
+
+    /// Publish a lifecycle change for other services; failures are only logged
+    async fn publish_status(nats_client: &NatsClient, status: SessionStatus) {
+        if let Err(e) = nats_client.publish_status(status).await {
+            error!("Failed to publish session status: {}", e);
+        }
+    }
+}
+
+impl Drop for RecordingSession {
+    /// Tear down a session dropped while still recording: its tasks are
+    /// told to stop (the audio task stops capture on its way out) and the
+    /// chunk being written is closed, so nothing keeps running or stays
+    /// open. Nothing is saved; stop or close the session first for that.
+    fn drop(&mut self) {
+        if !self.is_recording.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        warn!(
+            "Session {} dropped while recording; its meeting is not saved",
+            self.config.session_id
+        );
+
+        if let Ok(shutdown) = self.shutdown.get_mut() {
+            shutdown.cancel();
+        }
+        for handle in [&self.device_task_handle, &self.app_activity_task_handle] {
+            if let Some(handle) = handle.try_lock().ok().and_then(|mut handle| handle.take()) {
+                handle.abort();
+            }
+        }
+
+        // Taken, so frames the audio task writes on its way out are dropped
+        let recorder = self
+            .recorder
+            .lock()
+            .ok()
+            .and_then(|mut recorder| recorder.take());
+        if let Some(mut recorder) = recorder {
+            if let Err(e) = recorder.finish() {
+                error!("Failed to finish audio chunk: {:#}", e);
+            }
+        }
+        self.state.send_replace(SessionState::Stopped);
+    }
+}
+
+/// Everything the audio task shares with its session, so the supervisor can
+/// start it again
+#[derive(Clone)]
+struct AudioPipeline {
+    config: SessionConfig,
+    /// Protocol agreed with the STT service
+    protocol: Protocol,
+    /// None in record-only sessions
+    stt: Option<Arc<dyn SttProvider>>,
+    shutdown: CancellationToken,
+    frame_sequence: Arc<AtomicUsize>,
+    mic_sequence: Arc<AtomicUsize>,
+    recorder: Arc<std::sync::Mutex<Option<ChunkedRecorder>>>,
+    system_level: Arc<AtomicU32>,
+    frame_drops: Arc<Mutex<FrameDropStats>>,
+    warnings: Arc<Mutex<Vec<SessionWarning>>>,
+    markers: Arc<Mutex<Vec<Marker>>>,
+    events: broadcast::Sender<SessionEvent>,
+    frames: broadcast::Sender<AudioFrame>,
+    /// Counts speech for the transcript filter (None = not checked)
+    speech_ms: Option<Arc<AtomicU64>>,
+    device_task_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    latency: Arc<std::sync::Mutex<PipelineLatency>>,
+    mutes: Arc<std::sync::Mutex<SourceMutes>>,
+    stt_budget: Option<Arc<SttBudget>>,
+    transcribing: Arc<AtomicBool>,
+    preroll: Option<Arc<std::sync::Mutex<PrerollBuffer>>>,
+    backfill: Arc<std::sync::Mutex<Option<Vec<i16>>>>,
+    /// Duration of the backfill, which captured frames are timed after
+    backfilled: Duration,
+    /// Record system capture frames in their channel layout, not the mix
+    stereo_chunks: bool,
+    companion_rx: Arc<Mutex<mpsc::UnboundedReceiver<CompanionAudio>>>,
+    companions: Companions,
+}
+
+/// Running capture backends and their merged frames
+struct Capture {
+    backends: Vec<(AudioStreamSource, Box<dyn AudioBackend>)>,
+    audio_rx: mpsc::Receiver<AudioFrame>,
+    /// Adds a restarted source to `audio_rx` (None with a single source)
+    merged: Option<MergedCapture>,
+    /// Frame taken off `audio_rx` during warm-up, processed first
+    first_frame: Option<AudioFrame>,
+}
+
+impl AudioPipeline {
+    fn backend_config(&self) -> AudioBackendConfig {
+        AudioBackendConfig {
+            target_sample_rate: self.config.sample_rate,
+            target_channels: self.config.channels,
+            buffer_duration_ms: self.config.quality.frame_duration_ms(),
+            excluded_apps: self.config.excluded_apps.clone(),
+            microphone_device: self.config.microphone_device.clone(),
+            resampler_quality: self
+                .config
+                .resampler_quality
+                .unwrap_or(self.config.quality.resampler_quality()),
+        }
+    }
+
+    /// Create and start the backends: system capture (or the replayed file
+    /// or network source in its place) plus any additional microphones, and
+    /// monitor the system capture's device changes
+    async fn start_capture(&self) -> Result<Capture> {
+        let backend_config = self.backend_config();
+        let primary = match (&self.config.file_source, &self.config.network_source) {
+            (Some(file), _) => AudioSource::File(file.clone()),
+            (None, Some(network)) => AudioSource::Network(network.clone()),
+            (None, None) => AudioSource::System,
+        };
+
+        let mut backends: Vec<(AudioStreamSource, Box<dyn AudioBackend>)> = vec![(
+            AudioStreamSource::System,
+            AudioBackendFactory::create(primary, backend_config.clone())
+                .context("Failed to create audio backend")?,
+        )];
+
+        for (index, microphone) in self.config.microphones.iter().enumerate() {
+            let mic_config = AudioBackendConfig {
+                microphone_device: Some(microphone.device_id.clone()),
+                ..backend_config.clone()
+            };
+            let backend = AudioBackendFactory::create(AudioSource::Microphone, mic_config)
+                .with_context(|| {
+                    format!(
+                        "Failed to create backend for microphone {}",
+                        microphone.device_id
+                    )
+                })?;
+            backends.push((AudioStreamSource::MicrophoneDevice(index as u8), backend));
+        }
+
+        // Start capturing audio
+        let mut receivers = Vec::with_capacity(backends.len());
+        let mut failure = None;
+        for (source, backend) in backends.iter_mut() {
+            match backend.start().await {
+                Ok(rx) => receivers.push((*source, rx)),
+                Err(e) => {
+                    failure =
+                        Some(e.context(format!("Failed to start {} capture", backend.name())));
+                    break;
+                }
+            }
+        }
+
+        // Release the devices already capturing, so a failed start holds none
+        if let Some(e) = failure {
+            backends.truncate(receivers.len());
+            Self::stop_backends(backends).await;
+            return Err(e);
+        }
+        let (audio_rx, merged) =
+            RecordingSession::merge_sources(receivers, self.config.quality.channel_capacity());
+
+        // Spawn device change monitoring task (system capture reports device changes)
+        if let Some(device_rx) = backends[0].1.take_device_events() {
+            let device_task = tokio::spawn(RecordingSession::monitor_devices(
+                device_rx,
+                Arc::clone(&self.markers),
+                self.events.clone(),
+                Arc::clone(&self.config.clock),
+            ));
+
+            let mut handle = self.device_task_handle.lock().await;
+            *handle = Some(device_task);
+        }
+
+        Ok(Capture {
+            backends,
+            audio_rx,
+            merged,
+            first_frame: None,
+        })
+    }
+
+    /// Wait for capture to deliver its first frame, up to the configured
+    /// warm-up
+    ///
+    /// Capture can start cleanly and still never deliver audio, most often
+    /// because Screen Recording permission was not granted. The frame is
+    /// kept in `capture` so no audio is lost.
+    async fn warm_up(&self, capture: &mut Capture) -> Result<Duration> {
+        let warmup = self.config.warmup;
+        let started = tokio::time::Instant::now();
+
+        match tokio::time::timeout(warmup, capture.audio_rx.recv()).await {
+            Ok(Some(frame)) => {
+                let latency = started.elapsed();
+                debug!("First audio frame arrived after {:?}", latency);
+                capture.first_frame = Some(frame);
+                Ok(latency)
+            }
+            Ok(None) => anyhow::bail!("Audio capture ended before any frames were received"),
+            Err(_) => anyhow::bail!(
+                "No audio frames received within {} ms — check Screen Recording permission",
+                warmup.as_millis()
+            ),
+        }
+    }
+
+    /// Mixer configuration: system audio, the system capture's microphone
+    /// channel, and each additional microphone, each at its gain
+    ///
+    /// In dual-stream mode system audio is published on its own, so only
+    /// microphones are mixed.
+    fn mixer_config(&self) -> MixerConfig {
+        let mut inputs = Vec::new();
+        for source in StereoSplitter::new(self.config.channel_map.clone()).sources() {
+            match source {
+                AudioStreamSource::System if !self.config.dual_stream => inputs.push(MixerInput {
+                    source,
+                    gain: self.config.system_gain,
+                }),
+                AudioStreamSource::Microphone => inputs.push(MixerInput {
+                    source,
+                    gain: self.config.microphone_gain,
+                }),
+                _ => {}
+            }
+        }
+
+        for (index, microphone) in self.config.microphones.iter().enumerate() {
+            inputs.push(MixerInput {
+                source: AudioStreamSource::MicrophoneDevice(index as u8),
+                gain: microphone.gain,
+            });
+        }
+
+        let quality = self.config.quality;
+        MixerConfig {
+            frame_duration_ms: quality.frame_duration_ms(),
+            max_latency_ms: quality.max_latency_ms(),
+            format_mismatch: self.config.format_mismatch,
+            ..MixerConfig::new(self.config.sample_rate, self.config.channels, inputs)
+        }
+    }
+
+    /// Process, mix, and publish captured audio until recording stops
+    ///
+    /// Fails if capture ends while the session is still recording, or if the
+    /// mixer rejects a frame in the wrong format.
+    #[tracing::instrument(name = "audio", skip_all, fields(session = %self.config.session_id))]
+    async fn run(self, capture: Capture) -> Result<()> {
+        info!("Audio processing task started");
+
+        let Capture {
+            mut backends,
+            mut audio_rx,
+            merged,
+            mut first_frame,
+        } = capture;
+
+        // Mix sources only when more than one is captured (in dual-stream mode,
+        // only microphones are mixed; system audio is published on its own)
+        let dual_stream = self.config.dual_stream;
+        let mixer_config = self.mixer_config();
+        let mut mixer = (mixer_config.inputs.len() > 1).then(|| Mixer::new(mixer_config));
+
+        let stt = self.stt.as_deref();
+        let shutdown = &self.shutdown;
+        let frame_sequence = &self.frame_sequence;
+        let mic_sequence = &self.mic_sequence;
+        let recorder = &self.recorder;
+        let system_level = &self.system_level;
+        let frame_drops = &self.frame_drops;
+        let warnings = &self.warnings;
+        let events = &self.events;
+        let max_drop_rate = self.config.max_drop_rate;
+        let sample_rate = self.config.sample_rate;
+        let channels = self.config.channels;
+        let resampler_quality = self.backend_config().resampler_quality;
+        let float_pipeline = self.config.float_pipeline();
+        let downmix = &self.config.downmix;
+        let mut splitter = StereoSplitter::new(self.config.channel_map.clone());
+        let session_id = &self.config.session_id;
+        let protocol = self.protocol;
+        let utterance = &self.config.utterance;
+        let latency = &*self.latency;
+        let mutes = &*self.mutes;
+        let stt_budget = self.stt_budget.as_deref();
+        let transcribing = &*self.transcribing;
+        let preroll = self.preroll.as_deref();
+        let mut channel_archive = self.stereo_chunks.then(|| ChannelArchive {
+            channel_map: self.config.channel_map.clone(),
+            pipeline: Pipeline::from_stages(
+                &self.config.stages,
+                sample_rate,
+                self.config.channel_map.channels(),
+                DownmixStrategy::default(),
+                resampler_quality,
+                !float_pipeline,
+            ),
+            mismatch_warned: false,
+        });
+
+        let publisher = FramePublisher {
+            stt,
+            transcribing,
+            session_id: if dual_stream {
+                StreamRole::System.sub_session_id(session_id)
+            } else {
+                session_id.clone()
+            },
+            frame_sequence,
+            recorder: (!self.stereo_chunks).then_some(recorder),
+            preroll,
+            frames: &self.frames,
+            source: AudioStreamSource::System,
+            speech_ms: self.speech_ms.as_deref(),
+            sample_rate,
+            channels,
+            opus: FramePublisher::encoder(protocol.codec, sample_rate, channels),
+            protocol,
+            batch: Default::default(),
+            utterance: std::sync::Mutex::new(UtteranceTracker::new(utterance.clone())),
+            latency,
+            clock: &*self.config.clock,
+        };
+        let mic_publisher = dual_stream.then(|| FramePublisher {
+            stt,
+            transcribing,
+            session_id: StreamRole::Mic.sub_session_id(session_id),
+            frame_sequence: mic_sequence,
+            recorder: None,
+            preroll: None,
+            frames: &self.frames,
+            source: AudioStreamSource::Microphone,
+            speech_ms: self.speech_ms.as_deref(),
+            sample_rate,
+            channels,
+            opus: FramePublisher::encoder(protocol.codec, sample_rate, channels),
+            protocol,
+            batch: Default::default(),
+            utterance: std::sync::Mutex::new(UtteranceTracker::new(utterance.clone())),
+            latency,
+            clock: &*self.config.clock,
+        });
+        // Mixed audio goes to the mic stream in dual-stream mode
+        let mix_publisher = mic_publisher.as_ref().unwrap_or(&publisher);
+        let mut pipeline = Pipeline::from_stages(
+            &self.config.stages,
+            sample_rate,
+            channels,
+            downmix.clone(),
+            resampler_quality,
+            !float_pipeline,
+        );
+
+        // Processed audio played back out, e.g. as the mic of a call
+        let mut playback = match PlaybackBridge::start(
+            &self.config.playback,
+            self.mixer_config(),
+            self.backfilled.as_millis() as u64,
+        ) {
+            Ok(bridge) => bridge,
+            Err(e) => {
+                warn!("Audio is not played back: {:#}", e);
+                None
+            }
+        };
+
+        // Frame timestamps count from each backend's own start, so queueing
+        // is measured against the offset seen on each source's first frame
+        let clock = std::time::Instant::now();
+        let mut capture_offsets: HashMap<AudioStreamSource, i64> = HashMap::new();
+
+        // Each backend's source is watched for stalls
+        let restart_stalled = self.config.watchdog.restart;
+        let mut watchdog = CaptureWatchdog::new(
+            self.config.watchdog.stall_timeout(),
+            backends.iter().map(|(source, _)| *source),
+        );
+        let mut stalled = false;
+
+        // Audio from before the start goes first (taken, so a restarted
+        // task doesn't repeat it)
+        let backfill = self.backfill.lock().unwrap().take();
+        if let Some(samples) = backfill {
+            let frame_len = (sample_rate as u64 * self.config.quality.frame_duration_ms() / 1000)
+                as usize
+                * channels as usize;
+            for (index, samples) in samples.chunks(frame_len.max(1)).enumerate() {
+                let frame = AudioFrame {
+                    samples: samples.to_vec(),
+                    sample_rate,
+                    channels,
+                    timestamp_ms: index as u64 * self.config.quality.frame_duration_ms(),
+                    source: AudioStreamSource::System,
+                };
+                publisher.publish(&frame).await;
+            }
+        }
+
+        // Companion devices' audio is timed from how far capture had got
+        // when each was first heard
+        let mut companion_rx = self.companion_rx.lock().await;
+        let mut companion_offsets: HashMap<AudioStreamSource, u64> = HashMap::new();
+        let mut captured_ms: u64 = 0;
+
+        let backfilled_ms = self.backfilled.as_millis() as u64;
+        loop {
+            let mut frame = match first_frame.take() {
+                Some(frame) => frame,
+                None => tokio::select! {
+                    biased;
+                    _ = shutdown.cancelled() => break,
+                    frame = audio_rx.recv() => match frame {
+                        Some(frame) => frame,
+                        None => break,
+                    },
+                    _ = stall_deadline(watchdog.deadline()) => {
+                        for (source, silent) in watchdog.stall() {
+                            let Some((_, backend)) =
+                                backends.iter_mut().find(|(backend_source, _)| *backend_source == source)
+                            else {
+                                continue;
+                            };
+                            // A replayed file goes quiet at its end, which is no stall
+                            if backend.is_finished() {
+                                watchdog.unwatch(source);
+                                continue;
+                            }
+
+                            RecordingSession::report_capture_stall(
+                                source,
+                                backend.name(),
+                                silent,
+                                restart_stalled,
+                                warnings,
+                                events,
+                                &*self.config.clock,
+                            )
+                            .await;
+                            if !restart_stalled {
+                                continue;
+                            }
+
+                            match &merged {
+                                // A single source is restarted with the rest of capture
+                                None => stalled = true,
+                                Some(merged) => {
+                                    // Stamped on from where capture has got to
+                                    match Self::restart_source(source, backend, merged, captured_ms)
+                                        .await
+                                    {
+                                        Ok(()) => {
+                                            watchdog.watch(source);
+                                            capture_offsets.remove(&source);
+                                        }
+                                        Err(e) => {
+                                            warn!("{:#}; restarting all capture", e);
+                                            stalled = true;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        if stalled {
+                            break;
+                        }
+                        continue;
+                    }
+                    Some(audio) = companion_rx.recv() => match audio {
+                        CompanionAudio::Frame(mut frame) => {
+                            frame.timestamp_ms += *companion_offsets
+                                .entry(frame.source)
+                                .or_insert_with(|| captured_ms.saturating_sub(frame.timestamp_ms));
+
+                            // Mixed from where the mix has got to, with a
+                            // mixer started for it if sources weren't mixed
+                            let mixer = mixer.get_or_insert_with(|| {
+                                Mixer::new(self.mixer_config())
+                                    .starting_at(captured_ms + backfilled_ms)
+                            });
+                            if !mixer.has_input(frame.source) {
+                                let gain = self.companions.lock().unwrap().get(&frame.source).copied();
+                                mixer.add_input(MixerInput {
+                                    source: frame.source,
+                                    gain: gain.unwrap_or(1.0),
+                                });
+                            }
+                            frame
+                        }
+                        CompanionAudio::Left(source) => {
+                            info!("Companion device {} left", source);
+                            companion_offsets.remove(&source);
+                            if let Some(mixer) = mixer.as_mut() {
+                                mixer.remove_input(source);
+                            }
+                            if let Some(playback) = playback.as_mut() {
+                                playback.remove_source(source);
+                            }
+                            continue;
+                        }
+                    },
+                },
+            };
+            if !companion_offsets.contains_key(&frame.source) {
+                if let Some(silent) = watchdog.frame(frame.source) {
+                    info!(
+                        "Audio capture from {} resumed after {:.1}s",
+                        frame.source,
+                        silent.as_secs_f64()
+                    );
+                    let _ = events.send(SessionEvent::CaptureResumed {
+                        source: frame.source.to_string(),
+                        stalled_secs: silent.as_secs_f64(),
+                        timestamp: self.config.clock.now(),
+                    });
+                }
+                let frames = frame.samples.len() / frame.channels.max(1) as usize;
+                captured_ms =
+                    frame.timestamp_ms + frames as u64 * 1000 / frame.sample_rate.max(1) as u64;
+            }
+            // Captured audio follows the backfill in the recording
+            frame.timestamp_ms += backfilled_ms;
+
+            if let Some(budget) = stt_budget {
+                RecordingSession::check_stt_budget(
+                    budget,
+                    transcribing,
+                    warnings,
+                    events,
+                    &*self.config.clock,
+                )
+                .await;
+            }
+
+            let lag_ms = clock.elapsed().as_millis() as i64 - frame.timestamp_ms as i64;
+            let offset = *capture_offsets.entry(frame.source).or_insert(lag_ms);
+            record_stage(
+                latency,
+                PipelineStage::Capture,
+                Duration::from_millis((lag_ms - offset).max(0) as u64),
+            );
+
+            if let Some(archive) = channel_archive.as_mut() {
+                archive.write(&frame, mutes, recorder);
+            }
+
+            // Separate the system capture's sources into mono frames
+            for mut frame in splitter.process(frame) {
+                // Muted sources keep their timing, with silence for audio
+                if mutes.lock().unwrap().is_muted(frame.source) {
+                    frame.samples.fill(0);
+                }
+
+                if frame.source == AudioStreamSource::System {
+                    let level = channel_level(&frame.samples, frame.channels, 0);
+                    system_level.store(level.to_bits(), Ordering::Relaxed);
+                }
+
+                // Process frame: convert to mono and resample if needed
+                let processed_frame = {
+                    let _span = debug_span!("process", source = ?frame.source).entered();
+                    let started = std::time::Instant::now();
+                    let processed = pipeline.process(FloatFrame::from(frame));
+                    record_stage(latency, PipelineStage::Process, started.elapsed());
+                    processed
+                };
+                if let Some(playback) = playback.as_mut() {
+                    playback.push(processed_frame.clone());
+                }
+
+                if dual_stream && processed_frame.source == AudioStreamSource::System {
+                    publisher.publish(&processed_frame.to_pcm16()).await;
+                    continue;
+                }
+
+                match mixer.as_mut() {
+                    Some(mixer) => {
+                        if let Err(e) = mixer.try_push_float(processed_frame) {
+                            Self::stop_backends(backends).await;
+                            return Err(e.context("Mixer rejected captured audio"));
+                        }
+                        for mixed in mixer.pop_ready() {
+                            mix_publisher.publish(&mixed).await;
+                        }
+
+                        let drops = mixer.drop_stats();
+                        *frame_drops.lock().await = drops;
+                        RecordingSession::check_drop_rate(
+                            &drops,
+                            max_drop_rate,
+                            warnings,
+                            events,
+                            &*self.config.clock,
+                        )
+                        .await;
+                    }
+                    None => mix_publisher.publish(&processed_frame.to_pcm16()).await,
+                }
+            }
+        }
+
+        // Capture ended by itself: stop what is left and let the supervisor restart it
+        if !shutdown.is_cancelled() {
+            Self::stop_backends(backends).await;
+            if stalled {
+                anyhow::bail!("Audio capture stalled");
+            }
+            anyhow::bail!("Audio capture ended unexpectedly");
+        }
+
+        // Publish any audio still buffered in the mixer
+        if let Some(remaining) = mixer.as_mut().and_then(Mixer::flush) {
+            mix_publisher.publish(&remaining).await;
+        }
+
+        info!("Audio processing task stopped");
+
+        // Send final frames
+        publisher.finish().await;
+        if let Some(mic_publisher) = &mic_publisher {
+            mic_publisher.finish().await;
+        }
+
+        Self::stop_backends(backends).await;
+        Ok(())
+    }
+
+    /// Stop and start a stalled source's backend, adding its frames to the
+    /// merged stream again from `position_ms`
+    async fn restart_source(
+        source: AudioStreamSource,
+        backend: &mut Box<dyn AudioBackend>,
+        merged: &MergedCapture,
+        position_ms: u64,
+    ) -> Result<()> {
+        if let Err(e) = backend.stop().await {
+            warn!("Failed to stop stalled {} backend: {}", backend.name(), e);
+        }
+        let source_rx = backend
+            .start()
+            .await
+            .with_context(|| format!("Failed to restart {} capture", backend.name()))?;
+        merged.restart(source, source_rx, position_ms)?;
+        info!("Restarted {} capture for {}", backend.name(), source);
+        Ok(())
+    }
+
+    async fn stop_backends(backends: Vec<(AudioStreamSource, Box<dyn AudioBackend>)>) {
+        for (_, mut backend) in backends {
+            if let Err(e) = backend.stop().await {
+                error!("Failed to stop {} backend: {}", backend.name(), e);
+            }
+        }
+    }
+}
+
+/// Records system capture frames with their channels kept apart (e.g.
+/// system left, microphone right), for stereo chunks
+struct ChannelArchive {
+    channel_map: ChannelMap,
+    pipeline: Pipeline,
+    mismatch_warned: bool,
+}
+
+impl ChannelArchive {
+    /// Resample and store a system capture frame, silencing the channels
+    /// of muted sources
+    ///
+    /// Frames in another layout than the channel map are not stored, as
+    /// their channels can't be labelled.
+    fn write(
+        &mut self,
+        frame: &AudioFrame,
+        mutes: &std::sync::Mutex<SourceMutes>,
+        recorder: &std::sync::Mutex<Option<ChunkedRecorder>>,
+    ) {
+        if frame.source != AudioStreamSource::System {
+            return;
+        }
+        if frame.channels != self.channel_map.channels() {
+            if !self.mismatch_warned {
+                warn!(
+                    "System capture has {} channels but the channel map {} describes {}; not storing it",
+                    frame.channels,
+                    self.channel_map,
+                    self.channel_map.channels()
+                );
+                self.mismatch_warned = true;
+            }
+            return;
+        }
+
+        let mut frame = frame.clone();
+        let channels = frame.channels as usize;
+        {
+            let mutes = mutes.lock().unwrap();
+            for (channel, role) in self.channel_map.roles().iter().enumerate() {
+                if role.source().is_some_and(|source| mutes.is_muted(source)) {
+                    for sample in frame.samples.iter_mut().skip(channel).step_by(channels) {
+                        *sample = 0;
+                    }
+                }
+            }
+        }
+
+        let processed = self.pipeline.process(FloatFrame::from(frame));
+        if let Some(recorder) = recorder.lock().unwrap().as_mut() {
+            if let Err(e) = recorder.write(&processed) {
+                error!("Failed to store audio, no more will be stored: {:#}", e);
+            }
+        }
+    }
+}
+
+/// Everything the transcript task shares with its session
+#[derive(Clone)]
+struct TranscriptCollector {
+    session_id: String,
+    stt: Arc<dyn SttProvider>,
+    shutdown: CancellationToken,
+    transcript_segments: Arc<Mutex<TranscriptLog>>,
+    transcripts: broadcast::Sender<TranscriptSegment>,
+    filter: Option<Arc<TranscriptFilter>>,
+    /// Speech heard so far, counted by the audio task
+    speech_ms: Arc<AtomicU64>,
+    segment_ledger: Arc<std::sync::Mutex<SegmentLedger>>,
+    stream_config: TranscriptStreamConfig,
+    latency: Arc<std::sync::Mutex<PipelineLatency>>,
+    processors: Arc<Vec<Box<dyn TranscriptProcessor>>>,
+    clock: SharedClock,
+}
+
+impl TranscriptCollector {
+    async fn subscribe(&self) -> Result<TranscriptSubscription> {
+        self.stt.subscribe(&self.stream_config).await
+    }
+
+    /// Collect this session's transcripts until recording stops
+    ///
+    /// Fails if the subscription closes while the session is still recording.
+    #[tracing::instrument(name = "transcripts", skip_all, fields(session = %self.session_id))]
+    async fn run(self, mut transcript_sub: TranscriptSubscription) -> Result<()> {
+        info!("Transcript receiving task started");
+        let started = self.clock.now();
+
+        let session_id = self.session_id.as_str();
+        let shutdown = &self.shutdown;
+        let transcript_segments = &self.transcript_segments;
+        let mut processing = JoinSet::new();
+        // Speech heard up to the previous final segment
+        let mut speech_before = self.speech_ms.load(Ordering::Relaxed);
+
+        loop {
+            let delivery = tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => break,
+                Some(_) = processing.join_next(), if !processing.is_empty() => continue,
+                delivery = transcript_sub.next() => match delivery {
+                    Some(delivery) => delivery,
+                    None => break,
+                },
+            };
+            // Only durable delivery knows when a transcript was published
+            let published = delivery.published;
+
+            // Parse transcript message
+            match delivery.transcript {
+                Ok(transcript) => {
+                    // Filter by session_id (or one of its dual-stream sub-IDs)
+                    let role = StreamRole::from_sub_session_id(session_id, &transcript.session_id);
+                    if transcript.session_id != session_id && role.is_none() {
+                        continue;
+                    }
+
+                    // Partials delivered from before this task started are stale
+                    if transcript.partial && published.is_some_and(|at| at < started) {
+                        continue;
+                    }
+
+                    // Backlog from a durable consumer would swamp the timings
+                    if published.is_none_or(|at| at >= started) {
+                        if let Ok(at) = chrono::DateTime::parse_from_rfc3339(&transcript.timestamp)
+                        {
+                            let lag = self.clock.now().signed_duration_since(at);
+                            record_stage(
+                                &self.latency,
+                                PipelineStage::Transcript,
+                                lag.to_std().unwrap_or_default(),
+                            );
+                        }
+                    }
+
+                    // Skip segments the STT service resent (e.g. after a reconnect)
+                    if !self.segment_ledger.lock().unwrap().admit(&transcript) {
+                        debug!(
+                            "Ignoring resent segment for {}: {}",
+                            transcript.session_id, transcript.text
+                        );
+                        continue;
+                    }
+
+                    // Create segment; dual-stream segments keep the STT
+                    // timestamp so the two streams interleave correctly, and
+                    // durable ones their publish time so transcripts missed
+                    // during a restart land where they were spoken
+                    let timestamp = match role {
+                        Some(_) => chrono::DateTime::parse_from_rfc3339(&transcript.timestamp)
+                            .map(|t| t.with_timezone(&Utc))
+                            .unwrap_or_else(|_| self.clock.now()),
+                        None => published.unwrap_or_else(|| self.clock.now()),
+                    };
+                    let segment = TranscriptSegment {
+                        text: transcript.text.clone(),
+                        timestamp,
+                        confidence: transcript.confidence,
+                        partial: transcript.partial,
+                        speaker: role
+                            .map(|role| role.speaker().to_string())
+                            .or_else(|| transcript.speaker.clone()),
+                        language: segment_language(
+                            transcript.language.as_deref(),
+                            &transcript.text,
+                        ),
+                        translation: None,
+                    };
+
+                    // Drop likely hallucinated final segments before they
+                    // are stored
+                    if let Some(filter) = &self.filter {
+                        let speech_ms = self.speech_ms.load(Ordering::Relaxed);
+                        let speech = filter
+                            .checks_speech()
+                            .then(|| Duration::from_millis(speech_ms - speech_before));
+                        if !segment.partial {
+                            speech_before = speech_ms;
+                        }
+                        if let Some(reason) = filter.rejects(&segment, speech) {
+                            debug!("Dropping segment ({}): {}", reason, segment.text);
+                            continue;
+                        }
+                    }
+
+                    // Store segment
+                    let sequence = {
+                        let mut segments = transcript_segments.lock().await;
+                        match (role, published) {
+                            (None, None) => segments.push(segment.clone()),
+                            _ => segments.insert_by_timestamp(segment.clone()),
+                        }
+                    };
+
+                    // Post-process final segments without holding up the next
+                    // one; subscribers get them once processed
+                    if !segment.partial && !self.processors.is_empty() {
+                        processing.spawn(
+                            self.clone()
+                                .process_segment(sequence, segment)
+                                .in_current_span(),
+                        );
+                    } else {
+                        let _ = self.transcripts.send(segment);
+                    }
+
+                    // Log to console
+                    if transcript.partial {
+                        print!("\r{}", transcript.text);
+                        std::io::Write::flush(&mut std::io::stdout()).ok();
+                    } else {
+                        println!("\n{}", transcript.text);
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to parse transcript message: {}", e);
+                }
+            }
+        }
+
+        if !shutdown.is_cancelled() {
+            anyhow::bail!("Transcript subscription closed");
+        }
+
+        // Let segments still being processed land before the transcript is saved
+        while processing.join_next().await.is_some() {}
+
+        info!("Transcript receiving task stopped");
+        Ok(())
+    }
+
+    /// Run the processors over a stored final segment, replacing it with
+    /// the result
+    async fn process_segment(self, sequence: u64, mut segment: TranscriptSegment) {
+        let mut changed = false;
+        for processor in self.processors.iter() {
+            match processor.process(&self.session_id, &segment).await {
+                Ok(Some(processed)) => {
+                    segment = processed;
+                    changed = true;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("{} failed on a segment: {:#}", processor.name(), e),
+            }
+        }
+
+        if changed {
+            self.transcript_segments
+                .lock()
+                .await
+                .replace(sequence, segment.clone());
+        }
+        let _ = self.transcripts.send(segment);
+    }
+}
+
+/// Publishes processed frames to the STT provider with sequence numbering
+struct FramePublisher<'a> {
+    /// None in record-only sessions, where frames are only stored
+    stt: Option<&'a dyn SttProvider>,
+    /// Cleared once the session falls back to record-only
+    transcribing: &'a AtomicBool,
+    /// Session ID frames are published under
+    session_id: String,
+    frame_sequence: &'a AtomicUsize,
+    /// Stores published audio in chunk files (only the primary stream is
+    /// recorded)
+    recorder: Option<&'a std::sync::Mutex<Option<ChunkedRecorder>>>,
+    /// Keeps the recorded stream's recent audio for bookmarks
+    preroll: Option<&'a std::sync::Mutex<PrerollBuffer>>,
+    /// Frame subscribers
+    frames: &'a broadcast::Sender<AudioFrame>,
+    /// Source frames are sent to subscribers as: system for the main
+    /// stream, microphone for the dual-stream mic stream
+    source: AudioStreamSource,
+    /// Speech heard, for the transcript filter (None = not counted)
+    speech_ms: Option<&'a AtomicU64>,
+    sample_rate: u32,
+    channels: u16,
+    /// Encodes frames when Opus was negotiated (None = raw PCM)
+    opus: Option<std::sync::Mutex<OpusEncoder>>,
+    /// Version and batching agreed with the STT service
+    protocol: Protocol,
+    /// Audio waiting for the batch to fill
+    batch: std::sync::Mutex<Batch>,
+    /// Marks utterance ends, which are sent without waiting for the batch
+    utterance: std::sync::Mutex<UtteranceTracker>,
+    /// Publish timings
+    latency: &'a std::sync::Mutex<PipelineLatency>,
+    /// Timestamps published messages
+    clock: &'a dyn Clock,
+}
+
+/// Audio for the next published message
+#[derive(Default)]
+struct Batch {
+    /// Frames added since the last message
+    frames: u32,
+    samples: Vec<i16>,
+    packets: Vec<Vec<u8>>,
+}
+
+impl Batch {
+    fn is_empty(&self) -> bool {
+        self.samples.is_empty() && self.packets.is_empty()
+    }
+}
+
+impl FramePublisher<'_> {
+    /// Encoder for `codec`, falling back to PCM if one cannot be created
+    fn encoder(
+        codec: AudioCodec,
+        sample_rate: u32,
+        channels: u16,
+    ) -> Option<std::sync::Mutex<OpusEncoder>> {
+        if codec != AudioCodec::Opus {
+            return None;
+        }
+
+        match OpusEncoder::new(sample_rate, channels) {
+            Ok(encoder) => Some(std::sync::Mutex::new(encoder)),
+            Err(e) => {
+                warn!("{:#}; publishing PCM", e);
+                None
+            }
+        }
+    }
+
+    /// Store and publish a frame, timing the publish stage
+    async fn publish(&self, frame: &AudioFrame) {
+        let started = std::time::Instant::now();
+        self.publish_frame(frame)
+            .instrument(debug_span!("publish", stream = %self.session_id))
+            .await;
+        record_stage(self.latency, PipelineStage::Publish, started.elapsed());
+    }
+
+    async fn publish_frame(&self, frame: &AudioFrame) {
+        if let Some(recorder) = self.recorder {
+            if let Some(recorder) = recorder.lock().unwrap().as_mut() {
+                if let Err(e) = recorder.write(&FloatFrame::from(frame)) {
+                    error!("Failed to store audio, no more will be stored: {:#}", e);
+                }
+            }
+        }
+        if let Some(preroll) = self.preroll {
+            preroll.lock().unwrap().push(&frame.samples);
+        }
+        if self.frames.receiver_count() > 0 {
+            let _ = self.frames.send(AudioFrame {
+                source: self.source,
+                ..frame.clone()
+            });
+        }
+
+        let Some(stt) = self
+            .stt
+            .filter(|_| self.transcribing.load(Ordering::Relaxed))
+        else {
+            return;
+        };
+
+        // Speech the transcript filter checks final segments against
+        if let Some(speech_ms) = self.speech_ms {
+            let threshold = self.utterance.lock().unwrap().silence_threshold_dbfs();
+            if rms_dbfs(&frame.samples) >= threshold {
+                let frames = frame.samples.len() / frame.channels.max(1) as usize;
+                let ms = frames as u64 * 1000 / frame.sample_rate.max(1) as u64;
+                speech_ms.fetch_add(ms, Ordering::Relaxed);
+            }
+        }
+
+        let segment_final =
+            self.utterance
+                .lock()
+                .unwrap()
+                .push(&frame.samples, frame.sample_rate, frame.channels);
+
+        // Add the frame to the batch (encoded to Opus, in whole packets)
+        let batch = {
+            let mut batch = self.batch.lock().unwrap();
+            match &self.opus {
+                Some(opus) => match opus.lock().unwrap().encode(&frame.samples) {
+                    Ok(packets) => batch.packets.extend(packets),
+                    Err(e) => error!("Failed to encode audio frame: {:#}", e),
+                },
+                None => batch.samples.extend_from_slice(&frame.samples),
+            }
+
+            batch.frames += 1;
+            if (batch.frames < self.protocol.batch_frames && !segment_final) || batch.is_empty() {
+                return;
+            }
+            std::mem::take(&mut *batch)
+        };
+
+        // Get sequence number
+        let seq = self.frame_sequence.fetch_add(1, Ordering::SeqCst);
+
+        // Publish to the STT provider
+        if let Err(e) = self.send(stt, batch, seq, false, segment_final).await {
+            error!("Failed to publish audio frame: {}", e);
+        }
+    }
+
+    /// Send any partial batch, then the final (empty) frame for this stream
+    async fn finish(&self) {
+        let Some(stt) = self
+            .stt
+            .filter(|_| self.transcribing.load(Ordering::Relaxed))
+        else {
+            return;
+        };
+
+        let batch = {
+            let mut batch = self.batch.lock().unwrap();
+            if let Some(opus) = &self.opus {
+                // Encode the last partial Opus packet
+                match opus.lock().unwrap().flush() {
+                    Ok(packet) => batch.packets.extend(packet),
+                    Err(e) => error!("Failed to encode audio frame: {:#}", e),
+                }
+            }
+            std::mem::take(&mut *batch)
+        };
+        if !batch.is_empty() {
+            let seq = self.frame_sequence.fetch_add(1, Ordering::SeqCst);
+            if let Err(e) = self.send(stt, batch, seq, false, false).await {
+                error!("Failed to publish audio frame: {}", e);
+            }
+        }
+
+        let seq = self.frame_sequence.load(Ordering::SeqCst);
+        if let Err(e) = self.send(stt, Batch::default(), seq, true, false).await {
+            error!("Failed to send final frame for {}: {}", self.session_id, e);
+        }
+    }
+
+    /// Publish a batch as one message
+    async fn send(
+        &self,
+        stt: &dyn SttProvider,
+        batch: Batch,
+        seq: usize,
+        is_final: bool,
+        segment_final: bool,
+    ) -> Result<()> {
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+
+        // Convert to PCM bytes
+        let pcm_bytes: Vec<u8> = batch.samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        let message = AudioFrameMessage {
+            version: self.protocol.version,
+            session_id: self.session_id.clone(),
+            sequence: seq as u32,
+            pcm: encode(&pcm_bytes),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            timestamp: self.clock.now().to_rfc3339(),
+            final_frame: is_final,
+            segment_final,
+            model: None,
+            codec: match self.opus {
+                Some(_) => AudioCodec::Opus,
+                None => AudioCodec::Pcm16,
+            },
+            packets: batch.packets.iter().map(|packet| encode(packet)).collect(),
+        };
+
+        stt.send_audio(&message).await
+    }
+}
+
+/// Wait up to `timeout` for a session task, aborting it if it does not finish
+async fn join_or_abort(name: &str, handle: Option<JoinHandle<()>>, timeout: Duration) {
+    let Some(mut handle) = handle else {
+        return;
+    };
+
+    match tokio::time::timeout(timeout, &mut handle).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => error!("{} panicked: {}", name, e),
+        Err(_) => {
+            warn!("{} did not stop within {:?}; aborting it", name, timeout);
+            handle.abort();
+        }
+    }
+}
+
+/// Record time spent in a pipeline stage, warning when it spikes
+fn record_stage(
+    latency: &std::sync::Mutex<PipelineLatency>,
+    stage: PipelineStage,
+    elapsed: Duration,
+) {
+    let mut latency = latency.lock().unwrap();
+    if latency.record(stage, elapsed) {
+        warn!(
+            %stage,
+            "Pipeline lag in {} stage: {:.1} ms (mean {:.1} ms)",
+            stage,
+            elapsed.as_secs_f64() * 1000.0,
+            latency.stage(stage).mean_ms
+        );
     }
 }
@@ -0,0 +1,187 @@
+use crate::audio::{
+    AudioBackendConfig, AudioBackendFactory, AudioSource, DownmixStrategy, FloatFrame, Pipeline,
+    PrerollBuffer, StageConfig,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+/// Standby capture between meetings, so a session can start with the
+/// audio from just before it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StandbyConfig {
+    pub enabled: bool,
+    /// Minutes of audio kept in memory (it is never written to disk)
+    pub minutes: u64,
+}
+
+impl Default for StandbyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            minutes: 2,
+        }
+    }
+}
+
+impl StandbyConfig {
+    pub fn buffer_duration(&self) -> Duration {
+        Duration::from_secs(self.minutes * 60)
+    }
+}
+
+/// Standby capture while it runs
+struct StandbyCapture {
+    shutdown: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+/// Captures system audio while no session is recording, keeping only the
+/// last few minutes in memory
+///
+/// System capture is shared by the whole process, so standby pauses while
+/// sessions record; the audio it held goes to the session that paused it.
+pub struct Standby {
+    backend_config: AudioBackendConfig,
+    downmix: DownmixStrategy,
+    stages: Vec<StageConfig>,
+    buffer_duration: Duration,
+    buffer: Arc<std::sync::Mutex<PrerollBuffer>>,
+    capture: Mutex<Option<StandbyCapture>>,
+}
+
+impl Standby {
+    /// Standby buffering `config`'s minutes of audio, captured with
+    /// `backend_config` and kept in its target format, processed by
+    /// `stages` as a session's audio would be
+    pub fn new(
+        config: &StandbyConfig,
+        backend_config: AudioBackendConfig,
+        downmix: DownmixStrategy,
+        stages: Vec<StageConfig>,
+    ) -> Self {
+        let buffer_duration = config.buffer_duration();
+        Self {
+            buffer: Arc::new(std::sync::Mutex::new(PrerollBuffer::new(
+                buffer_duration,
+                backend_config.target_sample_rate,
+                backend_config.target_channels,
+            ))),
+            backend_config,
+            downmix,
+            stages,
+            buffer_duration,
+            capture: Mutex::new(None),
+        }
+    }
+
+    /// Whether standby is capturing
+    pub async fn is_running(&self) -> bool {
+        self.capture
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|capture| !capture.handle.is_finished())
+    }
+
+    /// Audio currently buffered
+    pub fn buffered(&self) -> Duration {
+        self.buffer.lock().unwrap().duration()
+    }
+
+    /// Start capturing, unless standby already is
+    pub async fn resume(&self) -> Result<()> {
+        let mut capture = self.capture.lock().await;
+        if capture
+            .as_ref()
+            .is_some_and(|capture| !capture.handle.is_finished())
+        {
+            return Ok(());
+        }
+
+        let mut backend =
+            AudioBackendFactory::create(AudioSource::System, self.backend_config.clone())
+                .context("Failed to create standby audio backend")?;
+        let mut audio_rx = backend
+            .start()
+            .await
+            .context("Failed to start standby capture")?;
+
+        let shutdown = CancellationToken::new();
+        let stop = shutdown.clone();
+        let buffer = Arc::clone(&self.buffer);
+        let sample_rate = self.backend_config.target_sample_rate;
+        let channels = self.backend_config.target_channels;
+        let quality = self.backend_config.resampler_quality;
+        let downmix = self.downmix.clone();
+        let stages = self.stages.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut pipeline =
+                Pipeline::from_stages(&stages, sample_rate, channels, downmix, quality, true);
+            loop {
+                let frame = tokio::select! {
+                    biased;
+                    _ = stop.cancelled() => break,
+                    frame = audio_rx.recv() => match frame {
+                        Some(frame) => frame,
+                        None => {
+                            error!("Standby capture ended unexpectedly");
+                            break;
+                        }
+                    },
+                };
+
+                let processed = pipeline.process(FloatFrame::from(frame));
+                buffer.lock().unwrap().push(&processed.to_pcm16().samples);
+            }
+
+            if let Err(e) = backend.stop().await {
+                error!("Failed to stop standby {} backend: {}", backend.name(), e);
+            }
+        });
+
+        info!(
+            "Standby capture started, keeping the last {}s in memory",
+            self.buffer_duration.as_secs()
+        );
+        *capture = Some(StandbyCapture { shutdown, handle });
+        Ok(())
+    }
+
+    /// Stop capturing and take the buffered audio (None = nothing buffered)
+    ///
+    /// Returns once standby has released the capture device.
+    pub async fn pause(&self) -> Option<PrerollBuffer> {
+        if let Some(capture) = self.capture.lock().await.take() {
+            capture.shutdown.cancel();
+            if let Err(e) = capture.handle.await {
+                error!("Standby capture task failed: {}", e);
+            }
+            info!("Standby capture paused");
+        }
+
+        let empty = PrerollBuffer::new(
+            self.buffer_duration,
+            self.backend_config.target_sample_rate,
+            self.backend_config.target_channels,
+        );
+        let audio = std::mem::replace(&mut *self.buffer.lock().unwrap(), empty);
+        (!audio.duration().is_zero()).then_some(audio)
+    }
+}
+
+impl Drop for Standby {
+    /// Stop capturing (the capture task stops its backend on the way out)
+    fn drop(&mut self) {
+        if let Some(capture) = self.capture.get_mut().take() {
+            capture.shutdown.cancel();
+        }
+    }
+}
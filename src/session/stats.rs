@@ -1,27 +1,156 @@
+use super::supervisor::SessionState;
+use super::translation::Translation;
+use crate::audio::workdir::is_workdir_file;
+use crate::audio::FrameDropStats;
+use crate::stt::BudgetPeriod;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 /// Statistics about a recording session
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionStats {
     /// Whether recording is currently active
     pub is_recording: bool,
 
+    /// Lifecycle state (recording, failed, or stopped)
+    #[serde(default)]
+    pub state: SessionState,
+
     /// When the recording started
     pub started_at: DateTime<Utc>,
 
     /// Total duration in seconds
     pub duration_secs: f64,
 
-    /// Number of audio chunks recorded so far
+    /// Number of audio chunk files written so far
     pub chunks_count: usize,
 
+    /// Size of the chunk files written so far
+    #[serde(default)]
+    pub audio_bytes: u64,
+
+    /// How full the chunk being written is, 0-100 (None when no chunk is
+    /// being written)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_chunk_percent: Option<f64>,
+
     /// Number of transcript segments received
     pub transcript_segments_count: usize,
+
+    /// Frames received by the mixer and dropped, by reason
+    pub frame_drops: FrameDropStats,
+
+    /// Quality warnings raised during the session
+    pub warnings: Vec<SessionWarning>,
+
+    /// Whether the session's audio was kept on disk
+    #[serde(default)]
+    pub audio_retention: AudioRetention,
+}
+
+/// What a session kept of its audio
+///
+/// In privacy mode audio is streamed to STT and never stored; the session
+/// checks its recordings directory so clients can confirm nothing was written.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AudioRetention {
+    /// Transcript-only session (no audio is stored)
+    pub privacy: bool,
+
+    /// Audio files found for the meeting when last checked
+    pub audio_files: usize,
+
+    /// Total size of those files in bytes
+    pub audio_bytes: u64,
+
+    /// When the recordings directory was checked (None = not checked)
+    pub checked_at: Option<DateTime<Utc>>,
+
+    /// Privacy mode was on and the check found no audio
+    pub verified: bool,
+
+    /// Transcribe-only session: audio was not stored, but the recordings
+    /// directory is not checked
+    #[serde(default)]
+    pub transcribe_only: bool,
+}
+
+impl AudioRetention {
+    /// Check the meeting's recordings directory for stored audio, as of
+    /// `checked_at`
+    ///
+    /// A missing directory counts as no audio; files in subdirectories are
+    /// included, the directory's lock and manifest are not.
+    pub fn check(
+        privacy: bool,
+        audio_dir: &Path,
+        checked_at: DateTime<Utc>,
+    ) -> std::io::Result<Self> {
+        let mut retention = Self {
+            privacy,
+            checked_at: Some(checked_at),
+            ..Default::default()
+        };
+
+        let mut dirs = vec![audio_dir.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                if metadata.is_dir() {
+                    dirs.push(entry.path());
+                } else if !is_workdir_file(&entry.file_name()) {
+                    retention.audio_files += 1;
+                    retention.audio_bytes += metadata.len();
+                }
+            }
+        }
+
+        retention.verified = privacy && retention.audio_files == 0;
+        Ok(retention)
+    }
+}
+
+/// A quality problem detected during a recording session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionWarning {
+    /// The fraction of dropped audio frames exceeded the configured threshold
+    HighDropRate {
+        /// Drop rate when the warning was raised
+        drop_rate: f64,
+        /// Configured maximum drop rate
+        threshold: f64,
+        raised_at: DateTime<Utc>,
+    },
+    /// The cloud STT budget ran out; the session continues record-only
+    SttBudgetExhausted {
+        period: BudgetPeriod,
+        raised_at: DateTime<Utc>,
+    },
+    /// A capture source delivered no frames for longer than the watchdog
+    /// allows
+    CaptureStalled {
+        /// Source that went silent ("system", "mic-<index>"; empty in
+        /// warnings raised before sources were watched separately)
+        #[serde(default)]
+        source: String,
+        /// Time without frames when the warning was raised
+        silent_secs: f64,
+        /// Whether the source's capture is being restarted
+        restarting: bool,
+        raised_at: DateTime<Utc>,
+    },
 }
 
 /// A single transcript segment from the STT service
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TranscriptSegment {
     /// Transcribed text
     pub text: String,
@@ -34,4 +163,17 @@ pub struct TranscriptSegment {
 
     /// Whether this is a partial (interim) result
     pub partial: bool,
+
+    /// Speaker label, if known
+    #[serde(default)]
+    pub speaker: Option<String>,
+
+    /// Language of the segment (ISO 639-1, e.g. "en"), as the STT service
+    /// reported it or detected from the text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Translation of the text, if the session translates transcripts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<Translation>,
 }
@@ -0,0 +1,61 @@
+use super::stats::TranscriptSegment;
+use crate::audio::{AudioFrame, ChannelMap};
+use serde::{Deserialize, Serialize};
+
+/// Which side of the conversation a dual-stream STT stream carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamRole {
+    /// System audio: the remote participants
+    System,
+    /// Microphone audio: the local user
+    Mic,
+}
+
+impl StreamRole {
+    /// Suffix appended to the session ID for this stream
+    pub fn suffix(self) -> &'static str {
+        match self {
+            StreamRole::System => "system",
+            StreamRole::Mic => "mic",
+        }
+    }
+
+    /// Session ID the stream is published under (e.g. "standup.mic")
+    pub fn sub_session_id(self, session_id: &str) -> String {
+        format!("{}.{}", session_id, self.suffix())
+    }
+
+    /// The role `sub_id` names, if it is one of `session_id`'s streams
+    pub fn from_sub_session_id(session_id: &str, sub_id: &str) -> Option<Self> {
+        let suffix = sub_id.strip_prefix(session_id)?.strip_prefix('.')?;
+        [StreamRole::System, StreamRole::Mic]
+            .into_iter()
+            .find(|role| role.suffix() == suffix)
+    }
+
+    /// Speaker attributed to transcripts from this stream
+    pub fn speaker(self) -> &'static str {
+        match self {
+            StreamRole::System => "Them",
+            StreamRole::Mic => "Me",
+        }
+    }
+}
+
+/// Split a System→L, Mic→R stereo frame into mono system and mic frames
+///
+/// Uses the default [`ChannelMap`]; sessions split with their configured
+/// map. Returns `None` for frames that are not stereo.
+pub fn split_stereo(frame: &AudioFrame) -> Option<(AudioFrame, AudioFrame)> {
+    let mut frames = ChannelMap::default().split(frame)?.into_iter();
+    Some((frames.next()?, frames.next()?))
+}
+
+/// Insert a segment, keeping the transcript ordered by timestamp
+///
+/// Segments with equal timestamps keep their arrival order.
+pub fn insert_by_timestamp(segments: &mut Vec<TranscriptSegment>, segment: TranscriptSegment) {
+    let index = segments.partition_point(|s| s.timestamp <= segment.timestamp);
+    segments.insert(index, segment);
+}
@@ -0,0 +1,209 @@
+use super::events::SessionEvent;
+use anyhow::Result;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tokio::task::{Id, JoinError, JoinSet};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// A supervised long-running task of a recording session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionTask {
+    /// Capture, processing, and publishing of audio
+    Audio,
+    /// Collection of transcripts from the STT service
+    Transcript,
+}
+
+impl fmt::Display for SessionTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionTask::Audio => write!(f, "audio"),
+            SessionTask::Transcript => write!(f, "transcript"),
+        }
+    }
+}
+
+/// Lifecycle state of a recording session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SessionState {
+    /// Capturing and transcribing
+    #[default]
+    Recording,
+    /// A task failed and could not be restarted; the session keeps what it
+    /// captured until it is stopped
+    Failed { task: SessionTask, error: String },
+    /// Stopped normally
+    Stopped,
+}
+
+/// How failed session tasks are restarted
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RestartPolicy {
+    /// Restarts allowed per task before the session fails (0 = never restart)
+    pub max_restarts: u32,
+    /// Delay before each restart
+    pub backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Starts a fresh instance of a task
+pub type TaskFactory = Arc<dyn Fn() -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+struct Supervised {
+    restart: TaskFactory,
+    restarts: u32,
+}
+
+/// Watches a session's tasks, restarting them per policy
+///
+/// A task that panics, returns an error, or returns at all while the session
+/// is still recording has failed. It is restarted after the policy's backoff
+/// until it runs out of restarts, at which point the session moves to
+/// [`SessionState::Failed`]. Each failure is reported as a
+/// [`SessionEvent::TaskFailed`]. Once `shutdown` is cancelled, tasks are
+/// expected to return and their errors are only logged.
+pub struct Supervisor {
+    tasks: JoinSet<Result<()>>,
+    running: HashMap<Id, SessionTask>,
+    supervised: HashMap<SessionTask, Supervised>,
+    policy: RestartPolicy,
+    shutdown: CancellationToken,
+    state: watch::Sender<SessionState>,
+    events: broadcast::Sender<SessionEvent>,
+}
+
+impl Supervisor {
+    pub fn new(
+        policy: RestartPolicy,
+        shutdown: CancellationToken,
+        state: watch::Sender<SessionState>,
+        events: broadcast::Sender<SessionEvent>,
+    ) -> Self {
+        Self {
+            tasks: JoinSet::new(),
+            running: HashMap::new(),
+            supervised: HashMap::new(),
+            policy,
+            shutdown,
+            state,
+            events,
+        }
+    }
+
+    /// Run `first` as `task`, restarting it with `restart` if it fails
+    pub fn spawn(
+        &mut self,
+        task: SessionTask,
+        first: impl Future<Output = Result<()>> + Send + 'static,
+        restart: TaskFactory,
+    ) {
+        let handle = self.tasks.spawn(first);
+        self.running.insert(handle.id(), task);
+        self.supervised.insert(
+            task,
+            Supervised {
+                restart,
+                restarts: 0,
+            },
+        );
+    }
+
+    /// Watch the tasks until all of them have returned
+    pub async fn run(mut self) {
+        while let Some(result) = self.tasks.join_next_with_id().await {
+            let (id, outcome) = match result {
+                Ok((id, outcome)) => (id, outcome.map_err(|e| format!("{:#}", e))),
+                Err(e) => (e.id(), Err(panic_message(e))),
+            };
+            let Some(task) = self.running.remove(&id) else {
+                continue;
+            };
+
+            if self.shutdown.is_cancelled() {
+                if let Err(error) = outcome {
+                    error!("Session {} task failed while stopping: {}", task, error);
+                }
+                continue;
+            }
+
+            let error = outcome
+                .err()
+                .unwrap_or_else(|| "Task ended unexpectedly".to_string());
+            self.handle_failure(task, error);
+        }
+    }
+
+    fn handle_failure(&mut self, task: SessionTask, error: String) {
+        let Some(supervised) = self.supervised.get_mut(&task) else {
+            return;
+        };
+
+        let restarting = supervised.restarts < self.policy.max_restarts;
+        if restarting {
+            supervised.restarts += 1;
+            warn!(
+                "Session {} task failed, restarting (attempt {} of {}): {}",
+                task, supervised.restarts, self.policy.max_restarts, error
+            );
+
+            let restart = Arc::clone(&supervised.restart);
+            let backoff = self.policy.backoff;
+            let shutdown = self.shutdown.clone();
+            let handle = self.tasks.spawn(async move {
+                tokio::select! {
+                    _ = shutdown.cancelled() => return Ok(()),
+                    _ = tokio::time::sleep(backoff) => {}
+                }
+                info!("Restarting session {} task", task);
+                restart().await
+            });
+            self.running.insert(handle.id(), task);
+        } else {
+            error!("Session {} task failed: {}", task, error);
+            self.state.send_replace(SessionState::Failed {
+                task,
+                error: error.clone(),
+            });
+        }
+
+        // No subscribers is not an error
+        let _ = self.events.send(SessionEvent::TaskFailed {
+            task,
+            error,
+            restarts: supervised.restarts,
+            restarting,
+        });
+    }
+}
+
+/// Readable description of a task that did not return
+fn panic_message(error: JoinError) -> String {
+    if error.is_cancelled() {
+        return "Task was cancelled".to_string();
+    }
+
+    let panic = error.into_panic();
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    format!("Task panicked: {}", message)
+}
@@ -0,0 +1,196 @@
+use super::language::normalize_language;
+use super::stats::TranscriptSegment;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Filters and paging for reading a transcript
+///
+/// Filters combine; `offset`/`limit` page through what matches them.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TranscriptQuery {
+    /// Matching segments to skip
+    pub offset: Option<usize>,
+    /// Most segments to return
+    pub limit: Option<usize>,
+    /// Only segments timestamped after this
+    pub since: Option<DateTime<Utc>>,
+    /// Only segments at or after this offset into the meeting
+    pub from_ms: Option<u64>,
+    /// Only segments before this offset into the meeting
+    pub to_ms: Option<u64>,
+    /// Only segments added after this sequence number (from a previous page)
+    pub since_seq: Option<u64>,
+    /// Only segments in this language (e.g. "de")
+    pub language: Option<String>,
+}
+
+impl TranscriptQuery {
+    /// Whether any paging or filter was asked for
+    pub fn is_paged(&self) -> bool {
+        self.offset.is_some()
+            || self.limit.is_some()
+            || self.since.is_some()
+            || self.from_ms.is_some()
+            || self.to_ms.is_some()
+            || self.since_seq.is_some()
+            || self.language.is_some()
+    }
+}
+
+/// One page of a transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptPage {
+    pub segments: Vec<TranscriptSegment>,
+    /// Segments matching the filters, across all pages
+    pub total: usize,
+    pub offset: usize,
+    /// Offset of the next page (None on the last page)
+    pub next_offset: Option<usize>,
+    /// Latest sequence number; pass as `since_seq` to poll for changes
+    pub sequence: u64,
+}
+
+/// Segments added or changed since a cursor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptUpdate {
+    /// New and changed segments, in time order
+    pub segments: Vec<TranscriptSegment>,
+    /// Cursor for the next call (the latest sequence number)
+    pub cursor: u64,
+}
+
+/// Transcript segments in time order, each numbered in the order it arrived
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptLog {
+    entries: Vec<(u64, TranscriptSegment)>,
+    sequence: u64,
+}
+
+impl TranscriptLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A finished transcript, numbered in order (the first segment is 1)
+    pub fn from_segments(segments: Vec<TranscriptSegment>) -> Self {
+        let mut log = Self::new();
+        for segment in segments {
+            log.push(segment);
+        }
+        log
+    }
+
+    /// Append a segment, returning its sequence number
+    pub fn push(&mut self, segment: TranscriptSegment) -> u64 {
+        self.sequence += 1;
+        self.entries.push((self.sequence, segment));
+        self.sequence
+    }
+
+    /// Insert a segment at its timestamp (see [`super::insert_by_timestamp`]),
+    /// returning its sequence number
+    pub fn insert_by_timestamp(&mut self, segment: TranscriptSegment) -> u64 {
+        // After any segments with the same timestamp, as insert_by_timestamp does
+        let index = self
+            .entries
+            .partition_point(|(_, s)| s.timestamp <= segment.timestamp);
+
+        self.sequence += 1;
+        self.entries.insert(index, (self.sequence, segment));
+        self.sequence
+    }
+
+    /// Replace the segment numbered `sequence` (e.g. once it is translated),
+    /// returning its new sequence number so pollers see the change
+    ///
+    /// None if no segment has that number.
+    pub fn replace(&mut self, sequence: u64, segment: TranscriptSegment) -> Option<u64> {
+        let entry = self.entries.iter_mut().find(|(seq, _)| *seq == sequence)?;
+        self.sequence += 1;
+        *entry = (self.sequence, segment);
+        Some(self.sequence)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sequence number of the latest segment (0 when empty)
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// All segments, in time order
+    pub fn segments(&self) -> Vec<TranscriptSegment> {
+        self.entries
+            .iter()
+            .map(|(_, segment)| segment.clone())
+            .collect()
+    }
+
+    /// Segments added or changed after sequence number `cursor` (0 = all)
+    pub fn changes_since(&self, cursor: u64) -> TranscriptUpdate {
+        TranscriptUpdate {
+            segments: self.segments_since(cursor).cloned().collect(),
+            cursor: self.sequence,
+        }
+    }
+
+    /// Segments numbered after `cursor`, without cloning them
+    fn segments_since(&self, cursor: u64) -> impl Iterator<Item = &TranscriptSegment> {
+        self.entries
+            .iter()
+            .filter(move |(seq, _)| *seq > cursor)
+            .map(|(_, segment)| segment)
+    }
+
+    /// Segments matching `query`, at most `limit` of them
+    ///
+    /// `started_at` is the meeting start that `from_ms`/`to_ms` count from.
+    pub fn page(
+        &self,
+        started_at: DateTime<Utc>,
+        query: &TranscriptQuery,
+        limit: usize,
+    ) -> TranscriptPage {
+        let matching: Vec<&TranscriptSegment> = self
+            .segments_since(query.since_seq.unwrap_or(0))
+            .filter(|segment| query.since.is_none_or(|since| segment.timestamp > since))
+            .filter(|segment| {
+                let offset_ms = segment
+                    .timestamp
+                    .signed_duration_since(started_at)
+                    .num_milliseconds();
+                query.from_ms.is_none_or(|from| offset_ms >= from as i64)
+                    && query.to_ms.is_none_or(|to| offset_ms < to as i64)
+            })
+            .filter(|segment| {
+                query.language.as_deref().is_none_or(|language| {
+                    segment.language.as_deref() == normalize_language(language).as_deref()
+                })
+            })
+            .collect();
+
+        let total = matching.len();
+        let offset = query.offset.unwrap_or(0).min(total);
+        let segments: Vec<TranscriptSegment> = matching
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect();
+        let end = offset + segments.len();
+
+        TranscriptPage {
+            segments,
+            total,
+            offset,
+            next_offset: (end < total).then_some(end),
+            sequence: self.sequence,
+        }
+    }
+}
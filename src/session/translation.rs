@@ -0,0 +1,222 @@
+use super::language::normalize_language;
+use super::stats::TranscriptSegment;
+use crate::nats::NatsClient;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Text of a segment translated into another language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Translation {
+    /// Language the text was translated into (ISO 639-1, e.g. "en")
+    pub language: String,
+    pub text: String,
+}
+
+/// Translation of final transcript segments as they arrive
+///
+/// Requests go to the NATS subject if one is set, otherwise to the HTTP
+/// endpoint; with neither (or no target language) nothing is translated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationConfig {
+    /// Language segments are translated into (e.g. "en")
+    pub target_language: Option<String>,
+    /// NATS subject answering translation requests
+    pub subject: Option<String>,
+    /// HTTP endpoint answering translation requests
+    pub url: Option<String>,
+    /// How long to wait for each translation, in milliseconds
+    pub timeout_ms: u64,
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            target_language: None,
+            subject: None,
+            url: None,
+            timeout_ms: 10_000,
+        }
+    }
+}
+
+/// Something run on each final segment after it is stored, e.g. to
+/// translate it
+#[async_trait]
+pub trait TranscriptProcessor: Send + Sync {
+    /// Processor name, for logging
+    fn name(&self) -> &str;
+
+    /// Process a final segment, returning it changed (None = unchanged)
+    async fn process(
+        &self,
+        session_id: &str,
+        segment: &TranscriptSegment,
+    ) -> Result<Option<TranscriptSegment>>;
+}
+
+/// Body of a translation request, over NATS or HTTP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationRequest {
+    pub session_id: String,
+    pub text: String,
+    /// Language of the text, if known
+    pub language: Option<String>,
+    pub target_language: String,
+}
+
+/// Reply to a translation request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationReply {
+    pub text: String,
+}
+
+/// Where translation requests are sent
+enum Translator {
+    Nats {
+        client: Arc<NatsClient>,
+        subject: String,
+    },
+    Http {
+        client: reqwest::Client,
+        url: String,
+    },
+}
+
+/// Stores a translation alongside each final segment that is not already
+/// in the target language
+pub struct TranslationProcessor {
+    target_language: String,
+    translator: Translator,
+    timeout: Duration,
+}
+
+impl TranslationProcessor {
+    /// Translate through a NATS request/reply `subject`
+    pub fn nats(
+        target_language: &str,
+        client: Arc<NatsClient>,
+        subject: impl Into<String>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            target_language: normalize_language(target_language)
+                .unwrap_or_else(|| target_language.to_string()),
+            translator: Translator::Nats {
+                client,
+                subject: subject.into(),
+            },
+            timeout,
+        }
+    }
+
+    /// Translate by POSTing to an HTTP endpoint
+    pub fn http(target_language: &str, url: impl Into<String>, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            target_language: normalize_language(target_language)
+                .unwrap_or_else(|| target_language.to_string()),
+            translator: Translator::Http {
+                client: reqwest::Client::builder().timeout(timeout).build()?,
+                url: url.into(),
+            },
+            timeout,
+        })
+    }
+
+    /// The processor `config` selects (None when translation is off)
+    ///
+    /// `nats_client` is the session's NATS connection, if it has one; a
+    /// subject without one connects to `nats_url`.
+    pub async fn from_config(
+        config: &TranslationConfig,
+        session_id: &str,
+        nats_client: Option<Arc<NatsClient>>,
+        nats_url: &str,
+    ) -> Result<Option<Self>> {
+        let Some(target_language) = config.target_language.as_deref() else {
+            return Ok(None);
+        };
+        let timeout = Duration::from_millis(config.timeout_ms);
+
+        match (&config.subject, &config.url) {
+            (Some(subject), _) => {
+                let client = match nats_client {
+                    Some(client) => client,
+                    None => Arc::new(
+                        NatsClient::connect(nats_url, session_id.to_string())
+                            .await
+                            .context("Failed to connect to NATS for translation")?,
+                    ),
+                };
+                Ok(Some(Self::nats(target_language, client, subject, timeout)))
+            }
+            (None, Some(url)) => Ok(Some(Self::http(target_language, url, timeout)?)),
+            (None, None) => Ok(None),
+        }
+    }
+
+    pub fn target_language(&self) -> &str {
+        &self.target_language
+    }
+
+    async fn translate(&self, request: &TranslationRequest) -> Result<TranslationReply> {
+        match &self.translator {
+            Translator::Nats { client, subject } => {
+                client.request_json(subject, request, self.timeout).await
+            }
+            Translator::Http { client, url } => client
+                .post(url)
+                .json(request)
+                .send()
+                .await
+                .with_context(|| format!("Failed to call {}", url))?
+                .error_for_status()
+                .with_context(|| format!("Translator {} returned an error", url))?
+                .json()
+                .await
+                .with_context(|| format!("Invalid response from translator {}", url)),
+        }
+    }
+}
+
+#[async_trait]
+impl TranscriptProcessor for TranslationProcessor {
+    fn name(&self) -> &str {
+        match &self.translator {
+            Translator::Nats { subject, .. } => subject,
+            Translator::Http { url, .. } => url,
+        }
+    }
+
+    async fn process(
+        &self,
+        session_id: &str,
+        segment: &TranscriptSegment,
+    ) -> Result<Option<TranscriptSegment>> {
+        if segment.partial
+            || segment.text.trim().is_empty()
+            || segment.language.as_deref() == Some(self.target_language.as_str())
+        {
+            return Ok(None);
+        }
+
+        let request = TranslationRequest {
+            session_id: session_id.to_string(),
+            text: segment.text.clone(),
+            language: segment.language.clone(),
+            target_language: self.target_language.clone(),
+        };
+        let reply = self.translate(&request).await?;
+
+        Ok(Some(TranscriptSegment {
+            translation: Some(Translation {
+                language: self.target_language.clone(),
+                text: reply.text,
+            }),
+            ..segment.clone()
+        }))
+    }
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+/// When published audio is marked as the end of an utterance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UtteranceBoundary {
+    /// Only the final frame at the end of the session
+    #[default]
+    None,
+    /// After a pause in speech (or `max_utterance_secs` without one)
+    Silence,
+    /// Every `interval_secs` of audio
+    Interval,
+}
+
+/// Utterance-boundary policy for published audio
+///
+/// Boundaries set `segment_final` on the published message so the STT
+/// service finalizes what it has heard so far instead of waiting for the
+/// session to stop.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UtteranceConfig {
+    pub boundary: UtteranceBoundary,
+    /// Frames quieter than this (RMS, dBFS) count as silence
+    pub silence_threshold_dbfs: f32,
+    /// Pause that ends an utterance, in milliseconds
+    pub silence_ms: u64,
+    /// Longest utterance in silence mode before a boundary is forced (0 = no limit)
+    pub max_utterance_secs: u64,
+    /// Boundary spacing in interval mode
+    pub interval_secs: u64,
+}
+
+impl Default for UtteranceConfig {
+    fn default() -> Self {
+        Self {
+            boundary: UtteranceBoundary::None,
+            silence_threshold_dbfs: -45.0,
+            silence_ms: 700,
+            max_utterance_secs: 30,
+            interval_secs: 10,
+        }
+    }
+}
+
+/// Finds utterance boundaries in a stream of frames
+#[derive(Debug, Clone)]
+pub struct UtteranceTracker {
+    config: UtteranceConfig,
+    /// Audio since the last boundary
+    utterance_ms: u64,
+    /// Trailing silence
+    silent_ms: u64,
+    /// Whether anything above the threshold was heard since the last boundary
+    heard_speech: bool,
+}
+
+impl UtteranceTracker {
+    pub fn new(config: UtteranceConfig) -> Self {
+        Self {
+            config,
+            utterance_ms: 0,
+            silent_ms: 0,
+            heard_speech: false,
+        }
+    }
+
+    /// Frames quieter than this (RMS, dBFS) count as silence
+    pub fn silence_threshold_dbfs(&self) -> f32 {
+        self.config.silence_threshold_dbfs
+    }
+
+    /// Feed one interleaved frame; returns true if it ends an utterance
+    pub fn push(&mut self, samples: &[i16], sample_rate: u32, channels: u16) -> bool {
+        let frames = samples.len() / channels.max(1) as usize;
+        let frame_ms = frames as u64 * 1000 / sample_rate.max(1) as u64;
+        self.utterance_ms += frame_ms;
+
+        let boundary = match self.config.boundary {
+            UtteranceBoundary::None => false,
+            UtteranceBoundary::Interval => {
+                self.utterance_ms >= self.config.interval_secs.max(1) * 1000
+            }
+            UtteranceBoundary::Silence => {
+                if rms_dbfs(samples) < self.config.silence_threshold_dbfs {
+                    self.silent_ms += frame_ms;
+                } else {
+                    self.silent_ms = 0;
+                    self.heard_speech = true;
+                }
+
+                // Only speech needs finalizing; a long silence is one boundary
+                let max_ms = self.config.max_utterance_secs * 1000;
+                self.heard_speech
+                    && (self.silent_ms >= self.config.silence_ms
+                        || (max_ms > 0 && self.utterance_ms >= max_ms))
+            }
+        };
+
+        if boundary {
+            self.utterance_ms = 0;
+            self.heard_speech = false;
+        }
+        boundary
+    }
+}
+
+/// RMS level of 16-bit samples in dBFS (-inf for digital silence)
+pub fn rms_dbfs(samples: &[i16]) -> f32 {
+    if samples.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+
+    let sum: f64 = samples
+        .iter()
+        .map(|&s| {
+            let s = s as f64 / 32768.0;
+            s * s
+        })
+        .sum();
+    let rms = (sum / samples.len() as f64).sqrt();
+    20.0 * rms.log10() as f32
+}
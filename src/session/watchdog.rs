@@ -0,0 +1,139 @@
+use crate::audio::AudioStreamSource;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How a session notices capture that has stopped delivering frames
+///
+/// A backend can stop without failing (ScreenCaptureKit going quiet after a
+/// permission or device change), leaving the session recording nothing
+/// while it looks healthy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    /// Seconds without a frame from a capture source before it counts as
+    /// stalled (0 = no watchdog)
+    pub stall_secs: u64,
+    /// Restart a source's capture when it stalls; otherwise only warn. A
+    /// session's only source is restarted as if capture had failed
+    /// (subject to the session's restart policy).
+    pub restart: bool,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            stall_secs: 10,
+            restart: false,
+        }
+    }
+}
+
+impl WatchdogConfig {
+    /// Time without frames that counts as a stall (None = no watchdog)
+    pub fn stall_timeout(&self) -> Option<Duration> {
+        (self.stall_secs > 0).then(|| Duration::from_secs(self.stall_secs))
+    }
+}
+
+/// Tracks when each capture source last delivered a frame
+///
+/// Sources are watched separately, so one silent microphone is noticed
+/// while the others keep capturing.
+#[derive(Debug, Clone)]
+pub struct CaptureWatchdog {
+    timeout: Option<Duration>,
+    sources: HashMap<AudioStreamSource, SourceWatch>,
+}
+
+#[derive(Debug, Clone)]
+struct SourceWatch {
+    last_frame: Instant,
+    /// Whether the current silence was already reported
+    stalled: bool,
+}
+
+impl SourceWatch {
+    fn new() -> Self {
+        Self {
+            last_frame: Instant::now(),
+            stalled: false,
+        }
+    }
+}
+
+impl CaptureWatchdog {
+    /// A watchdog over `sources`, counting from now
+    pub fn new(
+        timeout: Option<Duration>,
+        sources: impl IntoIterator<Item = AudioStreamSource>,
+    ) -> Self {
+        Self {
+            timeout,
+            sources: sources
+                .into_iter()
+                .map(|source| (source, SourceWatch::new()))
+                .collect(),
+        }
+    }
+
+    /// Watch `source`, counting from now (e.g. once it is restarted)
+    pub fn watch(&mut self, source: AudioStreamSource) {
+        self.sources.insert(source, SourceWatch::new());
+    }
+
+    /// Stop watching `source`, e.g. a replayed file that has ended
+    pub fn unwatch(&mut self, source: AudioStreamSource) {
+        self.sources.remove(&source);
+    }
+
+    /// When the first source counts as stalled if no frame arrives from it
+    /// (None once every stall is reported, or with no watchdog)
+    pub fn deadline(&self) -> Option<Instant> {
+        let timeout = self.timeout?;
+        self.sources
+            .values()
+            .filter(|watch| !watch.stalled)
+            .map(|watch| watch.last_frame + timeout)
+            .min()
+    }
+
+    /// Record a frame from `source`, returning how long it had been silent
+    /// if it was reported stalled (sources not watched are ignored)
+    pub fn frame(&mut self, source: AudioStreamSource) -> Option<Duration> {
+        let watch = self.sources.get_mut(&source)?;
+        let silent = watch.last_frame.elapsed();
+        watch.last_frame = Instant::now();
+        std::mem::take(&mut watch.stalled).then_some(silent)
+    }
+
+    /// Mark the sources past their deadline stalled, returning each with
+    /// how long it has been silent
+    pub fn stall(&mut self) -> Vec<(AudioStreamSource, Duration)> {
+        let Some(timeout) = self.timeout else {
+            return Vec::new();
+        };
+
+        let now = Instant::now();
+        let mut stalled: Vec<_> = self
+            .sources
+            .iter_mut()
+            .filter(|(_, watch)| !watch.stalled && now >= watch.last_frame + timeout)
+            .map(|(&source, watch)| {
+                watch.stalled = true;
+                (source, now - watch.last_frame)
+            })
+            .collect();
+        stalled.sort_by_key(|(source, _)| source.to_string());
+        stalled
+    }
+}
+
+/// Resolves at `deadline`, or never without one
+pub(super) async fn stall_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
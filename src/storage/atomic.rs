@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Suffix of the temporary file a write goes to before it is renamed
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// Replace `path` with `contents` so that a crash at any point leaves
+/// either the old file or the new one, never a mix or an empty file
+///
+/// The contents go to a temporary file beside `path` that is flushed to
+/// disk before being renamed over it, and the rename itself is made
+/// durable by syncing the directory.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let partial = partial_path(path)?;
+    let written = write_synced(&partial, contents.as_ref())
+        .and_then(|()| fs::rename(&partial, path))
+        .with_context(|| format!("Failed to write {}", path.display()));
+    if written.is_err() {
+        let _ = fs::remove_file(&partial);
+        return written;
+    }
+
+    sync_dir(path.parent().unwrap_or(Path::new(".")))
+        .with_context(|| format!("Failed to sync the directory of {}", path.display()))
+}
+
+/// Create `path` with `contents`, failing with `AlreadyExists` if it is
+/// already there
+///
+/// Like [`write_atomic`], but never replaces an existing file: the flushed
+/// temporary file is hard-linked into place, so `path` appears complete or
+/// not at all, and of two concurrent creators exactly one succeeds.
+pub fn create_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let partial = partial_path(path).map_err(std::io::Error::other)?;
+    let created =
+        write_synced(&partial, contents.as_ref()).and_then(|()| fs::hard_link(&partial, path));
+    let _ = fs::remove_file(&partial);
+    created?;
+
+    sync_dir(path.parent().unwrap_or(Path::new(".")))
+}
+
+/// The file a leftover temporary file named `name` was replacing, if it
+/// is one (e.g. after a crash mid-write)
+pub fn partial_target(name: &str) -> Option<&str> {
+    let (target, _unique) = name
+        .strip_prefix('.')?
+        .strip_suffix(PARTIAL_SUFFIX)?
+        .rsplit_once('.')?;
+    Some(target)
+}
+
+/// Hidden temporary file beside `path`, unique to this write so concurrent
+/// writers never share one
+fn partial_path(path: &Path) -> Result<std::path::PathBuf> {
+    static WRITES: AtomicU64 = AtomicU64::new(0);
+
+    let name = path
+        .file_name()
+        .with_context(|| format!("Not a file path: {}", path.display()))?;
+    Ok(path.with_file_name(format!(
+        ".{}.{}-{}{}",
+        name.to_string_lossy(),
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed),
+        PARTIAL_SUFFIX
+    )))
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    fs::File::open(dir)?.sync_all()
+}
+
+/// Directories can't be opened for syncing here; the rename is as durable
+/// as the filesystem makes it
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use tokio::fs;
+use tracing::warn;
+
+use super::{validate_meeting_id, write_atomic, MeetingRecord, MeetingSummary, SearchHit, Storage};
+
+/// File name of the meeting document inside each meeting directory
+const MEETING_FILE: &str = "meeting.json";
+
+/// Stores each meeting as `<root>/<meeting_id>/meeting.json`
+pub struct FilesystemStorage {
+    root: PathBuf,
+}
+
+impl FilesystemStorage {
+    /// Create storage rooted at `root` (created on first save)
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn meeting_dir(&self, meeting_id: &str) -> Result<PathBuf> {
+        validate_meeting_id(meeting_id)?;
+        Ok(self.root.join(meeting_id))
+    }
+
+    /// Load every readable meeting under the root
+    async fn load_all(&self) -> Result<Vec<MeetingRecord>> {
+        let mut entries = match fs::read_dir(&self.root).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).context("Failed to read storage directory"),
+        };
+
+        let mut records = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let Some(meeting_id) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+
+            match self.get_meeting(&meeting_id).await {
+                Ok(Some(record)) => records.push(record),
+                Ok(None) => {}
+                Err(e) => warn!("Skipping unreadable meeting {}: {}", meeting_id, e),
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+#[async_trait::async_trait]
+impl Storage for FilesystemStorage {
+    async fn save_meeting(&self, record: &MeetingRecord) -> Result<()> {
+        let dir = self.meeting_dir(&record.meeting_id)?;
+        fs::create_dir_all(&dir)
+            .await
+            .with_context(|| format!("Failed to create meeting directory: {}", dir.display()))?;
+
+        let json = serde_json::to_vec_pretty(record).context("Failed to serialize meeting")?;
+
+        // A crash mid-save must not cost the only copy of the transcript
+        let path = dir.join(MEETING_FILE);
+        tokio::task::spawn_blocking(move || write_atomic(&path, json))
+            .await
+            .context("Meeting save task panicked")?
+    }
+
+    async fn get_meeting(&self, meeting_id: &str) -> Result<Option<MeetingRecord>> {
+        let path = self.meeting_dir(meeting_id)?.join(MEETING_FILE);
+
+        let json = match fs::read(&path).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        let record = serde_json::from_slice(&json)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(record))
+    }
+
+    async fn list_meetings(&self, limit: usize, offset: usize) -> Result<Vec<MeetingSummary>> {
+        let mut summaries: Vec<MeetingSummary> = self
+            .load_all()
+            .await?
+            .iter()
+            .map(MeetingSummary::from)
+            .collect();
+
+        summaries.sort_by_key(|m| std::cmp::Reverse(m.started_at));
+        Ok(summaries.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn search_transcripts(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // No index: scan every transcript
+        let mut records = self.load_all().await?;
+        records.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+
+        let hits = records
+            .iter()
+            .flat_map(|record| {
+                record.transcript.iter().filter_map(|segment| {
+                    let text = segment.text.to_lowercase();
+                    words.iter().all(|w| text.contains(w)).then(|| SearchHit {
+                        meeting_id: record.meeting_id.clone(),
+                        title: record.title.clone(),
+                        started_at: record.started_at,
+                        text: segment.text.clone(),
+                        timestamp: segment.timestamp,
+                    })
+                })
+            })
+            .take(limit)
+            .collect();
+
+        Ok(hits)
+    }
+
+    async fn delete_meeting(&self, meeting_id: &str) -> Result<bool> {
+        let dir = self.meeting_dir(meeting_id)?;
+
+        match fs::remove_file(dir.join(MEETING_FILE)).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e).context("Failed to delete meeting file"),
+        }
+
+        // Leave the directory if other files (e.g. audio chunks) remain
+        let _ = fs::remove_dir(&dir).await;
+        Ok(true)
+    }
+
+    async fn expired_meetings(&self, now: DateTime<Utc>) -> Result<Vec<String>> {
+        Ok(self
+            .load_all()
+            .await?
+            .into_iter()
+            .filter(|record| record.is_expired(now))
+            .map(|record| record.meeting_id)
+            .collect())
+    }
+
+    fn meeting_file(&self, meeting_id: &str) -> Option<PathBuf> {
+        Some(self.meeting_dir(meeting_id).ok()?.join(MEETING_FILE))
+    }
+
+    fn name(&self) -> &str {
+        "Filesystem"
+    }
+}
@@ -0,0 +1,76 @@
+use super::validate_meeting_id;
+use anyhow::Result;
+use chrono::NaiveDate;
+
+/// Longest title slug kept in a generated meeting ID
+const MAX_SLUG_LEN: usize = 48;
+
+/// Longest meeting ID accepted for a new recording
+pub const MAX_MEETING_ID_LEN: usize = 128;
+
+/// Suffixed candidates tried before a generated ID gives up on readability
+const MAX_COLLISION_SUFFIX: usize = 99;
+
+/// Lowercase, dash-separated form of a title ("Product Sync!" -> "product-sync")
+///
+/// Only ASCII letters and digits are kept; everything else separates words.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for word in title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let separator = usize::from(!slug.is_empty());
+        if slug.len() + separator + word.len() > MAX_SLUG_LEN {
+            // A single overlong word is cut rather than dropped
+            if slug.is_empty() {
+                slug.push_str(&word[..MAX_SLUG_LEN].to_ascii_lowercase());
+            }
+            break;
+        }
+        if separator == 1 {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug
+}
+
+/// Readable meeting ID for a title recorded on `date` (e.g. "2025-06-03-product-sync")
+///
+/// Titles with nothing usable in them fall back to "meeting".
+pub fn meeting_slug(date: NaiveDate, title: &str) -> String {
+    let slug = slugify(title);
+    let slug = if slug.is_empty() { "meeting" } else { &slug };
+    format!("{}-{}", date.format("%Y-%m-%d"), slug)
+}
+
+/// IDs to try, in order, for a generated ID: `base`, then `base-2`,
+/// `base-3`, ... up to `base-99`
+pub fn candidate_ids(base: &str) -> impl Iterator<Item = String> + '_ {
+    std::iter::once(base.to_string())
+        .chain((2..=MAX_COLLISION_SUFFIX).map(move |n| format!("{}-{}", base, n)))
+}
+
+/// Check a meeting ID given for a new recording
+///
+/// On top of [`validate_meeting_id`], the ID must be usable as a file name
+/// on every platform and as a single NATS subject token (no `.`, `*`, `>`
+/// or whitespace), since sessions publish on `meetings.*.{id}`.
+pub fn validate_new_meeting_id(meeting_id: &str) -> Result<()> {
+    validate_meeting_id(meeting_id)?;
+    if meeting_id.len() > MAX_MEETING_ID_LEN {
+        anyhow::bail!(
+            "Meeting ID is longer than {} characters",
+            MAX_MEETING_ID_LEN
+        );
+    }
+    if let Some(c) = meeting_id.chars().find(|&c| {
+        c.is_whitespace()
+            || c.is_control()
+            || matches!(c, '.' | '*' | '>' | ':' | '<' | '"' | '|' | '?')
+    }) {
+        anyhow::bail!("Meeting ID {:?} contains {:?}", meeting_id, c);
+    }
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use super::MeetingRecord;
+use crate::audio::file_sha256;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+
+/// Result of checking one audio chunk against its stored checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkIntegrity {
+    /// The file matches its checksum
+    Ok,
+    /// The file's contents differ from when it was written
+    Corrupted,
+    /// The file is gone
+    Missing,
+    /// The chunk has no stored checksum (recorded before checksums were kept)
+    Unverified,
+}
+
+/// One chunk's integrity check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkCheck {
+    pub chunk_index: usize,
+    pub file_path: String,
+    pub status: ChunkIntegrity,
+    /// Checksum stored when the chunk was written
+    pub expected_sha256: Option<String>,
+    /// Checksum of the file as it is now (None if it couldn't be read)
+    pub actual_sha256: Option<String>,
+    /// Why the file couldn't be read, other than being missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Integrity of a stored meeting's audio chunks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub meeting_id: String,
+    pub checked_at: DateTime<Utc>,
+    /// No chunk is corrupted or missing
+    pub intact: bool,
+    pub corrupted: usize,
+    pub missing: usize,
+    pub unverified: usize,
+    pub chunks: Vec<ChunkCheck>,
+}
+
+impl IntegrityReport {
+    /// Recompute the checksum of every chunk of `record` and compare it with
+    /// the one stored when the chunk was written (reads every file, so this
+    /// blocks)
+    pub fn check(record: &MeetingRecord) -> Self {
+        let chunks: Vec<ChunkCheck> = record
+            .chunks
+            .iter()
+            .map(|chunk| {
+                let (actual, status, error) = match file_sha256(&chunk.file_path) {
+                    Ok(actual) => {
+                        let status = match &chunk.sha256 {
+                            None => ChunkIntegrity::Unverified,
+                            Some(expected) if *expected == actual => ChunkIntegrity::Ok,
+                            Some(_) => ChunkIntegrity::Corrupted,
+                        };
+                        (Some(actual), status, None)
+                    }
+                    Err(e) if e.kind() == ErrorKind::NotFound => {
+                        (None, ChunkIntegrity::Missing, None)
+                    }
+                    // Unreadable is as good as corrupted
+                    Err(e) => (None, ChunkIntegrity::Corrupted, Some(e.to_string())),
+                };
+                ChunkCheck {
+                    chunk_index: chunk.chunk_index,
+                    file_path: chunk.file_path.display().to_string(),
+                    status,
+                    expected_sha256: chunk.sha256.clone(),
+                    actual_sha256: actual,
+                    error,
+                }
+            })
+            .collect();
+
+        let count = |status| chunks.iter().filter(|c| c.status == status).count();
+        let corrupted = count(ChunkIntegrity::Corrupted);
+        let missing = count(ChunkIntegrity::Missing);
+        let unverified = count(ChunkIntegrity::Unverified);
+        Self {
+            meeting_id: record.meeting_id.clone(),
+            checked_at: Utc::now(),
+            intact: corrupted == 0 && missing == 0,
+            corrupted,
+            missing,
+            unverified,
+            chunks,
+        }
+    }
+}
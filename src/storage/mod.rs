@@ -0,0 +1,245 @@
+//! Persistence for finished meetings
+//!
+//! This module abstracts where transcripts and meeting metadata are stored:
+//! - `Storage` trait implemented by each backend
+//! - Filesystem backend (one JSON document per meeting)
+//! - SQLite backend (single database file, indexed for listing and search)
+//! - `StorageFactory` selecting the backend from `StorageConfig`
+//! - `RetentionWorker` deleting meetings once their TTL runs out
+//! - Sealing meeting content to a client-held key
+//! - Readable meeting IDs generated from titles
+//! - Checking stored audio chunks against their checksums
+//! - Crash-safe atomic file writes, used by every file-based persistence path
+
+mod atomic;
+mod filesystem;
+mod ids;
+mod integrity;
+mod retention;
+mod sealed;
+mod sqlite;
+
+pub use atomic::{create_atomic, partial_target, write_atomic};
+pub use filesystem::FilesystemStorage;
+pub use ids::{candidate_ids, meeting_slug, slugify, validate_new_meeting_id, MAX_MEETING_ID_LEN};
+pub use integrity::{ChunkCheck, ChunkIntegrity, IntegrityReport};
+pub use retention::{ExpiredMeeting, RetentionWorker};
+pub use sealed::{
+    parse_public_key, remove_unsealed, SealedContent, SealedPayload, SEALED_ALGORITHM,
+};
+pub use sqlite::SqliteStorage;
+
+use crate::audio::ChunkMetadata;
+use crate::config::{StorageBackend, StorageConfig};
+use crate::session::{HostInfo, Marker, SessionStats, TranscriptSegment};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Everything persisted for a finished meeting
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingRecord {
+    /// Meeting identifier
+    pub meeting_id: String,
+
+    /// Optional meeting title
+    pub title: Option<String>,
+
+    /// Session profile the meeting was recorded with
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Machine the meeting was recorded on
+    #[serde(default)]
+    pub host: Option<HostInfo>,
+
+    /// When recording started
+    pub started_at: DateTime<Utc>,
+
+    /// When recording stopped
+    pub ended_at: DateTime<Utc>,
+
+    /// Final session statistics
+    pub stats: SessionStats,
+
+    /// Final (non-partial) transcript segments
+    pub transcript: Vec<TranscriptSegment>,
+
+    /// Timeline markers
+    pub markers: Vec<Marker>,
+
+    /// Recorded audio chunks
+    #[serde(default)]
+    pub chunks: Vec<ChunkMetadata>,
+
+    /// Manual transcript corrections, oldest first
+    #[serde(default)]
+    pub edits: Vec<SegmentEdit>,
+
+    /// When the retention worker deletes the meeting (None = kept until deleted)
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// Transcript, markers, and edits encrypted to the client's key (these
+    /// fields are then empty)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed: Option<SealedContent>,
+}
+
+/// A manual correction to a transcript segment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentEdit {
+    /// Index of the segment in the transcript at the time of the edit
+    pub segment_index: usize,
+
+    /// When the edited segment was spoken
+    pub segment_timestamp: DateTime<Utc>,
+
+    /// Text before the edit
+    pub original: String,
+
+    /// Text after the edit
+    pub edited: String,
+
+    /// Who made the edit, if the client said
+    pub editor: Option<String>,
+
+    /// When the edit was made
+    pub edited_at: DateTime<Utc>,
+}
+
+impl MeetingRecord {
+    /// Whether the meeting's TTL has run out
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Replace a segment's text, recording the change in `edits`
+    pub fn edit_segment(
+        &mut self,
+        index: usize,
+        text: &str,
+        editor: Option<String>,
+    ) -> Result<&SegmentEdit> {
+        let text = text.trim();
+        if text.is_empty() {
+            anyhow::bail!("Segment text cannot be empty");
+        }
+
+        let Some(segment) = self.transcript.get_mut(index) else {
+            anyhow::bail!(
+                "Meeting has no segment {} ({} segments)",
+                index,
+                self.transcript.len()
+            );
+        };
+
+        let original = std::mem::replace(&mut segment.text, text.to_string());
+        self.edits.push(SegmentEdit {
+            segment_index: index,
+            segment_timestamp: segment.timestamp,
+            original,
+            edited: text.to_string(),
+            editor,
+            edited_at: Utc::now(),
+        });
+
+        Ok(&self.edits[self.edits.len() - 1])
+    }
+}
+
+/// Lightweight listing entry for a stored meeting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingSummary {
+    pub meeting_id: String,
+    pub title: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub duration_secs: f64,
+    pub transcript_segments_count: usize,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A transcript segment matching a search query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub meeting_id: String,
+    pub title: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// Matching segment text
+    pub text: String,
+    /// When the matching segment was received
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<&MeetingRecord> for MeetingSummary {
+    fn from(record: &MeetingRecord) -> Self {
+        Self {
+            meeting_id: record.meeting_id.clone(),
+            title: record.title.clone(),
+            started_at: record.started_at,
+            duration_secs: record.stats.duration_secs,
+            transcript_segments_count: record.transcript.len(),
+            expires_at: record.expires_at,
+        }
+    }
+}
+
+/// Meeting persistence backend
+///
+/// Implementations:
+/// - Filesystem: JSON documents under a directory
+/// - SQLite: embedded database file
+#[async_trait::async_trait]
+pub trait Storage: Send + Sync {
+    /// Insert or replace a meeting
+    async fn save_meeting(&self, record: &MeetingRecord) -> Result<()>;
+
+    /// Load a meeting by ID
+    async fn get_meeting(&self, meeting_id: &str) -> Result<Option<MeetingRecord>>;
+
+    /// List stored meetings, newest first
+    async fn list_meetings(&self, limit: usize, offset: usize) -> Result<Vec<MeetingSummary>>;
+
+    /// Find transcript segments containing every word of `query`
+    async fn search_transcripts(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>>;
+
+    /// Delete a meeting; returns whether it existed
+    async fn delete_meeting(&self, meeting_id: &str) -> Result<bool>;
+
+    /// IDs of meetings whose expiry is at or before `now`
+    async fn expired_meetings(&self, now: DateTime<Utc>) -> Result<Vec<String>>;
+
+    /// File a meeting is stored in, for people browsing their meetings
+    /// (None when it isn't a file of its own)
+    fn meeting_file(&self, _meeting_id: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// Get backend name for logging
+    fn name(&self) -> &str;
+}
+
+/// Storage backend factory
+pub struct StorageFactory;
+
+impl StorageFactory {
+    /// Create the storage backend selected in configuration
+    pub fn create(config: &StorageConfig) -> Result<Arc<dyn Storage>> {
+        let path = config.resolved_path();
+
+        match config.backend {
+            StorageBackend::Filesystem => Ok(Arc::new(FilesystemStorage::new(path))),
+            StorageBackend::Sqlite => Ok(Arc::new(SqliteStorage::open(path.join("meetings.db"))?)),
+        }
+    }
+}
+
+/// Reject meeting IDs that could escape the storage directory
+pub fn validate_meeting_id(meeting_id: &str) -> Result<()> {
+    if meeting_id.is_empty() || meeting_id.starts_with('.') || meeting_id.contains(['/', '\\']) {
+        anyhow::bail!("Invalid meeting ID: {:?}", meeting_id);
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use super::Storage;
+use crate::clock::{system_clock, SharedClock};
+use crate::session::AudioRetention;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs;
+use tracing::{error, info};
+
+/// A meeting removed by the retention worker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpiredMeeting {
+    pub meeting_id: String,
+    /// When the meeting's TTL ran out
+    pub expired_at: DateTime<Utc>,
+    /// When it was deleted
+    pub deleted_at: DateTime<Utc>,
+    /// Audio files deleted with it
+    pub audio_files: usize,
+}
+
+/// Deletes meetings whose TTL has run out, along with their audio
+///
+/// After each deletion the worker checks that the record can no longer be
+/// loaded and that no audio is left, so an expired meeting is either gone
+/// or reported as an error.
+pub struct RetentionWorker {
+    storage: Arc<dyn Storage>,
+    /// Directory holding each meeting's audio in `<recordings>/<meeting_id>`
+    recordings_path: PathBuf,
+    clock: SharedClock,
+}
+
+impl RetentionWorker {
+    pub fn new(storage: Arc<dyn Storage>, recordings_path: PathBuf) -> Self {
+        Self {
+            storage,
+            recordings_path,
+            clock: system_clock(),
+        }
+    }
+
+    /// Take the time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sweep for expired meetings every `interval`, forever
+    pub async fn run(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.sweep(self.clock.now()).await {
+                error!("Failed to sweep expired meetings: {:#}", e);
+            }
+        }
+    }
+
+    /// Delete every meeting expired at `now`, returning those removed
+    ///
+    /// A meeting that fails to delete is logged and left for the next sweep.
+    pub async fn sweep(&self, now: DateTime<Utc>) -> Result<Vec<ExpiredMeeting>> {
+        let mut deleted = Vec::new();
+        for meeting_id in self.storage.expired_meetings(now).await? {
+            match self.expire(&meeting_id, now).await {
+                Ok(Some(expired)) => {
+                    info!(
+                        "Deleted expired meeting {} ({} audio files)",
+                        meeting_id, expired.audio_files
+                    );
+                    deleted.push(expired);
+                }
+                Ok(None) => {}
+                Err(e) => error!("Failed to delete expired meeting {}: {:#}", meeting_id, e),
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn expire(&self, meeting_id: &str, now: DateTime<Utc>) -> Result<Option<ExpiredMeeting>> {
+        // The expiry may have been extended since the meetings were listed
+        let Some(record) = self.storage.get_meeting(meeting_id).await? else {
+            return Ok(None);
+        };
+        let Some(expired_at) = record.expires_at.filter(|_| record.is_expired(now)) else {
+            return Ok(None);
+        };
+
+        let mut audio_files = 0;
+        for chunk in &record.chunks {
+            match fs::remove_file(&chunk.file_path).await {
+                Ok(()) => audio_files += 1,
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Failed to delete {}", chunk.file_path.display()))
+                }
+            }
+        }
+
+        let audio_dir = self.recordings_path.join(meeting_id);
+        let remaining = AudioRetention::check(false, &audio_dir, now)?;
+        match fs::remove_dir_all(&audio_dir).await {
+            Ok(()) => audio_files += remaining.audio_files,
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to delete {}", audio_dir.display()))
+            }
+        }
+
+        self.storage.delete_meeting(meeting_id).await?;
+
+        // Confirm nothing of the meeting is left
+        if self.storage.get_meeting(meeting_id).await?.is_some() {
+            anyhow::bail!("Meeting is still stored after deletion");
+        }
+        if AudioRetention::check(false, &audio_dir, now)?.audio_files > 0 {
+            anyhow::bail!("Audio is still stored in {}", audio_dir.display());
+        }
+        if let Some(chunk) = record.chunks.iter().find(|c| c.file_path.exists()) {
+            anyhow::bail!("Audio chunk {} still exists", chunk.file_path.display());
+        }
+
+        Ok(Some(ExpiredMeeting {
+            meeting_id: meeting_id.to_string(),
+            expired_at,
+            deleted_at: self.clock.now(),
+            audio_files,
+        }))
+    }
+}
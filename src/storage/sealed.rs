@@ -0,0 +1,170 @@
+use super::{write_atomic, MeetingRecord, SegmentEdit};
+use crate::session::{Marker, TranscriptSegment};
+use anyhow::{Context, Result};
+use base64::Engine;
+use crypto_box::aead::OsRng;
+use crypto_box::{PublicKey, SecretKey, KEY_SIZE};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tracing::{error, warn};
+
+/// Sealed box construction used for meeting content (libsodium `crypto_box_seal`)
+pub const SEALED_ALGORITHM: &str = "x25519-xsalsa20poly1305-sealed";
+
+/// File extension appended to encrypted audio chunks
+const SEALED_EXTENSION: &str = "sealed";
+
+/// Meeting content encrypted to a key only the client holds
+///
+/// `payload` is a base64 sealed box of the JSON-encoded [`SealedPayload`].
+/// Any libsodium binding can open it with `crypto_box_seal_open` and the
+/// client's key pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedContent {
+    pub algorithm: String,
+    /// Recipient public key (base64)
+    pub public_key: String,
+    pub payload: String,
+}
+
+/// What a sealed meeting hides from the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SealedPayload {
+    pub transcript: Vec<TranscriptSegment>,
+    pub markers: Vec<Marker>,
+    pub edits: Vec<SegmentEdit>,
+}
+
+/// Parse a base64 X25519 public key supplied by a client
+pub fn parse_public_key(key: &str) -> Result<PublicKey> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(key.trim())
+        .context("Encryption key is not valid base64")?;
+    PublicKey::from_slice(&bytes).map_err(|_| {
+        anyhow::anyhow!(
+            "Encryption key must be a {}-byte X25519 public key, got {} bytes",
+            KEY_SIZE,
+            bytes.len()
+        )
+    })
+}
+
+fn seal(key: &PublicKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    key.seal(&mut OsRng, plaintext)
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt meeting content"))
+}
+
+impl MeetingRecord {
+    /// Encrypt the transcript, markers, edits, and audio chunks to `public_key`
+    ///
+    /// The plaintext is removed from the record and its chunks point at
+    /// `<file>.sealed` copies, leaving only metadata (ID, title, times,
+    /// stats) readable by the server. Everything is encrypted before
+    /// anything is replaced: on failure the sealed files written so far are
+    /// removed and the record is left as it was.
+    ///
+    /// The unencrypted chunk files are returned rather than deleted, so they
+    /// outlive any failure to store the sealed record; delete them with
+    /// [`remove_unsealed`] once it is stored.
+    pub fn seal(&mut self, public_key: &str) -> Result<Vec<PathBuf>> {
+        if self.sealed.is_some() {
+            anyhow::bail!("Meeting {} is already encrypted", self.meeting_id);
+        }
+        let key = parse_public_key(public_key)?;
+
+        let mut staged: Vec<(PathBuf, String)> = Vec::with_capacity(self.chunks.len());
+        let mut stage = || -> Result<SealedContent> {
+            for chunk in &self.chunks {
+                staged.push(seal_chunk(&key, &chunk.file_path)?);
+            }
+            self.seal_content(&key)
+        };
+        let sealed = match stage() {
+            Ok(sealed) => sealed,
+            Err(e) => {
+                for (sealed_path, _) in &staged {
+                    if let Err(e) = std::fs::remove_file(sealed_path) {
+                        warn!("Failed to remove {}: {}", sealed_path.display(), e);
+                    }
+                }
+                return Err(e);
+            }
+        };
+
+        // Every piece is on disk: switch the record over
+        let mut unsealed = Vec::with_capacity(staged.len());
+        for (chunk, (sealed_path, sha256)) in self.chunks.iter_mut().zip(staged) {
+            unsealed.push(std::mem::replace(&mut chunk.file_path, sealed_path));
+            // The checksum follows the file, so sealed chunks still verify
+            chunk.sha256 = Some(sha256);
+        }
+        self.transcript.clear();
+        self.markers.clear();
+        self.edits.clear();
+        self.sealed = Some(sealed);
+        Ok(unsealed)
+    }
+
+    /// The transcript, markers, and edits sealed to `key`
+    fn seal_content(&self, key: &PublicKey) -> Result<SealedContent> {
+        let payload = SealedPayload {
+            transcript: self.transcript.clone(),
+            markers: self.markers.clone(),
+            edits: self.edits.clone(),
+        };
+        let json = serde_json::to_vec(&payload).context("Failed to serialize meeting content")?;
+
+        Ok(SealedContent {
+            algorithm: SEALED_ALGORITHM.to_string(),
+            public_key: base64::engine::general_purpose::STANDARD.encode(key.as_bytes()),
+            payload: base64::engine::general_purpose::STANDARD.encode(seal(key, &json)?),
+        })
+    }
+}
+
+/// Write an encrypted copy of the chunk at `path` next to it, returning the
+/// copy's path and checksum
+fn seal_chunk(key: &PublicKey, path: &Path) -> Result<(PathBuf, String)> {
+    let audio =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut sealed_path = path.to_path_buf().into_os_string();
+    sealed_path.push(".");
+    sealed_path.push(SEALED_EXTENSION);
+    let sealed_path = PathBuf::from(sealed_path);
+
+    let sealed_audio = seal(key, &audio)?;
+    // On disk for good before the plaintext is removed
+    write_atomic(&sealed_path, &sealed_audio)?;
+    Ok((sealed_path, format!("{:x}", Sha256::digest(&sealed_audio))))
+}
+
+/// Delete the unencrypted chunk files a sealed meeting was made from
+/// (failures are logged)
+pub fn remove_unsealed(meeting_id: &str, unsealed: &[PathBuf]) {
+    for path in unsealed {
+        if let Err(e) = std::fs::remove_file(path) {
+            error!(
+                "Failed to remove unencrypted chunk {} of meeting {}: {}",
+                path.display(),
+                meeting_id,
+                e
+            );
+        }
+    }
+}
+
+impl SealedContent {
+    /// Decrypt with the client's secret key (for clients written in Rust)
+    pub fn open(&self, secret_key: &[u8]) -> Result<SealedPayload> {
+        let secret_key =
+            SecretKey::from_slice(secret_key).map_err(|_| anyhow::anyhow!("Invalid secret key"))?;
+        let ciphertext = base64::engine::general_purpose::STANDARD
+            .decode(&self.payload)
+            .context("Sealed payload is not valid base64")?;
+        let json = secret_key
+            .unseal(&ciphertext)
+            .map_err(|_| anyhow::anyhow!("Failed to decrypt meeting content"))?;
+        serde_json::from_slice(&json).context("Failed to parse meeting content")
+    }
+}
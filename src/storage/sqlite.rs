@@ -0,0 +1,415 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+use super::{validate_meeting_id, MeetingRecord, MeetingSummary, SearchHit, Storage};
+
+/// Schema migrations, applied in order; `PRAGMA user_version` records how many ran
+///
+/// Never edit a migration once released — append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: meeting documents
+    "CREATE TABLE IF NOT EXISTS meetings (
+        id TEXT PRIMARY KEY,
+        title TEXT,
+        started_at TEXT NOT NULL,
+        duration_secs REAL NOT NULL,
+        segments_count INTEGER NOT NULL,
+        record TEXT NOT NULL
+    );",
+    // 2: index tables for listing and transcript search
+    "CREATE INDEX idx_meetings_started_at ON meetings (started_at);
+
+    CREATE TABLE segments (
+        id INTEGER PRIMARY KEY,
+        meeting_id TEXT NOT NULL REFERENCES meetings (id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        confidence REAL
+    );
+    CREATE INDEX idx_segments_meeting ON segments (meeting_id);
+
+    CREATE VIRTUAL TABLE segments_fts USING fts5 (
+        text, content = 'segments', content_rowid = 'id'
+    );
+    CREATE TRIGGER segments_ai AFTER INSERT ON segments BEGIN
+        INSERT INTO segments_fts (rowid, text) VALUES (new.id, new.text);
+    END;
+    CREATE TRIGGER segments_ad AFTER DELETE ON segments BEGIN
+        INSERT INTO segments_fts (segments_fts, rowid, text) VALUES ('delete', old.id, old.text);
+    END;
+
+    CREATE TABLE markers (
+        meeting_id TEXT NOT NULL REFERENCES meetings (id) ON DELETE CASCADE,
+        kind TEXT NOT NULL,
+        offset_ms INTEGER NOT NULL,
+        timestamp TEXT NOT NULL,
+        label TEXT NOT NULL
+    );
+    CREATE INDEX idx_markers_meeting ON markers (meeting_id);
+
+    CREATE TABLE chunks (
+        meeting_id TEXT NOT NULL REFERENCES meetings (id) ON DELETE CASCADE,
+        chunk_index INTEGER NOT NULL,
+        file_path TEXT NOT NULL,
+        start_ms INTEGER NOT NULL,
+        end_ms INTEGER NOT NULL,
+        sample_rate INTEGER NOT NULL,
+        channels INTEGER NOT NULL,
+        sample_count INTEGER NOT NULL,
+        PRIMARY KEY (meeting_id, chunk_index)
+    );",
+    // 3: meeting expiry (unix milliseconds) for the retention worker
+    "ALTER TABLE meetings ADD COLUMN expires_at INTEGER;
+    CREATE INDEX idx_meetings_expires_at ON meetings (expires_at);",
+];
+
+/// First migration that introduced the index tables
+const INDEX_MIGRATION: usize = 2;
+
+/// Stores meetings in an embedded SQLite database
+///
+/// Each meeting is kept as a JSON document, with segments, markers, and chunk
+/// metadata also indexed in their own tables for listing and full-text search.
+pub struct SqliteStorage {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStorage {
+    /// Open (or create) the database at `path`, applying pending migrations
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create database directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open database: {}", path.display()))?;
+        Self::from_connection(conn)
+    }
+
+    /// Open a private in-memory database (for tests)
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Number of migrations applied to the database
+    pub async fn schema_version(&self) -> Result<usize> {
+        self.with_conn(|conn| Self::user_version(conn)).await
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        Self::migrate(&mut conn)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn user_version(conn: &Connection) -> Result<usize> {
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        Ok(version as usize)
+    }
+
+    /// Apply pending migrations, each in its own transaction
+    fn migrate(conn: &mut Connection) -> Result<()> {
+        let current = Self::user_version(conn)?;
+
+        for (index, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+            let version = index + 1;
+            let tx = conn.transaction()?;
+
+            tx.execute_batch(sql)
+                .with_context(|| format!("Failed to apply migration {}", version))?;
+
+            // Meetings saved before the index existed need their rows populated
+            if version == INDEX_MIGRATION {
+                Self::reindex_all(&tx)?;
+            }
+
+            tx.pragma_update(None, "user_version", version as i64)?;
+            tx.commit()?;
+            info!("Applied database migration {}", version);
+        }
+
+        Ok(())
+    }
+
+    fn reindex_all(tx: &Transaction) -> Result<()> {
+        let records: Vec<String> = tx
+            .prepare("SELECT record FROM meetings")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        for json in records {
+            let record: MeetingRecord =
+                serde_json::from_str(&json).context("Failed to parse stored meeting")?;
+            Self::index_record(tx, &record)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the index rows (segments, markers, chunks) for a meeting
+    fn index_record(tx: &Transaction, record: &MeetingRecord) -> Result<()> {
+        let id = &record.meeting_id;
+
+        tx.execute("DELETE FROM segments WHERE meeting_id = ?1", params![id])?;
+        tx.execute("DELETE FROM markers WHERE meeting_id = ?1", params![id])?;
+        tx.execute("DELETE FROM chunks WHERE meeting_id = ?1", params![id])?;
+
+        let mut insert_segment = tx.prepare(
+            "INSERT INTO segments (meeting_id, seq, text, timestamp, confidence)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for (seq, segment) in record.transcript.iter().enumerate() {
+            insert_segment.execute(params![
+                id,
+                seq as i64,
+                segment.text,
+                segment.timestamp.to_rfc3339(),
+                segment.confidence,
+            ])?;
+        }
+
+        let mut insert_marker = tx.prepare(
+            "INSERT INTO markers (meeting_id, kind, offset_ms, timestamp, label)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for marker in &record.markers {
+            let kind = serde_json::to_value(marker.kind)?;
+            insert_marker.execute(params![
+                id,
+                kind.as_str().unwrap_or_default(),
+                marker.offset_ms as i64,
+                marker.timestamp.to_rfc3339(),
+                marker.label,
+            ])?;
+        }
+
+        let mut insert_chunk = tx.prepare(
+            "INSERT INTO chunks (meeting_id, chunk_index, file_path, start_ms, end_ms,
+                                 sample_rate, channels, sample_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for chunk in &record.chunks {
+            insert_chunk.execute(params![
+                id,
+                chunk.chunk_index as i64,
+                chunk.file_path.display().to_string(),
+                chunk.start_ms as i64,
+                chunk.end_ms as i64,
+                chunk.sample_rate,
+                chunk.channels,
+                chunk.sample_count as i64,
+            ])?;
+        }
+
+        Ok(())
+    }
+
+    /// Run a blocking database operation off the async runtime
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Database connection poisoned"))?;
+            f(&mut conn)
+        })
+        .await
+        .context("Database task panicked")?
+    }
+}
+
+/// Quote each word so user input is matched literally, not as FTS5 syntax
+fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[async_trait::async_trait]
+impl Storage for SqliteStorage {
+    async fn save_meeting(&self, record: &MeetingRecord) -> Result<()> {
+        validate_meeting_id(&record.meeting_id)?;
+        let json = serde_json::to_string(record).context("Failed to serialize meeting")?;
+        let record = record.clone();
+
+        self.with_conn(move |conn| {
+            let summary = MeetingSummary::from(&record);
+            let tx = conn.transaction()?;
+
+            tx.execute(
+                "INSERT INTO meetings
+                    (id, title, started_at, duration_secs, segments_count, record, expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (id) DO UPDATE SET
+                    title = excluded.title,
+                    started_at = excluded.started_at,
+                    duration_secs = excluded.duration_secs,
+                    segments_count = excluded.segments_count,
+                    record = excluded.record,
+                    expires_at = excluded.expires_at",
+                params![
+                    summary.meeting_id,
+                    summary.title,
+                    summary.started_at.to_rfc3339(),
+                    summary.duration_secs,
+                    summary.transcript_segments_count as i64,
+                    json,
+                    summary.expires_at.map(|t| t.timestamp_millis()),
+                ],
+            )
+            .context("Failed to save meeting")?;
+
+            Self::index_record(&tx, &record).context("Failed to index meeting")?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_meeting(&self, meeting_id: &str) -> Result<Option<MeetingRecord>> {
+        let meeting_id = meeting_id.to_string();
+
+        let json: Option<String> = self
+            .with_conn(move |conn| {
+                conn.query_row(
+                    "SELECT record FROM meetings WHERE id = ?1",
+                    params![meeting_id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .context("Failed to load meeting")
+            })
+            .await?;
+
+        json.map(|json| serde_json::from_str(&json).context("Failed to parse stored meeting"))
+            .transpose()
+    }
+
+    async fn list_meetings(&self, limit: usize, offset: usize) -> Result<Vec<MeetingSummary>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, title, started_at, duration_secs, segments_count, expires_at
+                 FROM meetings ORDER BY started_at DESC LIMIT ?1 OFFSET ?2",
+            )?;
+
+            let rows = stmt.query_map(params![limit as i64, offset as i64], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, Option<i64>>(5)?,
+                ))
+            })?;
+
+            let mut summaries = Vec::new();
+            for row in rows {
+                let (meeting_id, title, started_at, duration_secs, count, expires_at) = row?;
+                summaries.push(MeetingSummary {
+                    meeting_id,
+                    title,
+                    started_at: started_at
+                        .parse()
+                        .context("Invalid started_at in database")?,
+                    duration_secs,
+                    transcript_segments_count: count as usize,
+                    expires_at: expires_at.and_then(DateTime::from_timestamp_millis),
+                });
+            }
+            Ok(summaries)
+        })
+        .await
+    }
+
+    async fn search_transcripts(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let fts_query = fts_query(query);
+        if fts_query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT s.meeting_id, m.title, m.started_at, s.text, s.timestamp
+                 FROM segments_fts
+                 JOIN segments s ON s.id = segments_fts.rowid
+                 JOIN meetings m ON m.id = s.meeting_id
+                 WHERE segments_fts MATCH ?1
+                 ORDER BY segments_fts.rank
+                 LIMIT ?2",
+            )?;
+
+            let rows = stmt.query_map(params![fts_query, limit as i64], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })?;
+
+            let mut hits = Vec::new();
+            for row in rows {
+                let (meeting_id, title, started_at, text, timestamp) = row?;
+                hits.push(SearchHit {
+                    meeting_id,
+                    title,
+                    started_at: started_at
+                        .parse()
+                        .context("Invalid started_at in database")?,
+                    text,
+                    timestamp: timestamp.parse().context("Invalid timestamp in database")?,
+                });
+            }
+            Ok(hits)
+        })
+        .await
+    }
+
+    async fn delete_meeting(&self, meeting_id: &str) -> Result<bool> {
+        let meeting_id = meeting_id.to_string();
+
+        // Index rows are removed by ON DELETE CASCADE
+        self.with_conn(move |conn| {
+            let deleted = conn
+                .execute("DELETE FROM meetings WHERE id = ?1", params![meeting_id])
+                .context("Failed to delete meeting")?;
+            Ok(deleted > 0)
+        })
+        .await
+    }
+
+    async fn expired_meetings(&self, now: DateTime<Utc>) -> Result<Vec<String>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare("SELECT id FROM meetings WHERE expires_at <= ?1")?;
+            let ids = stmt
+                .query_map(params![now.timestamp_millis()], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()
+                .context("Failed to list expired meetings")?;
+            Ok(ids)
+        })
+        .await
+    }
+
+    fn name(&self) -> &str {
+        "SQLite"
+    }
+}
@@ -0,0 +1,196 @@
+use crate::storage::write_atomic;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::warn;
+
+/// Limits on audio sent to cloud STT providers, in minutes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SttBudgetConfig {
+    /// Minutes per (UTC) day (None = unlimited)
+    pub daily_minutes: Option<f64>,
+
+    /// Minutes per (UTC) calendar month (None = unlimited)
+    pub monthly_minutes: Option<f64>,
+
+    /// File usage is kept in across restarts (None = in memory only)
+    pub usage_path: Option<String>,
+}
+
+impl SttBudgetConfig {
+    /// Usage file path with `~` expanded
+    pub fn resolved_usage_path(&self) -> Option<PathBuf> {
+        self.usage_path
+            .as_deref()
+            .map(|path| PathBuf::from(shellexpand::tilde(path).as_ref()))
+    }
+}
+
+/// Period a budget applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Day,
+    Month,
+}
+
+impl fmt::Display for BudgetPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetPeriod::Day => write!(f, "daily"),
+            BudgetPeriod::Month => write!(f, "monthly"),
+        }
+    }
+}
+
+/// Minutes sent to cloud providers in the current day and month
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SttUsage {
+    /// Day `day_minutes` counts (None before any usage)
+    pub day: Option<NaiveDate>,
+    pub day_minutes: f64,
+    /// First day of the month `month_minutes` counts
+    pub month: Option<NaiveDate>,
+    pub month_minutes: f64,
+}
+
+impl SttUsage {
+    /// Start new periods for `today`, dropping usage from earlier ones
+    fn roll(&mut self, today: NaiveDate) {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.day_minutes = 0.0;
+        }
+        let month = today.with_day(1);
+        if self.month != month {
+            self.month = month;
+            self.month_minutes = 0.0;
+        }
+    }
+}
+
+/// Usage against the budget, as reported by session status and /metrics
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub day_minutes: f64,
+    pub daily_limit: Option<f64>,
+    pub month_minutes: f64,
+    pub monthly_limit: Option<f64>,
+    /// Budget that has run out (None = transcription may continue)
+    pub exhausted: Option<BudgetPeriod>,
+}
+
+/// Tracks minutes of audio sent to cloud STT providers against the
+/// configured budget, shared by every session
+#[derive(Debug)]
+pub struct SttBudget {
+    config: SttBudgetConfig,
+    usage: std::sync::Mutex<SttUsage>,
+}
+
+impl SttBudget {
+    /// Create the tracker, resuming usage from the usage file if there is one
+    pub fn new(config: SttBudgetConfig) -> Self {
+        let usage = match config.resolved_usage_path() {
+            Some(path) => match Self::load(&path) {
+                Ok(usage) => usage,
+                Err(e) => {
+                    warn!("STT usage starts from zero: {:#}", e);
+                    SttUsage::default()
+                }
+            },
+            None => SttUsage::default(),
+        };
+
+        Self {
+            config,
+            usage: std::sync::Mutex::new(usage),
+        }
+    }
+
+    fn load(path: &std::path::Path) -> Result<SttUsage> {
+        match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Invalid STT usage file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SttUsage::default()),
+            Err(e) => {
+                Err(e).with_context(|| format!("Failed to read STT usage from {}", path.display()))
+            }
+        }
+    }
+
+    pub fn config(&self) -> &SttBudgetConfig {
+        &self.config
+    }
+
+    /// Count audio sent to a provider now
+    pub fn record(&self, audio: Duration) {
+        self.record_at(audio, Utc::now());
+    }
+
+    /// Count audio sent to a provider at `at`
+    pub fn record_at(&self, audio: Duration, at: DateTime<Utc>) {
+        let minutes = audio.as_secs_f64() / 60.0;
+        let usage = {
+            let mut usage = self.usage.lock().unwrap();
+            usage.roll(at.date_naive());
+            usage.day_minutes += minutes;
+            usage.month_minutes += minutes;
+            *usage
+        };
+
+        if let Some(path) = self.config.resolved_usage_path() {
+            if let Err(e) = Self::save(&path, &usage) {
+                warn!("{:#}", e);
+            }
+        }
+    }
+
+    fn save(path: &std::path::Path, usage: &SttUsage) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_atomic(path, serde_json::to_vec(usage)?)
+            .with_context(|| format!("Failed to save STT usage to {}", path.display()))
+    }
+
+    /// Budget that has run out now (None = transcription may continue)
+    pub fn exhausted(&self) -> Option<BudgetPeriod> {
+        self.status_at(Utc::now()).exhausted
+    }
+
+    /// Usage against the budget now
+    pub fn status(&self) -> BudgetStatus {
+        self.status_at(Utc::now())
+    }
+
+    /// Usage against the budget at `at`
+    pub fn status_at(&self, at: DateTime<Utc>) -> BudgetStatus {
+        let usage = {
+            let mut usage = self.usage.lock().unwrap();
+            usage.roll(at.date_naive());
+            *usage
+        };
+
+        let over = |used: f64, limit: Option<f64>| limit.is_some_and(|limit| used >= limit);
+        let exhausted = if over(usage.month_minutes, self.config.monthly_minutes) {
+            Some(BudgetPeriod::Month)
+        } else if over(usage.day_minutes, self.config.daily_minutes) {
+            Some(BudgetPeriod::Day)
+        } else {
+            None
+        };
+
+        BudgetStatus {
+            day_minutes: usage.day_minutes,
+            daily_limit: self.config.daily_minutes,
+            month_minutes: usage.month_minutes,
+            monthly_limit: self.config.monthly_minutes,
+            exhausted,
+        }
+    }
+}
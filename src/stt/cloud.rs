@@ -0,0 +1,298 @@
+use super::{SttBudget, SttProvider};
+use crate::nats::{
+    AudioCodec, AudioFrameMessage, TranscriptMessage, TranscriptStreamConfig,
+    TranscriptSubscription, PROTOCOL_VERSION,
+};
+use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{debug, warn};
+
+/// Transcripts waiting for the session's transcript task
+const TRANSCRIPT_CHANNEL_CAPACITY: usize = 64;
+
+/// Transcribes one WAV segment through a cloud API
+#[async_trait::async_trait]
+pub trait Transcriber: Send + Sync + 'static {
+    async fn transcribe(&self, wav: Vec<u8>) -> Result<CloudTranscript>;
+}
+
+/// Text a cloud API returned for a segment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CloudTranscript {
+    pub text: String,
+    pub confidence: Option<f32>,
+    /// Language the API detected, as it reported it
+    pub language: Option<String>,
+}
+
+/// A finished segment of one stream's audio
+#[derive(Debug, Clone)]
+pub struct Segment {
+    /// Stream the audio was sent under (the session ID or a dual-stream
+    /// sub-ID)
+    pub session_id: String,
+    /// Index of the segment within its stream
+    pub index: u32,
+    /// When the segment's first frame was sent
+    pub started_at: DateTime<Utc>,
+    pub duration: Duration,
+    /// The audio as a 16-bit PCM WAV file
+    pub wav: Vec<u8>,
+}
+
+/// Collects one stream's frames into segments for a cloud provider
+///
+/// A segment ends at an utterance boundary, at the stream's final frame, or
+/// once it reaches the maximum length.
+#[derive(Debug)]
+pub struct SegmentBuffer {
+    max_segment: Duration,
+    samples: Vec<i16>,
+    sample_rate: u32,
+    channels: u16,
+    started_at: Option<DateTime<Utc>>,
+    next_index: u32,
+}
+
+impl SegmentBuffer {
+    pub fn new(max_segment: Duration) -> Self {
+        Self {
+            max_segment,
+            samples: Vec::new(),
+            sample_rate: 0,
+            channels: 0,
+            started_at: None,
+            next_index: 0,
+        }
+    }
+
+    /// Add a frame message's audio, returning the segments it finished (a
+    /// format change also ends the segment before it)
+    pub fn push(&mut self, message: &AudioFrameMessage) -> Result<Vec<Segment>> {
+        if message.codec != AudioCodec::Pcm16 {
+            anyhow::bail!(
+                "Cloud STT providers take PCM audio, not {:?}",
+                message.codec
+            );
+        }
+        let pcm = base64::engine::general_purpose::STANDARD
+            .decode(&message.pcm)
+            .context("Invalid PCM in audio frame")?;
+
+        let mut segments = Vec::new();
+        if !self.samples.is_empty()
+            && (message.sample_rate, message.channels) != (self.sample_rate, self.channels)
+        {
+            segments.extend(self.take(&message.session_id)?);
+        }
+
+        if !pcm.is_empty() {
+            if self.samples.is_empty() {
+                self.sample_rate = message.sample_rate;
+                self.channels = message.channels;
+                self.started_at = Some(
+                    DateTime::parse_from_rfc3339(&message.timestamp)
+                        .map(|t| t.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                );
+            }
+            self.samples.extend(
+                pcm.chunks_exact(2)
+                    .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]])),
+            );
+        }
+
+        if message.segment_final || message.final_frame || self.duration() >= self.max_segment {
+            segments.extend(self.take(&message.session_id)?);
+        }
+        Ok(segments)
+    }
+
+    /// Length of the audio collected so far
+    pub fn duration(&self) -> Duration {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return Duration::ZERO;
+        }
+        let frames = self.samples.len() as u64 / self.channels as u64;
+        Duration::from_micros(frames * 1_000_000 / self.sample_rate as u64)
+    }
+
+    /// End the current segment (None if no audio was collected)
+    fn take(&mut self, session_id: &str) -> Result<Option<Segment>> {
+        if self.samples.is_empty() {
+            return Ok(None);
+        }
+
+        let duration = self.duration();
+        let samples = std::mem::take(&mut self.samples);
+        let spec = hound::WavSpec {
+            channels: self.channels,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut wav, spec)?;
+            for sample in samples {
+                writer.write_sample(sample)?;
+            }
+            writer.finalize()?;
+        }
+
+        let index = self.next_index;
+        self.next_index += 1;
+        Ok(Some(Segment {
+            session_id: session_id.to_string(),
+            index,
+            started_at: self.started_at.take().unwrap_or_else(Utc::now),
+            duration,
+            wav: wav.into_inner(),
+        }))
+    }
+}
+
+/// An STT provider that uploads segments to a cloud API
+///
+/// Segments are transcribed one at a time, in order, by a background task;
+/// a segment the API fails on is logged and skipped. With a budget,
+/// transcribed segments are charged to it and no more are uploaded once it
+/// runs out.
+pub struct CloudProvider {
+    name: &'static str,
+    max_segment: Duration,
+    budget: Option<Arc<SttBudget>>,
+    /// Segment being collected for each stream
+    buffers: std::sync::Mutex<HashMap<String, SegmentBuffer>>,
+    segments: mpsc::UnboundedSender<Segment>,
+    transcripts: Arc<Mutex<mpsc::Receiver<TranscriptMessage>>>,
+}
+
+impl CloudProvider {
+    /// Create the provider and start its upload task (which ends when the
+    /// provider is dropped)
+    pub fn new<T: Transcriber>(name: &'static str, transcriber: T, max_segment: Duration) -> Self {
+        Self::with_budget(name, transcriber, max_segment, None)
+    }
+
+    /// Create the provider, charging transcribed segments to `budget`
+    pub fn with_budget<T: Transcriber>(
+        name: &'static str,
+        transcriber: T,
+        max_segment: Duration,
+        budget: Option<Arc<SttBudget>>,
+    ) -> Self {
+        let (segments, mut pending) = mpsc::unbounded_channel::<Segment>();
+        let (sender, transcripts) = mpsc::channel(TRANSCRIPT_CHANNEL_CAPACITY);
+
+        let charged = budget.clone();
+        tokio::spawn(async move {
+            while let Some(segment) = pending.recv().await {
+                if let Some(period) = charged.as_ref().and_then(|budget| budget.exhausted()) {
+                    debug!(
+                        "Skipping segment {} of {}: {} STT budget exhausted",
+                        segment.index, segment.session_id, period
+                    );
+                    continue;
+                }
+
+                debug!(
+                    "Transcribing {:.1}s segment {} of {} with {}",
+                    segment.duration.as_secs_f64(),
+                    segment.index,
+                    segment.session_id,
+                    name
+                );
+                let transcript = match transcriber.transcribe(segment.wav).await {
+                    Ok(transcript) => {
+                        if let Some(budget) = &charged {
+                            budget.record(segment.duration);
+                        }
+                        transcript
+                    }
+                    Err(e) => {
+                        warn!(
+                            "{} failed to transcribe segment {} of {}: {:#}",
+                            name, segment.index, segment.session_id, e
+                        );
+                        continue;
+                    }
+                };
+                if transcript.text.trim().is_empty() {
+                    continue;
+                }
+
+                let message = TranscriptMessage {
+                    version: PROTOCOL_VERSION,
+                    segment_id: Some(format!("{}-{}", name, segment.index)),
+                    session_id: segment.session_id,
+                    text: transcript.text.trim().to_string(),
+                    partial: false,
+                    timestamp: segment.started_at.to_rfc3339(),
+                    confidence: transcript.confidence,
+                    speaker: None,
+                    language: transcript.language,
+                };
+                if sender.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            name,
+            max_segment,
+            budget,
+            buffers: std::sync::Mutex::new(HashMap::new()),
+            segments,
+            transcripts: Arc::new(Mutex::new(transcripts)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SttProvider for CloudProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn send_audio(&self, message: &AudioFrameMessage) -> Result<()> {
+        // Nothing more will be uploaded, so there is nothing to collect
+        if self
+            .budget
+            .as_ref()
+            .is_some_and(|budget| budget.exhausted().is_some())
+        {
+            return Ok(());
+        }
+
+        let segments = self
+            .buffers
+            .lock()
+            .unwrap()
+            .entry(message.session_id.clone())
+            .or_insert_with(|| SegmentBuffer::new(self.max_segment))
+            .push(message)?;
+
+        for segment in segments {
+            self.segments
+                .send(segment)
+                .map_err(|_| anyhow::anyhow!("{} upload task stopped", self.name))?;
+        }
+        Ok(())
+    }
+
+    /// Transcripts arrive as the upload task finishes segments; durable
+    /// delivery is a NATS feature and is ignored
+    async fn subscribe(&self, _stream: &TranscriptStreamConfig) -> Result<TranscriptSubscription> {
+        Ok(TranscriptSubscription::Provider(Arc::clone(
+            &self.transcripts,
+        )))
+    }
+}
@@ -0,0 +1,106 @@
+use super::{CloudSttConfig, CloudTranscript, Transcriber};
+use anyhow::{Context, Result};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use serde::Deserialize;
+use std::time::Duration;
+
+const DEFAULT_URL: &str = "https://api.deepgram.com/v1/listen";
+const DEFAULT_MODEL: &str = "nova-2";
+const DEFAULT_API_KEY_ENV: &str = "DEEPGRAM_API_KEY";
+
+/// How long a segment upload may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Transcribes segments with Deepgram's pre-recorded audio API
+pub struct DeepgramTranscriber {
+    http: reqwest::Client,
+    url: String,
+    model: String,
+    api_key: String,
+}
+
+#[derive(Deserialize)]
+struct ListenResponse {
+    results: ListenResults,
+}
+
+#[derive(Deserialize)]
+struct ListenResults {
+    channels: Vec<ListenChannel>,
+}
+
+#[derive(Deserialize)]
+struct ListenChannel {
+    alternatives: Vec<ListenAlternative>,
+    /// Set when language detection is requested
+    #[serde(default)]
+    detected_language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ListenAlternative {
+    transcript: String,
+    #[serde(default)]
+    confidence: Option<f32>,
+}
+
+impl DeepgramTranscriber {
+    pub fn new(config: &CloudSttConfig) -> Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()?,
+            url: config
+                .url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_URL.to_string()),
+            model: config
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            api_key: config.api_key(DEFAULT_API_KEY_ENV)?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Transcriber for DeepgramTranscriber {
+    async fn transcribe(&self, wav: Vec<u8>) -> Result<CloudTranscript> {
+        let response: ListenResponse = self
+            .http
+            .post(&self.url)
+            .query(&[
+                ("model", self.model.as_str()),
+                ("punctuate", "true"),
+                ("smart_format", "true"),
+                ("detect_language", "true"),
+            ])
+            .header(AUTHORIZATION, format!("Token {}", self.api_key))
+            .header(CONTENT_TYPE, "audio/wav")
+            .body(wav)
+            .send()
+            .await
+            .context("Failed to reach the Deepgram API")?
+            .error_for_status()
+            .context("Deepgram transcription failed")?
+            .json()
+            .await
+            .context("Invalid Deepgram response")?;
+
+        // The first channel's best alternative (segments are mono or mixed)
+        let Some(channel) = response.results.channels.into_iter().next() else {
+            return Ok(CloudTranscript::default());
+        };
+        let language = channel.detected_language;
+        Ok(channel
+            .alternatives
+            .into_iter()
+            .next()
+            .map(|alternative| CloudTranscript {
+                text: alternative.transcript,
+                confidence: alternative.confidence,
+                language,
+            })
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,193 @@
+//! Speech-to-text providers
+//!
+//! A session sends its audio to one `SttProvider` and collects the
+//! transcripts it returns:
+//! - `nats`: the loqa-core STT service over NATS (default)
+//! - `openai`: the OpenAI Whisper API (feature `openai`)
+//! - `deepgram`: Deepgram's pre-recorded API (feature `deepgram`)
+//!
+//! Cloud providers transcribe audio in segments, cut at utterance
+//! boundaries or after `segment_secs`, so they work without a local STT
+//! stack. The minutes they transcribe count against an optional daily and
+//! monthly budget; once it runs out, sessions fall back to record-only.
+
+pub mod budget;
+pub mod cloud;
+#[cfg(feature = "deepgram")]
+pub mod deepgram;
+pub mod nats;
+#[cfg(feature = "openai")]
+pub mod openai;
+
+pub use budget::{BudgetPeriod, BudgetStatus, SttBudget, SttBudgetConfig, SttUsage};
+pub use cloud::{CloudProvider, CloudTranscript, Segment, SegmentBuffer, Transcriber};
+pub use nats::NatsProvider;
+
+use crate::nats::{
+    AudioFrameMessage, HandshakeRequest, Protocol, TranscriptStreamConfig, TranscriptSubscription,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Sends a session's audio to a speech-to-text service and receives its
+/// transcripts
+#[async_trait::async_trait]
+pub trait SttProvider: Send + Sync {
+    /// Provider name, for logs
+    fn name(&self) -> &'static str;
+
+    /// Agree on the protocol audio is sent in
+    ///
+    /// Providers without a handshake take PCM, one frame per message.
+    async fn handshake(&self, _offer: &HandshakeRequest) -> Result<Protocol> {
+        Ok(Protocol::legacy())
+    }
+
+    /// Send one audio frame message
+    async fn send_audio(&self, message: &AudioFrameMessage) -> Result<()>;
+
+    /// Subscribe to transcripts (called again when the transcript task
+    /// restarts)
+    async fn subscribe(&self, stream: &TranscriptStreamConfig) -> Result<TranscriptSubscription>;
+
+    /// Release the provider's connection
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Which STT provider transcribes a session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SttProviderKind {
+    /// loqa-core STT service over NATS
+    #[default]
+    Nats,
+    /// OpenAI Whisper API
+    OpenAi,
+    /// Deepgram
+    Deepgram,
+}
+
+impl SttProviderKind {
+    /// Whether the provider is a cloud API (and counts against the budget)
+    pub fn is_cloud(self) -> bool {
+        self != SttProviderKind::Nats
+    }
+}
+
+impl fmt::Display for SttProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SttProviderKind::Nats => write!(f, "nats"),
+            SttProviderKind::OpenAi => write!(f, "openai"),
+            SttProviderKind::Deepgram => write!(f, "deepgram"),
+        }
+    }
+}
+
+/// STT provider selection and cloud provider settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SttConfig {
+    /// Provider sessions use unless their profile or request picks another
+    pub provider: SttProviderKind,
+
+    /// Longest segment sent to a cloud provider, in seconds (segments also
+    /// end at utterance boundaries)
+    pub segment_secs: u64,
+
+    pub openai: CloudSttConfig,
+
+    pub deepgram: CloudSttConfig,
+
+    /// Limits on cloud transcription
+    pub budget: SttBudgetConfig,
+}
+
+impl Default for SttConfig {
+    fn default() -> Self {
+        Self {
+            provider: SttProviderKind::default(),
+            segment_secs: 15,
+            openai: CloudSttConfig::default(),
+            deepgram: CloudSttConfig::default(),
+            budget: SttBudgetConfig::default(),
+        }
+    }
+}
+
+impl SttConfig {
+    /// Longest segment sent to a cloud provider
+    pub fn max_segment(&self) -> Duration {
+        Duration::from_secs(self.segment_secs.max(1))
+    }
+}
+
+/// Settings for a cloud STT provider (unset fields use the provider's
+/// defaults)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloudSttConfig {
+    /// Environment variable holding the API key, so the key itself never
+    /// appears in config files or session configs
+    pub api_key_env: Option<String>,
+
+    /// Model to transcribe with
+    pub model: Option<String>,
+
+    /// API endpoint (e.g. a proxy or compatible self-hosted service)
+    pub url: Option<String>,
+}
+
+impl CloudSttConfig {
+    /// Read the API key from the configured (or default) environment variable
+    pub fn api_key(&self, default_env: &str) -> Result<String> {
+        let env = self.api_key_env.as_deref().unwrap_or(default_env);
+        match std::env::var(env) {
+            Ok(key) if !key.trim().is_empty() => Ok(key.trim().to_string()),
+            _ => anyhow::bail!("Set {} to an API key to use this STT provider", env),
+        }
+    }
+}
+
+/// Create the cloud provider `config.provider` names, charging what it
+/// transcribes to `budget` if given
+///
+/// The NATS provider shares the session's NATS connection, so the session
+/// creates it itself. Providers whose feature was not built in are an error.
+#[cfg_attr(
+    not(any(feature = "openai", feature = "deepgram")),
+    allow(unused_variables)
+)]
+pub fn cloud_provider(
+    config: &SttConfig,
+    budget: Option<Arc<SttBudget>>,
+) -> Result<Arc<dyn SttProvider>> {
+    match config.provider {
+        SttProviderKind::Nats => anyhow::bail!("The NATS provider is not a cloud provider"),
+        #[cfg(feature = "openai")]
+        SttProviderKind::OpenAi => Ok(Arc::new(CloudProvider::with_budget(
+            "openai",
+            openai::OpenAiTranscriber::new(&config.openai)?,
+            config.max_segment(),
+            budget,
+        ))),
+        #[cfg(feature = "deepgram")]
+        SttProviderKind::Deepgram => Ok(Arc::new(CloudProvider::with_budget(
+            "deepgram",
+            deepgram::DeepgramTranscriber::new(&config.deepgram)?,
+            config.max_segment(),
+            budget,
+        ))),
+        #[allow(unreachable_patterns)]
+        kind => anyhow::bail!(
+            "The {} STT provider requires building with --features {}",
+            kind,
+            kind
+        ),
+    }
+}
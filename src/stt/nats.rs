@@ -0,0 +1,58 @@
+use super::SttProvider;
+use crate::nats::{
+    AudioFrameMessage, HandshakeRequest, NatsClient, Protocol, TranscriptStreamConfig,
+    TranscriptSubscription,
+};
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use tracing::warn;
+
+/// The loqa-core STT service, reached over NATS
+pub struct NatsProvider {
+    client: Arc<NatsClient>,
+}
+
+impl NatsProvider {
+    pub fn new(client: Arc<NatsClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait::async_trait]
+impl SttProvider for NatsProvider {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn handshake(&self, offer: &HandshakeRequest) -> Result<Protocol> {
+        self.client.handshake(offer).await
+    }
+
+    async fn send_audio(&self, message: &AudioFrameMessage) -> Result<()> {
+        self.client.publish_audio_message(message).await
+    }
+
+    /// Subscribe through a durable consumer if configured, falling back to
+    /// a plain subscription
+    async fn subscribe(&self, stream: &TranscriptStreamConfig) -> Result<TranscriptSubscription> {
+        if stream.durable {
+            match self.client.subscribe_transcripts_durable(stream).await {
+                Ok(subscription) => return Ok(subscription),
+                Err(e) => warn!(
+                    "Durable transcripts unavailable, using a plain subscription: {:#}",
+                    e
+                ),
+            }
+        }
+
+        self.client
+            .subscribe_transcripts()
+            .await
+            .map(TranscriptSubscription::Core)
+            .context("Failed to subscribe to transcripts")
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.client.close().await
+    }
+}
@@ -0,0 +1,83 @@
+use super::{CloudSttConfig, CloudTranscript, Transcriber};
+use anyhow::{Context, Result};
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+use std::time::Duration;
+
+const DEFAULT_URL: &str = "https://api.openai.com/v1/audio/transcriptions";
+const DEFAULT_MODEL: &str = "whisper-1";
+const DEFAULT_API_KEY_ENV: &str = "OPENAI_API_KEY";
+
+/// How long a segment upload may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Transcribes segments with the OpenAI audio transcription API
+pub struct OpenAiTranscriber {
+    http: reqwest::Client,
+    url: String,
+    model: String,
+    api_key: String,
+}
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+    /// Detected language, by name (e.g. "english")
+    #[serde(default)]
+    language: Option<String>,
+}
+
+impl OpenAiTranscriber {
+    pub fn new(config: &CloudSttConfig) -> Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()?,
+            url: config
+                .url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_URL.to_string()),
+            model: config
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            api_key: config.api_key(DEFAULT_API_KEY_ENV)?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Transcriber for OpenAiTranscriber {
+    async fn transcribe(&self, wav: Vec<u8>) -> Result<CloudTranscript> {
+        let form = Form::new()
+            .text("model", self.model.clone())
+            // verbose_json includes the detected language
+            .text("response_format", "verbose_json")
+            .part(
+                "file",
+                Part::bytes(wav)
+                    .file_name("segment.wav")
+                    .mime_str("audio/wav")?,
+            );
+
+        let response: TranscriptionResponse = self
+            .http
+            .post(&self.url)
+            .bearer_auth(&self.api_key)
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to reach the OpenAI API")?
+            .error_for_status()
+            .context("OpenAI transcription failed")?
+            .json()
+            .await
+            .context("Invalid OpenAI transcription response")?;
+
+        Ok(CloudTranscript {
+            text: response.text,
+            confidence: None,
+            language: response.language,
+        })
+    }
+}
@@ -0,0 +1,67 @@
+//! OpenTelemetry trace export (feature "otel")
+//!
+//! Spans for HTTP requests, NATS publishes, and the session lifecycle go
+//! over OTLP/HTTP to the collector the standard `OTEL_EXPORTER_OTLP_*`
+//! variables name (default `http://localhost:4318`), so the service can be
+//! observed alongside loqa-core. Spans are reported under the service name
+//! "loqa-meetings" unless `OTEL_SERVICE_NAME` says otherwise.
+
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use std::sync::OnceLock;
+use tracing::Subscriber;
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+const SERVICE_NAME: &str = "loqa-meetings";
+
+/// Provider behind the layer, kept for flushing on shutdown
+static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+/// Tracing layer exporting this crate's spans (down to per-frame publishes)
+/// and HTTP request spans; other crates' spans are exported from info up
+///
+/// Fails if the exporter can't be created or a layer was already made.
+pub fn layer<S>() -> Result<impl Layer<S>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()
+        .context("Failed to create OTLP span exporter")?;
+
+    let mut resource = Resource::builder();
+    if std::env::var_os("OTEL_SERVICE_NAME").is_none() {
+        resource = resource.with_service_name(SERVICE_NAME);
+    }
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource.build())
+        .build();
+    let tracer = provider.tracer(SERVICE_NAME);
+    PROVIDER
+        .set(provider)
+        .map_err(|_| anyhow::anyhow!("OpenTelemetry export is already set up"))?;
+
+    let targets = Targets::new()
+        .with_target("loqa_meetings", LevelFilter::DEBUG)
+        .with_target("tower_http", LevelFilter::DEBUG)
+        .with_default(LevelFilter::INFO);
+    Ok(tracing_opentelemetry::layer()
+        .with_tracer(tracer)
+        .with_filter(targets))
+}
+
+/// Export spans still queued and stop exporting
+pub fn shutdown() -> Result<()> {
+    match PROVIDER.get() {
+        Some(provider) => provider
+            .shutdown()
+            .context("Failed to flush OpenTelemetry spans"),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,52 @@
+//! Build information reported by GET /version
+//!
+//! Lets support and clients see which release, commit, and optional
+//! features a server was built with, and what it can capture from.
+
+use crate::audio::{AudioBackendFactory, CaptureBackendInfo};
+use serde::Serialize;
+
+/// Crate version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Short hash of the commit built from (set by build.rs; None outside a
+/// git checkout)
+pub const GIT_COMMIT: Option<&str> = option_env!("LOQA_GIT_COMMIT");
+
+/// Optional cargo features compiled into this build
+pub fn enabled_features() -> Vec<&'static str> {
+    [
+        ("opus", cfg!(feature = "opus")),
+        ("console", cfg!(feature = "console")),
+        ("openai", cfg!(feature = "openai")),
+        ("deepgram", cfg!(feature = "deepgram")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
+/// What this server was built as and can do
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_commit: Option<&'static str>,
+    /// Optional cargo features enabled
+    pub features: Vec<&'static str>,
+    /// Operating system and architecture, e.g. "macos-aarch64"
+    pub platform: String,
+    /// Capture sources and whether this build can capture from them
+    pub capture_backends: Vec<CaptureBackendInfo>,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        Self {
+            version: VERSION,
+            git_commit: GIT_COMMIT,
+            features: enabled_features(),
+            platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+            capture_backends: AudioBackendFactory::backends(),
+        }
+    }
+}
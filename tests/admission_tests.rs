@@ -0,0 +1,98 @@
+// Tests for concurrent session limits and the start queue
+
+use loqa_meetings::session::SessionSlots;
+use loqa_meetings::{Config, SessionLimitsConfig};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+
+#[test]
+fn test_sessions_over_the_limit_are_refused() {
+    let slots = SessionSlots::new(2);
+    assert_eq!(slots.limit(), Some(2));
+
+    assert!(slots.try_admit("standup"));
+    assert!(slots.try_admit("retro"));
+    assert!(!slots.try_admit("planning"));
+    assert_eq!(slots.active(), 2);
+
+    assert!(slots.release("standup"));
+    assert!(!slots.release("standup"), "Already released");
+    assert!(slots.try_admit("planning"));
+}
+
+#[test]
+fn test_zero_means_unlimited() {
+    let slots = SessionSlots::new(0);
+    assert_eq!(slots.limit(), None);
+    assert!((0..50).all(|i| slots.try_admit(&format!("meeting-{}", i))));
+}
+
+#[tokio::test]
+async fn test_queued_sessions_start_in_order() {
+    let slots = Arc::new(SessionSlots::new(1));
+    assert!(slots.try_admit("standup"));
+
+    let first = tokio::spawn(slots.admit("retro"));
+    let second = tokio::spawn(slots.admit("planning"));
+    assert_eq!(slots.queued(), vec!["retro", "planning"]);
+    assert_eq!(slots.queue_position("planning"), Some(2));
+
+    // Nothing is admitted while the slot is taken
+    tokio::task::yield_now().await;
+    assert!(!first.is_finished());
+
+    slots.release("standup");
+    assert!(timeout(Duration::from_secs(1), first)
+        .await
+        .unwrap()
+        .unwrap());
+    assert_eq!(slots.queued(), vec!["planning"]);
+    assert!(!second.is_finished());
+
+    slots.release("retro");
+    assert!(timeout(Duration::from_secs(1), second)
+        .await
+        .unwrap()
+        .unwrap());
+    assert!(slots.queued().is_empty());
+    assert_eq!(slots.active(), 1);
+}
+
+#[tokio::test]
+async fn test_cancelled_sessions_leave_the_queue() {
+    let slots = Arc::new(SessionSlots::new(1));
+    assert!(slots.try_admit("standup"));
+
+    let cancelled = tokio::spawn(slots.admit("retro"));
+    let next = tokio::spawn(slots.admit("planning"));
+    tokio::task::yield_now().await;
+
+    assert!(slots.cancel("retro"));
+    assert!(!slots.cancel("retro"), "No longer queued");
+    assert!(!timeout(Duration::from_secs(1), cancelled)
+        .await
+        .unwrap()
+        .unwrap());
+    assert_eq!(slots.queue_position("planning"), Some(1));
+
+    // The slot goes to the next session still waiting
+    slots.release("standup");
+    assert!(timeout(Duration::from_secs(1), next)
+        .await
+        .unwrap()
+        .unwrap());
+}
+
+#[test]
+fn test_session_limit_config() {
+    let config = Config::load("config/loqa-meetings").unwrap();
+    assert!(!config.sessions.queue);
+
+    let expected = if cfg!(target_os = "macos") { 1 } else { 0 };
+    assert_eq!(
+        SessionLimitsConfig::default().max_concurrent,
+        expected,
+        "One ScreenCaptureKit capture per process on macOS"
+    );
+}
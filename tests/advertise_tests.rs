@@ -0,0 +1,59 @@
+// Tests for advertising the HTTP API over mDNS
+
+use loqa_meetings::config::AdvertiseConfig;
+use loqa_meetings::http::{service_info, Advertisement, SERVICE_TYPE};
+use loqa_meetings::{version, Config};
+use std::net::SocketAddr;
+
+#[test]
+fn test_advertisement_is_off_by_default() {
+    let config = Config::load("config/loqa-meetings").unwrap();
+    assert!(!config.service.http.advertise.enabled);
+
+    let addr: SocketAddr = "0.0.0.0:8081".parse().unwrap();
+    let advertisement = Advertisement::start(&AdvertiseConfig::default(), addr).unwrap();
+    assert!(advertisement.is_none());
+}
+
+#[test]
+fn test_loopback_listener_is_not_advertised() {
+    let config = AdvertiseConfig {
+        enabled: true,
+        instance_name: None,
+    };
+    let addr: SocketAddr = "127.0.0.1:8081".parse().unwrap();
+
+    let err = Advertisement::start(&config, addr).err().unwrap();
+    assert!(err.to_string().contains("127.0.0.1:8081"), "{}", err);
+}
+
+#[test]
+fn test_service_record_has_port_and_version() {
+    let service = service_info(
+        &AdvertiseConfig {
+            enabled: true,
+            instance_name: Some("Meeting room".to_string()),
+        },
+        8081,
+    )
+    .unwrap();
+
+    assert_eq!(service.get_type(), SERVICE_TYPE);
+    assert_eq!(
+        service.get_fullname(),
+        format!("Meeting room.{}", SERVICE_TYPE)
+    );
+    assert_eq!(service.get_port(), 8081);
+    assert!(service.get_hostname().ends_with(".local."));
+    assert_eq!(
+        service.get_property_val_str("version"),
+        Some(version::VERSION)
+    );
+}
+
+#[test]
+fn test_instance_is_named_after_the_host_by_default() {
+    let service = service_info(&AdvertiseConfig::default(), 8081).unwrap();
+    let host = service.get_hostname().trim_end_matches(".local.");
+    assert_eq!(service.get_fullname(), format!("{}.{}", host, SERVICE_TYPE));
+}
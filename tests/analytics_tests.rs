@@ -0,0 +1,218 @@
+// Tests for meeting analytics: talk time, monologues, silence, and overlaps
+
+mod common;
+
+use anyhow::Result;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{DateTime, Duration, Utc};
+use loqa_meetings::session::{meeting_analytics, MeetingAnalytics};
+use loqa_meetings::{
+    create_router, render_note, AppState, Config, FilesystemStorage, MeetingRecord, NoteFormat,
+    Storage, TranscriptSegment,
+};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::Service;
+
+fn segment(
+    start: DateTime<Utc>,
+    millis: i64,
+    speaker: Option<&str>,
+    text: &str,
+) -> TranscriptSegment {
+    TranscriptSegment {
+        speaker: speaker.map(str::to_string),
+        ..common::segment(text, start + Duration::milliseconds(millis))
+    }
+}
+
+/// Me says 15 words over two segments, then Them says two
+fn conversation(start: DateTime<Utc>) -> Vec<TranscriptSegment> {
+    vec![
+        segment(start, 4000, Some("Me"), "one two three four five"),
+        segment(start, 8000, Some("Me"), "a b c d e f g h i j"),
+        segment(start, 10000, Some("Them"), "sounds good"),
+    ]
+}
+
+fn record(meeting_id: &str, transcript: Vec<TranscriptSegment>) -> MeetingRecord {
+    let started_at = Utc::now() - Duration::hours(1);
+    let transcript = transcript
+        .into_iter()
+        .map(|segment| TranscriptSegment {
+            timestamp: started_at + (segment.timestamp - transcript_start()),
+            ..segment
+        })
+        .collect::<Vec<_>>();
+    common::meeting(
+        meeting_id,
+        started_at,
+        Duration::seconds(10),
+        transcript,
+        Vec::new(),
+    )
+}
+
+/// Fixed start the test transcripts are built against
+fn transcript_start() -> DateTime<Utc> {
+    DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+}
+
+#[test]
+fn test_talk_time_per_speaker() {
+    let start = transcript_start();
+    let analytics = meeting_analytics(start, 10.0, &conversation(start));
+
+    assert!(analytics.has_speakers());
+    assert_eq!(analytics.speakers.len(), 2);
+    let me = &analytics.speakers[0];
+    assert_eq!(me.speaker.as_deref(), Some("Me"));
+    assert_eq!(me.segments, 2);
+    assert_eq!(me.words, 15);
+    assert!((me.talk_secs - 6.0).abs() < 1e-9);
+    let them = &analytics.speakers[1];
+    assert!((them.talk_secs - 0.8).abs() < 1e-9);
+
+    assert!((analytics.talk_secs - 6.8).abs() < 1e-9);
+    assert!((me.percent - 6.0 / 6.8 * 100.0).abs() < 1e-9);
+    assert!((me.percent + them.percent - 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_longest_monologue() {
+    let start = transcript_start();
+    let analytics = meeting_analytics(start, 10.0, &conversation(start));
+
+    let longest = analytics.longest_monologue.unwrap();
+    assert_eq!(longest.speaker.as_deref(), Some("Me"));
+    assert_eq!(longest.offset_ms, 2000);
+    assert!((longest.duration_secs - 6.0).abs() < 1e-9);
+    assert_eq!(longest.segments, 2);
+    assert!((analytics.speakers[1].longest_monologue_secs - 0.8).abs() < 1e-9);
+}
+
+#[test]
+fn test_long_pause_ends_a_monologue() {
+    let start = transcript_start();
+    let mut transcript = vec![
+        segment(start, 2000, Some("Me"), "first thought"),
+        segment(start, 20000, Some("Me"), "second thought"),
+    ];
+    let mut partial = segment(start, 21000, Some("Them"), "interim words are not counted");
+    partial.partial = true;
+    transcript.push(partial);
+
+    let analytics = meeting_analytics(start, 10.0, &transcript);
+    assert_eq!(analytics.speakers.len(), 1);
+    assert_eq!(analytics.speakers[0].segments, 2);
+    let longest = analytics.longest_monologue.unwrap();
+    assert_eq!(longest.segments, 1);
+    assert!((longest.duration_secs - 0.8).abs() < 1e-9);
+}
+
+#[test]
+fn test_silence_overlaps_and_responses() {
+    let start = transcript_start();
+    let transcript = vec![
+        segment(start, 4000, Some("Me"), "one two three four five"),
+        // Starts a second before Me stops
+        segment(start, 5000, Some("Them"), "a b c d e"),
+        segment(start, 8000, Some("Me"), "x y"),
+        segment(start, 9000, Some("Them"), "okay"),
+        // Too long after anyone spoke to be a response
+        segment(start, 30000, Some("Me"), "anything else"),
+    ];
+    let analytics = meeting_analytics(start, 40.0, &transcript);
+
+    assert_eq!(analytics.overlaps, 1);
+    assert!((analytics.overlap_secs - 1.0).abs() < 1e-9);
+    assert_eq!(analytics.responses, 2);
+    assert!((analytics.avg_response_secs.unwrap() - 1.4).abs() < 1e-9);
+    // Speech covers 2-5 s, 7.2-8 s, 8.6-9 s, and 29.2-30 s
+    assert!((analytics.silence_secs - 35.0).abs() < 1e-9);
+
+    let me = analytics
+        .speakers
+        .iter()
+        .find(|s| s.speaker.as_deref() == Some("Me"))
+        .unwrap();
+    assert_eq!(me.interruptions, 0);
+    assert!((me.avg_response_secs.unwrap() - 2.2).abs() < 1e-9);
+    let them = analytics
+        .speakers
+        .iter()
+        .find(|s| s.speaker.as_deref() == Some("Them"))
+        .unwrap();
+    assert_eq!(them.interruptions, 1);
+    assert!((them.avg_response_secs.unwrap() - 0.6).abs() < 1e-9);
+}
+
+#[test]
+fn test_silence_runs_to_the_last_segment() {
+    let start = transcript_start();
+    // The meeting duration is shorter than the transcript
+    let analytics = meeting_analytics(start, 1.0, &[segment(start, 5000, None, "hello there")]);
+    assert!((analytics.silence_secs - 4.2).abs() < 1e-9);
+    assert_eq!(analytics.overlaps, 0);
+    assert_eq!(analytics.avg_response_secs, None);
+}
+
+#[test]
+fn test_segments_without_speakers() {
+    let start = transcript_start();
+    let analytics = meeting_analytics(start, 10.0, &[segment(start, 3000, None, "hello there")]);
+
+    assert!(!analytics.has_speakers());
+    assert_eq!(analytics.speakers.len(), 1);
+    assert_eq!(analytics.speakers[0].percent, 100.0);
+
+    let empty = meeting_analytics(start, 10.0, &[]);
+    assert!(empty.speakers.is_empty());
+    assert_eq!(empty.longest_monologue, None);
+    assert_eq!(empty.silence_secs, 10.0);
+}
+
+#[test]
+fn test_note_summary_shows_talk_time() {
+    let note = render_note(
+        &record("talk", conversation(transcript_start())),
+        &NoteFormat::default(),
+    );
+    assert!(note.contains("## Summary\n"), "Got {}", note);
+    assert!(note.contains("- **Talk time:** Me 88% (00:00:06), Them 12% (00:00:01)\n"));
+    assert!(note.contains("- **Longest monologue:** Me, 00:00:06 from [00:00:02]\n"));
+
+    let start = transcript_start();
+    let unattributed = record("quiet", vec![segment(start, 3000, None, "hello there")]);
+    assert!(!render_note(&unattributed, &NoteFormat::default()).contains("## Summary"));
+}
+
+#[tokio::test]
+async fn test_analytics_endpoint() -> Result<()> {
+    let dir = TempDir::new()?;
+    let storage = Arc::new(FilesystemStorage::new(dir.path().to_path_buf()));
+    storage
+        .save_meeting(&record("talk", conversation(transcript_start())))
+        .await?;
+    let mut router = create_router(AppState::with_config(Config::default(), storage));
+
+    for (uri, status) in [
+        ("/meetings/talk/analytics", StatusCode::OK),
+        ("/meetings/missing/analytics", StatusCode::NOT_FOUND),
+    ] {
+        std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx)).await?;
+        let response = router
+            .call(Request::get(uri).body(Body::empty()).unwrap())
+            .await?;
+        assert_eq!(response.status(), status, "{}", uri);
+
+        if status == StatusCode::OK {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            let analytics: MeetingAnalytics = serde_json::from_slice(&body)?;
+            assert_eq!(analytics.speakers[0].speaker.as_deref(), Some("Me"));
+            assert!(analytics.longest_monologue.is_some());
+        }
+    }
+    Ok(())
+}
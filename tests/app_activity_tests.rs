@@ -0,0 +1,84 @@
+// Unit tests for per-application audio activity tracking
+//
+// These tests verify that app activity is attributed and summarized correctly
+// without requiring a live ScreenCaptureKit session.
+
+use loqa_meetings::audio::activity::channel_level;
+use loqa_meetings::audio::{AppActivityTracker, AudioApplication};
+use std::time::Duration;
+
+fn app(bundle_id: &str, name: &str) -> AudioApplication {
+    AudioApplication {
+        pid: 1,
+        bundle_id: bundle_id.to_string(),
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn test_tracker_attributes_share_by_level() {
+    let zoom = app("us.zoom.xos", "zoom.us");
+    let music = app("com.apple.Music", "Music");
+    let mut tracker = AppActivityTracker::new();
+
+    // Zoom alone while the system stream is loud
+    for _ in 0..3 {
+        tracker.record(std::slice::from_ref(&zoom), 0.5, Duration::from_secs(1));
+    }
+    // Music alone while the system stream is quiet
+    tracker.record(std::slice::from_ref(&music), 0.1, Duration::from_secs(1));
+
+    let summary = tracker.take_summary();
+
+    assert_eq!(summary.window_secs, 4.0);
+    assert_eq!(summary.apps.len(), 2);
+
+    let dominant = summary.dominant().expect("Should have a dominant app");
+    assert_eq!(dominant.bundle_id, "us.zoom.xos");
+    assert_eq!(dominant.active_secs, 3.0);
+    assert!((dominant.share - 1.5 / 1.6).abs() < 1e-6);
+
+    let total: f32 = summary.apps.iter().map(|a| a.share).sum();
+    assert!((total - 1.0).abs() < 1e-6, "Shares should sum to 1.0");
+}
+
+#[test]
+fn test_tracker_falls_back_to_active_time_when_silent() {
+    let mut tracker = AppActivityTracker::new();
+
+    tracker.record(&[app("a", "A")], 0.0, Duration::from_secs(3));
+    tracker.record(&[app("b", "B")], 0.0, Duration::from_secs(1));
+
+    let summary = tracker.take_summary();
+
+    assert_eq!(summary.apps[0].bundle_id, "a");
+    assert!((summary.apps[0].share - 0.75).abs() < 1e-6);
+}
+
+#[test]
+fn test_tracker_resets_after_summary() {
+    let mut tracker = AppActivityTracker::new();
+    assert!(tracker.is_empty());
+
+    tracker.record(&[app("a", "A")], 0.2, Duration::from_secs(1));
+    assert!(!tracker.is_empty());
+
+    let _ = tracker.take_summary();
+    assert!(tracker.is_empty());
+
+    let summary = tracker.take_summary();
+    assert_eq!(summary.window_secs, 0.0);
+    assert!(summary.apps.is_empty());
+    assert!(summary.dominant().is_none());
+}
+
+#[test]
+fn test_channel_level_selects_channel() {
+    // Stereo: left at full scale, right silent
+    let samples = vec![i16::MAX, 0, i16::MAX, 0];
+
+    assert!((channel_level(&samples, 2, 0) - 1.0).abs() < 1e-6);
+    assert_eq!(channel_level(&samples, 2, 1), 0.0);
+    assert_eq!(channel_level(&samples, 2, 2), 0.0, "Out-of-range channel");
+    assert_eq!(channel_level(&[], 1, 0), 0.0, "Empty input");
+}
@@ -0,0 +1,74 @@
+// Tests for crash-safe atomic writes of persisted files
+
+use anyhow::Result;
+use loqa_meetings::audio::workdir::{is_workdir_file, MANIFEST_FILE};
+use loqa_meetings::storage::{create_atomic, partial_target, write_atomic};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_write_replaces_the_file_and_leaves_nothing_behind() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("meeting.json");
+
+    write_atomic(&path, "first")?;
+    write_atomic(&path, b"second")?;
+    assert_eq!(fs::read_to_string(&path)?, "second");
+    assert_eq!(file_names(dir.path()), vec!["meeting.json"]);
+    Ok(())
+}
+
+#[test]
+fn test_failed_write_keeps_what_was_there() -> Result<()> {
+    let dir = TempDir::new()?;
+    // A directory can't be replaced by a file, so the rename fails
+    let path = dir.path().join("meeting.json");
+    fs::create_dir(&path)?;
+    fs::write(path.join("keep"), "old")?;
+
+    assert!(write_atomic(&path, "new").is_err());
+    assert_eq!(fs::read_to_string(path.join("keep"))?, "old");
+    assert_eq!(file_names(dir.path()), vec!["meeting.json"]);
+
+    assert!(write_atomic(&dir.path().join("missing").join("a.json"), "new").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_create_never_replaces_an_existing_file() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join(".loqa-lock");
+
+    create_atomic(&path, "first")?;
+    let err = create_atomic(&path, "second").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read_to_string(&path)?, "first");
+    assert_eq!(file_names(dir.path()), vec![".loqa-lock"]);
+    Ok(())
+}
+
+#[test]
+fn test_leftover_partial_files_are_recognized() {
+    assert_eq!(
+        partial_target(".meeting.json.1234-7.partial"),
+        Some("meeting.json")
+    );
+    assert_eq!(partial_target("meeting.json"), None);
+    assert_eq!(partial_target(".partial"), None);
+
+    // A manifest write cut short isn't mistaken for meeting audio
+    let leftover = format!(".{}.99-0.partial", MANIFEST_FILE);
+    assert!(is_workdir_file(OsStr::new(&leftover)));
+    assert!(!is_workdir_file(OsStr::new(".chunk_000.wav.99-0.partial")));
+}
@@ -61,6 +61,7 @@ fn test_audio_backend_config_custom() {
         target_sample_rate: 48000,
         target_channels: 2,
         buffer_duration_ms: 200,
+        ..Default::default()
     };
 
     assert_eq!(config.target_sample_rate, 48000);
@@ -74,6 +75,7 @@ fn test_audio_backend_config_clone() {
         target_sample_rate: 16000,
         target_channels: 1,
         buffer_duration_ms: 100,
+        ..Default::default()
     };
 
     let cloned = config.clone();
@@ -131,6 +133,7 @@ fn test_audio_backend_config_for_whisper() {
         target_sample_rate: 16000,
         target_channels: 1,
         buffer_duration_ms: 100,
+        ..Default::default()
     };
 
     assert_eq!(whisper_config.target_sample_rate, 16000);
@@ -144,6 +147,7 @@ fn test_audio_backend_config_for_hifi() {
         target_sample_rate: 48000,
         target_channels: 2,
         buffer_duration_ms: 50, // Lower latency for live monitoring
+        ..Default::default()
     };
 
     assert_eq!(hifi_config.target_sample_rate, 48000);
@@ -3,8 +3,9 @@
 // These tests verify that we can read WAV files and extract audio data correctly.
 
 use anyhow::Result;
-use loqa_meetings::audio::AudioFile;
+use loqa_meetings::audio::{AudioDecoder, AudioFile, DecodeProgress};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 fn get_test_fixture_path(filename: &str) -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -120,15 +121,13 @@ fn test_audio_file_samples_are_i16() -> Result<()> {
     let path = get_test_fixture_path("sample-meeting.wav");
     let audio = AudioFile::open(&path)?;
 
-    // Verify samples are in valid i16 range
-    for (i, &sample) in audio.samples.iter().take(100).enumerate() {
-        assert!(
-            sample >= i16::MIN && sample <= i16::MAX,
-            "Sample {} at index {} is out of i16 range",
-            sample,
-            i
-        );
-    }
+    // Samples are decoded to 16-bit PCM, and the recording isn't silent
+    let samples: &[i16] = &audio.samples;
+    assert!(!samples.is_empty());
+    assert!(
+        samples.iter().any(|&sample| sample != 0),
+        "Decoded samples are all zero"
+    );
 
     Ok(())
 }
@@ -156,3 +155,130 @@ fn test_audio_file_interleaved_channels() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_audio_decoder_blocks_match_open() -> Result<()> {
+    let path = get_test_fixture_path("sample-meeting.wav");
+    let audio = AudioFile::open(&path)?;
+    let decoder = AudioDecoder::open(&path)?;
+
+    assert_eq!(decoder.sample_rate, audio.sample_rate);
+    assert_eq!(decoder.channels, audio.channels);
+
+    let channels = decoder.channels as u64;
+    let mut samples = Vec::new();
+    let mut expected_offset = 0;
+    for block in decoder {
+        let block = block?;
+        assert_eq!(
+            block.offset_frames, expected_offset,
+            "Blocks should be contiguous"
+        );
+        expected_offset += block.samples.len() as u64 / channels;
+        samples.extend_from_slice(&block.samples);
+    }
+
+    assert_eq!(samples, audio.samples);
+    Ok(())
+}
+
+#[test]
+fn test_decode_progress_fraction() {
+    let progress = DecodeProgress {
+        decoded_frames: 250,
+        total_frames: Some(1000),
+    };
+    assert_eq!(progress.fraction(), Some(0.25));
+
+    let unknown = DecodeProgress {
+        decoded_frames: 250,
+        total_frames: None,
+    };
+    assert_eq!(unknown.fraction(), None);
+}
+
+#[tokio::test]
+async fn test_audio_file_open_async_reports_progress() -> Result<()> {
+    let path = get_test_fixture_path("sample-meeting.wav");
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let sink = reports.clone();
+
+    let audio = AudioFile::open_async(&path, move |p| sink.lock().unwrap().push(p)).await?;
+    assert_eq!(audio.samples, AudioFile::open(&path)?.samples);
+
+    let reports = reports.lock().unwrap();
+    assert!(!reports.is_empty(), "Progress should be reported");
+    assert!(reports
+        .windows(2)
+        .all(|w| w[0].decoded_frames < w[1].decoded_frames));
+    let last = reports.last().unwrap();
+    assert_eq!(
+        last.decoded_frames,
+        audio.samples.len() as u64 / audio.channels as u64
+    );
+    if last.total_frames.is_some() {
+        assert_eq!(last.fraction(), Some(1.0));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_audio_stream_is_bounded() -> Result<()> {
+    let path = get_test_fixture_path("sample-meeting.wav");
+    let reports = Arc::new(Mutex::new(0usize));
+    let sink = reports.clone();
+
+    let mut stream = AudioFile::stream(&path, 1, move |_| *sink.lock().unwrap() += 1).await?;
+    let first = stream.next_block().await?.expect("file has audio");
+    assert_eq!(first.offset_frames, 0);
+
+    // With the reader paused, the decoder can only run a couple of blocks ahead
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(*reports.lock().unwrap() <= 3);
+
+    let mut blocks = 1;
+    while stream.next_block().await?.is_some() {
+        blocks += 1;
+    }
+    assert_eq!(*reports.lock().unwrap(), blocks);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_audio_stream_nonexistent() {
+    let result = AudioFile::stream("/nonexistent/path/to/audio.wav", 4, |_| {}).await;
+    assert!(result.is_err(), "Streaming nonexistent file should fail");
+}
+
+#[test]
+fn test_audio_file_probe_matches_open() -> Result<()> {
+    let path = get_test_fixture_path("sample-meeting.wav");
+    let info = AudioFile::probe(&path)?;
+    let audio = AudioFile::open(&path)?;
+
+    assert_eq!(info.sample_rate, audio.sample_rate);
+    assert_eq!(info.channels, audio.channels);
+    assert!(
+        info.codec.starts_with("pcm"),
+        "WAV codec was {}",
+        info.codec
+    );
+    let duration = info.duration_seconds.expect("WAV declares its length");
+    assert!((duration - audio.duration_seconds).abs() < 0.01);
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_file_probe_rejects_unsupported() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("notes.wav");
+    std::fs::write(&path, b"this is not audio")?;
+
+    assert!(AudioFile::probe(&path).is_err());
+    assert!(AudioFile::probe("/nonexistent/path/to/audio.wav").is_err());
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// Tests for bookmarks and the pre-roll audio they save
+
+use anyhow::Result;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use loqa_meetings::audio::{write_clip, PrerollBuffer};
+use loqa_meetings::session::Bookmark;
+use loqa_meetings::storage::FilesystemStorage;
+use loqa_meetings::{
+    create_router, AppState, Config, Marker, MarkerKind, RecordingSession, SessionConfig,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tower::Service;
+
+#[test]
+fn test_preroll_keeps_only_the_most_recent_audio() {
+    // One second of 10Hz stereo audio
+    let mut preroll = PrerollBuffer::new(Duration::from_secs(1), 10, 2);
+    preroll.push(&(0..12).collect::<Vec<i16>>());
+    assert_eq!(preroll.duration(), Duration::from_millis(600));
+
+    preroll.push(&(12..30).collect::<Vec<i16>>());
+    assert_eq!(preroll.duration(), Duration::from_secs(1));
+    assert_eq!(
+        preroll.last(Duration::from_secs(10)),
+        (10..30).collect::<Vec<i16>>()
+    );
+    assert_eq!(
+        preroll.last(Duration::from_millis(200)),
+        vec![26, 27, 28, 29],
+        "Whole frames from the end"
+    );
+
+    // A push larger than the buffer keeps its tail
+    preroll.push(&(100..150).collect::<Vec<i16>>());
+    assert_eq!(
+        preroll.last(Duration::from_secs(1)),
+        (130..150).collect::<Vec<i16>>()
+    );
+}
+
+#[test]
+fn test_clip_is_written_as_wav() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("highlights").join("bookmark-1000.wav");
+    let samples: Vec<i16> = (0..1600).map(|i| (i % 100) as i16).collect();
+    write_clip(&path, &samples, 16000, 1)?;
+
+    let mut reader = hound::WavReader::open(&path)?;
+    assert_eq!(reader.spec().sample_rate, 16000);
+    assert_eq!(reader.spec().channels, 1);
+    let read: Vec<i16> = reader.samples::<i16>().collect::<Result<_, _>>()?;
+    assert_eq!(read, samples);
+    Ok(())
+}
+
+#[test]
+fn test_bookmark_serialization() {
+    let bookmark = Bookmark {
+        marker: Marker {
+            kind: MarkerKind::Bookmark,
+            offset_ms: 61_000,
+            timestamp: chrono::Utc::now(),
+            label: "Pricing decision".to_string(),
+        },
+        clip_ms: 30_000,
+        clip: Some("/recordings/m/highlights/bookmark-61000.wav".into()),
+    };
+
+    let json = serde_json::to_value(&bookmark).unwrap();
+    assert_eq!(json["marker"]["kind"], "bookmark");
+    assert_eq!(json["clip_ms"], 30_000);
+    assert_eq!(json["clip"], "/recordings/m/highlights/bookmark-61000.wav");
+}
+
+#[tokio::test]
+async fn test_bookmark_needs_a_recording_session() -> Result<()> {
+    let session = RecordingSession::new(SessionConfig {
+        transcription: false,
+        ..Default::default()
+    })
+    .await?;
+    assert!(session.bookmark(None, None).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bookmark_endpoint_for_unknown_meeting() {
+    let dir = TempDir::new().unwrap();
+    let storage = Arc::new(FilesystemStorage::new(dir.path().to_path_buf()));
+    let mut router = create_router(AppState::with_config(Config::default(), storage));
+
+    let request = Request::post("/meetings/not-recording/bookmark?seconds=10")
+        .body(Body::empty())
+        .unwrap();
+    std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx))
+        .await
+        .unwrap();
+    let response = router.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,102 @@
+// Tests for the C API over the meetings service
+//
+// Only built with the `capi` feature.
+
+#![cfg(feature = "capi")]
+
+mod common;
+
+use chrono::{Duration, Utc};
+use loqa_meetings::capi::{
+    loqa_meetings_free, loqa_meetings_new, loqa_meetings_start, loqa_meetings_status,
+    loqa_meetings_string_free, loqa_meetings_transcript, LoqaMeetings, LoqaStatus,
+};
+use loqa_meetings::{FilesystemStorage, Storage};
+use std::ffi::{c_char, CStr, CString};
+use tempfile::TempDir;
+
+/// A service storing meetings in `dir`
+fn service(dir: &TempDir) -> *mut LoqaMeetings {
+    // The shipped config, storing meetings in `dir`
+    let config = std::fs::read_to_string("config/loqa-meetings.yaml")
+        .unwrap()
+        .replace(
+            "path: ~/.loqa/meetings",
+            &format!("path: {}", dir.path().join("meetings").display()),
+        );
+    let path = dir.path().join("loqa-meetings.yaml");
+    std::fs::write(&path, config).unwrap();
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let handle = unsafe { loqa_meetings_new(path.as_ptr()) };
+    assert!(!handle.is_null());
+    handle
+}
+
+/// Take a returned JSON string, freeing it
+fn take_json(out: *mut c_char) -> serde_json::Value {
+    assert!(!out.is_null());
+    let json = unsafe { CStr::from_ptr(out) }.to_str().unwrap().to_string();
+    unsafe { loqa_meetings_string_free(out) };
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn test_errors_come_back_as_status_and_json() {
+    let dir = TempDir::new().unwrap();
+    let handle = service(&dir);
+    let meeting_id = CString::new("missing").unwrap();
+
+    let mut out = std::ptr::null_mut();
+    let status = unsafe { loqa_meetings_status(handle, meeting_id.as_ptr(), &mut out) };
+    assert_eq!(status, LoqaStatus::NotFound);
+    assert_eq!(take_json(out)["error"], "Meeting missing not found");
+
+    let options = CString::new("{not json").unwrap();
+    let mut out = std::ptr::null_mut();
+    let status = unsafe { loqa_meetings_start(handle, options.as_ptr(), &mut out) };
+    assert_eq!(status, LoqaStatus::InvalidArgument);
+    assert!(take_json(out)["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid options"));
+
+    let status = unsafe {
+        loqa_meetings_status(std::ptr::null(), meeting_id.as_ptr(), std::ptr::null_mut())
+    };
+    assert_eq!(status, LoqaStatus::InvalidArgument);
+
+    unsafe { loqa_meetings_free(handle) };
+}
+
+#[test]
+fn test_stored_transcript_is_returned_as_json() {
+    let dir = TempDir::new().unwrap();
+    let handle = service(&dir);
+
+    let started_at = Utc::now() - Duration::hours(1);
+    let transcript = vec![common::segment(
+        "Morning everyone",
+        started_at + Duration::seconds(5),
+    )];
+    let record = common::meeting(
+        "standup",
+        started_at,
+        Duration::minutes(1),
+        transcript,
+        Vec::new(),
+    );
+    let storage = FilesystemStorage::new(dir.path().join("meetings"));
+    tokio::runtime::Runtime::new()
+        .unwrap()
+        .block_on(storage.save_meeting(&record))
+        .unwrap();
+
+    let meeting_id = CString::new("standup").unwrap();
+    let mut out = std::ptr::null_mut();
+    let status = unsafe { loqa_meetings_transcript(handle, meeting_id.as_ptr(), &mut out) };
+    assert_eq!(status, LoqaStatus::Ok);
+    let transcript = take_json(out);
+    unsafe { loqa_meetings_free(handle) };
+
+    assert_eq!(transcript[0]["text"], "Morning everyone");
+}
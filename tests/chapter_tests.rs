@@ -0,0 +1,184 @@
+// Tests for automatic chapter detection
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use common::segment;
+use loqa_meetings::export::NoteTemplate;
+use loqa_meetings::session::{apply_chapters, detect_chapters, LexicalChapterDetector};
+use loqa_meetings::{
+    ChapterConfig, Marker, MarkerKind, MeetingRecord, NoteFormat, TranscriptSegment,
+};
+
+/// Segments every `step` seconds, cycling through `texts`
+fn topic(
+    started_at: DateTime<Utc>,
+    from_secs: i64,
+    step: i64,
+    count: usize,
+    texts: &[&str],
+) -> Vec<TranscriptSegment> {
+    (0..count)
+        .map(|i| {
+            segment(
+                texts[i % texts.len()],
+                started_at + Duration::seconds(from_secs + i as i64 * step),
+            )
+        })
+        .collect()
+}
+
+const BUDGET: &[&str] = &[
+    "The budget forecast for next quarter looks tight",
+    "Spending on cloud infrastructure is over budget",
+    "We should revisit the budget forecast with finance",
+];
+
+const HIRING: &[&str] = &[
+    "Hiring is behind, we interviewed two candidates",
+    "The backend candidates did well in the interview loop",
+    "Hiring another engineer depends on the interview feedback",
+];
+
+fn marker(kind: MarkerKind, offset_ms: u64, label: &str, started_at: DateTime<Utc>) -> Marker {
+    Marker {
+        kind,
+        offset_ms,
+        timestamp: started_at + Duration::milliseconds(offset_ms as i64),
+        label: label.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_topic_shift_starts_a_chapter() {
+    let started_at = Utc::now();
+    let mut transcript = topic(started_at, 0, 20, 16, BUDGET);
+    transcript.extend(topic(started_at, 320, 20, 16, HIRING));
+
+    let detector = LexicalChapterDetector::new(ChapterConfig::default());
+    assert_eq!(detector.boundaries(started_at, &transcript), vec![0, 16]);
+
+    let chapters = detect_chapters(&ChapterConfig::default(), started_at, &transcript).await;
+    assert_eq!(chapters.len(), 2);
+    assert_eq!(chapters[0].offset_ms, 0);
+    assert!(chapters[0].title.starts_with("Budget"));
+    assert_eq!(chapters[1].offset_ms, 320_000);
+    assert!(chapters[1].title.starts_with("Hiring"));
+}
+
+#[test]
+fn test_long_pause_starts_a_chapter() {
+    let started_at = Utc::now();
+    let mut transcript = topic(started_at, 0, 30, 10, BUDGET);
+    transcript.extend(topic(started_at, 360, 30, 10, BUDGET));
+
+    let detector = LexicalChapterDetector::new(ChapterConfig::default());
+    assert_eq!(detector.boundaries(started_at, &transcript), vec![0, 10]);
+}
+
+#[tokio::test]
+async fn test_short_or_disabled_meetings_get_no_chapters() {
+    let started_at = Utc::now();
+
+    // Too short to split: a single chapter is not worth marking
+    let mut short = topic(started_at, 0, 10, 10, BUDGET);
+    short.extend(topic(started_at, 100, 10, 10, HIRING));
+    assert!(
+        detect_chapters(&ChapterConfig::default(), started_at, &short)
+            .await
+            .is_empty()
+    );
+
+    let mut transcript = topic(started_at, 0, 20, 16, BUDGET);
+    transcript.extend(topic(started_at, 320, 20, 16, HIRING));
+    let disabled = ChapterConfig {
+        enabled: false,
+        ..ChapterConfig::default()
+    };
+    assert!(detect_chapters(&disabled, started_at, &transcript)
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_unreachable_model_falls_back_to_heuristics() {
+    let started_at = Utc::now();
+    let mut transcript = topic(started_at, 0, 20, 16, BUDGET);
+    transcript.extend(topic(started_at, 320, 20, 16, HIRING));
+
+    let config = ChapterConfig {
+        detector_url: Some("http://127.0.0.1:9/chapters".to_string()),
+        ..ChapterConfig::default()
+    };
+    let chapters = detect_chapters(&config, started_at, &transcript).await;
+    assert_eq!(chapters.len(), 2);
+}
+
+#[tokio::test]
+async fn test_chapters_replace_old_chapter_markers() {
+    let started_at = Utc::now();
+    let mut markers = vec![
+        marker(MarkerKind::Chapter, 0, "Stale", started_at),
+        marker(MarkerKind::Gap, 400_000, "Microphone lost: USB", started_at),
+    ];
+
+    let mut transcript = topic(started_at, 0, 20, 16, BUDGET);
+    transcript.extend(topic(started_at, 320, 20, 16, HIRING));
+    let chapters = detect_chapters(&ChapterConfig::default(), started_at, &transcript).await;
+    apply_chapters(&mut markers, started_at, chapters);
+
+    let kinds: Vec<_> = markers.iter().map(|m| (m.kind, m.offset_ms)).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (MarkerKind::Chapter, 0),
+            (MarkerKind::Chapter, 320_000),
+            (MarkerKind::Gap, 400_000),
+        ]
+    );
+    assert!(markers.iter().all(|m| m.label != "Stale"));
+    assert_eq!(markers[1].timestamp, started_at + Duration::seconds(320));
+}
+
+#[test]
+fn test_chapters_in_exported_notes() {
+    let started_at = Utc::now();
+    let transcript = vec![segment("Budget first", started_at)];
+    let record = MeetingRecord {
+        markers: vec![
+            marker(MarkerKind::Chapter, 0, "Budget, forecast", started_at),
+            marker(
+                MarkerKind::Chapter,
+                320_000,
+                "Hiring, interview",
+                started_at,
+            ),
+            marker(MarkerKind::Gap, 400_000, "Microphone lost: USB", started_at),
+        ],
+        ..common::meeting(
+            "planning",
+            started_at,
+            Duration::minutes(10),
+            transcript,
+            Vec::new(),
+        )
+    };
+
+    let note = loqa_meetings::render_note(&record, &NoteFormat::default());
+    let chapters = note.find("## Chapters").expect("chapters section");
+    assert!(chapters < note.find("## Transcript").unwrap());
+    assert!(
+        note.contains("1. **[00:00:00]** Budget, forecast\n2. **[00:05:20]** Hiring, interview\n")
+    );
+    let markers = &note[note.find("## Markers").expect("markers section")..];
+    assert!(markers.contains("Microphone lost: USB"));
+    assert!(!markers.contains("Hiring"));
+
+    let template =
+        NoteTemplate::from_source("{% for c in chapters %}{{ c.time }} {{ c.label }};{% endfor %}")
+            .unwrap();
+    assert_eq!(
+        template.render(&record, &NoteFormat::default()).unwrap(),
+        "00:00:00 Budget, forecast;00:05:20 Hiring, interview;"
+    );
+}
@@ -4,7 +4,11 @@
 // time-based chunks and saved to disk as WAV files.
 
 use anyhow::Result;
-use loqa_meetings::audio::{AudioFrame, AudioStreamSource, ChunkConfig, ChunkedRecorder};
+use chrono::{TimeZone, Utc};
+use loqa_meetings::audio::{
+    file_sha256, write_clip, AudioFrame, AudioStreamSource, ChannelMap, ChunkCodec, ChunkConfig,
+    ChunkRotation, ChunkedRecorder, FloatFrame, WavInfo,
+};
 use std::fs;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -20,6 +24,12 @@ async fn test_chunked_recording_creates_single_chunk() -> Result<()> {
         chunk_duration_secs: 10, // 10 second chunks
         output_dir: output_dir.clone(),
         meeting_id: "test-meeting".to_string(),
+        codec: ChunkCodec::Pcm16,
+        channel_labels: Vec::new(),
+        title: None,
+        started_at: None,
+        rotation: ChunkRotation::default(),
+        overlap_secs: 0,
     };
 
     let mut recorder = ChunkedRecorder::new(config)?;
@@ -88,6 +98,12 @@ async fn test_chunked_recording_splits_into_multiple_chunks() -> Result<()> {
         chunk_duration_secs: 2, // 2 second chunks
         output_dir: output_dir.clone(),
         meeting_id: "multi-chunk-test".to_string(),
+        codec: ChunkCodec::Pcm16,
+        channel_labels: Vec::new(),
+        title: None,
+        started_at: None,
+        rotation: ChunkRotation::default(),
+        overlap_secs: 0,
     };
 
     let mut recorder = ChunkedRecorder::new(config)?;
@@ -180,6 +196,12 @@ async fn test_chunked_recording_handles_empty_input() -> Result<()> {
         chunk_duration_secs: 5,
         output_dir: output_dir.clone(),
         meeting_id: "empty-test".to_string(),
+        codec: ChunkCodec::Pcm16,
+        channel_labels: Vec::new(),
+        title: None,
+        started_at: None,
+        rotation: ChunkRotation::default(),
+        overlap_secs: 0,
     };
 
     let mut recorder = ChunkedRecorder::new(config)?;
@@ -209,6 +231,12 @@ async fn test_chunked_recording_preserves_audio_format() -> Result<()> {
         chunk_duration_secs: 10,
         output_dir: output_dir.clone(),
         meeting_id: "format-test".to_string(),
+        codec: ChunkCodec::Pcm16,
+        channel_labels: Vec::new(),
+        title: None,
+        started_at: None,
+        rotation: ChunkRotation::default(),
+        overlap_secs: 0,
     };
 
     let mut recorder = ChunkedRecorder::new(config)?;
@@ -248,6 +276,216 @@ async fn test_chunked_recording_preserves_audio_format() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_chunk_progress_tracks_files_on_disk() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config = ChunkConfig {
+        chunk_duration_secs: 2,
+        ..ChunkConfig::new("test-meeting".to_string(), temp_dir.path().to_path_buf())
+    };
+    let mut recorder = ChunkedRecorder::new(config)?;
+
+    let progress = recorder.progress();
+    assert_eq!(progress.chunks_count, 0);
+    assert_eq!(progress.audio_bytes, 0);
+    assert_eq!(progress.current_chunk_percent, None);
+
+    // 3 seconds of 100ms frames: one full chunk and half of the next
+    let mut finished = Vec::new();
+    for i in 0..31 {
+        let frame = FloatFrame::from(AudioFrame {
+            samples: vec![0i16; 1600],
+            sample_rate: 16000,
+            channels: 1,
+            timestamp_ms: i * 100,
+            source: AudioStreamSource::System,
+        });
+        finished.extend(recorder.write(&frame)?);
+    }
+    assert_eq!(finished.len(), 1);
+    assert_eq!(recorder.chunks().len(), 1);
+
+    let progress = recorder.progress();
+    assert_eq!(progress.chunks_count, 2);
+    assert_eq!(progress.current_chunk_percent, Some(50.0));
+    let first_file = fs::metadata(&finished[0].file_path)?.len();
+    assert_eq!(progress.audio_bytes, first_file + 11 * 1600 * 2);
+
+    // Finishing closes the current chunk and counts its real size
+    let last = recorder.finish()?.expect("a chunk was open");
+    let progress = recorder.progress();
+    assert_eq!(progress.chunks_count, 2);
+    assert_eq!(progress.current_chunk_percent, None);
+    assert_eq!(
+        progress.audio_bytes,
+        first_file + fs::metadata(&last.file_path)?.len()
+    );
+    assert!(recorder.finish()?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_stereo_chunks_are_labelled() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config = ChunkConfig {
+        channel_labels: ChannelMap::default().labels(),
+        ..ChunkConfig::new("stereo".to_string(), temp_dir.path().to_path_buf())
+    };
+    let mut recorder = ChunkedRecorder::new(config)?;
+
+    // System left, microphone right
+    let samples: Vec<i16> = (0..1600).flat_map(|_| [1000i16, -2000]).collect();
+    recorder.write(&FloatFrame::from(AudioFrame {
+        samples: samples.clone(),
+        sample_rate: 16000,
+        channels: 2,
+        timestamp_ms: 0,
+        source: AudioStreamSource::System,
+    }))?;
+    let chunk = recorder.finish()?.expect("a chunk was open");
+
+    assert_eq!(chunk.channel_labels, vec!["system", "microphone"]);
+    assert_eq!(
+        WavInfo::read(&chunk.file_path)?.unwrap().channel_labels,
+        vec!["system", "microphone"]
+    );
+    assert_eq!(
+        chunk.sha256,
+        Some(file_sha256(&chunk.file_path)?),
+        "Hashed after labelling"
+    );
+
+    // The labels don't disturb the audio
+    let mut reader = hound::WavReader::open(&chunk.file_path)?;
+    assert_eq!(reader.spec().channels, 2);
+    let read: Vec<i16> = reader.samples::<i16>().collect::<Result<_, _>>()?;
+    assert_eq!(read, samples);
+
+    let json = serde_json::to_value(&chunk)?;
+    assert_eq!(json["channel_labels"][1], "microphone");
+    Ok(())
+}
+
+#[test]
+fn test_chunks_are_tagged_with_the_meeting() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let started_at = Utc.with_ymd_and_hms(2025, 6, 3, 9, 0, 0).unwrap();
+    let config = ChunkConfig {
+        chunk_duration_secs: 2,
+        title: Some("Product sync".to_string()),
+        started_at: Some(started_at),
+        ..ChunkConfig::new("2025-06-03-sync".to_string(), temp_dir.path().to_path_buf())
+    };
+    let mut recorder = ChunkedRecorder::new(config)?;
+    for i in 0..30 {
+        recorder.write(&FloatFrame::from(AudioFrame {
+            samples: vec![0i16; 1600],
+            sample_rate: 16000,
+            channels: 1,
+            timestamp_ms: i * 100,
+            source: AudioStreamSource::System,
+        }))?;
+    }
+    recorder.finish()?;
+
+    let chunk = &recorder.chunks()[1];
+    let info = WavInfo::read(&chunk.file_path)?.expect("chunk is tagged");
+    assert_eq!(info.title.as_deref(), Some("Product sync"));
+    assert_eq!(info.meeting_id.as_deref(), Some("2025-06-03-sync"));
+    assert_eq!(info.track, Some(2));
+    assert_eq!(
+        info.created,
+        Some(started_at + chrono::Duration::seconds(2)),
+        "Tagged with when the chunk's audio starts"
+    );
+    assert!(info
+        .software
+        .is_some_and(|software| software.starts_with("loqa-meetings ")));
+    Ok(())
+}
+
+#[test]
+fn test_wav_info_roundtrip() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("clip.wav");
+    let samples = vec![7i16; 160];
+    write_clip(&path, &samples, 16000, 1)?;
+    assert_eq!(WavInfo::read(&path)?, None);
+
+    // Odd-length values are padded
+    let info = WavInfo {
+        title: Some("Odd".to_string()),
+        track: Some(12),
+        channel_labels: vec!["system".to_string(), "unused".to_string()],
+        ..Default::default()
+    };
+    info.append_to(&path)?;
+    assert_eq!(WavInfo::read(&path)?, Some(info));
+
+    let mut reader = hound::WavReader::open(&path)?;
+    let read: Vec<i16> = reader.samples::<i16>().collect::<Result<_, _>>()?;
+    assert_eq!(read, samples);
+    Ok(())
+}
+
+#[test]
+fn test_mono_chunks_are_not_labelled() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config = ChunkConfig {
+        channel_labels: ChannelMap::default().labels(),
+        ..ChunkConfig::new("mono".to_string(), temp_dir.path().to_path_buf())
+    };
+    let mut recorder = ChunkedRecorder::new(config)?;
+    recorder.write(&FloatFrame::from(AudioFrame {
+        samples: vec![0i16; 1600],
+        sample_rate: 16000,
+        channels: 1,
+        timestamp_ms: 0,
+        source: AudioStreamSource::System,
+    }))?;
+    let chunk = recorder.finish()?.expect("a chunk was open");
+
+    assert!(chunk.channel_labels.is_empty());
+    assert!(WavInfo::read(&chunk.file_path)?
+        .unwrap()
+        .channel_labels
+        .is_empty());
+    assert!(serde_json::to_value(&chunk)?
+        .get("channel_labels")
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn test_chunk_write_failure_stops_recording() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("chunks");
+    let mut recorder = ChunkedRecorder::new(ChunkConfig::new(
+        "test-meeting".to_string(),
+        output_dir.clone(),
+    ))?;
+
+    // The chunk file can't be created once its directory is gone
+    fs::remove_dir(&output_dir)?;
+    let frame = FloatFrame::from(AudioFrame {
+        samples: vec![0i16; 1600],
+        sample_rate: 16000,
+        channels: 1,
+        timestamp_ms: 0,
+        source: AudioStreamSource::System,
+    });
+    assert!(recorder.write(&frame).is_err());
+    assert!(recorder.is_failed());
+
+    // Later frames are ignored rather than failing again
+    fs::create_dir(&output_dir)?;
+    assert!(recorder.write(&frame)?.is_none());
+    assert_eq!(recorder.progress().chunks_count, 0);
+
+    Ok(())
+}
+
 #[test]
 fn test_chunk_config_creation() {
     let config = ChunkConfig::new("test-meeting".to_string(), PathBuf::from("/tmp/test"));
@@ -266,7 +504,117 @@ fn test_chunk_config_custom_duration() {
         chunk_duration_secs: 60, // 1 minute
         output_dir: PathBuf::from("/tmp/test"),
         meeting_id: "test".to_string(),
+        codec: ChunkCodec::Pcm16,
+        channel_labels: Vec::new(),
+        title: None,
+        started_at: None,
+        rotation: ChunkRotation::default(),
+        overlap_secs: 0,
     };
 
     assert_eq!(config.chunk_duration_secs, 60);
 }
+
+/// Record 100ms frames, silent where `silent(timestamp_ms)` is true
+fn record_speech(config: ChunkConfig, secs: u64, silent: impl Fn(u64) -> bool) -> Result<Vec<u64>> {
+    let mut recorder = ChunkedRecorder::new(config)?;
+    for i in 0..secs * 10 {
+        let timestamp_ms = i * 100;
+        let level = if silent(timestamp_ms) { 0 } else { 3000 };
+        recorder.write(&FloatFrame::from(AudioFrame {
+            samples: vec![level; 1600],
+            sample_rate: 16000,
+            channels: 1,
+            timestamp_ms,
+            source: AudioStreamSource::System,
+        }))?;
+    }
+    recorder.finish()?;
+    Ok(recorder
+        .chunks()
+        .iter()
+        .map(|chunk| chunk.start_ms)
+        .collect())
+}
+
+fn silence_rotation(temp_dir: &TempDir) -> ChunkConfig {
+    ChunkConfig {
+        chunk_duration_secs: 2,
+        rotation: ChunkRotation {
+            on_silence: true,
+            tolerance_secs: 1,
+            ..Default::default()
+        },
+        ..ChunkConfig::new("test-meeting".to_string(), temp_dir.path().to_path_buf())
+    }
+}
+
+#[test]
+fn test_chunks_rotate_at_the_first_pause_after_the_duration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    // A pause at 1.0s is too early; the one at 2.4s ends the first chunk
+    // once 300ms of it have passed
+    let starts = record_speech(silence_rotation(&temp_dir), 5, |ms| {
+        (1000..1400).contains(&ms) || (2400..2800).contains(&ms)
+    })?;
+    assert_eq!(starts, vec![0, 2600]);
+    Ok(())
+}
+
+#[test]
+fn test_chunks_rotate_after_the_tolerance_without_a_pause() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let starts = record_speech(silence_rotation(&temp_dir), 5, |_| false)?;
+    assert_eq!(starts, vec![0, 3000]);
+
+    // Without silence rotation, chunks split exactly at the duration
+    let temp_dir = TempDir::new()?;
+    let config = ChunkConfig {
+        chunk_duration_secs: 2,
+        ..ChunkConfig::new("test-meeting".to_string(), temp_dir.path().to_path_buf())
+    };
+    let starts = record_speech(config, 5, |ms| (2400..2800).contains(&ms))?;
+    assert_eq!(starts, vec![0, 2000, 4000]);
+    Ok(())
+}
+
+#[test]
+fn test_chunks_repeat_the_overlap_from_the_previous_chunk() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config = ChunkConfig {
+        chunk_duration_secs: 2,
+        overlap_secs: 1,
+        ..ChunkConfig::new("test-meeting".to_string(), temp_dir.path().to_path_buf())
+    };
+    let mut recorder = ChunkedRecorder::new(config)?;
+
+    // Each 100ms frame holds its own index
+    for i in 0..50 {
+        recorder.write(&FloatFrame::from(AudioFrame {
+            samples: vec![i as i16; 1600],
+            sample_rate: 16000,
+            channels: 1,
+            timestamp_ms: i * 100,
+            source: AudioStreamSource::System,
+        }))?;
+    }
+    recorder.finish()?;
+
+    let chunks = recorder.chunks();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].overlap_ms, 0);
+    assert_eq!(chunks[0].sample_count, 20 * 1600);
+
+    // The second chunk starts at 2s, led in by 1-2s
+    assert_eq!(chunks[1].start_ms, 2000);
+    assert_eq!(chunks[1].overlap_ms, 1000);
+    assert_eq!(chunks[1].overlap_samples(), 16000);
+    assert_eq!(chunks[1].sample_count, 30 * 1600);
+    let samples: Vec<i16> = hound::WavReader::open(&chunks[1].file_path)?
+        .samples::<i16>()
+        .collect::<Result<_, _>>()?;
+    assert_eq!(samples[0], 10);
+    assert_eq!(samples[chunks[1].overlap_samples()], 20);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+// Tests for injecting a manual clock into sessions
+
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use loqa_meetings::audio::{AudioStreamSource, FilePacing, FileSourceConfig};
+use loqa_meetings::clock::{Clock, ManualClock, SystemClock};
+use loqa_meetings::{RecordingSession, SessionConfig, SessionEvent};
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[test]
+fn test_manual_clock_moves_only_when_told() {
+    let start = Utc.with_ymd_and_hms(2025, 6, 3, 9, 0, 0).unwrap();
+    let clock = ManualClock::new(start);
+    assert_eq!(clock.now(), start);
+
+    clock.advance(Duration::from_secs(90));
+    assert_eq!(clock.now(), start + chrono::Duration::seconds(90));
+    clock.set(start);
+    assert_eq!(clock.now(), start);
+
+    let before = Utc::now();
+    assert!(SystemClock.now() >= before);
+}
+
+#[test]
+fn test_clock_is_not_part_of_the_serialized_config() -> Result<()> {
+    let config = SessionConfig {
+        clock: ManualClock::new(Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap()),
+        ..Default::default()
+    };
+    let json = serde_json::to_value(&config)?;
+    assert!(json.get("clock").is_none());
+
+    let restored: SessionConfig = serde_json::from_value(json)?;
+    assert!(restored.clock.now().timestamp() > 946_684_800);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_session_times_follow_the_injected_clock() -> Result<()> {
+    let start = Utc.with_ymd_and_hms(2025, 6, 3, 9, 0, 0).unwrap();
+    let clock = ManualClock::new(start);
+    let session = RecordingSession::builder("standup")
+        .no_transcription()
+        .clock(clock.clone())
+        .build()
+        .await?;
+    let mut events = session.subscribe_events();
+
+    clock.advance(Duration::from_secs(150));
+    let stats = session.get_stats().await?;
+    assert_eq!(stats.started_at, start);
+    assert_eq!(stats.duration_secs, 150.0);
+
+    assert!(session.mute_source(AudioStreamSource::System).await?);
+    clock.advance(Duration::from_secs(30));
+    assert!(session.unmute_source(AudioStreamSource::System).await?);
+
+    let SessionEvent::SourceMuted { timestamp, .. } = events.recv().await? else {
+        panic!("Expected a mute event");
+    };
+    assert_eq!(timestamp, start + chrono::Duration::seconds(150));
+    let SessionEvent::SourceMuted { timestamp, .. } = events.recv().await? else {
+        panic!("Expected an unmute event");
+    };
+    assert_eq!(timestamp, start + chrono::Duration::seconds(180));
+
+    let markers = session.get_markers().await;
+    assert_eq!(markers[0].offset_ms, 150_000);
+    assert_eq!(markers[0].timestamp, start + chrono::Duration::seconds(150));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restarted_session_stops_at_its_last_stop() -> Result<()> {
+    let start = Utc.with_ymd_and_hms(2025, 6, 3, 9, 0, 0).unwrap();
+    let clock = ManualClock::new(start);
+    let dir = TempDir::new()?;
+    let session = RecordingSession::builder("standup")
+        .no_transcription()
+        .chunking(dir.path().join("audio"), Duration::from_secs(30))
+        .clock(clock.clone())
+        .configure(|config| {
+            config.file_source = Some(FileSourceConfig {
+                path: "tests/fixtures/sample-meeting.wav".into(),
+                pacing: FilePacing::Fast,
+            });
+        })
+        .build()
+        .await?;
+
+    // Stopped before it ever started
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(session.stop().await?.duration_secs, 60.0);
+    assert_eq!(
+        session.stopped_at(),
+        Some(start + chrono::Duration::seconds(60))
+    );
+
+    session.start().await?;
+    assert_eq!(session.stopped_at(), None);
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(session.stop().await?.duration_secs, 120.0);
+    assert_eq!(
+        session.stopped_at(),
+        Some(start + chrono::Duration::seconds(120))
+    );
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Fixtures shared by the integration tests
+//
+// Each test crate uses only some of them.
+
+#![allow(dead_code)]
+
+use chrono::{DateTime, Duration, Utc};
+use loqa_meetings::{ChunkMetadata, MeetingRecord, SessionState, SessionStats, TranscriptSegment};
+use std::path::Path;
+
+/// A final transcript segment heard at `at`
+pub fn segment(text: &str, at: DateTime<Utc>) -> TranscriptSegment {
+    TranscriptSegment {
+        text: text.to_string(),
+        timestamp: at,
+        ..Default::default()
+    }
+}
+
+/// A stopped meeting that started at `started_at` and ran for `duration`,
+/// its stats counting `transcript` and `chunks`
+pub fn meeting(
+    meeting_id: &str,
+    started_at: DateTime<Utc>,
+    duration: Duration,
+    transcript: Vec<TranscriptSegment>,
+    chunks: Vec<ChunkMetadata>,
+) -> MeetingRecord {
+    MeetingRecord {
+        meeting_id: meeting_id.to_string(),
+        started_at,
+        ended_at: started_at + duration,
+        stats: SessionStats {
+            state: SessionState::Stopped,
+            started_at,
+            duration_secs: duration.num_milliseconds() as f64 / 1000.0,
+            chunks_count: chunks.len(),
+            transcript_segments_count: transcript.len(),
+            ..Default::default()
+        },
+        transcript,
+        chunks,
+        ..Default::default()
+    }
+}
+
+/// A 16kHz mono chunk of `path` covering `start_ms` to `end_ms` of the
+/// meeting
+pub fn chunk(index: usize, path: &Path, start_ms: u64, end_ms: u64) -> ChunkMetadata {
+    ChunkMetadata {
+        chunk_index: index,
+        file_path: path.to_path_buf(),
+        start_ms,
+        end_ms,
+        sample_rate: 16000,
+        channels: 1,
+        sample_count: ((end_ms - start_ms) * 16) as usize,
+        track: None,
+        sha256: None,
+        channel_labels: Vec::new(),
+        overlap_ms: 0,
+    }
+}
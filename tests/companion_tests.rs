@@ -0,0 +1,217 @@
+// Tests for mixing a companion device's audio into a live session
+
+use loqa_meetings::audio::{AudioStreamSource, NetworkSourceConfig};
+use loqa_meetings::session::{CompanionFormat, MAX_COMPANIONS};
+use loqa_meetings::storage::FilesystemStorage;
+use loqa_meetings::{create_router, AppState, Config, RecordingSession, SessionConfig};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Record-only session receiving (silent) network audio, which records on
+/// any platform
+async fn network_session(dir: &TempDir) -> RecordingSession {
+    RecordingSession::new(SessionConfig {
+        session_id: "companion".to_string(),
+        audio_dir: Some(dir.path().to_path_buf()),
+        transcription: false,
+        network_source: Some(NetworkSourceConfig {
+            bind: SocketAddr::from(([127, 0, 0, 1], 0)),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_companions_join_only_recording_sessions() {
+    let dir = TempDir::new().unwrap();
+    let session = network_session(&dir).await;
+    assert!(session.join_companion(&CompanionFormat::default()).is_err());
+
+    session.start().await.unwrap();
+    let bad_format = CompanionFormat {
+        channels: 3,
+        ..Default::default()
+    };
+    assert!(session.join_companion(&bad_format).is_err());
+
+    let inputs: Vec<_> = (0..MAX_COMPANIONS)
+        .map(|_| session.join_companion(&CompanionFormat::default()).unwrap())
+        .collect();
+    assert!(session.join_companion(&CompanionFormat::default()).is_err());
+    assert_eq!(inputs[0].source(), AudioStreamSource::MicrophoneDevice(0));
+    assert!(session.sources().contains(&inputs[1].source()));
+
+    // A companion's source is free again once it leaves
+    let left = inputs[0].source();
+    drop(inputs);
+    assert!(!session.sources().contains(&left));
+    assert_eq!(
+        session
+            .join_companion(&CompanionFormat::default())
+            .unwrap()
+            .source(),
+        left
+    );
+
+    session.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_companion_audio_is_mixed_into_the_session() {
+    let dir = TempDir::new().unwrap();
+    let session = network_session(&dir).await;
+    let mut frames = session.subscribe_frames();
+    session.start().await.unwrap();
+
+    let mut input = session
+        .join_companion(&CompanionFormat {
+            sample_rate: 16000,
+            ..Default::default()
+        })
+        .unwrap();
+    session.mute_source(input.source()).await.unwrap();
+    session.unmute_source(input.source()).await.unwrap();
+
+    // A second of audio: the network source sends nothing, so the mix stops
+    // waiting for it
+    let audio: Vec<u8> = [1000i16; 1600]
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect();
+    for _ in 0..10 {
+        input.send(&audio).unwrap();
+    }
+    assert!(input.send(&[0x01]).is_ok(), "A lone byte holds no sample");
+
+    let frame = tokio::time::timeout(Duration::from_secs(5), frames.recv())
+        .await
+        .expect("companion audio should be mixed")
+        .unwrap();
+    assert!(frame.samples.iter().any(|&s| s != 0));
+
+    session.stop().await.unwrap();
+    assert!(input.send(&audio).is_err());
+}
+
+#[tokio::test]
+async fn test_pairing_token_is_issued_once_per_recording_session() {
+    let dir = TempDir::new().unwrap();
+    let session = network_session(&dir).await;
+    assert!(session.pair_companion().is_err());
+    assert!(!session.is_paired_companion(""));
+
+    session.start().await.unwrap();
+    let token = session.pair_companion().unwrap();
+    assert_eq!(session.pair_companion().unwrap(), token);
+    assert!(session.is_paired_companion(&token));
+    assert!(!session.is_paired_companion(""));
+    assert!(!session.is_paired_companion(&token[1..]));
+
+    session.stop().await.unwrap();
+}
+
+/// Serve the API with `session` recording, returning its address
+async fn serve(dir: &TempDir, session: Arc<RecordingSession>) -> SocketAddr {
+    let storage = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let state = AppState::with_config(Config::default(), storage);
+    state
+        .sessions
+        .write()
+        .await
+        .insert("companion".to_string(), session);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, create_router(state)).await });
+    addr
+}
+
+/// Status line of the response to a GET of `path`, as a WebSocket upgrade
+async fn upgrade_status(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\
+         Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+        path, addr
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = vec![0; 1024];
+    let read = stream.read(&mut response).await.unwrap();
+    let response = String::from_utf8_lossy(&response[..read]).into_owned();
+    response.lines().next().unwrap_or_default().to_string()
+}
+
+#[tokio::test]
+async fn test_unpaired_companion_upgrade_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let session = Arc::new(network_session(&dir).await);
+    session.start().await.unwrap();
+    let addr = serve(&dir, Arc::clone(&session)).await;
+
+    // Before any device is paired, and with a wrong token after
+    let status = upgrade_status(addr, "/meetings/companion/ingest").await;
+    assert!(status.contains("401"), "{}", status);
+    let token = session.pair_companion().unwrap();
+    let status = upgrade_status(addr, "/meetings/companion/ingest?token=guess").await;
+    assert!(status.contains("401"), "{}", status);
+    assert!(session
+        .sources()
+        .iter()
+        .all(|source| !matches!(source, AudioStreamSource::MicrophoneDevice(_))));
+
+    let path = format!(
+        "/meetings/companion/ingest?token={}&sample_rate=16000",
+        token
+    );
+    let status = upgrade_status(addr, &path).await;
+    assert!(status.contains("101"), "{}", status);
+
+    session.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_capture_page_is_served_to_paired_devices() {
+    let dir = TempDir::new().unwrap();
+    let session = Arc::new(network_session(&dir).await);
+    session.start().await.unwrap();
+    let addr = serve(&dir, Arc::clone(&session)).await;
+
+    let pairing: serde_json::Value = reqwest::Client::new()
+        .post(format!("http://{}/meetings/companion/companion", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let token = pairing["token"].as_str().unwrap();
+    assert_eq!(
+        pairing["ingest_url"],
+        format!("/meetings/companion/ingest?token={}", token)
+    );
+
+    let page = reqwest::get(format!(
+        "http://{}{}",
+        addr,
+        pairing["page_url"].as_str().unwrap()
+    ))
+    .await
+    .unwrap();
+    assert_eq!(page.status(), 200);
+    assert!(page.text().await.unwrap().contains("getUserMedia"));
+
+    let unpaired = reqwest::get(format!("http://{}/meetings/companion/companion", addr))
+        .await
+        .unwrap();
+    assert_eq!(unpaired.status(), 401);
+
+    session.stop().await.unwrap();
+}
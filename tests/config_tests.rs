@@ -0,0 +1,85 @@
+// Tests for service configuration loading and profile resolution
+
+use anyhow::Result;
+use loqa_meetings::audio::DownmixStrategy;
+use loqa_meetings::session::UtteranceBoundary;
+use loqa_meetings::{ChannelMap, Config, StorageBackend};
+
+#[test]
+fn test_load_default_config_file() -> Result<()> {
+    let config = Config::load("config/loqa-meetings")?;
+
+    assert_eq!(config.service.name, "loqa-meetings");
+    assert_eq!(config.audio.sample_rate, 16000);
+    assert_eq!(config.audio.channel_map, ChannelMap::default());
+    assert!(config.profiles.contains_key("default"));
+    assert!(config.host.report);
+    assert_eq!(config.host.name, None);
+    assert_eq!(config.audio.utterance.boundary, UtteranceBoundary::Silence);
+    assert!(config.uploads.parallel);
+    assert_eq!(config.uploads.windows.overlap_secs, 5);
+    assert!(!config.transcripts.durable);
+    assert_eq!(config.audio.downmix, DownmixStrategy::Sum);
+    assert_eq!(config.transcripts.stream, "STT_TEXT");
+
+    Ok(())
+}
+
+#[test]
+fn test_profile_resolution() -> Result<()> {
+    let config = Config::load("config/loqa-meetings")?;
+
+    let focus = config.profile(Some("focus")).expect("focus profile exists");
+    assert!(focus
+        .excluded_apps
+        .contains(&"com.spotify.client".to_string()));
+    assert_eq!(focus.microphone_gain, Some(1.5));
+
+    let default = config.profile(None).expect("default always resolves");
+    assert!(default.excluded_apps.is_empty());
+    assert!(!default.privacy);
+
+    let confidential = config
+        .profile(Some("confidential"))
+        .expect("confidential profile exists");
+    assert!(confidential.privacy);
+
+    assert!(config.profile(Some("missing")).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_profile_falls_back_without_default() {
+    let config = Config::default();
+
+    let profile = config.profile(None).expect("falls back to empty profile");
+    assert!(profile.excluded_apps.is_empty());
+}
+
+#[test]
+fn test_storage_config_defaults() -> Result<()> {
+    let config = Config::load("config/loqa-meetings")?;
+
+    assert_eq!(config.storage.backend, StorageBackend::Filesystem);
+    assert!(!config
+        .storage
+        .resolved_path()
+        .to_string_lossy()
+        .starts_with('~'));
+
+    Ok(())
+}
+
+#[test]
+fn test_note_format_falls_back_to_obsidian_default() -> Result<()> {
+    let config = Config::load("config/loqa-meetings")?;
+
+    assert_eq!(config.note_format(None), config.obsidian.note_format);
+    assert_eq!(
+        config.note_format(Some("missing")),
+        config.obsidian.note_format
+    );
+
+    Ok(())
+}
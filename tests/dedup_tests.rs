@@ -0,0 +1,80 @@
+// Tests for ignoring transcript segments the STT service resends
+
+use loqa_meetings::nats::{TranscriptMessage, PROTOCOL_VERSION};
+use loqa_meetings::session::SegmentLedger;
+
+fn transcript(session_id: &str, text: &str, segment_id: Option<&str>) -> TranscriptMessage {
+    TranscriptMessage {
+        version: PROTOCOL_VERSION,
+        session_id: session_id.to_string(),
+        text: text.to_string(),
+        partial: false,
+        timestamp: "2026-01-01T10:00:05Z".to_string(),
+        confidence: None,
+        speaker: None,
+        segment_id: segment_id.map(str::to_string),
+        language: None,
+    }
+}
+
+#[test]
+fn test_resent_segment_id_is_ignored() {
+    let mut ledger = SegmentLedger::new();
+
+    assert!(ledger.admit(&transcript("standup", "hello", Some("seg-1"))));
+    // A resend may be re-worded, but keeps its ID
+    assert!(!ledger.admit(&transcript("standup", "hello there", Some("seg-1"))));
+    assert!(ledger.admit(&transcript("standup", "next", Some("seg-2"))));
+    assert_eq!(ledger.duplicates(), 1);
+}
+
+#[test]
+fn test_segments_without_ids_match_on_offset_and_text() {
+    let mut ledger = SegmentLedger::new();
+
+    assert!(ledger.admit(&transcript("standup", "hello", None)));
+    assert!(!ledger.admit(&transcript("standup", " hello ", None)));
+
+    // Same time, different words: a separate segment
+    assert!(ledger.admit(&transcript("standup", "goodbye", None)));
+
+    let mut later = transcript("standup", "hello", None);
+    later.timestamp = "2026-01-01T10:00:09Z".to_string();
+    assert!(ledger.admit(&later));
+}
+
+#[test]
+fn test_ledger_is_per_stream() {
+    let mut ledger = SegmentLedger::new();
+
+    assert!(ledger.admit(&transcript("standup.mic", "hello", Some("seg-1"))));
+    assert!(ledger.admit(&transcript("standup.system", "hello", Some("seg-1"))));
+    assert!(!ledger.admit(&transcript("standup.mic", "hello", Some("seg-1"))));
+}
+
+#[test]
+fn test_partials_are_not_recorded() {
+    let mut ledger = SegmentLedger::new();
+
+    let mut partial = transcript("standup", "hel", Some("seg-1"));
+    partial.partial = true;
+    assert!(ledger.admit(&partial));
+    assert!(ledger.admit(&partial));
+
+    // The final segment still goes through after its partials
+    assert!(ledger.admit(&transcript("standup", "hello", Some("seg-1"))));
+    assert_eq!(ledger.duplicates(), 0);
+}
+
+#[test]
+fn test_segment_id_is_optional_in_json() {
+    let json = r#"{"version":1,"session_id":"standup","text":"hi","partial":false,"timestamp":"2026-01-01T10:00:05Z"}"#;
+    let msg: TranscriptMessage = serde_json::from_str(json).unwrap();
+    assert_eq!(msg.segment_id, None);
+
+    let with_id = transcript("standup", "hi", Some("seg-9"));
+    let value = serde_json::to_value(&with_id).unwrap();
+    assert_eq!(value["segment_id"], "seg-9");
+    let without = serde_json::to_value(transcript("standup", "hi", None)).unwrap();
+    assert!(without.get("segment_id").is_none());
+}
@@ -0,0 +1,138 @@
+// Tests for word-level transcript diffs
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::segment;
+use loqa_meetings::session::{diff_transcripts, DiffKind};
+
+#[test]
+fn test_identical_transcripts_have_no_errors() {
+    let start = Utc::now();
+    let transcript = vec![
+        segment("Good morning everyone.", start),
+        segment("Let's get started.", start + Duration::seconds(5)),
+    ];
+
+    let diff = diff_transcripts(start, &transcript, start, &transcript);
+
+    assert_eq!(diff.chunks.len(), 1);
+    assert_eq!(diff.chunks[0].kind, DiffKind::Equal);
+    assert_eq!(diff.stats.words_a, 6);
+    assert_eq!(diff.stats.matched, 6);
+    assert_eq!(diff.stats.word_error_rate, 0.0);
+}
+
+#[test]
+fn test_case_and_punctuation_are_ignored() {
+    let start = Utc::now();
+    let a = vec![segment("Hello, world!", start)];
+    let b = vec![segment("hello world", start)];
+
+    let diff = diff_transcripts(start, &a, start, &b);
+
+    assert_eq!(diff.stats.matched, 2);
+    assert_eq!(diff.stats.word_error_rate, 0.0);
+    // Original text is kept
+    assert_eq!(diff.chunks[0].a, "Hello, world!");
+    assert_eq!(diff.chunks[0].b, "hello world");
+}
+
+#[test]
+fn test_substitutions_insertions_and_deletions() {
+    let start = Utc::now();
+    let a = vec![segment("the quick brown fox jumps over the dog", start)];
+    let b = vec![segment(
+        "the quick brown socks jumps over the lazy dog",
+        start,
+    )];
+
+    let diff = diff_transcripts(start, &a, start, &b);
+
+    assert_eq!(diff.stats.substituted, 1);
+    assert_eq!(diff.stats.inserted, 1);
+    assert_eq!(diff.stats.deleted, 0);
+    assert_eq!(diff.stats.matched, 7);
+    assert!((diff.stats.word_error_rate - 2.0 / 8.0).abs() < 1e-9);
+
+    let replaced = diff
+        .chunks
+        .iter()
+        .find(|c| c.kind == DiffKind::Replace)
+        .unwrap();
+    assert_eq!((replaced.a.as_str(), replaced.b.as_str()), ("fox", "socks"));
+    let inserted = diff
+        .chunks
+        .iter()
+        .find(|c| c.kind == DiffKind::Insert)
+        .unwrap();
+    assert_eq!(inserted.a, "");
+    assert_eq!(inserted.b, "lazy");
+}
+
+#[test]
+fn test_segmentation_differences_align() {
+    let start = Utc::now();
+    let a = vec![
+        segment("we shipped the release", start),
+        segment("on friday", start + Duration::seconds(4)),
+    ];
+    let b = vec![segment("we shipped the release on friday", start)];
+
+    let diff = diff_transcripts(start, &a, start, &b);
+
+    assert_eq!(diff.stats.matched, 6);
+    assert_eq!(diff.stats.word_error_rate, 0.0);
+}
+
+#[test]
+fn test_offsets_are_relative_to_each_meeting() {
+    let a_start = Utc::now();
+    let b_start = a_start + Duration::hours(3);
+    let a = vec![
+        segment("intro", a_start),
+        segment("budget review", a_start + Duration::seconds(90)),
+    ];
+    let b = vec![
+        segment("intro", b_start),
+        segment("budget reviews", b_start + Duration::seconds(90)),
+    ];
+
+    let diff = diff_transcripts(a_start, &a, b_start, &b);
+
+    let replaced = diff
+        .chunks
+        .iter()
+        .find(|c| c.kind == DiffKind::Replace)
+        .unwrap();
+    assert_eq!(replaced.offset_ms, 90_000);
+    assert_eq!(diff.chunks[0].offset_ms, 0);
+}
+
+#[test]
+fn test_partial_segments_are_skipped() {
+    let start = Utc::now();
+    let mut partial = segment("hel", start);
+    partial.partial = true;
+    let a = vec![partial, segment("hello", start)];
+    let b = vec![segment("hello", start)];
+
+    let diff = diff_transcripts(start, &a, start, &b);
+
+    assert_eq!(diff.stats.words_a, 1);
+    assert_eq!(diff.stats.word_error_rate, 0.0);
+}
+
+#[test]
+fn test_empty_reference() {
+    let start = Utc::now();
+    let b = vec![segment("something", start)];
+
+    let diff = diff_transcripts(start, &[], start, &b);
+    assert_eq!(diff.stats.inserted, 1);
+    assert_eq!(diff.stats.word_error_rate, 1.0);
+
+    let diff = diff_transcripts(start, &[], start, &[]);
+    assert!(diff.chunks.is_empty());
+    assert_eq!(diff.stats.word_error_rate, 0.0);
+}
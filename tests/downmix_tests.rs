@@ -0,0 +1,193 @@
+// Tests for downmix strategies, surround downmixing, and audio track selection
+
+use anyhow::Result;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use loqa_meetings::audio::{
+    AudioDecoder, AudioFile, AudioStreamSource, Downmix, DownmixStrategy, FloatFrame,
+};
+use std::path::Path;
+use symphonia::core::audio::Channels;
+
+/// Write a 5.1 WAV (L R C LFE Ls Rs) with a different constant per channel
+fn write_surround_wav(path: &Path, frames: usize, levels: [i16; 6]) -> Result<()> {
+    let spec = WavSpec {
+        channels: 6,
+        sample_rate: 48000,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec)?;
+    for _ in 0..frames {
+        for level in levels {
+            writer.write_sample(level)?;
+        }
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+#[test]
+fn test_downmix_mono_and_stereo() {
+    assert_eq!(Downmix::for_channel_count(1).apply(&[5, -7]), vec![5, -7]);
+    assert_eq!(
+        Downmix::for_channel_count(2).apply(&[1000, 3000, -200, 200]),
+        vec![2000, 0]
+    );
+}
+
+#[test]
+fn test_downmix_5_1_keeps_centre_and_drops_lfe() {
+    let downmix = Downmix::for_channel_count(6);
+    assert_eq!(downmix.channels(), 6);
+
+    // Dialogue only in the centre channel comes through at -3dB
+    let centre = downmix.apply(&[0, 0, 10000, 0, 0, 0]);
+    assert_eq!(centre, vec![7071]);
+
+    // LFE never reaches the mono mix
+    assert_eq!(downmix.apply(&[0, 0, 0, 30000, 0, 0]), vec![0]);
+
+    // Surrounds are quieter than the fronts
+    let front = downmix.apply(&[10000, 0, 0, 0, 0, 0])[0];
+    let surround = downmix.apply(&[0, 0, 0, 0, 10000, 0])[0];
+    assert!(surround < front && surround > 0);
+}
+
+#[test]
+fn test_downmix_for_layout_follows_channel_order() {
+    // 3.0: L R C
+    let layout = Channels::FRONT_LEFT | Channels::FRONT_RIGHT | Channels::FRONT_CENTRE;
+    let weights = Downmix::for_layout(layout).weights().to_vec();
+    assert_eq!(weights.len(), 3);
+    assert_eq!(weights[0], 0.5);
+    assert_eq!(weights[1], 0.5);
+    assert!((weights[2] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+}
+
+#[test]
+fn test_downmix_clips_instead_of_wrapping() {
+    let downmix = Downmix::new(vec![1.0, 1.0]);
+    assert_eq!(downmix.apply(&[i16::MAX, i16::MAX]), vec![i16::MAX]);
+    assert_eq!(downmix.apply(&[i16::MIN, i16::MIN]), vec![i16::MIN]);
+}
+
+#[test]
+fn test_stereo_downmix_strategies() {
+    let stereo = [24_000i16, 12_000, -4_000, 0];
+    let mono = |strategy: DownmixStrategy| Downmix::for_strategy(&strategy, 2).apply(&stereo);
+
+    assert_eq!(mono(DownmixStrategy::Sum), vec![i16::MAX, -4_000]);
+    assert_eq!(mono(DownmixStrategy::Average), vec![18_000, -2_000]);
+    assert_eq!(mono(DownmixStrategy::LeftOnly), vec![24_000, -4_000]);
+    assert_eq!(mono(DownmixStrategy::RightOnly), vec![12_000, 0]);
+    assert_eq!(
+        mono(DownmixStrategy::Weighted(vec![0.75, 0.25])),
+        vec![21_000, -3_000]
+    );
+}
+
+#[test]
+fn test_downmix_strategies_beyond_stereo() {
+    let standard = Downmix::for_channel_count(6);
+    assert_eq!(Downmix::for_strategy(&DownmixStrategy::Sum, 6), standard);
+    assert_eq!(
+        Downmix::for_strategy(&DownmixStrategy::Average, 6),
+        standard
+    );
+    // Weights only apply with one per channel
+    assert_eq!(
+        Downmix::for_strategy(&DownmixStrategy::Weighted(vec![0.5, 0.5]), 6),
+        standard
+    );
+
+    assert_eq!(
+        Downmix::for_strategy(&DownmixStrategy::RightOnly, 3).weights(),
+        &[0.0, 1.0, 0.0]
+    );
+    assert_eq!(
+        Downmix::for_strategy(&DownmixStrategy::RightOnly, 1).weights(),
+        &[1.0]
+    );
+}
+
+#[test]
+fn test_float_frames_share_the_strategy() {
+    let frame = FloatFrame {
+        samples: vec![0.5, 0.25, -0.5, 0.0],
+        sample_rate: 48000,
+        channels: 2,
+        timestamp_ms: 0,
+        source: AudioStreamSource::System,
+    };
+
+    let average = frame.clone().downmix(&DownmixStrategy::Average);
+    assert_eq!(average.channels, 1);
+    assert_eq!(average.samples, vec![0.375, -0.25]);
+
+    // Float frames keep headroom instead of clipping
+    let sum = FloatFrame {
+        samples: vec![0.75, 0.75],
+        ..frame
+    }
+    .downmix(&DownmixStrategy::Sum);
+    assert_eq!(sum.samples, vec![1.5]);
+}
+
+#[test]
+fn test_downmix_strategy_names() -> Result<()> {
+    let parsed: Vec<DownmixStrategy> = serde_json::from_str(
+        r#"["sum", "average", "left_only", "right_only", {"weighted": [0.7, 0.3]}]"#,
+    )?;
+    assert_eq!(
+        parsed,
+        vec![
+            DownmixStrategy::Sum,
+            DownmixStrategy::Average,
+            DownmixStrategy::LeftOnly,
+            DownmixStrategy::RightOnly,
+            DownmixStrategy::Weighted(vec![0.7, 0.3]),
+        ]
+    );
+    assert_eq!(DownmixStrategy::default(), DownmixStrategy::Sum);
+    Ok(())
+}
+
+#[test]
+fn test_surround_file_folds_to_mono() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("surround.wav");
+    write_surround_wav(&path, 4800, [0, 0, 10000, 20000, 0, 0])?;
+
+    let audio = AudioFile::open(&path)?;
+    assert_eq!(audio.channels, 6);
+    assert_eq!(audio.downmix.channels(), 6);
+
+    let mono = audio.to_mono();
+    assert_eq!(mono.len(), 4800);
+    assert!(
+        mono.iter().all(|&s| s == 7071),
+        "centre at -3dB, LFE dropped"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_track_selection() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("surround.wav");
+    write_surround_wav(&path, 480, [1, 2, 3, 4, 5, 6])?;
+
+    let decoder = AudioDecoder::open_track(&path, 0)?;
+    assert_eq!(decoder.track, 0);
+    assert_eq!(decoder.tracks, 1);
+    let info = AudioFile::probe(&path)?;
+    assert_eq!((info.track, info.tracks, info.other_tracks), (0, 1, 0));
+
+    let err = AudioFile::open_track(&path, 1)
+        .err()
+        .expect("no second track");
+    assert!(err.to_string().contains("1 audio track"), "{}", err);
+
+    Ok(())
+}
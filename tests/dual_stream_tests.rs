@@ -0,0 +1,137 @@
+// Tests for dual-stream (system/mic) transcription helpers
+
+use chrono::{Duration, Utc};
+use loqa_meetings::session::{insert_by_timestamp, split_stereo, StreamRole};
+use loqa_meetings::{AudioFrame, AudioStreamSource, ChannelMap, ChannelRole, TranscriptSegment};
+
+#[test]
+fn test_stream_role_sub_session_ids() {
+    assert_eq!(
+        StreamRole::System.sub_session_id("standup"),
+        "standup.system"
+    );
+    assert_eq!(StreamRole::Mic.sub_session_id("standup"), "standup.mic");
+
+    assert_eq!(
+        StreamRole::from_sub_session_id("standup", "standup.mic"),
+        Some(StreamRole::Mic)
+    );
+    assert_eq!(
+        StreamRole::from_sub_session_id("standup", "standup.system"),
+        Some(StreamRole::System)
+    );
+    assert_eq!(StreamRole::from_sub_session_id("standup", "standup"), None);
+    assert_eq!(
+        StreamRole::from_sub_session_id("standup", "standup.other"),
+        None
+    );
+    assert_eq!(
+        StreamRole::from_sub_session_id("standup", "retro.mic"),
+        None
+    );
+
+    assert_eq!(StreamRole::System.speaker(), "Them");
+    assert_eq!(StreamRole::Mic.speaker(), "Me");
+}
+
+#[test]
+fn test_split_stereo_separates_channels() {
+    let frame = AudioFrame {
+        samples: vec![1, -1, 2, -2, 3, -3],
+        sample_rate: 48000,
+        channels: 2,
+        timestamp_ms: 500,
+        source: AudioStreamSource::System,
+    };
+
+    let (system, mic) = split_stereo(&frame).expect("stereo frame");
+    assert_eq!(system.samples, vec![1, 2, 3]);
+    assert_eq!(mic.samples, vec![-1, -2, -3]);
+    assert_eq!(system.channels, 1);
+    assert_eq!(mic.timestamp_ms, 500);
+
+    let mono = AudioFrame {
+        channels: 1,
+        ..frame
+    };
+    assert!(split_stereo(&mono).is_none());
+}
+
+#[test]
+fn test_insert_by_timestamp_interleaves_streams() {
+    let start = Utc::now();
+    let segment = |text: &str, secs: i64, speaker: &str| TranscriptSegment {
+        text: text.to_string(),
+        timestamp: start + Duration::seconds(secs),
+        speaker: Some(speaker.to_string()),
+        ..Default::default()
+    };
+
+    let mut transcript = Vec::new();
+    insert_by_timestamp(&mut transcript, segment("Hello", 0, "Them"));
+    insert_by_timestamp(&mut transcript, segment("Can you hear me", 10, "Them"));
+    // The mic stream's result arrives late but belongs in between
+    insert_by_timestamp(&mut transcript, segment("Hi there", 4, "Me"));
+    insert_by_timestamp(&mut transcript, segment("Yes", 10, "Me"));
+
+    let order: Vec<&str> = transcript.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(order, vec!["Hello", "Hi there", "Can you hear me", "Yes"]);
+}
+
+#[test]
+fn test_channel_map_splits_by_role() {
+    let frame = AudioFrame {
+        samples: vec![1, -1, 2, -2, 3, -3],
+        sample_rate: 48000,
+        channels: 2,
+        timestamp_ms: 500,
+        source: AudioStreamSource::System,
+    };
+
+    // A bridge that swaps the channels
+    let swapped = ChannelMap::new(vec![ChannelRole::Microphone, ChannelRole::System]);
+    let frames = swapped.split(&frame).expect("matching layout");
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].source, AudioStreamSource::System);
+    assert_eq!(frames[0].samples, vec![-1, -2, -3]);
+    assert_eq!(frames[1].source, AudioStreamSource::Microphone);
+    assert_eq!(frames[1].samples, vec![1, 2, 3]);
+    assert_eq!(swapped.channel(ChannelRole::System), Some(1));
+
+    // A layout that does not match the frame is not guessed at
+    let three = ChannelMap::new(vec![
+        ChannelRole::System,
+        ChannelRole::Microphone,
+        ChannelRole::Unused,
+    ]);
+    assert!(three.split(&frame).is_none());
+}
+
+#[test]
+fn test_channel_map_sums_shared_roles_and_drops_unused() {
+    let frame = AudioFrame {
+        samples: vec![100, 20, 7, i16::MAX, 1, 9],
+        sample_rate: 48000,
+        channels: 3,
+        timestamp_ms: 0,
+        source: AudioStreamSource::System,
+    };
+
+    let map = ChannelMap::new(vec![
+        ChannelRole::System,
+        ChannelRole::System,
+        ChannelRole::Unused,
+    ]);
+    let frames = map.split(&frame).expect("matching layout");
+    assert_eq!(frames.len(), 1, "No microphone channel");
+    assert_eq!(frames[0].samples, vec![120, i16::MAX]);
+}
+
+#[test]
+fn test_channel_map_config_format() {
+    let map: ChannelMap = serde_json::from_str(r#"["microphone", "system"]"#).unwrap();
+    assert_eq!(map.roles(), &[ChannelRole::Microphone, ChannelRole::System]);
+    assert_eq!(map.to_string(), "[microphone, system]");
+    assert_eq!(ChannelMap::default().to_string(), "[system, microphone]");
+    assert!(serde_json::from_str::<ChannelMap>(r#"["left"]"#).is_err());
+}
@@ -0,0 +1,621 @@
+// Tests for meeting export (Markdown notes and zip bundles)
+
+mod common;
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use common::chunk;
+use loqa_meetings::audio::write_clip;
+use loqa_meetings::config::{DailyNotesConfig, NoteExportMode, ObsidianConfig};
+use loqa_meetings::export::{
+    export_meeting_note, obsidian_uri, post_callback, refresh_exported_note, render_meeting_note,
+    write_meeting_note, BundleFileKind, BundleReader, Linker, NoteExportedCallback, NoteLinks,
+    NoteTemplate, SpeakerLabelStyle, TimestampStyle, TranscriptLayout, BUNDLE_FORMAT_VERSION,
+};
+use loqa_meetings::{
+    render_note, write_bundle, BundleManifest, ChunkMetadata, MeetingRecord, NoteFormat,
+    TranscriptSegment,
+};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn record(chunks: Vec<ChunkMetadata>) -> MeetingRecord {
+    let started_at = Utc::now();
+    let transcript = vec![
+        TranscriptSegment {
+            confidence: Some(0.95),
+            ..common::segment("Good morning everyone", started_at + Duration::seconds(5))
+        },
+        common::segment(
+            "Let's start with blockers",
+            started_at + Duration::seconds(3725),
+        ),
+    ];
+    MeetingRecord {
+        title: Some("Daily \"Standup\"".to_string()),
+        ..common::meeting(
+            "standup-2025-10-28",
+            started_at,
+            Duration::seconds(905),
+            transcript,
+            chunks,
+        )
+    }
+}
+
+#[test]
+fn test_render_note() {
+    let note = render_note(&record(Vec::new()), &NoteFormat::default());
+
+    assert!(note.starts_with("---\nmeeting_id: standup-2025-10-28\n"));
+    assert!(note.contains("title: \"Daily \\\"Standup\\\"\""));
+    assert!(note.contains("duration: 00:15:05"));
+    assert!(note.contains("# Daily \"Standup\""));
+    assert!(note.contains("**[00:00:05]** Good morning everyone"));
+    assert!(note.contains("**[01:02:05]** Let's start with blockers"));
+}
+
+#[test]
+fn test_render_note_with_speakers_and_paragraphs() {
+    let mut record = record(Vec::new());
+    let start = record.started_at;
+    record.transcript = ["Hi all", "quick update", "Thanks"]
+        .iter()
+        .zip([("Alice", 0), ("Alice", 10), ("Bob", 12)])
+        .map(|(text, (speaker, secs))| TranscriptSegment {
+            text: text.to_string(),
+            timestamp: start + Duration::seconds(secs),
+            speaker: Some(speaker.to_string()),
+            ..Default::default()
+        })
+        .collect();
+
+    let format = NoteFormat {
+        layout: TranscriptLayout::Paragraphs,
+        speaker_labels: SpeakerLabelStyle::Plain,
+        timestamps: TimestampStyle::None,
+        heading: "## {meeting_id}".to_string(),
+        ..Default::default()
+    };
+    let note = render_note(&record, &format);
+
+    assert!(note.contains("## standup-2025-10-28\n"));
+    assert!(note.contains("\nAlice: Hi all quick update\n"));
+    assert!(note.contains("\nBob: Thanks\n"));
+    assert!(!note.contains("**["));
+}
+
+#[test]
+fn test_linker_inserts_wikilinks_and_collects_tags() {
+    let links = NoteLinks {
+        wikilinks: HashMap::from([
+            ("alice".to_string(), "People/Alice Smith".to_string()),
+            ("apollo".to_string(), "Apollo".to_string()),
+            ("project apollo".to_string(), "Projects/Apollo".to_string()),
+        ]),
+        tags: HashMap::from([("roadmap".to_string(), "planning/roadmap".to_string())]),
+        link_speakers: true,
+    };
+    let linker = Linker::new(&links);
+
+    let linked = linker.link("Alice: Project Apollo roadmap, then apollo. Malice, Apollos");
+    assert_eq!(
+        linked.text,
+        "[[People/Alice Smith|Alice]]: [[Projects/Apollo|Project Apollo]] roadmap, \
+         then [[Apollo|apollo]]. Malice, Apollos"
+    );
+    assert_eq!(linked.tags, vec!["planning/roadmap"]);
+
+    assert_eq!(linker.speaker("Alice"), "[[People/Alice Smith|Alice]]");
+    assert_eq!(linker.speaker("Bob"), "[[Bob]]");
+}
+
+#[test]
+fn test_render_note_with_links_and_tags() {
+    let mut record = record(Vec::new());
+    record.transcript[0].speaker = Some("Alice".to_string());
+    record.transcript[1].text = "Let's review the roadmap with Bob".to_string();
+
+    let format = NoteFormat {
+        timestamps: TimestampStyle::None,
+        links: NoteLinks {
+            wikilinks: HashMap::from([("bob".to_string(), "Bob".to_string())]),
+            tags: HashMap::from([("roadmap".to_string(), "roadmap".to_string())]),
+            link_speakers: true,
+        },
+        ..Default::default()
+    };
+    let note = render_note(&record, &format);
+
+    assert!(note.contains("tags: [meeting, roadmap]\n"), "Got {}", note);
+    assert!(note.contains("\n**[[Alice]]:** Good morning everyone\n"));
+    assert!(note.contains("\nLet's review the roadmap with [[Bob]] #roadmap\n"));
+}
+
+#[test]
+fn test_render_note_with_localized_absolute_dates() {
+    let format = NoteFormat {
+        timestamps: TimestampStyle::Absolute,
+        date_format: "%A".to_string(),
+        locale: Some("de_DE".to_string()),
+        heading: "# {date}".to_string(),
+        ..Default::default()
+    };
+    let note = render_note(&record(Vec::new()), &format);
+
+    let weekdays = [
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+        "Sonntag",
+    ];
+    assert!(
+        weekdays
+            .iter()
+            .any(|d| note.contains(&format!("# {}\n", d))),
+        "Heading not localized: {}",
+        note
+    );
+}
+
+#[test]
+fn test_write_bundle_contents() -> Result<()> {
+    let dir = TempDir::new()?;
+    let wav_path = dir.path().join("chunk_000.wav");
+    std::fs::write(&wav_path, b"RIFF fake wav data")?;
+
+    let record = record(vec![
+        chunk(0, &wav_path, 0, 1000),
+        chunk(1, &dir.path().join("missing.wav"), 0, 1000),
+    ]);
+
+    let mut progress = Vec::new();
+    let mut buffer = Vec::new();
+    let note = render_note(&record, &NoteFormat::default());
+    let created_at = record.ended_at + Duration::days(1);
+    let manifest = write_bundle(&record, &note, created_at, &mut buffer, |p| {
+        progress.push(p)
+    })?;
+
+    // Missing chunk is skipped
+    assert_eq!(manifest.format_version, BUNDLE_FORMAT_VERSION);
+    assert_eq!(manifest.created_at, created_at);
+    assert_eq!(manifest.files.len(), 4);
+    assert_eq!(manifest.files[3].kind, BundleFileKind::Audio);
+    assert_eq!(manifest.files[3].path, "audio/chunk_000.wav");
+    assert_eq!(progress.len(), 4);
+    assert_eq!(progress[0].files_total, 5);
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(buffer))?;
+    for name in [
+        "manifest.json",
+        "meeting.json",
+        "transcript.json",
+        "note.md",
+        "audio/chunk_000.wav",
+    ] {
+        assert!(archive.by_name(name).is_ok(), "Missing {}", name);
+    }
+
+    let mut audio = Vec::new();
+    archive
+        .by_name("audio/chunk_000.wav")?
+        .read_to_end(&mut audio)?;
+    assert_eq!(audio, b"RIFF fake wav data");
+
+    let mut json = String::new();
+    archive
+        .by_name("manifest.json")?
+        .read_to_string(&mut json)?;
+    let stored: BundleManifest = serde_json::from_str(&json)?;
+    assert_eq!(stored.meeting_id, "standup-2025-10-28");
+
+    Ok(())
+}
+
+#[test]
+fn test_bundle_roundtrip_restores_audio() -> Result<()> {
+    let source = TempDir::new()?;
+    let wav_path = source.path().join("chunk_000.wav");
+    std::fs::write(&wav_path, b"RIFF fake wav data")?;
+
+    let record = record(vec![chunk(0, &wav_path, 0, 1000)]);
+    let mut buffer = Vec::new();
+    write_bundle(&record, "# Note", Utc::now(), &mut buffer, |_| {})?;
+
+    let target = TempDir::new()?;
+    let reader = BundleReader::open(Cursor::new(buffer))?;
+    assert_eq!(reader.manifest.meeting_id, "standup-2025-10-28");
+
+    let restored = reader.extract(target.path())?;
+    assert_eq!(restored.transcript.len(), 2);
+    assert_eq!(restored.chunks.len(), 1);
+    assert_eq!(
+        restored.chunks[0].file_path,
+        target.path().join("chunk_000.wav")
+    );
+    assert_eq!(
+        std::fs::read(&restored.chunks[0].file_path)?,
+        b"RIFF fake wav data"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_bundle_reader_rejects_garbage() {
+    assert!(BundleReader::open(Cursor::new(b"not a zip".to_vec())).is_err());
+}
+
+#[test]
+fn test_write_meeting_note_to_vault() -> Result<()> {
+    let vault = TempDir::new()?;
+    let config = ObsidianConfig {
+        vault_path: vault.path().display().to_string(),
+        meetings_folder: "Meetings".to_string(),
+        ..Default::default()
+    };
+
+    let record = record(Vec::new());
+    let path = write_meeting_note(&config, &NoteFormat::default(), &record)?;
+
+    assert!(path.starts_with(vault.path().join("Meetings")));
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    assert!(name.ends_with(" Daily Standup.md"), "Got {}", name);
+    assert!(std::fs::read_to_string(&path)?.contains("Good morning everyone"));
+
+    Ok(())
+}
+
+#[test]
+fn test_note_template_renders_context() -> Result<()> {
+    let mut record = record(Vec::new());
+    record.transcript[1].text = "Action item: Bob sends the report".to_string();
+
+    let template = NoteTemplate::from_source(
+        "# {{ meeting.title }} ({{ meeting.duration }})\n\
+         {% for s in segments %}[{{ s.time }}] {{ s.text }}\n{% endfor %}\
+         {% for a in action_items %}- [ ] {{ a.text }}\n{% endfor %}\
+         {% if summary %}{{ summary }}{% else %}no summary{% endif %}",
+    )?;
+    let note = template.render(&record, &NoteFormat::default())?;
+
+    assert!(
+        note.starts_with("# Daily \"Standup\" (00:15:05)\n"),
+        "Got {}",
+        note
+    );
+    assert!(note.contains("[00:00:05] Good morning everyone"));
+    assert!(note.contains("- [ ] Action item: Bob sends the report\n"));
+    assert!(note.ends_with("no summary"));
+
+    Ok(())
+}
+
+#[test]
+fn test_note_template_errors() {
+    assert!(NoteTemplate::from_source("{% for s in segments %}").is_err());
+    assert!(NoteTemplate::load(std::path::Path::new("/nonexistent/note.md")).is_err());
+}
+
+#[test]
+fn test_render_meeting_note_uses_configured_template() -> Result<()> {
+    let dir = TempDir::new()?;
+    let template_path = dir.path().join("note.md");
+    std::fs::write(&template_path, "Meeting {{ meeting.id }}")?;
+
+    let config = ObsidianConfig {
+        vault_path: dir.path().display().to_string(),
+        template_path: Some(template_path.display().to_string()),
+        ..Default::default()
+    };
+    let note = render_meeting_note(&config, &NoteFormat::default(), &record(Vec::new()))?;
+    assert_eq!(note, "Meeting standup-2025-10-28");
+
+    Ok(())
+}
+
+#[test]
+fn test_daily_note_created_from_template_and_deduped() -> Result<()> {
+    let vault = TempDir::new()?;
+    let template_path = vault.path().join("daily-template.md");
+    std::fs::write(&template_path, "# {{title}}\n\n## Log\n")?;
+
+    let config = ObsidianConfig {
+        vault_path: vault.path().display().to_string(),
+        export_mode: NoteExportMode::DailyNote,
+        daily_notes: DailyNotesConfig {
+            folder: "Daily".to_string(),
+            template_path: Some(template_path.display().to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let first = record(Vec::new());
+    let path = export_meeting_note(&config, &NoteFormat::default(), &first)?;
+    let date = first
+        .started_at
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d")
+        .to_string();
+    assert_eq!(
+        path,
+        vault.path().join("Daily").join(format!("{}.md", date))
+    );
+
+    let note = std::fs::read_to_string(&path)?;
+    assert!(
+        note.starts_with(&format!("# {}\n\n## Log\n\n", date)),
+        "Got {}",
+        note
+    );
+    assert!(note.contains("<!-- loqa-meeting:start standup-2025-10-28 -->"));
+    // Front matter dropped, headings demoted under the daily note
+    assert!(!note.contains("tags: [meeting]"));
+    assert!(note.contains("\n## Daily \"Standup\"\n"));
+    assert!(note.contains("\n### Transcript\n"));
+
+    // User edits outside the section survive a re-export, which replaces
+    // the section rather than duplicating it
+    std::fs::write(&path, format!("{}\nMy own notes\n", note))?;
+    let mut updated = first.clone();
+    updated.transcript[0].text = "Good afternoon everyone".to_string();
+    export_meeting_note(&config, &NoteFormat::default(), &updated)?;
+
+    let note = std::fs::read_to_string(&path)?;
+    assert_eq!(
+        note.matches("loqa-meeting:start standup-2025-10-28")
+            .count(),
+        1
+    );
+    assert!(note.contains("Good afternoon everyone"));
+    assert!(!note.contains("Good morning everyone"));
+    assert!(note.contains("My own notes"));
+
+    // A second meeting the same day is appended
+    let mut second = first.clone();
+    second.meeting_id = "retro-2025-10-28".to_string();
+    export_meeting_note(&config, &NoteFormat::default(), &second)?;
+
+    let note = std::fs::read_to_string(&path)?;
+    assert_eq!(note.matches("loqa-meeting:start").count(), 2);
+    assert!(note.find("standup-2025-10-28").unwrap() < note.find("retro-2025-10-28").unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn test_obsidian_uri_encodes_path() {
+    let uri = obsidian_uri(std::path::Path::new(
+        "/vault/Meetings/2025-10-28 Daily & Co.md",
+    ));
+    assert_eq!(
+        uri,
+        "obsidian://open?path=%2Fvault%2FMeetings%2F2025-10-28%20Daily%20%26%20Co.md"
+    );
+}
+
+#[tokio::test]
+async fn test_post_callback_sends_note_details() -> Result<()> {
+    use axum::{routing::post, Json, Router};
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    let app = Router::new().route(
+        "/exported",
+        post(move |Json(body): Json<NoteExportedCallback>| async move {
+            let _ = tx.send(body).await;
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let payload = NoteExportedCallback {
+        meeting_id: "standup-2025-10-28".to_string(),
+        note_path: "/vault/Meetings/standup.md".to_string(),
+        obsidian_uri: obsidian_uri(std::path::Path::new("/vault/Meetings/standup.md")),
+    };
+    post_callback(&format!("http://{}/exported", addr), &payload).await?;
+
+    let received = rx.recv().await.expect("callback body");
+    assert_eq!(received.meeting_id, "standup-2025-10-28");
+    assert_eq!(received.note_path, "/vault/Meetings/standup.md");
+
+    // Error statuses are reported
+    assert!(post_callback(&format!("http://{}/missing", addr), &payload)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_refresh_exported_note_only_rewrites_existing_notes() -> Result<()> {
+    let vault = TempDir::new()?;
+    let config = ObsidianConfig {
+        vault_path: vault.path().display().to_string(),
+        ..Default::default()
+    };
+    let mut record = record(Vec::new());
+
+    // Never exported: left alone
+    assert!(refresh_exported_note(&config, &NoteFormat::default(), &record)?.is_none());
+    assert!(!vault.path().join("Meetings").exists());
+
+    let path = write_meeting_note(&config, &NoteFormat::default(), &record)?;
+    record.edit_segment(0, "Good morning everybody", None)?;
+    let refreshed = refresh_exported_note(&config, &NoteFormat::default(), &record)?;
+
+    assert_eq!(refreshed, Some(path.clone()));
+    assert!(std::fs::read_to_string(&path)?.contains("Good morning everybody"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_endpoint_restores_an_uploaded_bundle() -> Result<()> {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use loqa_meetings::{create_router, AppState, Config, FilesystemStorage, Storage};
+    use std::sync::Arc;
+    use tower::Service;
+
+    let dir = TempDir::new()?;
+    let mut config = Config::default();
+    config.audio.recordings_path = dir.path().join("recordings").display().to_string();
+    config.obsidian.vault_path = dir.path().join("vault").display().to_string();
+    let storage: Arc<dyn Storage> = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let mut router = create_router(AppState::with_config(config, Arc::clone(&storage)));
+
+    let mut bundle = Vec::new();
+    write_bundle(
+        &record(Vec::new()),
+        "# Note",
+        Utc::now(),
+        &mut bundle,
+        |_| {},
+    )?;
+
+    for (contents, status) in [
+        (bundle, StatusCode::OK),
+        (b"not a zip".to_vec(), StatusCode::BAD_REQUEST),
+    ] {
+        let mut body = b"--boundary\r\nContent-Disposition: form-data; name=\"bundle\"; filename=\"standup.zip\"\r\n\r\n".to_vec();
+        body.extend_from_slice(&contents);
+        body.extend_from_slice(b"\r\n--boundary--\r\n");
+        let request = Request::post("/meetings/import?overwrite=true")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(Body::from(body))
+            .unwrap();
+
+        std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx)).await?;
+        let response = router.call(request).await?;
+        assert_eq!(response.status(), status);
+    }
+
+    let restored = storage.get_meeting("standup-2025-10-28").await?.unwrap();
+    assert_eq!(restored.transcript.len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_overwriting_import_keeps_the_original_audio() -> Result<()> {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use loqa_meetings::{create_router, AppState, Config, FilesystemStorage, Storage};
+    use std::sync::Arc;
+    use tower::Service;
+
+    let dir = TempDir::new()?;
+    let recordings = dir.path().join("recordings");
+    let mut config = Config::default();
+    config.audio.recordings_path = recordings.display().to_string();
+    config.obsidian.vault_path = dir.path().join("vault").display().to_string();
+    let storage: Arc<dyn Storage> = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let mut router = create_router(AppState::with_config(config, Arc::clone(&storage)));
+
+    // The stored meeting: its chunk, and a clip no bundle carries
+    let meeting_dir = recordings.join("standup-2025-10-28");
+    std::fs::create_dir_all(&meeting_dir)?;
+    let wav = |path: &PathBuf, value: i16| write_clip(path, &[value; 16000], 16000, 1);
+    let original = meeting_dir.join("chunk_000.wav");
+    wav(&original, 100)?;
+    std::fs::write(meeting_dir.join("clip.wav"), b"old clip")?;
+    storage
+        .save_meeting(&record(vec![chunk(0, &original, 0, 1000)]))
+        .await?;
+    let original_audio = std::fs::read(&original)?;
+
+    let source = TempDir::new()?;
+    let bundle_chunk = source.path().join("chunk_000.wav");
+    async fn import(router: &mut axum::Router, contents: &[u8]) -> Result<StatusCode> {
+        let mut body = b"--boundary\r\nContent-Disposition: form-data; name=\"bundle\"; filename=\"standup.zip\"\r\n\r\n".to_vec();
+        body.extend_from_slice(contents);
+        body.extend_from_slice(b"\r\n--boundary--\r\n");
+        let request = Request::post("/meetings/import?overwrite=true")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(Body::from(body))
+            .unwrap();
+        std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(router, cx)).await?;
+        Ok(router.call(request).await?.status())
+    }
+
+    // Audio that fails the probe leaves the meeting's directory untouched
+    std::fs::write(&bundle_chunk, b"not audio")?;
+    let mut bundle = Vec::new();
+    write_bundle(
+        &record(vec![chunk(0, &bundle_chunk, 0, 1000)]),
+        "# Note",
+        Utc::now(),
+        &mut bundle,
+        |_| {},
+    )?;
+    assert_eq!(import(&mut router, &bundle).await?, StatusCode::BAD_REQUEST);
+    assert_eq!(std::fs::read(&original)?, original_audio);
+    assert!(meeting_dir.join("clip.wav").exists());
+
+    // A good bundle replaces the directory's files outright
+    wav(&bundle_chunk, 200)?;
+    let mut bundle = Vec::new();
+    write_bundle(
+        &record(vec![chunk(0, &bundle_chunk, 0, 1000)]),
+        "# Note",
+        Utc::now(),
+        &mut bundle,
+        |_| {},
+    )?;
+    assert_eq!(import(&mut router, &bundle).await?, StatusCode::OK);
+    assert_eq!(std::fs::read(&original)?, std::fs::read(&bundle_chunk)?);
+    assert!(!meeting_dir.join("clip.wav").exists());
+    assert!(!meeting_dir.join(".loqa-lock").exists());
+
+    // No staging or set-aside directories are left behind
+    let leftovers: Vec<_> = std::fs::read_dir(&recordings)?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+        .collect();
+    assert!(leftovers.is_empty(), "{:?}", leftovers);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_refuses_ids_unusable_as_subject_tokens() -> Result<()> {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use loqa_meetings::{create_router, AppState, Config, FilesystemStorage, Storage};
+    use std::sync::Arc;
+    use tower::Service;
+
+    let dir = TempDir::new()?;
+    let mut config = Config::default();
+    config.audio.recordings_path = dir.path().join("recordings").display().to_string();
+    let storage: Arc<dyn Storage> = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let mut router = create_router(AppState::with_config(config, Arc::clone(&storage)));
+
+    let mut bundle = Vec::new();
+    let record = MeetingRecord {
+        meeting_id: "team.sync >".to_string(),
+        ..record(Vec::new())
+    };
+    write_bundle(&record, "# Note", Utc::now(), &mut bundle, |_| {})?;
+
+    let mut body = b"--boundary\r\nContent-Disposition: form-data; name=\"bundle\"; filename=\"sync.zip\"\r\n\r\n".to_vec();
+    body.extend_from_slice(&bundle);
+    body.extend_from_slice(b"\r\n--boundary--\r\n");
+    let request = Request::post("/meetings/import")
+        .header("content-type", "multipart/form-data; boundary=boundary")
+        .body(Body::from(body))
+        .unwrap();
+    std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx)).await?;
+    let response = router.call(request).await?;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(storage.list_meetings(10, 0).await?.is_empty());
+    Ok(())
+}
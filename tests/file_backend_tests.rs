@@ -0,0 +1,136 @@
+// Tests for replaying audio files through the file backend and sessions
+
+use anyhow::Result;
+use loqa_meetings::audio::{
+    write_clip, AudioBackend, AudioBackendConfig, AudioBackendFactory, AudioSource, FileBackend,
+    FilePacing, FileSourceConfig,
+};
+use loqa_meetings::session::WatchdogConfig;
+use loqa_meetings::{RecordingSession, SessionConfig};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn source(path: PathBuf, pacing: FilePacing) -> FileSourceConfig {
+    FileSourceConfig { path, pacing }
+}
+
+#[tokio::test]
+async fn test_fast_replay_sends_the_whole_file() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("stereo.wav");
+    // 250ms of 48kHz stereo
+    let samples: Vec<i16> = (0..24000).map(|i| (i % 100) as i16).collect();
+    write_clip(&path, &samples, 48000, 2)?;
+
+    let mut backend = FileBackend::new(source(path, FilePacing::Fast), 100)?;
+    let mut frames = backend.start().await?;
+    assert!(backend.is_capturing());
+
+    let mut replayed = Vec::new();
+    let mut timestamps = Vec::new();
+    while replayed.len() < samples.len() {
+        let frame = tokio::time::timeout(Duration::from_secs(5), frames.recv())
+            .await?
+            .expect("capture stays open until stopped");
+        assert_eq!((frame.sample_rate, frame.channels), (48000, 2));
+        timestamps.push(frame.timestamp_ms);
+        replayed.extend(frame.samples);
+    }
+    assert_eq!(replayed, samples);
+    assert_eq!(timestamps, vec![0, 100, 200]);
+
+    // Nothing follows the end of the file until capture is stopped
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(backend.is_finished());
+    assert!(frames.try_recv().is_err());
+    backend.stop().await?;
+    assert!(!backend.is_capturing());
+    assert!(frames.recv().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_realtime_replay_takes_as_long_as_the_audio() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("mono.wav");
+    write_clip(&path, &[500; 4800], 16000, 1)?;
+
+    let mut backend = AudioBackendFactory::create(
+        AudioSource::File(source(path, FilePacing::Realtime)),
+        AudioBackendConfig::default(),
+    )?;
+    let started = Instant::now();
+    let mut frames = backend.start().await?;
+    for _ in 0..3 {
+        frames.recv().await.unwrap();
+    }
+    assert!(started.elapsed() >= Duration::from_millis(280));
+    backend.stop().await?;
+
+    assert!(AudioBackendFactory::create(
+        AudioSource::File(source(dir.path().join("missing.wav"), FilePacing::Fast)),
+        AudioBackendConfig::default(),
+    )
+    .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sessions_record_a_replayed_file() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("meeting.wav");
+    // A second of 48kHz audio, converted to 16kHz mono by the session
+    write_clip(&path, &[1000; 48000], 48000, 1)?;
+
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "replayed".to_string(),
+        audio_dir: Some(dir.path().join("audio")),
+        transcription: false,
+        file_source: Some(source(path, FilePacing::Fast)),
+        ..Default::default()
+    })
+    .await?;
+    let mut frames = session.subscribe_frames();
+    session.start().await?;
+
+    // The mix holds back the rest of the audio until the session stops
+    let mut published = 0;
+    while published < 8000 {
+        let frame = tokio::time::timeout(Duration::from_secs(5), frames.recv()).await??;
+        assert_eq!((frame.sample_rate, frame.channels), (16000, 1));
+        published += frame.samples.len();
+    }
+
+    let stats = session.stop().await?;
+    assert_eq!(stats.chunks_count, 1);
+    assert!(stats.audio_bytes >= 32000, "{}", stats.audio_bytes);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_a_replayed_file_ending_is_no_stall() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("meeting.wav");
+    write_clip(&path, &[1000; 1600], 16000, 1)?;
+
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "replayed".to_string(),
+        audio_dir: Some(dir.path().join("audio")),
+        transcription: false,
+        file_source: Some(source(path, FilePacing::Fast)),
+        watchdog: WatchdogConfig {
+            stall_secs: 1,
+            restart: true,
+        },
+        ..Default::default()
+    })
+    .await?;
+    session.start().await?;
+
+    // Past the stall timeout, long after the file ended
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let stats = session.stop().await?;
+    assert!(stats.warnings.is_empty(), "{:?}", stats.warnings);
+    Ok(())
+}
@@ -0,0 +1,183 @@
+// Tests for the 32-bit float processing pipeline
+//
+// These tests verify that float frames convert losslessly from 16-bit PCM,
+// keep headroom and sub-LSB detail between stages, and only clip or round
+// when converted back at the output boundary.
+
+use anyhow::Result;
+use loqa_meetings::audio::float::{sample_to_f32, sample_to_i16};
+use loqa_meetings::audio::{
+    AudioFrame, AudioStreamSource, ChunkCodec, ChunkConfig, ChunkedRecorder, FloatFrame, Mixer,
+    MixerConfig, MixerInput, QualityPreset, Resampler, ResamplerQuality,
+};
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+fn float_frame(source: AudioStreamSource, samples: Vec<f32>, timestamp_ms: u64) -> FloatFrame {
+    FloatFrame {
+        samples,
+        sample_rate: 16000,
+        channels: 1,
+        timestamp_ms,
+        source,
+    }
+}
+
+#[test]
+fn test_pcm16_roundtrip_is_exact() {
+    for sample in [i16::MIN, -12345, -1, 0, 1, 12345, i16::MAX] {
+        assert_eq!(sample_to_i16(sample_to_f32(sample)), sample);
+    }
+
+    let frame = AudioFrame {
+        samples: vec![-32768, -1, 0, 1, 32767],
+        sample_rate: 48000,
+        channels: 1,
+        timestamp_ms: 40,
+        source: AudioStreamSource::System,
+    };
+    let back = FloatFrame::from(&frame).to_pcm16();
+    assert_eq!(back.samples, frame.samples);
+    assert_eq!(back.sample_rate, 48000);
+    assert_eq!(back.timestamp_ms, 40);
+}
+
+#[test]
+fn test_conversion_clips_only_at_the_boundary() {
+    let mut frame = float_frame(AudioStreamSource::System, vec![0.75, -0.75, 0.25], 0);
+
+    // Doubling then halving survives in float; the i16 path would have clipped
+    frame.apply_gain(2.0);
+    assert_eq!(frame.samples, vec![1.5, -1.5, 0.5]);
+    assert_eq!(frame.to_pcm16().samples, vec![i16::MAX, i16::MIN, 16384]);
+
+    frame.apply_gain(0.5);
+    assert_eq!(frame.to_pcm16().samples, vec![24576, -24576, 8192]);
+}
+
+#[test]
+fn test_quantize_matches_pcm16_precision() {
+    let mut frame = float_frame(AudioStreamSource::System, vec![0.1, -0.3333, 0.00001], 0);
+    let expected = frame.to_pcm16().samples;
+
+    frame.quantize();
+    for (sample, pcm) in frame.samples.iter().zip(&expected) {
+        assert_eq!(*sample, sample_to_f32(*pcm));
+    }
+}
+
+#[test]
+fn test_with_channels_remixes_frames() {
+    let mono = float_frame(AudioStreamSource::System, vec![0.25, -0.5], 0);
+    let stereo = mono.clone().with_channels(2);
+    assert_eq!(stereo.channels, 2);
+    assert_eq!(stereo.samples, vec![0.25, 0.25, -0.5, -0.5]);
+
+    // Back to mono averages the channels
+    let mut uneven = stereo.clone();
+    uneven.samples = vec![0.5, 0.0, -0.5, -0.25];
+    assert_eq!(uneven.with_channels(1).samples, vec![0.25, -0.375]);
+
+    // Extra channels are silent, surplus ones are dropped
+    let mut quad = stereo.clone().with_channels(4);
+    assert_eq!(&quad.samples[..4], &[0.25, 0.25, 0.0, 0.0]);
+    quad.samples[2] = 0.9;
+    assert_eq!(quad.with_channels(2).samples, vec![0.25, 0.25, -0.5, -0.5]);
+}
+
+#[test]
+fn test_stereo_downmix_sums_channels() {
+    let frame = FloatFrame {
+        channels: 2,
+        ..float_frame(AudioStreamSource::System, vec![0.25, 0.5, -0.75, -0.5], 0)
+    };
+    let mono = frame.to_mono();
+    assert_eq!(mono.channels, 1);
+    assert_eq!(mono.samples, vec![0.75, -1.25]);
+}
+
+#[test]
+fn test_float_resampling_matches_pcm16_resampling() {
+    let input: Vec<i16> = (0..960).map(|i| ((i * 37) % 2000 - 1000) as i16).collect();
+    let floats: Vec<f32> = input.iter().map(|&s| sample_to_f32(s)).collect();
+
+    for quality in [
+        ResamplerQuality::Linear,
+        ResamplerQuality::Cubic,
+        ResamplerQuality::Sinc(16),
+    ] {
+        let mut pcm = Resampler::with_quality(48000, 16000, 1, quality);
+        let mut float = Resampler::with_quality(48000, 16000, 1, quality);
+
+        let expected = pcm.process(&input);
+        let actual: Vec<i16> = float
+            .process_f32(&floats)
+            .into_iter()
+            .map(sample_to_i16)
+            .collect();
+        assert_eq!(actual, expected, "{}", quality);
+    }
+}
+
+#[test]
+fn test_float_mixing_keeps_headroom() {
+    let mut mixer = Mixer::new(MixerConfig::new(
+        16000,
+        1,
+        vec![
+            MixerInput {
+                source: AudioStreamSource::System,
+                gain: 1.0,
+            },
+            MixerInput {
+                source: AudioStreamSource::MicrophoneDevice(0),
+                gain: 1.0,
+            },
+        ],
+    ));
+
+    mixer.push_float(float_frame(AudioStreamSource::System, vec![0.75; 1600], 0));
+    mixer.push_float(float_frame(
+        AudioStreamSource::MicrophoneDevice(0),
+        vec![0.75; 1600],
+        0,
+    ));
+
+    let mixed = mixer.pop_ready_float();
+    assert_eq!(mixed.len(), 1);
+    assert!(mixed[0].samples.iter().all(|&s| s == 1.5));
+    assert!(mixed[0].to_pcm16().samples.iter().all(|&s| s == i16::MAX));
+}
+
+#[tokio::test]
+async fn test_float_chunks_keep_full_precision() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config = ChunkConfig {
+        codec: ChunkCodec::Float32,
+        ..ChunkConfig::new("float-test".to_string(), temp_dir.path().to_path_buf())
+    };
+    let mut recorder = ChunkedRecorder::new(config)?;
+
+    let (tx, rx) = mpsc::channel(10);
+    let handle = tokio::spawn(async move { recorder.record_float(rx).await });
+
+    // Finer than one 16-bit step
+    let samples = vec![0.123_456_7_f32, -0.000_01, 0.5];
+    tx.send(float_frame(AudioStreamSource::System, samples.clone(), 0))
+        .await?;
+    drop(tx);
+    let metadata = handle.await??;
+
+    let mut reader = hound::WavReader::open(&metadata[0].file_path)?;
+    let written: Vec<f32> = reader.samples::<f32>().collect::<Result<_, _>>()?;
+    assert_eq!(written, samples);
+
+    Ok(())
+}
+
+#[test]
+fn test_only_archival_uses_the_float_pipeline() {
+    assert!(QualityPreset::Archival.float_pipeline());
+    assert!(!QualityPreset::Balanced.float_pipeline());
+    assert!(!QualityPreset::LowLatency.float_pipeline());
+}
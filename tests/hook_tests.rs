@@ -0,0 +1,114 @@
+// Tests for the shell commands run as meetings start, stop, and are exported
+
+use anyhow::Result;
+use loqa_meetings::hooks::{run_hooks, HookContext, HookEvent};
+use loqa_meetings::{
+    Config, FilesystemStorage, HooksConfig, MeetingsService, RecordingSession, SessionConfig,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// Hook appending `line` (expanded by the shell) to `file`
+fn append(file: &Path, line: &str) -> String {
+    format!("echo \"{}\" >> '{}'", line, file.display())
+}
+
+async fn wait_for(file: &Path) -> String {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        if let Ok(contents) = std::fs::read_to_string(file) {
+            if contents.ends_with('\n') {
+                return contents;
+            }
+        }
+        assert!(
+            Instant::now() < deadline,
+            "{} never written",
+            file.display()
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_hooks_see_the_meeting_in_their_environment() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("out.txt");
+    let config = HooksConfig {
+        on_export: vec![append(
+            &out,
+            "$LOQA_HOOK $LOQA_MEETING_ID [$LOQA_MEETING_TITLE] $LOQA_NOTE_PATH",
+        )],
+        ..Default::default()
+    };
+    let context = HookContext {
+        title: Some("Product sync".to_string()),
+        note_path: Some(PathBuf::from("/vault/Meetings/sync.md")),
+        ..HookContext::new("2025-06-03-product-sync")
+    };
+
+    run_hooks(&config, HookEvent::Export, &context).await;
+    assert_eq!(
+        std::fs::read_to_string(&out).unwrap(),
+        "export 2025-06-03-product-sync [Product sync] /vault/Meetings/sync.md\n"
+    );
+
+    // Only the event's own hooks run
+    run_hooks(&config, HookEvent::Start, &context).await;
+    assert_eq!(std::fs::read_to_string(&out).unwrap().lines().count(), 1);
+}
+
+#[tokio::test]
+async fn test_failing_and_slow_hooks_dont_stop_the_rest() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("out.txt");
+    let config = HooksConfig {
+        on_start: vec![
+            "exit 3".to_string(),
+            "sleep 30".to_string(),
+            append(&out, "ran"),
+        ],
+        timeout_secs: 1,
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    run_hooks(&config, HookEvent::Start, &HookContext::new("standup")).await;
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "ran\n");
+}
+
+#[tokio::test]
+async fn test_stopping_a_meeting_runs_the_stop_hooks() -> Result<()> {
+    let dir = TempDir::new()?;
+    let out = dir.path().join("out.txt");
+    let mut config = Config::default();
+    config.audio.recordings_path = dir.path().join("recordings").display().to_string();
+    config.hooks.on_stop = vec![append(&out, "$LOQA_HOOK $LOQA_MEETING_ID $LOQA_AUDIO_DIR")];
+    let storage = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let service = MeetingsService::with_config(config, storage);
+
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "standup".to_string(),
+        transcription: false,
+        ..Default::default()
+    })
+    .await?;
+    service
+        .sessions
+        .write()
+        .await
+        .insert("standup".to_string(), Arc::new(session));
+    service.stop("standup").await?;
+
+    assert_eq!(
+        wait_for(&out).await,
+        format!(
+            "stop standup {}\n",
+            dir.path().join("recordings").join("standup").display()
+        )
+    );
+    Ok(())
+}
@@ -0,0 +1,71 @@
+// Tests for response compression on the HTTP router
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::response::Response;
+use loqa_meetings::storage::FilesystemStorage;
+use loqa_meetings::{create_router, AppState, Config};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::Service;
+
+async fn get_capabilities(config: Config, accept_encoding: &str) -> Response {
+    let dir = TempDir::new().unwrap();
+    let storage = Arc::new(FilesystemStorage::new(dir.path().to_path_buf()));
+    let mut router = create_router(AppState::with_config(config, storage));
+
+    let request = Request::get("/capabilities")
+        .header(header::ACCEPT_ENCODING, accept_encoding)
+        .body(Body::empty())
+        .unwrap();
+    std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx))
+        .await
+        .unwrap();
+    router.call(request).await.unwrap()
+}
+
+fn small_threshold() -> Config {
+    let mut config = Config::default();
+    config.service.http.compression.min_size_bytes = 1;
+    config
+}
+
+fn content_encoding(response: &Response) -> Option<&str> {
+    response
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .map(|value| value.to_str().unwrap())
+}
+
+#[tokio::test]
+async fn test_responses_are_compressed_when_accepted() {
+    let response = get_capabilities(small_threshold(), "gzip").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(content_encoding(&response), Some("gzip"));
+
+    let response = get_capabilities(small_threshold(), "deflate").await;
+    assert_eq!(content_encoding(&response), Some("deflate"));
+
+    let response = get_capabilities(small_threshold(), "identity").await;
+    assert_eq!(content_encoding(&response), None);
+}
+
+#[tokio::test]
+async fn test_compression_can_be_disabled() {
+    let mut config = small_threshold();
+    config.service.http.compression.enabled = false;
+    let response = get_capabilities(config, "gzip").await;
+    assert_eq!(content_encoding(&response), None);
+
+    let mut config = small_threshold();
+    config.service.http.compression.gzip = false;
+    let response = get_capabilities(config, "gzip, deflate").await;
+    assert_eq!(content_encoding(&response), Some("deflate"));
+}
+
+#[tokio::test]
+async fn test_small_responses_are_sent_as_is() {
+    // Capabilities are well under the default 1 KiB threshold
+    let response = get_capabilities(Config::default(), "gzip").await;
+    assert_eq!(content_encoding(&response), None);
+}
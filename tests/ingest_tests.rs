@@ -0,0 +1,86 @@
+// Tests for ingesting recordings dropped into the inbox directory
+
+use loqa_meetings::http::{is_ingestable, title_from_filename};
+use loqa_meetings::storage::FilesystemStorage;
+use loqa_meetings::{AppState, Config, IngestWorker};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+#[test]
+fn test_meeting_title_from_filename() {
+    assert_eq!(
+        title_from_filename(Path::new("/inbox/weekly_sync.m4a")).as_deref(),
+        Some("weekly sync")
+    );
+    assert_eq!(
+        title_from_filename(Path::new("Design review 2025-06-03.wav")).as_deref(),
+        Some("Design review 2025-06-03")
+    );
+    assert_eq!(title_from_filename(Path::new("_.mp3")), None);
+}
+
+#[test]
+fn test_only_visible_audio_and_video_files_are_ingested() {
+    for name in ["call.wav", "call.M4A", "screen.mov", "memo.opus"] {
+        assert!(is_ingestable(Path::new(name)), "{}", name);
+    }
+    for name in ["notes.txt", "call.wav.part", ".call.wav", "call"] {
+        assert!(!is_ingestable(Path::new(name)), "{}", name);
+    }
+}
+
+#[test]
+fn test_ingest_config() {
+    assert!(Config::default().ingest.resolved_inbox().is_none());
+
+    let config = Config::load("config/loqa-meetings").unwrap();
+    let inbox = config
+        .ingest
+        .resolved_inbox()
+        .expect("example enables ingest");
+    assert!(inbox.ends_with("Meetings/Inbox"));
+    assert!(!inbox.starts_with("~"));
+    assert_eq!(config.ingest.poll_interval_secs, 5);
+}
+
+#[tokio::test]
+async fn test_growing_files_wait_and_failed_recordings_are_set_aside() {
+    let dir = TempDir::new().unwrap();
+    let inbox = dir.path().join("inbox");
+    let recordings = dir.path().join("recordings");
+    let mut config = Config::default();
+    config.audio.recordings_path = recordings.display().to_string();
+    let storage = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let mut worker = IngestWorker::new(AppState::with_config(config, storage), inbox.clone());
+
+    // Not decodable, so ingest fails before reaching STT
+    fs::create_dir_all(&inbox).unwrap();
+    fs::write(inbox.join("standup.wav"), b"not audio").unwrap();
+    fs::write(inbox.join("readme.txt"), b"ignored").unwrap();
+    assert!(worker.scan().await.unwrap().is_empty());
+    assert!(
+        inbox.join("standup.wav").exists(),
+        "Seen once, still waiting"
+    );
+
+    // Still growing
+    fs::write(inbox.join("standup.wav"), b"not audio yet").unwrap();
+    assert!(worker.scan().await.unwrap().is_empty());
+    assert!(inbox.join("standup.wav").exists());
+
+    assert!(worker.scan().await.unwrap().is_empty());
+    assert!(!inbox.join("standup.wav").exists());
+    assert_eq!(
+        fs::read(inbox.join("failed").join("standup.wav")).unwrap(),
+        b"not audio yet"
+    );
+    assert!(inbox.join("readme.txt").exists());
+
+    // Nothing is left of the meeting it would have been
+    let leftovers = fs::read_dir(&recordings)
+        .map(|entries| entries.count())
+        .unwrap_or(0);
+    assert_eq!(leftovers, 0);
+}
@@ -0,0 +1,133 @@
+// Tests for chunk checksums and verifying stored audio against them
+
+mod common;
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use loqa_meetings::audio::file_sha256;
+use loqa_meetings::storage::{ChunkIntegrity, IntegrityReport};
+use loqa_meetings::{
+    AudioFrame, AudioStreamSource, ChunkConfig, ChunkMetadata, ChunkedRecorder, MeetingRecord,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn chunk(index: usize, file_path: PathBuf, sha256: Option<String>) -> ChunkMetadata {
+    let start_ms = index as u64 * 1000;
+    ChunkMetadata {
+        sha256,
+        ..common::chunk(index, &file_path, start_ms, start_ms + 1000)
+    }
+}
+
+fn record(chunks: Vec<ChunkMetadata>) -> MeetingRecord {
+    common::meeting(
+        "synced",
+        Utc::now(),
+        Duration::seconds(4),
+        Vec::new(),
+        chunks,
+    )
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<Option<String>> {
+    fs::write(path, contents)?;
+    Ok(Some(file_sha256(path)?))
+}
+
+#[test]
+fn test_file_sha256_is_hex_digest() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("empty.wav");
+    fs::write(&path, b"")?;
+    assert_eq!(
+        file_sha256(&path)?,
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_finished_chunks_carry_their_checksum() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut recorder = ChunkedRecorder::new(ChunkConfig::new(
+        "synced".to_string(),
+        dir.path().to_path_buf(),
+    ))?;
+    recorder.write(
+        &AudioFrame {
+            samples: vec![1000i16; 1600],
+            sample_rate: 16000,
+            channels: 1,
+            timestamp_ms: 0,
+            source: AudioStreamSource::System,
+        }
+        .into(),
+    )?;
+
+    let chunk = recorder.finish()?.expect("a chunk was open");
+    assert_eq!(chunk.sha256, Some(file_sha256(&chunk.file_path)?));
+
+    // Stored with the meeting
+    let json = serde_json::to_value(&chunk)?;
+    assert_eq!(json["sha256"], chunk.sha256.clone().unwrap());
+    Ok(())
+}
+
+#[test]
+fn test_report_flags_corrupted_missing_and_unverified_chunks() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = |name: &str| dir.path().join(name);
+
+    let intact = write(&path("chunk-0.wav"), b"RIFF intact")?;
+    let corrupted = write(&path("chunk-1.wav"), b"RIFF original")?;
+    fs::write(path("chunk-1.wav"), b"RIFF truncat")?;
+    let missing = write(&path("chunk-2.wav"), b"RIFF gone")?;
+    fs::remove_file(path("chunk-2.wav"))?;
+    fs::write(path("chunk-3.wav"), b"RIFF legacy")?;
+
+    let report = IntegrityReport::check(&record(vec![
+        chunk(0, path("chunk-0.wav"), intact.clone()),
+        chunk(1, path("chunk-1.wav"), corrupted.clone()),
+        chunk(2, path("chunk-2.wav"), missing),
+        chunk(3, path("chunk-3.wav"), None),
+    ]));
+
+    let statuses: Vec<ChunkIntegrity> = report.chunks.iter().map(|c| c.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ChunkIntegrity::Ok,
+            ChunkIntegrity::Corrupted,
+            ChunkIntegrity::Missing,
+            ChunkIntegrity::Unverified,
+        ]
+    );
+    assert!(!report.intact);
+    assert_eq!(
+        (report.corrupted, report.missing, report.unverified),
+        (1, 1, 1)
+    );
+    assert_eq!(report.chunks[0].actual_sha256, intact);
+    assert_ne!(report.chunks[1].actual_sha256, corrupted);
+    assert_eq!(report.chunks[2].actual_sha256, None);
+
+    let json = serde_json::to_value(&report)?;
+    assert_eq!(json["chunks"][1]["status"], "corrupted");
+    Ok(())
+}
+
+#[test]
+fn test_report_is_intact_without_corruption() -> Result<()> {
+    let dir = TempDir::new()?;
+    let sha256 = write(&dir.path().join("chunk-0.wav"), b"RIFF")?;
+
+    let report = IntegrityReport::check(&record(vec![
+        chunk(0, dir.path().join("chunk-0.wav"), sha256),
+        chunk(1, dir.path().join("chunk-0.wav"), None),
+    ]));
+    assert!(report.intact, "Unverified chunks don't fail the check");
+    assert!(IntegrityReport::check(&record(Vec::new())).intact);
+    Ok(())
+}
@@ -0,0 +1,148 @@
+// Tests for per-segment transcript languages
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use loqa_meetings::session::{
+    detect_language, normalize_language, segment_language, TranscriptLog, TranscriptQuery,
+};
+use loqa_meetings::{render_note, MeetingRecord, NoteFormat, TranscriptSegment};
+
+fn segment(text: &str, at: DateTime<Utc>, language: Option<&str>) -> TranscriptSegment {
+    TranscriptSegment {
+        language: language.map(str::to_string),
+        ..common::segment(text, at)
+    }
+}
+
+fn record(transcript: Vec<TranscriptSegment>) -> MeetingRecord {
+    let started_at = transcript[0].timestamp;
+    common::meeting(
+        "sync-2025-11-04",
+        started_at,
+        Duration::minutes(5),
+        transcript,
+        Vec::new(),
+    )
+}
+
+fn bilingual(started_at: DateTime<Utc>) -> Vec<TranscriptSegment> {
+    vec![
+        segment("Hello everyone", started_at, Some("en")),
+        segment(
+            "Hallo zusammen",
+            started_at + Duration::seconds(5),
+            Some("de"),
+        ),
+        segment(
+            "Let's begin",
+            started_at + Duration::seconds(10),
+            Some("en"),
+        ),
+        segment("Mm-hmm", started_at + Duration::seconds(15), None),
+    ]
+}
+
+#[test]
+fn test_normalize_language() {
+    assert_eq!(normalize_language("en-US").as_deref(), Some("en"));
+    assert_eq!(normalize_language("pt_BR").as_deref(), Some("pt"));
+    assert_eq!(normalize_language(" English ").as_deref(), Some("en"));
+    assert_eq!(normalize_language("german").as_deref(), Some("de"));
+    assert_eq!(normalize_language("FR").as_deref(), Some("fr"));
+    assert_eq!(normalize_language("  "), None);
+}
+
+#[test]
+fn test_detect_language_from_stopwords() {
+    assert_eq!(
+        detect_language("We are not sure that this is the plan"),
+        Some("en")
+    );
+    assert_eq!(
+        detect_language("Wir haben das noch nicht besprochen"),
+        Some("de")
+    );
+    assert_eq!(
+        detect_language("El equipo está muy contento con los resultados"),
+        Some("es")
+    );
+
+    assert_eq!(detect_language("Sounds good"), None, "Too short to guess");
+    assert_eq!(
+        detect_language("Kubernetes deployment pipeline rollback"),
+        None,
+        "No stopwords"
+    );
+}
+
+#[test]
+fn test_reported_language_wins_over_detection() {
+    let text = "We are not sure that this is the plan";
+    assert_eq!(segment_language(Some("en-GB"), text).as_deref(), Some("en"));
+    assert_eq!(segment_language(Some("nl"), text).as_deref(), Some("nl"));
+    assert_eq!(segment_language(None, text).as_deref(), Some("en"));
+    assert_eq!(segment_language(Some(""), "Okay"), None);
+}
+
+#[test]
+fn test_transcript_page_filters_by_language() {
+    let started_at = Utc::now();
+    let log = TranscriptLog::from_segments(bilingual(started_at));
+
+    let query = TranscriptQuery {
+        language: Some("EN-us".to_string()),
+        ..Default::default()
+    };
+    assert!(query.is_paged());
+    let page = log.page(started_at, &query, 100);
+    let texts: Vec<&str> = page.segments.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(texts, vec!["Hello everyone", "Let's begin"]);
+    assert_eq!(page.total, 2);
+}
+
+#[test]
+fn test_note_lists_languages_and_groups_by_language() {
+    let record = record(bilingual(Utc::now()));
+
+    let note = render_note(&record, &NoteFormat::default());
+    assert!(note.contains("languages: [en, de]\n"));
+    assert!(!note.contains("### en"));
+
+    let grouped = NoteFormat {
+        group_by_language: true,
+        ..Default::default()
+    };
+    let note = render_note(&record, &grouped);
+    let en = note.find("### en\n").expect("English section");
+    let de = note.find("### de\n").expect("German section");
+    let unknown = note
+        .find("### Unknown language\n")
+        .expect("Untagged section");
+    assert!(en < de && de < unknown, "Sections in first-spoken order");
+    assert!(note[en..de].contains("Let's begin"));
+    assert!(note[de..unknown].contains("Hallo zusammen"));
+}
+
+#[test]
+fn test_note_filters_languages_but_keeps_untagged_segments() {
+    let record = record(bilingual(Utc::now()));
+
+    let german = NoteFormat {
+        languages: vec!["de".to_string()],
+        ..Default::default()
+    };
+    let note = render_note(&record, &german);
+    assert!(note.contains("Hallo zusammen"));
+    assert!(note.contains("Mm-hmm"));
+    assert!(!note.contains("Hello everyone"));
+    assert!(note.contains("languages: [de]\n"));
+
+    let french = NoteFormat {
+        languages: vec!["fr".to_string()],
+        ..Default::default()
+    };
+    let mut tagged = record.clone();
+    tagged.transcript.truncate(3);
+    assert!(render_note(&tagged, &french).contains("_Nothing was said in the selected languages._"));
+}
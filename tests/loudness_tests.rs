@@ -0,0 +1,211 @@
+// Tests for loudness measurement and normalized full-meeting audio exports
+
+mod common;
+
+use anyhow::Result;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{Duration, Utc};
+use loqa_meetings::audio::{
+    integrated_loudness, normalize_loudness, sample_peak_dbfs, write_clip, LoudnessConfig,
+};
+use loqa_meetings::export::MeetingAudio;
+use loqa_meetings::{
+    create_router, AppState, ChunkMetadata, Config, FilesystemStorage, MeetingRecord, Storage,
+};
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::Service;
+
+/// `secs` of a 1kHz sine at `amplitude` (fraction of full scale), mono
+fn sine(sample_rate: u32, secs: f64, amplitude: f64) -> Vec<i16> {
+    (0..(sample_rate as f64 * secs) as usize)
+        .map(|i| {
+            let t = i as f64 / sample_rate as f64;
+            (amplitude * 32767.0 * (2.0 * std::f64::consts::PI * 1000.0 * t).sin()) as i16
+        })
+        .collect()
+}
+
+fn record(meeting_id: &str, chunks: Vec<ChunkMetadata>) -> MeetingRecord {
+    let started_at = Utc::now() - Duration::hours(1);
+    common::meeting(
+        meeting_id,
+        started_at,
+        Duration::seconds(4),
+        Vec::new(),
+        chunks,
+    )
+}
+
+fn chunk(index: usize, path: &Path, samples: &[i16], sample_rate: u32) -> Result<ChunkMetadata> {
+    write_clip(path, samples, sample_rate, 1)?;
+    let start_ms = index as u64 * 2000;
+    let end_ms = start_ms + samples.len() as u64 * 1000 / sample_rate as u64;
+    Ok(ChunkMetadata {
+        sample_rate,
+        sample_count: samples.len(),
+        ..common::chunk(index, path, start_ms, end_ms)
+    })
+}
+
+#[test]
+fn test_sine_loudness_matches_bs1770() {
+    // A 1kHz sine reads about 3 dB below its peak level
+    for sample_rate in [48000, 16000] {
+        let loudness = integrated_loudness(&sine(sample_rate, 5.0, 0.1), sample_rate, 1).unwrap();
+        assert!(
+            (loudness + 23.0).abs() < 0.3,
+            "{} Hz: {} LUFS",
+            sample_rate,
+            loudness
+        );
+    }
+
+    // The same sine in both channels is counted once per channel
+    let stereo: Vec<i16> = sine(48000, 5.0, 0.1)
+        .into_iter()
+        .flat_map(|sample| [sample, sample])
+        .collect();
+    let loudness = integrated_loudness(&stereo, 48000, 2).unwrap();
+    assert!((loudness + 20.0).abs() < 0.3, "{} LUFS", loudness);
+}
+
+#[test]
+fn test_silence_and_short_audio_are_not_measured() {
+    assert_eq!(integrated_loudness(&vec![0; 48000 * 2], 48000, 1), None);
+    assert_eq!(integrated_loudness(&sine(48000, 0.3, 0.5), 48000, 1), None);
+    assert_eq!(sample_peak_dbfs(&[0, 0]), f64::NEG_INFINITY);
+}
+
+#[test]
+fn test_quiet_passages_are_gated_out() {
+    let mut samples = sine(16000, 5.0, 0.1);
+    samples.extend(sine(16000, 5.0, 0.001));
+
+    let loudness = integrated_loudness(&samples, 16000, 1).unwrap();
+    assert!((loudness + 23.0).abs() < 0.3, "{} LUFS", loudness);
+}
+
+#[test]
+fn test_normalize_reaches_the_target() {
+    let mut samples = sine(16000, 5.0, 0.02);
+    let report = normalize_loudness(&mut samples, 16000, 1, &LoudnessConfig::default());
+
+    let measured = report.measured_lufs.unwrap();
+    assert!((measured + 37.0).abs() < 0.3, "{} LUFS", measured);
+    assert!((report.gain_db - (-16.0 - measured)).abs() < 1e-9);
+
+    let after = integrated_loudness(&samples, 16000, 1).unwrap();
+    assert!((after + 16.0).abs() < 0.1, "{} LUFS", after);
+}
+
+#[test]
+fn test_normalize_gain_is_capped_by_the_peak_limit() {
+    let mut samples = sine(16000, 5.0, 0.02);
+    let config = LoudnessConfig {
+        max_peak_dbfs: -20.0,
+        ..Default::default()
+    };
+    let report = normalize_loudness(&mut samples, 16000, 1, &config);
+
+    assert!(report.gain_db < -16.0 - report.measured_lufs.unwrap());
+    assert!(sample_peak_dbfs(&samples) <= -20.0 + 0.01);
+}
+
+#[test]
+fn test_normalize_leaves_silence_alone() {
+    let mut samples = vec![0i16; 16000];
+    let report = normalize_loudness(&mut samples, 16000, 1, &LoudnessConfig::default());
+    assert_eq!(report.measured_lufs, None);
+    assert_eq!(report.gain_db, 0.0);
+}
+
+#[test]
+fn test_meeting_audio_joins_chunks_in_order() -> Result<()> {
+    let dir = TempDir::new()?;
+    let first = chunk(0, &dir.path().join("a.wav"), &vec![100; 32000], 16000)?;
+    // A later chunk in another rate is resampled to the first's
+    let second = chunk(1, &dir.path().join("b.wav"), &vec![200; 16000], 8000)?;
+
+    let audio = MeetingAudio::load(&record("joined", vec![second, first]))?;
+    assert_eq!(audio.sample_rate, 16000);
+    assert_eq!(audio.channels, 1);
+    assert_eq!(audio.samples[0], 100, "First chunk first");
+    assert!((audio.duration().as_secs_f64() - 4.0).abs() < 0.01);
+
+    let wav = audio.to_wav()?;
+    let reader = hound::WavReader::new(std::io::Cursor::new(wav))?;
+    assert_eq!(reader.spec().sample_rate, 16000);
+    assert_eq!(reader.len() as usize, audio.samples.len());
+    Ok(())
+}
+
+#[test]
+fn test_meeting_audio_skips_chunk_overlap() -> Result<()> {
+    let dir = TempDir::new()?;
+    let first = chunk(0, &dir.path().join("a.wav"), &vec![100; 32000], 16000)?;
+    // The second chunk repeats the last half second of the first
+    let mut samples = vec![100; 8000];
+    samples.extend(vec![200; 32000]);
+    let second = ChunkMetadata {
+        overlap_ms: 500,
+        ..chunk(1, &dir.path().join("b.wav"), &samples, 16000)?
+    };
+
+    let audio = MeetingAudio::load(&record("overlapping", vec![first, second]))?;
+    assert_eq!(audio.samples.len(), 64000);
+    assert_eq!(audio.samples[31999], 100);
+    assert_eq!(audio.samples[32000], 200);
+    Ok(())
+}
+
+async fn get(router: &mut axum::Router, uri: &str) -> axum::response::Response {
+    std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(router, cx))
+        .await
+        .unwrap();
+    router
+        .call(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_audio_endpoint_exports_normalized_wav() -> Result<()> {
+    let dir = TempDir::new()?;
+    let storage = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let samples = sine(16000, 4.0, 0.02);
+    let stored = record(
+        "quiet-call",
+        vec![chunk(0, &dir.path().join("quiet.wav"), &samples, 16000)?],
+    );
+    storage.save_meeting(&stored).await?;
+    storage
+        .save_meeting(&record("no-audio", Vec::new()))
+        .await?;
+    let mut router = create_router(AppState::with_config(Config::default(), storage));
+
+    let response = get(&mut router, "/meetings/quiet-call/audio").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "audio/wav");
+    assert!(response.headers().contains_key("x-loudness-measured-lufs"));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let exported: Vec<i16> = hound::WavReader::new(std::io::Cursor::new(body))?
+        .samples::<i16>()
+        .collect::<Result<_, _>>()?;
+    let loudness = integrated_loudness(&exported, 16000, 1).unwrap();
+    assert!((loudness + 16.0).abs() < 0.2, "{} LUFS", loudness);
+
+    let response = get(&mut router, "/meetings/quiet-call/audio?normalize=false").await;
+    assert!(!response.headers().contains_key("x-loudness-gain-db"));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let exported: Vec<i16> = hound::WavReader::new(std::io::Cursor::new(body))?
+        .samples::<i16>()
+        .collect::<Result<_, _>>()?;
+    assert_eq!(exported, samples);
+
+    let response = get(&mut router, "/meetings/no-audio/audio").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
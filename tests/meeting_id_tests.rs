@@ -0,0 +1,79 @@
+// Tests for generated meeting IDs and validation of new ones
+
+use chrono::NaiveDate;
+use loqa_meetings::storage::{
+    candidate_ids, meeting_slug, slugify, validate_new_meeting_id, MAX_MEETING_ID_LEN,
+};
+
+fn june_3() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 3).unwrap()
+}
+
+#[test]
+fn test_meeting_slug_from_title() {
+    assert_eq!(
+        meeting_slug(june_3(), "Product Sync"),
+        "2025-06-03-product-sync"
+    );
+    assert_eq!(
+        meeting_slug(june_3(), "  Q3 planning: budget / hiring!! "),
+        "2025-06-03-q3-planning-budget-hiring"
+    );
+    // Nothing usable in the title
+    assert_eq!(meeting_slug(june_3(), "会议"), "2025-06-03-meeting");
+    assert_eq!(meeting_slug(june_3(), ""), "2025-06-03-meeting");
+}
+
+#[test]
+fn test_slug_is_capped_at_a_word_boundary() {
+    let slug = slugify(&"retro ".repeat(20));
+    assert!(slug.len() <= 48, "Got {}", slug.len());
+    assert!(slug.starts_with("retro-retro") && slug.ends_with("retro"));
+
+    // A single huge word is cut instead of lost
+    assert_eq!(slugify(&"a".repeat(100)).len(), 48);
+}
+
+#[test]
+fn test_candidate_ids_add_collision_suffixes() {
+    let candidates: Vec<String> = candidate_ids("2025-06-03-standup").take(3).collect();
+    assert_eq!(
+        candidates,
+        vec![
+            "2025-06-03-standup",
+            "2025-06-03-standup-2",
+            "2025-06-03-standup-3"
+        ]
+    );
+    assert_eq!(candidate_ids("x").count(), 99);
+}
+
+#[test]
+fn test_generated_ids_are_valid() {
+    for title in ["Product Sync", "1:1 w/ Sam", "../../etc/passwd", "a.b*c>d"] {
+        let id = meeting_slug(june_3(), title);
+        assert!(validate_new_meeting_id(&id).is_ok(), "{} -> {}", title, id);
+    }
+}
+
+#[test]
+fn test_new_meeting_ids_must_suit_paths_and_subjects() {
+    assert!(validate_new_meeting_id("standup").is_ok());
+    assert!(validate_new_meeting_id("meeting-4f1c2b1e-0b7d-4c1e-9d1a-2f3e4d5c6b7a").is_ok());
+
+    for id in [
+        "",
+        "../x",
+        ".hidden",
+        "a/b",
+        "team.sync",
+        "a*",
+        "a>",
+        "a b",
+        "c:d",
+        "x\n",
+    ] {
+        assert!(validate_new_meeting_id(id).is_err(), "{:?} accepted", id);
+    }
+    assert!(validate_new_meeting_id(&"x".repeat(MAX_MEETING_ID_LEN + 1)).is_err());
+}
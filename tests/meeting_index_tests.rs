@@ -0,0 +1,106 @@
+// Tests for the human-readable index.md kept in each meeting directory
+
+mod common;
+
+use anyhow::Result;
+use chrono::Utc;
+use loqa_meetings::audio::workdir::is_workdir_file;
+use loqa_meetings::audio::{ChunkMetadata, MeetingDirLock, MeetingIndex, INDEX_FILE};
+use loqa_meetings::{Config, FilesystemStorage, MeetingsService, RecordingSession, SessionConfig};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn chunk(index: usize, file_path: PathBuf) -> ChunkMetadata {
+    let start_ms = index as u64 * 300_000;
+    common::chunk(index, &file_path, start_ms, start_ms + 300_000)
+}
+
+#[test]
+fn test_index_follows_the_recording() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("standup");
+
+    let lock =
+        MeetingDirLock::acquire(&dir, "standup")?.with_title(Some("Daily standup".to_string()));
+    let index = fs::read_to_string(dir.join(INDEX_FILE))?;
+    assert!(index.starts_with("# Daily standup\n"));
+    assert!(index.contains("| Status | In progress |"));
+    assert!(index.contains("No audio chunks yet."));
+
+    lock.update_manifest(&[
+        chunk(0, dir.join("chunk_000.wav")),
+        chunk(1, dir.join("chunk_001.wav")),
+    ])?;
+    let index = fs::read_to_string(dir.join(INDEX_FILE))?;
+    assert!(index.contains("| 1 | chunk_001.wav | 05:00 | 10:00 | 05:00 |"));
+
+    // The index isn't meeting audio, and goes with an abandoned directory
+    assert!(is_workdir_file(OsStr::new(INDEX_FILE)));
+    lock.abandon();
+    assert!(!dir.join(INDEX_FILE).exists());
+    Ok(())
+}
+
+#[test]
+fn test_saved_meeting_index() {
+    let index = MeetingIndex {
+        duration: Some(Duration::from_secs(3725)),
+        chunks: vec![chunk(0, PathBuf::from("/recordings/retro/chunk_000.wav"))],
+        transcript: Some("`/meetings/retro/meeting.json`".to_string()),
+        encrypted: true,
+        note_path: Some(PathBuf::from("/vault/Meetings/Retro.md")),
+        ..MeetingIndex::new("retro", Utc::now())
+    }
+    .render();
+
+    assert!(index.starts_with("# retro\n"));
+    assert!(index.contains("| Status | Complete |"));
+    assert!(index.contains("| Duration | 1:02:05 |"));
+    assert!(index.contains("| Transcript | `/meetings/retro/meeting.json` |"));
+    assert!(index.contains("| Note | Exported to `/vault/Meetings/Retro.md` |"));
+    assert!(index.contains("| Encrypted |"));
+    assert!(index.contains("| 0 | chunk_000.wav | 00:00 | 05:00 | 05:00 |"));
+}
+
+#[tokio::test]
+async fn test_stopping_a_meeting_completes_its_index() -> Result<()> {
+    let dir = TempDir::new()?;
+    let recordings = dir.path().join("recordings");
+    fs::create_dir_all(recordings.join("standup"))?;
+    let mut config = Config::default();
+    config.audio.recordings_path = recordings.display().to_string();
+    let storage = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let service = MeetingsService::with_config(config, storage);
+
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "standup".to_string(),
+        title: Some("Daily standup".to_string()),
+        transcription: false,
+        ..Default::default()
+    })
+    .await?;
+    service
+        .sessions
+        .write()
+        .await
+        .insert("standup".to_string(), Arc::new(session));
+    service.stop("standup").await?;
+
+    let index = fs::read_to_string(recordings.join("standup").join(INDEX_FILE))?;
+    assert!(index.starts_with("# Daily standup\n"));
+    assert!(index.contains("| Status | Complete |"));
+    assert!(index.contains(&format!(
+        "| Transcript | `{}` |",
+        dir.path()
+            .join("meetings")
+            .join("standup")
+            .join("meeting.json")
+            .display()
+    )));
+    assert!(index.contains("| Note | Not exported |"));
+    Ok(())
+}
@@ -0,0 +1,398 @@
+// Tests for the meetings service embedded without the HTTP server
+
+mod common;
+
+use anyhow::Result;
+use chrono::{Duration, TimeZone, Utc};
+use loqa_meetings::clock::{Clock, ManualClock};
+use loqa_meetings::session::TranscriptQuery;
+use loqa_meetings::{
+    Config, FilesystemStorage, MeetingRecord, MeetingStatus, MeetingsError, MeetingsService,
+    RecordingSession, SessionConfig, StartOptions, StartOutcome, StopOutcome, Transcript,
+};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn service(dir: &TempDir, max_concurrent: usize) -> MeetingsService {
+    let mut config = Config::default();
+    config.sessions.max_concurrent = max_concurrent;
+    let storage = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    MeetingsService::with_config(config, storage)
+}
+
+fn record(meeting_id: &str, segments: usize) -> MeetingRecord {
+    let started_at = Utc::now() - Duration::hours(1);
+    let transcript = (0..segments)
+        .map(|i| {
+            common::segment(
+                &format!("segment {}", i),
+                started_at + Duration::seconds(i as i64 * 10),
+            )
+        })
+        .collect();
+    common::meeting(
+        meeting_id,
+        started_at,
+        Duration::minutes(5),
+        transcript,
+        Vec::new(),
+    )
+}
+
+#[tokio::test]
+async fn test_stored_meetings_are_listed_and_read() -> Result<()> {
+    let dir = TempDir::new()?;
+    let service = service(&dir, 0);
+    service.storage.save_meeting(&record("standup", 3)).await?;
+
+    let meetings = service.list(10, 0).await?;
+    assert_eq!(meetings.len(), 1);
+    assert_eq!(meetings[0].meeting_id, "standup");
+
+    let Transcript::Full(segments) = service
+        .transcript("standup", &TranscriptQuery::default(), 50)
+        .await?
+    else {
+        panic!("Unpaged query returns the whole transcript");
+    };
+    assert_eq!(segments.len(), 3);
+
+    let query = TranscriptQuery {
+        limit: Some(2),
+        ..Default::default()
+    };
+    let Transcript::Page(page) = service.transcript("standup", &query, 2).await? else {
+        panic!("Paged query returns a page");
+    };
+    assert_eq!(page.segments.len(), 2);
+    assert_eq!(page.total, 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unknown_meetings_are_not_found() -> Result<()> {
+    let dir = TempDir::new()?;
+    let service = service(&dir, 0);
+
+    assert!(matches!(
+        service.status("nope").await,
+        Err(MeetingsError::NotFound(_))
+    ));
+    assert!(matches!(
+        service.stop("nope").await,
+        Err(MeetingsError::NotFound(_))
+    ));
+    assert!(matches!(
+        service
+            .transcript("nope", &TranscriptQuery::default(), 50)
+            .await,
+        Err(MeetingsError::NotFound(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_starts_are_refused_before_recording() -> Result<()> {
+    let dir = TempDir::new()?;
+    let service = service(&dir, 0);
+
+    let unknown_profile = service
+        .start(StartOptions {
+            meeting_id: Some("standup".to_string()),
+            profile: Some("missing".to_string()),
+            ..Default::default()
+        })
+        .await;
+    assert!(matches!(
+        unknown_profile,
+        Err(MeetingsError::InvalidRequest(message)) if message == "Unknown profile: missing"
+    ));
+
+    let records_nothing = service
+        .start(StartOptions {
+            meeting_id: Some("standup".to_string()),
+            transcription: Some(false),
+            store_audio: Some(false),
+            ..Default::default()
+        })
+        .await;
+    assert!(matches!(
+        records_nothing,
+        Err(MeetingsError::InvalidRequest(_))
+    ));
+
+    // Past the last representable expiry
+    let endless = service
+        .start(StartOptions {
+            meeting_id: Some("standup".to_string()),
+            ttl_secs: Some(9_000_000_000_000),
+            ..Default::default()
+        })
+        .await;
+    assert!(matches!(
+        endless,
+        Err(MeetingsError::InvalidRequest(message)) if message.contains("ttl_secs")
+    ));
+    assert!(service.sessions.read().await.is_empty());
+    assert_eq!(service.slots.active(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_starts_at_the_limit_queue_or_fail() -> Result<()> {
+    let dir = TempDir::new()?;
+    let service = service(&dir, 1);
+    assert!(service.slots.try_admit("standup"));
+
+    let refused = service
+        .start(StartOptions {
+            meeting_id: Some("retro".to_string()),
+            queue: Some(false),
+            ..Default::default()
+        })
+        .await;
+    assert!(matches!(refused, Err(MeetingsError::LimitReached(_))));
+
+    let queued = service
+        .start(StartOptions {
+            meeting_id: Some("retro".to_string()),
+            queue: Some(true),
+            ..Default::default()
+        })
+        .await?;
+    assert!(matches!(
+        queued,
+        StartOutcome::Queued { ref meeting_id, position: Some(1) } if meeting_id == "retro"
+    ));
+    assert!(matches!(
+        service.status("retro").await?,
+        MeetingStatus::Queued(1)
+    ));
+
+    let again = service
+        .start(StartOptions {
+            meeting_id: Some("retro".to_string()),
+            queue: Some(true),
+            ..Default::default()
+        })
+        .await;
+    assert!(matches!(again, Err(MeetingsError::Conflict(_))));
+
+    assert!(matches!(
+        service.stop("retro").await?,
+        StopOutcome::Cancelled
+    ));
+    assert!(service.slots.queued().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_close_saves_sessions_and_cancels_queued_starts() -> Result<()> {
+    let dir = TempDir::new()?;
+    let service = service(&dir, 1);
+
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "standup".to_string(),
+        transcription: false,
+        ..Default::default()
+    })
+    .await?;
+    service
+        .sessions
+        .write()
+        .await
+        .insert("standup".to_string(), Arc::new(session));
+    assert!(service.slots.try_admit("standup"));
+    service
+        .start(StartOptions {
+            meeting_id: Some("retro".to_string()),
+            queue: Some(true),
+            ..Default::default()
+        })
+        .await?;
+
+    service.close().await?;
+    assert!(service.sessions.read().await.is_empty());
+    assert!(service.slots.queued().is_empty());
+    assert!(service.readable_meeting("standup").await.is_ok());
+    assert!(matches!(
+        service.status("retro").await,
+        Err(MeetingsError::NotFound(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stopped_meeting_times_follow_the_service_clock() -> Result<()> {
+    let dir = TempDir::new()?;
+    let start = Utc.with_ymd_and_hms(2025, 6, 3, 9, 0, 0).unwrap();
+    let clock = ManualClock::new(start);
+    let service = service(&dir, 1).with_clock(clock.clone());
+
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "standup".to_string(),
+        transcription: false,
+        ttl: Some(std::time::Duration::from_secs(3600)),
+        clock: clock.clone(),
+        ..Default::default()
+    })
+    .await?;
+    service
+        .sessions
+        .write()
+        .await
+        .insert("standup".to_string(), Arc::new(session));
+
+    clock.advance(std::time::Duration::from_secs(600));
+    service.stop("standup").await?;
+    let record = service.readable_meeting("standup").await?;
+    assert_eq!(record.started_at, start);
+    assert_eq!(record.ended_at, start + Duration::minutes(10));
+    assert_eq!(record.expires_at, Some(start + Duration::minutes(70)));
+
+    // Expired by the service's clock, long before the system clock gets there
+    clock.advance(std::time::Duration::from_secs(3600));
+    assert!(matches!(
+        service.readable_meeting("standup").await,
+        Err(MeetingsError::NotFound(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generated_ids_are_dated_by_the_service_clock() -> Result<()> {
+    let dir = TempDir::new()?;
+    let clock = ManualClock::new(Utc.with_ymd_and_hms(2031, 1, 2, 12, 0, 0).unwrap());
+    let service = service(&dir, 1).with_clock(clock.clone());
+    assert!(service.slots.try_admit("standup"));
+
+    // Queued at the limit, so the ID is generated without recording
+    let queued = service
+        .start(StartOptions {
+            title: Some("Product Sync".to_string()),
+            queue: Some(true),
+            ..Default::default()
+        })
+        .await?;
+    let StartOutcome::Queued { meeting_id, .. } = queued else {
+        panic!("the start should be queued");
+    };
+    let today = clock.now().with_timezone(&chrono::Local).date_naive();
+    assert_eq!(
+        meeting_id,
+        format!("{}-product-sync", today.format("%Y-%m-%d"))
+    );
+    assert!(meeting_id.starts_with("2031-01-0"));
+
+    service.stop(&meeting_id).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retried_save_keeps_the_first_stop_time() -> Result<()> {
+    let dir = TempDir::new()?;
+    let root = dir.path().join("meetings");
+    std::fs::write(&root, b"not a directory")?;
+    let start = Utc.with_ymd_and_hms(2025, 6, 3, 9, 0, 0).unwrap();
+    let clock = ManualClock::new(start);
+    let service = service(&dir, 1).with_clock(clock.clone());
+
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "standup".to_string(),
+        transcription: false,
+        ttl: Some(std::time::Duration::from_secs(3600)),
+        clock: clock.clone(),
+        ..Default::default()
+    })
+    .await?;
+    service
+        .sessions
+        .write()
+        .await
+        .insert("standup".to_string(), Arc::new(session));
+
+    clock.advance(std::time::Duration::from_secs(600));
+    assert!(service.stop("standup").await.is_err());
+
+    // Saved hours later, the meeting still ends at the first stop
+    clock.advance(std::time::Duration::from_secs(3 * 3600));
+    std::fs::remove_file(&root)?;
+    let StopOutcome::Stopped(stats) = service.stop("standup").await? else {
+        panic!("the meeting was recording");
+    };
+    assert_eq!(stats.duration_secs, 600.0);
+
+    // Already expired by the clock, so read from storage directly
+    let record = service.storage.get_meeting("standup").await?.unwrap();
+    assert_eq!(record.ended_at, start + Duration::minutes(10));
+    assert_eq!(record.stats.duration_secs, 600.0);
+    assert_eq!(record.expires_at, Some(start + Duration::minutes(70)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_expiry_past_the_last_time_is_clamped() -> Result<()> {
+    let dir = TempDir::new()?;
+    let start = chrono::DateTime::<Utc>::MAX_UTC - Duration::hours(1);
+    let clock = ManualClock::new(start);
+    let service = service(&dir, 1).with_clock(clock.clone());
+
+    // The TTL fits at start, but not once the meeting has run
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "standup".to_string(),
+        transcription: false,
+        ttl: Some(std::time::Duration::from_secs(1800)),
+        clock: clock.clone(),
+        ..Default::default()
+    })
+    .await?;
+    service
+        .sessions
+        .write()
+        .await
+        .insert("standup".to_string(), Arc::new(session));
+
+    clock.advance(std::time::Duration::from_secs(2700));
+    service.stop("standup").await?;
+
+    let record = service.storage.get_meeting("standup").await?.unwrap();
+    assert_eq!(record.expires_at, Some(chrono::DateTime::<Utc>::MAX_UTC));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unsaved_meetings_keep_their_session_and_slot() -> Result<()> {
+    let dir = TempDir::new()?;
+    // Storage rooted at a plain file cannot create meeting directories
+    let root = dir.path().join("meetings");
+    std::fs::write(&root, b"not a directory")?;
+    let service = service(&dir, 1);
+
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "standup".to_string(),
+        transcription: false,
+        ..Default::default()
+    })
+    .await?;
+    service
+        .sessions
+        .write()
+        .await
+        .insert("standup".to_string(), Arc::new(session));
+    assert!(service.slots.try_admit("standup"));
+
+    assert!(matches!(
+        service.stop("standup").await,
+        Err(MeetingsError::Failed(_))
+    ));
+    assert!(service.sessions.read().await.contains_key("standup"));
+    assert_eq!(service.slots.active(), 1);
+
+    // Stopping again once storage recovers saves it and frees the slot
+    std::fs::remove_file(&root)?;
+    service.stop("standup").await?;
+    assert!(service.sessions.read().await.is_empty());
+    assert_eq!(service.slots.active(), 0);
+    assert!(service.readable_meeting("standup").await.is_ok());
+    Ok(())
+}
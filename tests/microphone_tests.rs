@@ -0,0 +1,73 @@
+// Tests for the microphone backend's conversion from device-native audio
+
+use loqa_meetings::audio::{
+    AudioBackendConfig, AudioBackendFactory, AudioSource, AudioStreamSource, CaptureConverter,
+    MicrophoneBackend,
+};
+
+fn config(target_sample_rate: u32, target_channels: u16) -> AudioBackendConfig {
+    AudioBackendConfig {
+        target_sample_rate,
+        target_channels,
+        buffer_duration_ms: 100,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_device_blocks_are_collected_into_frames() {
+    let mut converter = CaptureConverter::new(16000, 1, &config(16000, 1));
+
+    // Devices deliver blocks unrelated to the 100ms buffer duration
+    assert!(converter.push(&[7; 1000]).is_empty());
+    let frames = converter.push(&[7; 2500]);
+    assert_eq!(frames.len(), 2);
+    for (index, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.samples.len(), 1600);
+        assert_eq!(frame.sample_rate, 16000);
+        assert_eq!(frame.channels, 1);
+        assert_eq!(frame.timestamp_ms, index as u64 * 100);
+        assert_eq!(frame.source, AudioStreamSource::Microphone);
+    }
+
+    // The remainder starts the next frame
+    let frames = converter.push(&[7; 1300]);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].timestamp_ms, 200);
+}
+
+#[test]
+fn test_native_format_is_converted_to_the_target() {
+    // 48kHz stereo, left and right differing, to 16kHz mono
+    let mut converter = CaptureConverter::new(48000, 2, &config(16000, 1));
+    let native: Vec<i16> = (0..9600)
+        .map(|i| if i % 2 == 0 { 1000 } else { 3000 })
+        .collect();
+    let frames = converter.push(&native);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].channels, 1);
+    assert_eq!(frames[0].sample_rate, 16000);
+    assert!((1590..=1610).contains(&frames[0].samples.len()));
+    assert!(frames[0].samples[100..].iter().all(|&s| s == 2000));
+
+    // Mono devices are copied to each target channel
+    let mut converter = CaptureConverter::new(16000, 1, &config(16000, 2));
+    let frames = converter.push(&(0..1600).collect::<Vec<i16>>());
+    assert_eq!(frames[0].channels, 2);
+    assert_eq!(&frames[0].samples[..6], &[0, 0, 1, 1, 2, 2]);
+}
+
+#[tokio::test]
+async fn test_factory_creates_the_microphone_backend_when_built() {
+    let backend = AudioBackendFactory::create(AudioSource::Microphone, config(16000, 1));
+    assert_eq!(backend.is_ok(), MicrophoneBackend::is_available());
+    match backend {
+        Ok(backend) => {
+            assert_eq!(backend.name(), "cpal microphone");
+            assert!(!backend.is_capturing());
+        }
+        Err(e) => assert!(e.to_string().contains("`microphone` feature"), "{}", e),
+    }
+
+    assert!(MicrophoneBackend::new(config(0, 1)).is_err());
+}
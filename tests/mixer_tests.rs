@@ -0,0 +1,256 @@
+// Unit tests for the multi-source audio mixer
+//
+// These tests verify that frames from several sources are aligned, mixed
+// with per-source gains, that mismatched frames are coerced, dropped, or
+// rejected per policy, and that stale frames are dropped.
+
+use loqa_meetings::audio::{
+    AudioFrame, AudioStreamSource, FormatMismatch, Mixer, MixerConfig, MixerInput,
+};
+
+const RATE: u32 = 16000;
+const FRAME_SAMPLES: usize = 1600; // 100ms at 16kHz mono
+
+fn frame(source: AudioStreamSource, value: i16, timestamp_ms: u64) -> AudioFrame {
+    AudioFrame {
+        samples: vec![value; FRAME_SAMPLES],
+        sample_rate: RATE,
+        channels: 1,
+        timestamp_ms,
+        source,
+    }
+}
+
+fn two_source_mixer(mic_gain: f32) -> Mixer {
+    Mixer::new(two_source_config(mic_gain))
+}
+
+fn two_source_config(mic_gain: f32) -> MixerConfig {
+    MixerConfig::new(
+        RATE,
+        1,
+        vec![
+            MixerInput {
+                source: AudioStreamSource::System,
+                gain: 1.0,
+            },
+            MixerInput {
+                source: AudioStreamSource::MicrophoneDevice(0),
+                gain: mic_gain,
+            },
+        ],
+    )
+}
+
+fn dropping_mixer() -> Mixer {
+    Mixer::new(MixerConfig {
+        format_mismatch: FormatMismatch::Drop,
+        ..two_source_config(1.0)
+    })
+}
+
+#[test]
+fn test_mixer_waits_for_all_sources() {
+    let mut mixer = two_source_mixer(1.0);
+
+    mixer.push(frame(AudioStreamSource::System, 100, 0));
+    assert!(
+        mixer.pop_ready().is_empty(),
+        "Should wait for the microphone"
+    );
+
+    mixer.push(frame(AudioStreamSource::MicrophoneDevice(0), 50, 0));
+    let mixed = mixer.pop_ready();
+
+    assert_eq!(mixed.len(), 1);
+    assert_eq!(mixed[0].samples.len(), FRAME_SAMPLES);
+    assert!(mixed[0].samples.iter().all(|&s| s == 150));
+    assert_eq!(mixed[0].timestamp_ms, 0);
+}
+
+#[test]
+fn test_mixer_applies_per_device_gain() {
+    let mut mixer = two_source_mixer(2.0);
+
+    mixer.push(frame(AudioStreamSource::System, 100, 0));
+    mixer.push(frame(AudioStreamSource::MicrophoneDevice(0), 50, 0));
+
+    let mixed = mixer.pop_ready();
+    assert!(mixed[0].samples.iter().all(|&s| s == 200));
+}
+
+#[test]
+fn test_mixer_clamps_on_overflow() {
+    let mut mixer = two_source_mixer(1.0);
+
+    mixer.push(frame(AudioStreamSource::System, i16::MAX, 0));
+    mixer.push(frame(AudioStreamSource::MicrophoneDevice(0), i16::MAX, 0));
+
+    let mixed = mixer.pop_ready();
+    assert!(mixed[0].samples.iter().all(|&s| s == i16::MAX));
+}
+
+#[test]
+fn test_mixer_zero_fills_lagging_source() {
+    let mut mixer = two_source_mixer(1.0);
+
+    // System delivers 700ms while the microphone stays silent (no frames)
+    for i in 0..7 {
+        mixer.push(frame(AudioStreamSource::System, 100, i * 100));
+    }
+
+    let mixed = mixer.pop_ready();
+    assert!(!mixed.is_empty(), "Lagging source should not stall the mix");
+    assert!(mixed[0].samples.iter().all(|&s| s == 100));
+}
+
+#[test]
+fn test_mixer_drops_mismatched_and_old_frames() {
+    let mut mixer = dropping_mixer();
+
+    // Wrong sample rate is dropped
+    let mut wrong_rate = frame(AudioStreamSource::MicrophoneDevice(0), 1000, 0);
+    wrong_rate.sample_rate = 44100;
+    mixer.push(wrong_rate);
+
+    mixer.push(frame(AudioStreamSource::System, 100, 0));
+    mixer.push(frame(AudioStreamSource::MicrophoneDevice(0), 50, 0));
+    assert_eq!(mixer.pop_ready().len(), 1);
+
+    // A frame from before the mix position is dropped
+    mixer.push(frame(AudioStreamSource::MicrophoneDevice(0), 1000, 0));
+    assert!(mixer.flush().is_none(), "Old frame should not be buffered");
+}
+
+#[test]
+fn test_mixer_counts_drops_by_reason() {
+    let mut mixer = dropping_mixer();
+
+    let mut wrong_rate = frame(AudioStreamSource::System, 100, 0);
+    wrong_rate.sample_rate = 48000;
+    mixer.push(wrong_rate);
+    mixer.push(frame(AudioStreamSource::MicrophoneDevice(3), 100, 0));
+    mixer.push(frame(AudioStreamSource::System, 100, 0));
+    mixer.push(frame(AudioStreamSource::MicrophoneDevice(0), 50, 0));
+    mixer.pop_ready();
+    mixer.push(frame(AudioStreamSource::System, 100, 0));
+
+    let drops = mixer.drop_stats();
+    assert_eq!(drops.frames_received, 5);
+    assert_eq!(drops.mismatched_format, 1);
+    assert_eq!(drops.unknown_source, 1);
+    assert_eq!(drops.late, 1);
+    assert_eq!(drops.dropped(), 3);
+    assert!((drops.drop_rate() - 0.6).abs() < 1e-9);
+}
+
+#[test]
+fn test_mixer_flush_returns_partial_audio() {
+    let mut mixer = two_source_mixer(1.0);
+
+    mixer.push(frame(AudioStreamSource::System, 100, 0));
+
+    let flushed = mixer.flush().expect("Buffered audio should flush");
+    assert_eq!(flushed.samples.len(), FRAME_SAMPLES);
+    assert!(mixer.flush().is_none());
+}
+
+#[test]
+fn test_mixer_coerces_mismatched_frames_by_default() {
+    let mut mixer = two_source_mixer(1.0);
+
+    // A 44.1kHz stereo microphone is resampled and folded into the mix
+    let mic = AudioFrame {
+        samples: vec![50; 4410 * 2],
+        sample_rate: 44100,
+        channels: 2,
+        timestamp_ms: 0,
+        source: AudioStreamSource::MicrophoneDevice(0),
+    };
+    mixer.push(mic);
+    mixer.push(frame(AudioStreamSource::System, 100, 0));
+
+    let mixed = mixer.pop_ready();
+    assert_eq!(mixed.len(), 1, "Coerced audio counts toward the mix");
+    assert_eq!(mixed[0].sample_rate, RATE);
+    assert_eq!(mixed[0].channels, 1);
+    // Away from the resampler's edges, the mic is at its original level
+    assert_eq!(mixed[0].samples[FRAME_SAMPLES / 2], 150);
+
+    let drops = mixer.drop_stats();
+    assert_eq!(drops.coerced_format, 1);
+    assert_eq!(drops.dropped(), 0);
+}
+
+#[test]
+fn test_mixer_fail_policy_rejects_mismatched_frames() {
+    let mut mixer = Mixer::new(MixerConfig {
+        format_mismatch: FormatMismatch::Fail,
+        ..two_source_config(1.0)
+    });
+
+    let mut wrong_rate = frame(AudioStreamSource::MicrophoneDevice(0), 50, 0);
+    wrong_rate.sample_rate = 44100;
+    let error = mixer
+        .try_push_float(wrong_rate.into())
+        .expect_err("Mismatched frame should be rejected");
+    assert!(error.to_string().contains("44100Hz"), "Got: {}", error);
+    assert_eq!(mixer.drop_stats().mismatched_format, 1);
+
+    assert!(mixer
+        .try_push_float(frame(AudioStreamSource::System, 100, 0).into())
+        .is_ok());
+}
+
+#[test]
+fn test_format_mismatch_policy_names() {
+    for (name, policy) in [
+        ("\"coerce\"", FormatMismatch::Coerce),
+        ("\"drop\"", FormatMismatch::Drop),
+        ("\"fail\"", FormatMismatch::Fail),
+    ] {
+        assert_eq!(serde_json::to_string(&policy).unwrap(), name);
+        assert_eq!(
+            serde_json::from_str::<FormatMismatch>(name).unwrap(),
+            policy
+        );
+    }
+}
+
+#[test]
+fn test_sources_can_join_and_leave() {
+    let mut mixer = Mixer::new(MixerConfig::new(
+        RATE,
+        1,
+        vec![MixerInput {
+            source: AudioStreamSource::System,
+            gain: 1.0,
+        }],
+    ))
+    .starting_at(1000);
+    let companion = AudioStreamSource::MicrophoneDevice(1);
+    assert!(!mixer.has_input(companion));
+
+    mixer.add_input(MixerInput {
+        source: companion,
+        gain: 0.5,
+    });
+    assert!(mixer.has_input(companion));
+    mixer.push(frame(AudioStreamSource::System, 100, 1000));
+    assert!(
+        mixer.pop_ready().is_empty(),
+        "Should wait for the companion"
+    );
+
+    mixer.push(frame(companion, 100, 1000));
+    let mixed = mixer.pop_ready();
+    assert_eq!(mixed.len(), 1);
+    assert_eq!(mixed[0].timestamp_ms, 1000);
+    assert!(mixed[0].samples.iter().all(|&s| s == 150));
+
+    // Once it leaves, the mix stops waiting for it
+    mixer.remove_input(companion);
+    assert!(!mixer.has_input(companion));
+    mixer.push(frame(AudioStreamSource::System, 100, 1100));
+    assert_eq!(mixer.pop_ready().len(), 1);
+}
@@ -1,16 +1,25 @@
 use base64::Engine;
-use loqa_meetings::nats::messages::{AudioFrameMessage, TranscriptMessage};
+use loqa_meetings::nats::messages::{
+    AudioCodec, AudioFrameMessage, HandshakeReply, HandshakeRequest, Protocol, SessionStatus,
+    StatusMessage, TranscriptMessage, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
+};
+use loqa_meetings::session::{HostInfo, SessionTask};
 
 #[test]
 fn test_audio_frame_serialization() {
     let msg = AudioFrameMessage {
+        version: PROTOCOL_VERSION,
         session_id: "test-meeting".to_string(),
         sequence: 0,
-        pcm: base64::engine::general_purpose::STANDARD.encode(&[0u8; 100]),
+        pcm: base64::engine::general_purpose::STANDARD.encode([0u8; 100]),
         sample_rate: 16000,
         channels: 1,
         timestamp: "2025-10-27T14:30:00Z".to_string(),
         final_frame: false,
+        segment_final: false,
+        model: None,
+        codec: AudioCodec::Pcm16,
+        packets: Vec::new(),
     };
 
     let json = serde_json::to_string(&msg).unwrap();
@@ -30,6 +39,7 @@ fn test_audio_frame_serialization() {
 #[test]
 fn test_audio_frame_final_marker() {
     let msg = AudioFrameMessage {
+        version: PROTOCOL_VERSION,
         session_id: "test-meeting".to_string(),
         sequence: 10,
         pcm: String::new(), // Empty for final marker
@@ -37,10 +47,15 @@ fn test_audio_frame_final_marker() {
         channels: 1,
         timestamp: "2025-10-27T14:30:00Z".to_string(),
         final_frame: true,
+        segment_final: false,
+        model: None,
+        codec: AudioCodec::Pcm16,
+        packets: Vec::new(),
     };
 
     let json = serde_json::to_string(&msg).unwrap();
     assert!(json.contains("\"final\":true"));
+    assert!(!json.contains("model"));
 
     let deserialized: AudioFrameMessage = serde_json::from_str(&json).unwrap();
     assert!(deserialized.final_frame);
@@ -48,6 +63,37 @@ fn test_audio_frame_final_marker() {
     assert_eq!(deserialized.sequence, 10);
 }
 
+#[test]
+fn test_audio_frame_segment_final_hint() {
+    let mut msg = AudioFrameMessage {
+        version: PROTOCOL_VERSION,
+        session_id: "test-meeting".to_string(),
+        sequence: 3,
+        pcm: base64::engine::general_purpose::STANDARD.encode([0u8; 100]),
+        sample_rate: 16000,
+        channels: 1,
+        timestamp: "2025-10-27T14:30:00Z".to_string(),
+        final_frame: false,
+        segment_final: false,
+        model: None,
+        codec: AudioCodec::Pcm16,
+        packets: Vec::new(),
+    };
+
+    // Omitted unless set, so older consumers see the same messages
+    let json = serde_json::to_string(&msg).unwrap();
+    assert!(!json.contains("segment_final"));
+
+    msg.segment_final = true;
+    let json = serde_json::to_string(&msg).unwrap();
+    assert!(json.contains("\"segment_final\":true"));
+    assert!(json.contains("\"final\":false"));
+
+    let deserialized: AudioFrameMessage = serde_json::from_str(&json).unwrap();
+    assert!(deserialized.segment_final);
+    assert!(!deserialized.final_frame);
+}
+
 #[test]
 fn test_transcript_deserialization() {
     let json = r#"{
@@ -111,6 +157,7 @@ fn test_pcm_encoding_roundtrip() {
 
     // Create message
     let msg = AudioFrameMessage {
+        version: PROTOCOL_VERSION,
         session_id: "test".to_string(),
         sequence: 0,
         pcm: encoded,
@@ -118,6 +165,10 @@ fn test_pcm_encoding_roundtrip() {
         channels: 1,
         timestamp: "2025-10-27T14:30:00Z".to_string(),
         final_frame: false,
+        segment_final: false,
+        model: None,
+        codec: AudioCodec::Pcm16,
+        packets: Vec::new(),
     };
 
     // Serialize and deserialize
@@ -137,3 +188,232 @@ fn test_pcm_encoding_roundtrip() {
 
     assert_eq!(decoded_samples, original_samples);
 }
+
+#[test]
+fn test_status_message_serialization() {
+    let msg = StatusMessage {
+        version: PROTOCOL_VERSION,
+        session_id: "test-meeting".to_string(),
+        timestamp: "2025-10-27T14:30:00Z".to_string(),
+        status: SessionStatus::Failed {
+            task: SessionTask::Audio,
+            error: "Audio capture ended unexpectedly".to_string(),
+        },
+    };
+
+    let value = serde_json::to_value(&msg).unwrap();
+    assert_eq!(value["session_id"], "test-meeting");
+    assert_eq!(value["status"], "failed");
+    assert_eq!(value["task"], "audio");
+    assert_eq!(value["error"], "Audio capture ended unexpectedly");
+
+    let json = r#"{
+        "session_id": "test-meeting",
+        "timestamp": "2025-10-27T15:00:00Z",
+        "status": "stopped",
+        "duration_secs": 1800.5
+    }"#;
+    let deserialized: StatusMessage = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        deserialized.status,
+        SessionStatus::Stopped {
+            duration_secs: 1800.5
+        }
+    );
+
+    let started = serde_json::to_string(&SessionStatus::Started { host: None }).unwrap();
+    assert_eq!(started, r#"{"status":"started"}"#);
+}
+
+#[test]
+fn test_started_status_carries_host_info() {
+    let host = HostInfo {
+        capture_backend: Some("ScreenCaptureKit".to_string()),
+        ..HostInfo::current(Some("studio-mac".to_string()))
+    };
+    assert_eq!(host.app_version, env!("CARGO_PKG_VERSION"));
+    assert!(host.platform.starts_with(std::env::consts::OS));
+
+    let msg = StatusMessage {
+        version: PROTOCOL_VERSION,
+        session_id: "test-meeting".to_string(),
+        timestamp: "2025-10-27T14:30:00Z".to_string(),
+        status: SessionStatus::Started {
+            host: Some(host.clone()),
+        },
+    };
+
+    let value = serde_json::to_value(&msg).unwrap();
+    assert_eq!(value["status"], "started");
+    assert_eq!(value["host"]["hostname"], "studio-mac");
+    assert_eq!(value["host"]["capture_backend"], "ScreenCaptureKit");
+
+    let deserialized: StatusMessage = serde_json::from_value(value).unwrap();
+    assert_eq!(
+        deserialized.status,
+        SessionStatus::Started { host: Some(host) }
+    );
+}
+
+#[test]
+fn test_pcm_frames_omit_the_codec_for_version_1_consumers() {
+    let json = r#"{
+        "session_id": "test-meeting",
+        "sequence": 3,
+        "pcm": "",
+        "sample_rate": 16000,
+        "channels": 1,
+        "timestamp": "2025-10-27T14:30:00Z",
+        "final": false
+    }"#;
+    let msg: AudioFrameMessage = serde_json::from_str(json).unwrap();
+    assert_eq!(msg.codec, AudioCodec::Pcm16);
+    assert!(msg.packets.is_empty());
+
+    let json = serde_json::to_string(&msg).unwrap();
+    assert!(!json.contains("codec"));
+    assert!(!json.contains("packets"));
+}
+
+#[test]
+fn test_opus_frame_serialization() {
+    let msg = AudioFrameMessage {
+        version: PROTOCOL_VERSION,
+        session_id: "test-meeting".to_string(),
+        sequence: 7,
+        pcm: String::new(),
+        sample_rate: 16000,
+        channels: 1,
+        timestamp: "2025-10-27T14:30:00Z".to_string(),
+        final_frame: false,
+        segment_final: false,
+        model: None,
+        codec: AudioCodec::Opus,
+        packets: vec![
+            base64::engine::general_purpose::STANDARD.encode([0xf8, 0xff, 0xfe]),
+            base64::engine::general_purpose::STANDARD.encode([0xf8, 0x01]),
+        ],
+    };
+
+    let value = serde_json::to_value(&msg).unwrap();
+    assert_eq!(value["codec"], "opus");
+    assert_eq!(value["packets"].as_array().unwrap().len(), 2);
+
+    let deserialized: AudioFrameMessage = serde_json::from_value(value).unwrap();
+    assert_eq!(deserialized.codec, AudioCodec::Opus);
+    assert_eq!(deserialized.packets, msg.packets);
+}
+
+fn reply(version: u32, codecs: Vec<AudioCodec>, max_batch_frames: u32) -> HandshakeReply {
+    HandshakeReply {
+        version,
+        min_version: MIN_PROTOCOL_VERSION,
+        codecs,
+        max_batch_frames,
+    }
+}
+
+#[test]
+fn test_messages_without_a_version_are_version_1() {
+    let json = r#"{
+        "session_id": "test-meeting",
+        "text": "Hello world",
+        "partial": false,
+        "timestamp": "2025-10-27T14:30:05Z"
+    }"#;
+    let msg: TranscriptMessage = serde_json::from_str(json).unwrap();
+    assert_eq!(msg.version, 1);
+
+    let status = serde_json::to_value(StatusMessage {
+        version: PROTOCOL_VERSION,
+        session_id: "test-meeting".to_string(),
+        timestamp: "2025-10-27T14:30:00Z".to_string(),
+        status: SessionStatus::Started { host: None },
+    })
+    .unwrap();
+    assert_eq!(status["version"], PROTOCOL_VERSION);
+}
+
+#[test]
+fn test_handshake_offer_lists_preferred_codec_first() {
+    let offer = Protocol::offer("test-meeting", AudioCodec::Pcm16, 5, 16000, 1);
+    assert_eq!(offer.version, PROTOCOL_VERSION);
+    assert_eq!(offer.min_version, MIN_PROTOCOL_VERSION);
+    assert_eq!(offer.codecs[0], AudioCodec::Pcm16);
+    assert_eq!(offer.codecs, AudioCodec::supported());
+    assert_eq!(offer.max_batch_frames, 5);
+
+    let offer = Protocol::offer("test-meeting", AudioCodec::Opus, 0, 16000, 1);
+    assert_eq!(offer.max_batch_frames, 1);
+    assert_eq!(
+        offer.codecs.first() == Some(&AudioCodec::Opus),
+        AudioCodec::Opus.is_supported()
+    );
+}
+
+#[test]
+fn test_handshake_agrees_on_codec_and_batching() {
+    let offer = Protocol::offer("test-meeting", AudioCodec::Pcm16, 10, 16000, 1);
+
+    let protocol = Protocol::negotiate(
+        &offer,
+        &reply(
+            PROTOCOL_VERSION,
+            vec![AudioCodec::Opus, AudioCodec::Pcm16],
+            4,
+        ),
+    )
+    .unwrap();
+    assert_eq!(protocol.version, PROTOCOL_VERSION);
+    assert_eq!(protocol.codec, AudioCodec::Pcm16);
+    assert_eq!(protocol.batch_frames, 4);
+
+    // A service listing no codecs accepts PCM
+    let protocol = Protocol::negotiate(&offer, &reply(PROTOCOL_VERSION, Vec::new(), 20)).unwrap();
+    assert_eq!(protocol.codec, AudioCodec::Pcm16);
+    assert_eq!(protocol.batch_frames, 10);
+
+    // Version 1 services get plain PCM frames
+    assert_eq!(
+        Protocol::negotiate(&offer, &reply(1, vec![AudioCodec::Opus], 10)).unwrap(),
+        Protocol::legacy()
+    );
+}
+
+#[test]
+fn test_handshake_fails_when_incompatible() {
+    let offer = Protocol::offer("test-meeting", AudioCodec::Pcm16, 1, 16000, 1);
+
+    // Versions do not overlap
+    let future = HandshakeReply {
+        min_version: PROTOCOL_VERSION + 1,
+        ..reply(PROTOCOL_VERSION + 2, vec![AudioCodec::Pcm16], 1)
+    };
+    let error = Protocol::negotiate(&offer, &future).unwrap_err();
+    assert!(error.to_string().contains("protocol"), "{}", error);
+
+    // No codec in common
+    let pcm_offer = Protocol::offer("test-meeting", AudioCodec::Pcm16, 1, 16000, 1);
+    let error = Protocol::negotiate(
+        &HandshakeRequest {
+            codecs: vec![AudioCodec::Pcm16],
+            ..pcm_offer
+        },
+        &reply(PROTOCOL_VERSION, vec![AudioCodec::Opus], 1),
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("codecs"), "{}", error);
+}
+
+#[test]
+fn test_durable_consumer_name_is_valid_for_nats() {
+    use loqa_meetings::nats::consumer_name;
+
+    assert_eq!(consumer_name("standup"), "loqa-meetings-standup");
+    // Subject tokens and wildcards aren't allowed in consumer names
+    assert_eq!(
+        consumer_name("team.sync >*1 2"),
+        "loqa-meetings-team_sync___1_2"
+    );
+    assert_ne!(consumer_name("a.b"), consumer_name("a-b"));
+}
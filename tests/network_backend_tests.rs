@@ -0,0 +1,133 @@
+// Tests for receiving audio from another device over RTP or UDP
+
+use loqa_meetings::audio::{
+    AudioBackend, NetworkBackend, NetworkCodec, NetworkProtocol, NetworkSourceConfig, PacketDecoder,
+};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// RTP packet with an L16 (big-endian) payload
+fn rtp_packet(sequence: u16, ssrc: u32, samples: &[i16]) -> Vec<u8> {
+    let mut packet = vec![0x80, 96];
+    packet.extend(sequence.to_be_bytes());
+    packet.extend((sequence as u32 * 160).to_be_bytes());
+    packet.extend(ssrc.to_be_bytes());
+    for sample in samples {
+        packet.extend(sample.to_be_bytes());
+    }
+    packet
+}
+
+fn local_config(protocol: NetworkProtocol) -> NetworkSourceConfig {
+    NetworkSourceConfig {
+        bind: SocketAddr::from(([127, 0, 0, 1], 0)),
+        protocol,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_rtp_packets_are_parsed() {
+    let mut decoder = PacketDecoder::new(&NetworkSourceConfig::default()).unwrap();
+    assert_eq!(
+        decoder.decode(&rtp_packet(7, 1, &[1, -2, 300])).unwrap(),
+        vec![1, -2, 300]
+    );
+
+    // A CSRC, a header extension, and padding around the payload
+    let mut packet = rtp_packet(8, 1, &[]);
+    packet[0] = 0x80 | 0x20 | 0x10 | 1;
+    packet.extend([0, 0, 0, 9]);
+    packet.extend([0xbe, 0xde, 0, 1, 1, 2, 3, 4]);
+    packet.extend(5i16.to_be_bytes());
+    packet.extend([0, 0, 3]);
+    assert_eq!(decoder.decode(&packet).unwrap(), vec![5]);
+
+    assert!(decoder.decode(&[0x80, 96, 0]).is_err());
+    assert!(decoder.decode(&[0u8; 16]).is_err());
+}
+
+#[test]
+fn test_rtp_loss_is_concealed_and_late_packets_dropped() {
+    let mut decoder = PacketDecoder::new(&NetworkSourceConfig::default()).unwrap();
+    assert_eq!(
+        decoder
+            .decode(&rtp_packet(65534, 1, &[1, 1]))
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // Two packets lost across the sequence wrap
+    assert_eq!(
+        decoder.decode(&rtp_packet(1, 1, &[2, 2])).unwrap(),
+        vec![0, 0, 0, 0, 2, 2]
+    );
+    // The lost packet arriving late, a repeat, and another sender
+    assert!(decoder
+        .decode(&rtp_packet(0, 1, &[3, 3]))
+        .unwrap()
+        .is_empty());
+    assert!(decoder
+        .decode(&rtp_packet(1, 1, &[2, 2]))
+        .unwrap()
+        .is_empty());
+    assert!(decoder
+        .decode(&rtp_packet(2, 9, &[4, 4]))
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        decoder.decode(&rtp_packet(2, 1, &[5, 5])).unwrap(),
+        vec![5, 5]
+    );
+}
+
+#[test]
+fn test_opus_needs_the_feature() {
+    let config = NetworkSourceConfig {
+        codec: NetworkCodec::Opus,
+        ..Default::default()
+    };
+    assert_eq!(NetworkBackend::new(config).is_ok(), cfg!(feature = "opus"));
+
+    assert!(NetworkBackend::new(NetworkSourceConfig {
+        channels: 0,
+        ..Default::default()
+    })
+    .is_err());
+}
+
+#[tokio::test]
+async fn test_udp_audio_is_received_as_frames() {
+    let mut backend = NetworkBackend::new(local_config(NetworkProtocol::Udp)).unwrap();
+    let mut frames = backend.start().await.unwrap();
+    assert!(backend.is_capturing());
+    let addr = backend.local_addr().unwrap();
+
+    let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let samples: Vec<u8> = [100i16; 1600]
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect();
+    for _ in 0..2 {
+        sender.send_to(&samples, addr).await.unwrap();
+    }
+
+    let first = tokio::time::timeout(Duration::from_secs(5), frames.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.samples, vec![100; 1600]);
+    assert_eq!(first.sample_rate, 16000);
+    assert_eq!(first.timestamp_ms, 0);
+    let second = tokio::time::timeout(Duration::from_secs(5), frames.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(second.timestamp_ms, 100);
+
+    backend.stop().await.unwrap();
+    assert!(!backend.is_capturing());
+    assert!(frames.recv().await.is_none());
+}
@@ -0,0 +1,114 @@
+// Tests for writing spoken numbers, dates, and currencies as digits
+
+use anyhow::Result;
+use chrono::Utc;
+use loqa_meetings::session::{
+    NormalizationConfig, NumberFormat, SpokenNormalizer, TranscriptProcessor,
+};
+use loqa_meetings::TranscriptSegment;
+
+fn normalize(text: &str) -> String {
+    SpokenNormalizer::new("en-US").normalize(text)
+}
+
+#[test]
+fn test_currencies() {
+    assert_eq!(
+        normalize("The budget is twenty five thousand dollars."),
+        "The budget is $25,000."
+    );
+    assert_eq!(normalize("five dollars and fifty cents"), "$5.50");
+    assert_eq!(normalize("one hundred and five euros"), "€105");
+    assert_eq!(normalize("about 40 bucks"), "about $40");
+    assert_eq!(
+        normalize("three million two hundred thousand pounds"),
+        "£3,200,000"
+    );
+}
+
+#[test]
+fn test_numbers_and_percentages() {
+    assert_eq!(normalize("we had fifteen attendees"), "we had 15 attendees");
+    assert_eq!(normalize("growth of twelve percent"), "growth of 12%");
+    assert_eq!(normalize("three point five per cent"), "3.5%");
+    assert_eq!(normalize("nineteen ninety nine"), "1999");
+    assert_eq!(normalize("the twenty-first century"), "the 21st century");
+    assert_eq!(normalize("Two thousand and twelve people"), "2,012 people");
+}
+
+#[test]
+fn test_dates() {
+    assert_eq!(
+        normalize("Ship it by March fifth twenty twenty five."),
+        "Ship it by March 5, 2025."
+    );
+    assert_eq!(normalize("on the third of June"), "on June 3");
+    assert_eq!(
+        SpokenNormalizer::new("en-GB").normalize("on March the twenty-first two thousand twenty"),
+        "on 21 March 2020"
+    );
+}
+
+#[test]
+fn test_ambiguous_words_are_left_alone() {
+    for text in [
+        "I have two questions",
+        "we may first need a review",
+        "the code is four five six",
+        "give me a twenty second break",
+        "a five-year plan",
+        "it is 2025 already",
+    ] {
+        assert_eq!(normalize(text), text);
+    }
+}
+
+#[test]
+fn test_locale_formats() {
+    let de = SpokenNormalizer::new("de_DE");
+    assert_eq!(
+        de.normalize("twenty five thousand dollars and ten cents"),
+        "25.000,10 $"
+    );
+    assert_eq!(de.normalize("fifty percent"), "50 %");
+
+    assert_eq!(
+        NumberFormat::for_locale("en"),
+        NumberFormat::for_locale("en-US")
+    );
+    assert!(NumberFormat::for_locale("en-AU").day_first);
+    assert_eq!(NumberFormat::for_locale("xx").group, ",");
+}
+
+#[tokio::test]
+async fn test_processor_rewrites_english_final_segments() -> Result<()> {
+    assert!(SpokenNormalizer::from_config(&NormalizationConfig::default()).is_none());
+    let normalizer = SpokenNormalizer::from_config(&NormalizationConfig {
+        enabled: true,
+        ..Default::default()
+    })
+    .expect("Enabled");
+
+    let mut segment = TranscriptSegment {
+        text: "It costs twenty dollars".to_string(),
+        timestamp: Utc::now(),
+        language: Some("en".to_string()),
+        ..Default::default()
+    };
+    let processed = normalizer
+        .process("meeting-1", &segment)
+        .await?
+        .expect("Rewritten");
+    assert_eq!(processed.text, "It costs $20");
+
+    // Nothing to rewrite, other languages, and partials are left alone
+    segment.text = "Sounds good".to_string();
+    assert!(normalizer.process("meeting-1", &segment).await?.is_none());
+    segment.text = "twenty dollars".to_string();
+    segment.language = Some("de".to_string());
+    assert!(normalizer.process("meeting-1", &segment).await?.is_none());
+    segment.language = None;
+    segment.partial = true;
+    assert!(normalizer.process("meeting-1", &segment).await?.is_none());
+    Ok(())
+}
@@ -0,0 +1,42 @@
+// Tests for Opus encoding of published audio
+//
+// These tests verify that the encoder packs PCM into 20 ms packets across
+// frame boundaries, pads the last partial packet on flush, and that builds
+// without the `opus` feature refuse to create an encoder.
+
+use loqa_meetings::audio::OpusEncoder;
+
+#[cfg(feature = "opus")]
+#[test]
+fn test_encoder_emits_whole_20ms_packets() -> anyhow::Result<()> {
+    let mut encoder = OpusEncoder::new(16000, 1)?;
+    let tone: Vec<i16> = (0..1600)
+        .map(|i| ((i as f32 * 0.1).sin() * 8000.0) as i16)
+        .collect();
+
+    // 100 ms is five packets
+    let packets = encoder.encode(&tone)?;
+    assert_eq!(packets.len(), 5);
+    assert!(packets.iter().all(|packet| !packet.is_empty()));
+    assert!(packets.iter().map(Vec::len).sum::<usize>() < tone.len() * 2);
+
+    // 30 ms completes one packet and leaves 10 ms pending
+    assert_eq!(encoder.encode(&tone[..480])?.len(), 1);
+    assert!(encoder.flush()?.is_some());
+    assert!(encoder.flush()?.is_none());
+
+    Ok(())
+}
+
+#[cfg(feature = "opus")]
+#[test]
+fn test_encoder_rejects_unsupported_rates() {
+    assert!(OpusEncoder::new(44100, 1).is_err());
+}
+
+#[cfg(not(feature = "opus"))]
+#[test]
+fn test_encoder_requires_opus_feature() {
+    assert!(!OpusEncoder::is_available());
+    assert!(OpusEncoder::new(16000, 1).is_err());
+}
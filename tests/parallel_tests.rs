@@ -0,0 +1,124 @@
+// Tests for splitting long recordings into parallel transcription windows
+
+mod common;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use loqa_meetings::session::{merge_windows, plan_windows, TimeRange, WindowConfig};
+use loqa_meetings::TranscriptSegment;
+
+fn config(window_secs: u64, overlap_secs: u64) -> WindowConfig {
+    WindowConfig {
+        window_secs,
+        overlap_secs,
+        ..Default::default()
+    }
+}
+
+fn segment(text: &str, started_at: DateTime<Utc>, offset_ms: i64) -> TranscriptSegment {
+    common::segment(text, started_at + Duration::milliseconds(offset_ms))
+}
+
+fn texts(segments: &[TranscriptSegment]) -> Vec<&str> {
+    segments.iter().map(|s| s.text.as_str()).collect()
+}
+
+#[test]
+fn test_plan_windows_overlap_and_cover_range() -> Result<()> {
+    let windows = plan_windows(TimeRange::new(0, 250_000)?, &config(100, 10));
+
+    assert_eq!(
+        windows,
+        vec![
+            TimeRange::new(0, 100_000)?,
+            TimeRange::new(90_000, 190_000)?,
+            TimeRange::new(180_000, 250_000)?,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_plan_windows_short_range_is_one_window() -> Result<()> {
+    let range = TimeRange::new(5_000, 60_000)?;
+    assert_eq!(plan_windows(range, &config(120, 5)), vec![range]);
+
+    // Overlap is capped so windows always advance
+    let windows = plan_windows(TimeRange::new(0, 10_000)?, &config(4, 10));
+    assert_eq!(windows.first(), Some(&TimeRange::new(0, 4_000)?));
+    assert_eq!(windows.last().map(|w| w.end_ms), Some(10_000));
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_windows_cuts_overlap_at_midpoint() -> Result<()> {
+    let started_at = Utc::now();
+    let first = TimeRange::new(0, 100_000)?;
+    let second = TimeRange::new(90_000, 200_000)?;
+
+    let merged = merge_windows(
+        started_at,
+        &[
+            (
+                first,
+                vec![
+                    segment("opening", started_at, 10_000),
+                    segment("early overlap", started_at, 92_000),
+                    segment("late overlap from first", started_at, 98_000),
+                ],
+            ),
+            (
+                second,
+                vec![
+                    segment("early overlap from second", started_at, 91_000),
+                    segment("late overlap", started_at, 97_000),
+                    segment("closing", started_at, 150_000),
+                ],
+            ),
+        ],
+    );
+
+    assert_eq!(
+        texts(&merged),
+        vec!["opening", "early overlap", "late overlap", "closing"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_windows_drops_repeated_segment_at_cut() -> Result<()> {
+    let started_at = Utc::now();
+    let first = TimeRange::new(0, 100_000)?;
+    let second = TimeRange::new(90_000, 200_000)?;
+
+    // Both windows heard the same sentence, and placed it either side of the cut
+    let merged = merge_windows(
+        started_at,
+        &[
+            (
+                first,
+                vec![
+                    segment("hello", started_at, 1_000),
+                    segment("Let's move on.", started_at, 94_000),
+                ],
+            ),
+            (
+                second,
+                vec![
+                    segment("let's move on", started_at, 96_000),
+                    segment("next topic", started_at, 120_000),
+                ],
+            ),
+        ],
+    );
+
+    assert_eq!(
+        texts(&merged),
+        vec!["hello", "Let's move on.", "next topic"]
+    );
+
+    Ok(())
+}
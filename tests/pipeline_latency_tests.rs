@@ -0,0 +1,67 @@
+// Tests for per-stage pipeline timings
+
+use loqa_meetings::session::{PipelineLatency, PipelineStage};
+use std::time::Duration;
+
+#[test]
+fn test_stage_timings_accumulate() {
+    let mut latency = PipelineLatency::new();
+    for ms in [2, 4, 6] {
+        assert!(!latency.record(PipelineStage::Process, Duration::from_millis(ms)));
+    }
+
+    let process = latency.stage(PipelineStage::Process);
+    assert_eq!(process.samples, 3);
+    assert!((process.mean_ms - 4.0).abs() < 1e-9);
+    assert!((process.max_ms - 6.0).abs() < 1e-9);
+    assert!((process.last_ms - 6.0).abs() < 1e-9);
+
+    // Other stages are untouched
+    assert_eq!(latency.stage(PipelineStage::Publish).samples, 0);
+}
+
+#[test]
+fn test_spikes_are_attributed_to_their_stage() {
+    let mut latency = PipelineLatency::new();
+    for _ in 0..60 {
+        latency.record(PipelineStage::Publish, Duration::from_millis(5));
+        latency.record(PipelineStage::Process, Duration::from_millis(5));
+    }
+
+    assert!(latency.record(PipelineStage::Publish, Duration::from_millis(80)));
+    assert!(!latency.record(PipelineStage::Process, Duration::from_millis(6)));
+
+    assert_eq!(latency.stage(PipelineStage::Publish).spikes, 1);
+    assert_eq!(latency.stage(PipelineStage::Process).spikes, 0);
+}
+
+#[test]
+fn test_no_spikes_before_enough_samples_or_below_the_floor() {
+    let mut latency = PipelineLatency::new();
+    latency.record(PipelineStage::Capture, Duration::from_millis(1));
+    assert!(
+        !latency.record(PipelineStage::Capture, Duration::from_millis(200)),
+        "Too few samples to judge"
+    );
+
+    let mut latency = PipelineLatency::new();
+    for _ in 0..60 {
+        latency.record(PipelineStage::Process, Duration::from_micros(100));
+    }
+    assert!(
+        !latency.record(PipelineStage::Process, Duration::from_millis(2)),
+        "Sub-floor jitter is not a spike"
+    );
+}
+
+#[test]
+fn test_summary_lists_every_stage_by_name() {
+    let mut latency = PipelineLatency::new();
+    latency.record(PipelineStage::Transcript, Duration::from_millis(900));
+
+    let json = serde_json::to_value(latency.summary()).unwrap();
+    let stages: Vec<&String> = json.as_object().unwrap().keys().collect();
+    assert_eq!(stages, vec!["capture", "process", "publish", "transcript"]);
+    assert_eq!(json["transcript"]["samples"], 1);
+    assert_eq!(json["capture"]["samples"], 0);
+}
@@ -0,0 +1,244 @@
+// Tests for the per-frame processing pipeline and its stages
+
+use loqa_meetings::audio::{
+    AudioProcessor, AudioStreamSource, DownmixStage, DownmixStrategy, FloatFrame, GainStage,
+    GateStage, NoiseSuppressionStage, Pipeline, ResampleStage, ResamplerQuality, StageConfig,
+};
+use std::collections::HashMap;
+
+fn frame(sample_rate: u32, channels: u16, samples: Vec<f32>) -> FloatFrame {
+    FloatFrame {
+        samples,
+        sample_rate,
+        channels,
+        timestamp_ms: 0,
+        source: AudioStreamSource::System,
+    }
+}
+
+#[test]
+fn test_conversion_downmixes_then_resamples() {
+    let mut pipeline = Pipeline::conversion(
+        16000,
+        1,
+        DownmixStrategy::default(),
+        ResamplerQuality::Linear,
+        false,
+    );
+    assert_eq!(pipeline.stage_names(), vec!["downmix", "resample"]);
+
+    // 100ms of 48kHz stereo becomes 100ms of 16kHz mono
+    let processed = pipeline.process(frame(48000, 2, vec![0.25; 9600]));
+    assert_eq!(processed.channels, 1);
+    assert_eq!(processed.sample_rate, 16000);
+    assert_eq!(processed.samples.len(), 1600);
+
+    // Frames already in the target format pass through untouched
+    let samples = vec![0.1, -0.2, 0.3];
+    let processed = pipeline.process(frame(16000, 1, samples.clone()));
+    assert_eq!(processed.samples, samples);
+
+    // A stereo target keeps the channels apart
+    let mut stereo = Pipeline::conversion(
+        16000,
+        2,
+        DownmixStrategy::default(),
+        ResamplerQuality::Linear,
+        false,
+    );
+    assert_eq!(stereo.stage_names(), vec!["resample"]);
+    assert_eq!(stereo.process(frame(16000, 2, vec![0.5; 4])).channels, 2);
+}
+
+#[test]
+fn test_stages_quantize_only_when_asked() {
+    let sub_lsb = 0.3 / 32768.0;
+    let mut gain = HashMap::new();
+    gain.insert(AudioStreamSource::System, 2.0);
+
+    let mut float = Pipeline::new(false).with(GainStage::new(gain.clone()));
+    let processed = float.process(frame(16000, 1, vec![sub_lsb]));
+    assert!((processed.samples[0] - 2.0 * sub_lsb).abs() < 1e-9);
+
+    let mut pcm16 = Pipeline::new(true).with(GainStage::new(gain));
+    let processed = pcm16.process(frame(16000, 1, vec![sub_lsb]));
+    assert_eq!(processed.samples[0], 1.0 / 32768.0);
+
+    // Sources without a gain are left alone, without rounding
+    let mut microphone = frame(16000, 1, vec![sub_lsb]);
+    microphone.source = AudioStreamSource::Microphone;
+    assert_eq!(pcm16.process(microphone).samples[0], sub_lsb);
+}
+
+/// Inverts polarity, standing in for a stage added outside the crate
+struct Invert;
+
+impl AudioProcessor for Invert {
+    fn name(&self) -> &'static str {
+        "invert"
+    }
+
+    fn process(&mut self, mut frame: FloatFrame) -> FloatFrame {
+        frame
+            .samples
+            .iter_mut()
+            .for_each(|sample| *sample = -*sample);
+        frame
+    }
+}
+
+#[test]
+fn test_custom_stages_run_in_order() {
+    let mut pipeline = Pipeline::new(false)
+        .with(DownmixStage::new(DownmixStrategy::default()))
+        .with(Invert);
+    pipeline.push(Box::new(ResampleStage::new(
+        8000,
+        ResamplerQuality::Nearest,
+    )));
+    assert_eq!(
+        pipeline.stage_names(),
+        vec!["downmix", "invert", "resample"]
+    );
+
+    let processed = pipeline.process(frame(16000, 2, vec![0.25; 320]));
+    assert_eq!(processed.channels, 1);
+    assert_eq!(processed.sample_rate, 8000);
+    assert!(processed.samples.iter().all(|&sample| sample < 0.0));
+}
+
+#[test]
+fn test_pipeline_is_built_from_configured_stages() -> anyhow::Result<()> {
+    let stages: Vec<StageConfig> = serde_json::from_str(
+        r#"[{"stage": "gain", "gains": {"system": 0.5, "mic-0": 2.0}},
+            {"stage": "downmix"},
+            {"stage": "resample"}]"#,
+    )?;
+    assert_eq!(
+        stages[0],
+        StageConfig::Gain {
+            gains: [
+                (AudioStreamSource::System, 0.5),
+                (AudioStreamSource::MicrophoneDevice(0), 2.0)
+            ]
+            .into()
+        }
+    );
+
+    let mut pipeline = Pipeline::from_stages(
+        &stages,
+        16000,
+        1,
+        DownmixStrategy::default(),
+        ResamplerQuality::Linear,
+        false,
+    );
+    assert_eq!(pipeline.stage_names(), vec!["gain", "downmix", "resample"]);
+    let processed = pipeline.process(frame(16000, 1, vec![0.5; 4]));
+    assert_eq!(processed.samples, vec![0.25; 4]);
+
+    // A stereo target has nothing to downmix
+    let stereo = Pipeline::from_stages(
+        &stages,
+        16000,
+        2,
+        DownmixStrategy::default(),
+        ResamplerQuality::Linear,
+        false,
+    );
+    assert_eq!(stereo.stage_names(), vec!["gain", "resample"]);
+    Ok(())
+}
+
+/// 100ms of 16kHz mono: a 400Hz tone at `amplitude` over noise-like hiss
+/// at `hiss`
+fn tone(amplitude: f32, hiss: f32, seed: usize) -> FloatFrame {
+    let samples = (0..1600)
+        .map(|i| {
+            let n = i + seed * 1600;
+            let phase = n as f32 * 400.0 * std::f32::consts::TAU / 16000.0;
+            // Deterministic pseudo-random hiss in [-hiss, hiss]
+            let noise = ((n * 7919 % 1000) as f32 / 500.0 - 1.0) * hiss;
+            amplitude * phase.sin() + noise
+        })
+        .collect();
+    frame(16000, 1, samples)
+}
+
+fn peak(frame: &FloatFrame) -> f32 {
+    frame.samples.iter().fold(0.0, |peak, s| peak.max(s.abs()))
+}
+
+#[test]
+fn test_gate_silences_sources_quiet_past_the_hold() {
+    let mut gate = GateStage::new(-45.0, 200);
+
+    // Voice passes, as do the first 200ms of quiet after it
+    assert_eq!(
+        gate.process(tone(0.3, 0.0, 0)).samples,
+        tone(0.3, 0.0, 0).samples
+    );
+    for seed in 1..=2 {
+        let quiet = tone(0.0, 0.001, seed);
+        assert_eq!(gate.process(quiet.clone()).samples, quiet.samples);
+    }
+    let gated = gate.process(tone(0.0, 0.001, 3));
+    assert_eq!(gated.samples.len(), 1600);
+    assert!(gated.samples.iter().all(|&s| s == 0.0));
+
+    // Other sources are timed separately, and voice opens the gate again
+    let mut microphone = tone(0.0, 0.001, 4);
+    microphone.source = AudioStreamSource::Microphone;
+    assert_ne!(peak(&gate.process(microphone)), 0.0);
+    assert_ne!(peak(&gate.process(tone(0.3, 0.0, 5))), 0.0);
+}
+
+#[test]
+fn test_noise_suppression_turns_down_steady_noise() {
+    let mut suppression = NoiseSuppressionStage::new(12.0);
+
+    // A second of hiss alone ends up about 12dB down
+    let mut hiss = tone(0.0, 0.01, 0);
+    for seed in 0..10 {
+        hiss = suppression.process(tone(0.0, 0.01, seed));
+    }
+    let ratio = peak(&hiss) / peak(&tone(0.0, 0.01, 9));
+    assert!((0.2..0.3).contains(&ratio), "{}", ratio);
+
+    // Speech well above the hiss passes nearly unchanged
+    let speech = suppression.process(tone(0.3, 0.01, 10));
+    let ratio = peak(&speech) / peak(&tone(0.3, 0.01, 10));
+    assert!(ratio > 0.9, "{}", ratio);
+
+    // No reduction, no change
+    assert!(!NoiseSuppressionStage::new(0.0).applies(&hiss));
+}
+
+#[test]
+fn test_gate_and_noise_suppression_are_configurable_stages() -> anyhow::Result<()> {
+    let stages: Vec<StageConfig> = serde_json::from_str(
+        r#"[{"stage": "noise_suppression"},
+            {"stage": "gate", "threshold_dbfs": -50.0}]"#,
+    )?;
+    assert_eq!(
+        stages,
+        vec![
+            StageConfig::NoiseSuppression { reduction_db: 12.0 },
+            StageConfig::Gate {
+                threshold_dbfs: -50.0,
+                hold_ms: 300
+            },
+        ]
+    );
+
+    let pipeline = Pipeline::from_stages(
+        &stages,
+        16000,
+        1,
+        DownmixStrategy::default(),
+        ResamplerQuality::Linear,
+        false,
+    );
+    assert_eq!(pipeline.stage_names(), vec!["noise_suppression", "gate"]);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// Tests for playing a session's audio back out through a named pipe, which
+// only Linux turns into an input device
+#![cfg(target_os = "linux")]
+
+use loqa_meetings::audio::{
+    AudioStreamSource, FloatFrame, MixerConfig, MixerInput, PlaybackBridge, PlaybackConfig,
+};
+use std::io::Read;
+use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+use tempfile::TempDir;
+
+const RATE: u32 = 16000;
+
+fn frame(source: AudioStreamSource, value: f32, timestamp_ms: u64) -> FloatFrame {
+    FloatFrame {
+        samples: vec![value; 1600],
+        sample_rate: RATE,
+        channels: 1,
+        timestamp_ms,
+        source,
+    }
+}
+
+fn mic_mix() -> MixerConfig {
+    MixerConfig::new(
+        RATE,
+        1,
+        vec![
+            MixerInput {
+                source: AudioStreamSource::System,
+                gain: 1.0,
+            },
+            MixerInput {
+                source: AudioStreamSource::Microphone,
+                gain: 1.0,
+            },
+        ],
+    )
+}
+
+fn config(dir: &TempDir) -> PlaybackConfig {
+    PlaybackConfig {
+        enabled: true,
+        pipe: dir
+            .path()
+            .join("playback.pcm")
+            .to_string_lossy()
+            .into_owned(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_playback_is_off_by_default() {
+    assert!(
+        PlaybackBridge::start(&PlaybackConfig::default(), mic_mix(), 0)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_microphone_audio_is_written_to_the_pipe() {
+    let dir = TempDir::new().unwrap();
+    let config = config(&dir);
+    let mut bridge = PlaybackBridge::start(&config, mic_mix(), 0)
+        .unwrap()
+        .unwrap();
+    assert!(std::fs::metadata(config.resolved_pipe())
+        .unwrap()
+        .file_type()
+        .is_fifo());
+
+    // Nothing reads the pipe yet: audio is dropped without blocking
+    bridge.push(frame(AudioStreamSource::Microphone, 0.25, 0));
+
+    let mut reader = std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(config.resolved_pipe())
+        .unwrap();
+    // System audio isn't played, so the microphone alone completes the mix
+    bridge.push(frame(AudioStreamSource::System, 0.5, 100));
+    bridge.push(frame(AudioStreamSource::Microphone, 0.25, 100));
+    drop(bridge);
+
+    // The writer has finished, so the pipe holds everything played
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).unwrap();
+    assert!(bytes.len() >= 3200, "{} bytes played", bytes.len());
+    let samples: Vec<i16> = bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    assert!(samples.iter().all(|&s| s == 8192), "{:?}", &samples[..4]);
+}
+
+#[test]
+fn test_other_files_are_not_overwritten() {
+    let dir = TempDir::new().unwrap();
+    let config = config(&dir);
+    std::fs::write(config.resolved_pipe(), b"notes").unwrap();
+
+    assert!(PlaybackBridge::start(&config, mic_mix(), 0).is_err());
+    assert_eq!(std::fs::read(config.resolved_pipe()).unwrap(), b"notes");
+}
@@ -0,0 +1,68 @@
+// Tests for the Python module, run in an embedded interpreter
+//
+// Only built with the `python` feature.
+
+#![cfg(feature = "python")]
+
+use loqa_meetings::python::loqa_meetings;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+fn run(code: &str) -> PyResult<()> {
+    // Registering the module must happen before the interpreter starts
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        pyo3::append_to_inittab!(loqa_meetings);
+        pyo3::prepare_freethreaded_python();
+    });
+
+    Python::with_gil(|py| {
+        let locals = PyDict::new_bound(py);
+        locals.set_item("fixture", "tests/fixtures/sample-meeting.wav")?;
+        py.run_bound(code, None, Some(&locals))
+    })
+}
+
+#[test]
+fn test_audio_files_are_decoded() {
+    run(r#"
+import loqa_meetings
+
+info = loqa_meetings.probe(fixture)
+assert info["sample_rate"] == 16000, info
+assert info["codec"] == "pcm_s16le", info
+
+audio = loqa_meetings.AudioFile.open(fixture)
+assert audio.channels == 1
+assert len(audio.samples) == len(audio.to_mono())
+assert abs(audio.duration_seconds - info["duration_seconds"]) < 0.01
+
+try:
+    loqa_meetings.AudioFile.open("missing.wav")
+    raise AssertionError("Opening a missing file fails")
+except ValueError:
+    pass
+"#)
+    .unwrap();
+}
+
+#[test]
+fn test_unknown_meetings_raise_key_error() {
+    run(r#"
+import loqa_meetings, tempfile
+
+shipped = open("config/loqa-meetings.yaml").read()
+with tempfile.TemporaryDirectory() as dir:
+    with open(f"{dir}/loqa-meetings.yaml", "w") as config:
+        config.write(shipped.replace("path: ~/.loqa/meetings", f"path: {dir}/meetings"))
+    meetings = loqa_meetings.Meetings(f"{dir}/loqa-meetings.yaml")
+
+    assert meetings.list() == []
+    try:
+        meetings.transcript("missing")
+        raise AssertionError("Unknown meetings raise")
+    except KeyError:
+        pass
+"#)
+    .unwrap();
+}
@@ -0,0 +1,81 @@
+// Tests for recording quality presets
+
+use anyhow::Result;
+use loqa_meetings::audio::{
+    AudioBackendConfig, AudioFile, AudioFrame, AudioStreamSource, ChunkCodec, ChunkedRecorder,
+    MixerConfig, QualityPreset, ResamplerQuality,
+};
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+#[test]
+fn test_preset_names_roundtrip() -> Result<()> {
+    for preset in QualityPreset::ALL {
+        assert_eq!(preset.name().parse::<QualityPreset>()?, preset);
+        assert_eq!(serde_json::to_value(preset)?, preset.name());
+    }
+    assert_eq!(
+        serde_json::from_str::<QualityPreset>("\"low-latency\"")?,
+        QualityPreset::LowLatency
+    );
+    assert!("lossless".parse::<QualityPreset>().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_balanced_matches_pipeline_defaults() {
+    let preset = QualityPreset::default();
+    let mixer = MixerConfig::new(16000, 1, Vec::new());
+
+    assert_eq!(preset, QualityPreset::Balanced);
+    assert_eq!(
+        preset.frame_duration_ms(),
+        AudioBackendConfig::default().buffer_duration_ms
+    );
+    assert_eq!(preset.frame_duration_ms(), mixer.frame_duration_ms);
+    assert_eq!(preset.max_latency_ms(), mixer.max_latency_ms);
+    assert_eq!(preset.chunk_codec(), ChunkCodec::default());
+    assert_eq!(preset.resampler_quality(), ResamplerQuality::default());
+}
+
+#[test]
+fn test_presets_order_latency() {
+    let [low, balanced, archival] = QualityPreset::ALL;
+
+    assert!(low.frame_duration_ms() < balanced.frame_duration_ms());
+    assert!(balanced.frame_duration_ms() < archival.frame_duration_ms());
+    assert!(low.max_latency_ms() < archival.max_latency_ms());
+    assert!(low.channel_capacity() < archival.channel_capacity());
+}
+
+#[tokio::test]
+async fn test_archival_chunks_are_float_wav() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config =
+        QualityPreset::Archival.chunk_config("archive".to_string(), temp_dir.path().into());
+    assert_eq!(config.codec, ChunkCodec::Float32);
+
+    let mut recorder = ChunkedRecorder::new(config)?;
+    let (tx, rx) = mpsc::channel(10);
+    let handle = tokio::spawn(async move { recorder.record(rx).await });
+
+    tx.send(AudioFrame {
+        samples: vec![0, 16384, -16384, 32767],
+        sample_rate: 16000,
+        channels: 1,
+        timestamp_ms: 0,
+        source: AudioStreamSource::System,
+    })
+    .await?;
+    drop(tx);
+    let chunks = handle.await??;
+
+    let reader = hound::WavReader::open(&chunks[0].file_path)?;
+    assert_eq!(reader.spec().sample_format, hound::SampleFormat::Float);
+    assert_eq!(reader.spec().bits_per_sample, 32);
+
+    let audio = AudioFile::open(&chunks[0].file_path)?;
+    assert_eq!(audio.samples.len(), 4);
+    assert!((audio.samples[1] - 16384).abs() <= 1);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// Tests for record-only sessions (audio stored, no STT)
+
+use loqa_meetings::{Config, RecordingSession, SessionConfig};
+use tokio::sync::broadcast::error::TryRecvError;
+
+#[test]
+fn test_sessions_are_transcribed_by_default() {
+    assert!(SessionConfig::default().transcription);
+
+    let config = Config::load("config/loqa-meetings").unwrap();
+    let default = config.profile(None).expect("default always resolves");
+    assert_eq!(default.transcription, None);
+
+    let record_only = config
+        .profile(Some("record-only"))
+        .expect("record-only profile exists");
+    assert_eq!(record_only.transcription, Some(false));
+}
+
+#[tokio::test]
+async fn test_record_only_session_needs_no_nats() {
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "record-only".to_string(),
+        // Nothing listens here; a record-only session never connects
+        nats_url: "nats://127.0.0.1:1".to_string(),
+        transcription: false,
+        dual_stream: true,
+        ..Default::default()
+    })
+    .await
+    .expect("record-only sessions skip the NATS connection");
+
+    let config = session.effective_config();
+    assert!(!config.transcription);
+    assert!(!config.dual_stream, "Separate streams only matter to STT");
+}
+
+#[tokio::test]
+async fn test_record_only_privacy_session_is_rejected() {
+    let result = RecordingSession::new(SessionConfig {
+        transcription: false,
+        privacy: true,
+        ..Default::default()
+    })
+    .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_subscribers_get_nothing_before_recording() {
+    let session = RecordingSession::new(SessionConfig {
+        session_id: "frames".to_string(),
+        transcription: false,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let mut frames = session.subscribe_frames();
+    assert!(matches!(frames.try_recv(), Err(TryRecvError::Empty)));
+    let mut transcripts = session.subscribe_transcripts();
+    assert!(matches!(transcripts.try_recv(), Err(TryRecvError::Empty)));
+}
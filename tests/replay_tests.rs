@@ -0,0 +1,87 @@
+// Tests for replaying stored meetings through NATS
+
+mod common;
+
+use anyhow::Result;
+use loqa_meetings::audio::write_clip;
+use loqa_meetings::session::{chunk_frames, replay_delay, ReplayOptions, TimeRange};
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[test]
+fn test_chunk_frames_keep_meeting_offsets() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("chunk.wav");
+    // 250ms at 16kHz
+    write_clip(&path, &vec![7i16; 4000], 16000, 1)?;
+
+    let frames = chunk_frames(&common::chunk(0, &path, 60_000, 60_250), None)?;
+
+    let offsets: Vec<_> = frames.iter().map(|f| f.offset_ms).collect();
+    assert_eq!(offsets, vec![60_000, 60_100, 60_200]);
+    assert_eq!(frames[0].samples.len(), 1600);
+    assert_eq!(frames[2].samples.len(), 800);
+    assert!(frames[0].samples.iter().all(|&s| s == 7));
+    Ok(())
+}
+
+#[test]
+fn test_chunk_frames_resample_to_16khz_mono() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("chunk.wav");
+    // 100ms of 48kHz stereo
+    write_clip(&path, &vec![100i16; 9600], 48000, 2)?;
+
+    let frames = chunk_frames(&common::chunk(0, &path, 0, 100), None)?;
+
+    let samples: usize = frames.iter().map(|f| f.samples.len()).sum();
+    assert!((1590..=1610).contains(&samples), "got {} samples", samples);
+    Ok(())
+}
+
+#[test]
+fn test_chunk_frames_limited_to_range() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("chunk.wav");
+    // 1s at 16kHz
+    write_clip(&path, &vec![0i16; 16000], 16000, 1)?;
+
+    let range = TimeRange::new(10_300, 10_600)?;
+    let frames = chunk_frames(&common::chunk(0, &path, 10_000, 11_000), Some(range))?;
+
+    let offsets: Vec<_> = frames.iter().map(|f| f.offset_ms).collect();
+    assert_eq!(offsets, vec![10_300, 10_400, 10_500]);
+    Ok(())
+}
+
+#[test]
+fn test_replay_delay_follows_speed() {
+    assert_eq!(replay_delay(5_000, 5_000, 1.0), Some(Duration::ZERO));
+    assert_eq!(
+        replay_delay(7_000, 5_000, 1.0),
+        Some(Duration::from_secs(2))
+    );
+    assert_eq!(
+        replay_delay(7_000, 5_000, 2.0),
+        Some(Duration::from_secs(1))
+    );
+    assert_eq!(
+        replay_delay(7_000, 5_000, 0.5),
+        Some(Duration::from_secs(4))
+    );
+}
+
+#[test]
+fn test_replay_delay_unpaced() {
+    assert_eq!(replay_delay(7_000, 5_000, 0.0), None);
+    assert_eq!(replay_delay(7_000, 5_000, -1.0), None);
+    assert_eq!(replay_delay(7_000, 5_000, f64::INFINITY), None);
+}
+
+#[test]
+fn test_replay_options_default_to_real_time() {
+    let options = ReplayOptions::default();
+    assert_eq!(options.speed, 1.0);
+    assert!(options.range.is_none());
+    assert!(options.session_id.is_none());
+}
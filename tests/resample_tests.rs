@@ -0,0 +1,175 @@
+// Unit tests for the streaming resampler
+//
+// These tests verify output lengths for common rate conversions and that
+// resampling stays continuous across frame boundaries.
+
+use loqa_meetings::{Resampler, ResamplerQuality};
+
+#[test]
+fn test_downsample_48k_to_16k_length() {
+    let mut resampler = Resampler::new(48000, 16000, 1);
+
+    // 1 second in 100ms frames
+    let total: usize = (0..10)
+        .map(|_| resampler.process(&[0i16; 4800]).len())
+        .sum();
+
+    assert!((15999..=16000).contains(&total), "Got {} samples", total);
+}
+
+#[test]
+fn test_downsample_44_1k_to_16k_length() {
+    let mut resampler = Resampler::new(44100, 16000, 1);
+
+    let total: usize = (0..10)
+        .map(|_| resampler.process(&[0i16; 4410]).len())
+        .sum();
+
+    assert!((15999..=16000).contains(&total), "Got {} samples", total);
+}
+
+#[test]
+fn test_upsample_8k_to_16k() {
+    let mut resampler = Resampler::new(8000, 16000, 1);
+
+    let output = resampler.process(&[0, 100, 200, 300]);
+
+    // Interpolated midpoints between input samples
+    assert_eq!(&output[..6], &[0, 50, 100, 150, 200, 250]);
+}
+
+#[test]
+fn test_resampling_is_continuous_across_frames() {
+    let mut resampler = Resampler::new(44100, 16000, 1);
+
+    // A linear ramp split into uneven frames should stay a monotonic ramp
+    let ramp: Vec<i16> = (0..4410).map(|i| (i % 30000) as i16).collect();
+    let mut output = resampler.process(&ramp[..1000]);
+    output.extend(resampler.process(&ramp[1000..]));
+
+    assert!(output.windows(2).all(|w| w[1] >= w[0]));
+}
+
+#[test]
+fn test_stereo_channels_stay_separate() {
+    let mut resampler = Resampler::new(48000, 16000, 2);
+
+    // Left constant 1000, right constant -1000
+    let input: Vec<i16> = (0..4800).flat_map(|_| [1000i16, -1000]).collect();
+    let output = resampler.process(&input);
+
+    assert_eq!(output.len() % 2, 0);
+    assert!(output.chunks_exact(2).all(|c| c == [1000, -1000]));
+}
+
+#[test]
+fn test_cubic_downsample_length_matches_linear() {
+    let mut linear = Resampler::new(44100, 16000, 1);
+    let mut cubic = Resampler::with_quality(44100, 16000, 1, ResamplerQuality::Cubic);
+
+    let (mut linear_total, mut cubic_total) = (0, 0);
+    for _ in 0..10 {
+        linear_total += linear.process(&[0i16; 4410]).len();
+        cubic_total += cubic.process(&[0i16; 4410]).len();
+    }
+
+    assert_eq!(linear_total, cubic_total);
+}
+
+#[test]
+fn test_cubic_reproduces_ramps_across_frames() {
+    let mut resampler = Resampler::with_quality(8000, 16000, 1, ResamplerQuality::Cubic);
+
+    let ramp: Vec<i16> = (0..400).map(|i| i * 10).collect();
+    let mut output = resampler.process(&ramp[..150]);
+    output.extend(resampler.process(&ramp[150..]));
+
+    // Past the zero history at the start, a linear ramp stays exact
+    assert!(output[4..]
+        .iter()
+        .enumerate()
+        .all(|(i, &s)| s == ((i + 4) * 5) as i16));
+}
+
+#[test]
+fn test_cubic_stereo_channels_stay_separate() {
+    let mut resampler = Resampler::with_quality(48000, 16000, 2, ResamplerQuality::Cubic);
+
+    let input: Vec<i16> = (0..4800).flat_map(|_| [1000i16, -1000]).collect();
+    resampler.process(&input);
+    let output = resampler.process(&input);
+
+    assert!(output.chunks_exact(2).all(|c| c == [1000, -1000]));
+}
+
+#[test]
+fn test_resampler_quality_names() {
+    for quality in [
+        ResamplerQuality::Nearest,
+        ResamplerQuality::Linear,
+        ResamplerQuality::Cubic,
+        ResamplerQuality::Sinc(32),
+    ] {
+        assert_eq!(
+            quality.to_string().parse::<ResamplerQuality>().unwrap(),
+            quality
+        );
+    }
+
+    assert_eq!(
+        serde_json::from_str::<ResamplerQuality>("\"sinc-16\"").unwrap(),
+        ResamplerQuality::Sinc(16)
+    );
+    assert!("sinc-7".parse::<ResamplerQuality>().is_err());
+    assert!("sinc-1024".parse::<ResamplerQuality>().is_err());
+    assert!("bilinear".parse::<ResamplerQuality>().is_err());
+}
+
+#[test]
+fn test_nearest_repeats_input_samples() {
+    let mut resampler = Resampler::with_quality(8000, 16000, 1, ResamplerQuality::Nearest);
+
+    let output = resampler.process(&[0, 100, 200, 300]);
+
+    assert_eq!(&output[..6], &[0, 100, 100, 200, 200, 300]);
+}
+
+#[test]
+fn test_sinc_passes_constant_signal() {
+    let mut resampler = Resampler::with_quality(44100, 16000, 2, ResamplerQuality::Sinc(32));
+
+    let input: Vec<i16> = (0..4410).flat_map(|_| [1000i16, -1000]).collect();
+    resampler.process(&input);
+    let output = resampler.process(&input);
+
+    assert!(output
+        .chunks_exact(2)
+        .all(|c| (c[0] - 1000).abs() <= 1 && (c[1] + 1000).abs() <= 1));
+}
+
+#[test]
+fn test_sinc_suppresses_aliasing_when_downsampling() {
+    // 10kHz tone is above the 8kHz Nyquist limit of 16kHz output
+    let tone: Vec<i16> = (0..48000)
+        .map(|i| {
+            let t = i as f64 / 48000.0;
+            ((2.0 * std::f64::consts::PI * 10000.0 * t).sin() * 10000.0) as i16
+        })
+        .collect();
+
+    let rms = |quality| {
+        let mut resampler = Resampler::with_quality(48000, 16000, 1, quality);
+        let output = resampler.process(&tone);
+        let tail = &output[1000..];
+        (tail.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / tail.len() as f64).sqrt()
+    };
+
+    let linear = rms(ResamplerQuality::Linear);
+    let sinc = rms(ResamplerQuality::Sinc(64));
+    assert!(
+        sinc < linear / 10.0,
+        "linear {:.0}, sinc {:.0}",
+        linear,
+        sinc
+    );
+}
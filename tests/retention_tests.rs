@@ -0,0 +1,201 @@
+// Tests for meeting expiry and the retention worker
+//
+// Expiry queries run against both storage backends; sweeps use the
+// filesystem backend with audio files on disk.
+
+mod common;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use loqa_meetings::{FilesystemStorage, MeetingRecord, RetentionWorker, SqliteStorage, Storage};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn record(meeting_id: &str, expires_at: Option<DateTime<Utc>>) -> MeetingRecord {
+    let started_at = Utc::now() - Duration::hours(2);
+    MeetingRecord {
+        expires_at,
+        ..common::meeting(
+            meeting_id,
+            started_at,
+            Duration::minutes(30),
+            Vec::new(),
+            Vec::new(),
+        )
+    }
+}
+
+async fn exercise_expiry(storage: &dyn Storage) -> Result<()> {
+    let now = Utc::now();
+    storage
+        .save_meeting(&record("expired", Some(now - Duration::minutes(1))))
+        .await?;
+    storage
+        .save_meeting(&record("later", Some(now + Duration::hours(1))))
+        .await?;
+    storage.save_meeting(&record("kept", None)).await?;
+
+    assert_eq!(storage.expired_meetings(now).await?, vec!["expired"]);
+    let mut expired = storage.expired_meetings(now + Duration::hours(2)).await?;
+    expired.sort();
+    assert_eq!(expired, vec!["expired", "later"]);
+
+    // Expiry is part of the listing metadata
+    let summaries = storage.list_meetings(10, 0).await?;
+    let later = summaries.iter().find(|m| m.meeting_id == "later").unwrap();
+    assert_eq!(
+        later.expires_at.map(|t| t.timestamp_millis()),
+        Some((now + Duration::hours(1)).timestamp_millis())
+    );
+    let kept = summaries.iter().find(|m| m.meeting_id == "kept").unwrap();
+    assert_eq!(kept.expires_at, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_filesystem_expired_meetings() -> Result<()> {
+    let dir = TempDir::new()?;
+    exercise_expiry(&FilesystemStorage::new(dir.path().join("meetings"))).await
+}
+
+#[tokio::test]
+async fn test_sqlite_expired_meetings() -> Result<()> {
+    exercise_expiry(&SqliteStorage::open_in_memory()?).await
+}
+
+#[test]
+fn test_is_expired() {
+    let now = Utc::now();
+    assert!(record("a", Some(now)).is_expired(now));
+    assert!(!record("a", Some(now + Duration::seconds(1))).is_expired(now));
+    assert!(!record("a", None).is_expired(now));
+}
+
+#[tokio::test]
+async fn test_sweep_deletes_meeting_and_audio() -> Result<()> {
+    let dir = TempDir::new()?;
+    let recordings = dir.path().join("recordings");
+    let storage: Arc<dyn Storage> = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+
+    // Audio in the meeting's recordings directory, and a chunk stored elsewhere
+    let audio_dir = recordings.join("expired");
+    std::fs::create_dir_all(&audio_dir)?;
+    std::fs::write(audio_dir.join("chunk-0.wav"), [0u8; 64])?;
+    let outside = dir.path().join("imported-chunk.wav");
+    std::fs::write(&outside, [0u8; 64])?;
+
+    let now = Utc::now();
+    let mut expired = record("expired", Some(now - Duration::minutes(5)));
+    expired.chunks = vec![common::chunk(0, &outside, 0, 1000)];
+    storage.save_meeting(&expired).await?;
+    storage
+        .save_meeting(&record("later", Some(now + Duration::hours(1))))
+        .await?;
+
+    let worker = RetentionWorker::new(Arc::clone(&storage), recordings.clone());
+    let deleted = worker.sweep(now).await?;
+
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].meeting_id, "expired");
+    assert_eq!(deleted[0].expired_at, expired.expires_at.unwrap());
+    assert_eq!(deleted[0].audio_files, 2);
+
+    assert!(storage.get_meeting("expired").await?.is_none());
+    assert!(!audio_dir.exists());
+    assert!(!outside.exists());
+    assert!(storage.get_meeting("later").await?.is_some());
+
+    // Nothing left to delete
+    assert!(worker.sweep(now).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sweep_skips_extended_meetings() -> Result<()> {
+    let dir = TempDir::new()?;
+    let storage: Arc<dyn Storage> = Arc::new(SqliteStorage::open_in_memory()?);
+
+    let now = Utc::now();
+    storage
+        .save_meeting(&record("standup", Some(now - Duration::minutes(1))))
+        .await?;
+
+    // Extended past the sweep time
+    storage
+        .save_meeting(&record("standup", Some(now + Duration::days(1))))
+        .await?;
+
+    let worker = RetentionWorker::new(Arc::clone(&storage), dir.path().to_path_buf());
+    assert!(worker.sweep(now).await?.is_empty());
+    assert!(storage.get_meeting("standup").await?.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_worker_sweeps_by_its_clock() -> Result<()> {
+    use loqa_meetings::clock::ManualClock;
+
+    let dir = TempDir::new()?;
+    let storage: Arc<dyn Storage> = Arc::new(SqliteStorage::open_in_memory()?);
+
+    // Expired by the worker's clock, not yet by the system's
+    let now = Utc::now() + Duration::days(2);
+    storage
+        .save_meeting(&record("standup", Some(now - Duration::days(1))))
+        .await?;
+
+    let worker = RetentionWorker::new(Arc::clone(&storage), dir.path().to_path_buf())
+        .with_clock(ManualClock::new(now));
+    let sweeping = tokio::spawn(worker.run(std::time::Duration::from_millis(10)));
+
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    while storage.get_meeting("standup").await?.is_some() {
+        assert!(tokio::time::Instant::now() < deadline, "Meeting not swept");
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    sweeping.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_extend_endpoint_counts_from_the_clock() -> Result<()> {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use chrono::TimeZone;
+    use loqa_meetings::clock::ManualClock;
+    use loqa_meetings::{create_router, AppState, Config, MeetingsService};
+    use tower::Service;
+
+    let dir = TempDir::new()?;
+    let storage: Arc<dyn Storage> = Arc::new(FilesystemStorage::new(dir.path().to_path_buf()));
+    let now = Utc.with_ymd_and_hms(2020, 1, 1, 9, 0, 0).unwrap();
+    storage
+        .save_meeting(&record("standup", Some(now + Duration::hours(1))))
+        .await?;
+    let service = MeetingsService::with_config(Config::default(), Arc::clone(&storage))
+        .with_clock(ManualClock::new(now));
+    let mut router = create_router(AppState::from(service));
+
+    for (extend_secs, status) in [
+        (3600, StatusCode::OK),
+        (9_000_000_000_000, StatusCode::BAD_REQUEST),
+        (u64::MAX, StatusCode::BAD_REQUEST),
+    ] {
+        std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx)).await?;
+        let response = router
+            .call(
+                Request::post("/meetings/standup/extend")
+                    .header("content-type", "application/json")
+                    .body(Body::from(format!(r#"{{"extend_secs": {}}}"#, extend_secs)))
+                    .unwrap(),
+            )
+            .await?;
+        assert_eq!(response.status(), status, "{}", extend_secs);
+    }
+
+    // Long expired by the system clock, but not by the service's
+    let record = storage.get_meeting("standup").await?.unwrap();
+    assert_eq!(record.expires_at, Some(now + Duration::hours(2)));
+    Ok(())
+}
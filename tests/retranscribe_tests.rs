@@ -0,0 +1,176 @@
+// Tests for re-transcribing ranges of stored meetings
+
+mod common;
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use common::{chunk, segment};
+use loqa_meetings::audio::write_clip;
+use loqa_meetings::nats::{TranscriptMessage, PROTOCOL_VERSION};
+use loqa_meetings::session::{load_range_audio, place_segments, replace_range, TimeRange};
+use loqa_meetings::{ChunkMetadata, MeetingRecord};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn record(chunks: Vec<ChunkMetadata>) -> MeetingRecord {
+    let started_at = Utc::now();
+    let transcript = vec![
+        segment("before", started_at + Duration::seconds(1)),
+        segment("garbled", started_at + Duration::seconds(12)),
+        segment("after", started_at + Duration::seconds(19)),
+    ];
+    common::meeting(
+        "standup",
+        started_at,
+        Duration::seconds(20),
+        transcript,
+        chunks,
+    )
+}
+
+#[test]
+fn test_time_range_from_chunks() -> Result<()> {
+    let record = record(vec![
+        chunk(0, Path::new("a.wav"), 0, 10_000),
+        chunk(1, Path::new("b.wav"), 10_000, 20_000),
+    ]);
+
+    assert_eq!(
+        TimeRange::from_chunks(&record, &[1])?,
+        TimeRange::new(10_000, 20_000)?
+    );
+    assert_eq!(
+        TimeRange::from_chunks(&record, &[1, 0])?,
+        TimeRange::new(0, 20_000)?
+    );
+    assert!(TimeRange::from_chunks(&record, &[7]).is_err());
+    assert!(TimeRange::new(5_000, 5_000).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_load_range_audio_spans_chunks() -> Result<()> {
+    let dir = TempDir::new()?;
+    let first = dir.path().join("chunk_000.wav");
+    let second = dir.path().join("chunk_001.wav");
+    write_clip(&first, &vec![1; 16000], 16000, 1)?;
+    // Other sample rates are resampled to 16kHz
+    write_clip(&second, &vec![2; 8000], 8000, 1)?;
+
+    let record = record(vec![
+        chunk(0, &first, 0, 1_000),
+        chunk(1, &second, 1_000, 2_000),
+    ]);
+    let pcm = load_range_audio(&record, TimeRange::new(500, 1_500)?)?;
+
+    assert!((15_900..=16_000).contains(&pcm.len()), "Got {}", pcm.len());
+    assert_eq!(pcm[0], 1);
+    assert_eq!(pcm[pcm.len() / 2 + 100], 2);
+
+    assert!(load_range_audio(&record, TimeRange::new(5_000, 6_000)?).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_load_range_audio_uses_chunk_track() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("recording.wav");
+    write_clip(&path, &vec![3; 16000], 16000, 1)?;
+
+    let mut only_track = chunk(0, &path, 0, 1_000);
+    only_track.track = Some(0);
+    let pcm = load_range_audio(&record(vec![only_track]), TimeRange::new(0, 1_000)?)?;
+    assert_eq!(pcm.len(), 16000);
+
+    // A track the file doesn't have is an error, not a silent fallback
+    let mut missing_track = chunk(0, &path, 0, 1_000);
+    missing_track.track = Some(1);
+    assert!(load_range_audio(&record(vec![missing_track]), TimeRange::new(0, 1_000)?).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_track_is_optional_in_json() -> Result<()> {
+    let chunk = chunk(0, Path::new("chunk_000.wav"), 0, 1_000);
+    let json = serde_json::to_value(&chunk)?;
+    assert!(json.get("track").is_none(), "track omitted when unset");
+
+    let parsed: ChunkMetadata = serde_json::from_value(json)?;
+    assert_eq!(parsed.track, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_replace_range_swaps_segments() -> Result<()> {
+    let mut record = record(Vec::new());
+    let started_at = record.started_at;
+    let range = TimeRange::new(10_000, 15_000)?;
+
+    let results: Vec<TranscriptMessage> = ["first fix", "second fix"]
+        .iter()
+        .map(|text| TranscriptMessage {
+            version: PROTOCOL_VERSION,
+            session_id: "standup.retranscribe-1".to_string(),
+            text: text.to_string(),
+            partial: false,
+            // Not in the range: spread across it instead
+            timestamp: Utc::now().to_rfc3339(),
+            confidence: Some(0.9),
+            speaker: None,
+            segment_id: None,
+            language: None,
+        })
+        .collect();
+    let segments = place_segments(&results, started_at, range);
+    assert_eq!(segments[0].timestamp, started_at + Duration::seconds(10));
+    assert_eq!(
+        segments[1].timestamp,
+        started_at + Duration::milliseconds(12_500)
+    );
+
+    let removed = replace_range(&mut record.transcript, started_at, range, segments);
+    assert_eq!(removed, 1);
+
+    let texts: Vec<&str> = record.transcript.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(texts, vec!["before", "first fix", "second fix", "after"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upload_without_recording_leaves_no_directory() -> Result<()> {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use loqa_meetings::{create_router, AppState, Config, FilesystemStorage, Storage};
+    use std::sync::Arc;
+    use tower::Service;
+
+    let dir = TempDir::new()?;
+    let recordings = dir.path().join("recordings");
+    let mut config = Config::default();
+    config.audio.recordings_path = recordings.display().to_string();
+    let storage: Arc<dyn Storage> = Arc::new(FilesystemStorage::new(dir.path().join("meetings")));
+    let mut router = create_router(AppState::with_config(config, storage));
+
+    for body in [
+        // No "recording" field
+        "--boundary\r\nContent-Disposition: form-data; name=\"notes\"\r\n\r\nhello\r\n--boundary--\r\n",
+        // Cut off mid-stream
+        "--boundary\r\nContent-Disposition: form-data; name=\"notes\"\r\n\r\nhel",
+    ] {
+        let request = Request::post("/meetings/upload?meeting_id=standup")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(Body::from(body))
+            .unwrap();
+        std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx)).await?;
+        let response = router.call(request).await?;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(!recordings.join("standup").exists());
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+// Tests for sealing meeting content to a client-held key
+
+mod common;
+
+use anyhow::Result;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use crypto_box::aead::OsRng;
+use crypto_box::SecretKey;
+use loqa_meetings::audio::file_sha256;
+use loqa_meetings::storage::{parse_public_key, remove_unsealed, SEALED_ALGORITHM};
+use loqa_meetings::{
+    ChunkMetadata, FilesystemStorage, Marker, MarkerKind, MeetingRecord, Storage, TranscriptSegment,
+};
+use tempfile::TempDir;
+
+fn key_pair() -> (SecretKey, String) {
+    let secret = SecretKey::generate(&mut OsRng);
+    let public = base64::engine::general_purpose::STANDARD.encode(secret.public_key().as_bytes());
+    (secret, public)
+}
+
+fn record() -> MeetingRecord {
+    let started_at = Utc::now();
+    let transcript = vec![TranscriptSegment {
+        confidence: Some(0.9),
+        ..common::segment("The acquisition closes on Friday", started_at)
+    }];
+    MeetingRecord {
+        title: Some("Board meeting".to_string()),
+        markers: vec![Marker {
+            kind: MarkerKind::Chapter,
+            offset_ms: 0,
+            timestamp: started_at,
+            label: "acquisition".to_string(),
+        }],
+        ..common::meeting(
+            "board",
+            started_at,
+            Duration::seconds(60),
+            transcript,
+            Vec::new(),
+        )
+    }
+}
+
+#[test]
+fn test_sealed_content_opens_with_secret_key() -> Result<()> {
+    let (secret, public) = key_pair();
+    let mut record = record();
+
+    record.seal(&public)?;
+
+    assert!(record.transcript.is_empty());
+    assert!(record.markers.is_empty());
+    let sealed = record.sealed.clone().expect("sealed content");
+    assert_eq!(sealed.algorithm, SEALED_ALGORITHM);
+    assert_eq!(sealed.public_key, public);
+    assert!(!sealed.payload.contains("acquisition"));
+
+    let payload = sealed.open(&secret.to_bytes())?;
+    assert_eq!(
+        payload.transcript[0].text,
+        "The acquisition closes on Friday"
+    );
+    assert_eq!(payload.markers[0].label, "acquisition");
+
+    // Only the matching key opens it
+    let (other, _) = key_pair();
+    assert!(sealed.open(&other.to_bytes()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sealing_encrypts_audio_chunks() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("chunk-0.wav");
+    std::fs::write(&path, b"RIFF plaintext audio")?;
+
+    let (secret, public) = key_pair();
+    let mut record = record();
+    record.chunks = vec![ChunkMetadata {
+        chunk_index: 0,
+        file_path: path.clone(),
+        start_ms: 0,
+        end_ms: 1000,
+        sample_rate: 16000,
+        channels: 1,
+        sample_count: 16000,
+        track: None,
+        sha256: None,
+        channel_labels: Vec::new(),
+        overlap_ms: 0,
+    }];
+
+    let unsealed = record.seal(&public)?;
+
+    assert_eq!(unsealed, vec![path.clone()]);
+    assert!(
+        path.exists(),
+        "The plaintext stays until the record is stored"
+    );
+    remove_unsealed(&record.meeting_id, &unsealed);
+    assert!(!path.exists());
+    let sealed_path = &record.chunks[0].file_path;
+    assert_eq!(sealed_path, &dir.path().join("chunk-0.wav.sealed"));
+    let audio = secret
+        .unseal(&std::fs::read(sealed_path)?)
+        .map_err(|_| anyhow::anyhow!("failed to open chunk"))?;
+    assert_eq!(audio, b"RIFF plaintext audio");
+    assert_eq!(
+        record.chunks[0].sha256,
+        Some(file_sha256(sealed_path)?),
+        "The checksum follows the sealed file"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_failed_seal_leaves_meeting_unchanged() -> Result<()> {
+    let dir = TempDir::new()?;
+    let first = dir.path().join("chunk-0.wav");
+    std::fs::write(&first, b"RIFF plaintext audio")?;
+    let missing = dir.path().join("chunk-1.wav");
+
+    let (_, public) = key_pair();
+    let mut record = record();
+    record.chunks = [&first, &missing]
+        .into_iter()
+        .enumerate()
+        .map(|(index, path)| ChunkMetadata {
+            chunk_index: index,
+            file_path: path.clone(),
+            start_ms: index as u64 * 1000,
+            end_ms: (index as u64 + 1) * 1000,
+            sample_rate: 16000,
+            channels: 1,
+            sample_count: 16000,
+            track: None,
+            sha256: None,
+            channel_labels: Vec::new(),
+            overlap_ms: 0,
+        })
+        .collect();
+
+    assert!(record.seal(&public).is_err());
+
+    // Nothing was swapped in, and the chunk sealed first was rolled back
+    assert!(record.sealed.is_none());
+    assert_eq!(record.transcript.len(), 1);
+    assert_eq!(record.chunks[0].file_path, first);
+    assert!(record.chunks[0].sha256.is_none());
+    assert_eq!(std::fs::read(&first)?, b"RIFF plaintext audio");
+    assert!(!dir.path().join("chunk-0.wav.sealed").exists());
+    Ok(())
+}
+
+#[test]
+fn test_cannot_seal_twice() -> Result<()> {
+    let (_, public) = key_pair();
+    let mut record = record();
+    record.seal(&public)?;
+    assert!(record.seal(&public).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_rejects_invalid_public_keys() {
+    assert!(parse_public_key("not base64!").is_err());
+    let short = base64::engine::general_purpose::STANDARD.encode([0u8; 16]);
+    assert!(parse_public_key(&short).is_err());
+    let (_, public) = key_pair();
+    assert!(parse_public_key(&public).is_ok());
+}
+
+#[tokio::test]
+async fn test_sealed_meetings_round_trip_through_storage() -> Result<()> {
+    let dir = TempDir::new()?;
+    let storage = FilesystemStorage::new(dir.path().to_path_buf());
+    let (secret, public) = key_pair();
+    let mut record = record();
+    record.seal(&public)?;
+
+    storage.save_meeting(&record).await?;
+    let stored = std::fs::read_to_string(dir.path().join("board").join("meeting.json"))?;
+    assert!(!stored.contains("acquisition"));
+
+    let loaded = storage.get_meeting("board").await?.expect("stored meeting");
+    let payload = loaded
+        .sealed
+        .expect("sealed content")
+        .open(&secret.to_bytes())?;
+    assert_eq!(payload.transcript.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,122 @@
+// Tests for building recording sessions with RecordingSessionBuilder
+
+use anyhow::Result;
+use loqa_meetings::audio::{AudioStreamSource, StageConfig};
+use loqa_meetings::session::SessionSources;
+use loqa_meetings::stt::{SttConfig, SttProviderKind};
+use loqa_meetings::{MicrophoneConfig, RecordingSession, RecordingSessionBuilder, SessionConfig};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[test]
+fn test_builder_sets_the_config() -> Result<()> {
+    let config = RecordingSession::builder("weekly-sync")
+        .title("Weekly sync")
+        .sources(SessionSources {
+            excluded_apps: vec!["com.spotify.client".to_string()],
+            microphones: vec![MicrophoneConfig {
+                device_id: "usb-mic".to_string(),
+                gain: 0.5,
+            }],
+            dual_stream: true,
+            ..Default::default()
+        })
+        .chunking("/tmp/recordings/weekly-sync", Duration::from_secs(120))
+        .chunk_overlap(Duration::from_secs(2))
+        .nats("nats://nats.local:4222")
+        .stages(vec![StageConfig::Resample])
+        .configure(|config| config.preroll = Duration::ZERO)
+        .build_config()?;
+
+    assert_eq!(config.session_id, "weekly-sync");
+    assert_eq!(config.title.as_deref(), Some("Weekly sync"));
+    assert_eq!(config.excluded_apps, vec!["com.spotify.client"]);
+    assert_eq!(config.microphones[0].gain, 0.5);
+    assert_eq!(config.system_gain, 1.0);
+    assert!(config.dual_stream);
+    assert_eq!(
+        config.audio_dir,
+        Some(PathBuf::from("/tmp/recordings/weekly-sync"))
+    );
+    assert_eq!(config.chunk_duration, Duration::from_secs(120));
+    assert_eq!(config.chunk_overlap, Duration::from_secs(2));
+    assert_eq!(config.nats_url, "nats://nats.local:4222");
+    assert_eq!(config.stt.provider, SttProviderKind::Nats);
+    assert_eq!(config.stages, vec![StageConfig::Resample]);
+    assert_eq!(config.preroll, Duration::ZERO);
+    assert!(config.store_audio && config.transcription);
+    Ok(())
+}
+
+#[test]
+fn test_builder_rejects_invalid_settings() {
+    let invalid: Vec<(RecordingSessionBuilder, &str)> = vec![
+        (RecordingSession::builder("weekly sync"), "contains"),
+        (
+            RecordingSession::builder("short-chunks").chunking("/tmp", Duration::from_millis(500)),
+            "at least a second",
+        ),
+        (
+            RecordingSession::builder("overlap")
+                .chunking("/tmp", Duration::from_secs(2))
+                .chunk_overlap(Duration::from_secs(2)),
+            "shorter than the chunk duration",
+        ),
+        (
+            RecordingSession::builder("gain").sources(SessionSources {
+                system_gain: -1.0,
+                ..Default::default()
+            }),
+            "System gain",
+        ),
+        (
+            RecordingSession::builder("stage-gain").stages(vec![StageConfig::Gain {
+                gains: [(AudioStreamSource::Microphone, f32::NAN)].into(),
+            }]),
+            "Gain stage mic gain",
+        ),
+        (
+            RecordingSession::builder("nothing")
+                .no_audio_persistence()
+                .no_transcription(),
+            "records nothing",
+        ),
+        (RecordingSession::builder("no-nats").nats(" "), "NATS URL"),
+    ];
+
+    for (builder, expected) in invalid {
+        let err = builder.build_config().unwrap_err();
+        assert!(err.to_string().contains(expected), "{:#}", err);
+    }
+}
+
+#[test]
+fn test_builder_from_config_keeps_it() -> Result<()> {
+    let config = RecordingSessionBuilder::from_config(SessionConfig {
+        session_id: "from-config".to_string(),
+        store_audio: false,
+        ..Default::default()
+    })
+    .stt(SttConfig {
+        provider: SttProviderKind::OpenAi,
+        ..Default::default()
+    })
+    .build_config()?;
+
+    assert_eq!(config.session_id, "from-config");
+    assert!(!config.store_audio);
+    assert_eq!(config.stt.provider, SttProviderKind::OpenAi);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_builder_creates_a_record_only_session() -> Result<()> {
+    let session = RecordingSession::builder("record-only")
+        .no_transcription()
+        .build()
+        .await?;
+
+    let stats = session.get_stats().await?;
+    assert!(!stats.is_recording);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// Tests for session start: retrying failed setup and the capture warm-up
+
+use loqa_meetings::session::StartRetryPolicy;
+use loqa_meetings::{Config, SessionConfig};
+use std::time::Duration;
+
+#[test]
+fn test_starts_are_not_retried_by_default() {
+    let policy = SessionConfig::default().start_retry;
+    assert_eq!(policy.max_retries, 0);
+
+    let config = Config::load("config/loqa-meetings").unwrap();
+    assert_eq!(config.sessions.start_retries, 0);
+    assert_eq!(config.sessions.start_retry_backoff_ms, 500);
+}
+
+#[test]
+fn test_retry_delay_doubles_up_to_the_cap() {
+    let policy = StartRetryPolicy {
+        max_retries: 10,
+        backoff: Duration::from_millis(250),
+    };
+
+    assert_eq!(policy.delay(0), Duration::from_millis(250));
+    assert_eq!(policy.delay(1), Duration::from_millis(500));
+    assert_eq!(policy.delay(3), Duration::from_secs(2));
+    assert_eq!(policy.delay(8), StartRetryPolicy::MAX_BACKOFF);
+    // No overflow however many retries are configured
+    assert_eq!(policy.delay(u32::MAX), StartRetryPolicy::MAX_BACKOFF);
+}
+
+#[test]
+fn test_start_retry_policy_in_session_config_json() {
+    let config = SessionConfig {
+        start_retry: StartRetryPolicy {
+            max_retries: 2,
+            backoff: Duration::from_secs(1),
+        },
+        ..Default::default()
+    };
+
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["start_retry"]["max_retries"], 2);
+
+    let parsed: SessionConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.start_retry, config.start_retry);
+}
+
+#[test]
+fn test_capture_warm_up_is_off_by_default() {
+    assert!(SessionConfig::default().warmup.is_zero());
+
+    let config = Config::load("config/loqa-meetings").unwrap();
+    assert_eq!(config.audio.warmup_ms, 0);
+}
+
+#[test]
+fn test_warm_up_in_session_config_json() {
+    let config = SessionConfig {
+        warmup: Duration::from_millis(1500),
+        ..Default::default()
+    };
+
+    let json = serde_json::to_value(&config).unwrap();
+    let parsed: SessionConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.warmup, Duration::from_millis(1500));
+}
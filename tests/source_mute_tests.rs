@@ -0,0 +1,100 @@
+// Tests for muting individual sources mid-meeting
+
+use chrono::{Duration, TimeZone, Utc};
+use loqa_meetings::audio::AudioStreamSource;
+use loqa_meetings::session::{MuteSpan, SourceMutes};
+use loqa_meetings::{MarkerKind, SessionEvent};
+
+#[test]
+fn test_source_names_roundtrip() {
+    for source in [
+        AudioStreamSource::System,
+        AudioStreamSource::Microphone,
+        AudioStreamSource::MicrophoneDevice(2),
+    ] {
+        assert_eq!(
+            source.to_string().parse::<AudioStreamSource>().unwrap(),
+            source
+        );
+    }
+
+    assert_eq!(
+        "Microphone".parse::<AudioStreamSource>().unwrap(),
+        AudioStreamSource::Microphone
+    );
+    assert!("speaker".parse::<AudioStreamSource>().is_err());
+    assert!("mic-x".parse::<AudioStreamSource>().is_err());
+}
+
+#[test]
+fn test_mute_and_unmute_track_spans() {
+    let mut mutes = SourceMutes::new();
+
+    assert!(mutes.mute(AudioStreamSource::Microphone, 723_000));
+    assert!(
+        !mutes.mute(AudioStreamSource::Microphone, 730_000),
+        "Already muted"
+    );
+    assert!(mutes.is_muted(AudioStreamSource::Microphone));
+    assert!(!mutes.is_muted(AudioStreamSource::System));
+
+    let span = mutes
+        .unmute(AudioStreamSource::Microphone, 1_088_000)
+        .expect("Was muted");
+    assert_eq!(span.start_ms, 723_000, "Span starts at the first mute");
+    assert_eq!(span.end_ms, 1_088_000);
+    assert!(mutes
+        .unmute(AudioStreamSource::Microphone, 1_090_000)
+        .is_none());
+    assert!(mutes.muted().is_empty());
+}
+
+#[test]
+fn test_mute_span_marker_label() {
+    let started_at = Utc.with_ymd_and_hms(2025, 6, 3, 9, 0, 0).unwrap();
+    let span = MuteSpan {
+        source: AudioStreamSource::Microphone,
+        start_ms: 723_000,
+        end_ms: 728_000,
+    };
+
+    let marker = span.marker(started_at);
+    assert_eq!(marker.kind, MarkerKind::Muted);
+    assert_eq!(marker.label, "mic muted 12:03–12:08");
+    assert_eq!(marker.offset_ms, 723_000);
+    assert_eq!(marker.timestamp, started_at + Duration::seconds(723));
+
+    let long = MuteSpan {
+        source: AudioStreamSource::MicrophoneDevice(0),
+        start_ms: 3_599_000,
+        end_ms: 3_725_000,
+    };
+    assert_eq!(long.marker(started_at).label, "mic-0 muted 59:59–1:02:05");
+}
+
+#[test]
+fn test_finish_closes_open_mutes_in_order() {
+    let mut mutes = SourceMutes::new();
+    mutes.mute(AudioStreamSource::System, 50_000);
+    mutes.mute(AudioStreamSource::Microphone, 10_000);
+
+    let spans = mutes.finish(60_000);
+    let starts: Vec<u64> = spans.iter().map(|span| span.start_ms).collect();
+    assert_eq!(starts, vec![10_000, 50_000]);
+    assert!(spans.iter().all(|span| span.end_ms == 60_000));
+    assert!(mutes.muted().is_empty());
+}
+
+#[test]
+fn test_source_muted_event_serialization() {
+    let event = SessionEvent::SourceMuted {
+        source: "mic".to_string(),
+        muted: true,
+        timestamp: Utc::now(),
+    };
+
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["type"], "source_muted");
+    assert_eq!(json["source"], "mic");
+    assert_eq!(json["muted"], true);
+}
@@ -0,0 +1,102 @@
+// Tests for standby buffering and retroactive session starts
+
+use anyhow::Result;
+use loqa_meetings::audio::{AudioBackendConfig, DownmixStrategy, PrerollBuffer, StageConfig};
+use loqa_meetings::session::{Standby, StandbyConfig};
+use loqa_meetings::storage::FilesystemStorage;
+use loqa_meetings::{AppState, Config, RecordingSession, SessionConfig};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn record_only() -> SessionConfig {
+    SessionConfig {
+        transcription: false,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_standby_config_defaults() {
+    let config: StandbyConfig = serde_json::from_str("{}").unwrap();
+    assert!(!config.enabled);
+    assert_eq!(config.minutes, 2);
+    assert_eq!(config.buffer_duration(), Duration::from_secs(120));
+}
+
+#[tokio::test]
+async fn test_backfill_moves_the_start_back() -> Result<()> {
+    let mut audio = PrerollBuffer::new(Duration::from_secs(60), 16000, 1);
+    audio.push(&vec![100; 16000 * 45]);
+
+    let session = RecordingSession::new(record_only())
+        .await?
+        .with_backfill(audio);
+    let created = chrono::Utc::now();
+    assert_eq!(session.backfilled(), Duration::from_secs(45));
+
+    let stats = session.get_stats().await?;
+    assert!(stats.started_at <= created - chrono::Duration::seconds(45));
+    assert!(stats.duration_secs >= 45.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_backfill_in_another_format_is_ignored() -> Result<()> {
+    let mut audio = PrerollBuffer::new(Duration::from_secs(60), 48000, 2);
+    audio.push(&vec![100; 48000 * 2 * 10]);
+
+    let session = RecordingSession::new(record_only())
+        .await?
+        .with_backfill(audio);
+    assert_eq!(session.backfilled(), Duration::ZERO);
+    assert!(session.get_stats().await?.duration_secs < 1.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_paused_standby_without_audio_has_nothing_to_prepend() {
+    let standby = Standby::new(
+        &StandbyConfig {
+            enabled: true,
+            minutes: 1,
+        },
+        AudioBackendConfig::default(),
+        DownmixStrategy::default(),
+        StageConfig::conversion(),
+    );
+    assert!(!standby.is_running().await);
+    assert!(standby.pause().await.is_none());
+    assert_eq!(standby.buffered(), Duration::ZERO);
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tokio::test]
+async fn test_standby_without_system_capture_stays_stopped() {
+    let standby = Standby::new(
+        &StandbyConfig::default(),
+        AudioBackendConfig::default(),
+        DownmixStrategy::default(),
+        StageConfig::conversion(),
+    );
+    assert!(standby.resume().await.is_err());
+    assert!(!standby.is_running().await);
+}
+
+#[test]
+fn test_app_state_has_standby_only_when_enabled() {
+    let dir = TempDir::new().unwrap();
+    let storage = Arc::new(FilesystemStorage::new(dir.path().to_path_buf()));
+    assert!(AppState::with_config(Config::default(), storage.clone())
+        .standby
+        .is_none());
+
+    let config = Config {
+        standby: StandbyConfig {
+            enabled: true,
+            minutes: 5,
+        },
+        ..Config::default()
+    };
+    assert!(AppState::with_config(config, storage).standby.is_some());
+}
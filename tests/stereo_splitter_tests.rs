@@ -0,0 +1,105 @@
+// Tests for splitting ScreenCaptureKit stereo into per-source frames
+//
+// These tests verify that system capture is separated into tagged mono
+// frames that the mixer can weigh independently, and that frames the
+// splitter does not understand pass through untouched.
+
+use loqa_meetings::audio::{
+    AudioFrame, AudioStreamSource, ChannelMap, ChannelRole, Mixer, MixerConfig, MixerInput,
+    StereoSplitter,
+};
+
+fn stereo(samples: Vec<i16>, source: AudioStreamSource) -> AudioFrame {
+    AudioFrame {
+        samples,
+        sample_rate: 16000,
+        channels: 2,
+        timestamp_ms: 0,
+        source,
+    }
+}
+
+#[test]
+fn test_system_capture_splits_into_tagged_mono_frames() {
+    let mut splitter = StereoSplitter::new(ChannelMap::default());
+    let frames = splitter.process(stereo(vec![10, 20, 11, 21], AudioStreamSource::System));
+
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].source, AudioStreamSource::System);
+    assert_eq!(frames[0].samples, vec![10, 11]);
+    assert_eq!(frames[1].source, AudioStreamSource::Microphone);
+    assert_eq!(frames[1].samples, vec![20, 21]);
+    assert!(frames
+        .iter()
+        .all(|f| f.channels == 1 && f.sample_rate == 16000));
+}
+
+#[test]
+fn test_other_frames_pass_through() {
+    let mut splitter = StereoSplitter::new(ChannelMap::default());
+
+    // Additional microphones are not part of the system capture layout
+    let device = stereo(vec![1, 2], AudioStreamSource::MicrophoneDevice(0));
+    let frames = splitter.process(device);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].channels, 2);
+
+    let mono = AudioFrame {
+        channels: 1,
+        ..stereo(vec![1, 2], AudioStreamSource::System)
+    };
+    assert_eq!(splitter.process(mono)[0].samples, vec![1, 2]);
+
+    // A layout the map does not describe is left for the downmix
+    let quad = AudioFrame {
+        channels: 4,
+        ..stereo(vec![1, 2, 3, 4], AudioStreamSource::System)
+    };
+    let frames = splitter.process(quad);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].channels, 4);
+}
+
+#[test]
+fn test_sources_follow_the_channel_map() {
+    let splitter = StereoSplitter::new(ChannelMap::default());
+    assert_eq!(
+        splitter.sources(),
+        vec![AudioStreamSource::System, AudioStreamSource::Microphone]
+    );
+
+    let no_mic = StereoSplitter::new(ChannelMap::new(vec![
+        ChannelRole::System,
+        ChannelRole::Unused,
+    ]));
+    assert_eq!(no_mic.sources(), vec![AudioStreamSource::System]);
+}
+
+#[test]
+fn test_split_sources_mix_with_their_own_gains() {
+    let mut splitter = StereoSplitter::new(ChannelMap::default());
+    let mut mixer = Mixer::new(MixerConfig::new(
+        16000,
+        1,
+        vec![
+            MixerInput {
+                source: AudioStreamSource::System,
+                gain: 0.5,
+            },
+            MixerInput {
+                source: AudioStreamSource::Microphone,
+                gain: 2.0,
+            },
+        ],
+    ));
+
+    let samples: Vec<i16> = [1000, 100].repeat(1600);
+    for frame in splitter.process(stereo(samples, AudioStreamSource::System)) {
+        mixer.push(frame);
+    }
+
+    let mixed = mixer.pop_ready();
+    assert_eq!(mixed.len(), 1);
+    assert!(mixed[0].samples.iter().all(|&s| s == 700));
+    assert_eq!(mixer.drop_stats().dropped(), 0);
+}
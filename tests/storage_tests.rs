@@ -0,0 +1,168 @@
+// Tests for meeting storage backends
+//
+// The same scenarios run against the filesystem and SQLite backends to make
+// sure they are interchangeable behind the Storage trait.
+
+mod common;
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use loqa_meetings::{FilesystemStorage, MeetingRecord, SqliteStorage, Storage, TranscriptSegment};
+use tempfile::TempDir;
+
+fn record(meeting_id: &str, minutes_ago: i64) -> MeetingRecord {
+    let started_at = Utc::now() - Duration::minutes(minutes_ago);
+    let transcript = vec![TranscriptSegment {
+        confidence: Some(0.9),
+        ..common::segment("Let's get started", started_at)
+    }];
+    MeetingRecord {
+        title: Some(format!("Meeting {}", meeting_id)),
+        ..common::meeting(
+            meeting_id,
+            started_at,
+            Duration::minutes(5),
+            transcript,
+            Vec::new(),
+        )
+    }
+}
+
+async fn exercise_storage(storage: &dyn Storage) -> Result<()> {
+    assert!(storage.list_meetings(50, 0).await?.is_empty());
+    assert!(storage.get_meeting("missing").await?.is_none());
+
+    storage.save_meeting(&record("older", 60)).await?;
+    storage.save_meeting(&record("newer", 10)).await?;
+
+    let loaded = storage.get_meeting("older").await?.expect("saved meeting");
+    assert_eq!(loaded.title.as_deref(), Some("Meeting older"));
+    assert_eq!(loaded.transcript[0].text, "Let's get started");
+
+    // Newest first
+    let ids: Vec<String> = storage
+        .list_meetings(50, 0)
+        .await?
+        .into_iter()
+        .map(|m| m.meeting_id)
+        .collect();
+    assert_eq!(ids, vec!["newer", "older"]);
+
+    // Saving again replaces
+    let mut updated = record("older", 60);
+    updated.title = Some("Renamed".to_string());
+    storage.save_meeting(&updated).await?;
+    assert_eq!(storage.list_meetings(50, 0).await?.len(), 2);
+    assert_eq!(
+        storage
+            .get_meeting("older")
+            .await?
+            .unwrap()
+            .title
+            .as_deref(),
+        Some("Renamed")
+    );
+
+    // Paging
+    let page = storage.list_meetings(1, 1).await?;
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].meeting_id, "older");
+
+    // Search matches all words, case-insensitively
+    let hits = storage.search_transcripts("GET started", 10).await?;
+    assert_eq!(hits.len(), 2);
+    assert!(storage
+        .search_transcripts("started nowhere", 10)
+        .await?
+        .is_empty());
+
+    assert!(storage.delete_meeting("older").await?);
+    assert!(!storage.delete_meeting("older").await?);
+    assert!(storage.get_meeting("older").await?.is_none());
+    assert_eq!(storage.search_transcripts("started", 10).await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_filesystem_storage() -> Result<()> {
+    let dir = TempDir::new()?;
+    exercise_storage(&FilesystemStorage::new(dir.path().join("meetings"))).await
+}
+
+#[tokio::test]
+async fn test_sqlite_storage() -> Result<()> {
+    let dir = TempDir::new()?;
+    exercise_storage(&SqliteStorage::open(dir.path().join("meetings.db"))?).await
+}
+
+#[tokio::test]
+async fn test_sqlite_reopen_keeps_schema_and_data() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("meetings.db");
+
+    {
+        let storage = SqliteStorage::open(&path)?;
+        storage.save_meeting(&record("standup", 5)).await?;
+    }
+
+    // Reopening must not re-run migrations or lose the index
+    let storage = SqliteStorage::open(&path)?;
+    assert!(storage.schema_version().await? >= 2);
+    assert_eq!(storage.search_transcripts("started", 10).await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sqlite_search_treats_query_literally() -> Result<()> {
+    let storage = SqliteStorage::open_in_memory()?;
+    storage.save_meeting(&record("standup", 5)).await?;
+
+    // FTS5 operators and quotes must not cause syntax errors
+    assert!(storage
+        .search_transcripts("\"started AND OR", 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rejects_path_traversal_ids() -> Result<()> {
+    let dir = TempDir::new()?;
+    let storage = FilesystemStorage::new(dir.path().to_path_buf());
+
+    assert!(storage.save_meeting(&record("../escape", 0)).await.is_err());
+    assert!(storage.get_meeting("..").await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_segment_edits_are_recorded_and_indexed() -> Result<()> {
+    let storage = SqliteStorage::open_in_memory()?;
+    let mut meeting = record("standup", 5);
+
+    let edit = meeting
+        .edit_segment(0, "  Let's get going ", Some("alice".to_string()))?
+        .clone();
+    assert_eq!(edit.original, "Let's get started");
+    assert_eq!(edit.edited, "Let's get going");
+    assert_eq!(edit.editor.as_deref(), Some("alice"));
+    assert_eq!(meeting.transcript[0].text, "Let's get going");
+
+    assert!(meeting.edit_segment(9, "out of range", None).is_err());
+    assert!(meeting.edit_segment(0, "   ", None).is_err());
+    assert_eq!(meeting.edits.len(), 1);
+
+    // History survives storage, and search sees the corrected text
+    storage.save_meeting(&meeting).await?;
+    let loaded = storage.get_meeting("standup").await?.unwrap();
+    assert_eq!(loaded.edits.len(), 1);
+    assert_eq!(loaded.edits[0].original, "Let's get started");
+    assert_eq!(storage.search_transcripts("going", 10).await?.len(), 1);
+    assert!(storage.search_transcripts("started", 10).await?.is_empty());
+
+    Ok(())
+}
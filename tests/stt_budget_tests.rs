@@ -0,0 +1,194 @@
+// Tests for the cloud STT budget: usage tracking, falling back to
+// record-only, and /metrics
+
+use anyhow::Result;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use base64::Engine;
+use chrono::{TimeZone, Utc};
+use loqa_meetings::nats::{AudioCodec, AudioFrameMessage, TranscriptStreamConfig};
+use loqa_meetings::storage::FilesystemStorage;
+use loqa_meetings::stt::{
+    BudgetPeriod, CloudProvider, CloudTranscript, SttBudget, SttBudgetConfig, SttProvider,
+    Transcriber,
+};
+use loqa_meetings::{create_router, AppState, Config, SessionWarning};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tower::Service;
+
+fn budget(daily_minutes: f64, monthly_minutes: f64) -> SttBudget {
+    SttBudget::new(SttBudgetConfig {
+        daily_minutes: Some(daily_minutes),
+        monthly_minutes: Some(monthly_minutes),
+        usage_path: None,
+    })
+}
+
+#[test]
+fn test_usage_counts_against_the_daily_budget() {
+    let budget = budget(10.0, 100.0);
+    let morning = Utc.with_ymd_and_hms(2025, 6, 3, 9, 0, 0).unwrap();
+
+    budget.record_at(Duration::from_secs(6 * 60), morning);
+    let status = budget.status_at(morning);
+    assert!((status.day_minutes - 6.0).abs() < 1e-9);
+    assert_eq!(status.exhausted, None);
+
+    budget.record_at(Duration::from_secs(4 * 60), morning);
+    assert_eq!(budget.status_at(morning).exhausted, Some(BudgetPeriod::Day));
+
+    // The next day starts over, but the month keeps counting
+    let tomorrow = morning + chrono::Duration::days(1);
+    let status = budget.status_at(tomorrow);
+    assert_eq!(status.day_minutes, 0.0);
+    assert!((status.month_minutes - 10.0).abs() < 1e-9);
+    assert_eq!(status.exhausted, None);
+}
+
+#[test]
+fn test_monthly_budget_takes_precedence_and_resets_next_month() {
+    let budget = budget(60.0, 30.0);
+    let june = Utc.with_ymd_and_hms(2025, 6, 30, 23, 0, 0).unwrap();
+
+    budget.record_at(Duration::from_secs(30 * 60), june);
+    assert_eq!(budget.status_at(june).exhausted, Some(BudgetPeriod::Month));
+
+    let july = Utc.with_ymd_and_hms(2025, 7, 1, 0, 30, 0).unwrap();
+    let status = budget.status_at(july);
+    assert_eq!(status.month_minutes, 0.0);
+    assert_eq!(status.exhausted, None);
+}
+
+#[test]
+fn test_unlimited_budget_is_never_exhausted() {
+    let budget = SttBudget::new(SttBudgetConfig::default());
+    budget.record(Duration::from_secs(24 * 3600));
+    assert_eq!(budget.exhausted(), None);
+}
+
+#[test]
+fn test_usage_survives_restarts() {
+    let dir = TempDir::new().unwrap();
+    let config = SttBudgetConfig {
+        daily_minutes: Some(5.0),
+        monthly_minutes: None,
+        usage_path: Some(dir.path().join("usage.json").display().to_string()),
+    };
+
+    SttBudget::new(config.clone()).record(Duration::from_secs(5 * 60));
+
+    let resumed = SttBudget::new(config);
+    assert!((resumed.status().day_minutes - 5.0).abs() < 1e-9);
+    assert_eq!(resumed.exhausted(), Some(BudgetPeriod::Day));
+}
+
+#[derive(Default)]
+struct CountingTranscriber {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl Transcriber for CountingTranscriber {
+    async fn transcribe(&self, _wav: Vec<u8>) -> Result<CloudTranscript> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(CloudTranscript {
+            text: "hello".to_string(),
+            confidence: None,
+            language: None,
+        })
+    }
+}
+
+/// One second of 16 kHz mono audio that ends an utterance
+fn utterance() -> AudioFrameMessage {
+    let pcm: Vec<u8> = vec![0u8; 32_000];
+    AudioFrameMessage {
+        version: 2,
+        session_id: "standup".to_string(),
+        sequence: 0,
+        pcm: base64::engine::general_purpose::STANDARD.encode(pcm),
+        sample_rate: 16000,
+        channels: 1,
+        timestamp: Utc::now().to_rfc3339(),
+        final_frame: false,
+        segment_final: true,
+        model: None,
+        codec: AudioCodec::Pcm16,
+        packets: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_cloud_provider_charges_and_respects_the_budget() -> Result<()> {
+    let budget = Arc::new(SttBudget::new(SttBudgetConfig {
+        daily_minutes: Some(1.0 / 60.0),
+        ..Default::default()
+    }));
+    let transcriber = CountingTranscriber::default();
+    let calls = Arc::clone(&transcriber.calls);
+    let provider = CloudProvider::with_budget(
+        "test",
+        transcriber,
+        Duration::from_secs(15),
+        Some(Arc::clone(&budget)),
+    );
+    let mut transcripts = provider
+        .subscribe(&TranscriptStreamConfig::default())
+        .await?;
+
+    provider.send_audio(&utterance()).await?;
+    transcripts.next().await.unwrap().transcript?;
+    assert!((budget.status().day_minutes * 60.0 - 1.0).abs() < 1e-9);
+    assert_eq!(budget.exhausted(), Some(BudgetPeriod::Day));
+
+    // Spent: nothing more is uploaded
+    provider.send_audio(&utterance()).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn test_budget_warning_serialization() {
+    let warning = SessionWarning::SttBudgetExhausted {
+        period: BudgetPeriod::Month,
+        raised_at: Utc::now(),
+    };
+
+    let json = serde_json::to_value(&warning).unwrap();
+    assert_eq!(json["kind"], "stt_budget_exhausted");
+    assert_eq!(json["period"], "month");
+}
+
+#[tokio::test]
+async fn test_metrics_report_usage_and_limits() {
+    let dir = TempDir::new().unwrap();
+    let storage = Arc::new(FilesystemStorage::new(dir.path().to_path_buf()));
+    let mut config = Config::default();
+    config.stt.budget.daily_minutes = Some(30.0);
+    let state = AppState::with_config(config, storage);
+    state.stt_budget.record(Duration::from_secs(90));
+    let mut router = create_router(state);
+
+    std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx))
+        .await
+        .unwrap();
+    let response = router
+        .call(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    assert!(text.contains("loqa_meetings_active_sessions 0\n"));
+    assert!(text.contains("loqa_meetings_stt_minutes{period=\"day\"} 1.5\n"));
+    assert!(text.contains("loqa_meetings_stt_budget_minutes{period=\"day\"} 30\n"));
+    assert!(!text.contains("loqa_meetings_stt_budget_minutes{period=\"month\"}"));
+    assert!(text.contains("loqa_meetings_stt_budget_exhausted 0\n"));
+}
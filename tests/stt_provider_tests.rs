@@ -0,0 +1,183 @@
+// Tests for STT providers: selection, segmenting for cloud APIs, and
+// transcripts pushed by a provider
+
+use anyhow::Result;
+use base64::Engine;
+use loqa_meetings::nats::{AudioCodec, AudioFrameMessage, TranscriptStreamConfig};
+use loqa_meetings::stt::{
+    CloudProvider, CloudTranscript, SegmentBuffer, SttConfig, SttProvider, SttProviderKind,
+    Transcriber,
+};
+use loqa_meetings::Config;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A 100 ms, 16 kHz mono frame
+fn frame(session_id: &str, sequence: u32) -> AudioFrameMessage {
+    let pcm: Vec<u8> = vec![100i16; 1600]
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect();
+    AudioFrameMessage {
+        version: 2,
+        session_id: session_id.to_string(),
+        sequence,
+        pcm: base64::engine::general_purpose::STANDARD.encode(pcm),
+        sample_rate: 16000,
+        channels: 1,
+        timestamp: "2025-06-03T09:00:00Z".to_string(),
+        final_frame: false,
+        segment_final: false,
+        model: None,
+        codec: AudioCodec::Pcm16,
+        packets: Vec::new(),
+    }
+}
+
+/// Numbers the segments it receives
+#[derive(Default)]
+struct CountingTranscriber {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl Transcriber for CountingTranscriber {
+    async fn transcribe(&self, wav: Vec<u8>) -> Result<CloudTranscript> {
+        let reader = hound::WavReader::new(std::io::Cursor::new(wav))?;
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(CloudTranscript {
+            text: format!("segment {} ({} samples)", call, reader.len()),
+            confidence: Some(0.9),
+            language: None,
+        })
+    }
+}
+
+#[test]
+fn test_nats_is_the_default_provider() {
+    assert_eq!(SttConfig::default().provider, SttProviderKind::Nats);
+
+    let config = Config::load("config/loqa-meetings").unwrap();
+    assert_eq!(config.stt.provider, SttProviderKind::Nats);
+    assert_eq!(config.stt.segment_secs, 15);
+    assert_eq!(config.stt.openai.model.as_deref(), Some("whisper-1"));
+
+    let cloud = config.profile(Some("cloud")).expect("cloud profile exists");
+    assert_eq!(cloud.stt, Some(SttProviderKind::OpenAi));
+    assert_eq!(config.profile(None).unwrap().stt, None);
+}
+
+#[test]
+fn test_provider_names() {
+    let kind: SttProviderKind = serde_json::from_str("\"deepgram\"").unwrap();
+    assert_eq!(kind, SttProviderKind::Deepgram);
+    assert_eq!(SttProviderKind::OpenAi.to_string(), "openai");
+    assert!(serde_json::from_str::<SttProviderKind>("\"whisper\"").is_err());
+}
+
+#[cfg(not(feature = "deepgram"))]
+#[test]
+fn test_provider_without_its_feature_is_an_error() {
+    let config = SttConfig {
+        provider: SttProviderKind::Deepgram,
+        ..Default::default()
+    };
+
+    let error = loqa_meetings::stt::cloud_provider(&config, None)
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("--features deepgram"));
+}
+
+#[test]
+fn test_segments_end_at_utterances_and_the_final_frame() -> Result<()> {
+    let mut buffer = SegmentBuffer::new(Duration::from_secs(15));
+
+    assert!(buffer.push(&frame("standup", 0))?.is_empty());
+    let segments = buffer.push(&AudioFrameMessage {
+        segment_final: true,
+        ..frame("standup", 1)
+    })?;
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].index, 0);
+    assert_eq!(segments[0].duration, Duration::from_millis(200));
+    assert_eq!(segments[0].session_id, "standup");
+
+    let reader = hound::WavReader::new(std::io::Cursor::new(segments[0].wav.clone()))?;
+    assert_eq!(reader.spec().sample_rate, 16000);
+    assert_eq!(reader.len(), 3200);
+
+    buffer.push(&frame("standup", 2))?;
+    let segments = buffer.push(&AudioFrameMessage {
+        pcm: String::new(),
+        final_frame: true,
+        ..frame("standup", 3)
+    })?;
+    assert_eq!(segments.len(), 1, "The empty final frame ends the segment");
+    assert_eq!(segments[0].index, 1);
+    Ok(())
+}
+
+#[test]
+fn test_segments_are_capped_at_the_maximum_length() -> Result<()> {
+    let mut buffer = SegmentBuffer::new(Duration::from_millis(300));
+
+    let mut ended = Vec::new();
+    for sequence in 0..7 {
+        ended.extend(buffer.push(&frame("standup", sequence))?);
+    }
+
+    assert_eq!(ended.len(), 2);
+    assert!(ended
+        .iter()
+        .all(|segment| segment.duration == Duration::from_millis(300)));
+    assert_eq!(buffer.duration(), Duration::from_millis(100));
+    Ok(())
+}
+
+#[test]
+fn test_opus_frames_are_rejected() {
+    let mut buffer = SegmentBuffer::new(Duration::from_secs(15));
+    let opus = AudioFrameMessage {
+        codec: AudioCodec::Opus,
+        ..frame("standup", 0)
+    };
+    assert!(buffer.push(&opus).is_err());
+}
+
+#[tokio::test]
+async fn test_cloud_provider_delivers_transcripts_in_order() -> Result<()> {
+    let transcriber = CountingTranscriber::default();
+    let calls = Arc::clone(&transcriber.calls);
+    let provider = CloudProvider::new("test", transcriber, Duration::from_secs(15));
+    let mut transcripts = provider
+        .subscribe(&TranscriptStreamConfig::default())
+        .await?;
+
+    for (sequence, session_id) in ["standup", "standup-mic"].iter().enumerate() {
+        provider
+            .send_audio(&AudioFrameMessage {
+                segment_final: true,
+                ..frame(session_id, sequence as u32)
+            })
+            .await?;
+    }
+
+    let first = transcripts.next().await.unwrap().transcript?;
+    assert_eq!(first.session_id, "standup");
+    assert_eq!(first.text, "segment 0 (1600 samples)");
+    assert_eq!(first.segment_id.as_deref(), Some("test-0"));
+    assert_eq!(first.confidence, Some(0.9));
+    assert!(!first.partial);
+
+    let second = transcripts.next().await.unwrap().transcript?;
+    assert_eq!(second.session_id, "standup-mic");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // A restarted transcript task resubscribes to the same transcripts
+    let _again = provider
+        .subscribe(&TranscriptStreamConfig::default())
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,204 @@
+// Tests for session task supervision
+//
+// These tests verify that failed session tasks are restarted per the
+// restart policy, that a task out of restarts fails the session with a
+// reported event, and that tasks ending during shutdown are left alone
+// without waiting out a restart backoff.
+
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use loqa_meetings::session::{
+    RestartPolicy, SessionEvent, SessionState, SessionTask, Supervisor, TaskFactory,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tokio_util::sync::CancellationToken;
+
+fn policy(max_restarts: u32) -> RestartPolicy {
+    RestartPolicy {
+        max_restarts,
+        backoff: Duration::from_millis(10),
+    }
+}
+
+fn failing_factory(runs: Arc<AtomicUsize>) -> TaskFactory {
+    Arc::new(move || {
+        let runs = Arc::clone(&runs);
+        async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow!("stream closed"))
+        }
+        .boxed()
+    })
+}
+
+fn task_failures(events: &mut broadcast::Receiver<SessionEvent>) -> Vec<(String, u32, bool)> {
+    let mut failures = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let SessionEvent::TaskFailed {
+            error,
+            restarts,
+            restarting,
+            ..
+        } = event
+        {
+            failures.push((error, restarts, restarting));
+        }
+    }
+    failures
+}
+
+#[tokio::test]
+async fn test_failed_task_restarts_until_policy_is_exhausted() {
+    let shutdown = CancellationToken::new();
+    let (state_tx, state_rx) = watch::channel(SessionState::Recording);
+    let (events_tx, mut events_rx) = broadcast::channel(16);
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let factory = failing_factory(Arc::clone(&runs));
+    let mut supervisor = Supervisor::new(policy(2), shutdown, state_tx, events_tx);
+    supervisor.spawn(SessionTask::Audio, factory(), Arc::clone(&factory));
+    supervisor.run().await;
+
+    // The first run plus two restarts
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+    assert_eq!(
+        *state_rx.borrow(),
+        SessionState::Failed {
+            task: SessionTask::Audio,
+            error: "stream closed".to_string(),
+        }
+    );
+
+    let failures = task_failures(&mut events_rx);
+    assert_eq!(
+        failures,
+        vec![
+            ("stream closed".to_string(), 1, true),
+            ("stream closed".to_string(), 2, true),
+            ("stream closed".to_string(), 2, false),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_panicking_task_is_reported() {
+    let shutdown = CancellationToken::new();
+    let (state_tx, state_rx) = watch::channel(SessionState::Recording);
+    let (events_tx, _) = broadcast::channel(16);
+
+    let factory: TaskFactory = Arc::new(|| async { panic!("decoder blew up") }.boxed());
+    let mut supervisor = Supervisor::new(policy(0), shutdown, state_tx, events_tx);
+    supervisor.spawn(SessionTask::Transcript, factory(), Arc::clone(&factory));
+    supervisor.run().await;
+
+    let state = state_rx.borrow().clone();
+    match state {
+        SessionState::Failed { task, error } => {
+            assert_eq!(task, SessionTask::Transcript);
+            assert!(error.contains("decoder blew up"), "{}", error);
+        }
+        state => panic!("expected a failed session, got {:?}", state),
+    }
+}
+
+#[tokio::test]
+async fn test_tasks_ending_after_stop_are_not_restarted() {
+    let shutdown = CancellationToken::new();
+    shutdown.cancel();
+    let (state_tx, state_rx) = watch::channel(SessionState::Recording);
+    let (events_tx, mut events_rx) = broadcast::channel(16);
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let factory = failing_factory(Arc::clone(&runs));
+    let mut supervisor = Supervisor::new(policy(3), shutdown, state_tx, events_tx);
+    supervisor.spawn(SessionTask::Audio, factory(), Arc::clone(&factory));
+    supervisor.run().await;
+
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(*state_rx.borrow(), SessionState::Recording);
+    assert!(task_failures(&mut events_rx).is_empty());
+}
+
+#[tokio::test]
+async fn test_restarted_task_can_recover() {
+    let shutdown = CancellationToken::new();
+    let (state_tx, state_rx) = watch::channel(SessionState::Recording);
+    let (events_tx, mut events_rx) = broadcast::channel(16);
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    // Fails once, then runs until recording stops
+    let factory: TaskFactory = {
+        let runs = Arc::clone(&runs);
+        let shutdown = shutdown.clone();
+        Arc::new(move || {
+            let runs = Arc::clone(&runs);
+            let shutdown = shutdown.clone();
+            async move {
+                if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(anyhow!("transient"));
+                }
+                shutdown.cancel();
+                Ok(())
+            }
+            .boxed()
+        })
+    };
+    let mut supervisor = Supervisor::new(policy(3), shutdown.clone(), state_tx, events_tx);
+    supervisor.spawn(SessionTask::Transcript, factory(), Arc::clone(&factory));
+    supervisor.run().await;
+
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(*state_rx.borrow(), SessionState::Recording);
+    assert_eq!(
+        task_failures(&mut events_rx),
+        vec![("transient".to_string(), 1, true)]
+    );
+}
+
+#[tokio::test]
+async fn test_stop_during_backoff_skips_the_restart() {
+    let shutdown = CancellationToken::new();
+    let (state_tx, state_rx) = watch::channel(SessionState::Recording);
+    let (events_tx, _) = broadcast::channel(16);
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let factory = failing_factory(Arc::clone(&runs));
+    let policy = RestartPolicy {
+        max_restarts: 3,
+        backoff: Duration::from_secs(60),
+    };
+    let mut supervisor = Supervisor::new(policy, shutdown.clone(), state_tx, events_tx);
+    supervisor.spawn(SessionTask::Audio, factory(), Arc::clone(&factory));
+    let supervisor = tokio::spawn(supervisor.run());
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    shutdown.cancel();
+
+    // Returns as soon as shutdown is signalled rather than after the backoff
+    tokio::time::timeout(Duration::from_secs(1), supervisor)
+        .await
+        .expect("supervisor did not stop promptly")
+        .unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(*state_rx.borrow(), SessionState::Recording);
+}
+
+#[test]
+fn test_session_state_serializes_with_status_tag() -> Result<()> {
+    let failed = SessionState::Failed {
+        task: SessionTask::Audio,
+        error: "capture ended".to_string(),
+    };
+    assert_eq!(
+        serde_json::to_value(&failed)?,
+        serde_json::json!({"status": "failed", "task": "audio", "error": "capture ended"})
+    );
+    assert_eq!(
+        serde_json::to_value(SessionState::Recording)?,
+        serde_json::json!({"status": "recording"})
+    );
+    Ok(())
+}
@@ -0,0 +1,104 @@
+// Tests for dropping likely hallucinated transcript segments
+
+mod common;
+
+use chrono::Utc;
+use loqa_meetings::session::{TranscriptFilter, TranscriptFilterConfig};
+use loqa_meetings::{Config, TranscriptSegment};
+use std::time::Duration;
+
+fn segment(text: &str, confidence: Option<f32>) -> TranscriptSegment {
+    TranscriptSegment {
+        confidence,
+        ..common::segment(text, Utc::now())
+    }
+}
+
+fn filter(config: TranscriptFilterConfig) -> TranscriptFilter {
+    TranscriptFilter::from_config(&TranscriptFilterConfig {
+        enabled: true,
+        ..config
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_filter_is_off_by_default() {
+    assert!(TranscriptFilter::from_config(&TranscriptFilterConfig::default()).is_none());
+
+    let config = Config::load("config/loqa-meetings").unwrap();
+    assert!(!config.transcript_filter.enabled);
+    assert!(!config.transcript_filter.blocklist.is_empty());
+}
+
+#[test]
+fn test_blocklisted_phrases_are_dropped_whole() {
+    let filter = filter(TranscriptFilterConfig::default());
+
+    assert_eq!(
+        filter.rejects(&segment("Thanks for watching!", None), None),
+        Some("blocklisted phrase")
+    );
+    assert!(filter
+        .rejects(&segment("  thanks FOR watching ", None), None)
+        .is_some());
+    // Only segments that are nothing but the phrase
+    assert!(filter
+        .rejects(
+            &segment("Thanks for watching the demo, everyone.", None),
+            None
+        )
+        .is_none());
+}
+
+#[test]
+fn test_confidence_and_word_count() {
+    let filter = filter(TranscriptFilterConfig {
+        min_confidence: 0.5,
+        min_words: 2,
+        ..Default::default()
+    });
+
+    assert_eq!(
+        filter.rejects(&segment("We should ship it", Some(0.3)), None),
+        Some("low confidence")
+    );
+    assert!(filter
+        .rejects(&segment("We should ship it", None), None)
+        .is_none());
+    assert_eq!(
+        filter.rejects(&segment("Okay.", Some(0.9)), None),
+        Some("too few words")
+    );
+
+    // Partials are superseded by the final segment, so they are kept
+    let partial = TranscriptSegment {
+        partial: true,
+        ..segment("Okay.", Some(0.1))
+    };
+    assert!(filter.rejects(&partial, None).is_none());
+}
+
+#[test]
+fn test_segments_over_silence_are_dropped() {
+    let filter = filter(TranscriptFilterConfig {
+        min_speech_ms: 300,
+        ..Default::default()
+    });
+    assert!(filter.checks_speech());
+
+    let said = segment("Let's get started", None);
+    assert_eq!(
+        filter.rejects(&said, Some(Duration::from_millis(100))),
+        Some("no speech heard")
+    );
+    assert!(filter
+        .rejects(&said, Some(Duration::from_millis(1500)))
+        .is_none());
+    // Unknown speech (uploads) isn't held against a segment
+    assert!(filter.rejects(&said, None).is_none());
+
+    let kept = filter.apply(vec![said.clone(), segment("Thanks for watching!", None)]);
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].text, said.text);
+}
@@ -0,0 +1,131 @@
+// Tests for paged, time-filtered, and incremental transcript reads
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use common::segment;
+use loqa_meetings::session::{TranscriptLog, TranscriptQuery};
+use loqa_meetings::TranscriptSegment;
+
+fn texts(segments: &[TranscriptSegment]) -> Vec<&str> {
+    segments.iter().map(|s| s.text.as_str()).collect()
+}
+
+/// Segments at 0s, 10s, ..., 90s
+fn log(started_at: DateTime<Utc>) -> TranscriptLog {
+    TranscriptLog::from_segments(
+        (0..10)
+            .map(|i| segment(&format!("s{}", i), started_at + Duration::seconds(i * 10)))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_offset_and_limit_page_through() {
+    let started_at = Utc::now();
+    let log = log(started_at);
+
+    let first = log.page(started_at, &TranscriptQuery::default(), 4);
+    assert_eq!(texts(&first.segments), vec!["s0", "s1", "s2", "s3"]);
+    assert_eq!(first.total, 10);
+    assert_eq!(first.next_offset, Some(4));
+    assert_eq!(first.sequence, 10);
+
+    let query = TranscriptQuery {
+        offset: Some(8),
+        ..Default::default()
+    };
+    let last = log.page(started_at, &query, 4);
+    assert_eq!(texts(&last.segments), vec!["s8", "s9"]);
+    assert_eq!(last.next_offset, None);
+
+    let past_end = TranscriptQuery {
+        offset: Some(50),
+        ..Default::default()
+    };
+    assert!(log.page(started_at, &past_end, 4).segments.is_empty());
+}
+
+#[test]
+fn test_time_filters() {
+    let started_at = Utc::now();
+    let log = log(started_at);
+
+    let range = TranscriptQuery {
+        from_ms: Some(20_000),
+        to_ms: Some(50_000),
+        ..Default::default()
+    };
+    let page = log.page(started_at, &range, 100);
+    assert_eq!(texts(&page.segments), vec!["s2", "s3", "s4"]);
+    assert_eq!(page.total, 3);
+
+    let since = TranscriptQuery {
+        since: Some(started_at + Duration::seconds(70)),
+        ..Default::default()
+    };
+    assert_eq!(
+        texts(&log.page(started_at, &since, 100).segments),
+        vec!["s8", "s9"]
+    );
+}
+
+#[test]
+fn test_changes_since_sequence() {
+    let started_at = Utc::now();
+    let mut log = TranscriptLog::new();
+    log.push(segment("first", started_at + Duration::seconds(1)));
+    log.push(segment("third", started_at + Duration::seconds(3)));
+
+    let poll = log.page(started_at, &TranscriptQuery::default(), 100);
+    assert_eq!(poll.sequence, 2);
+
+    // A late segment lands in time order but still counts as a change
+    assert_eq!(
+        log.insert_by_timestamp(segment("second", started_at + Duration::seconds(2))),
+        3
+    );
+    log.push(segment("fourth", started_at + Duration::seconds(4)));
+    assert_eq!(
+        texts(&log.segments()),
+        vec!["first", "second", "third", "fourth"]
+    );
+
+    let query = TranscriptQuery {
+        since_seq: Some(poll.sequence),
+        ..Default::default()
+    };
+    let changes = log.page(started_at, &query, 100);
+    assert_eq!(texts(&changes.segments), vec!["second", "fourth"]);
+    assert_eq!(changes.sequence, 4);
+}
+
+#[test]
+fn test_cursor_returns_only_new_and_changed_segments() {
+    let started_at = Utc::now();
+    let mut log = TranscriptLog::new();
+    log.push(segment("first", started_at + Duration::seconds(1)));
+    let second = log.push(segment("second", started_at + Duration::seconds(2)));
+
+    let update = log.changes_since(0);
+    assert_eq!(texts(&update.segments), vec!["first", "second"]);
+    assert_eq!(update.cursor, 2);
+
+    let update = log.changes_since(update.cursor);
+    assert!(update.segments.is_empty());
+    assert_eq!(update.cursor, 2);
+
+    // A translated segment counts as changed
+    log.replace(second, segment("zweite", started_at + Duration::seconds(2)));
+    log.push(segment("third", started_at + Duration::seconds(3)));
+    let update = log.changes_since(update.cursor);
+    assert_eq!(texts(&update.segments), vec!["zweite", "third"]);
+    assert_eq!(update.cursor, 4);
+}
+
+#[test]
+fn test_unpaged_query_is_detected() {
+    assert!(!TranscriptQuery::default().is_paged());
+    let query: TranscriptQuery = serde_json::from_str(r#"{"since_seq": 7}"#).unwrap();
+    assert!(query.is_paged());
+}
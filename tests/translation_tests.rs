@@ -0,0 +1,181 @@
+// Tests for translating final transcript segments
+
+mod common;
+
+use anyhow::Result;
+use axum::routing::post;
+use axum::{Json, Router};
+use chrono::{DateTime, Duration, Utc};
+use loqa_meetings::session::{
+    TranscriptLog, TranscriptProcessor, TranscriptQuery, Translation, TranslationConfig,
+    TranslationProcessor, TranslationReply, TranslationRequest,
+};
+use loqa_meetings::{render_note, NoteFormat, TranscriptSegment};
+use std::time::Duration as StdDuration;
+
+fn segment(text: &str, at: DateTime<Utc>, language: Option<&str>) -> TranscriptSegment {
+    TranscriptSegment {
+        language: language.map(str::to_string),
+        ..common::segment(text, at)
+    }
+}
+
+/// Translator that shouts the text back, tagged with the target language
+async fn translator() -> Result<String> {
+    let app = Router::new().route(
+        "/translate",
+        post(|Json(request): Json<TranslationRequest>| async move {
+            Json(TranslationReply {
+                text: format!(
+                    "[{}] {}",
+                    request.target_language,
+                    request.text.to_uppercase()
+                ),
+            })
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok(format!("http://{}/translate", addr))
+}
+
+#[tokio::test]
+async fn test_http_translation_is_stored_with_the_segment() -> Result<()> {
+    let url = translator().await?;
+    let processor = TranslationProcessor::http("English", &url, StdDuration::from_secs(5))?;
+    assert_eq!(processor.target_language(), "en");
+
+    let original = segment("Hallo zusammen", Utc::now(), Some("de"));
+    let processed = processor
+        .process("meeting-1", &original)
+        .await?
+        .expect("Translated");
+    assert_eq!(processed.text, "Hallo zusammen");
+    assert_eq!(
+        processed.translation,
+        Some(Translation {
+            language: "en".to_string(),
+            text: "[en] HALLO ZUSAMMEN".to_string(),
+        })
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_segments_in_the_target_language_or_partial_are_skipped() -> Result<()> {
+    let url = translator().await?;
+    let processor = TranslationProcessor::http("en", &url, StdDuration::from_secs(5))?;
+
+    let english = segment("Good morning", Utc::now(), Some("en"));
+    assert!(processor.process("meeting-1", &english).await?.is_none());
+
+    let mut partial = segment("Guten Mor", Utc::now(), Some("de"));
+    partial.partial = true;
+    assert!(processor.process("meeting-1", &partial).await?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_translator_errors_are_reported() -> Result<()> {
+    let url = translator().await?.replace("/translate", "/missing");
+    let processor = TranslationProcessor::http("en", &url, StdDuration::from_secs(5))?;
+
+    let original = segment("Hallo zusammen", Utc::now(), Some("de"));
+    assert!(processor.process("meeting-1", &original).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_translation_is_off_without_a_target_or_endpoint() -> Result<()> {
+    let config = TranslationConfig::default();
+    assert!(
+        TranslationProcessor::from_config(&config, "meeting-1", None, "nats://localhost:4222")
+            .await?
+            .is_none()
+    );
+
+    let no_endpoint = TranslationConfig {
+        target_language: Some("en".to_string()),
+        ..Default::default()
+    };
+    assert!(TranslationProcessor::from_config(
+        &no_endpoint,
+        "meeting-1",
+        None,
+        "nats://localhost:4222"
+    )
+    .await?
+    .is_none());
+    Ok(())
+}
+
+#[test]
+fn test_replaced_segment_shows_up_as_a_change() {
+    let started_at = Utc::now();
+    let mut log = TranscriptLog::new();
+    let first = log.push(segment("Hallo", started_at, Some("de")));
+    log.push(segment(
+        "Tschüss",
+        started_at + Duration::seconds(1),
+        Some("de"),
+    ));
+
+    let mut translated = segment("Hallo", started_at, Some("de"));
+    translated.translation = Some(Translation {
+        language: "en".to_string(),
+        text: "Hello".to_string(),
+    });
+    assert_eq!(log.replace(first, translated), Some(3));
+    assert_eq!(log.replace(first, segment("gone", started_at, None)), None);
+
+    // Order is kept, and a poll since the last sequence sees the translation
+    let texts: Vec<String> = log.segments().into_iter().map(|s| s.text).collect();
+    assert_eq!(texts, vec!["Hallo", "Tschüss"]);
+    let query = TranscriptQuery {
+        since_seq: Some(2),
+        ..Default::default()
+    };
+    let changes = log.page(started_at, &query, 100);
+    assert_eq!(changes.segments.len(), 1);
+    assert_eq!(
+        changes.segments[0].translation.as_ref().unwrap().text,
+        "Hello"
+    );
+}
+
+#[test]
+fn test_bilingual_note_quotes_translations() {
+    let started_at = Utc::now();
+    let mut translated = segment("Hallo zusammen", started_at, Some("de"));
+    translated.translation = Some(Translation {
+        language: "en".to_string(),
+        text: "Hello everyone".to_string(),
+    });
+    let transcript = vec![
+        translated,
+        segment("Good", started_at + Duration::seconds(5), Some("en")),
+    ];
+    let record = common::meeting(
+        "sync-2025-11-04",
+        started_at,
+        Duration::minutes(1),
+        transcript,
+        Vec::new(),
+    );
+
+    let plain = render_note(&record, &NoteFormat::default());
+    assert!(!plain.contains("Hello everyone"));
+
+    let bilingual = NoteFormat {
+        bilingual: true,
+        ..Default::default()
+    };
+    let note = render_note(&record, &bilingual);
+    assert!(note.contains("Hallo zusammen\n> Hello everyone\n"));
+    assert_eq!(
+        note.matches("\n> ").count(),
+        1,
+        "Untranslated lines get no quote"
+    );
+}
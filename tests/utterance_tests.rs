@@ -0,0 +1,107 @@
+// Tests for utterance-boundary hints on published audio
+
+use loqa_meetings::session::{rms_dbfs, UtteranceBoundary, UtteranceConfig, UtteranceTracker};
+
+/// 100ms of 16kHz mono audio
+const FRAME: usize = 1600;
+
+fn speech() -> Vec<i16> {
+    (0..FRAME)
+        .map(|i| if i % 2 == 0 { 8000 } else { -8000 })
+        .collect()
+}
+
+fn silence() -> Vec<i16> {
+    vec![0; FRAME]
+}
+
+/// Indices of the frames the tracker marked as utterance ends
+fn boundaries(tracker: &mut UtteranceTracker, frames: &[Vec<i16>]) -> Vec<usize> {
+    frames
+        .iter()
+        .enumerate()
+        .filter(|(_, frame)| tracker.push(frame, 16000, 1))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[test]
+fn test_rms_dbfs() {
+    assert_eq!(rms_dbfs(&[]), f32::NEG_INFINITY);
+    assert_eq!(rms_dbfs(&silence()), f32::NEG_INFINITY);
+    assert!((rms_dbfs(&[i16::MIN; 10])).abs() < 0.01);
+    assert!((rms_dbfs(&speech()) - -12.3).abs() < 0.1);
+}
+
+#[test]
+fn test_no_boundaries_by_default() {
+    let mut tracker = UtteranceTracker::new(UtteranceConfig::default());
+    let frames: Vec<_> = (0..600).map(|_| speech()).collect();
+    assert!(boundaries(&mut tracker, &frames).is_empty());
+}
+
+#[test]
+fn test_interval_boundaries() {
+    let mut tracker = UtteranceTracker::new(UtteranceConfig {
+        boundary: UtteranceBoundary::Interval,
+        interval_secs: 2,
+        ..Default::default()
+    });
+    let frames: Vec<_> = (0..50).map(|_| speech()).collect();
+
+    // Every 20 frames of 100ms
+    assert_eq!(boundaries(&mut tracker, &frames), vec![19, 39]);
+}
+
+#[test]
+fn test_silence_boundary_after_pause() {
+    let mut tracker = UtteranceTracker::new(UtteranceConfig {
+        boundary: UtteranceBoundary::Silence,
+        silence_ms: 300,
+        ..Default::default()
+    });
+
+    let mut frames: Vec<_> = (0..10).map(|_| speech()).collect();
+    frames.extend((0..10).map(|_| silence()));
+    frames.extend((0..5).map(|_| speech()));
+    frames.push(silence());
+    frames.push(silence());
+    frames.push(silence());
+
+    // The third silent frame ends each utterance; the rest of the pause
+    // does not produce more boundaries
+    assert_eq!(boundaries(&mut tracker, &frames), vec![12, 27]);
+}
+
+#[test]
+fn test_silence_boundary_forced_in_long_monologue() {
+    let mut tracker = UtteranceTracker::new(UtteranceConfig {
+        boundary: UtteranceBoundary::Silence,
+        max_utterance_secs: 3,
+        ..Default::default()
+    });
+    let frames: Vec<_> = (0..70).map(|_| speech()).collect();
+
+    assert_eq!(boundaries(&mut tracker, &frames), vec![29, 59]);
+}
+
+#[test]
+fn test_silence_only_never_marks_boundaries() {
+    let mut tracker = UtteranceTracker::new(UtteranceConfig {
+        boundary: UtteranceBoundary::Silence,
+        max_utterance_secs: 1,
+        ..Default::default()
+    });
+    let frames: Vec<_> = (0..100).map(|_| silence()).collect();
+
+    assert!(boundaries(&mut tracker, &frames).is_empty());
+}
+
+#[test]
+fn test_utterance_config_partial() {
+    let config: UtteranceConfig =
+        serde_json::from_str(r#"{"boundary": "interval", "interval_secs": 5}"#).unwrap();
+    assert_eq!(config.boundary, UtteranceBoundary::Interval);
+    assert_eq!(config.interval_secs, 5);
+    assert_eq!(config.silence_ms, UtteranceConfig::default().silence_ms);
+}
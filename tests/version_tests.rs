@@ -0,0 +1,65 @@
+// Tests for the build information served by GET /version
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use loqa_meetings::audio::AudioBackendFactory;
+use loqa_meetings::storage::FilesystemStorage;
+use loqa_meetings::version::{self, BuildInfo};
+use loqa_meetings::{create_router, AppState, Config};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::Service;
+
+#[test]
+fn test_build_info_describes_this_build() {
+    let info = BuildInfo::current();
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        info.platform,
+        format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+    );
+    assert_eq!(
+        info.features.contains(&"opus"),
+        cfg!(feature = "opus"),
+        "Features follow the build"
+    );
+    if let Some(commit) = info.git_commit {
+        assert!(commit.chars().all(|c| c.is_ascii_hexdigit()), "{}", commit);
+    }
+}
+
+#[test]
+fn test_capture_backends_follow_the_platform() {
+    let backends = AudioBackendFactory::backends();
+    let sources: Vec<&str> = backends.iter().map(|b| b.source).collect();
+    assert_eq!(sources, vec!["system", "microphone", "network", "file"]);
+    assert_eq!(backends[0].available, cfg!(target_os = "macos"));
+    assert_eq!(backends[0].backend.is_some(), backends[0].available);
+    assert_eq!(backends[1].available, cfg!(feature = "microphone"));
+    // Network audio needs no platform support
+    assert!(backends[2].available);
+    assert!(backends[3].available);
+}
+
+#[tokio::test]
+async fn test_version_endpoint() {
+    let dir = TempDir::new().unwrap();
+    let storage = Arc::new(FilesystemStorage::new(dir.path().to_path_buf()));
+    let mut router = create_router(AppState::with_config(Config::default(), storage));
+
+    let request = Request::get("/version").body(Body::empty()).unwrap();
+    std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut router, cx))
+        .await
+        .unwrap();
+    let response = router.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["version"], version::VERSION);
+    assert!(json["features"].is_array());
+    assert_eq!(json["capture_backends"][0]["source"], "system");
+    assert!(json.get("git_commit").is_some());
+}
@@ -0,0 +1,253 @@
+// Tests for the watchdog that notices capture delivering no frames, and
+// for restarting a stalled source
+
+use loqa_meetings::audio::{
+    AudioFrame, AudioStreamSource, Mixer, MixerConfig, MixerInput, NetworkSourceConfig,
+};
+use loqa_meetings::session::{
+    CaptureWatchdog, MergedCapture, RestartPolicy, SessionTask, WatchdogConfig,
+};
+use loqa_meetings::{RecordingSession, SessionConfig, SessionEvent, SessionWarning};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::sync::{broadcast, mpsc};
+
+/// Record-only session receiving network audio that never arrives
+async fn silent_session(dir: &TempDir, restart: bool) -> RecordingSession {
+    RecordingSession::new(SessionConfig {
+        session_id: "stalled".to_string(),
+        audio_dir: Some(dir.path().to_path_buf()),
+        transcription: false,
+        network_source: Some(NetworkSourceConfig {
+            bind: SocketAddr::from(([127, 0, 0, 1], 0)),
+            ..Default::default()
+        }),
+        watchdog: WatchdogConfig {
+            stall_secs: 1,
+            restart,
+        },
+        restart_policy: RestartPolicy {
+            max_restarts: 1,
+            backoff: Duration::ZERO,
+        },
+        ..Default::default()
+    })
+    .await
+    .unwrap()
+}
+
+async fn next_event(events: &mut broadcast::Receiver<SessionEvent>) -> SessionEvent {
+    tokio::time::timeout(Duration::from_secs(5), events.recv())
+        .await
+        .expect("the watchdog should fire")
+        .unwrap()
+}
+
+#[test]
+fn test_watchdog_reports_each_stall_once() {
+    assert_eq!(WatchdogConfig::default().stall_secs, 10);
+    let disabled = WatchdogConfig {
+        stall_secs: 0,
+        restart: false,
+    };
+    assert!(
+        CaptureWatchdog::new(disabled.stall_timeout(), [AudioStreamSource::System])
+            .deadline()
+            .is_none()
+    );
+
+    let mut watchdog =
+        CaptureWatchdog::new(Some(Duration::from_secs(1)), [AudioStreamSource::System]);
+    assert!(
+        watchdog.frame(AudioStreamSource::System).is_none(),
+        "Frames while healthy resume nothing"
+    );
+    assert!(watchdog.deadline().is_some());
+
+    let mut watchdog = CaptureWatchdog::new(Some(Duration::ZERO), [AudioStreamSource::System]);
+    assert_eq!(watchdog.stall().len(), 1);
+    assert!(watchdog.deadline().is_none(), "A stall is reported once");
+    assert!(watchdog.stall().is_empty());
+    assert!(watchdog.frame(AudioStreamSource::System).is_some());
+    assert!(
+        watchdog.deadline().is_some(),
+        "Watched again once frames resume"
+    );
+}
+
+#[test]
+fn test_watchdog_watches_each_source() {
+    let mic = AudioStreamSource::MicrophoneDevice(0);
+    let mut watchdog = CaptureWatchdog::new(Some(Duration::ZERO), [AudioStreamSource::System, mic]);
+
+    let stalled: Vec<_> = watchdog
+        .stall()
+        .into_iter()
+        .map(|(source, _)| source)
+        .collect();
+    assert_eq!(stalled, vec![mic, AudioStreamSource::System]);
+
+    // Only the source that delivered a frame has resumed
+    assert!(watchdog.frame(mic).is_some());
+    assert!(
+        watchdog.frame(AudioStreamSource::Microphone).is_none(),
+        "Sources not watched are ignored"
+    );
+    let stalled: Vec<_> = watchdog
+        .stall()
+        .into_iter()
+        .map(|(source, _)| source)
+        .collect();
+    assert_eq!(stalled, vec![mic]);
+
+    // A source no longer watched is never reported
+    watchdog.frame(AudioStreamSource::System);
+    watchdog.unwatch(AudioStreamSource::System);
+    watchdog.frame(mic);
+    let stalled: Vec<_> = watchdog
+        .stall()
+        .into_iter()
+        .map(|(source, _)| source)
+        .collect();
+    assert_eq!(stalled, vec![mic]);
+}
+
+#[tokio::test]
+async fn test_stalled_capture_raises_a_warning() {
+    let dir = TempDir::new().unwrap();
+    let session = silent_session(&dir, false).await;
+    let mut events = session.subscribe_events();
+    session.start().await.unwrap();
+
+    let SessionEvent::Warning { warning } = next_event(&mut events).await else {
+        panic!("Expected a warning");
+    };
+    let SessionWarning::CaptureStalled {
+        source,
+        silent_secs,
+        restarting,
+        ..
+    } = warning
+    else {
+        panic!("Expected a capture stall, got {:?}", warning);
+    };
+    assert_eq!(source, "system");
+    assert!(silent_secs >= 1.0);
+    assert!(!restarting);
+
+    let stats = session.get_stats().await.unwrap();
+    assert!(stats.is_recording);
+    assert!(matches!(
+        stats.warnings.as_slice(),
+        [SessionWarning::CaptureStalled { .. }]
+    ));
+    session.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_stalled_capture_can_be_restarted() {
+    let dir = TempDir::new().unwrap();
+    let session = silent_session(&dir, true).await;
+    let mut events = session.subscribe_events();
+    session.start().await.unwrap();
+
+    assert!(matches!(
+        next_event(&mut events).await,
+        SessionEvent::Warning {
+            warning: SessionWarning::CaptureStalled {
+                restarting: true,
+                ..
+            }
+        }
+    ));
+    let SessionEvent::TaskFailed {
+        task,
+        error,
+        restarting,
+        ..
+    } = next_event(&mut events).await
+    else {
+        panic!("Expected the audio task to be restarted");
+    };
+    assert_eq!(task, SessionTask::Audio);
+    assert!(error.contains("stalled"), "{}", error);
+    assert!(restarting);
+    session.stop().await.unwrap();
+}
+
+/// 100ms of 16kHz mono audio at `value`, stamped from the backend's start
+fn frame(value: i16, timestamp_ms: u64) -> AudioFrame {
+    AudioFrame {
+        samples: vec![value; 1600],
+        sample_rate: 16000,
+        channels: 1,
+        timestamp_ms,
+        source: AudioStreamSource::System,
+    }
+}
+
+#[tokio::test]
+async fn test_restarted_source_is_mixed_from_where_capture_got_to() {
+    let mic = AudioStreamSource::MicrophoneDevice(0);
+    let mut mixer = Mixer::new(MixerConfig::new(
+        16000,
+        1,
+        [AudioStreamSource::System, mic]
+            .into_iter()
+            .map(|source| MixerInput { source, gain: 1.0 })
+            .collect(),
+    ));
+    let (system_tx, system_rx) = mpsc::channel(64);
+    let (mic_tx, mic_rx) = mpsc::channel(64);
+    let (mut audio_rx, merged) = MergedCapture::new(
+        vec![(AudioStreamSource::System, system_rx), (mic, mic_rx)],
+        64,
+    );
+
+    // Both sources for a second, then the microphone stalls for two
+    for index in 0..30 {
+        system_tx.send(frame(100, index * 100)).await.unwrap();
+        if index < 10 {
+            mic_tx.send(frame(50, index * 100)).await.unwrap();
+        }
+    }
+    drop(mic_tx);
+    for _ in 0..40 {
+        mixer.push(audio_rx.recv().await.unwrap());
+    }
+    let mixed = mixer.pop_ready();
+
+    // The restarted backend stamps its frames from 0 again
+    let (mic_tx, mic_rx) = mpsc::channel(64);
+    merged.restart(mic, mic_rx, 3000).unwrap();
+    for index in 0..5 {
+        mic_tx.send(frame(50, index * 100)).await.unwrap();
+        system_tx
+            .send(frame(100, 3000 + index * 100))
+            .await
+            .unwrap();
+    }
+    for _ in 0..10 {
+        let frame = audio_rx.recv().await.unwrap();
+        if frame.source == mic {
+            assert!(frame.timestamp_ms >= 3000, "{}", frame.timestamp_ms);
+        }
+        mixer.push(frame);
+    }
+    let resumed: Vec<_> = mixed
+        .into_iter()
+        .chain(mixer.pop_ready())
+        .chain(mixer.flush())
+        .filter(|frame| frame.timestamp_ms >= 3000)
+        .collect();
+
+    assert_eq!(mixer.drop_stats().late, 0);
+    assert!(!resumed.is_empty());
+    assert!(
+        resumed
+            .iter()
+            .all(|frame| frame.samples.iter().all(|&s| s == 150)),
+        "The restarted microphone is mixed with system audio"
+    );
+}
@@ -0,0 +1,217 @@
+// Tests for meeting directory locks and the instance-stamped manifest
+
+use anyhow::Result;
+use chrono::Utc;
+use loqa_meetings::audio::workdir::{LOCK_FILE, MANIFEST_FILE};
+use loqa_meetings::audio::{
+    instance_id, AudioFrame, AudioStreamSource, ChunkConfig, ChunkedRecorder, DirInUse, FloatFrame,
+    InstanceStamp, MeetingDirLock, RecordingManifest,
+};
+use loqa_meetings::session::AudioRetention;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn frame(timestamp_ms: u64) -> FloatFrame {
+    FloatFrame::from(AudioFrame {
+        samples: vec![0i16; 1600],
+        sample_rate: 16000,
+        channels: 1,
+        timestamp_ms,
+        source: AudioStreamSource::System,
+    })
+}
+
+fn write_lock(dir: &Path, holder: &InstanceStamp) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(LOCK_FILE), serde_json::to_vec(holder)?)?;
+    Ok(())
+}
+
+fn this_host() -> String {
+    gethostname::gethostname().to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_lock_stamps_manifest_and_refuses_second_holder() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("meeting-1");
+
+    let lock = MeetingDirLock::acquire(&dir, "meeting-1")?;
+    assert!(dir.join(LOCK_FILE).exists());
+    let manifest = RecordingManifest::load(&dir)?.expect("manifest written");
+    assert_eq!(manifest.meeting_id, "meeting-1");
+    assert_eq!(manifest.writer.instance_id, instance_id());
+    assert_eq!(manifest.writer.pid, std::process::id());
+    assert!(manifest.chunks.is_empty());
+
+    let err = MeetingDirLock::acquire(&dir, "meeting-1").unwrap_err();
+    let in_use = err.downcast_ref::<DirInUse>().expect("typed error");
+    assert_eq!(in_use.holder.instance_id, instance_id());
+
+    // Released on drop
+    drop(lock);
+    assert!(!dir.join(LOCK_FILE).exists());
+    MeetingDirLock::acquire(&dir, "meeting-1")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_lock_held_on_another_host_is_refused_with_its_path() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("meeting-2");
+    write_lock(
+        &dir,
+        &InstanceStamp {
+            instance_id: "other-instance".to_string(),
+            host: "some-other-host".to_string(),
+            pid: 1,
+            since: Utc::now(),
+        },
+    )?;
+
+    let err = MeetingDirLock::acquire(&dir, "meeting-2").unwrap_err();
+    assert!(err.is::<DirInUse>());
+    let message = err.to_string();
+    assert!(message.contains("other-instance"), "{}", message);
+    assert!(message.contains("some-other-host"), "{}", message);
+    assert!(
+        message.contains(&dir.join(LOCK_FILE).display().to_string()),
+        "{}",
+        message
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_lock_left_by_dead_process_is_taken_over() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("meeting-3");
+    write_lock(
+        &dir,
+        &InstanceStamp {
+            instance_id: "crashed-instance".to_string(),
+            host: this_host(),
+            // Above any real pid limit, so never running
+            pid: 999_999_999,
+            since: Utc::now(),
+        },
+    )?;
+
+    let _lock = MeetingDirLock::acquire(&dir, "meeting-3")?;
+    let holder: InstanceStamp = serde_json::from_slice(&fs::read(dir.join(LOCK_FILE))?)?;
+    assert_eq!(holder.instance_id, instance_id());
+
+    Ok(())
+}
+
+#[test]
+fn test_unreadable_lock_is_taken_over() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("meeting-4");
+    // Left empty by a crash between creating the lock and stamping it
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(LOCK_FILE), "")?;
+
+    let _lock = MeetingDirLock::acquire(&dir, "meeting-4")?;
+    let holder: InstanceStamp = serde_json::from_slice(&fs::read(dir.join(LOCK_FILE))?)?;
+    assert_eq!(holder.instance_id, instance_id());
+
+    Ok(())
+}
+
+#[test]
+fn test_recorder_lists_chunks_and_stops_when_directory_is_taken_over() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("meeting-4");
+    let lock = MeetingDirLock::acquire(&dir, "meeting-4")?;
+    let config = ChunkConfig {
+        chunk_duration_secs: 1,
+        ..ChunkConfig::new("meeting-4".to_string(), dir.clone())
+    };
+    let mut recorder = ChunkedRecorder::new(config)?.with_lock(lock);
+
+    for i in 0..11 {
+        recorder.write(&frame(i * 100))?;
+    }
+    let manifest = RecordingManifest::load(&dir)?.unwrap();
+    assert_eq!(manifest.chunks.len(), 1);
+    assert_eq!(manifest.chunks[0].file, "meeting-4-chunk-000.wav");
+    assert_eq!(
+        manifest.chunks[0].sha256,
+        recorder.chunks()[0].sha256,
+        "Checksums are listed with the chunks"
+    );
+
+    // Another instance stamps the manifest while this one still records
+    let mut stolen = manifest.clone();
+    stolen.writer.instance_id = "other-instance".to_string();
+    fs::write(dir.join(MANIFEST_FILE), serde_json::to_vec(&stolen)?)?;
+
+    let mut result = Ok(None);
+    for i in 11..21 {
+        result = recorder.write(&frame(i * 100));
+        if result.is_err() {
+            break;
+        }
+    }
+    let err = result.unwrap_err();
+    assert!(err.is::<DirInUse>(), "{:#}", err);
+    assert!(recorder.is_failed());
+    assert_eq!(recorder.chunks().len(), 2, "The open chunk is still closed");
+
+    // The lock is left to whoever holds it now
+    recorder.release_lock();
+    assert!(!dir.join(LOCK_FILE).exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_abandoned_lock_leaves_directory_empty() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("meeting-5");
+
+    MeetingDirLock::acquire(&dir, "meeting-5")?.abandon();
+    assert_eq!(fs::read_dir(&dir)?.count(), 0);
+    fs::remove_dir(&dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_retention_check_ignores_lock_and_manifest() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("meeting-6");
+    let _lock = MeetingDirLock::acquire(&dir, "meeting-6")?;
+
+    let retention = AudioRetention::check(true, &dir, Utc::now())?;
+    assert_eq!(retention.audio_files, 0);
+    assert!(retention.verified);
+
+    Ok(())
+}
+
+#[test]
+fn test_manifest_lists_chunk_overlap() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let dir = temp_dir.path().join("meeting-5");
+    let lock = MeetingDirLock::acquire(&dir, "meeting-5")?;
+    let config = ChunkConfig {
+        chunk_duration_secs: 2,
+        overlap_secs: 1,
+        ..ChunkConfig::new("meeting-5".to_string(), dir.clone())
+    };
+    let mut recorder = ChunkedRecorder::new(config)?.with_lock(lock);
+
+    for i in 0..41 {
+        recorder.write(&frame(i * 100))?;
+    }
+    let manifest = RecordingManifest::load(&dir)?.unwrap();
+    let overlaps: Vec<u64> = manifest.chunks.iter().map(|c| c.overlap_ms).collect();
+    assert_eq!(overlaps, vec![0, 1000]);
+
+    Ok(())
+}